@@ -3,8 +3,12 @@ use std::time::Duration;
 use anathema_geometry::{Pos, Size};
 use anathema_store::tree::{AsNodePath, Node, TreeValues};
 use anathema_widgets::components::events::Event;
-use anathema_widgets::layout::{layout_widget, position_widget, Constraints, LayoutCtx, LayoutFilter, Viewport};
-use anathema_widgets::{AttributeStorage, Element, FloatingWidgets, WidgetKind, WidgetTree};
+use anathema_widgets::error::{Error, Result};
+use anathema_widgets::layout::{
+    align_elements, find_mount, is_hidden, layout_widget, portal_order, position_widget, Constraints, LayoutCtx,
+    LayoutError, LayoutFilter, Viewport,
+};
+use anathema_widgets::{AttributeStorage, Element, FloatingWidgets, WidgetId, WidgetKind, WidgetTree};
 
 pub mod test;
 pub mod tui;
@@ -16,6 +20,11 @@ pub trait Backend {
 
     fn resize(&mut self, new_size: Size);
 
+    /// The size of a cell in pixels, or zero if it's not known
+    fn cell_size(&self) -> Size {
+        Size::ZERO
+    }
+
     /// Paint the widgets
     fn paint<'bp>(
         &mut self,
@@ -46,6 +55,9 @@ pub struct WidgetCycle<'rt, 'bp, T> {
     attribute_storage: &'rt AttributeStorage<'bp>,
     floating_widgets: &'rt FloatingWidgets,
     viewport: Viewport,
+    origin: Pos,
+    // The errors reported during the layout
+    errors: Vec<(WidgetId, LayoutError)>,
 }
 
 impl<'rt, 'bp, T: Backend> WidgetCycle<'rt, 'bp, T> {
@@ -64,18 +76,82 @@ impl<'rt, 'bp, T: Backend> WidgetCycle<'rt, 'bp, T> {
             attribute_storage,
             floating_widgets,
             viewport,
+            origin: Pos::ZERO,
+            errors: vec![],
         }
     }
 
+    /// The position of the root widgets, e.g. to keep space free above the widgets
+    pub fn origin(mut self, origin: Pos) -> Self {
+        self.origin = origin;
+        self
+    }
+
     fn floating(&mut self) {
-        // Floating widgets
-        for widget_id in self.floating_widgets.iter() {
+        // Floating widgets are painted in the order they appear in the tree,
+        // so overlays end up on top of the floating widgets of the layers underneath
+        // Portals are painted as part of their mount point.
+        // A portal without a mount point is not painted.
+        let (_, values) = self.tree.split();
+        let mut floating_widgets = self
+            .floating_widgets
+            .iter()
+            .filter_map(|widget_id| {
+                let (path, widget) = values.get(*widget_id)?;
+                match widget {
+                    WidgetKind::Element(el) if el.is_portal() => {
+                        let (mount_path, mount) = find_mount(*widget_id, values, self.attribute_storage)?;
+                        Some((portal_order(mount_path), widget_id, Some(mount)))
+                    }
+                    _ => Some((path.clone(), widget_id, None)),
+                }
+            })
+            .collect::<Vec<_>>();
+        floating_widgets.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (_, widget_id, mount) in floating_widgets {
+            let hidden = match self.tree.get_ref_by_id(*widget_id) {
+                Some(WidgetKind::Element(el)) => is_hidden(self.attribute_storage.get(el.id())),
+                _ => false,
+            };
+            if hidden {
+                continue;
+            }
+
+            // The anchor is positioned by now, either as part of the tree or as a preceding floating widget
+            let anchor = match self.tree.get_ref_by_id(*widget_id) {
+                Some(WidgetKind::Element(el)) => el.anchor(),
+                _ => None,
+            };
+
+            if let Some(anchor) = anchor {
+                let region = match self.tree.get_ref_by_id(anchor) {
+                    Some(WidgetKind::Element(el)) => Some(el.region()),
+                    _ => None,
+                };
+
+                if let Some(WidgetKind::Element(el)) = self.tree.get_mut_by_id(*widget_id) {
+                    el.set_anchor_region(region);
+                }
+            }
+
+            // A portal is laid out inside its mount point, unless the mount point is hidden
+            if let Some(mount) = mount {
+                if let Some(WidgetKind::Element(el)) = self.tree.get_ref_by_id(mount) {
+                    if !is_hidden(self.attribute_storage.get(el.id())) {
+                        let bounds = el.inner_bounds();
+                        self.layout_floating(*widget_id, bounds.start, Constraints::from(bounds));
+                    }
+                }
+                continue;
+            }
+
             // Find the parent widget and get the position
             // If no parent element is found assume Pos::ZERO
             let mut parent = self.tree.path_ref(*widget_id).parent();
             let (pos, constraints) = loop {
                 match parent {
-                    None => break (Pos::ZERO, self.constraints),
+                    None => break (self.origin, self.constraints),
                     Some(p) => match self.tree.get_ref_by_path(p) {
                         Some(WidgetKind::Element(el)) => {
                             let bounds = el.inner_bounds();
@@ -86,22 +162,30 @@ impl<'rt, 'bp, T: Backend> WidgetCycle<'rt, 'bp, T> {
                 }
             };
 
-            self.tree.with_nodes_and_values(*widget_id, |widget, children, values| {
-                let WidgetKind::Element(el) = widget else { unreachable!("this is always a floating widget") };
-                let mut layout_ctx = LayoutCtx::new(self.attribute_storage, &self.viewport);
+            self.layout_floating(*widget_id, pos, constraints);
+        }
+    }
 
-                layout_widget(el, children, values, constraints, &mut layout_ctx, true);
+    fn layout_floating(&mut self, widget_id: WidgetId, pos: Pos, constraints: Constraints) {
+        self.tree.with_nodes_and_values(widget_id, |widget, children, values| {
+            let WidgetKind::Element(el) = widget else { unreachable!("this is always a floating widget") };
+            let mut layout_ctx = LayoutCtx::new(self.attribute_storage, &self.viewport);
 
-                // Position
-                position_widget(pos, el, children, values, self.attribute_storage, true, self.viewport);
+            layout_widget(el, children, values, constraints, &mut layout_ctx, true);
+            self.errors.extend(layout_ctx.take_errors());
 
-                // Paint
-                self.backend.paint(el, children, values, self.attribute_storage, true);
-            });
-        }
+            // Position
+            position_widget(pos, el, children, values, self.attribute_storage, true, self.viewport);
+
+            // Paint
+            self.backend.paint(el, children, values, self.attribute_storage, true);
+        });
     }
 
-    pub fn run(&mut self) {
+    /// Layout, position and paint the widgets.
+    /// Widgets are painted even if the layout reports an error,
+    /// in which case the first error is returned along with the widget that reported it.
+    pub fn run(&mut self) -> Result<()> {
         let mut filter = LayoutFilter::new(true, self.attribute_storage);
         self.tree.for_each(&mut filter).first(&mut |widget, children, values| {
             // Layout
@@ -112,10 +196,11 @@ impl<'rt, 'bp, T: Backend> WidgetCycle<'rt, 'bp, T> {
             //       as it will do when dealing with the floating widgets
             let mut layout_ctx = LayoutCtx::new(self.attribute_storage, &self.viewport);
             layout_widget(widget, children, values, self.constraints, &mut layout_ctx, true);
+            self.errors.extend(layout_ctx.take_errors());
 
             // Position
             position_widget(
-                Pos::ZERO,
+                self.origin,
                 widget,
                 children,
                 values,
@@ -123,12 +208,30 @@ impl<'rt, 'bp, T: Backend> WidgetCycle<'rt, 'bp, T> {
                 true,
                 self.viewport,
             );
+        });
+
+        // Alignment constraints need the position of every element
+        align_elements(self.tree, self.attribute_storage, self.viewport);
 
+        let mut filter = LayoutFilter::new(true, self.attribute_storage);
+        self.tree.for_each(&mut filter).first(&mut |widget, children, values| {
             // Paint
             self.backend
                 .paint(widget, children, values, self.attribute_storage, true);
         });
 
         self.floating();
+
+        let Some((widget_id, error)) = self.errors.drain(..).next() else { return Ok(()) };
+        let widget = match self.tree.get_ref_by_id(widget_id) {
+            Some(WidgetKind::Element(el)) => el.ident,
+            _ => "",
+        };
+
+        Err(Error::Layout {
+            widget: widget.to_string(),
+            path: self.tree.path(widget_id),
+            error,
+        })
     }
 }
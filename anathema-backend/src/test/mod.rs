@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::fmt::Display;
 
 use anathema_geometry::{Pos, Size};
@@ -5,12 +6,20 @@ use anathema_store::tree::{Node, TreeValues};
 use anathema_widgets::components::events::Event;
 use anathema_widgets::paint::CellAttributes;
 use anathema_widgets::{AttributeStorage, Element, WidgetKind, WidgetRenderer};
+use unicode_width::UnicodeWidthChar;
 
+use crate::tui::Style;
 use crate::Backend;
 
 pub struct TestBackend {
     pub surface: TestSurface,
     pub output: String,
+    /// The size of a cell in pixels, zero by default
+    pub cell_size: Size,
+    // The style of each cell, as of the last render
+    styles: Vec<Style>,
+    // The results of the calls to `next_event`, where `None` is a poll that times out
+    events: VecDeque<Option<Event>>,
 }
 
 impl TestBackend {
@@ -19,8 +28,29 @@ impl TestBackend {
         Self {
             surface: TestSurface::new(size),
             output: String::new(),
+            cell_size: Size::ZERO,
+            styles: vec![],
+            events: VecDeque::new(),
         }
     }
+
+    /// Queue an event, returned by a call to [`Backend::next_event`]
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(Some(event));
+    }
+
+    /// Queue a poll that times out without an event, e.g. to end the events of a frame.
+    /// Once the queue is empty every poll times out.
+    pub fn push_timeout(&mut self) {
+        self.events.push_back(None);
+    }
+
+    /// The style of a cell, as of the last render
+    pub fn style(&self, pos: impl Into<Pos>) -> Style {
+        let pos = pos.into();
+        let index = pos.y as usize * self.surface.size.width + pos.x as usize;
+        self.styles.get(index).copied().unwrap_or_else(Style::new)
+    }
 }
 
 impl Backend for TestBackend {
@@ -29,11 +59,15 @@ impl Backend for TestBackend {
     }
 
     fn next_event(&mut self, _timeout: std::time::Duration) -> Option<Event> {
-        None
+        self.events.pop_front().flatten()
     }
 
-    fn resize(&mut self, _new_size: Size) {
-        todo!()
+    fn resize(&mut self, new_size: Size) {
+        self.surface = TestSurface::new(new_size);
+    }
+
+    fn cell_size(&self) -> Size {
+        self.cell_size
     }
 
     fn paint<'bp>(
@@ -60,12 +94,14 @@ impl Backend for TestBackend {
 
     fn render(&mut self) {
         self.output = format!("{}", self.surface);
+        self.styles = self.surface.styles.clone();
     }
 }
 
 pub struct TestSurface {
     size: Size,
-    buffer: Vec<char>,
+    buffer: Vec<String>,
+    styles: Vec<Style>,
 }
 
 impl TestSurface {
@@ -73,13 +109,15 @@ impl TestSurface {
         let size = size.into();
         let buffer_size = size.width * size.height;
         Self {
-            buffer: vec![' '; buffer_size],
+            buffer: vec![" ".to_string(); buffer_size],
+            styles: vec![Style::new(); buffer_size],
             size,
         }
     }
 
     fn clear(&mut self) {
-        self.buffer.fill_with(|| ' ');
+        self.buffer.fill_with(|| " ".to_string());
+        self.styles.fill(Style::new());
     }
 }
 
@@ -88,24 +126,41 @@ impl WidgetRenderer for TestSurface {
         let y_offset = local_pos.y as usize * self.size.width;
         let x_offset = local_pos.x as usize;
         let index = y_offset + x_offset;
-        self.buffer[index] = c;
+        self.buffer[index] = c.to_string();
+    }
+
+    fn draw_cluster(&mut self, cluster: &str, local_pos: Pos) {
+        let y_offset = local_pos.y as usize * self.size.width;
+        let x_offset = local_pos.x as usize;
+        let index = y_offset + x_offset;
+        self.buffer[index] = cluster.to_string();
     }
 
     fn size(&self) -> Size {
         self.size
     }
 
-    fn set_attributes(&mut self, _attribs: &dyn CellAttributes, _local_pos: Pos) {
-        // NOTE: currently no attributes are stored on the test surface
+    // Same as the tui buffer: colours replace the previous ones, and the attributes are added
+    fn set_attributes(&mut self, attribs: &dyn CellAttributes, local_pos: Pos) {
+        let index = local_pos.y as usize * self.size.width + local_pos.x as usize;
+        let Some(cell) = self.styles.get_mut(index) else { return };
+        let style = Style::from_cell_attribs(attribs);
+        cell.fg = style.fg.or(cell.fg);
+        cell.bg = style.bg.or(cell.bg);
+        cell.underline_color = style.underline_color.or(cell.underline_color);
+        cell.attributes |= style.attributes;
     }
 }
 
 impl Display for TestSurface {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for y in 0..self.size.height {
-            for x in 0..self.size.width {
-                let idx = y * self.size.width + x;
-                write!(f, "{}", self.buffer[idx])?;
+            // The cell after a wide char is covered by the char
+            let mut x = 0;
+            while x < self.size.width {
+                let cluster = &self.buffer[y * self.size.width + x];
+                write!(f, "{cluster}")?;
+                x += cluster.chars().next().and_then(|c| c.width()).unwrap_or(1).max(1);
             }
             writeln!(f)?;
         }
@@ -6,12 +6,15 @@ use crossterm::style::Print;
 use crossterm::{cursor, QueueableCommand};
 use unicode_width::UnicodeWidthChar;
 
-use super::{LocalPos, Style};
+use super::link::{LinkId, Links};
+use super::{ColorSupport, LocalPos, Style};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) struct Cell {
     pub(crate) style: Style,
     pub(crate) state: CellState,
+    pub(crate) link: Option<LinkId>,
+    pub(crate) marks: Marks,
 }
 
 impl Cell {
@@ -21,6 +24,8 @@ impl Cell {
         Self {
             style: Style::reset(),
             state: CellState::Empty,
+            link: None,
+            marks: Marks::EMPTY,
         }
     }
 
@@ -28,6 +33,8 @@ impl Cell {
         Self {
             style: Style::reset(),
             state: CellState::Occupied(' '),
+            link: None,
+            marks: Marks::EMPTY,
         }
     }
 
@@ -35,6 +42,8 @@ impl Cell {
         Self {
             style,
             state: CellState::Continuation,
+            link: None,
+            marks: Marks::EMPTY,
         }
     }
 
@@ -42,8 +51,53 @@ impl Cell {
         Self {
             style,
             state: CellState::Occupied(c),
+            link: None,
+            marks: Marks::EMPTY,
         }
     }
+
+    fn cluster(cluster: &str, style: Style) -> Self {
+        let mut chars = cluster.chars();
+        let c = chars.next().unwrap_or(' ');
+        Self {
+            style,
+            state: CellState::Occupied(c),
+            link: None,
+            marks: Marks::new(chars.as_str()),
+        }
+    }
+}
+
+/// The chars following the first char of a grapheme cluster, e.g combining marks.
+/// These are stored inline so a [`Cell`] can be copied,
+/// and are truncated to the chars that fit.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Marks {
+    bytes: [u8; 15],
+    len: u8,
+}
+
+impl Marks {
+    const EMPTY: Self = Self { bytes: [0; 15], len: 0 };
+
+    fn new(s: &str) -> Self {
+        let mut marks = Self::EMPTY;
+        for (index, c) in s.char_indices() {
+            let end = index + c.len_utf8();
+            if end > marks.bytes.len() {
+                break;
+            }
+            marks.len = end as u8;
+        }
+        let len = marks.len as usize;
+        marks.bytes[..len].copy_from_slice(&s.as_bytes()[..len]);
+        marks
+    }
+
+    fn as_str(&self) -> &str {
+        // The bytes always end on a char boundary
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
 }
 
 /// Represent the state of a cell inside a [`Buffer`].
@@ -128,6 +182,18 @@ impl Buffer {
         self.put(cell, pos);
     }
 
+    /// Put a grapheme cluster, e.g a char followed by combining marks,
+    /// with a style at a given position.
+    /// The cluster occupies as many cells as its first char.
+    pub fn put_cluster(&mut self, cluster: &str, pos: LocalPos) {
+        let style = match self.get(pos) {
+            Some((_, style)) => *style,
+            None => Style::new(),
+        };
+        let cell = Cell::cluster(cluster, style);
+        self.put(cell, pos);
+    }
+
     /// Update the attributes at a given cell.
     /// If there is no character at that cell, then write an empty space into it
     pub fn update_cell(&mut self, style: Style, pos: LocalPos) {
@@ -146,6 +212,10 @@ impl Buffer {
             cell.style.bg = bg;
         }
 
+        if let color @ Some(_) = style.underline_color {
+            cell.style.underline_color = color;
+        }
+
         cell.style.attributes |= style.attributes;
 
         if let CellState::Empty = cell.state {
@@ -153,6 +223,16 @@ impl Buffer {
         }
     }
 
+    /// Make the cell at a given position part of a hyperlink
+    pub(crate) fn set_link(&mut self, link: LinkId, pos: LocalPos) {
+        if pos.x as usize >= self.size.width || pos.y as usize >= self.size.height {
+            return;
+        }
+
+        let index = pos.to_index(self.size.width);
+        self.inner[index].link = Some(link);
+    }
+
     /// Get a reference to a `char` and [`Style`] at a given position inside the buffer.
     pub fn get(&self, pos: LocalPos) -> Option<(&char, &Style)> {
         let index = self.index(pos);
@@ -179,6 +259,19 @@ impl Buffer {
         self.inner[index] = Cell::empty();
     }
 
+    /// Force the cells in a region to be drawn again on the next render,
+    /// e.g. after an image covering them has been removed.
+    pub(crate) fn invalidate(&mut self, pos: LocalPos, size: Size) {
+        let to_x = (pos.x as usize + size.width).min(self.size.width);
+        let to_y = (pos.y as usize + size.height).min(self.size.height);
+        for y in pos.y as usize..to_y {
+            for x in pos.x as usize..to_x {
+                let index = self.index(LocalPos::new(x as u16, y as u16));
+                self.inner[index] = Cell::continuation(Style::reset());
+            }
+        }
+    }
+
     /// An iterator over all the rows in the buffer
     pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Option<(char, Style)>> + '_> {
         self.cell_lines().map(|chunk| {
@@ -196,13 +289,39 @@ impl Buffer {
     fn put(&mut self, mut cell: Cell, pos: LocalPos) {
         let index = self.index(pos);
 
-        if let CellState::Occupied(c) = cell.state {
+        // Overwriting the second half of a wide char leaves the first half on its own,
+        // and overwriting the first half leaves the second half,
+        // so replace what is left with a space.
+        if pos.x > 0 && self.inner[index].state == CellState::Continuation {
+            let previous = &mut self.inner[index - 1];
+            if let CellState::Occupied(ref mut c) = previous.state {
+                *c = ' ';
+                previous.marks = Marks::EMPTY;
+            }
+        }
+
+        if let CellState::Occupied(c) = self.inner[index].state {
+            let next = pos.x as usize + 1;
+            if c.width().unwrap_or(1) > 1 && next < self.size.width {
+                if let CellState::Continuation = self.inner[index + 1].state {
+                    self.inner[index + 1].state = CellState::Occupied(' ');
+                    self.inner[index + 1].marks = Marks::EMPTY;
+                }
+            }
+        }
+
+        if let CellState::Occupied(ref mut c) = cell.state {
             // If this is a unicode char that is wider than one cell,
             // add a continuation cell if it fits, this way if we overwrite it
             // we can set the continuation cell to `Empty`.
-            if pos.x < self.size.width as u16 {
-                if let Some(2..) = c.width() {
-                    self.put(Cell::continuation(cell.style), LocalPos::new(pos.x + 1, pos.y));
+            // A wide char that doesn't fit at the end of the line is replaced by a space.
+            if let Some(2..) = c.width() {
+                match pos.x as usize + 1 < self.size.width {
+                    true => self.put(Cell::continuation(cell.style), LocalPos::new(pos.x + 1, pos.y)),
+                    false => {
+                        *c = ' ';
+                        cell.marks = Marks::EMPTY;
+                    }
                 }
             }
         }
@@ -214,6 +333,7 @@ impl Buffer {
             // Merge the styles
             (CellState::Occupied(ref mut current_char), CellState::Occupied(new_char)) => {
                 *current_char = new_char;
+                current.marks = cell.marks;
                 current.style.attributes |= cell.style.attributes;
 
                 if let Some(col) = cell.style.fg {
@@ -240,6 +360,11 @@ impl Buffer {
         self.inner[index]
     }
 
+    /// Get the character at a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is not occupied by a character.
     pub fn char_at(&self, x: usize, y: usize) -> char {
         let cell = self.cell_at(x, y);
         match cell.state {
@@ -252,19 +377,23 @@ impl Buffer {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum Change {
     Remove,
-    Insert(char),
+    Insert(char, Marks),
 }
 
 impl Change {
     fn width(self) -> usize {
         match self {
             Change::Remove => 1,
-            Change::Insert(c) => c.width().unwrap_or(1),
+            Change::Insert(c, _) => c.width().unwrap_or(1),
         }
     }
 }
 
-pub(crate) fn diff(old: &Buffer, new: &Buffer, changes: &mut Vec<(LocalPos, Option<Style>, Change)>) -> Result<()> {
+pub(crate) fn diff(
+    old: &Buffer,
+    new: &Buffer,
+    changes: &mut Vec<(LocalPos, Option<Style>, Option<LinkId>, Change)>,
+) -> Result<()> {
     let mut previous_style = None;
 
     for (y, (old_line, new_line)) in old.cell_lines().zip(new.cell_lines()).enumerate() {
@@ -286,10 +415,10 @@ pub(crate) fn diff(old: &Buffer, new: &Buffer, changes: &mut Vec<(LocalPos, Opti
             let change = match new_cell.state {
                 CellState::Empty => Change::Remove,
                 CellState::Continuation => continue,
-                CellState::Occupied(c) => Change::Insert(c),
+                CellState::Occupied(c) => Change::Insert(c, new_cell.marks),
             };
 
-            changes.push((LocalPos::new(x, y), style, change));
+            changes.push((LocalPos::new(x, y), style, new_cell.link, change));
         }
     }
 
@@ -299,11 +428,17 @@ pub(crate) fn diff(old: &Buffer, new: &Buffer, changes: &mut Vec<(LocalPos, Opti
 // -----------------------------------------------------------------------------
 //     - Draw changes -
 // -----------------------------------------------------------------------------
-pub(crate) fn draw_changes(mut w: impl Write, changes: &Vec<(LocalPos, Option<Style>, Change)>) -> Result<()> {
+pub(crate) fn draw_changes(
+    mut w: impl Write,
+    changes: &Vec<(LocalPos, Option<Style>, Option<LinkId>, Change)>,
+    colors: ColorSupport,
+    links: Option<&Links>,
+) -> Result<()> {
     let mut last_y = None;
     let mut next_cell_x = None;
+    let mut current_link = None;
 
-    for (screen_pos, style, change) in changes {
+    for (screen_pos, style, link, change) in changes {
         // Cursor movement
         let should_move = match (last_y, next_cell_x) {
             (Some(last_y), Some(next_x)) => screen_pos.y > last_y || next_x != screen_pos.x,
@@ -319,16 +454,28 @@ pub(crate) fn draw_changes(mut w: impl Write, changes: &Vec<(LocalPos, Option<St
 
         // Apply style
         if let Some(style) = style {
-            style.write(&mut w)?;
+            style.write(&mut w, colors)?;
+        }
+
+        // Start or end a hyperlink, unless hyperlinks are disabled
+        if let Some(links) = links {
+            if *link != current_link {
+                links.write(&mut w, *link)?;
+                current_link = *link;
+            }
         }
 
         // Draw changes
         match change {
-            Change::Insert(c) => w.queue(Print(c))?,
+            Change::Insert(c, marks) => w.queue(Print(c))?.queue(Print(marks.as_str()))?,
             Change::Remove => w.queue(Print(' '))?,
         };
     }
 
+    if let (Some(links), Some(_)) = (links, current_link) {
+        links.write(&mut w, None)?;
+    }
+
     Ok(())
 }
 
@@ -354,13 +501,79 @@ mod test {
 
         diff(&old_buffer, &new_buffer, &mut changes).unwrap();
 
-        let (_, _, change_1) = changes[0]; // Insert 'C'
-        let (_, _, change_2) = changes[1]; // Remove 'V'
-        let (_, _, change_3) = changes[2]; // Insert 'N'
+        let (_, _, _, change_1) = changes[0]; // Insert 'C'
+        let (_, _, _, change_2) = changes[1]; // Remove 'V'
+        let (_, _, _, change_3) = changes[2]; // Insert 'N'
 
-        assert_eq!(Change::Insert('C'), change_1);
+        assert_eq!(Change::Insert('C', Marks::EMPTY), change_1);
         assert_eq!(Change::Remove, change_2);
-        assert_eq!(Change::Insert('N'), change_3);
+        assert_eq!(Change::Insert('N', Marks::EMPTY), change_3);
+    }
+
+    #[test]
+    fn wide_chars() {
+        let mut buffer = Buffer::new((4u16, 1));
+        buffer.put_char('🐇', LocalPos::new(0, 0));
+        assert_eq!(buffer.cell_at(1, 0).state, CellState::Continuation);
+
+        // Overwriting the first half clears the second half
+        buffer.put_char('a', LocalPos::new(0, 0));
+        assert_eq!(buffer.char_at(0, 0), 'a');
+        assert_eq!(buffer.char_at(1, 0), ' ');
+
+        // Overwriting the second half clears the first half
+        buffer.put_char('漢', LocalPos::new(1, 0));
+        buffer.put_char('b', LocalPos::new(2, 0));
+        assert_eq!(buffer.char_at(1, 0), ' ');
+        assert_eq!(buffer.char_at(2, 0), 'b');
+
+        // A wide char doesn't fit in the last cell
+        buffer.put_char('漢', LocalPos::new(3, 0));
+        assert_eq!(buffer.char_at(3, 0), ' ');
+    }
+
+    #[test]
+    fn clusters() {
+        let mut buffer = Buffer::new((3u16, 1));
+        buffer.put_cluster("e\u{301}", LocalPos::new(0, 0));
+        assert_eq!(buffer.char_at(0, 0), 'e');
+        assert_eq!(buffer.cell_at(0, 0).marks.as_str(), "\u{301}");
+
+        let mut changes = vec![];
+        diff(&Buffer::new((3u16, 1)), &buffer, &mut changes).unwrap();
+        let mut output = vec![];
+        draw_changes(&mut output, &changes, ColorSupport::TrueColor, None).unwrap();
+        assert!(String::from_utf8(output).unwrap().ends_with("e\u{301}"));
+
+        // Putting a char in the cell removes the marks
+        buffer.put_char('a', LocalPos::new(0, 0));
+        assert_eq!(buffer.cell_at(0, 0).marks, Marks::EMPTY);
+    }
+
+    #[test]
+    fn hyperlinks() {
+        let mut links = Links::default();
+        let link = links.insert("https://example.com");
+
+        let old_buffer = Buffer::new((3u16, 1));
+        let mut new_buffer = Buffer::new((3u16, 1));
+        for x in 0..3 {
+            new_buffer.put_char('a', LocalPos::new(x, 0));
+        }
+        new_buffer.set_link(link, LocalPos::new(1, 0));
+
+        let mut changes = vec![];
+        diff(&old_buffer, &new_buffer, &mut changes).unwrap();
+
+        let mut output = vec![];
+        draw_changes(&mut output, &changes, ColorSupport::TrueColor, Some(&links)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with("a\x1b]8;id=0;https://example.com\x1b\\a\x1b]8;;\x1b\\a"));
+
+        // Nothing is written when hyperlinks are disabled
+        let mut output = vec![];
+        draw_changes(&mut output, &changes, ColorSupport::TrueColor, None).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("\x1b]8"));
     }
 
     #[test]
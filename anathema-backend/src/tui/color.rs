@@ -0,0 +1,179 @@
+//! Colors supported by the terminal.
+use anathema_state::Color;
+
+// The 16 standard colors, using the xterm defaults
+const PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// The levels of each channel in the 6x6x6 color cube of the 256 colors
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The colors the terminal can display.
+///
+/// Colors that the terminal can't display are replaced with the nearest color it can display
+/// when they are written to the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// The 16 standard colors
+    Ansi16,
+    /// The 256 indexed colors
+    Ansi256,
+    /// 24 bit colors
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detect the color support from the environment.
+    ///
+    /// Set `ANATHEMA_COLORS` to `16`, `256` or `truecolor` to override
+    /// the detection.
+    pub fn detect() -> Self {
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    /// Detect the color support using a function to look up environment variables.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(colors) = var("ANATHEMA_COLORS") {
+            match colors.to_lowercase().as_str() {
+                "16" => return Self::Ansi16,
+                "256" => return Self::Ansi256,
+                "truecolor" | "24bit" => return Self::TrueColor,
+                _ => {}
+            }
+        }
+
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || var("WT_SESSION").is_some() {
+            return Self::TrueColor;
+        }
+
+        let term = var("TERM").unwrap_or_default();
+        match term.contains("256") {
+            true => Self::Ansi256,
+            false => Self::Ansi16,
+        }
+    }
+
+    /// The nearest color the terminal can display
+    pub fn downgrade(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, _) => color,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::AnsiVal(nearest_256((r, g, b))),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+            (Self::Ansi16, Color::AnsiVal(index @ 0..=15)) => PALETTE[index as usize].0,
+            (Self::Ansi16, Color::AnsiVal(index)) => nearest_16(indexed_to_rgb(index)),
+            _ => color,
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    PALETTE
+        .iter()
+        .min_by_key(|(_, palette)| distance(rgb, *palette))
+        .map(|(color, _)| *color)
+        .unwrap_or_default()
+}
+
+// The nearest color of the color cube or the greyscale ramp
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE.len())
+            .min_by_key(|i| (CUBE[*i] as i32 - c as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    match distance(rgb, indexed_to_rgb(grey)) < distance(rgb, indexed_to_rgb(cube)) {
+        true => grey,
+        false => cube,
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => PALETTE[index as usize].1,
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE[(index / 36) as usize],
+                CUBE[(index / 6 % 6) as usize],
+                CUBE[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn detect_color_support() {
+        assert_eq!(
+            ColorSupport::from_env(env(&[("COLORTERM", "truecolor")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(env(&[("TERM", "xterm-256color")])),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(env(&[("TERM", "xterm-256color"), ("ANATHEMA_COLORS", "16")])),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(ColorSupport::from_env(env(&[("TERM", "linux")])), ColorSupport::Ansi16);
+    }
+
+    #[test]
+    fn downgrade_colors() {
+        let support = ColorSupport::Ansi256;
+        assert_eq!(support.downgrade(Color::Rgb(255, 0, 0)), Color::AnsiVal(196));
+        assert_eq!(support.downgrade(Color::Rgb(128, 128, 128)), Color::AnsiVal(244));
+        assert_eq!(support.downgrade(Color::Red), Color::Red);
+
+        let support = ColorSupport::Ansi16;
+        assert_eq!(support.downgrade(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(support.downgrade(Color::AnsiVal(4)), Color::Blue);
+        assert_eq!(support.downgrade(Color::AnsiVal(232)), Color::Black);
+        assert_eq!(support.downgrade(Color::Reset), Color::Reset);
+
+        assert_eq!(
+            ColorSupport::TrueColor.downgrade(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+}
@@ -0,0 +1,578 @@
+//! Terminal graphics.
+//!
+//! Images are drawn using the kitty graphics protocol, sixel or iTerm2 inline images,
+//! depending on what the terminal supports.
+//!
+//! The protocol is picked from the environment (see [`GraphicsProtocol::detect`]),
+//! and if none of them are supported the images are drawn with unicode half blocks instead.
+use std::io::{Result, Write};
+
+use anathema_geometry::Size;
+use anathema_state::Color;
+use crossterm::{cursor, QueueableCommand};
+
+use super::LocalPos;
+
+// Used to calculate the footprint of an image in cells, if the size of a cell is unknown
+const DEFAULT_CELL_SIZE: Size = Size::new(8, 16);
+// The maximum size of a chunk of data in the kitty graphics protocol
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Graphics protocols for drawing images in the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum GraphicsProtocol {
+    /// The kitty graphics protocol (kitty, WezTerm, ghostty, Konsole)
+    Kitty,
+    /// Sixel graphics (foot, mlterm, contour, xterm with sixel support)
+    Sixel,
+    /// iTerm2 inline images (iTerm2, mintty)
+    ITerm2,
+    /// No graphics support: images are drawn using unicode half blocks
+    #[default]
+    HalfBlocks,
+}
+
+impl GraphicsProtocol {
+    /// Detect the graphics protocol from the environment.
+    ///
+    /// Set `ANATHEMA_GRAPHICS` to `kitty`, `sixel`, `iterm` or `none` to override
+    /// the detection.
+    pub fn detect() -> Self {
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    /// Detect the graphics protocol using a function to look up environment variables.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(protocol) = var("ANATHEMA_GRAPHICS") {
+            match protocol.to_lowercase().as_str() {
+                "kitty" => return Self::Kitty,
+                "sixel" => return Self::Sixel,
+                "iterm" | "iterm2" => return Self::ITerm2,
+                "none" | "halfblocks" => return Self::HalfBlocks,
+                _ => {}
+            }
+        }
+
+        // Multiplexers don't pass any of these through
+        if var("TMUX").is_some() || var("STY").is_some() {
+            return Self::HalfBlocks;
+        }
+
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+
+        if var("KITTY_WINDOW_ID").is_some() || var("KONSOLE_VERSION").is_some() || term.contains("kitty") {
+            return Self::Kitty;
+        }
+
+        match program.as_str() {
+            "WezTerm" | "ghostty" => return Self::Kitty,
+            "iTerm.app" | "mintty" => return Self::ITerm2,
+            _ => {}
+        }
+
+        if ["foot", "mlterm", "contour", "sixel"].iter().any(|t| term.contains(t)) {
+            return Self::Sixel;
+        }
+
+        Self::HalfBlocks
+    }
+}
+
+/// The number of cells an image covers.
+///
+/// If only one of `width` or `height` is given, the other one is calculated
+/// from the aspect ratio of the image. If neither is given the image is
+/// displayed at its pixel size.
+/// If `cell_size` is zero a cell is assumed to be 8x16 pixels.
+pub fn image_cells(image_size: Size, width: Option<usize>, height: Option<usize>, cell_size: Size) -> Size {
+    let cell_size = match cell_size.width == 0 || cell_size.height == 0 {
+        true => DEFAULT_CELL_SIZE,
+        false => cell_size,
+    };
+
+    if image_size.width == 0 || image_size.height == 0 {
+        return Size::ZERO;
+    }
+
+    // Image size in cells, not rounded
+    let cols = image_size.width as f64 / cell_size.width as f64;
+    let rows = image_size.height as f64 / cell_size.height as f64;
+
+    let (cols, rows) = match (width, height) {
+        (Some(width), Some(height)) => (width as f64, height as f64),
+        (Some(width), None) => (width as f64, rows * width as f64 / cols),
+        (None, Some(height)) => (cols * height as f64 / rows, height as f64),
+        (None, None) => (cols, rows),
+    };
+
+    Size::new(cols.ceil().max(1.0) as usize, rows.ceil().max(1.0) as usize)
+}
+
+/// Draw the image using unicode half blocks (`▀`), two pixels per cell.
+/// Returns the top and bottom color of each cell, row by row.
+pub fn half_blocks(pixels: &[u8], image_size: Size, cells: Size) -> Vec<(Color, Color)> {
+    let scaled = resize(pixels, image_size, Size::new(cells.width, cells.height * 2));
+    let pixel = |x: usize, y: usize| {
+        let i = (y * cells.width + x) * 4;
+        let alpha = scaled[i + 3] as u16;
+        let channel = |c: u8| (c as u16 * alpha / 255) as u8;
+        Color::Rgb(channel(scaled[i]), channel(scaled[i + 1]), channel(scaled[i + 2]))
+    };
+
+    let mut output = Vec::with_capacity(cells.width * cells.height);
+    for y in 0..cells.height {
+        for x in 0..cells.width {
+            output.push((pixel(x, y * 2), pixel(x, y * 2 + 1)));
+        }
+    }
+    output
+}
+
+/// Encode an image as an escape sequence for the given protocol.
+///
+/// The `id` is used by the kitty protocol to remove the image again,
+/// and `cell_size` is the size of a cell in pixels (used by sixel as it can't scale the image).
+///
+/// Returns `None` for [`GraphicsProtocol::HalfBlocks`].
+pub fn encode(
+    protocol: GraphicsProtocol,
+    pixels: &[u8],
+    image_size: Size,
+    cells: Size,
+    cell_size: Size,
+    id: u32,
+) -> Option<String> {
+    if pixels.len() < image_size.width * image_size.height * 4 {
+        return None;
+    }
+
+    let output = match protocol {
+        GraphicsProtocol::Kitty => kitty(pixels, image_size, cells, id),
+        GraphicsProtocol::Sixel => {
+            let cell_size = match cell_size.width == 0 || cell_size.height == 0 {
+                true => DEFAULT_CELL_SIZE,
+                false => cell_size,
+            };
+            let size = Size::new(cells.width * cell_size.width, cells.height * cell_size.height);
+            sixel(&resize(pixels, image_size, size), size)
+        }
+        GraphicsProtocol::ITerm2 => {
+            let png = png(pixels, image_size);
+            format!(
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+                png.len(),
+                cells.width,
+                cells.height,
+                base64(&png)
+            )
+        }
+        GraphicsProtocol::HalfBlocks => return None,
+    };
+
+    Some(output)
+}
+
+fn kitty(pixels: &[u8], image_size: Size, cells: Size, id: u32) -> String {
+    let data = base64(&pixels[..image_size.width * image_size.height * 4]);
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+
+    let mut output = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        // Chunks are always valid utf8 as base64 is ascii
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        match i {
+            0 => output.push_str(&format!(
+                "\x1b_Ga=T,f=32,s={},v={},c={},r={},i={id},q=2,C=1,m={more};{chunk}\x1b\\",
+                image_size.width, image_size.height, cells.width, cells.height
+            )),
+            _ => output.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\")),
+        }
+    }
+    output
+}
+
+/// Remove an image drawn with the kitty graphics protocol
+fn kitty_delete(id: u32) -> String {
+    format!("\x1b_Ga=d,d=i,i={id},q=2\x1b\\")
+}
+
+// Sixel, using a 6x6x6 color cube as the palette.
+// Transparent pixels are not drawn.
+fn sixel(pixels: &[u8], size: Size) -> String {
+    const LEVELS: usize = 6;
+
+    let index = |x: usize, y: usize| -> Option<usize> {
+        let i = (y * size.width + x) * 4;
+        if pixels[i + 3] < 128 {
+            return None;
+        }
+        let level = |c: u8| (c as usize * (LEVELS - 1) + 127) / 255;
+        Some(level(pixels[i]) * LEVELS * LEVELS + level(pixels[i + 1]) * LEVELS + level(pixels[i + 2]))
+    };
+
+    // P2 = 1: pixels that are not set keep their current color
+    let mut output = format!("\x1bP0;1;0q\"1;1;{};{}", size.width, size.height);
+
+    for color in 0..LEVELS * LEVELS * LEVELS {
+        let percent = |level: usize| level * 100 / (LEVELS - 1);
+        let (r, g, b) = (color / (LEVELS * LEVELS), color / LEVELS % LEVELS, color % LEVELS);
+        output.push_str(&format!("#{color};2;{};{};{}", percent(r), percent(g), percent(b)));
+    }
+
+    for band in (0..size.height).step_by(6) {
+        let rows = (size.height - band).min(6);
+
+        let mut colors = vec![];
+        for y in band..band + rows {
+            for x in 0..size.width {
+                if let Some(color) = index(x, y) {
+                    if !colors.contains(&color) {
+                        colors.push(color);
+                    }
+                }
+            }
+        }
+
+        for color in colors {
+            output.push_str(&format!("#{color}"));
+
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..size.width {
+                let mut bits = 0;
+                for dy in 0..rows {
+                    if index(x, band + dy) == Some(color) {
+                        bits |= 1 << dy;
+                    }
+                }
+
+                let c = 63 + bits;
+                match run.as_mut() {
+                    Some((prev, count)) if *prev == c => *count += 1,
+                    _ => {
+                        if let Some((prev, count)) = run.take() {
+                            push_sixel_run(&mut output, prev, count);
+                        }
+                        run = Some((c, 1));
+                    }
+                }
+            }
+
+            if let Some((prev, count)) = run {
+                push_sixel_run(&mut output, prev, count);
+            }
+
+            // Carriage return, to draw the next color over the same band
+            output.push('$');
+        }
+
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    output
+}
+
+fn push_sixel_run(output: &mut String, c: u8, count: usize) {
+    match count {
+        0..=3 => (0..count).for_each(|_| output.push(c as char)),
+        _ => output.push_str(&format!("!{count}{}", c as char)),
+    }
+}
+
+// Nearest neighbour resize
+fn resize(pixels: &[u8], from: Size, to: Size) -> Vec<u8> {
+    let mut output = vec![0; to.width * to.height * 4];
+    if from.width == 0 || from.height == 0 {
+        return output;
+    }
+
+    for y in 0..to.height {
+        let src_y = y * from.height / to.height.max(1);
+        for x in 0..to.width {
+            let src_x = x * from.width / to.width.max(1);
+            let src = (src_y * from.width + src_x) * 4;
+            let dst = (y * to.width + x) * 4;
+            output[dst..dst + 4].copy_from_slice(&pixels[src..src + 4]);
+        }
+    }
+
+    output
+}
+
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        output.push(TABLE[(n >> 18) as usize & 63] as char);
+        output.push(TABLE[(n >> 12) as usize & 63] as char);
+        match chunk.len() {
+            1 => output.push_str("=="),
+            2 => {
+                output.push(TABLE[(n >> 6) as usize & 63] as char);
+                output.push('=');
+            }
+            _ => {
+                output.push(TABLE[(n >> 6) as usize & 63] as char);
+                output.push(TABLE[n as usize & 63] as char);
+            }
+        }
+    }
+    output
+}
+
+// Uncompressed PNG, as iTerm2 requires an image file format
+fn png(pixels: &[u8], size: Size) -> Vec<u8> {
+    fn chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        output.extend((data.len() as u32).to_be_bytes());
+        let start = output.len();
+        output.extend(kind);
+        output.extend(data);
+        let crc = crc32(&output[start..]);
+        output.extend(crc.to_be_bytes());
+    }
+
+    let mut header = vec![];
+    header.extend((size.width as u32).to_be_bytes());
+    header.extend((size.height as u32).to_be_bytes());
+    // Bit depth, color type (rgba), compression, filter, interlace
+    header.extend([8, 6, 0, 0, 0]);
+
+    // Every row starts with the filter type (none)
+    let mut raw = Vec::with_capacity((size.width * 4 + 1) * size.height);
+    for row in pixels[..size.width * size.height * 4].chunks(size.width * 4) {
+        raw.push(0);
+        raw.extend(row);
+    }
+
+    // zlib stream with stored (uncompressed) deflate blocks
+    let mut data = vec![0x78, 0x01];
+    let blocks = raw.chunks(u16::MAX as usize).collect::<Vec<_>>();
+    for (i, block) in blocks.iter().enumerate() {
+        let len = block.len() as u16;
+        data.push((i + 1 == blocks.len()) as u8);
+        data.extend(len.to_le_bytes());
+        data.extend((!len).to_le_bytes());
+        data.extend(*block);
+    }
+    if blocks.is_empty() {
+        data.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    data.extend(adler32(&raw).to_be_bytes());
+
+    let mut output = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut output, b"IHDR", &header);
+    chunk(&mut output, b"IDAT", &data);
+    chunk(&mut output, b"IEND", &[]);
+    output
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xedb8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
+
+// FNV-1a, used to tell if an image has changed between frames
+fn image_id(pixels: &[u8], size: Size) -> u32 {
+    let mut hash = 0x811c_9dc5u32;
+    let size = [(size.width as u32).to_le_bytes(), (size.height as u32).to_le_bytes()];
+    for &byte in size.iter().flatten().chain(pixels) {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    // Kitty image ids can't be zero
+    hash.max(1)
+}
+
+#[derive(Debug, PartialEq)]
+struct Placement {
+    pos: LocalPos,
+    cells: Size,
+    id: u32,
+    // `None` if the image is already on screen
+    data: Option<String>,
+}
+
+impl Placement {
+    fn same_as(&self, other: &Placement) -> bool {
+        self.pos == other.pos && self.cells == other.cells && self.id == other.id
+    }
+}
+
+/// Images drawn by the [`crate::tui::Screen`].
+#[derive(Debug, Default)]
+pub(crate) struct Images {
+    pub(crate) protocol: GraphicsProtocol,
+    pub(crate) cell_size: Size,
+    current: Vec<Placement>,
+    previous: Vec<Placement>,
+}
+
+impl Images {
+    pub(crate) fn add(&mut self, pixels: &[u8], image_size: Size, pos: LocalPos, cells: Size) -> bool {
+        if self.protocol == GraphicsProtocol::HalfBlocks {
+            return false;
+        }
+
+        let mut placement = Placement {
+            pos,
+            cells,
+            id: image_id(pixels, image_size),
+            data: None,
+        };
+
+        if !self.previous.iter().any(|p| p.same_as(&placement)) {
+            match encode(self.protocol, pixels, image_size, cells, self.cell_size, placement.id) {
+                Some(data) => placement.data = Some(data),
+                None => return false,
+            }
+        }
+
+        self.current.push(placement);
+        true
+    }
+
+    /// Images that were drawn in the previous frame but not in this one.
+    /// Call `remove` first to get rid of the old images, and then `draw` once the cells are drawn.
+    pub(crate) fn removed(&self) -> impl Iterator<Item = (LocalPos, Size)> + '_ {
+        self.previous
+            .iter()
+            .filter(|prev| !self.current.iter().any(|p| p.same_as(prev)))
+            .map(|p| (p.pos, p.cells))
+    }
+
+    pub(crate) fn has_changes(&self) -> bool {
+        self.current.iter().any(|p| p.data.is_some()) || self.removed().next().is_some()
+    }
+
+    pub(crate) fn remove(&self, mut output: impl Write) -> Result<()> {
+        if self.protocol != GraphicsProtocol::Kitty {
+            return Ok(());
+        }
+
+        for prev in &self.previous {
+            if !self.current.iter().any(|p| p.same_as(prev)) {
+                output.write_all(kitty_delete(prev.id).as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn draw(&mut self, mut output: impl Write) -> Result<()> {
+        for placement in &mut self.current {
+            let Some(data) = placement.data.take() else { continue };
+            output.queue(cursor::MoveTo(placement.pos.x, placement.pos.y))?;
+            output.write_all(data.as_bytes())?;
+        }
+
+        self.previous = std::mem::take(&mut self.current);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn detect_protocol() {
+        assert_eq!(GraphicsProtocol::from_env(env(&[("TERM", "xterm-kitty")])), GraphicsProtocol::Kitty);
+        assert_eq!(GraphicsProtocol::from_env(env(&[("TERM_PROGRAM", "iTerm.app")])), GraphicsProtocol::ITerm2);
+        assert_eq!(GraphicsProtocol::from_env(env(&[("TERM", "foot")])), GraphicsProtocol::Sixel);
+        assert_eq!(GraphicsProtocol::from_env(env(&[("TERM", "xterm")])), GraphicsProtocol::HalfBlocks);
+
+        let vars = [("TERM", "xterm-kitty"), ("ANATHEMA_GRAPHICS", "sixel")];
+        assert_eq!(GraphicsProtocol::from_env(env(&vars)), GraphicsProtocol::Sixel);
+    }
+
+    #[test]
+    fn cell_footprint() {
+        let cell = Size::new(8, 16);
+        assert_eq!(image_cells(Size::new(80, 80), None, None, cell), Size::new(10, 5));
+        assert_eq!(image_cells(Size::new(80, 80), Some(20), None, cell), Size::new(20, 10));
+        assert_eq!(image_cells(Size::new(80, 80), None, Some(2), cell), Size::new(4, 2));
+        assert_eq!(image_cells(Size::new(80, 80), Some(3), Some(3), cell), Size::new(3, 3));
+    }
+
+    #[test]
+    fn encode_base64() {
+        assert_eq!(base64(b"a"), "YQ==");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+    }
+
+    #[test]
+    fn png_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let png = png(&[255, 0, 0, 255], Size::new(1, 1));
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn half_block_colors() {
+        // One red pixel above one blue pixel
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 255];
+        let cells = half_blocks(&pixels, Size::new(1, 2), Size::new(1, 1));
+        assert_eq!(cells, vec![(Color::Rgb(255, 0, 0), Color::Rgb(0, 0, 255))]);
+    }
+
+    #[test]
+    fn sixel_encoding() {
+        let pixels = [255, 255, 255, 255].repeat(8);
+        let sixel = sixel(&pixels, Size::new(8, 1));
+        // White is the last color in the palette, and the top row is set in all eight columns
+        assert!(sixel.ends_with("#215!8@$-\x1b\\"));
+    }
+
+    #[test]
+    fn unchanged_images_are_not_redrawn() {
+        let mut images = Images {
+            protocol: GraphicsProtocol::Kitty,
+            ..Default::default()
+        };
+        let pixels = [0; 4];
+        let size = Size::new(1, 1);
+
+        assert!(images.add(&pixels, size, LocalPos::ZERO, size));
+        assert!(images.has_changes());
+        images.draw(vec![]).unwrap();
+
+        images.add(&pixels, size, LocalPos::ZERO, size);
+        assert!(!images.has_changes());
+        images.draw(vec![]).unwrap();
+
+        // Not drawn this frame
+        assert!(images.has_changes());
+        assert_eq!(images.removed().count(), 1);
+    }
+}
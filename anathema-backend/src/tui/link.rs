@@ -0,0 +1,87 @@
+use std::io::{Result, Write};
+
+/// The id of a link url in [`Links`], stored in each cell of the link
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct LinkId(u32);
+
+/// The urls of the hyperlinks painted in the current frame.
+#[derive(Debug, Default)]
+pub(crate) struct Links {
+    urls: Vec<String>,
+}
+
+impl Links {
+    /// The id of the url, adding it if it's new
+    pub(crate) fn insert(&mut self, url: &str) -> LinkId {
+        // Control characters would end the escape sequence early
+        let url = url.chars().filter(|c| !c.is_control()).collect::<String>();
+
+        let index = match self.urls.iter().position(|u| *u == url) {
+            Some(index) => index,
+            None => {
+                self.urls.push(url);
+                self.urls.len() - 1
+            }
+        };
+
+        LinkId(index as u32)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.urls.clear();
+    }
+
+    /// Start the link (OSC 8), or end the current link if `link` is `None`.
+    /// The id makes terminals treat a link that is wrapped across lines as one link.
+    pub(crate) fn write(&self, w: &mut impl Write, link: Option<LinkId>) -> Result<()> {
+        match link.and_then(|LinkId(id)| Some((id, self.urls.get(id as usize)?))) {
+            Some((id, url)) => write!(w, "\x1b]8;id={id};{url}\x1b\\"),
+            None => write!(w, "\x1b]8;;\x1b\\"),
+        }
+    }
+}
+
+/// Hyperlinks are written unless the terminal is known to print the escape sequence
+/// instead of ignoring it.
+///
+/// Set `ANATHEMA_HYPERLINKS` to `0` or `1` to override the detection.
+pub(crate) fn detect_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    match var("ANATHEMA_HYPERLINKS").as_deref() {
+        Some("0") => return false,
+        Some("1") => return true,
+        _ => {}
+    }
+
+    !matches!(var("TERM").as_deref(), Some("linux" | "dumb"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_links() {
+        let mut links = Links::default();
+        let a = links.insert("https://a.example");
+        let b = links.insert("https://b.example\x1b]");
+        assert_eq!(links.insert("https://a.example"), a);
+
+        let mut output = vec![];
+        links.write(&mut output, Some(b)).unwrap();
+        links.write(&mut output, None).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b]8;id=1;https://b.example]\x1b\\\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn detect() {
+        let env = |term: &'static str| move |key: &str| (key == "TERM").then(|| term.to_string());
+        assert!(detect_hyperlinks(env("xterm-256color")));
+        assert!(!detect_hyperlinks(env("linux")));
+        assert!(!detect_hyperlinks(
+            |key| (key == "ANATHEMA_HYPERLINKS").then(|| "0".into())
+        ));
+    }
+}
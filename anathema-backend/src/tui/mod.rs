@@ -11,17 +11,22 @@ use anathema_geometry::{LocalPos, Pos, Size};
 use anathema_store::tree::{Node, TreeValues};
 use anathema_widgets::components::events::Event;
 use anathema_widgets::{AttributeStorage, Element, WidgetKind, WidgetRenderer};
-use crossterm::terminal::size;
+use crossterm::terminal::{size, window_size};
 pub use screen::Screen;
 
 pub use self::buffer::Buffer;
+pub use self::color::ColorSupport;
 use self::events::Events;
+pub use self::image::GraphicsProtocol;
 pub use self::style::{Attributes, Style};
 use crate::Backend;
 
 mod buffer;
+pub mod color;
 /// Events
 pub mod events;
+pub mod image;
+mod link;
 mod screen;
 mod style;
 
@@ -34,6 +39,9 @@ pub struct TuiBackendBuilder {
     enable_raw_mode: bool,
     enable_alt_screen: bool,
     enable_mouse: bool,
+    graphics: Option<GraphicsProtocol>,
+    colors: Option<ColorSupport>,
+    hyperlinks: Option<bool>,
 }
 
 impl TuiBackendBuilder {
@@ -65,10 +73,38 @@ impl TuiBackendBuilder {
         self
     }
 
+    /// Set the graphics protocol used to draw images.
+    /// If this is not set the protocol is detected from the environment.
+    pub fn graphics(mut self, protocol: GraphicsProtocol) -> Self {
+        self.graphics = Some(protocol);
+        self
+    }
+
+    /// Set the colors the terminal can display.
+    /// If this is not set the color support is detected from the environment.
+    pub fn color_support(mut self, colors: ColorSupport) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    /// Write the `link` attribute of widgets as clickable hyperlinks.
+    /// If this is not set hyperlinks are enabled, unless the terminal is known not to support them.
+    pub fn hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = Some(hyperlinks);
+        self
+    }
+
     /// Consume self and create the tui backend.
     pub fn finish(self) -> Result<TuiBackend, std::io::Error> {
         let size = size()?;
-        let screen = Screen::new(size);
+        let mut screen = Screen::new(size);
+        let protocol = self.graphics.unwrap_or_else(GraphicsProtocol::detect);
+        screen.set_graphics(protocol, cell_size());
+        screen.set_color_support(self.colors.unwrap_or_else(ColorSupport::detect));
+        let hyperlinks = self
+            .hyperlinks
+            .unwrap_or_else(|| link::detect_hyperlinks(|key| std::env::var(key).ok()));
+        screen.set_hyperlinks(hyperlinks);
 
         let backend = TuiBackend {
             quit_on_ctrl_c: self.quit_on_ctrl_c,
@@ -114,6 +150,9 @@ impl TuiBackend {
             enable_raw_mode: false,
             enable_alt_screen: false,
             enable_mouse: false,
+            graphics: None,
+            colors: None,
+            hyperlinks: None,
         }
     }
 
@@ -135,6 +174,11 @@ impl Backend for TuiBackend {
 
     fn resize(&mut self, new_size: Size) {
         self.screen.resize(new_size);
+        self.screen.set_graphics(self.screen.graphics(), cell_size());
+    }
+
+    fn cell_size(&self) -> Size {
+        self.screen.cell_size()
     }
 
     fn paint<'bp>(
@@ -187,6 +231,17 @@ impl Backend for TuiBackend {
     }
 }
 
+// The size of a cell in pixels, or zero if the terminal doesn't report it
+fn cell_size() -> Size {
+    match window_size() {
+        Ok(size) if size.columns > 0 && size.rows > 0 => Size::new(
+            (size.width / size.columns) as usize,
+            (size.height / size.rows) as usize,
+        ),
+        _ => Size::ZERO,
+    }
+}
+
 impl Drop for TuiBackend {
     fn drop(&mut self) {
         let _ = self.screen.restore(&mut self.output);
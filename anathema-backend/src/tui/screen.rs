@@ -8,14 +8,20 @@ use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScree
 use crossterm::{cursor, ExecutableCommand, QueueableCommand};
 
 use super::buffer::{diff, draw_changes, Buffer, Change};
-use super::{LocalPos, Style};
+use super::image::{GraphicsProtocol, Images};
+use super::link::{LinkId, Links};
+use super::{ColorSupport, LocalPos, Style};
 
 /// The `Screen` is used to draw to some `std::io::Write`able output (generally `stdout`);
 pub struct Screen {
     // This is pub(crate) for testing purposes
     pub(crate) new_buffer: Buffer,
     old_buffer: Buffer,
-    changes: Vec<(LocalPos, Option<Style>, Change)>,
+    changes: Vec<(LocalPos, Option<Style>, Option<LinkId>, Change)>,
+    images: Images,
+    colors: ColorSupport,
+    links: Links,
+    hyperlinks: bool,
 }
 
 impl Screen {
@@ -47,9 +53,47 @@ impl Screen {
             old_buffer: Buffer::new(size),
             new_buffer: Buffer::new(size),
             changes: vec![],
+            images: Images::default(),
+            colors: ColorSupport::TrueColor,
+            links: Links::default(),
+            hyperlinks: true,
         }
     }
 
+    /// Set the graphics protocol used to draw images,
+    /// and the size of a single cell in pixels (if known).
+    pub fn set_graphics(&mut self, protocol: GraphicsProtocol, cell_size: Size) {
+        self.images.protocol = protocol;
+        self.images.cell_size = cell_size;
+    }
+
+    /// The graphics protocol used to draw images
+    pub fn graphics(&self) -> GraphicsProtocol {
+        self.images.protocol
+    }
+
+    /// The size of a cell in pixels, or zero if the terminal doesn't report it
+    pub fn cell_size(&self) -> Size {
+        self.images.cell_size
+    }
+
+    /// Set the colors the terminal can display.
+    /// Other colors are replaced with the nearest color that can be displayed.
+    pub fn set_color_support(&mut self, colors: ColorSupport) {
+        self.colors = colors;
+    }
+
+    /// The colors the terminal can display
+    pub fn color_support(&self) -> ColorSupport {
+        self.colors
+    }
+
+    /// Write the `link` attribute as a hyperlink (OSC 8).
+    /// Disable this for terminals that print the escape sequence instead of ignoring it.
+    pub fn set_hyperlinks(&mut self, hyperlinks: bool) {
+        self.hyperlinks = hyperlinks;
+    }
+
     /// Resize the buffer.
     /// This will empty the underlying buffers so everything will have
     /// to be redrawn.
@@ -60,6 +104,8 @@ impl Screen {
 
     /// Erase the entire buffer by writing empty cells
     pub(crate) fn erase(&mut self) {
+        // Every cell is erased, so none of them refer to the links any more
+        self.links.clear();
         self.erase_region(LocalPos::ZERO, self.size());
     }
 
@@ -83,19 +129,32 @@ impl Screen {
         self.new_buffer.put_char(c, pos);
     }
 
+    /// Put a grapheme cluster at the given screen position.
+    pub(crate) fn paint_cluster(&mut self, cluster: &str, pos: LocalPos) {
+        self.new_buffer.put_cluster(cluster, pos);
+    }
+
     pub(crate) fn update_cell(&mut self, style: Style, pos: LocalPos) {
         self.new_buffer.update_cell(style, pos);
     }
 
     /// Draw the changes to the screen
     pub(crate) fn render(&mut self, mut output: impl Write) -> Result<()> {
+        // Redraw the cells underneath images that are no longer drawn
+        for (pos, size) in self.images.removed() {
+            self.old_buffer.invalidate(pos, size);
+        }
+
         diff(&self.old_buffer, &self.new_buffer, &mut self.changes)?;
 
-        if self.changes.is_empty() {
+        if self.changes.is_empty() && !self.images.has_changes() {
             return Ok(());
         }
 
-        draw_changes(&mut output, &self.changes)?;
+        self.images.remove(&mut output)?;
+        let links = self.hyperlinks.then_some(&self.links);
+        draw_changes(&mut output, &self.changes, self.colors, links)?;
+        self.images.draw(&mut output)?;
 
         self.changes.clear();
 
@@ -143,15 +202,31 @@ impl WidgetRenderer for Screen {
         self.paint_glyph(c, screen_pos);
     }
 
+    fn draw_cluster(&mut self, cluster: &str, pos: Pos) {
+        let Ok(screen_pos) = pos.try_into() else { return };
+        self.paint_cluster(cluster, screen_pos);
+    }
+
     fn set_attributes(&mut self, attribs: &dyn CellAttributes, pos: Pos) {
         let Ok(screen_pos) = pos.try_into() else { return };
         let style = Style::from_cell_attribs(attribs);
         self.update_cell(style, screen_pos);
+
+        let mut link = None;
+        attribs.with_str("link", &mut |url| link = Some(self.links.insert(url)));
+        if let Some(link) = link {
+            self.new_buffer.set_link(link, screen_pos);
+        }
     }
 
     fn size(&self) -> Size {
         self.new_buffer.size()
     }
+
+    fn draw_image(&mut self, pixels: &[u8], image_size: Size, pos: Pos, cells: Size) -> bool {
+        let Ok(screen_pos) = pos.try_into() else { return false };
+        self.images.add(pixels, image_size, screen_pos, cells)
+    }
 }
 
 #[cfg(test)]
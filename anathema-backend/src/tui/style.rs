@@ -4,9 +4,11 @@ use std::str::FromStr;
 use anathema_state::{Color, Hex};
 use anathema_widgets::paint::CellAttributes;
 pub use crossterm::style::{Attribute as CrossAttrib, Color as CTColor};
-use crossterm::style::{SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::style::{SetAttribute, SetBackgroundColor, SetForegroundColor, SetUnderlineColor};
 use crossterm::QueueableCommand;
 
+use super::ColorSupport;
+
 struct ColorWrapper(Color);
 
 impl From<ColorWrapper> for CTColor {
@@ -59,12 +61,21 @@ impl From<ColorWrapper> for CTColor {
 /// else:
 ///     text: "is false"
 /// ```
+///
+/// Underlines can be one of `single`, `double`, `curly`, `dotted` or `dashed` (where supported)
+/// with a colour of their own, e.g. for spelling mistakes:
+///
+/// ```text
+/// text [underline_style: "curly", underline_color: "red"] "mispeled"
+/// ```
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Style {
     /// Foreground colour.
     pub fg: Option<Color>,
     /// Background colour.
     pub bg: Option<Color>,
+    /// Underline colour.
+    pub underline_color: Option<Color>,
     /// Attributes.
     pub attributes: Attributes,
 }
@@ -78,6 +89,7 @@ impl CellAttributes for Style {
         match key {
             "foreground" => self.fg,
             "background" => self.bg,
+            "underline_color" => self.underline_color,
             _ => None,
         }
     }
@@ -90,7 +102,17 @@ impl CellAttributes for Style {
         None
     }
 
-    fn with_str(&self, _: &str, _: &mut dyn FnMut(&str)) {}
+    fn with_str(&self, key: &str, f: &mut dyn FnMut(&str)) {
+        let style = match key {
+            "underline_style" if self.attributes.contains(Attributes::DOUBLE_UNDERLINED) => "double",
+            "underline_style" if self.attributes.contains(Attributes::UNDERCURLED) => "curly",
+            "underline_style" if self.attributes.contains(Attributes::UNDERDOTTED) => "dotted",
+            "underline_style" if self.attributes.contains(Attributes::UNDERDASHED) => "dashed",
+            "underline_style" if self.attributes.contains(Attributes::UNDERLINED) => "single",
+            _ => return,
+        };
+        f(style)
+    }
 
     fn get_bool(&self, key: &str) -> bool {
         match key {
@@ -112,6 +134,7 @@ impl Style {
         Self {
             fg: None,
             bg: None,
+            underline_color: None,
             attributes: Attributes::empty(),
         }
     }
@@ -120,27 +143,9 @@ impl Style {
     pub fn from_cell_attribs(attributes: &dyn CellAttributes) -> Self {
         let mut style = Self::new();
 
-        match attributes.get_color("foreground") {
-            Some(color) => style.fg = Some(color),
-            None => match attributes.get_hex("foreground") {
-                Some(Hex { r, g, b }) => style.fg = Some(Color::from((r, g, b))),
-                None => match attributes.get_u8("foreground") {
-                    Some(ansi) => style.fg = Some(Color::AnsiVal(ansi)),
-                    None => attributes.with_str("foreground", &mut |s| style.fg = Color::from_str(s).ok()),
-                },
-            },
-        }
-
-        match attributes.get_color("background") {
-            Some(color) => style.bg = Some(color),
-            None => match attributes.get_hex("background") {
-                Some(Hex { r, g, b }) => style.bg = Some(Color::from((r, g, b))),
-                None => match attributes.get_u8("background") {
-                    Some(ansi) => style.bg = Some(Color::AnsiVal(ansi)),
-                    None => attributes.with_str("background", &mut |s| style.bg = Color::from_str(s).ok()),
-                },
-            },
-        }
+        style.fg = color_attribute(attributes, "foreground");
+        style.bg = color_attribute(attributes, "background");
+        style.underline_color = color_attribute(attributes, "underline_color");
 
         if attributes.get_bool("bold") {
             style.attributes |= Attributes::BOLD;
@@ -158,6 +163,17 @@ impl Style {
             style.attributes |= Attributes::UNDERLINED;
         }
 
+        attributes.with_str("underline_style", &mut |s| {
+            style.attributes |= match s {
+                "single" => Attributes::UNDERLINED,
+                "double" => Attributes::DOUBLE_UNDERLINED,
+                "curly" => Attributes::UNDERCURLED,
+                "dotted" => Attributes::UNDERDOTTED,
+                "dashed" => Attributes::UNDERDASHED,
+                _ => Attributes::empty(),
+            }
+        });
+
         if attributes.get_bool("crossed-out") {
             style.attributes |= Attributes::CROSSED_OUT;
         }
@@ -173,13 +189,13 @@ impl Style {
         style
     }
 
-    pub(crate) fn write(&self, w: &mut impl Write) -> Result<()> {
+    pub(crate) fn write(&self, w: &mut impl Write, colors: ColorSupport) -> Result<()> {
         if let Some(fg) = self.fg {
-            w.queue(SetForegroundColor(ColorWrapper(fg).into()))?;
+            w.queue(SetForegroundColor(ColorWrapper(colors.downgrade(fg)).into()))?;
         }
 
         if let Some(bg) = self.bg {
-            w.queue(SetBackgroundColor(ColorWrapper(bg).into()))?;
+            w.queue(SetBackgroundColor(ColorWrapper(colors.downgrade(bg)).into()))?;
         }
 
         // Dim and bold are a special case, as they are both
@@ -204,10 +220,26 @@ impl Style {
             w.queue(SetAttribute(CrossAttrib::NoItalic))?;
         }
 
-        if self.attributes.contains(Attributes::UNDERLINED) {
-            w.queue(SetAttribute(CrossAttrib::Underlined))?;
-        } else {
-            w.queue(SetAttribute(CrossAttrib::NoUnderline))?;
+        // The more specific underline styles take priority over a single underline
+        let underline = [
+            (Attributes::DOUBLE_UNDERLINED, CrossAttrib::DoubleUnderlined),
+            (Attributes::UNDERCURLED, CrossAttrib::Undercurled),
+            (Attributes::UNDERDOTTED, CrossAttrib::Underdotted),
+            (Attributes::UNDERDASHED, CrossAttrib::Underdashed),
+            (Attributes::UNDERLINED, CrossAttrib::Underlined),
+        ]
+        .into_iter()
+        .find(|(flag, _)| self.attributes.contains(*flag));
+
+        match underline {
+            Some((_, attribute)) => {
+                w.queue(SetAttribute(attribute))?;
+                let color = self.underline_color.unwrap_or(Color::Reset);
+                w.queue(SetUnderlineColor(ColorWrapper(colors.downgrade(color)).into()))?;
+            }
+            None => {
+                w.queue(SetAttribute(CrossAttrib::NoUnderline))?;
+            }
         }
 
         if self.attributes.contains(Attributes::OVERLINED) {
@@ -277,6 +309,11 @@ impl Style {
         }
     }
 
+    /// Set the colour of the underline, as long as it's supported
+    pub fn set_underline_color(&mut self, color: Color) {
+        self.underline_color = Some(color);
+    }
+
     /// Make the cell overlined as long as it's supported
     pub fn set_overlined(&mut self, overlined: bool) {
         if overlined {
@@ -324,6 +361,10 @@ impl Style {
             self.bg = Some(bg);
         }
 
+        if let (None, Some(color)) = (self.underline_color, other.underline_color) {
+            self.underline_color = Some(color);
+        }
+
         self.attributes |= other.attributes;
     }
 }
@@ -331,24 +372,51 @@ impl Style {
 bitflags::bitflags! {
     /// Style attributes
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-    pub struct Attributes: u8 {
+    pub struct Attributes: u16 {
         /// Make the characters bold (in supported output)
-        const BOLD =        0b0000_0001;
+        const BOLD =              0b0000_0000_0001;
         /// Make the characters dim (in supported output)
-        const DIM =         0b0000_0010;
+        const DIM =               0b0000_0000_0010;
         /// Make the characters italic (in supported output)
-        const ITALIC =      0b0000_0100;
+        const ITALIC =            0b0000_0000_0100;
         /// Make the characters underlined (in supported output)
-        const UNDERLINED =  0b0000_1000;
+        const UNDERLINED =        0b0000_0000_1000;
         /// Make the characters crossed out (in supported output)
-        const CROSSED_OUT = 0b0001_0000;
+        const CROSSED_OUT =       0b0000_0001_0000;
         /// Make the characters overlined (in supported output)
-        const OVERLINED =   0b0010_0000;
+        const OVERLINED =         0b0000_0010_0000;
         /// Make the characters inverse (in supported output)
-        const INVERSE =     0b0100_0000;
+        const INVERSE =           0b0000_0100_0000;
+        /// Make the characters double underlined (in supported output)
+        const DOUBLE_UNDERLINED = 0b0000_1000_0000;
+        /// Make the characters underlined with a curly line (in supported output)
+        const UNDERCURLED =       0b0001_0000_0000;
+        /// Make the characters underlined with a dotted line (in supported output)
+        const UNDERDOTTED =       0b0010_0000_0000;
+        /// Make the characters underlined with a dashed line (in supported output)
+        const UNDERDASHED =       0b0100_0000_0000;
     }
 }
 
+// A colour from a colour value, a hex value, an ansi value or a colour name
+fn color_attribute(attributes: &dyn CellAttributes, key: &str) -> Option<Color> {
+    if let Some(color) = attributes.get_color(key) {
+        return Some(color);
+    }
+
+    if let Some(Hex { r, g, b }) = attributes.get_hex(key) {
+        return Some(Color::from((r, g, b)));
+    }
+
+    if let Some(ansi) = attributes.get_u8(key) {
+        return Some(Color::AnsiVal(ansi));
+    }
+
+    let mut color = None;
+    attributes.with_str(key, &mut |s| color = Color::from_str(s).ok());
+    color
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,4 +435,21 @@ mod tests {
         assert_eq!(left.fg.unwrap(), Color::Red);
         assert_eq!(left.bg.unwrap(), Color::Blue);
     }
+
+    #[test]
+    fn underline_style() {
+        let mut style = Style::new();
+        style.attributes |= Attributes::UNDERCURLED;
+        style.set_underline_color(Color::Red);
+
+        // Styles are cell attributes themselves
+        let copy = Style::from_cell_attribs(&style);
+        assert_eq!(copy, style);
+
+        let mut output = vec![];
+        style.write(&mut output, ColorSupport::TrueColor).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\x1b[4:3m"));
+        assert!(output.contains("\x1b[58;5;1m"));
+    }
 }
@@ -0,0 +1,138 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::Size;
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+use crate::layout::single_layout;
+
+const RATIO: &str = "ratio";
+const DEFAULT_RATIO: f64 = 1.0;
+
+/// Gives the child the largest size that fits inside the constraints,
+/// where the width divided by the height is the `ratio` (defaults to `1.0`).
+///
+/// The ratio is in cells, and since a cell is usually twice as tall as it's wide,
+/// a square on screen has a ratio of `2.0`.
+///
+/// ```text
+/// aspectratio [ratio: 2.0]
+///     border
+///         text 'board'
+/// ```
+#[derive(Debug, Default)]
+pub struct AspectRatio;
+
+impl AspectRatio {
+    fn size(ratio: f64, constraints: Constraints) -> Size {
+        let max_width = constraints.max_width();
+        let max_height = constraints.max_height();
+
+        if constraints.is_width_unbounded() {
+            let width = (max_height as f64 * ratio).round() as usize;
+            return Size::new(width, max_height);
+        }
+
+        let height = (max_width as f64 / ratio).round() as usize;
+        if height <= max_height {
+            return Size::new(max_width, height);
+        }
+
+        let width = (max_height as f64 * ratio).round() as usize;
+        Size::new(width.min(max_width), max_height)
+    }
+}
+
+impl Widget for AspectRatio {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One).attribute(RATIO, AttributeType::Float, Some("1.0"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        if constraints.is_unbounded() {
+            return single_layout(children, constraints, ctx);
+        }
+
+        let ratio = ctx
+            .attribs
+            .get(id)
+            .get_float(RATIO)
+            .filter(|ratio| *ratio > 0.0)
+            .unwrap_or(DEFAULT_RATIO);
+
+        let size = Self::size(ratio, constraints);
+        let mut constraints = Constraints::new(size.width, size.height);
+        constraints.make_width_tight(size.width);
+        constraints.make_height_tight(size.height);
+        single_layout(children, constraints, ctx);
+
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, ctx.pos, attribute_storage, ctx.viewport);
+            ControlFlow::Break(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn aspect_ratio() {
+        let tpl = "
+            aspectratio [ratio: 2.0]
+                border
+                    text 'x'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌──────┐║
+            ║│x     │║
+            ║│      │║
+            ║└──────┘║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn limited_by_height() {
+        let tpl = "
+            aspectratio [ratio: 2.0]
+                border
+                    text 'x'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌────┐  ║
+            ║│x   │  ║
+            ║└────┘  ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 3)).instance().render_assert(expected);
+    }
+}
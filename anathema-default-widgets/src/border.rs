@@ -7,15 +7,20 @@ use anathema_widgets::expressions::EvalValue;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
 use anathema_widgets::{
-    AnyWidget, AttributeStorage, Attributes, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    AnyWidget, AttributeStorage, AttributeType, Attributes, Children, LayoutChildren, PaintChildren, PositionChildren,
+    Widget, WidgetId, WidgetMetadata,
 };
 use unicode_width::UnicodeWidthChar;
 
 use crate::layout::border::BorderLayout;
 use crate::layout::Axis;
+use crate::style::Prefixed;
+use crate::text::TextAlignment;
 use crate::{HEIGHT, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH, WIDTH};
 
 pub const BORDER_STYLE: &str = "border_style";
+const TITLE: &str = "title";
+const TITLE_ALIGN: &str = "title_align";
 
 // -----------------------------------------------------------------------------
 //     - Indices -
@@ -38,6 +43,11 @@ bitflags::bitflags! {
     /// ```ignore
     /// let sides = Sides::TOP | Sides::LEFT;
     /// ```
+    ///
+    /// In a template the sides are given as a list or separated by `|`:
+    /// ```text
+    /// border [sides: "top|left"]
+    /// ```
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub struct Sides: u8 {
         /// Empty
@@ -73,14 +83,17 @@ impl TryFrom<&EvalValue<'_>> for Sides {
 
 impl From<&str> for Sides {
     fn from(value: &str) -> Self {
-        match value {
-            "all" => Sides::ALL,
-            "top" => Sides::TOP,
-            "left" => Sides::LEFT,
-            "right" => Sides::RIGHT,
-            "bottom" => Sides::BOTTOM,
-            _ => Sides::EMPTY,
-        }
+        value
+            .split('|')
+            .map(|side| match side.trim() {
+                "all" => Sides::ALL,
+                "top" => Sides::TOP,
+                "left" => Sides::LEFT,
+                "right" => Sides::RIGHT,
+                "bottom" => Sides::BOTTOM,
+                _ => Sides::EMPTY,
+            })
+            .fold(Sides::EMPTY, |sides, side| sides | side)
     }
 }
 
@@ -90,6 +103,18 @@ impl From<&str> for Sides {
 pub const DEFAULT_SLIM_EDGES: [char; 8] = ['┌', '─', '┐', '│', '┘', '─', '└', '│'];
 pub const DEFAULT_THICK_EDGES: [char; 8] = ['╔', '═', '╗', '║', '╝', '═', '╚', '║'];
 
+// The keys of a custom glyph set, in the same order as the edges
+const EDGE_NAMES: [&str; 8] = [
+    "top_left",
+    "top",
+    "top_right",
+    "right",
+    "bottom_right",
+    "bottom",
+    "bottom_left",
+    "left",
+];
+
 /// The style of the border.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum BorderStyle {
@@ -111,6 +136,13 @@ pub enum BorderStyle {
     /// 7hello3
     /// 6555554
     /// ```
+    ///
+    /// The glyphs can also be given as a map, where missing glyphs are thin:
+    /// ```text
+    /// border [border_style: {top_left: "╭", top_right: "╮", bottom_right: "╯", bottom_left: "╰"}]
+    /// ```
+    /// Valid keys are `top_left`, `top`, `top_right`, `right`,
+    /// `bottom_right`, `bottom`, `bottom_left` and `left`.
     Custom(String),
 }
 
@@ -140,10 +172,26 @@ impl Display for BorderStyle {
     }
 }
 
+// Attribute values are read with `TryFrom`, see `Attributes::get_ref`
+#[allow(clippy::infallible_try_from)]
 impl TryFrom<&EvalValue<'_>> for BorderStyle {
     type Error = Infallible;
 
     fn try_from(value: &EvalValue<'_>) -> Result<Self, Self::Error> {
+        if let EvalValue::ExprMap(map) = value {
+            let mut edges = DEFAULT_SLIM_EDGES;
+            for (edge, name) in edges.iter_mut().zip(EDGE_NAMES) {
+                let Some(value) = map.get(name) else { continue };
+                let _ = value.str_iter(|s| {
+                    if let Some(c) = s.chars().next() {
+                        *edge = c;
+                    }
+                    ControlFlow::Break(())
+                });
+            }
+            return Ok(BorderStyle::Custom(edges.iter().collect()));
+        }
+
         let mut style = None::<BorderStyle>;
         value.str_for_each(|s| match s {
             "thin" => style = Some(BorderStyle::Thin),
@@ -342,6 +390,19 @@ impl BorderSize {
 /// If a border has no size (width and height) and no child then nothing will be rendered.
 ///
 /// To render a border with no child provide a width and a height.
+///
+/// A `title` is drawn into the top edge, between the corners, and is clipped if it doesn't fit.
+/// The title is aligned with `title_align` (`left`, `centre` or `right`)
+/// and styled with the `title_` attributes, e.g `title_foreground` and `title_bold`:
+/// ```text
+/// border [title: " Files ", title_align: "centre", title_bold: true]
+///     text "readme.md"
+/// ```
+/// ```text
+/// ┌─ Files ─┐
+/// │readme.md│
+/// └─────────┘
+/// ```
 #[derive(Debug)]
 pub struct Border {
     /// The border style decides the characters
@@ -398,9 +459,74 @@ impl Border {
 
         border_size
     }
+
+    // Draw the title on top of the top edge, between the corners
+    fn paint_title(
+        &self,
+        title: &str,
+        border_size: BorderSize,
+        attributes: &Attributes<'_>,
+        ctx: &mut PaintCtx<'_, SizePos>,
+    ) {
+        if border_size.top == 0 {
+            return;
+        }
+
+        let start = border_size.top_left as usize;
+        let end = ctx.local_size.width.saturating_sub(border_size.top_right as usize);
+        let available = end.saturating_sub(start);
+
+        // Clip the title to the space between the corners
+        let mut width = 0;
+        let title = title
+            .chars()
+            .take_while(|c| {
+                width += c.width().unwrap_or(0);
+                width <= available
+            })
+            .collect::<String>();
+        let width = title.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+
+        let offset = match attributes.get(TITLE_ALIGN).unwrap_or_default() {
+            TextAlignment::Left | TextAlignment::Justify => 0,
+            TextAlignment::Centre => (available - width) / 2,
+            TextAlignment::Right => available - width,
+        };
+
+        let style = Prefixed::new(TITLE, attributes);
+        let mut pos = LocalPos::new((start + offset) as u16, 0);
+        for c in title.chars() {
+            if !style.is_empty() {
+                ctx.set_attributes(&style, pos);
+            }
+            match ctx.place_glyph(c, pos) {
+                Some(next) => pos = next,
+                None => break,
+            }
+        }
+    }
 }
 
 impl Widget for Border {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(BORDER_STYLE, AttributeType::Any, Some("\"thin\""))
+            .attribute("sides", AttributeType::Any, Some("\"all\""))
+            .attribute(TITLE, AttributeType::String, None)
+            .attribute(
+                "title_align",
+                AttributeType::Enum(&["left", "centre", "right", "justify"]),
+                Some("\"left\""),
+            )
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None)
+            .attribute(MIN_WIDTH, AttributeType::Int, None)
+            .attribute(MIN_HEIGHT, AttributeType::Int, None)
+            .attribute(MAX_WIDTH, AttributeType::Int, None)
+            .attribute(MAX_HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -437,15 +563,10 @@ impl Widget for Border {
         attribute_storage: &AttributeStorage<'bp>,
         mut ctx: PositionCtx,
     ) {
+        let bs = self.border_size(self.sides);
         children.for_each(|child, children| {
-            if self.sides.contains(Sides::TOP) {
-                ctx.pos.y += 1;
-            }
-
-            if self.sides.contains(Sides::LEFT) {
-                ctx.pos.x += self.edges[BORDER_EDGE_LEFT].width().unwrap_or(0) as i32;
-            }
-
+            ctx.pos.x += bs.top_left.max(bs.bottom_left).max(bs.left) as i32;
+            ctx.pos.y += bs.top as i32;
             child.position(children, ctx.pos, attribute_storage, ctx.viewport);
             ControlFlow::Break(())
         });
@@ -454,7 +575,7 @@ impl Widget for Border {
     fn paint<'bp>(
         &mut self,
         mut children: PaintChildren<'_, '_, 'bp>,
-        _id: WidgetId,
+        id: WidgetId,
         attribute_storage: &AttributeStorage<'bp>,
         mut ctx: PaintCtx<'_, SizePos>,
     ) {
@@ -483,6 +604,13 @@ impl Widget for Border {
         };
 
         painter.paint(&mut paint);
+
+        let attributes = attribute_storage.get(id);
+        if let Some(value) = attributes.get_val(TITLE) {
+            let mut title = String::new();
+            value.str_for_each(|s| title.push_str(s));
+            self.paint_title(&title, border_size, attributes, &mut ctx);
+        }
     }
 
     fn inner_bounds(&self, mut pos: Pos, mut size: Size) -> Rect {
@@ -680,6 +808,111 @@ mod test {
         TestRunner::new(tpl, (8, 6)).instance().render_assert(expected);
     }
 
+    #[test]
+    fn border_sides_separated_by_pipe() {
+        let tpl = "
+            border [sides: 'top|left']
+                text 'hello'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌─────  ║
+            ║│hello  ║
+            ║        ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn border_sides_only_shrink_present_edges() {
+        let tpl = "
+            border [sides: 'right|bottom']
+                text 'hello world'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║hello  │║
+            ║world  │║
+            ║───────┘║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn custom_glyph_map() {
+        let tpl = "
+            border [border_style: {top_left: '╭', top_right: '╮', bottom_right: '╯', bottom_left: '╰', left: '┆'}]
+                text 'hi'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║╭──╮    ║
+            ║┆hi│    ║
+            ║╰──╯    ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn title() {
+        let tpl = "
+            vstack
+                border [title: 'ab']
+                    text 'hello'
+                border [title: 'ab', title_align: 'centre']
+                    text 'hello'
+                border [title: 'ab', title_align: 'right']
+                    text 'hello'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌ab───┐ ║
+            ║│hello│ ║
+            ║└─────┘ ║
+            ║┌─ab──┐ ║
+            ║│hello│ ║
+            ║└─────┘ ║
+            ║┌───ab┐ ║
+            ║│hello│ ║
+            ║└─────┘ ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 9)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn title_is_clipped() {
+        let tpl = "
+            border [title: 'a long title']
+                text 'hi'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌a ┐    ║
+            ║│hi│    ║
+            ║└──┘    ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
     #[test]
     fn unsized_empty_border() {
         let tpl = "
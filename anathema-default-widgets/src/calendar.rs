@@ -0,0 +1,409 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{CellAttributes, PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::style::{Inverse, Prefixed};
+
+const YEAR: &str = "year";
+const MONTH: &str = "month";
+const DAY: &str = "day";
+const MIN: &str = "min";
+const MAX: &str = "max";
+const TODAY: &str = "today";
+const WEEKEND: &str = "weekend";
+const DISABLED: &str = "disabled";
+const SELECTED: &str = "selected";
+
+const WEEKDAYS: &str = "Mo Tu We Th Fr Sa Su";
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+// Two cells per day and a space in between
+const WIDTH: usize = 20;
+// The title and the weekdays
+const HEADER: usize = 2;
+
+/// A date in the proleptic Gregorian calendar
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Create a new date, or `None` if the date doesn't exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// The current date (UTC)
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self::from_days((secs / 86_400) as i64)
+    }
+
+    /// Day of the week, where Monday is 0 and Sunday is 6
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as u8
+    }
+
+    pub fn is_weekend(&self) -> bool {
+        self.weekday() >= 5
+    }
+
+    /// Number of days in the month of the date
+    pub fn days_in_month(&self) -> u8 {
+        days_in_month(self.year, self.month)
+    }
+
+    /// Add (or subtract) a number of days
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Add (or subtract) a number of months.
+    /// The day is clamped to the number of days in the new month.
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u8 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Self { year, month, day }
+    }
+
+    fn first_of_month(&self) -> Self {
+        Self { day: 1, ..*self }
+    }
+
+    // Days since 1970-01-01
+    fn to_days(self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    pub(crate) fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (year_of_era + era * 400 + (month <= 2) as i64) as i32;
+        Self { year, month, day }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ();
+
+    /// Parse a date in the `YYYY-MM-DD` format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let year = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let month = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let day = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        Self::new(year, month, day).ok_or(())
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A month grid with a selected date.
+///
+/// The month of the selected date (`year`, `month` and `day`) is shown,
+/// which defaults to today.
+/// Dates before `min` or after `max` (`YYYY-MM-DD`), and dates passed to
+/// [`Calendar::disable`], are disabled.
+///
+/// Days are styled with the `weekend_`, `disabled_`, `today_` and `selected_`
+/// attributes, e.g `today_bold: true`. The selected date is inverted if there are
+/// no `selected_` attributes.
+///
+/// ```text
+/// calendar [year: year, month: month, day: day, weekend_foreground: 'red', today_bold: true]
+/// ```
+///
+/// See [`crate::components::DatePicker`] for a component with keyboard navigation.
+#[derive(Debug, Default)]
+pub struct Calendar {
+    selected: Option<Date>,
+    today: Option<Date>,
+    min: Option<Date>,
+    max: Option<Date>,
+    disabled: Vec<Date>,
+    is_dirty: bool,
+}
+
+impl Calendar {
+    /// The selected date, once the calendar has been laid out
+    pub fn selected(&self) -> Option<Date> {
+        self.selected
+    }
+
+    /// Set the date shown as today (the current date is used by default)
+    pub fn set_today(&mut self, today: Date) {
+        self.today = Some(today);
+        self.is_dirty = true;
+    }
+
+    /// Disable a date
+    pub fn disable(&mut self, date: Date) {
+        self.disabled.push(date);
+        self.is_dirty = true;
+    }
+
+    /// Returns true if the date can't be selected
+    pub fn is_disabled(&self, date: Date) -> bool {
+        self.min.is_some_and(|min| date < min)
+            || self.max.is_some_and(|max| date > max)
+            || self.disabled.contains(&date)
+    }
+
+    fn weeks(date: Date) -> usize {
+        let first = date.first_of_month();
+        (first.weekday() as usize + date.days_in_month() as usize).div_ceil(7)
+    }
+
+    // Position of the day in the grid
+    fn cell(date: Date) -> LocalPos {
+        let offset = date.first_of_month().weekday() as usize + date.day as usize - 1;
+        let x = offset % 7 * 3;
+        let y = offset / 7 + HEADER;
+        LocalPos::new(x as u16, y as u16)
+    }
+}
+
+impl Widget for Calendar {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(YEAR, AttributeType::Int, None)
+            .attribute(MONTH, AttributeType::Int, None)
+            .attribute(DAY, AttributeType::Int, None)
+            .attribute(MIN, AttributeType::String, None)
+            .attribute(MAX, AttributeType::String, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.is_dirty = false;
+
+        let today = *self.today.get_or_insert_with(Date::today);
+        let year = attributes.get_int(YEAR).map(|year| year as i32).unwrap_or(today.year);
+        let month = attributes.get_usize(MONTH).unwrap_or(today.month as usize).clamp(1, 12) as u8;
+        let day = attributes.get_usize(DAY).unwrap_or(today.day as usize).max(1) as u8;
+        let selected = Date {
+            year,
+            month,
+            day: day.min(days_in_month(year, month)),
+        };
+        self.selected = Some(selected);
+
+        self.min = attributes.get_ref::<&str>(MIN).and_then(|s| s.parse().ok());
+        self.max = attributes.get_ref::<&str>(MAX).and_then(|s| s.parse().ok());
+
+        let size = Size::new(WIDTH, HEADER + Self::weeks(selected));
+        Size::new(
+            size.width.max(constraints.min_width).min(constraints.max_width()),
+            size.height.max(constraints.min_height).min(constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The calendar has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let Some(selected) = self.selected else { return };
+        let attributes = attribute_storage.get(id);
+
+        let title = format!("{} {}", MONTHS[selected.month as usize - 1], selected.year);
+        let x = WIDTH.saturating_sub(title.len()) / 2;
+        ctx.place_glyphs(&title, LocalPos::new(x as u16, 0));
+        ctx.place_glyphs(WEEKDAYS, LocalPos::new(0, 1));
+
+        let weekend = Prefixed::new(WEEKEND, attributes);
+        let disabled = Prefixed::new(DISABLED, attributes);
+        let today = Prefixed::new(TODAY, attributes);
+        let selected_style = Prefixed::new(SELECTED, attributes);
+        let selected_style: &dyn CellAttributes = match selected_style.is_empty() {
+            true => &Inverse,
+            false => &selected_style,
+        };
+
+        for day in 1..=selected.days_in_month() {
+            let date = Date { day, ..selected };
+            let pos = Self::cell(date);
+
+            let mut styles: Vec<&dyn CellAttributes> = vec![];
+            if date.is_weekend() {
+                styles.push(&weekend);
+            }
+            if self.is_disabled(date) {
+                styles.push(&disabled);
+            }
+            if Some(date) == self.today {
+                styles.push(&today);
+            }
+            if date == selected {
+                styles.push(selected_style);
+            }
+
+            for style in styles {
+                ctx.set_attributes(style, pos);
+                ctx.set_attributes(style, LocalPos::new(pos.x + 1, pos.y));
+            }
+
+            ctx.place_glyphs(&format!("{day:>2}"), pos);
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn dates() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(date.weekday(), 3);
+        assert_eq!(date.add_days(1), Date::new(2024, 3, 1).unwrap());
+        assert_eq!(date.add_months(12), Date::new(2025, 2, 28).unwrap());
+        assert_eq!(date.add_months(-2), Date::new(2023, 12, 29).unwrap());
+        assert_eq!(Date::from_days(date.to_days()), date);
+        assert_eq!("2024-02-29".parse(), Ok(date));
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert!(Date::new(2023, 2, 29).is_none());
+    }
+
+    #[test]
+    fn month_grid() {
+        let tpl = "calendar [year: 2024, month: 5, day: 16]";
+
+        let expected = "
+            ╔════════════════════╗
+            ║      May 2024      ║
+            ║Mo Tu We Th Fr Sa Su║
+            ║       1  2  3  4  5║
+            ║ 6  7  8  9 10 11 12║
+            ║13 14 15 16 17 18 19║
+            ║20 21 22 23 24 25 26║
+            ║27 28 29 30 31      ║
+            ╚════════════════════╝
+        ";
+
+        TestRunner::new(tpl, (20, 7))
+            .instance()
+            .render_assert(expected)
+            .with_widget(|mut elements| {
+                elements.by_tag("calendar").first(|el, _| {
+                    let calendar = el.to::<Calendar>();
+                    assert_eq!(calendar.selected(), Date::new(2024, 5, 16));
+                })
+            });
+    }
+
+    #[test]
+    fn disabled_dates() {
+        let tpl = "calendar [year: 2024, month: 5, day: 16, min: '2024-05-10']";
+
+        TestRunner::new(tpl, (20, 7))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("calendar").first(|el, _| {
+                    el.to::<Calendar>().disable(Date::new(2024, 5, 20).unwrap());
+                })
+            })
+            .render_assert(
+                "
+                ╔════════════════════╗
+                ║      May 2024      ║
+                ║Mo Tu We Th Fr Sa Su║
+                ║       1  2  3  4  5║
+                ║ 6  7  8  9 10 11 12║
+                ║13 14 15 16 17 18 19║
+                ║20 21 22 23 24 25 26║
+                ║27 28 29 30 31      ║
+                ╚════════════════════╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("calendar").first(|el, _| {
+                    let calendar = el.to::<Calendar>();
+                    assert!(calendar.is_disabled(Date::new(2024, 5, 9).unwrap()));
+                    assert!(calendar.is_disabled(Date::new(2024, 5, 20).unwrap()));
+                    assert!(!calendar.is_disabled(Date::new(2024, 5, 21).unwrap()));
+                })
+            });
+    }
+}
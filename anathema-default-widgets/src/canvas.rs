@@ -1,8 +1,13 @@
+use std::fmt;
+
 use anathema_backend::tui::Style;
 use anathema_geometry::{LocalPos, Pos, Size};
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
 
 use crate::{HEIGHT, WIDTH};
 
@@ -31,7 +36,7 @@ impl Buffer {
         let pos = pos.into();
 
         if pos.x as usize >= self.size.width || pos.y as usize >= self.size.height {
-            return
+            return;
         }
         let index = pos.to_index(self.size.width);
 
@@ -107,11 +112,125 @@ impl Buffer {
     }
 }
 
+/// Drawing operations for a canvas.
+///
+/// This is implemented by both the [`Canvas`] itself (drawing into the buffer)
+/// and the [`CanvasPainter`] handed to a painting function during paint.
+pub trait Draw {
+    /// The size of the drawing area
+    fn size(&self) -> Size;
+
+    /// Put a character at a given position.
+    /// Characters outside of the drawing area are ignored.
+    fn put_char(&mut self, c: char, style: Style, pos: LocalPos);
+
+    /// Write a string, starting at `pos`, without wrapping
+    fn put_str(&mut self, s: &str, style: Style, mut pos: LocalPos) {
+        for c in s.chars() {
+            self.put_char(c, style, pos);
+            pos.x = pos.x.saturating_add(1);
+        }
+    }
+
+    /// Draw a line between two points (inclusive) using Bresenham's algorithm
+    fn line(&mut self, c: char, style: Style, from: LocalPos, to: LocalPos) {
+        let (mut x, mut y) = (from.x as i32, from.y as i32);
+        let (x1, y1) = (to.x as i32, to.y as i32);
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let sx = if x < x1 { 1 } else { -1 };
+        let sy = if y < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.put_char(c, style, LocalPos::new(x as u16, y as u16));
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draw the outline of a rectangle using box drawing characters
+    fn rect(&mut self, style: Style, pos: LocalPos, size: Size) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        let left = pos.x;
+        let top = pos.y;
+        let right = pos.x.saturating_add(size.width as u16 - 1);
+        let bottom = pos.y.saturating_add(size.height as u16 - 1);
+
+        for x in left..=right {
+            self.put_char('─', style, LocalPos::new(x, top));
+            self.put_char('─', style, LocalPos::new(x, bottom));
+        }
+
+        for y in top..=bottom {
+            self.put_char('│', style, LocalPos::new(left, y));
+            self.put_char('│', style, LocalPos::new(right, y));
+        }
+
+        self.put_char('┌', style, LocalPos::new(left, top));
+        self.put_char('┐', style, LocalPos::new(right, top));
+        self.put_char('└', style, LocalPos::new(left, bottom));
+        self.put_char('┘', style, LocalPos::new(right, bottom));
+    }
+
+    /// Fill a rectangle with a character
+    fn fill_rect(&mut self, c: char, style: Style, pos: LocalPos, size: Size) {
+        for y in 0..size.height as u16 {
+            for x in 0..size.width as u16 {
+                self.put_char(c, style, LocalPos::new(pos.x + x, pos.y + y));
+            }
+        }
+    }
+}
+
+/// Drawing context handed to the painting function of a [`Canvas`].
+/// Anything drawn here is drawn on top of the canvas buffer,
+/// and only lasts for the current frame.
+pub struct CanvasPainter<'a, 'surface> {
+    ctx: &'a mut PaintCtx<'surface, SizePos>,
+}
+
+impl Draw for CanvasPainter<'_, '_> {
+    fn size(&self) -> Size {
+        self.ctx.local_size
+    }
+
+    fn put_char(&mut self, c: char, style: Style, pos: LocalPos) {
+        if pos.x as usize >= self.ctx.local_size.width || pos.y as usize >= self.ctx.local_size.height {
+            return;
+        }
+        self.ctx.set_attributes(&style, pos);
+        self.ctx.place_glyph(c, pos);
+    }
+}
+
+struct Painter(Box<dyn FnMut(&mut CanvasPainter<'_, '_>)>);
+
+impl fmt::Debug for Painter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<painter>")
+    }
+}
+
 #[derive(Debug)]
 pub struct Canvas {
     buffer: Buffer,
     pos: Pos,
     is_dirty: bool,
+    painter: Option<Painter>,
 }
 
 impl Canvas {
@@ -146,6 +265,43 @@ impl Canvas {
         self.is_dirty = true;
         self.buffer.remove(pos)
     }
+
+    /// Erase everything in the buffer
+    pub fn clear(&mut self) {
+        self.is_dirty = true;
+        self.buffer = Buffer::new(self.buffer.size);
+    }
+
+    /// Set a function that is called every time the canvas is painted.
+    ///
+    /// ```ignore
+    /// elements.by_tag("canvas").first(|el, _| {
+    ///     el.to::<Canvas>().set_painter(|painter| {
+    ///         let size = painter.size();
+    ///         painter.rect(Style::reset(), LocalPos::ZERO, size);
+    ///     });
+    /// });
+    /// ```
+    pub fn set_painter(&mut self, f: impl FnMut(&mut CanvasPainter<'_, '_>) + 'static) {
+        self.is_dirty = true;
+        self.painter = Some(Painter(Box::new(f)));
+    }
+
+    /// Remove the painting function
+    pub fn clear_painter(&mut self) {
+        self.is_dirty = true;
+        self.painter = None;
+    }
+}
+
+impl Draw for Canvas {
+    fn size(&self) -> Size {
+        self.buffer.size
+    }
+
+    fn put_char(&mut self, c: char, style: Style, pos: LocalPos) {
+        self.put(c, style, pos);
+    }
 }
 
 impl Default for Canvas {
@@ -154,11 +310,19 @@ impl Default for Canvas {
             buffer: Buffer::new((32, 32).into()),
             pos: Pos::ZERO,
             is_dirty: true,
+            painter: None,
         }
     }
 }
 
 impl Widget for Canvas {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         _children: LayoutChildren<'_, '_, 'bp>,
@@ -206,6 +370,12 @@ impl Widget for Canvas {
             ctx.set_attributes(style, pos);
             ctx.place_glyph(c, pos);
         }
+
+        if let Some(Painter(painter)) = self.painter.as_mut() {
+            painter(&mut CanvasPainter { ctx: &mut ctx });
+        }
+
+        self.is_dirty = false;
     }
 
     fn needs_reflow(&self) -> bool {
@@ -229,6 +399,27 @@ mod test {
         TestRunner::new("canvas", (2, 2)).instance().render_assert(expected);
     }
 
+    #[test]
+    fn paint_with_painter() {
+        let expected = "
+            ╔═══╗
+            ║┌─┐║
+            ║└─┘║
+            ╚═══╝
+        ";
+        TestRunner::new("canvas", (3, 2))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("canvas").first(|el, _| {
+                    el.to::<Canvas>().set_painter(|painter| {
+                        let size = painter.size();
+                        painter.rect(Style::reset(), LocalPos::ZERO, size);
+                    })
+                });
+            })
+            .render_assert(expected);
+    }
+
     #[test]
     fn get_set_glyph() {
         let mut canvas = Canvas::default();
@@ -237,6 +428,26 @@ mod test {
         assert_eq!(c, 'a');
     }
 
+    #[test]
+    fn draw_line() {
+        let mut canvas = Canvas::default();
+        canvas.line('x', Style::reset(), LocalPos::new(0, 0), LocalPos::new(3, 3));
+        for i in 0..4 {
+            assert_eq!(canvas.get((i, i)).unwrap().0, 'x');
+        }
+        assert!(canvas.get((1, 0)).is_none());
+    }
+
+    #[test]
+    fn draw_rect() {
+        let mut canvas = Canvas::default();
+        canvas.rect(Style::reset(), LocalPos::new(1, 1), Size::new(3, 2));
+        assert_eq!(canvas.get((1, 1)).unwrap().0, '┌');
+        assert_eq!(canvas.get((2, 1)).unwrap().0, '─');
+        assert_eq!(canvas.get((3, 2)).unwrap().0, '┘');
+        assert!(canvas.get((0, 0)).is_none());
+    }
+
     #[test]
     fn remove_glyph() {
         let mut canvas = Canvas::default();
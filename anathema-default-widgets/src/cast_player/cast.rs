@@ -0,0 +1,342 @@
+use std::fmt::{self, Display};
+use std::str::{Chars, FromStr};
+use std::time::Duration;
+
+use anathema_geometry::Size;
+
+// The largest terminal a cast can be played in, in either direction
+const MAX_SIZE: f64 = 1000.0;
+
+/// Invalid cast file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CastError {
+    /// The first line is not a header, or the header has no `width` and `height`
+    /// between 1 and 1000
+    InvalidHeader,
+    /// Only version 2 of the format is supported
+    UnsupportedVersion(i64),
+    /// The event on the line (starting at one) is not a `[time, code, data]` list
+    InvalidEvent(usize),
+}
+
+impl Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "invalid cast header"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported cast version: {version}"),
+            Self::InvalidEvent(line) => write!(f, "invalid event on line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
+/// Output written to the terminal at a point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastEvent {
+    /// Time since the start of the recording
+    pub time: Duration,
+    pub output: String,
+}
+
+/// A terminal session in the [asciinema cast format](https://docs.asciinema.org/manual/asciicast/v2/) (version 2).
+///
+/// Only output events (`"o"`) are played back, input and marker events are ignored.
+/// If the header has an `idle_time_limit`, longer pauses are shortened to the limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    /// Size of the terminal, in cells
+    pub size: Size,
+    pub title: Option<String>,
+    pub events: Vec<CastEvent>,
+}
+
+impl Cast {
+    /// Time of the last event
+    pub fn duration(&self) -> Duration {
+        self.events.last().map(|event| event.time).unwrap_or_default()
+    }
+}
+
+impl FromStr for Cast {
+    type Err = CastError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut lines = src.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines.next().ok_or(CastError::InvalidHeader)?;
+        let header = Json::parse(header).ok_or(CastError::InvalidHeader)?;
+        let version = header.get("version").and_then(Json::as_f64).unwrap_or(0.0) as i64;
+        if version != 2 {
+            return Err(CastError::UnsupportedVersion(version));
+        }
+
+        let width = header.get("width").and_then(Json::as_f64);
+        let height = header.get("height").and_then(Json::as_f64);
+        let (Some(width), Some(height)) = (width, height) else { return Err(CastError::InvalidHeader) };
+        if ![width, height].iter().all(|n| (1.0..=MAX_SIZE).contains(n)) {
+            return Err(CastError::InvalidHeader);
+        }
+        let title = header.get("title").and_then(Json::as_str).map(str::to_string);
+        let idle_time_limit = header.get("idle_time_limit").and_then(Json::as_f64);
+
+        let mut events = vec![];
+        // Time of the previous event, in the recording and after shortening pauses
+        let mut prev = 0.0;
+        let mut time = 0.0;
+        for (index, line) in lines {
+            let invalid = CastError::InvalidEvent(index + 1);
+            let Some(Json::List(event)) = Json::parse(line) else { return Err(invalid) };
+            let [Json::Number(event_time), Json::Str(code), Json::Str(data)] = &event[..] else {
+                return Err(invalid);
+            };
+            if !event_time.is_finite() {
+                return Err(invalid);
+            }
+
+            let mut pause = (event_time - prev).max(0.0);
+            if let Some(limit) = idle_time_limit {
+                pause = pause.min(limit);
+            }
+            prev = *event_time;
+            time += pause;
+
+            if code == "o" {
+                events.push(CastEvent {
+                    time: Duration::try_from_secs_f64(time).map_err(|_| invalid)?,
+                    output: data.clone(),
+                });
+            }
+        }
+
+        Ok(Self {
+            size: Size::new(width as usize, height as usize),
+            title,
+            events,
+        })
+    }
+}
+
+// Just enough JSON to read a cast file
+#[derive(Debug, PartialEq)]
+enum Json {
+    // `true`, `false` and `null` are not used by the player
+    Literal,
+    Number(f64),
+    Str(String),
+    List(Vec<Json>),
+    Map(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(src: &str) -> Option<Self> {
+        let mut chars = src.chars();
+        let value = parse_value(&mut chars)?;
+        match skip_whitespace(&mut chars) {
+            None => Some(value),
+            Some(_) => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Map(map) => map.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+// Skip whitespace and return the next char
+fn skip_whitespace(chars: &mut Chars<'_>) -> Option<char> {
+    chars.find(|c| !c.is_whitespace())
+}
+
+fn parse_value(chars: &mut Chars<'_>) -> Option<Json> {
+    let c = skip_whitespace(chars)?;
+    parse_value_at(c, chars)
+}
+
+fn parse_value_at(c: char, chars: &mut Chars<'_>) -> Option<Json> {
+    match c {
+        '"' => parse_str(chars).map(Json::Str),
+        '[' => {
+            let mut list = vec![];
+            let mut c = skip_whitespace(chars)?;
+            if c == ']' {
+                return Some(Json::List(list));
+            }
+            loop {
+                list.push(parse_value_at(c, chars)?);
+                match skip_whitespace(chars)? {
+                    ',' => c = skip_whitespace(chars)?,
+                    ']' => return Some(Json::List(list)),
+                    _ => return None,
+                }
+            }
+        }
+        '{' => {
+            let mut map = vec![];
+            let mut c = skip_whitespace(chars)?;
+            if c == '}' {
+                return Some(Json::Map(map));
+            }
+            loop {
+                if c != '"' {
+                    return None;
+                }
+                let key = parse_str(chars)?;
+                if skip_whitespace(chars)? != ':' {
+                    return None;
+                }
+                map.push((key, parse_value(chars)?));
+                match skip_whitespace(chars)? {
+                    ',' => c = skip_whitespace(chars)?,
+                    '}' => return Some(Json::Map(map)),
+                    _ => return None,
+                }
+            }
+        }
+        't' => parse_word(chars, "rue").then_some(Json::Literal),
+        'f' => parse_word(chars, "alse").then_some(Json::Literal),
+        'n' => parse_word(chars, "ull").then_some(Json::Literal),
+        '-' | '0'..='9' => {
+            let mut number = String::from(c);
+            // A number ends at the next delimiter, which has to be put back
+            let rest = chars.as_str();
+            let len = rest
+                .find(|c: char| !matches!(c, '0'..='9' | '.' | 'e' | 'E' | '+' | '-'))
+                .unwrap_or(rest.len());
+            number.push_str(&rest[..len]);
+            *chars = rest[len..].chars();
+            number.parse().ok().map(Json::Number)
+        }
+        _ => None,
+    }
+}
+
+fn parse_word(chars: &mut Chars<'_>, word: &str) -> bool {
+    word.chars().all(|c| chars.next() == Some(c))
+}
+
+// Parse a string, after the opening quote
+fn parse_str(chars: &mut Chars<'_>) -> Option<String> {
+    let mut s = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(s),
+            '\\' => match chars.next()? {
+                'n' => s.push('\n'),
+                'r' => s.push('\r'),
+                't' => s.push('\t'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'u' => {
+                    let mut code = parse_hex(chars)?;
+                    // Surrogate pair
+                    if (0xd800..0xdc00).contains(&code) {
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+                        let low = parse_hex(chars)?;
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low.checked_sub(0xdc00)?);
+                    }
+                    s.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                c => s.push(c),
+            },
+            c => s.push(c),
+        }
+    }
+}
+
+fn parse_hex(chars: &mut Chars<'_>) -> Option<u32> {
+    let hex = chars.by_ref().take(4).collect::<String>();
+    match hex.len() {
+        4 => u32::from_str_radix(&hex, 16).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_cast() {
+        let src = r#"{"version": 2, "width": 10, "height": 2, "title": "demo", "env": {"TERM": "xterm"}}
+[0.5, "o", "hello\r\n"]
+[1.0, "i", "q"]
+[1.25, "o", "\u001b[1mbyeé"]
+"#;
+        let cast = src.parse::<Cast>().unwrap();
+        assert_eq!(cast.size, Size::new(10, 2));
+        assert_eq!(cast.title.as_deref(), Some("demo"));
+        assert_eq!(cast.events.len(), 2);
+        assert_eq!(cast.events[0].output, "hello\r\n");
+        assert_eq!(cast.events[1].output, "\x1b[1mbyeé");
+        assert_eq!(cast.duration(), Duration::from_millis(1250));
+    }
+
+    #[test]
+    fn idle_time_limit() {
+        let src = r#"{"version": 2, "width": 10, "height": 2, "idle_time_limit": 1}
+[0.5, "o", "a"]
+[10.5, "o", "b"]
+[11, "o", "c"]
+"#;
+        let cast = src.parse::<Cast>().unwrap();
+        let times = cast.events.iter().map(|event| event.time).collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            [
+                Duration::from_millis(500),
+                Duration::from_millis(1500),
+                Duration::from_millis(2000)
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_cast() {
+        assert_eq!("".parse::<Cast>(), Err(CastError::InvalidHeader));
+        assert_eq!(
+            r#"{"version": 1, "width": 1, "height": 1}"#.parse::<Cast>(),
+            Err(CastError::UnsupportedVersion(1))
+        );
+        assert_eq!(
+            "{\"version\": 2, \"width\": 1, \"height\": 1}\n[0.1, \"o\"]".parse::<Cast>(),
+            Err(CastError::InvalidEvent(2))
+        );
+    }
+
+    #[test]
+    fn time_out_of_range() {
+        let header = r#"{"version": 2, "width": 1, "height": 1}"#;
+        for time in ["1e999", "-1e999", "1e300"] {
+            let src = format!("{header}\n[0.1, \"o\", \"a\"]\n[{time}, \"o\", \"b\"]");
+            assert_eq!(src.parse::<Cast>(), Err(CastError::InvalidEvent(3)), "{time}");
+        }
+    }
+
+    #[test]
+    fn size_out_of_range() {
+        for (width, height) in [("0", "1"), ("1", "1e999"), ("-5", "2"), ("100000", "100000")] {
+            let src = format!(r#"{{"version": 2, "width": {width}, "height": {height}}}"#);
+            assert_eq!(src.parse::<Cast>(), Err(CastError::InvalidHeader), "{width}x{height}");
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::time::Duration;
+
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+pub use self::cast::{Cast, CastError, CastEvent};
+use self::terminal::Terminal;
+
+mod cast;
+mod terminal;
+
+const SPEED: &str = "speed";
+const DEFAULT_SPEED: f64 = 1.0;
+
+/// Play back a recorded terminal session, in the [`Cast`] format of asciinema.
+///
+/// The screen of the recording is drawn from the top left corner,
+/// and is cut off if it doesn't fit the constraints.
+/// `speed` is the playback speed (defaults to `1.0`).
+///
+/// ```text
+/// border
+///     castplayer [speed: 2.0]
+/// ```
+///
+/// The cast is loaded, played and paused through the widget.
+/// Time only passes when the player is ticked, e.g from [`Component::tick`](anathema_widgets::components::Component::tick):
+/// ```ignore
+/// let cast = std::fs::read_to_string("demo.cast")?.parse::<Cast>()?;
+/// elements.by_tag("castplayer").first(|el, _| {
+///     let player = el.to::<CastPlayer>();
+///     player.set_cast(cast);
+///     player.play();
+/// });
+///
+/// // Component::tick
+/// elements.by_tag("castplayer").first(|el, _| el.to::<CastPlayer>().tick(dt));
+///
+/// // Component::on_key
+/// elements.by_tag("castplayer").first(|el, _| {
+///     let player = el.to::<CastPlayer>();
+///     match key.code {
+///         KeyCode::Char(' ') => player.toggle(),
+///         KeyCode::Left => player.rewind(Duration::from_secs(5)),
+///         KeyCode::Right => player.forward(Duration::from_secs(5)),
+///         _ => {}
+///     }
+/// });
+/// ```
+#[derive(Debug)]
+pub struct CastPlayer {
+    cast: Cast,
+    terminal: Terminal,
+    position: Duration,
+    // Index of the first event that is not written to the terminal
+    next_event: usize,
+    playing: bool,
+    speed: f64,
+    is_dirty: bool,
+}
+
+impl Default for CastPlayer {
+    fn default() -> Self {
+        Self {
+            cast: Cast {
+                size: Size::ZERO,
+                title: None,
+                events: vec![],
+            },
+            terminal: Terminal::new(Size::ZERO),
+            position: Duration::ZERO,
+            next_event: 0,
+            playing: false,
+            speed: DEFAULT_SPEED,
+            is_dirty: false,
+        }
+    }
+}
+
+impl CastPlayer {
+    /// Replace the cast, and rewind to the start.
+    /// The player keeps playing if it was playing.
+    pub fn set_cast(&mut self, cast: Cast) {
+        self.is_dirty = self.cast.size != cast.size;
+        self.cast = cast;
+        self.rewind_to_start();
+    }
+
+    pub fn cast(&self) -> &Cast {
+        &self.cast
+    }
+
+    /// Start playing, from the start if the cast has been played to the end
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.rewind_to_start();
+        }
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn toggle(&mut self) {
+        match self.playing {
+            true => self.pause(),
+            false => self.play(),
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Returns true if the position is at the end of the cast
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.duration()
+    }
+
+    /// The current time of the playback
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// Duration of the cast
+    pub fn duration(&self) -> Duration {
+        self.cast.duration()
+    }
+
+    /// Move to a point in time, without changing whether the player is playing.
+    ///
+    /// Seeking backwards plays the cast again from the start, up to the position.
+    pub fn seek(&mut self, position: Duration) {
+        let position = position.min(self.duration());
+        if position < self.position {
+            self.terminal = Terminal::new(self.cast.size);
+            self.next_event = 0;
+        }
+        self.position = position;
+
+        while let Some(event) = self.cast.events.get(self.next_event) {
+            if event.time > position {
+                break;
+            }
+            self.terminal.write(&event.output);
+            self.next_event += 1;
+        }
+    }
+
+    pub fn forward(&mut self, offset: Duration) {
+        self.seek(self.position + offset);
+    }
+
+    pub fn rewind(&mut self, offset: Duration) {
+        self.seek(self.position.saturating_sub(offset));
+    }
+
+    /// Advance the playback by `dt` (times the `speed`) if the player is playing.
+    /// The player is paused once it reaches the end.
+    pub fn tick(&mut self, dt: Duration) {
+        if !self.playing {
+            return;
+        }
+
+        self.seek(self.position + dt.mul_f64(self.speed));
+        if self.is_finished() {
+            self.playing = false;
+        }
+    }
+
+    fn rewind_to_start(&mut self) {
+        self.terminal = Terminal::new(self.cast.size);
+        self.position = Duration::ZERO;
+        self.next_event = 0;
+        self.seek(Duration::ZERO);
+    }
+}
+
+impl Widget for CastPlayer {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None).attribute(SPEED, AttributeType::Float, Some("1.0"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.speed = attributes.get_float(SPEED).unwrap_or(DEFAULT_SPEED).max(0.0);
+        self.is_dirty = false;
+
+        let size = self.terminal.size();
+        Size::new(
+            size.width.clamp(constraints.min_width, constraints.max_width()),
+            size.height.clamp(constraints.min_height, constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The player has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let size = ctx.local_size;
+        for (y, row) in self.terminal.rows().enumerate().take(size.height) {
+            for (x, cell) in row.iter().enumerate().take(size.width) {
+                let pos = LocalPos::new(x as u16, y as u16);
+                if cell.style != Style::new() {
+                    ctx.set_attributes(&cell.style, pos);
+                }
+                if let Some(c) = cell.c {
+                    ctx.place_glyph(c, pos);
+                }
+            }
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    const CAST: &str = r#"{"version": 2, "width": 6, "height": 2}
+[0.5, "o", "$ ls\r\n"]
+[1.0, "o", "a b"]
+[2.0, "o", "\r\u001b[Kdone"]
+"#;
+
+    #[test]
+    fn play_pause_and_seek() {
+        let mut player = CastPlayer::default();
+        player.set_cast(CAST.parse().unwrap());
+        assert_eq!(player.duration(), Duration::from_secs(2));
+
+        // Not playing
+        player.tick(Duration::from_secs(1));
+        assert_eq!(player.position(), Duration::ZERO);
+
+        player.play();
+        player.tick(Duration::from_secs(1));
+        assert_eq!(player.next_event, 2);
+
+        player.pause();
+        player.tick(Duration::from_secs(1));
+        assert_eq!(player.position(), Duration::from_secs(1));
+
+        player.seek(Duration::from_secs(5));
+        assert!(player.is_finished());
+        assert_eq!(player.next_event, 3);
+
+        player.rewind(Duration::from_millis(1500));
+        assert_eq!(player.position(), Duration::from_millis(500));
+        assert_eq!(player.next_event, 1);
+
+        // Playing to the end pauses the player
+        player.play();
+        player.tick(Duration::from_secs(2));
+        assert!(!player.is_playing());
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn render_cast() {
+        let tpl = "castplayer [speed: 2.0]";
+
+        let expected_empty = "
+            ╔════════╗
+            ║        ║
+            ║        ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        let expected_first = "
+            ╔════════╗
+            ║$ ls    ║
+            ║a b     ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        let expected_done = "
+            ╔════════╗
+            ║$ ls    ║
+            ║done    ║
+            ║        ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 3))
+            .instance()
+            // The speed is read from the attributes on layout
+            .render_assert(expected_empty)
+            .with_widget(|mut elements| {
+                elements.by_tag("castplayer").first(|el, _| {
+                    let player = el.to::<CastPlayer>();
+                    player.set_cast(CAST.parse().unwrap());
+                    player.play();
+                    player.tick(Duration::from_millis(500));
+                });
+            })
+            .render_assert(expected_first)
+            .with_widget(|mut elements| {
+                elements
+                    .by_tag("castplayer")
+                    .first(|el, _| el.to::<CastPlayer>().tick(Duration::from_millis(500)));
+            })
+            .render_assert(expected_done);
+    }
+}
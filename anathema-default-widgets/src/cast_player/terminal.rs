@@ -0,0 +1,404 @@
+use anathema::Color;
+use anathema_backend::tui::{Attributes, Style};
+use anathema_geometry::Size;
+use unicode_width::UnicodeWidthChar;
+
+const TAB_WIDTH: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) struct Cell {
+    // `None` for the cell covered by a wide char
+    pub(super) c: Option<char>,
+    pub(super) style: Style,
+}
+
+impl Cell {
+    fn blank(style: Style) -> Self {
+        Self { c: Some(' '), style }
+    }
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Ground,
+    Escape,
+    // Control sequence, with the parameters so far
+    Csi(String),
+    // Operating system command, e.g setting the title, ends with a bell or `ESC \`
+    Osc,
+    OscEscape,
+    // Character set selection, e.g `ESC ( B`, the next char is ignored
+    Charset,
+}
+
+/// The screen of a terminal, written to with the output of a cast.
+///
+/// Only what is needed to play back recordings is supported:
+/// cursor movement, erasing, scrolling and colours / attributes (SGR).
+/// Anything else is ignored.
+#[derive(Debug)]
+pub(super) struct Terminal {
+    size: Size,
+    cells: Vec<Cell>,
+    cursor: (usize, usize),
+    saved_cursor: (usize, usize),
+    // The cursor is past the last column, and the next char wraps
+    pending_wrap: bool,
+    style: Style,
+    state: State,
+}
+
+impl Terminal {
+    pub(super) fn new(size: Size) -> Self {
+        Self {
+            size,
+            cells: vec![Cell::blank(Style::new()); size.width * size.height],
+            cursor: (0, 0),
+            saved_cursor: (0, 0),
+            pending_wrap: false,
+            style: Style::new(),
+            state: State::Ground,
+        }
+    }
+
+    pub(super) fn size(&self) -> Size {
+        self.size
+    }
+
+    pub(super) fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.size.width.max(1))
+    }
+
+    pub(super) fn write(&mut self, output: &str) {
+        for c in output.chars() {
+            self.input(c);
+        }
+    }
+
+    fn input(&mut self, c: char) {
+        match std::mem::take(&mut self.state) {
+            State::Ground => self.ground(c),
+            State::Escape => match c {
+                '[' => self.state = State::Csi(String::new()),
+                ']' => self.state = State::Osc,
+                '(' | ')' => self.state = State::Charset,
+                '7' => self.saved_cursor = self.cursor,
+                '8' => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+                'D' => self.line_feed(),
+                'E' => {
+                    self.cursor.0 = 0;
+                    self.line_feed();
+                }
+                'M' => self.reverse_line_feed(),
+                'c' => *self = Self::new(self.size),
+                _ => {}
+            },
+            State::Csi(mut params) => match c {
+                '0'..='9' | ';' | '?' | '>' | '=' | ' ' | '!' => {
+                    params.push(c);
+                    self.state = State::Csi(params);
+                }
+                _ => self.csi(&params, c),
+            },
+            State::Osc => match c {
+                '\x07' => {}
+                '\x1b' => self.state = State::OscEscape,
+                _ => self.state = State::Osc,
+            },
+            State::OscEscape => match c {
+                '\\' => {}
+                _ => self.state = State::Osc,
+            },
+            State::Charset => {}
+        }
+    }
+
+    fn ground(&mut self, c: char) {
+        match c {
+            '\x1b' => self.state = State::Escape,
+            '\r' => {
+                self.cursor.0 = 0;
+                self.pending_wrap = false;
+            }
+            '\n' | '\x0b' | '\x0c' => self.line_feed(),
+            '\x08' => {
+                self.cursor.0 = self.cursor.0.saturating_sub(1);
+                self.pending_wrap = false;
+            }
+            '\t' => {
+                let x = (self.cursor.0 / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.cursor.0 = x.min(self.size.width.saturating_sub(1));
+            }
+            c if c.is_control() => {}
+            c => self.print(c),
+        }
+    }
+
+    fn print(&mut self, c: char) {
+        let width = c.width().unwrap_or(0);
+        if width == 0 || self.size.width < width || self.size.height == 0 {
+            return;
+        }
+
+        if self.pending_wrap || self.cursor.0 + width > self.size.width {
+            self.cursor.0 = 0;
+            self.line_feed();
+        }
+
+        let (x, y) = self.cursor;
+        let index = y * self.size.width + x;
+        self.cells[index] = Cell {
+            c: Some(c),
+            style: self.style,
+        };
+        if width == 2 {
+            self.cells[index + 1] = Cell {
+                c: None,
+                style: self.style,
+            };
+        }
+
+        match x + width == self.size.width {
+            true => {
+                self.cursor.0 = self.size.width - 1;
+                self.pending_wrap = true;
+            }
+            false => self.cursor.0 += width,
+        }
+    }
+
+    fn move_to(&mut self, x: usize, y: usize) {
+        self.cursor = (
+            x.min(self.size.width.saturating_sub(1)),
+            y.min(self.size.height.saturating_sub(1)),
+        );
+        self.pending_wrap = false;
+    }
+
+    fn line_feed(&mut self) {
+        self.pending_wrap = false;
+        match self.cursor.1 + 1 >= self.size.height {
+            true => self.scroll_up(1),
+            false => self.cursor.1 += 1,
+        }
+    }
+
+    fn reverse_line_feed(&mut self) {
+        self.pending_wrap = false;
+        match self.cursor.1 {
+            0 => self.scroll_down(1),
+            _ => self.cursor.1 -= 1,
+        }
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        let lines = lines.min(self.size.height);
+        let blank = Cell::blank(self.style);
+        self.cells.drain(..lines * self.size.width);
+        self.cells.extend((0..lines * self.size.width).map(|_| blank));
+    }
+
+    fn scroll_down(&mut self, lines: usize) {
+        let lines = lines.min(self.size.height);
+        let blank = Cell::blank(self.style);
+        self.cells.truncate(self.cells.len() - lines * self.size.width);
+        self.cells.splice(0..0, (0..lines * self.size.width).map(|_| blank));
+    }
+
+    // Erase the cells from `start` to `end` (exclusive), as indices into the cells
+    fn erase(&mut self, start: usize, end: usize) {
+        let blank = Cell::blank(self.style);
+        let end = end.min(self.cells.len());
+        self.cells[start.min(end)..end].fill(blank);
+    }
+
+    fn csi(&mut self, params: &str, c: char) {
+        // Private modes, e.g hiding the cursor, are ignored
+        if params.starts_with(['?', '>', '=']) {
+            return;
+        }
+
+        let args = params
+            .split(';')
+            .map(|arg| arg.parse::<usize>().ok())
+            .collect::<Vec<_>>();
+        let arg = |index: usize, default: usize| match args.get(index).copied().flatten() {
+            Some(0) | None => default,
+            Some(arg) => arg,
+        };
+
+        let (x, y) = self.cursor;
+        let width = self.size.width;
+        let line = y * width;
+        match c {
+            'A' => self.move_to(x, y.saturating_sub(arg(0, 1))),
+            'B' | 'e' => self.move_to(x, y + arg(0, 1)),
+            'C' | 'a' => self.move_to(x + arg(0, 1), y),
+            'D' => self.move_to(x.saturating_sub(arg(0, 1)), y),
+            'E' => self.move_to(0, y + arg(0, 1)),
+            'F' => self.move_to(0, y.saturating_sub(arg(0, 1))),
+            'G' | '`' => self.move_to(arg(0, 1) - 1, y),
+            'd' => self.move_to(x, arg(0, 1) - 1),
+            'H' | 'f' => self.move_to(arg(1, 1) - 1, arg(0, 1) - 1),
+            'J' => match args.first().copied().flatten().unwrap_or(0) {
+                0 => self.erase(line + x, self.cells.len()),
+                1 => self.erase(0, line + x + 1),
+                _ => self.erase(0, self.cells.len()),
+            },
+            'K' => match args.first().copied().flatten().unwrap_or(0) {
+                0 => self.erase(line + x, line + width),
+                1 => self.erase(line, line + x + 1),
+                _ => self.erase(line, line + width),
+            },
+            'X' => self.erase(line + x, line + (x + arg(0, 1)).min(width)),
+            'P' => {
+                let row = &mut self.cells[line..line + width];
+                let count = arg(0, 1).min(width - x);
+                row[x..].rotate_left(count);
+                row[width - count..].fill(Cell::blank(self.style));
+            }
+            '@' => {
+                let row = &mut self.cells[line..line + width];
+                let count = arg(0, 1).min(width - x);
+                row[x..].rotate_right(count);
+                row[x..x + count].fill(Cell::blank(self.style));
+            }
+            'S' => self.scroll_up(arg(0, 1)),
+            'T' => self.scroll_down(arg(0, 1)),
+            'L' | 'M' => {
+                // Insert / delete lines in the region from the cursor to the bottom
+                let count = arg(0, 1).min(self.size.height - y);
+                let region = &mut self.cells[line..];
+                match c {
+                    'L' => region.rotate_right(count * width),
+                    _ => region.rotate_left(count * width),
+                }
+                let blank = Cell::blank(self.style);
+                match c {
+                    'L' => region[..count * width].fill(blank),
+                    _ => {
+                        let end = region.len();
+                        region[end - count * width..].fill(blank)
+                    }
+                }
+            }
+            's' => self.saved_cursor = self.cursor,
+            'u' => self.move_to(self.saved_cursor.0, self.saved_cursor.1),
+            'm' => self.sgr(&args),
+            _ => {}
+        }
+    }
+
+    fn sgr(&mut self, args: &[Option<usize>]) {
+        let mut args = args.iter().map(|arg| arg.unwrap_or(0));
+        while let Some(arg) = args.next() {
+            let style = &mut self.style;
+            match arg {
+                0 => *style = Style::new(),
+                1 => style.attributes |= Attributes::BOLD,
+                2 => style.attributes |= Attributes::DIM,
+                3 => style.attributes |= Attributes::ITALIC,
+                4 => style.attributes |= Attributes::UNDERLINED,
+                7 => style.attributes |= Attributes::INVERSE,
+                9 => style.attributes |= Attributes::CROSSED_OUT,
+                22 => style.attributes -= Attributes::BOLD | Attributes::DIM,
+                23 => style.attributes -= Attributes::ITALIC,
+                24 => style.attributes -= Attributes::UNDERLINED,
+                27 => style.attributes -= Attributes::INVERSE,
+                29 => style.attributes -= Attributes::CROSSED_OUT,
+                30..=37 => style.fg = Some(ansi_color(arg - 30)),
+                38 => style.fg = extended_color(&mut args),
+                39 => style.fg = None,
+                40..=47 => style.bg = Some(ansi_color(arg - 40)),
+                48 => style.bg = extended_color(&mut args),
+                49 => style.bg = None,
+                90..=97 => style.fg = Some(ansi_color(arg - 90 + 8)),
+                100..=107 => style.bg = Some(ansi_color(arg - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+// `5;n` for 256 colours or `2;r;g;b` for 24 bit colours
+fn extended_color(args: &mut impl Iterator<Item = usize>) -> Option<Color> {
+    match args.next()? {
+        5 => Some(Color::AnsiVal(args.next()? as u8)),
+        2 => Some(Color::Rgb(args.next()? as u8, args.next()? as u8, args.next()? as u8)),
+        _ => None,
+    }
+}
+
+fn ansi_color(index: usize) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Grey,
+        8 => Color::DarkGrey,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(terminal: &Terminal) -> Vec<String> {
+        terminal
+            .rows()
+            .map(|row| row.iter().filter_map(|cell| cell.c).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn write_and_wrap() {
+        let mut terminal = Terminal::new(Size::new(4, 2));
+        terminal.write("abcdef\r\nxy");
+        assert_eq!(text(&terminal), ["ef  ", "xy  "]);
+    }
+
+    #[test]
+    fn cursor_and_erase() {
+        let mut terminal = Terminal::new(Size::new(4, 2));
+        terminal.write("abcd\r\nefgh");
+        terminal.write("\x1b[1;2H\x1b[K\x1b[2;3HX");
+        assert_eq!(text(&terminal), ["a   ", "efXh"]);
+
+        terminal.write("\x1b[2J");
+        assert_eq!(text(&terminal), ["    ", "    "]);
+    }
+
+    #[test]
+    fn sequence_split_across_writes() {
+        let mut terminal = Terminal::new(Size::new(4, 1));
+        terminal.write("\x1b[1;3");
+        terminal.write("1mA\x1b]0;title\x07\x1b[0mB");
+        assert_eq!(text(&terminal), ["AB  "]);
+
+        let rows = terminal.rows().next().unwrap();
+        assert_eq!(rows[0].style.fg, Some(Color::Red));
+        assert!(rows[0].style.attributes.contains(Attributes::BOLD));
+        assert_eq!(rows[1].style, Style::new());
+    }
+
+    #[test]
+    fn extended_colors() {
+        let mut terminal = Terminal::new(Size::new(2, 1));
+        terminal.write("\x1b[38;5;208;48;2;1;2;3mA");
+        let cell = terminal.rows().next().unwrap()[0];
+        assert_eq!(cell.style.fg, Some(Color::AnsiVal(208)));
+        assert_eq!(cell.style.bg, Some(Color::Rgb(1, 2, 3)));
+    }
+}
@@ -0,0 +1,177 @@
+use anathema_state::{State, Value};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+use crate::calendar::Date;
+
+#[derive(State)]
+pub struct DatePickerState {
+    pub year: Value<i32>,
+    pub month: Value<u8>,
+    pub day: Value<u8>,
+    /// The selected date as `YYYY-MM-DD`
+    pub date: Value<String>,
+    /// The first date that can be selected as `YYYY-MM-DD`, or empty
+    pub min: Value<String>,
+    /// The last date that can be selected as `YYYY-MM-DD`, or empty
+    pub max: Value<String>,
+}
+
+impl DatePickerState {
+    pub fn new(date: Date) -> Self {
+        Self {
+            year: date.year.into(),
+            month: date.month.into(),
+            day: date.day.into(),
+            date: date.to_string().into(),
+            min: String::new().into(),
+            max: String::new().into(),
+        }
+    }
+
+    /// Limit the dates that can be selected
+    pub fn with_range(mut self, min: Option<Date>, max: Option<Date>) -> Self {
+        self.min.set(min.map(|d| d.to_string()).unwrap_or_default());
+        self.max.set(max.map(|d| d.to_string()).unwrap_or_default());
+        self
+    }
+
+    pub fn date(&self) -> Date {
+        let (year, month) = (self.year.copy_value(), self.month.copy_value());
+        Date::new(year, month, self.day.copy_value())
+            .or(Date::new(year, month, 1))
+            .unwrap_or_else(Date::today)
+    }
+
+    fn set(&mut self, date: Date) {
+        let min = self.min.to_ref().parse::<Date>().ok();
+        let max = self.max.to_ref().parse::<Date>().ok();
+        let date = min.map(|min| date.max(min)).unwrap_or(date);
+        let date = max.map(|max| date.min(max)).unwrap_or(date);
+
+        self.year.set(date.year);
+        self.month.set(date.month);
+        self.day.set(date.day);
+        self.date.set(date.to_string());
+    }
+}
+
+/// Messages sent to a [`DatePicker`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CalendarMessage {
+    NextMonth,
+    PrevMonth,
+    Today,
+    Select(Date),
+}
+
+/// Date selection using a [`crate::Calendar`].
+///
+/// The arrow keys move the selection by a day / week, `PageUp` / `PageDown` moves
+/// to the previous / next month and `Home` selects today.
+///
+/// The date is published as `change` (`YYYY-MM-DD`) whenever it changes,
+/// and as `select` when `Enter` is pressed.
+#[derive(Debug, Default)]
+pub struct DatePicker;
+
+impl DatePicker {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+calendar [year: year, month: month, day: day, min: min, max: max]
+";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Apply a message to the state.
+    /// Returns true if the date changed.
+    pub fn apply(&self, message: CalendarMessage, state: &mut DatePickerState) -> bool {
+        let date = state.date();
+        let new = match message {
+            CalendarMessage::NextMonth => date.add_months(1),
+            CalendarMessage::PrevMonth => date.add_months(-1),
+            CalendarMessage::Today => Date::today(),
+            CalendarMessage::Select(date) => date,
+        };
+        state.set(new);
+        state.date() != date
+    }
+
+    /// Apply a key press to the state.
+    /// Returns true if the date changed.
+    pub fn apply_key(&self, code: KeyCode, state: &mut DatePickerState) -> bool {
+        let date = state.date();
+        let message = match code {
+            KeyCode::Left => CalendarMessage::Select(date.add_days(-1)),
+            KeyCode::Right => CalendarMessage::Select(date.add_days(1)),
+            KeyCode::Up => CalendarMessage::Select(date.add_days(-7)),
+            KeyCode::Down => CalendarMessage::Select(date.add_days(7)),
+            KeyCode::PageUp => CalendarMessage::PrevMonth,
+            KeyCode::PageDown => CalendarMessage::NextMonth,
+            KeyCode::Home => CalendarMessage::Today,
+            _ => return false,
+        };
+        self.apply(message, state)
+    }
+}
+
+impl Component for DatePicker {
+    type Message = CalendarMessage;
+    type State = DatePickerState;
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        if let KeyCode::Enter = key.code {
+            context.publish("select", |state| &state.date);
+        } else if self.apply_key(key.code, state) {
+            context.publish("change", |state| &state.date);
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if self.apply(message, state) {
+            context.publish("change", |state| &state.date);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn navigate_dates() {
+        let picker = DatePicker::new();
+        let min = Date::new(2024, 1, 10);
+        let mut state = DatePickerState::new(Date::new(2024, 1, 31).unwrap()).with_range(min, None);
+
+        assert!(picker.apply_key(KeyCode::PageDown, &mut state));
+        assert_eq!(state.date(), Date::new(2024, 2, 29).unwrap());
+
+        picker.apply_key(KeyCode::Down, &mut state);
+        assert_eq!(*state.date.to_ref(), "2024-03-07");
+
+        picker.apply(CalendarMessage::Select(Date::new(2024, 1, 1).unwrap()), &mut state);
+        // Clamped to the first date
+        assert_eq!(state.date(), min.unwrap());
+        assert!(!picker.apply_key(KeyCode::Left, &mut state));
+    }
+}
@@ -0,0 +1,261 @@
+use anathema_geometry::Pos;
+use anathema_state::{State, Value};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState, MouseEvent};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::{Elements, WidgetId};
+
+use crate::ContextMenu;
+
+/// The attribute with the name of the context menu of a widget
+pub const CONTEXT_MENU: &str = "context_menu";
+
+const ID: &str = "id";
+
+/// An item activated in a context menu
+#[derive(State, Default)]
+pub struct ContextMenuSelection {
+    /// The name of the menu
+    pub menu: Value<String>,
+    /// The `id` of the widget the menu was opened for
+    pub source: Value<String>,
+    /// The `id` of the item
+    pub item: Value<String>,
+}
+
+#[derive(State, Default)]
+pub struct ContextMenuState {
+    /// The name of the open menu, or empty if no menu is open
+    pub menu: Value<String>,
+    pub x: Value<i32>,
+    pub y: Value<i32>,
+    pub item: Value<usize>,
+    /// The `id` of the widget the menu was opened for
+    pub source: Value<String>,
+    pub selection: Value<ContextMenuSelection>,
+}
+
+impl ContextMenuState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if a menu is open
+    pub fn is_open(&self) -> bool {
+        !self.menu.to_ref().is_empty()
+    }
+}
+
+/// Opens a [`crate::ContextMenu`] for the widget under the mouse when right-clicking,
+/// or for the first widget with a context menu when the menu key is pressed.
+///
+/// Widgets name their menu with the `context_menu` attribute, and the name of the
+/// open menu is stored in `menu`, so one component can have several menus.
+///
+/// `Up` / `Down` selects an item and `Esc` closes the menu.
+/// When an item is activated, with `Enter` or by clicking it, the menu is closed
+/// and a [`ContextMenuSelection`] with the menu, the `id` of the source widget
+/// and the `id` of the item is published as `select`.
+///
+/// ```text
+/// vstack
+///     text [id: 'notes.txt', context_menu: 'file'] 'notes.txt'
+///     text [id: 'todo.txt', context_menu: 'file'] 'todo.txt'
+/// contextmenu [open: menu == 'file', x: x, y: y, item: item]
+///     text [id: 'rename'] 'Rename'
+///     text [id: 'delete'] 'Delete'
+/// ```
+///
+/// Put the context menu in a `portal` to paint it in an overlay.
+#[derive(Debug, Default)]
+pub struct ContextMenuInput;
+
+impl ContextMenuInput {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Open a menu at a screen position for a source widget
+    pub fn open(&self, menu: &str, source: &str, pos: Pos, state: &mut ContextMenuState) {
+        state.menu.set(menu.into());
+        state.source.set(source.into());
+        state.x.set(pos.x);
+        state.y.set(pos.y);
+        state.item.set(0);
+    }
+
+    pub fn close(&self, state: &mut ContextMenuState) {
+        state.menu.set(String::new());
+    }
+
+    /// Apply a key press to an open menu, given the number of items in the menu.
+    /// Returns true if the state changed.
+    pub fn apply_key(&self, code: KeyCode, items: usize, state: &mut ContextMenuState) -> bool {
+        if !state.is_open() {
+            return false;
+        }
+
+        let item = state.item.copy_value();
+        match code {
+            KeyCode::Down => state.item.set((item + 1).min(items.saturating_sub(1))),
+            KeyCode::Up => state.item.set(item.saturating_sub(1)),
+            KeyCode::Esc => {
+                self.close(state);
+                return true;
+            }
+            _ => return false,
+        }
+        state.item.copy_value() != item
+    }
+
+    // The number of items in the open menu
+    fn items(elements: &mut Elements<'_, '_>) -> usize {
+        let mut items = 0;
+        elements.by_tag("contextmenu").each(|el, _| {
+            let menu = el.to::<ContextMenu>();
+            if menu.is_open() {
+                items = menu.len();
+            }
+        });
+        items
+    }
+
+    fn activate(&self, index: usize, elements: &mut Elements<'_, '_>, state: &mut ContextMenuState) -> bool {
+        let mut id = None;
+        elements.by_tag("contextmenu").each(|el, _| {
+            let menu = el.to::<ContextMenu>();
+            if menu.is_open() {
+                id = menu.item_id(index).map(String::from);
+            }
+        });
+
+        let Some(id) = id else { return false };
+        {
+            let mut selection = state.selection.to_mut();
+            selection.menu.set(state.menu.to_ref().clone());
+            selection.source.set(state.source.to_ref().clone());
+            selection.item.set(id);
+        }
+        state.item.set(index);
+        self.close(state);
+        true
+    }
+
+    // The top-most widget with a context menu at the position,
+    // or the first widget with a context menu if there is no position.
+    // Returns the name of the menu, the `id` of the widget and the position of the widget.
+    fn source(elements: &mut Elements<'_, '_>, pos: Option<Pos>) -> Option<(String, String, Pos)> {
+        let mut sources: Vec<(WidgetId, (String, String, Pos))> = vec![];
+        elements.with_attribute(CONTEXT_MENU).each(|el, attributes| {
+            let menu = attributes.get_ref::<&str>(CONTEXT_MENU).unwrap_or("");
+            let id = attributes.get_ref::<&str>(ID).unwrap_or("");
+            sources.push((el.id(), (menu.into(), id.into(), el.get_pos())));
+        });
+
+        let Some(pos) = pos else { return sources.into_iter().next().map(|(_, source)| source) };
+
+        let index = elements
+            .widget_at(pos)
+            .into_iter()
+            .find_map(|hit| sources.iter().position(|(id, _)| *id == hit))?;
+        Some(sources.swap_remove(index).1)
+    }
+}
+
+impl Component for ContextMenuInput {
+    type Message = ();
+    type State = ContextMenuState;
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Enter if state.is_open() => {
+                let index = state.item.copy_value();
+                if self.activate(index, &mut elements, state) {
+                    context.publish("select", |state| &state.selection);
+                }
+            }
+            KeyCode::Menu => {
+                if let Some((menu, source, pos)) = Self::source(&mut elements, None) {
+                    self.open(&menu, &source, pos, state);
+                }
+            }
+            code => {
+                let items = Self::items(&mut elements);
+                self.apply_key(code, items, state);
+            }
+        }
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if mouse.rsb_down() {
+            match Self::source(&mut elements, Some(mouse.pos())) {
+                Some((menu, source, _)) => self.open(&menu, &source, mouse.pos(), state),
+                None => self.close(state),
+            }
+            return;
+        }
+
+        if !mouse.lsb_down() || !state.is_open() {
+            return;
+        }
+
+        let mut item = None;
+        elements.by_tag("contextmenu").each(|el, _| {
+            let menu = el.to::<ContextMenu>();
+            if menu.is_open() {
+                item = menu.item_at(mouse.pos());
+            }
+        });
+
+        match item {
+            Some(item) => {
+                if self.activate(item, &mut elements, state) {
+                    context.publish("select", |state| &state.selection);
+                }
+            }
+            // Close the menu when clicking somewhere else
+            None => self.close(state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn navigate_context_menu() {
+        let input = ContextMenuInput::new();
+        let mut state = ContextMenuState::new();
+
+        // Nothing happens while the menu is closed
+        assert!(!input.apply_key(KeyCode::Down, 3, &mut state));
+
+        input.open("file", "notes.txt", Pos::new(4, 2), &mut state);
+        assert!(state.is_open());
+        assert_eq!((state.x.copy_value(), state.y.copy_value()), (4, 2));
+
+        assert!(input.apply_key(KeyCode::Down, 2, &mut state));
+        // Clamped to the last item
+        assert!(!input.apply_key(KeyCode::Down, 2, &mut state));
+        assert_eq!(state.item.copy_value(), 1);
+
+        assert!(input.apply_key(KeyCode::Esc, 2, &mut state));
+        assert!(!state.is_open());
+    }
+}
@@ -0,0 +1,146 @@
+use anathema_state::{List, State, Value};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState, MouseEvent};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+use super::RovingFocus;
+
+#[derive(State)]
+pub struct SelectListState {
+    pub items: Value<List<String>>,
+    pub selected: Value<usize>,
+}
+
+impl SelectListState {
+    pub fn new(items: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            items: List::from_iter(items.into_iter().map(Into::into)),
+            selected: 0.into(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.items.to_ref().len()
+    }
+}
+
+/// Selectable list of strings using a [`crate::List`].
+///
+/// `Up` / `Down` moves the selection, `Home` / `End` selects the first / last item,
+/// and clicking an item selects it.
+///
+/// The index is published as `change` whenever the selection changes,
+/// and as `select` when `Enter` is pressed.
+#[derive(Debug, Default)]
+pub struct SelectList;
+
+impl SelectList {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+list [selected: selected]
+    for item in items
+        text item
+";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn set(&self, selected: usize, state: &mut SelectListState) -> bool {
+        let selected = selected.min(state.len().saturating_sub(1));
+        if selected == state.selected.copy_value() {
+            return false;
+        }
+        state.selected.set(selected);
+        true
+    }
+
+    /// Apply a key press to the state.
+    /// Returns true if the selection changed.
+    pub fn apply_key(&self, code: KeyCode, state: &mut SelectListState) -> bool {
+        match RovingFocus::vertical().apply_key(code, state.selected.copy_value(), state.len()) {
+            Some(selected) => self.set(selected, state),
+            None => false,
+        }
+    }
+}
+
+impl Component for SelectList {
+    type Message = usize;
+    type State = SelectListState;
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        if let KeyCode::Enter = key.code {
+            context.publish("select", |state| &state.selected);
+        } else if self.apply_key(key.code, state) {
+            context.publish("change", |state| &state.selected);
+        }
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if !mouse.lsb_down() {
+            return;
+        }
+
+        let mut row = None;
+        elements.at_position(mouse.pos()).by_tag("list").first(|el, _| {
+            let pos = el.get_pos();
+            row = el.to::<crate::List>().row_at(mouse.pos(), pos);
+        });
+
+        if let Some(row) = row {
+            if self.set(row, state) {
+                context.publish("change", |state| &state.selected);
+            }
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.set(message, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn move_selection_with_keys() {
+        let list = SelectList::new();
+        let mut state = SelectListState::new(["a", "b", "c"]);
+
+        assert!(list.apply_key(KeyCode::Down, &mut state));
+        assert_eq!(state.selected.copy_value(), 1);
+
+        assert!(list.apply_key(KeyCode::End, &mut state));
+        // Clamped to the last item
+        assert!(!list.apply_key(KeyCode::Down, &mut state));
+        assert_eq!(state.selected.copy_value(), 2);
+
+        list.apply_key(KeyCode::Home, &mut state);
+        assert!(!list.apply_key(KeyCode::Up, &mut state));
+        assert_eq!(state.selected.copy_value(), 0);
+    }
+}
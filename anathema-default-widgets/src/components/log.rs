@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+
+use anathema_state::{AnsiParser, List, State, StyledSpan, Value};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+/// A line of a [`Log`], as seen by the template.
+#[derive(State)]
+pub struct LogLine {
+    pub spans: Value<List<StyledSpan>>,
+}
+
+#[derive(State)]
+pub struct LogState {
+    pub lines: Value<List<LogLine>>,
+    /// Number of lines removed from the front to stay within the limits
+    pub trimmed: Value<usize>,
+}
+
+impl LogState {
+    pub fn new() -> Self {
+        Self {
+            lines: List::empty(),
+            trimmed: 0.into(),
+        }
+    }
+}
+
+impl Default for LogState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Append-only log output, e.g the output of a child process.
+///
+/// Text is appended as it arrives and doesn't have to end on a line break:
+/// the last line stays open until a line break is appended.
+/// ANSI escape sequences are parsed into styled spans (see [`AnsiParser`]).
+///
+/// The oldest lines are removed once there are more than `max_lines` lines,
+/// or the text (excluding escape sequences) is larger than `max_bytes`.
+///
+/// ```ignore
+/// let log = Log::new().max_lines(10_000).max_bytes(1024 * 1024);
+/// runtime.register_component("log", Log::TEMPLATE.to_template(), log, LogState::new());
+///
+/// // Each message is appended to the log
+/// emitter.emit(log_id, String::from_utf8_lossy(&output).into_owned());
+/// ```
+pub struct Log {
+    parser: AnsiParser,
+    max_lines: Option<usize>,
+    max_bytes: Option<usize>,
+    // The size in bytes of each line
+    sizes: VecDeque<usize>,
+    bytes: usize,
+    // The last line didn't end with a line break
+    open: bool,
+}
+
+impl Log {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+vstack
+    for line in lines
+        text
+            for span in line.spans
+                span [foreground: span.foreground, background: span.background, bold: span.bold, italic: span.italic, inverse: span.inverse] span.text
+";
+
+    pub fn new() -> Self {
+        Self {
+            parser: AnsiParser::new(),
+            max_lines: None,
+            max_bytes: None,
+            sizes: VecDeque::new(),
+            bytes: 0,
+            open: false,
+        }
+    }
+
+    /// Keep at most this many lines
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines.max(1));
+        self
+    }
+
+    /// Keep at most this many bytes of text.
+    /// The last line is kept even if it's larger than the limit.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// The size of the text in bytes, excluding escape sequences
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Append text to the log
+    pub fn append(&mut self, text: &str, state: &mut LogState) {
+        if text.is_empty() {
+            return;
+        }
+
+        let (text, open) = match text.strip_suffix('\n') {
+            Some(text) => (text, false),
+            None => (text, true),
+        };
+
+        for (index, line) in text.split('\n').enumerate() {
+            let spans = self.parser.parse(line);
+            let size = spans.iter().map(|span| span.text.to_ref().len()).sum::<usize>();
+            self.bytes += size;
+
+            let last = state.lines.len().checked_sub(1);
+            match last {
+                Some(last) if index == 0 && self.open => {
+                    if let Some(size_of_last) = self.sizes.back_mut() {
+                        *size_of_last += size;
+                    }
+
+                    let mut lines = state.lines.to_mut();
+                    let Some(line) = lines.get_mut(last) else { continue };
+                    let mut line = line.to_mut();
+                    spans.into_iter().for_each(|span| line.spans.push_back(span));
+                }
+                _ => {
+                    self.sizes.push_back(size);
+                    state.lines.push_back(LogLine {
+                        spans: List::from_iter(spans),
+                    });
+                }
+            }
+        }
+
+        self.open = open;
+        self.trim(state);
+    }
+
+    /// Remove all lines
+    pub fn clear(&mut self, state: &mut LogState) {
+        while state.lines.pop_back().is_some() {}
+        self.sizes.clear();
+        self.bytes = 0;
+        self.open = false;
+        self.parser.reset();
+    }
+
+    // Remove lines from the front until the log is within the limits
+    fn trim(&mut self, state: &mut LogState) {
+        let over_lines = |log: &Self| log.max_lines.is_some_and(|max| log.sizes.len() > max);
+        let over_bytes = |log: &Self| log.max_bytes.is_some_and(|max| log.bytes > max);
+
+        while self.sizes.len() > 1 && (over_lines(self) || over_bytes(self)) {
+            let Some(size) = self.sizes.pop_front() else { break };
+            self.bytes -= size;
+            state.lines.pop_front();
+            *state.trimmed.to_mut() += 1;
+        }
+    }
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for Log {
+    type Message = String;
+    type State = LogState;
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.append(&message, state);
+    }
+
+    fn accept_focus(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(state: &LogState) -> Vec<String> {
+        state
+            .lines
+            .to_ref()
+            .iter()
+            .map(|line| {
+                line.to_ref()
+                    .spans
+                    .to_ref()
+                    .iter()
+                    .map(|span| span.to_ref().text.to_ref().clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn template_compiles() {
+        let mut doc = anathema_templates::Document::new(Log::TEMPLATE);
+        assert!(doc.compile().is_ok());
+    }
+
+    #[test]
+    fn append_partial_lines() {
+        let mut log = Log::new();
+        let mut state = LogState::new();
+
+        log.append("one\ntw", &mut state);
+        log.append("o\nthree\n", &mut state);
+        log.append("\x1b[31mfour", &mut state);
+        assert_eq!(lines(&state), ["one", "two", "three", "four"]);
+        assert_eq!(log.bytes(), 15);
+
+        let lines = state.lines.to_ref();
+        let line = lines.get(3).unwrap().to_ref();
+        let spans = line.spans.to_ref();
+        assert!(spans.get(0).unwrap().to_ref().foreground.copy_value().is_some());
+    }
+
+    #[test]
+    fn trim_from_the_front() {
+        let mut log = Log::new().max_lines(3);
+        let mut state = LogState::new();
+        log.append("1\n2\n3\n4\n5\n", &mut state);
+        assert_eq!(lines(&state), ["3", "4", "5"]);
+        assert_eq!(state.trimmed.copy_value(), 2);
+
+        let mut log = Log::new().max_bytes(8);
+        let mut state = LogState::new();
+        log.append("aaa\nbbb\nccc\n", &mut state);
+        assert_eq!(lines(&state), ["bbb", "ccc"]);
+        assert_eq!(log.bytes(), 6);
+
+        // The last line stays even when it's over the limit
+        log.append("0123456789", &mut state);
+        assert_eq!(lines(&state), ["0123456789"]);
+    }
+}
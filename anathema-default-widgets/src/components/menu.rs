@@ -0,0 +1,214 @@
+use anathema_state::{State, Value};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState, MouseEvent};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+use crate::{Menu, MenuBar};
+
+#[derive(State, Default)]
+pub struct MenuBarState {
+    pub selected: Value<usize>,
+    pub item: Value<usize>,
+    pub open: Value<bool>,
+    /// The `id` of the last activated item
+    pub activated: Value<String>,
+}
+
+impl MenuBarState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Keyboard and mouse input for a [`crate::MenuBar`].
+///
+/// `Left` / `Right` selects the previous / next menu, `Down` opens the menu
+/// or selects the next item, `Up` selects the previous item and `Esc` closes the menu.
+/// Clicking a label opens the menu.
+///
+/// When an item is activated, with `Enter` or by clicking it, the menu is closed
+/// and the `id` of the item is published as `activate`.
+///
+/// The template has to bind the state to the menu bar:
+/// ```text
+/// menubar [selected: selected, item: item, open: open]
+///     menu [label: 'File']
+///         text [id: 'quit'] 'Quit'
+/// ```
+#[derive(Debug, Default)]
+pub struct MenuBarInput;
+
+impl MenuBarInput {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Apply a key press to the state, given the number of menus
+    /// and the number of items in the open menu.
+    /// Returns true if the state changed.
+    pub fn apply_key(&self, code: KeyCode, menus: usize, items: usize, state: &mut MenuBarState) -> bool {
+        if menus == 0 {
+            return false;
+        }
+
+        let before = (
+            state.selected.copy_value(),
+            state.item.copy_value(),
+            state.open.copy_value(),
+        );
+        let (selected, item, open) = before;
+
+        let after = match code {
+            KeyCode::Left => ((selected + menus - 1) % menus, 0, open),
+            KeyCode::Right => ((selected + 1) % menus, 0, open),
+            KeyCode::Down if !open => (selected, 0, true),
+            KeyCode::Down => (selected, (item + 1).min(items.saturating_sub(1)), open),
+            KeyCode::Up => (selected, item.saturating_sub(1), open),
+            KeyCode::Esc => (selected, item, false),
+            _ => return false,
+        };
+
+        let (selected, item, open) = after;
+        state.selected.set(selected);
+        state.item.set(item);
+        state.open.set(open);
+        before != after
+    }
+
+    // The number of menus, and the number of items in the open menu
+    fn counts(elements: &mut Elements<'_, '_>) -> (usize, usize) {
+        let mut menus = 0;
+        elements
+            .by_tag("menubar")
+            .first(|el, _| menus = el.to::<MenuBar>().len());
+
+        let mut items = 0;
+        elements.by_tag("menu").each(|el, _| {
+            let menu = el.to::<Menu>();
+            if menu.is_open() {
+                items = menu.len();
+            }
+        });
+
+        (menus, items)
+    }
+
+    fn activate(&self, index: usize, elements: &mut Elements<'_, '_>, state: &mut MenuBarState) -> bool {
+        let mut id = None;
+        elements.by_tag("menu").each(|el, _| {
+            let menu = el.to::<Menu>();
+            if menu.is_open() {
+                id = menu.item_id(index).map(String::from);
+            }
+        });
+
+        let Some(id) = id else { return false };
+        state.activated.set(id);
+        state.item.set(index);
+        state.open.set(false);
+        true
+    }
+}
+
+impl Component for MenuBarInput {
+    type Message = ();
+    type State = MenuBarState;
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Enter if state.open.copy_value() => {
+                let index = state.item.copy_value();
+                if self.activate(index, &mut elements, state) {
+                    context.publish("activate", |state| &state.activated);
+                }
+            }
+            KeyCode::Enter => state.open.set(true),
+            code => {
+                let (menus, items) = Self::counts(&mut elements);
+                self.apply_key(code, menus, items, state);
+            }
+        }
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if !mouse.lsb_down() {
+            return;
+        }
+
+        let mut item = None;
+        elements.by_tag("menu").each(|el, _| {
+            let menu = el.to::<Menu>();
+            if menu.is_open() {
+                item = menu.item_at(mouse.pos());
+            }
+        });
+
+        if let Some(item) = item {
+            if self.activate(item, &mut elements, state) {
+                context.publish("activate", |state| &state.activated);
+            }
+            return;
+        }
+
+        let mut menu = None;
+        elements.by_tag("menubar").first(|el, _| {
+            let pos = el.get_pos();
+            menu = el.to::<MenuBar>().menu_at(mouse.pos(), pos);
+        });
+
+        match menu {
+            Some(menu) => {
+                let open = !(state.open.copy_value() && state.selected.copy_value() == menu);
+                state.selected.set(menu);
+                state.item.set(0);
+                state.open.set(open);
+            }
+            // Close the menu when clicking somewhere else
+            None => state.open.set(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn navigate_menus() {
+        let input = MenuBarInput::new();
+        let mut state = MenuBarState::new();
+
+        assert!(input.apply_key(KeyCode::Left, 3, 0, &mut state));
+        assert_eq!(state.selected.copy_value(), 2);
+
+        assert!(input.apply_key(KeyCode::Down, 3, 0, &mut state));
+        assert!(state.open.copy_value());
+
+        input.apply_key(KeyCode::Down, 3, 2, &mut state);
+        // Clamped to the last item
+        assert!(!input.apply_key(KeyCode::Down, 3, 2, &mut state));
+        assert_eq!(state.item.copy_value(), 1);
+
+        input.apply_key(KeyCode::Right, 3, 2, &mut state);
+        assert_eq!((state.selected.copy_value(), state.item.copy_value()), (0, 0));
+
+        assert!(input.apply_key(KeyCode::Esc, 3, 2, &mut state));
+        assert!(!state.open.copy_value());
+    }
+}
@@ -0,0 +1,33 @@
+//! Reusable components.
+//!
+//! Unlike widgets these are registered with the runtime together with their template:
+//! ```ignore
+//! runtime.register_component(
+//!     "progress",
+//!     MultiProgress::TEMPLATE.to_template(),
+//!     MultiProgress::new(),
+//!     MultiProgressState::new(),
+//! );
+//! ```
+
+pub use self::calendar::{CalendarMessage, DatePicker, DatePickerState};
+pub use self::context_menu::{ContextMenuInput, ContextMenuSelection, ContextMenuState, CONTEXT_MENU};
+pub use self::list::{SelectList, SelectListState};
+pub use self::log::{Log, LogLine, LogState};
+pub use self::menu::{MenuBarInput, MenuBarState};
+pub use self::progress::{MultiProgress, MultiProgressState, ProgressBar, ProgressMessage};
+pub use self::radio::{RadioGroup, RadioGroupState, RadioOption};
+pub use self::roving::RovingFocus;
+pub use self::slider::{SliderInput, SliderState};
+pub use self::text_area::{TextArea, TextAreaLine, TextAreaState};
+
+mod calendar;
+mod context_menu;
+mod list;
+mod log;
+mod menu;
+mod progress;
+mod radio;
+mod roving;
+mod slider;
+mod text_area;
@@ -0,0 +1,303 @@
+use anathema_state::{List, State, Value};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+const FILLED: char = '█';
+const EMPTY: char = '░';
+const DEFAULT_BAR_WIDTH: usize = 20;
+
+/// Messages sent to a [`MultiProgress`] component.
+///
+/// The `id` is chosen by the sender and is used to address
+/// the same bar in subsequent messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressMessage {
+    /// Add a new bar. Adding a bar with an id that is already
+    /// in use will reset the existing bar.
+    Add { id: usize, label: String, total: usize },
+    /// Advance the bar by `amount`
+    Advance { id: usize, amount: usize },
+    /// Set the current progress of the bar
+    Set { id: usize, current: usize },
+    /// Mark the bar as complete
+    Finish { id: usize },
+    /// Remove the bar without counting it as complete
+    Remove { id: usize },
+}
+
+/// A single progress bar, as seen by the template.
+#[derive(State)]
+pub struct ProgressBar {
+    pub label: Value<String>,
+    pub current: Value<usize>,
+    pub total: Value<usize>,
+    pub percent: Value<usize>,
+    pub bar: Value<String>,
+}
+
+impl ProgressBar {
+    fn new(label: String, total: usize, width: usize) -> Self {
+        Self {
+            label: label.into(),
+            current: 0.into(),
+            total: total.into(),
+            percent: percent(0, total).into(),
+            bar: render_bar(0, total, width).into(),
+        }
+    }
+
+    fn update(&mut self, current: usize, width: usize) {
+        let total = self.total.copy_value();
+        let current = current.min(total);
+        self.current.set(current);
+        self.percent.set(percent(current, total));
+        self.bar.set(render_bar(current, total, width));
+    }
+}
+
+#[derive(State)]
+pub struct MultiProgressState {
+    /// Bars that are still in progress
+    pub bars: Value<List<ProgressBar>>,
+    /// Number of bars that have been completed and collapsed into the summary
+    pub completed: Value<usize>,
+    pub total_current: Value<usize>,
+    pub total: Value<usize>,
+    pub total_percent: Value<usize>,
+    pub total_bar: Value<String>,
+}
+
+impl MultiProgressState {
+    pub fn new() -> Self {
+        Self {
+            bars: List::empty(),
+            completed: 0.into(),
+            total_current: 0.into(),
+            total: 0.into(),
+            total_percent: 0.into(),
+            total_bar: render_bar(0, 0, DEFAULT_BAR_WIDTH).into(),
+        }
+    }
+}
+
+/// Manage multiple concurrent progress bars driven by [`ProgressMessage`]s.
+///
+/// Completed bars are removed from the list of bars and counted in `completed`,
+/// while still contributing to the aggregate `total_*` values.
+pub struct MultiProgress {
+    ids: Vec<usize>,
+    bar_width: usize,
+    // Current and total of the bars that are no longer in the list
+    completed_current: usize,
+    completed_total: usize,
+}
+
+impl MultiProgress {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+vstack
+    for bar in bars
+        hstack
+            text bar.label \" \"
+            text bar.bar
+            text \" \" bar.percent \"%\"
+    if completed
+        text completed \" completed\"
+    hstack
+        text \"total \"
+        text total_bar
+        text \" \" total_percent \"%\"
+";
+
+    pub fn new() -> Self {
+        Self {
+            ids: vec![],
+            bar_width: DEFAULT_BAR_WIDTH,
+            completed_current: 0,
+            completed_total: 0,
+        }
+    }
+
+    /// Set the width of the rendered bars (in characters)
+    pub fn bar_width(mut self, width: usize) -> Self {
+        self.bar_width = width;
+        self
+    }
+
+    fn index(&self, id: usize) -> Option<usize> {
+        self.ids.iter().position(|i| *i == id)
+    }
+
+    /// Apply a message to the state
+    pub fn apply(&mut self, message: ProgressMessage, state: &mut MultiProgressState) {
+        let width = self.bar_width;
+        match message {
+            ProgressMessage::Add { id, label, total } => match self.index(id) {
+                Some(index) => {
+                    let mut bars = state.bars.to_mut();
+                    if let Some(bar) = bars.get_mut(index) {
+                        let mut bar = bar.to_mut();
+                        bar.label.set(label);
+                        bar.total.set(total);
+                        bar.update(0, width);
+                    }
+                }
+                None => {
+                    self.ids.push(id);
+                    state.bars.push_back(ProgressBar::new(label, total, width));
+                }
+            },
+            ProgressMessage::Advance { id, amount } => {
+                let Some(index) = self.index(id) else { return };
+                let mut bars = state.bars.to_mut();
+                if let Some(bar) = bars.get_mut(index) {
+                    let mut bar = bar.to_mut();
+                    let current = bar.current.copy_value() + amount;
+                    bar.update(current, width);
+                }
+            }
+            ProgressMessage::Set { id, current } => {
+                let Some(index) = self.index(id) else { return };
+                let mut bars = state.bars.to_mut();
+                if let Some(bar) = bars.get_mut(index) {
+                    bar.to_mut().update(current, width);
+                }
+            }
+            ProgressMessage::Finish { id } => {
+                let Some(index) = self.index(id) else { return };
+                self.ids.remove(index);
+                if let Some(bar) = state.bars.remove(index) {
+                    let total = bar.to_ref().total.copy_value();
+                    self.completed_current += total;
+                    self.completed_total += total;
+                }
+                *state.completed.to_mut() += 1;
+            }
+            ProgressMessage::Remove { id } => {
+                let Some(index) = self.index(id) else { return };
+                self.ids.remove(index);
+                state.bars.remove(index);
+            }
+        }
+
+        self.update_total(state);
+    }
+
+    fn update_total(&self, state: &mut MultiProgressState) {
+        let (current, total) = state.bars.to_ref().iter().fold(
+            (self.completed_current, self.completed_total),
+            |(current, total), bar| {
+                let bar = bar.to_ref();
+                (current + bar.current.copy_value(), total + bar.total.copy_value())
+            },
+        );
+
+        state.total_current.set(current);
+        state.total.set(total);
+        state.total_percent.set(percent(current, total));
+        state.total_bar.set(render_bar(current, total, self.bar_width));
+    }
+}
+
+impl Component for MultiProgress {
+    type Message = ProgressMessage;
+    type State = MultiProgressState;
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.apply(message, state);
+    }
+
+    fn accept_focus(&self) -> bool {
+        false
+    }
+}
+
+fn percent(current: usize, total: usize) -> usize {
+    match total {
+        0 => 100,
+        _ => current.min(total) * 100 / total,
+    }
+}
+
+fn render_bar(current: usize, total: usize, width: usize) -> String {
+    let filled = match total {
+        0 => width,
+        _ => current.min(total) * width / total,
+    };
+
+    let mut bar = String::with_capacity(width * FILLED.len_utf8());
+    (0..filled).for_each(|_| bar.push(FILLED));
+    (filled..width).for_each(|_| bar.push(EMPTY));
+    bar
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn add(id: usize, total: usize) -> ProgressMessage {
+        ProgressMessage::Add {
+            id,
+            label: format!("job {id}"),
+            total,
+        }
+    }
+
+    #[test]
+    fn template_compiles() {
+        let mut doc = anathema_templates::Document::new(MultiProgress::TEMPLATE);
+        assert!(doc.compile().is_ok());
+    }
+
+    #[test]
+    fn render_partial_bar() {
+        assert_eq!(render_bar(5, 10, 4), "██░░");
+        assert_eq!(render_bar(0, 10, 4), "░░░░");
+        assert_eq!(render_bar(20, 10, 4), "████");
+    }
+
+    #[test]
+    fn advance_bars() {
+        let mut progress = MultiProgress::new().bar_width(4);
+        let mut state = MultiProgressState::new();
+
+        progress.apply(add(1, 10), &mut state);
+        progress.apply(add(2, 10), &mut state);
+        progress.apply(ProgressMessage::Advance { id: 1, amount: 5 }, &mut state);
+        progress.apply(ProgressMessage::Set { id: 2, current: 10 }, &mut state);
+
+        let bars = state.bars.to_ref();
+        assert_eq!(bars.get(0).unwrap().to_ref().percent.copy_value(), 50);
+        assert_eq!(*bars.get(1).unwrap().to_ref().bar.to_ref(), "████");
+        drop(bars);
+
+        assert_eq!(state.total_current.copy_value(), 15);
+        assert_eq!(state.total.copy_value(), 20);
+        assert_eq!(state.total_percent.copy_value(), 75);
+    }
+
+    #[test]
+    fn completed_bars_collapse() {
+        let mut progress = MultiProgress::new();
+        let mut state = MultiProgressState::new();
+
+        progress.apply(add(1, 10), &mut state);
+        progress.apply(add(2, 30), &mut state);
+        progress.apply(ProgressMessage::Finish { id: 1 }, &mut state);
+
+        assert_eq!(state.bars.len(), 1);
+        assert_eq!(state.completed.copy_value(), 1);
+        assert_eq!(state.total_current.copy_value(), 10);
+        assert_eq!(state.total.copy_value(), 40);
+
+        progress.apply(ProgressMessage::Remove { id: 2 }, &mut state);
+        assert_eq!(state.bars.len(), 0);
+        assert_eq!(state.total_percent.copy_value(), 100);
+    }
+}
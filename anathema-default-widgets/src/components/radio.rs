@@ -0,0 +1,243 @@
+use std::time::Duration;
+
+use anathema_state::{List, State, Value};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState, MouseEvent};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+use super::RovingFocus;
+
+// The attribute the index is bound to
+const SELECTED: &str = "selected";
+
+/// An option of a [`RadioGroup`], as seen by the template.
+#[derive(State)]
+pub struct RadioOption {
+    pub label: Value<String>,
+    pub checked: Value<bool>,
+}
+
+#[derive(State)]
+pub struct RadioGroupState {
+    pub options: Value<List<RadioOption>>,
+    pub selected: Value<usize>,
+    pub in_focus: Value<bool>,
+}
+
+impl RadioGroupState {
+    pub fn new(options: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let options = options.into_iter().enumerate().map(|(index, label)| RadioOption {
+            label: label.into().into(),
+            checked: (index == 0).into(),
+        });
+
+        Self {
+            options: List::from_iter(options),
+            selected: 0.into(),
+            in_focus: false.into(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.options.to_ref().len()
+    }
+}
+
+/// A group of options where one option is checked at a time.
+///
+/// The group is a single tab stop: the arrow keys move the check to the
+/// previous / next option, wrapping around at the ends, and clicking an option checks it.
+///
+/// The index of the checked option is published as `change` whenever it changes.
+///
+/// The index can also be bound to a `Value<usize>` of the parent state,
+/// e.g `@radio { selected: state.size }`: the group follows changes of `state.size`,
+/// and sets `state.size` when the checked option changes.
+#[derive(Debug)]
+pub struct RadioGroup {
+    focus: RovingFocus,
+}
+
+impl RadioGroup {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+vstack
+    for option in options
+        if option.checked
+            text [bold: in_focus] '(•) '
+                span option.label
+        else
+            text '( ) '
+                span option.label
+";
+
+    pub fn new() -> Self {
+        Self {
+            focus: RovingFocus::both().wrap(true),
+        }
+    }
+
+    fn set(&self, selected: usize, state: &mut RadioGroupState) -> bool {
+        let previous = state.selected.copy_value();
+        if selected == previous || selected >= state.len() {
+            return false;
+        }
+
+        let mut options = state.options.to_mut();
+        for (index, checked) in [(previous, false), (selected, true)] {
+            if let Some(option) = options.get_mut(index) {
+                option.to_mut().checked.set(checked);
+            }
+        }
+        drop(options);
+
+        state.selected.set(selected);
+        true
+    }
+
+    /// Apply a key press to the state.
+    /// Returns true if the checked option changed.
+    pub fn apply_key(&self, code: KeyCode, state: &mut RadioGroupState) -> bool {
+        match self.focus.apply_key(code, state.selected.copy_value(), state.len()) {
+            Some(selected) => self.set(selected, state),
+            None => false,
+        }
+    }
+
+    fn changed(state: &RadioGroupState, context: &mut Context<'_, RadioGroupState>) {
+        context.set_external(SELECTED, state.selected.copy_value());
+        context.publish("change", |state| &state.selected);
+    }
+}
+
+impl Default for RadioGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for RadioGroup {
+    type Message = usize;
+    type State = RadioGroupState;
+
+    fn on_blur(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(false);
+    }
+
+    fn on_focus(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(true);
+    }
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        if self.apply_key(key.code, state) {
+            Self::changed(state, &mut context);
+        }
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if !mouse.lsb_down() {
+            return;
+        }
+
+        // Each option is a single line
+        let mut row = None;
+        elements.at_position(mouse.pos()).by_tag("vstack").first(|el, _| {
+            row = Some((mouse.y as i32 - el.get_pos().y) as usize);
+        });
+
+        if row.is_some_and(|row| self.set(row, state)) {
+            Self::changed(state, &mut context);
+        }
+    }
+
+    fn tick(&mut self, state: &mut Self::State, _: Elements<'_, '_>, context: Context<'_, Self::State>, _: Duration) {
+        if let Some(selected) = context.external(SELECTED) {
+            self.set(selected, state);
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.set(message, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn check_with_arrow_keys() {
+        let group = RadioGroup::new();
+        let mut state = RadioGroupState::new(["a", "b", "c"]);
+
+        assert!(group.apply_key(KeyCode::Down, &mut state));
+        assert_eq!(state.selected.copy_value(), 1);
+
+        // Only one option is checked
+        let checked = state
+            .options
+            .to_ref()
+            .iter()
+            .map(|option| option.to_ref().checked.copy_value())
+            .collect::<Vec<_>>();
+        assert_eq!(checked, [false, true, false]);
+
+        // Wraps around at the ends
+        assert!(group.apply_key(KeyCode::End, &mut state));
+        assert!(group.apply_key(KeyCode::Right, &mut state));
+        assert_eq!(state.selected.copy_value(), 0);
+        assert!(!group.apply_key(KeyCode::Enter, &mut state));
+    }
+
+    #[test]
+    fn write_back_to_parent() {
+        let src = "
+vstack
+    text 'selected: ' value
+    @radio { selected: value }
+";
+        let expected = "
+            ╔═══════════╗
+            ║selected: 1║
+            ║( ) a      ║
+            ║(•) b      ║
+            ╚═══════════╝
+            ";
+
+        let mut runner = TestRunner::new(src, (11, 3));
+        runner.register_component(
+            "radio",
+            RadioGroup::TEMPLATE,
+            RadioGroup::new(),
+            RadioGroupState::new(["a", "b"]),
+        );
+        runner
+            .instance()
+            .send_key("radio", KeyCode::Down)
+            .render_assert(expected)
+            .with_state(|state| assert_eq!(*state.value.to_ref(), 1));
+    }
+}
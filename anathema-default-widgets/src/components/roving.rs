@@ -0,0 +1,116 @@
+use anathema_widgets::components::events::KeyCode;
+
+use crate::layout::Axis;
+
+/// Roving focus for composite components.
+///
+/// A composite component (lists, menus, radio groups etc.) is a single tab stop,
+/// and the arrow keys move the active element inside of it.
+/// `Home` / `End` moves to the first / last element.
+///
+/// ```
+/// # use anathema_default_widgets::components::RovingFocus;
+/// # use anathema_widgets::components::events::KeyCode;
+/// let focus = RovingFocus::vertical().wrap(true);
+/// assert_eq!(focus.apply_key(KeyCode::Down, 0, 3), Some(1));
+/// assert_eq!(focus.apply_key(KeyCode::Up, 0, 3), Some(2));
+/// assert_eq!(focus.apply_key(KeyCode::Left, 0, 3), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RovingFocus {
+    // The arrow keys that move the active element, or all of them if there is no axis
+    axis: Option<Axis>,
+    wrap: bool,
+}
+
+impl RovingFocus {
+    /// Move with `Up` / `Down`
+    pub fn vertical() -> Self {
+        Self {
+            axis: Some(Axis::Vertical),
+            wrap: false,
+        }
+    }
+
+    /// Move with `Left` / `Right`
+    pub fn horizontal() -> Self {
+        Self {
+            axis: Some(Axis::Horizontal),
+            wrap: false,
+        }
+    }
+
+    /// Move with any of the arrow keys, where `Up` and `Left` moves to the previous element
+    pub fn both() -> Self {
+        Self {
+            axis: None,
+            wrap: false,
+        }
+    }
+
+    /// Moving past the last element moves to the first element and the other way around
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// The new active element of `len` elements after the key press.
+    /// Returns `None` if the key doesn't move the active element.
+    pub fn apply_key(&self, code: KeyCode, active: usize, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+
+        let last = len - 1;
+        let active = active.min(last);
+        let vertical = self.axis != Some(Axis::Horizontal);
+        let horizontal = self.axis != Some(Axis::Vertical);
+
+        let new = match code {
+            KeyCode::Up if vertical => self.previous(active, last),
+            KeyCode::Left if horizontal => self.previous(active, last),
+            KeyCode::Down if vertical => self.next(active, last),
+            KeyCode::Right if horizontal => self.next(active, last),
+            KeyCode::Home => 0,
+            KeyCode::End => last,
+            _ => return None,
+        };
+
+        (new != active).then_some(new)
+    }
+
+    fn previous(&self, active: usize, last: usize) -> usize {
+        match active {
+            0 if self.wrap => last,
+            _ => active.saturating_sub(1),
+        }
+    }
+
+    fn next(&self, active: usize, last: usize) -> usize {
+        match active == last {
+            true if self.wrap => 0,
+            _ => (active + 1).min(last),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn move_active_element() {
+        let focus = RovingFocus::horizontal();
+        assert_eq!(focus.apply_key(KeyCode::Right, 0, 3), Some(1));
+        assert_eq!(focus.apply_key(KeyCode::Down, 0, 3), None);
+        assert_eq!(focus.apply_key(KeyCode::End, 0, 3), Some(2));
+        // Without wrapping the active element stays at the edges
+        assert_eq!(focus.apply_key(KeyCode::Right, 2, 3), None);
+        assert_eq!(focus.apply_key(KeyCode::Left, 0, 3), None);
+
+        let focus = RovingFocus::both().wrap(true);
+        assert_eq!(focus.apply_key(KeyCode::Down, 2, 3), Some(0));
+        assert_eq!(focus.apply_key(KeyCode::Left, 0, 3), Some(2));
+        assert_eq!(focus.apply_key(KeyCode::Down, 0, 0), None);
+    }
+}
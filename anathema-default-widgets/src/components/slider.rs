@@ -0,0 +1,252 @@
+use std::time::Duration;
+
+use anathema_state::{State, Value};
+use anathema_widgets::components::events::{InputSettings, KeyCode, KeyEvent, KeyState, MouseEvent, MouseState};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::Elements;
+
+use crate::Slider;
+
+// The attribute the value is bound to
+const VALUE: &str = "value";
+
+#[derive(State)]
+pub struct SliderState {
+    pub value: Value<f64>,
+    pub min: Value<f64>,
+    pub max: Value<f64>,
+    pub in_focus: Value<bool>,
+}
+
+impl SliderState {
+    pub fn new(value: f64, min: f64, max: f64) -> Self {
+        Self {
+            value: value.clamp(min, max).into(),
+            min: min.into(),
+            max: max.into(),
+            in_focus: false.into(),
+        }
+    }
+}
+
+/// Numeric input using a [`Slider`].
+///
+/// `Left` / `Right` moves the value by `step`, `Home` / `End` moves it to `min` / `max`,
+/// and clicking or dragging on the slider moves the value to the mouse position.
+///
+/// Scrolling the mouse wheel over the slider, or while it's in focus, also moves the value by `step`.
+/// Holding shift / ctrl while scrolling makes the step coarser / finer, see [`InputSettings`].
+///
+/// The value is published as `change` whenever it changes.
+///
+/// The value can also be bound to a `Value<f64>` of the parent state,
+/// e.g `@slider { value: state.volume }`: the slider follows changes of `state.volume`,
+/// and sets `state.volume` when the value changes.
+pub struct SliderInput {
+    step: f64,
+}
+
+impl SliderInput {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+if in_focus
+    slider [value: value, min: min, max: max, bold: true]
+else
+    slider [value: value, min: min, max: max]
+";
+
+    pub fn new(step: f64) -> Self {
+        Self { step }
+    }
+
+    fn set(&self, value: f64, state: &mut SliderState) -> bool {
+        let value = value.clamp(state.min.copy_value(), state.max.copy_value());
+        if value == state.value.copy_value() {
+            return false;
+        }
+        state.value.set(value);
+        true
+    }
+
+    /// Apply a key press to the state.
+    /// Returns true if the value changed.
+    pub fn apply_key(&self, code: KeyCode, state: &mut SliderState) -> bool {
+        let value = state.value.copy_value();
+        let value = match code {
+            KeyCode::Left | KeyCode::Down => value - self.step,
+            KeyCode::Right | KeyCode::Up => value + self.step,
+            KeyCode::Home => state.min.copy_value(),
+            KeyCode::End => state.max.copy_value(),
+            _ => return false,
+        };
+        self.set(value, state)
+    }
+
+    /// Apply a scroll of the mouse wheel to the state.
+    /// Returns true if the value changed.
+    pub fn apply_scroll(&self, mouse: MouseEvent, settings: &InputSettings, state: &mut SliderState) -> bool {
+        if !settings.scroll_adjust {
+            return false;
+        }
+
+        let step = settings.step(self.step, mouse.ctrl, mouse.shift);
+        let value = state.value.copy_value();
+        let value = match mouse.state {
+            MouseState::ScrollUp | MouseState::ScrollRight => value + step,
+            MouseState::ScrollDown | MouseState::ScrollLeft => value - step,
+            _ => return false,
+        };
+        self.set(value, state)
+    }
+
+    fn changed(state: &SliderState, context: &mut Context<'_, SliderState>) {
+        context.set_external(VALUE, state.value.copy_value());
+        context.publish("change", |state| &state.value);
+    }
+}
+
+impl Default for SliderInput {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl Component for SliderInput {
+    type Message = f64;
+    type State = SliderState;
+
+    fn on_blur(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(false);
+    }
+
+    fn on_focus(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(true);
+    }
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        if self.apply_key(key.code, state) {
+            Self::changed(state, &mut context);
+        }
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        let scroll = matches!(
+            mouse.state,
+            MouseState::ScrollUp | MouseState::ScrollDown | MouseState::ScrollLeft | MouseState::ScrollRight
+        );
+        if !mouse.lsb_down() && !scroll {
+            return;
+        }
+
+        let mut changed = false;
+        let (min, max) = (state.min.copy_value(), state.max.copy_value());
+        let settings = context.input;
+        if scroll && state.in_focus.copy_value() {
+            changed = self.apply_scroll(mouse, &settings, state);
+        }
+
+        elements.at_position(mouse.pos()).by_tag("slider").first(|el, _| {
+            if scroll {
+                // A focused slider has already been scrolled
+                if !changed {
+                    changed = self.apply_scroll(mouse, &settings, state);
+                }
+                return;
+            }
+
+            let offset = (mouse.x as i32 - el.get_pos().x).max(0) as usize;
+            let value = Slider::value_at(offset, el.size().width, min, max);
+            changed = self.set(value, state);
+        });
+
+        if changed {
+            Self::changed(state, &mut context);
+        }
+    }
+
+    fn tick(&mut self, state: &mut Self::State, _: Elements<'_, '_>, context: Context<'_, Self::State>, _: Duration) {
+        if let Some(value) = context.external(VALUE) {
+            self.set(value, state);
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.set(message, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn step_with_keys() {
+        let input = SliderInput::new(2.5);
+        let mut state = SliderState::new(5.0, 0.0, 10.0);
+
+        assert!(input.apply_key(KeyCode::Right, &mut state));
+        assert_eq!(state.value.copy_value(), 7.5);
+
+        input.apply_key(KeyCode::Right, &mut state);
+        // Clamped to max
+        assert!(!input.apply_key(KeyCode::Right, &mut state));
+        assert_eq!(state.value.copy_value(), 10.0);
+
+        input.apply_key(KeyCode::Home, &mut state);
+        assert_eq!(state.value.copy_value(), 0.0);
+    }
+
+    #[test]
+    fn step_with_scroll() {
+        let input = SliderInput::new(1.0);
+        let settings = InputSettings::default();
+        let mut state = SliderState::new(50.0, 0.0, 100.0);
+        let scroll = |state, ctrl, shift| MouseEvent {
+            x: 0,
+            y: 0,
+            state,
+            ctrl,
+            shift,
+            local: None,
+        };
+
+        assert!(input.apply_scroll(scroll(MouseState::ScrollUp, false, false), &settings, &mut state));
+        assert_eq!(state.value.copy_value(), 51.0);
+
+        // Coarse steps with shift
+        input.apply_scroll(scroll(MouseState::ScrollDown, false, true), &settings, &mut state);
+        assert_eq!(state.value.copy_value(), 41.0);
+
+        // Fine steps with ctrl
+        input.apply_scroll(scroll(MouseState::ScrollUp, true, false), &settings, &mut state);
+        assert!((state.value.copy_value() - 41.1).abs() < f64::EPSILON * 100.0);
+
+        let settings = InputSettings {
+            scroll_adjust: false,
+            ..settings
+        };
+        assert!(!input.apply_scroll(scroll(MouseState::ScrollUp, false, false), &settings, &mut state));
+    }
+}
@@ -0,0 +1,340 @@
+use anathema_state::{List, State, Value};
+use anathema_store::rope::Rope;
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+use anathema_widgets::components::{Component, Context};
+use anathema_widgets::layout::words::WordBoundary;
+use anathema_widgets::Elements;
+
+/// A visible line of a [`TextArea`], as seen by the template.
+/// The line is split around the cursor, which is empty on any other line than the cursor line.
+#[derive(State)]
+pub struct TextAreaLine {
+    pub before: Value<String>,
+    pub cursor: Value<String>,
+    pub after: Value<String>,
+}
+
+#[derive(State)]
+pub struct TextAreaState {
+    /// The visible lines
+    pub lines: Value<List<TextAreaLine>>,
+    /// The line of the cursor
+    pub line: Value<usize>,
+    /// The column of the cursor, in chars
+    pub column: Value<usize>,
+    pub in_focus: Value<bool>,
+}
+
+impl TextAreaState {
+    pub fn new() -> Self {
+        Self {
+            lines: List::empty(),
+            line: 0.into(),
+            column: 0.into(),
+            in_focus: false.into(),
+        }
+    }
+}
+
+impl Default for TextAreaState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Multi-line text input, backed by a [`Rope`].
+///
+/// Only the visible lines are copied into the state, so large documents
+/// can be edited and scrolled without copying the whole text on every key press.
+///
+/// The arrow keys move the cursor, `ctrl` + `Left` / `Right` moves between words
+/// (see [`anathema_widgets::components::events::InputSettings::word_boundary`]),
+/// `Home` / `End` moves to the start / end of the line and `PageUp` / `PageDown` moves a page.
+///
+/// ```ignore
+/// let mut text_area = TextArea::new(&std::fs::read_to_string("notes.txt")?, 20);
+/// let state = text_area.state();
+/// runtime.register_component("editor", TextArea::TEMPLATE.to_template(), text_area, state);
+/// ```
+pub struct TextArea {
+    text: Rope,
+    // The char index of the cursor
+    cursor: usize,
+    // The column to return to when moving between lines of different length
+    goal_column: Option<usize>,
+    // The first visible line
+    offset: usize,
+    height: usize,
+}
+
+impl TextArea {
+    /// A default template for the component.
+    pub const TEMPLATE: &'static str = "
+vstack
+    for line in lines
+        text line.before
+            span [inverse: in_focus] line.cursor
+            span line.after
+";
+
+    /// A text area showing `height` lines at a time
+    pub fn new(text: &str, height: usize) -> Self {
+        Self {
+            text: Rope::from(text),
+            cursor: 0,
+            goal_column: None,
+            offset: 0,
+            height: height.max(1),
+        }
+    }
+
+    /// The state showing the first lines of the text,
+    /// to register with the component.
+    pub fn state(&mut self) -> TextAreaState {
+        let mut state = TextAreaState::new();
+        self.refresh(&mut state);
+        state
+    }
+
+    pub fn text(&self) -> &Rope {
+        &self.text
+    }
+
+    /// Replace the text and move the cursor to the start
+    pub fn set_text(&mut self, text: &str, state: &mut TextAreaState) {
+        self.text = Rope::from(text);
+        self.cursor = 0;
+        self.goal_column = None;
+        self.offset = 0;
+        self.refresh(state);
+    }
+
+    fn line_and_column(&self) -> (usize, usize) {
+        let line = self.text.char_to_line(self.cursor);
+        (line, self.cursor - self.text.line_to_char(line))
+    }
+
+    fn move_to_line(&mut self, line: usize) {
+        let (_, column) = self.line_and_column();
+        let column = *self.goal_column.get_or_insert(column);
+        let line = line.min(self.text.len_lines() - 1);
+        self.cursor = self.text.line_to_char(line) + column.min(self.text.line_len(line));
+    }
+
+    // Move between words of the cursor line, or to the next / previous line at the ends
+    fn move_word(&mut self, boundary: WordBoundary, forward: bool) {
+        let (line, column) = self.line_and_column();
+        let start = self.text.line_to_char(line);
+        let chars = self.text.line(line).chars().collect::<Vec<_>>();
+
+        self.cursor = match forward {
+            true if column == chars.len() => (self.cursor + 1).min(self.text.len_chars()),
+            true => start + boundary.next(&chars, column),
+            false if column == 0 => self.cursor.saturating_sub(1),
+            false => start + boundary.previous(&chars, column),
+        };
+    }
+
+    /// Apply a key press to the text.
+    /// Returns true if the text or the cursor changed.
+    pub fn apply_key(&mut self, key: KeyEvent, boundary: WordBoundary, state: &mut TextAreaState) -> bool {
+        let before = (self.cursor, self.text.len_chars());
+        let (line, _) = self.line_and_column();
+
+        match key.code {
+            KeyCode::Char(c) if !key.ctrl => {
+                let mut buf = [0; 4];
+                self.text.insert(self.cursor, c.encode_utf8(&mut buf));
+                self.cursor += 1;
+            }
+            KeyCode::Enter => {
+                self.text.insert(self.cursor, "\n");
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.text.remove(self.cursor - 1..self.cursor);
+                self.cursor -= 1;
+            }
+            KeyCode::Delete if self.cursor < self.text.len_chars() => {
+                self.text.remove(self.cursor..self.cursor + 1);
+            }
+            KeyCode::Left if key.ctrl => self.move_word(boundary, false),
+            KeyCode::Right if key.ctrl => self.move_word(boundary, true),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len_chars()),
+            KeyCode::Up => self.move_to_line(line.saturating_sub(1)),
+            KeyCode::Down => self.move_to_line(line + 1),
+            KeyCode::PageUp => self.move_to_line(line.saturating_sub(self.height)),
+            KeyCode::PageDown => self.move_to_line(line + self.height),
+            KeyCode::Home => self.cursor = self.text.line_to_char(line),
+            KeyCode::End => self.cursor = self.text.line_to_char(line) + self.text.line_len(line),
+            _ => return false,
+        }
+
+        if !matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown
+        ) {
+            self.goal_column = None;
+        }
+
+        let changed = before != (self.cursor, self.text.len_chars());
+        if changed {
+            self.refresh(state);
+        }
+        changed
+    }
+
+    /// Scroll to the cursor and copy the visible lines into the state
+    pub fn refresh(&mut self, state: &mut TextAreaState) {
+        let (line, column) = self.line_and_column();
+        if line < self.offset {
+            self.offset = line;
+        } else if line >= self.offset + self.height {
+            self.offset = line + 1 - self.height;
+        }
+        // Don't leave empty space at the bottom once lines are removed
+        self.offset = self.offset.min(self.text.len_lines().saturating_sub(self.height));
+        state.line.set(line);
+        state.column.set(column);
+
+        let visible = self.offset..(self.offset + self.height).min(self.text.len_lines());
+        while state.lines.to_ref().len() > visible.len() {
+            state.lines.pop_back();
+        }
+        while state.lines.to_ref().len() < visible.len() {
+            state.lines.push_back(TextAreaLine {
+                before: String::new().into(),
+                cursor: String::new().into(),
+                after: String::new().into(),
+            });
+        }
+
+        let mut lines = state.lines.to_mut();
+
+        for (index, visible_line) in visible.enumerate() {
+            let text = self.text.line(visible_line);
+            let (before, cursor, after) = match visible_line == line {
+                true => {
+                    let mut chars = text.chars();
+                    let before = chars.by_ref().take(column).collect::<String>();
+                    let cursor = chars.next().unwrap_or(' ').to_string();
+                    (before, cursor, chars.collect())
+                }
+                false => (text, String::new(), String::new()),
+            };
+
+            let Some(line) = lines.get_mut(index) else { continue };
+            let mut line = line.to_mut();
+            line.before.set(before);
+            line.cursor.set(cursor);
+            line.after.set(after);
+        }
+    }
+}
+
+impl Component for TextArea {
+    type Message = String;
+    type State = TextAreaState;
+
+    fn on_blur(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(false);
+    }
+
+    fn on_focus(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+        state.in_focus.set(true);
+    }
+
+    fn on_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        if matches!(key.state, KeyState::Release) {
+            return;
+        }
+
+        self.apply_key(key, context.input.word_boundary, state);
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.set_text(&message, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(code: KeyCode, ctrl: bool) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl,
+            state: KeyState::Press,
+        }
+    }
+
+    fn visible(state: &TextAreaState) -> Vec<String> {
+        state
+            .lines
+            .to_ref()
+            .iter()
+            .map(|line| {
+                let line = line.to_ref();
+                format!(
+                    "{}{}{}",
+                    line.before.to_ref().as_str(),
+                    line.cursor.to_ref().as_str(),
+                    line.after.to_ref().as_str()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn edit_and_scroll() {
+        let mut area = TextArea::new("one\ntwo\nthree", 2);
+        let mut state = area.state();
+        assert_eq!(visible(&state), ["one", "two"]);
+
+        area.apply_key(key(KeyCode::End, false), WordBoundary::Identifier, &mut state);
+        area.apply_key(key(KeyCode::Char('!'), false), WordBoundary::Identifier, &mut state);
+        assert_eq!(area.text().to_string(), "one!\ntwo\nthree");
+
+        // Moving past the last visible line scrolls
+        area.apply_key(key(KeyCode::Down, false), WordBoundary::Identifier, &mut state);
+        area.apply_key(key(KeyCode::Down, false), WordBoundary::Identifier, &mut state);
+        assert_eq!(state.line.copy_value(), 2);
+        assert_eq!(state.column.copy_value(), 4);
+        assert_eq!(visible(&state), ["two", "three"]);
+
+        area.apply_key(key(KeyCode::Backspace, false), WordBoundary::Identifier, &mut state);
+        assert_eq!(area.text().to_string(), "one!\ntwo\nthre");
+
+        area.apply_key(key(KeyCode::Home, false), WordBoundary::Identifier, &mut state);
+        area.apply_key(key(KeyCode::Backspace, false), WordBoundary::Identifier, &mut state);
+        assert_eq!(area.text().to_string(), "one!\ntwothre");
+        assert_eq!(visible(&state), ["one!", "twothre"]);
+    }
+
+    #[test]
+    fn move_between_words() {
+        let mut area = TextArea::new("let max_width = 1;", 1);
+        let mut state = TextAreaState::new();
+
+        area.apply_key(key(KeyCode::Right, true), WordBoundary::Identifier, &mut state);
+        area.apply_key(key(KeyCode::Right, true), WordBoundary::Identifier, &mut state);
+        assert_eq!(state.column.copy_value(), 13);
+
+        area.apply_key(key(KeyCode::Left, true), WordBoundary::Natural, &mut state);
+        assert_eq!(state.column.copy_value(), 8);
+    }
+}
@@ -2,7 +2,9 @@ use std::ops::ControlFlow;
 
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use crate::{HEIGHT, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH, WIDTH};
 
@@ -10,6 +12,17 @@ use crate::{HEIGHT, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH, WIDTH};
 pub struct Container;
 
 impl Widget for Container {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None)
+            .attribute(MIN_WIDTH, AttributeType::Int, None)
+            .attribute(MIN_HEIGHT, AttributeType::Int, None)
+            .attribute(MAX_WIDTH, AttributeType::Int, None)
+            .attribute(MAX_HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         mut children: LayoutChildren<'_, '_, 'bp>,
@@ -30,11 +43,11 @@ impl Widget for Container {
         }
 
         if let Some(width) = attribs.get_usize(MIN_WIDTH) {
-            constraints.min_width = width;
+            constraints.set_min_width(width);
         }
 
         if let Some(height) = attribs.get_usize(MIN_HEIGHT) {
-            constraints.min_height = height;
+            constraints.set_min_height(height);
         }
 
         if let Some(width) = attribs.get_usize(MAX_WIDTH) {
@@ -72,6 +85,9 @@ impl Widget for Container {
 
 #[cfg(test)]
 mod test {
+    use anathema_widgets::error::Error;
+    use anathema_widgets::layout::{ConstraintError, LayoutError};
+
     use crate::testing::TestRunner;
 
     #[test]
@@ -90,4 +106,56 @@ mod test {
 
         TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn background_char() {
+        let tpl = "
+            container [width: 4, height: 2, background_char: '.']
+                text 'a'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║a...  ║
+            ║....  ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn tight_width_in_expanded_parent() {
+        let tpl = "
+            hstack
+                expand
+                    container [width: 2]
+                        text 'abc'
+        ";
+
+        let err = TestRunner::new(tpl, (5, 2)).instance().render_err();
+        let Error::Layout { widget, path, error } = err else { panic!("{err}") };
+        assert_eq!(widget, "text");
+        assert_eq!(&*path, &[0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            error,
+            LayoutError::Constraints(ConstraintError::TightWidthBelowMin { width: 2, min: 5 })
+        );
+    }
+
+    #[test]
+    fn min_width_larger_than_max_width() {
+        let tpl = "
+            container [min_width: 10]
+                text 'a'
+        ";
+
+        let expected = "
+            ╔═════╗
+            ║a    ║
+            ╚═════╝
+        ";
+
+        TestRunner::new(tpl, (5, 1)).instance().render_assert(expected);
+    }
 }
@@ -0,0 +1,159 @@
+use anathema_geometry::Size;
+use anathema_widgets::layout::{Constraints, Layout, LayoutCtx, PositionCtx};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, Layouts, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::stacks::ZStack;
+
+// The name of the registered layout
+const LAYOUT: &str = "layout";
+
+/// Lays out the children with a layout registered on the factory,
+/// see [`anathema_widgets::Factory::register_layout`].
+///
+/// ```text
+/// layout [layout: "masonry"]
+///     text 'a'
+///     text 'b'
+/// ```
+///
+/// Without a registered layout the children are placed on top of each other, like a `zstack`.
+pub struct CustomLayout {
+    layouts: Layouts,
+    // The name and the instance of the current layout
+    layout: Option<(String, Box<dyn Layout>)>,
+}
+
+impl CustomLayout {
+    pub fn new(layouts: Layouts) -> Self {
+        Self { layouts, layout: None }
+    }
+}
+
+impl Widget for CustomLayout {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many).attribute(LAYOUT, AttributeType::String, None))
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let name = ctx.attribs.get(id).get_ref::<&str>(LAYOUT).unwrap_or("");
+
+        // The layout keeps its state until the attribute changes
+        if self.layout.as_ref().is_none_or(|(current, _)| current != name) {
+            self.layout = self.layouts.make(name).map(|layout| (name.to_string(), layout));
+        }
+
+        match &mut self.layout {
+            Some((_, layout)) => layout.layout(children, constraints, id, ctx),
+            None => ZStack.layout(children, constraints, id, ctx),
+        }
+    }
+
+    fn position<'bp>(
+        &mut self,
+        children: PositionChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        match &mut self.layout {
+            Some((_, layout)) => layout.position(children, id, attribute_storage, ctx),
+            None => ZStack.position(children, id, attribute_storage, ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::ControlFlow;
+
+    use anathema_geometry::Pos;
+
+    use super::*;
+    use crate::testing::TestRunner;
+
+    // Places the children on a diagonal
+    struct Diagonal;
+
+    impl Layout for Diagonal {
+        fn layout<'bp>(
+            &mut self,
+            mut children: LayoutChildren<'_, '_, 'bp>,
+            constraints: Constraints,
+            _: WidgetId,
+            ctx: &mut LayoutCtx<'_, 'bp>,
+        ) -> Size {
+            let mut size = Size::ZERO;
+            children.for_each(|child, children| {
+                let child_size = child.layout(children, constraints, ctx);
+                size.width += child_size.width;
+                size.height += child_size.height;
+                ControlFlow::Continue(())
+            });
+            size
+        }
+
+        fn position<'bp>(
+            &mut self,
+            mut children: PositionChildren<'_, '_, 'bp>,
+            _: WidgetId,
+            attribute_storage: &AttributeStorage<'bp>,
+            ctx: PositionCtx,
+        ) {
+            let mut pos = ctx.pos;
+            children.for_each(|child, children| {
+                child.position(children, pos, attribute_storage, ctx.viewport);
+                let size = child.size();
+                pos += Pos::new(size.width as i32, size.height as i32);
+                ControlFlow::Continue(())
+            });
+        }
+    }
+
+    #[test]
+    fn registered_layout() {
+        let tpl = "
+            layout [layout: 'diagonal']
+                text 'a'
+                text 'b'
+                text 'c'
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║a  ║
+            ║ b ║
+            ║  c║
+            ╚═══╝
+        ";
+
+        let mut runner = TestRunner::new(tpl, (3, 3));
+        runner.factory().register_layout("diagonal", || Diagonal);
+        runner.instance().render_assert(expected);
+    }
+
+    #[test]
+    fn unknown_layout() {
+        let tpl = "
+            layout [layout: 'unknown']
+                text '22'
+                text '1'
+        ";
+
+        let expected = "
+            ╔══╗
+            ║12║
+            ╚══╝
+        ";
+
+        TestRunner::new(tpl, (2, 1)).instance().render_assert(expected);
+    }
+}
@@ -0,0 +1,205 @@
+use std::ops::ControlFlow;
+
+use anathema::CommonVal;
+use anathema_geometry::{Pos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::{
+    AttributeStorage, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+// The side of the dock a child is placed on
+const DOCK: &str = "dock";
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    #[default]
+    Fill,
+}
+
+impl TryFrom<CommonVal<'_>> for Side {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        match value.to_common_str().as_ref() {
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "fill" => Ok(Self::Fill),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Places each child against a side of the remaining space, with the `dock` attribute of the child.
+///
+/// Docked children are laid out in the order they are declared, and each one takes its
+/// height (`top`, `bottom`) or width (`left`, `right`) out of the remaining space.
+/// The `fill` children, and children without a `dock` attribute, are given the space that is left.
+///
+/// ```text
+/// dock
+///     text [dock: 'top'] 'header'
+///     text [dock: 'bottom'] 'status'
+///     border [dock: 'left']
+///         text 'sidebar'
+///     text 'content'
+/// ```
+#[derive(Debug, Default)]
+pub struct Dock {
+    // Offset of each child, relative to the widget
+    offsets: Vec<Pos>,
+}
+
+impl Widget for Dock {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many))
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let size = constraints.max_size();
+
+        // The remaining space
+        let mut pos = Pos::ZERO;
+        let mut remaining = size;
+        let mut fill = vec![];
+        self.offsets.clear();
+
+        children.for_each(|child, children| {
+            let side: Side = ctx.attribs.get(child.id()).get(DOCK).unwrap_or_default();
+            let index = self.offsets.len();
+            self.offsets.push(Pos::ZERO);
+
+            let mut constraints = Constraints::new(remaining.width, remaining.height);
+            match side {
+                Side::Fill => {
+                    fill.push(index);
+                    return ControlFlow::Continue(());
+                }
+                Side::Top | Side::Bottom => constraints.make_width_tight(remaining.width),
+                Side::Left | Side::Right => constraints.make_height_tight(remaining.height),
+            }
+
+            let child_size = child.layout(children, constraints, ctx);
+            let width = child_size.width.min(remaining.width);
+            let height = child_size.height.min(remaining.height);
+
+            self.offsets[index] = match side {
+                Side::Top => {
+                    let offset = pos;
+                    pos.y += height as i32;
+                    remaining.height -= height;
+                    offset
+                }
+                Side::Bottom => {
+                    remaining.height -= height;
+                    Pos::new(pos.x, pos.y + remaining.height as i32)
+                }
+                Side::Left => {
+                    let offset = pos;
+                    pos.x += width as i32;
+                    remaining.width -= width;
+                    offset
+                }
+                Side::Right => {
+                    remaining.width -= width;
+                    Pos::new(pos.x + remaining.width as i32, pos.y)
+                }
+                Side::Fill => unreachable!("fill children are laid out last"),
+            };
+
+            ControlFlow::Continue(())
+        });
+
+        let mut constraints = Constraints::new(remaining.width, remaining.height);
+        constraints.make_width_tight(remaining.width);
+        constraints.make_height_tight(remaining.height);
+
+        let mut index = 0;
+        children.for_each(|child, children| {
+            if fill.contains(&index) {
+                child.layout(children, constraints, ctx);
+                self.offsets[index] = pos;
+            }
+            index += 1;
+            ControlFlow::Continue(())
+        });
+
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let offset = self.offsets.get(index).copied().unwrap_or(Pos::ZERO);
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn app_shell() {
+        let tpl = "
+            dock
+                text [dock: 'top'] 'head'
+                text [dock: 'bottom'] 'foot'
+                text [dock: 'left'] 'l'
+                text [dock: 'right'] 'r'
+                text 'main'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║head    ║
+            ║lmain  r║
+            ║        ║
+            ║foot    ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn declaration_order() {
+        // The left child is docked before the top child, so it takes the full height
+        let tpl = "
+            dock
+                text 'fill'
+                text [dock: 'left'] 'l'
+                text [dock: 'top'] 'top'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║ltop  ║
+            ║ fill ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 3)).instance().render_assert(expected);
+    }
+}
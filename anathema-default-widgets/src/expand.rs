@@ -3,7 +3,10 @@ use std::ops::ControlFlow;
 use anathema_geometry::{LocalPos, Size};
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
 
 use crate::layout::{single_layout, Axis};
 
@@ -11,6 +14,14 @@ use crate::layout::{single_layout, Axis};
 pub struct Expand;
 
 impl Widget for Expand {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute("axis", AttributeType::Enum(&["horizontal", "vertical"]), None)
+            .attribute("factor", AttributeType::Int, Some("1"))
+            .attribute("fill", AttributeType::String, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -0,0 +1,195 @@
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{HEIGHT, WIDTH};
+
+pub const VALUE: &str = "value";
+pub const MIN: &str = "min";
+pub const MAX: &str = "max";
+pub const LABEL: &str = "label";
+pub const FILL: &str = "fill";
+
+const DEFAULT_FILL: char = '█';
+const DEFAULT_MAX: f64 = 100.0;
+// Width used if the constraints are unbounded and no width is given
+const DEFAULT_WIDTH: usize = 20;
+
+/// A filled bar with a centred label.
+///
+/// The label defaults to the percentage, and is painted after the bar
+/// (the same ordering as a `zstack`), inverting the style of the label
+/// wherever it overlaps the filled part of the bar.
+///
+/// ```text
+/// gauge [value: 42, max: 100, foreground: "green"]
+/// ```
+#[derive(Debug, Default)]
+pub struct Gauge {
+    filled: usize,
+    label: String,
+    fill: char,
+}
+
+impl Gauge {
+    /// The number of filled cells for a given width
+    fn filled(value: f64, min: f64, max: f64, width: usize) -> usize {
+        (Self::fraction(value, min, max) * width as f64).round() as usize
+    }
+
+    fn fraction(value: f64, min: f64, max: f64) -> f64 {
+        match max > min {
+            true => (value.clamp(min, max) - min) / (max - min),
+            false => 0.0,
+        }
+    }
+}
+
+impl Widget for Gauge {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(VALUE, AttributeType::Float, None)
+            .attribute(MIN, AttributeType::Float, Some("0.0"))
+            .attribute(MAX, AttributeType::Float, Some("100.0"))
+            .attribute(LABEL, AttributeType::String, None)
+            .attribute(FILL, AttributeType::Char, Some("\"█\""))
+            .attribute(WIDTH, AttributeType::Int, Some("20"))
+            .attribute(HEIGHT, AttributeType::Int, Some("1"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+
+        let width = match attributes.get_usize(WIDTH) {
+            Some(width) => width.min(constraints.max_width()),
+            None if constraints.is_width_unbounded() => DEFAULT_WIDTH,
+            None => constraints.max_width(),
+        };
+        let width = width.max(constraints.min_width);
+        let height = attributes
+            .get_usize(HEIGHT)
+            .unwrap_or(1)
+            .max(constraints.min_height)
+            .min(constraints.max_height());
+
+        let min = attributes.get_float(MIN).unwrap_or(0.0);
+        let max = attributes.get_float(MAX).unwrap_or(DEFAULT_MAX);
+        let value = attributes.get_float(VALUE).unwrap_or(min);
+        self.filled = Self::filled(value, min, max, width);
+
+        self.label.clear();
+        match attributes.get_val(LABEL) {
+            Some(label) => label.str_for_each(|s| self.label.push_str(s)),
+            None => {
+                let percent = (Self::fraction(value, min, max) * 100.0).round();
+                self.label = format!("{percent}%");
+            }
+        }
+
+        self.fill = attributes
+            .get_ref::<&str>(FILL)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(DEFAULT_FILL);
+
+        Size::new(width, height)
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The gauge has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let Size { width, height } = ctx.local_size;
+
+        // Bar
+        for y in 0..height {
+            for x in 0..self.filled.min(width) {
+                ctx.place_glyph(self.fill, LocalPos::new(x as u16, y as u16));
+            }
+        }
+
+        // Label
+        let label_width = self.label.width();
+        if label_width > width || height == 0 {
+            return;
+        }
+
+        let mut inverse = Style::new();
+        inverse.set_inverse(true);
+
+        let mut pos = LocalPos::new(((width - label_width) / 2) as u16, (height / 2) as u16);
+        for c in self.label.chars() {
+            if (pos.x as usize) < self.filled {
+                ctx.set_attributes(&inverse, pos);
+            }
+            match ctx.place_glyph(c, pos) {
+                Some(next) => pos = next,
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn half_filled_gauge() {
+        let expected = "
+            ╔══════════╗
+            ║███50%    ║
+            ╚══════════╝
+        ";
+        TestRunner::new("gauge [value: 50]", (10, 1))
+            .instance()
+            .render_assert(expected);
+    }
+
+    #[test]
+    fn gauge_with_label() {
+        let expected = "
+            ╔══════════╗
+            ║██████████║
+            ║███done███║
+            ║██████████║
+            ╚══════════╝
+        ";
+        TestRunner::new("gauge [value: 1, max: 1, label: 'done', height: 3]", (10, 3))
+            .instance()
+            .render_assert(expected);
+    }
+
+    #[test]
+    fn filled_cells() {
+        assert_eq!(Gauge::filled(0.0, 0.0, 100.0, 10), 0);
+        assert_eq!(Gauge::filled(25.0, 0.0, 100.0, 10), 3);
+        assert_eq!(Gauge::filled(200.0, 0.0, 100.0, 10), 10);
+    }
+}
@@ -0,0 +1,208 @@
+use anathema_backend::tui::image::{half_blocks, image_cells};
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::{HEIGHT, WIDTH};
+
+const HALF_BLOCK: char = '▀';
+
+/// Display an image.
+///
+/// The image is drawn with the graphics protocol of the terminal (kitty, sixel or iTerm2),
+/// falling back to unicode half blocks if the terminal doesn't support any of them.
+///
+/// The `width` and `height` attributes are in cells. If only one of them is set, the other
+/// is calculated from the aspect ratio of the image. The image will shrink to fit the constraints.
+///
+/// ```text
+/// image [width: 20]
+/// ```
+///
+/// The pixels (RGBA, eight bits per channel) are set through the widget:
+/// ```ignore
+/// elements.by_tag("image").first(|el, _| el.to::<Image>().set_pixels(rgba, Size::new(64, 64)));
+/// ```
+#[derive(Debug, Default)]
+pub struct Image {
+    pixels: Vec<u8>,
+    image_size: Size,
+    is_dirty: bool,
+}
+
+impl Image {
+    /// Set the pixels of the image.
+    /// Returns `false` if the number of pixels does not match the size.
+    pub fn set_pixels(&mut self, pixels: Vec<u8>, size: Size) -> bool {
+        if pixels.len() != size.width * size.height * 4 {
+            return false;
+        }
+
+        self.pixels = pixels;
+        self.image_size = size;
+        self.is_dirty = true;
+        true
+    }
+
+    /// Remove the image
+    pub fn clear(&mut self) {
+        self.pixels.clear();
+        self.image_size = Size::ZERO;
+        self.is_dirty = true;
+    }
+
+    /// Size of the image in pixels
+    pub fn image_size(&self) -> Size {
+        self.image_size
+    }
+}
+
+impl Widget for Image {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.is_dirty = false;
+
+        // If the backend doesn't know the size of a cell in pixels
+        // the footprint assumes a common cell size (8x16)
+        let mut cells = image_cells(
+            self.image_size,
+            attributes.get_usize(WIDTH),
+            attributes.get_usize(HEIGHT),
+            ctx.viewport.cell_size(),
+        );
+
+        // Shrink to fit, keeping the aspect ratio
+        if cells.width > constraints.max_width() {
+            cells.height = (cells.height * constraints.max_width() / cells.width).max(1);
+            cells.width = constraints.max_width();
+        }
+        if cells.height > constraints.max_height() {
+            cells.width = (cells.width * constraints.max_height() / cells.height).max(1);
+            cells.height = constraints.max_height();
+        }
+
+        cells
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The image has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let cells = ctx.local_size;
+        if self.pixels.is_empty() || cells.width == 0 || cells.height == 0 {
+            return;
+        }
+
+        if ctx.draw_image(&self.pixels, self.image_size, LocalPos::ZERO, cells) {
+            // Reserve the cells so nothing else is drawn underneath the image
+            for y in 0..cells.height as u16 {
+                for x in 0..cells.width as u16 {
+                    ctx.place_glyph(' ', LocalPos::new(x, y));
+                }
+            }
+            return;
+        }
+
+        let colors = half_blocks(&self.pixels, self.image_size, cells);
+        for (i, (top, bottom)) in colors.into_iter().enumerate() {
+            let pos = LocalPos::new((i % cells.width) as u16, (i / cells.width) as u16);
+            let mut style = Style::new();
+            style.set_fg(top);
+            style.set_bg(bottom);
+            ctx.set_attributes(&style, pos);
+            ctx.place_glyph(HALF_BLOCK, pos);
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn half_block_fallback() {
+        let expected = "
+            ╔════╗
+            ║▀▀  ║
+            ║▀▀  ║
+            ║    ║
+            ╚════╝
+        ";
+
+        TestRunner::new("image [width: 2]", (4, 3))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("image").first(|el, _| {
+                    let pixels = [255, 0, 0, 255].repeat(8);
+                    assert!(el.to::<Image>().set_pixels(pixels, Size::new(2, 4)));
+                });
+            })
+            .render_assert(expected);
+    }
+
+    #[test]
+    fn footprint_uses_cell_size() {
+        let mut runner = TestRunner::new("image", (4, 4));
+        runner.set_cell_size(Size::new(4, 8));
+        runner
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("image").first(|el, _| {
+                    let pixels = [255, 0, 0, 255].repeat(8 * 8);
+                    assert!(el.to::<Image>().set_pixels(pixels, Size::new(8, 8)));
+                });
+            })
+            .render_assert(
+                "
+                ╔════╗
+                ║▀▀  ║
+                ║    ║
+                ║    ║
+                ║    ║
+                ╚════╝
+                ",
+            );
+    }
+
+    #[test]
+    fn pixel_count_must_match_size() {
+        let mut image = Image::default();
+        assert!(!image.set_pixels(vec![0; 3], Size::new(1, 1)));
+    }
+}
@@ -1,4 +1,5 @@
 use anathema::CommonVal;
+use anathema_geometry::{Pos, Size};
 
 pub const ALIGNMENT: &str = "alignment";
 
@@ -38,3 +39,25 @@ impl TryFrom<CommonVal<'_>> for Alignment {
         }
     }
 }
+
+impl Alignment {
+    /// The offset of something of the `inner` size, aligned inside the `outer` size
+    pub fn offset(self, outer: Size, inner: Size) -> Pos {
+        let width = outer.width as i32;
+        let height = outer.height as i32;
+        let child_width = inner.width as i32;
+        let child_height = inner.height as i32;
+
+        match self {
+            Alignment::TopLeft => Pos::ZERO,
+            Alignment::Top => Pos::new(width / 2 - child_width / 2, 0),
+            Alignment::TopRight => Pos::new(width - child_width, 0),
+            Alignment::Right => Pos::new(width - child_width, height / 2 - child_height / 2),
+            Alignment::BottomRight => Pos::new(width - child_width, height - child_height),
+            Alignment::Bottom => Pos::new(width / 2 - child_width / 2, height - child_height),
+            Alignment::BottomLeft => Pos::new(0, height - child_height),
+            Alignment::Left => Pos::new(0, height / 2 - child_height / 2),
+            Alignment::Centre => Pos::new(width / 2 - child_width / 2, height / 2 - child_height / 2),
+        }
+    }
+}
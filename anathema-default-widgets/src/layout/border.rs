@@ -71,10 +71,12 @@ impl BorderLayout {
             //     border [min-width: 10]
             //         ...
 
-            child_constraints.sub_max_width((border_size.left + border_size.right) as usize);
-            child_constraints.sub_max_height((border_size.top + border_size.bottom) as usize);
+            // Only the present edges (and corners) take up space
+            let border = border_size.as_size();
+            child_constraints.sub_max_width(border.width);
+            child_constraints.sub_max_height(border.height);
             let mut child_size = child.layout(children, child_constraints, ctx);
-            child_size += border_size.as_size();
+            child_size += border;
             size.width = child_size.width.max(size.width);
             size.height = child_size.height.max(size.height);
 
@@ -2,30 +2,46 @@ use std::ops::ControlFlow;
 
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx};
-use anathema_widgets::LayoutChildren;
+use anathema_widgets::{AttributeStorage, Element, LayoutChildren};
 
 use super::Axis;
 
 const DEFAULT_FACTOR: usize = 1;
 
+/// Any child of a stack with a `flex` attribute shares the remaining space
+/// with the other flexible children, proportional to the value of the attribute.
+pub const FLEX: &str = "flex";
+
+/// Returns true if the element is given a share of the remaining space,
+/// either by being an `expand` or by having a `flex` attribute.
+pub(crate) fn is_flexible(node: &Element<'_>, attributes: &AttributeStorage<'_>) -> bool {
+    node.ident == "expand" || attributes.get(node.id()).contains(FLEX)
+}
+
+fn factor(node: &Element<'_>, attributes: &AttributeStorage<'_>) -> usize {
+    let attributes = attributes.get(node.id());
+    match node.ident {
+        "expand" => attributes.get("factor").or(attributes.get(FLEX)),
+        _ => attributes.get(FLEX),
+    }
+    .unwrap_or(DEFAULT_FACTOR)
+}
+
 /// Distributes the total size over a list of weights
 ///
 /// It uses the [Huntington-Hill method](https://en.wikipedia.org/wiki/Huntington%E2%80%93Hill_method)
 ///
-/// Panics when called with more weights than the total number of available size.
-/// Allocates a minimum of one to each weight.
+/// Allocates a minimum of one to each weight, in order, as long as there is size left.
 fn distribute_size(weights: &[usize], mut total: usize) -> Vec<usize> {
-    assert!(total > weights.len());
-
+    // A weight without any allocation has an infinite priority,
+    // so every weight is given one before any weight is given two.
     let mut indexed = weights
         .iter()
         .copied()
         .enumerate()
-        .map(|(i, w)| (i, w, 1usize))
+        .map(|(i, w)| (i, w, 0usize))
         .collect::<Vec<_>>();
 
-    total -= weights.len();
-
     fn pop(n: &mut usize) -> bool {
         if let Some(nn) = n.checked_sub(1) {
             *n = nn;
@@ -53,10 +69,8 @@ pub fn layout_all_expansions<'bp>(
     let mut factors = vec![];
 
     nodes.for_each(|node, _children| {
-        if node.ident == "expand" {
-            let attributes = ctx.attribs.get(node.id());
-            let factor = attributes.get("factor").unwrap_or(DEFAULT_FACTOR);
-            factors.push(factor);
+        if is_flexible(node, ctx.attribs) {
+            factors.push(factor(node, ctx.attribs));
         }
 
         ControlFlow::Continue(())
@@ -76,7 +90,7 @@ pub fn layout_all_expansions<'bp>(
 
     let mut index = 0;
     nodes.for_each(|node, children| {
-        if node.ident != "expand" {
+        if !is_flexible(node, ctx.attribs) {
             return ControlFlow::Continue(());
         }
 
@@ -92,6 +92,12 @@ impl Many {
                 return ControlFlow::Continue(());
             }
 
+            // Flexible children are given what is left after the rest of the children,
+            // unless there is no limit to what is left
+            if !self.unconstrained && expand::is_flexible(node, ctx.attribs) {
+                return ControlFlow::Continue(());
+            }
+
             let widget_constraints = {
                 let mut constraints = self.used_size.to_constraints();
                 if self.unconstrained {
@@ -113,7 +119,7 @@ impl Many {
             }
         });
 
-        // Apply spacer and expand if the layout is constrained and we have remaining space
+        // Apply spacer, expand and flex if the layout is constrained and we have remaining space
         if !self.unconstrained && !self.used_size.no_space_left() {
             let constraints = self.used_size.to_constraints();
             let expanded_size = expand::layout_all_expansions(&mut children, constraints, self.axis, ctx);
@@ -59,8 +59,8 @@ impl TryFrom<CommonVal<'_>> for Direction {
 
     fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
         match value.to_common_str().as_ref() {
-            "fwd" | "forward" | "forwards" => Ok(Self::Forward),
-            "back" | "backward" | "backwards" => Ok(Self::Backward),
+            "fwd" | "forward" | "forwards" | "ltr" => Ok(Self::Forward),
+            "back" | "backward" | "backwards" | "rtl" => Ok(Self::Backward),
             _ => Err(()),
         }
     }
@@ -1,19 +1,47 @@
 #[allow(unused_extern_crates)]
 extern crate anathema_state as anathema;
-use anathema_widgets::Factory;
+use anathema_widgets::{Factory, Widget};
 
 mod alignment;
+mod aspect_ratio;
 mod border;
+mod calendar;
 mod canvas;
+mod cast_player;
+pub mod components;
 mod container;
+mod custom_layout;
+mod dock;
 mod expand;
+mod gauge;
+mod image;
 mod layout;
+mod list;
+mod masonry;
+mod menu;
+mod number;
 mod overflow;
+mod overlay;
 mod padding;
+mod panel;
+mod portal;
 mod position;
+mod radial;
+mod region;
+mod relative_time;
+mod shadow;
+mod slider;
 mod spacer;
+mod splitpane;
 mod stacks;
+mod style;
 mod text;
+mod tiling;
+mod timeline;
+mod timeseries;
+mod toc;
+mod tooltip;
+mod wrap;
 
 #[cfg(test)]
 mod testing;
@@ -30,30 +58,201 @@ pub(crate) const BOTTOM: &str = "bottom";
 pub(crate) const LEFT: &str = "left";
 
 pub use alignment::Align;
+pub use aspect_ratio::AspectRatio;
 pub use border::Border;
-pub use canvas::Canvas;
+pub use calendar::{Calendar, Date};
+pub use canvas::{Canvas, CanvasPainter, Draw};
+pub use cast_player::{Cast, CastError, CastEvent, CastPlayer};
+pub use custom_layout::CustomLayout;
+pub use dock::Dock;
 pub use expand::Expand;
+pub use gauge::Gauge;
+pub use image::Image;
+pub use layout::Axis;
+pub use list::List;
+pub use masonry::Masonry;
+pub use menu::{ContextMenu, Menu, MenuBar};
+pub use number::{Number, Sign};
 pub use overflow::Overflow;
+pub use overlay::Overlay;
 pub use padding::Padding;
+pub use panel::{Panel, PanelEvent, PanelGeometry};
+pub use portal::Portal;
 pub use position::Position;
-pub use stacks::{Column, HStack, Row, VStack};
+pub use radial::Radial;
+pub use region::Region;
+pub use relative_time::RelativeTime;
+pub use shadow::Shadow;
+pub use slider::Slider;
+pub use splitpane::SplitPane;
+pub use stacks::{Column, HStack, Row, StackOverflow, VStack};
 pub use text::Text;
+pub use tiling::Tiling;
+pub use timeline::{Timeline, TimelineSpan};
+pub use timeseries::{Sample, TimeSeries};
+pub use toc::Toc;
+pub use tooltip::{Tooltip, TooltipSide};
+pub use wrap::Wrap;
 
 pub fn register_default_widgets(factory: &mut Factory) {
     factory.register_default::<alignment::Align>("align");
+    factory.register_default::<aspect_ratio::AspectRatio>("aspectratio");
     factory.register_default::<expand::Expand>("expand");
+    factory.register_default::<calendar::Calendar>("calendar");
     factory.register_default::<canvas::Canvas>("canvas");
+    factory.register_default::<cast_player::CastPlayer>("castplayer");
     factory.register_default::<container::Container>("container");
+    factory.register_default::<menu::ContextMenu>("contextmenu");
+    factory.register_default::<dock::Dock>("dock");
+    factory.register_default::<gauge::Gauge>("gauge");
+    factory.register_default::<image::Image>("image");
+    factory.register_default::<list::List>("list");
+    factory.register_default::<masonry::Masonry>("masonry");
+    factory.register_default::<menu::Menu>("menu");
+    factory.register_default::<menu::MenuBar>("menubar");
+    factory.register_default::<number::Number>("number");
     factory.register_default::<padding::Padding>("padding");
+    factory.register_default::<panel::Panel>("panel");
+    factory.register_default::<portal::Portal>("portal");
     factory.register_default::<position::Position>("position");
+    factory.register_default::<radial::Radial>("radial");
+    factory.register_default::<region::Region>("region");
+    factory.register_default::<relative_time::RelativeTime>("relativetime");
+    factory.register_default::<shadow::Shadow>("shadow");
     factory.register_default::<stacks::Column>("column");
+    factory.register_default::<slider::Slider>("slider");
     factory.register_default::<spacer::Spacer>("spacer");
+    factory.register_default::<splitpane::SplitPane>("splitpane");
     factory.register_default::<stacks::HStack>("hstack");
     factory.register_default::<stacks::Row>("row");
     factory.register_default::<stacks::VStack>("vstack");
     factory.register_default::<stacks::ZStack>("zstack");
     factory.register_default::<text::Span>("span");
     factory.register_default::<text::Text>("text");
+    factory.register_default::<tiling::Tiling>("tiling");
+    factory.register_default::<toc::Toc>("toc");
+    factory.register_default::<tooltip::Tooltip>("tooltip");
+    factory.register_default::<timeline::Timeline>("timeline");
+    factory.register_default::<timeseries::TimeSeries>("timeseries");
     factory.register_default::<overflow::Overflow>("overflow");
+    factory.register_default::<overlay::Overlay>("overlay");
+    factory.register_default::<wrap::Wrap>("wrap");
     factory.register_widget("border", border::make);
+    if let Some(metadata) = border::Border::metadata() {
+        factory.set_metadata("border", metadata);
+    }
+    factory.register_layout("masonry", masonry::Masonry::default);
+    let layouts = factory.layouts();
+    factory.register_widget("layout", move |_| {
+        Box::new(custom_layout::CustomLayout::new(layouts.clone()))
+    });
+    if let Some(metadata) = custom_layout::CustomLayout::metadata() {
+        factory.set_metadata("layout", metadata);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widgets::{AttributeType, Children};
+
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn metadata() {
+        let mut factory = Factory::new();
+        register_default_widgets(&mut factory);
+
+        let missing = factory
+            .widgets()
+            .into_iter()
+            .filter(|ident| factory.metadata(ident).is_none())
+            .collect::<Vec<_>>();
+        assert!(missing.is_empty(), "no metadata: {missing:?}");
+
+        let border = factory.metadata("border").unwrap();
+        assert_eq!(border.children, Children::One);
+        assert_eq!(border.get("title").unwrap().ty, AttributeType::String);
+        assert_eq!(factory.metadata("spacer").unwrap().children, Children::None);
+    }
+
+    #[test]
+    fn align_left() {
+        let tpl = "
+            vstack
+                hstack
+                    text 'Email '
+                    text [id: 'field'] 'a@b'
+                // The row needs room for the field once it's moved
+                hstack [width: 10]
+                    text 'Name '
+                    text [align_left: 'field'] 'ab'
+        ";
+
+        let expected = "
+            ╔══════════╗
+            ║Email a@b ║
+            ║Name  ab  ║
+            ╚══════════╝
+        ";
+
+        TestRunner::new(tpl, (10, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn match_width() {
+        let tpl = "
+            vstack
+                border [id: 'wide']
+                    text 'abcd'
+                border [match_width: 'wide']
+                    text 'a'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌────┐  ║
+            ║│abcd│  ║
+            ║└────┘  ║
+            ║┌────┐  ║
+            ║│a   │  ║
+            ║└────┘  ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 6)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn export_text() {
+        let tpl = "
+            border [id: 'report']
+                vstack
+                    text 'Report'
+                    text 'one two three'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌──────┐║
+            ║│Report│║
+            ║└──────┘║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 3))
+            .instance()
+            .render_assert(expected)
+            .with_widget(|mut elements| {
+                let mut id = None;
+                elements.by_attribute("id", "report").first(|el, _| id = Some(el.id()));
+
+                let text = elements.export_text(id.unwrap(), 20).unwrap();
+                assert_eq!(
+                    text,
+                    "┌─────────────┐\n│Report       │\n│one two three│\n└─────────────┘\n"
+                );
+            })
+            .render_assert(expected);
+    }
 }
@@ -0,0 +1,267 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{LocalPos, Pos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{CellAttributes, PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::style::{Inverse, Prefixed};
+
+const SELECTED: &str = "selected";
+
+/// A vertical list of rows where one row is selected.
+///
+/// Every child is a row. The row at the `selected` index is highlighted
+/// and always scrolled into view.
+///
+/// The selected row is painted with the `selected_` attributes
+/// (`selected_foreground`, `selected_background`, `selected_bold` etc.),
+/// or inverted if there are none.
+///
+/// ```text
+/// list [selected: selected, selected_background: 'blue']
+///     for item in items
+///         text item
+/// ```
+///
+/// See [`crate::components::SelectList`] for a component with keyboard navigation.
+#[derive(Debug, Default)]
+pub struct List {
+    selected: Option<usize>,
+    // Offset of the first visible row
+    offset: usize,
+    // Top and height of each row
+    rows: Vec<(usize, usize)>,
+    size: Size,
+}
+
+impl List {
+    /// Index of the selected row
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if the list has no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The offset of the first visible line
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The index of the row at a screen position, e.g. for selecting a row with the mouse.
+    pub fn row_at(&self, pos: Pos, list_pos: Pos) -> Option<usize> {
+        let y = pos.y - list_pos.y;
+        if y < 0 || y as usize >= self.size.height {
+            return None;
+        }
+        let y = y as usize + self.offset;
+        self.rows
+            .iter()
+            .position(|&(top, height)| (top..top + height).contains(&y))
+    }
+
+    // Scroll the least amount needed to show the selected row
+    fn scroll_into_view(&mut self) {
+        let Some(&(top, height)) = self.selected.and_then(|selected| self.rows.get(selected)) else {
+            return;
+        };
+        let visible = self.size.height;
+
+        if top < self.offset {
+            self.offset = top;
+        } else if top + height > self.offset + visible {
+            self.offset = (top + height).saturating_sub(visible).min(top);
+        }
+    }
+}
+
+impl Widget for List {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many).attribute(SELECTED, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        self.selected = ctx.attribs.get(id).get_usize(SELECTED);
+
+        let mut child_constraints = Constraints::new(constraints.max_width(), None);
+        child_constraints.min_width = constraints.min_width;
+
+        self.rows.clear();
+        let mut width = constraints.min_width;
+        let mut height = 0;
+        children.for_each(|child, children| {
+            let size = child.layout(children, child_constraints, ctx);
+            self.rows.push((height, size.height));
+            width = width.max(size.width);
+            height += size.height;
+            ControlFlow::Continue(())
+        });
+
+        self.size = Size::new(
+            width.min(constraints.max_width()),
+            height.max(constraints.min_height).min(constraints.max_height()),
+        );
+
+        // Keep the offset in range if rows were removed
+        self.offset = self.offset.min(height.saturating_sub(self.size.height));
+        self.scroll_into_view();
+
+        self.size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let top = self.rows.get(index).map(|(top, _)| *top).unwrap_or(0);
+            let pos = Pos::new(ctx.pos.x, ctx.pos.y + top as i32 - self.offset as i32);
+            child.position(children, pos, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        // Paint the style of the selected row first, so the row is painted on top
+        if let Some(&(top, height)) = self.selected.and_then(|selected| self.rows.get(selected)) {
+            let attributes = attribute_storage.get(id);
+            let selected = Prefixed::new(SELECTED, attributes);
+            let style: &dyn CellAttributes = match selected.is_empty() {
+                true => &Inverse,
+                false => &selected,
+            };
+
+            let from = top.saturating_sub(self.offset);
+            let to = (top + height).saturating_sub(self.offset).min(self.size.height);
+            for y in from..to {
+                for x in 0..self.size.width {
+                    ctx.set_attributes(style, LocalPos::new(x as u16, y as u16));
+                }
+            }
+        }
+
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let visible = self
+                .rows
+                .get(index)
+                .map(|&(top, height)| top + height > self.offset && top < self.offset + self.size.height)
+                .unwrap_or(false);
+
+            if visible {
+                let ctx = ctx.to_unsized();
+                child.paint(children, ctx, attribute_storage);
+            }
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn scroll_selection_into_view() {
+        let tpl = "
+            list [selected: value]
+                for i in [0, 1, 2, 3, 4]
+                    text i
+        ";
+
+        TestRunner::new(tpl, (3, 2))
+            .instance()
+            .render_assert(
+                "
+                ╔═══╗
+                ║0  ║
+                ║1  ║
+                ╚═══╝
+            ",
+            )
+            .with_state(|state| *state.value.to_mut() = 3)
+            .render_assert(
+                "
+                ╔═══╗
+                ║2  ║
+                ║3  ║
+                ╚═══╝
+            ",
+            )
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(
+                "
+                ╔═══╗
+                ║1  ║
+                ║2  ║
+                ╚═══╝
+            ",
+            );
+    }
+
+    #[test]
+    fn user_data_of_row_under_mouse() {
+        let tpl = "
+            list
+                for i in [0, 1, 2]
+                    text i
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║0  ║
+            ║1  ║
+            ║2  ║
+            ╚═══╝
+        ";
+
+        TestRunner::new(tpl, (3, 3))
+            .instance()
+            .render_assert(expected)
+            .with_widget(|mut elements| {
+                let mut index = 0;
+                elements.by_tag("text").each(|el, _| {
+                    el.user_data_mut().insert(index * 10);
+                    index += 1;
+                });
+
+                let mut data = vec![];
+                for id in elements.widget_at((1, 2)) {
+                    elements
+                        .by_id(id)
+                        .first(|el, _| data.extend(el.user_data().get::<i32>().copied()));
+                }
+                assert_eq!(data, [10]);
+            });
+    }
+}
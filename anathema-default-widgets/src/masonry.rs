@@ -0,0 +1,177 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{Pos, Size};
+use anathema_widgets::layout::{Constraints, Layout, LayoutCtx, PositionCtx};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+const COLUMNS: &str = "columns";
+const SPACING: &str = "spacing";
+
+const DEFAULT_COLUMNS: usize = 2;
+
+/// Packs the children into columns of the same width,
+/// placing each child at the bottom of the shortest column.
+///
+/// `spacing` is the number of cells between the columns, and between the children of a column.
+/// This is also available as the `masonry` layout of the `layout` widget.
+///
+/// ```text
+/// masonry [columns: 3, spacing: 1]
+///     for card in cards
+///         border
+///             text card.body
+/// ```
+#[derive(Debug, Default)]
+pub struct Masonry {
+    // Offset of each child, relative to the widget
+    offsets: Vec<Pos>,
+}
+
+impl Widget for Masonry {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(COLUMNS, AttributeType::Int, Some("2"))
+            .attribute(SPACING, AttributeType::Int, Some("0"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let columns = attributes.get_usize(COLUMNS).unwrap_or(DEFAULT_COLUMNS).max(1);
+        let spacing = attributes.get_usize(SPACING).unwrap_or(0);
+
+        let max_width = constraints.max_width();
+        let column_width = max_width.saturating_sub(spacing * (columns - 1)) / columns;
+        let child_constraints = Constraints::new(column_width, None);
+
+        // The height of each column, including the spacing after the last child
+        let mut heights = vec![0; columns];
+        let mut used = 0;
+        self.offsets.clear();
+
+        children.for_each(|child, children| {
+            let size = child.layout(children, child_constraints, ctx);
+
+            // The first of the shortest columns
+            let (column, y) = heights
+                .iter()
+                .copied()
+                .enumerate()
+                .min_by_key(|&(_, height)| height)
+                .expect("there is at least one column");
+
+            self.offsets
+                .push(Pos::new((column * (column_width + spacing)) as i32, y as i32));
+            heights[column] = y + size.height + spacing;
+            used = used.max(column + 1);
+            ControlFlow::Continue(())
+        });
+
+        let width = (used * column_width + used.saturating_sub(1) * spacing).min(max_width);
+        let height = heights
+            .iter()
+            .filter(|&&height| height > 0)
+            .map(|height| height - spacing)
+            .max()
+            .unwrap_or(0);
+
+        Size::new(
+            width.max(constraints.min_width),
+            height.max(constraints.min_height).min(constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let offset = self.offsets.get(index).copied().unwrap_or(Pos::ZERO);
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+impl Layout for Masonry {
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        Widget::layout(self, children, constraints, id, ctx)
+    }
+
+    fn position<'bp>(
+        &mut self,
+        children: PositionChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        Widget::position(self, children, id, attribute_storage, ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn shortest_column_first() {
+        let tpl = "
+            masonry [columns: 2, spacing: 1]
+                text 'a a a'
+                text 'b'
+                text 'c'
+                text 'd'
+        ";
+
+        let expected = "
+            ╔═══════╗
+            ║a a b  ║
+            ║a      ║
+            ║    c  ║
+            ║d      ║
+            ║       ║
+            ╚═══════╝
+        ";
+
+        TestRunner::new(tpl, (7, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn masonry_layout() {
+        let tpl = "
+            layout [layout: 'masonry', columns: 3]
+                text 'a'
+                text 'b'
+                text 'c'
+                text 'd'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║a b c ║
+            ║d     ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
+    }
+}
@@ -0,0 +1,597 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{LocalPos, Pos, Rect, Region, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, LayoutFilter, PositionCtx};
+use anathema_widgets::paint::{CellAttributes, PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::style::{Inverse, Prefixed};
+
+const SELECTED: &str = "selected";
+const ITEM: &str = "item";
+const OPEN: &str = "open";
+const LABEL: &str = "label";
+const ID: &str = "id";
+const X: &str = "x";
+const Y: &str = "y";
+
+fn selected_style<'a>(selected: &'a Prefixed<'_, '_>) -> &'a dyn CellAttributes {
+    match selected.is_empty() {
+        true => &Inverse,
+        false => selected,
+    }
+}
+
+/// A horizontal bar with the labels of the [`Menu`]s it contains.
+///
+/// The menu at the `selected` index is highlighted, and if `open` is true
+/// the dropdown of the menu is shown below the label, with the row at the
+/// `item` index highlighted.
+///
+/// The selected label and item are painted with the `selected_` attributes,
+/// or inverted if there are none.
+///
+/// ```text
+/// menubar [selected: selected, item: item, open: open]
+///     menu [label: 'File']
+///         text [id: 'open'] 'Open'
+///         text [id: 'quit'] 'Quit'
+///     menu [label: 'Edit']
+///         text [id: 'copy'] 'Copy'
+/// ```
+///
+/// See [`crate::components::MenuBarInput`] for a component with keyboard navigation.
+#[derive(Debug, Default)]
+pub struct MenuBar {
+    labels: Vec<String>,
+    // Start and width of each label
+    regions: Vec<(usize, usize)>,
+    selected: Option<usize>,
+    size: Size,
+}
+
+impl MenuBar {
+    /// Index of the selected menu
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Number of menus
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns true if the bar has no menus
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The index of the menu with the label at a screen position, e.g. for opening a menu with the mouse.
+    pub fn menu_at(&self, pos: Pos, bar_pos: Pos) -> Option<usize> {
+        if pos.y != bar_pos.y || pos.x < bar_pos.x {
+            return None;
+        }
+        let x = (pos.x - bar_pos.x) as usize;
+        self.regions
+            .iter()
+            .position(|&(start, width)| (start..start + width).contains(&x))
+    }
+}
+
+impl Widget for MenuBar {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(SELECTED, AttributeType::Int, None)
+            .attribute(ITEM, AttributeType::Int, None)
+            .attribute(OPEN, AttributeType::Bool, Some("false"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.selected = attributes.get_usize(SELECTED);
+        let item = attributes.get_usize(ITEM);
+        let open = attributes.get_bool(OPEN);
+
+        self.labels.clear();
+        self.regions.clear();
+
+        // The menus are floating widgets, which are not part of the children
+        // during layout, so the menus are found with a filter that includes them.
+        let filter = LayoutFilter::new(false, ctx.attribs);
+        let mut x = 0;
+        children.with_filter(&filter).for_each(|child, _| {
+            let label = ctx.attribs.get(child.id()).get_ref::<&str>(LABEL).unwrap_or("");
+            let Some(menu) = child.try_to::<Menu>() else { return ControlFlow::Continue(()) };
+
+            let index = self.labels.len();
+            let width = label.width() + 2;
+            menu.dropdown.open = open && self.selected == Some(index);
+            menu.dropdown.selected = item;
+            menu.offset = x;
+
+            self.labels.push(label.into());
+            self.regions.push((x, width));
+            x += width;
+            ControlFlow::Continue(())
+        });
+
+        self.size = Size::new(x.max(constraints.min_width).min(constraints.max_width()), 1);
+        self.size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The menus are positioned by themselves, as they float
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let attributes = attribute_storage.get(id);
+        for x in 0..self.size.width as u16 {
+            ctx.set_attributes(attributes, LocalPos::new(x, 0));
+        }
+
+        let selected = Prefixed::new(SELECTED, attributes);
+        for (index, (label, &(start, width))) in self.labels.iter().zip(&self.regions).enumerate() {
+            if self.selected == Some(index) {
+                let style = selected_style(&selected);
+                for x in start..start + width {
+                    ctx.set_attributes(style, LocalPos::new(x as u16, 0));
+                }
+            }
+            ctx.place_glyphs(label, LocalPos::new(start as u16 + 1, 0));
+        }
+    }
+}
+
+/// The rows of an open menu, shared by [`Menu`] and [`ContextMenu`]
+#[derive(Debug, Default)]
+struct Dropdown {
+    open: bool,
+    selected: Option<usize>,
+    ids: Vec<Option<String>>,
+    // Top and height of each row
+    rows: Vec<(usize, usize)>,
+    pos: Pos,
+    size: Size,
+}
+
+impl Dropdown {
+    fn item_id(&self, index: usize) -> Option<&str> {
+        self.ids.get(index)?.as_deref()
+    }
+
+    fn item_at(&self, pos: Pos) -> Option<usize> {
+        if !Region::from((self.pos, self.size)).contains(pos) {
+            return None;
+        }
+        let y = (pos.y - self.pos.y) as usize;
+        self.rows
+            .iter()
+            .position(|&(top, height)| (top..top + height).contains(&y))
+    }
+
+    fn painted_region(&self) -> Rect {
+        match self.open {
+            true => Rect::from((self.pos, self.size)),
+            false => Rect::ZERO,
+        }
+    }
+
+    fn layout<'bp>(&mut self, mut children: LayoutChildren<'_, '_, 'bp>, ctx: &mut LayoutCtx<'_, 'bp>) {
+        self.ids.clear();
+        self.rows.clear();
+        self.size = Size::ZERO;
+
+        if !self.open {
+            return;
+        }
+
+        let screen = ctx.viewport.size();
+        let constraints = Constraints::new(screen.width, None);
+        let mut width = 0;
+        let mut height = 0;
+        children.for_each(|child, children| {
+            let id = ctx.attribs.get(child.id()).get_ref::<&str>(ID).map(Into::into);
+            let size = child.layout(children, constraints, ctx);
+            self.ids.push(id);
+            self.rows.push((height, size.height));
+            width = width.max(size.width);
+            height += size.height;
+            ControlFlow::Continue(())
+        });
+        self.size = Size::new(width, height.min(screen.height));
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let top = self.rows.get(index).map(|(top, _)| *top).unwrap_or(0);
+            let pos = Pos::new(self.pos.x, self.pos.y + top as i32);
+            child.position(children, pos, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        if !self.open {
+            return;
+        }
+
+        ctx.clip = None;
+        ctx.update(self.size, self.pos);
+
+        // Clear the area, as the menu covers what's underneath
+        let attributes = attribute_storage.get(id);
+        for y in 0..self.size.height as u16 {
+            for x in 0..self.size.width as u16 {
+                ctx.place_glyph(' ', LocalPos::new(x, y));
+                ctx.set_attributes(attributes, LocalPos::new(x, y));
+            }
+        }
+
+        // Paint the style of the selected row first, so the row is painted on top
+        if let Some(&(top, height)) = self.selected.and_then(|selected| self.rows.get(selected)) {
+            let selected = Prefixed::new(SELECTED, attributes);
+            let style = selected_style(&selected);
+            for y in top..(top + height).min(self.size.height) {
+                for x in 0..self.size.width {
+                    ctx.set_attributes(style, LocalPos::new(x as u16, y as u16));
+                }
+            }
+        }
+
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+/// A dropdown menu in a [`MenuBar`].
+///
+/// Every child is a row of the menu, and the `id` attribute of the row
+/// identifies the item when it's activated.
+///
+/// The menu is a floating widget painted on top of the widgets underneath it,
+/// and is only shown while the menu bar has it open.
+/// The background of the menu is painted with the attributes of the menu.
+#[derive(Debug, Default)]
+pub struct Menu {
+    dropdown: Dropdown,
+    // Offset of the label in the menu bar
+    offset: usize,
+}
+
+impl Menu {
+    /// Returns true if the menu is open
+    pub fn is_open(&self) -> bool {
+        self.dropdown.open
+    }
+
+    /// Number of items, once the menu has been laid out while open
+    pub fn len(&self) -> usize {
+        self.dropdown.rows.len()
+    }
+
+    /// Returns true if the menu has no items, or isn't open
+    pub fn is_empty(&self) -> bool {
+        self.dropdown.rows.is_empty()
+    }
+
+    /// The `id` of the item at the index
+    pub fn item_id(&self, index: usize) -> Option<&str> {
+        self.dropdown.item_id(index)
+    }
+
+    /// The index of the item at a screen position
+    pub fn item_at(&self, pos: Pos) -> Option<usize> {
+        self.dropdown.item_at(pos)
+    }
+}
+
+impl Widget for Menu {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many))
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        self.dropdown.painted_region()
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        self.dropdown.layout(children, ctx);
+
+        // The menu paints its own area, as it's not placed
+        // where the container is
+        Size::ZERO
+    }
+
+    fn position<'bp>(
+        &mut self,
+        children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        // Below the label, but kept on the screen
+        let max_x = ctx.viewport.size().width.saturating_sub(self.dropdown.size.width) as i32;
+        self.dropdown.pos = Pos::new((ctx.pos.x + self.offset as i32).min(max_x), ctx.pos.y + 1);
+        self.dropdown.position(children, attribute_storage, ctx);
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PaintCtx<'_, SizePos>,
+    ) {
+        self.dropdown.paint(children, id, attribute_storage, ctx);
+    }
+}
+
+/// A menu opened at a position on the screen, e.g. where the mouse was right-clicked.
+///
+/// The menu is shown below and to the right of (`x`, `y`) while `open` is true,
+/// and is flipped to the other side of the position if it would not fit on the screen.
+/// The row at the `item` index is highlighted.
+///
+/// Like a [`Menu`], every child is a row of the menu with an `id` attribute.
+///
+/// ```text
+/// contextmenu [open: menu == 'file', x: x, y: y, item: item]
+///     text [id: 'rename'] 'Rename'
+///     text [id: 'delete'] 'Delete'
+/// ```
+///
+/// See [`crate::components::ContextMenuInput`] for a component that opens the menu.
+#[derive(Debug, Default)]
+pub struct ContextMenu {
+    dropdown: Dropdown,
+    // The position the menu is opened at
+    origin: Pos,
+}
+
+impl ContextMenu {
+    /// Returns true if the menu is open
+    pub fn is_open(&self) -> bool {
+        self.dropdown.open
+    }
+
+    /// Number of items, once the menu has been laid out while open
+    pub fn len(&self) -> usize {
+        self.dropdown.rows.len()
+    }
+
+    /// Returns true if the menu has no items, or isn't open
+    pub fn is_empty(&self) -> bool {
+        self.dropdown.rows.is_empty()
+    }
+
+    /// The `id` of the item at the index
+    pub fn item_id(&self, index: usize) -> Option<&str> {
+        self.dropdown.item_id(index)
+    }
+
+    /// The index of the item at a screen position
+    pub fn item_at(&self, pos: Pos) -> Option<usize> {
+        self.dropdown.item_at(pos)
+    }
+}
+
+impl Widget for ContextMenu {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(OPEN, AttributeType::Bool, Some("false"))
+            .attribute(ITEM, AttributeType::Int, None)
+            .attribute(X, AttributeType::Int, Some("0"))
+            .attribute(Y, AttributeType::Int, Some("0"));
+        Some(metadata)
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        self.dropdown.painted_region()
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.dropdown.open = attributes.get_bool(OPEN);
+        self.dropdown.selected = attributes.get_usize(ITEM);
+        self.origin = Pos::new(
+            attributes.get_int(X).unwrap_or(0) as i32,
+            attributes.get_int(Y).unwrap_or(0) as i32,
+        );
+
+        self.dropdown.layout(children, ctx);
+        Size::ZERO
+    }
+
+    fn position<'bp>(
+        &mut self,
+        children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let screen = ctx.viewport.size();
+        let (width, height) = (self.dropdown.size.width as i32, self.dropdown.size.height as i32);
+
+        let mut pos = self.origin;
+        if pos.x + width > screen.width as i32 {
+            pos.x -= width;
+        }
+        if pos.y + height > screen.height as i32 {
+            pos.y -= height;
+        }
+        self.dropdown.pos = Pos::new(pos.x.max(0), pos.y.max(0));
+
+        self.dropdown.position(children, attribute_storage, ctx);
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PaintCtx<'_, SizePos>,
+    ) {
+        self.dropdown.paint(children, id, attribute_storage, ctx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    const TPL: &str = "
+        vstack
+            menubar [selected: 1, item: 0, open: value == 1]
+                menu [label: 'File']
+                    text [id: 'open'] 'Open'
+                menu [label: 'Edit']
+                    text [id: 'copy'] 'Copy'
+                    text [id: 'cut'] 'Cut'
+            text 'abcdefghijkl'
+            text 'abcdefghijkl'
+    ";
+
+    #[test]
+    fn closed_menu_bar() {
+        let expected = "
+            ╔════════════╗
+            ║ File  Edit ║
+            ║abcdefghijkl║
+            ║abcdefghijkl║
+            ╚════════════╝
+        ";
+
+        TestRunner::new(TPL, (12, 3)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn open_menu_is_painted_on_top() {
+        let expected = "
+            ╔════════════╗
+            ║ File  Edit ║
+            ║abcdefCopykl║
+            ║abcdefCut kl║
+            ╚════════════╝
+        ";
+
+        TestRunner::new(TPL, (12, 3))
+            .instance()
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(expected)
+            .with_widget(|mut elements| {
+                let mut open = vec![];
+                elements.by_tag("menu").each(|el, _| {
+                    let menu = el.to::<Menu>();
+                    if menu.is_open() {
+                        open.push((
+                            menu.len(),
+                            menu.item_id(1).map(String::from),
+                            menu.item_at(Pos::new(8, 3)),
+                        ));
+                    }
+                });
+                assert_eq!(open, [(2, Some("cut".into()), Some(1))]);
+
+                elements.by_tag("menubar").first(|el, _| {
+                    let pos = el.get_pos();
+                    assert_eq!(el.to::<MenuBar>().menu_at(Pos::new(7, 1), pos), Some(1));
+                });
+            });
+    }
+
+    #[test]
+    fn context_menu_is_flipped_to_fit() {
+        let tpl = "
+            vstack
+                text 'abcdefghijkl'
+                text 'abcdefghijkl'
+                text 'abcdefghijkl'
+                contextmenu [open: true, x: 2 + value * 10, y: 1 + value * 3]
+                    text [id: 'copy'] 'Copy'
+                    text [id: 'cut'] 'Cut'
+        ";
+
+        TestRunner::new(tpl, (12, 3))
+            .instance()
+            .render_assert(
+                "
+                ╔════════════╗
+                ║aCopyfghijkl║
+                ║aCut fghijkl║
+                ║abcdefghijkl║
+                ╚════════════╝
+            ",
+            )
+            // Opened at (12, 4) on the screen, which is too close to the edges
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(
+                "
+                ╔════════════╗
+                ║abcdefghijkl║
+                ║abcdefgCopyl║
+                ║abcdefgCut l║
+                ╚════════════╝
+            ",
+            );
+    }
+}
@@ -0,0 +1,260 @@
+use anathema::CommonVal;
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::style::Prefixed;
+use crate::WIDTH;
+
+const PRECISION: &str = "precision";
+const DECIMALS: &str = "decimals";
+const SIGN: &str = "sign";
+const THOUSANDS: &str = "thousands";
+const NEGATIVE: &str = "negative";
+
+/// How the sign of a number is shown
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum Sign {
+    /// `-` in front of negative numbers
+    #[default]
+    Negative,
+    /// `-` in front of negative numbers and `+` in front of positive numbers
+    Always,
+    /// No sign
+    Never,
+    /// Negative numbers in parentheses, e.g `(12.50)`, as in accounting
+    Parens,
+}
+
+impl TryFrom<CommonVal<'_>> for Sign {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        match value.to_common_str().as_ref() {
+            "negative" => Ok(Self::Negative),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            "parens" => Ok(Self::Parens),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A number, right aligned on the decimal point, so numbers line up in a column.
+///
+/// * `precision`: the number of decimals, defaults to the decimals of the value
+/// * `decimals`: the number of cells after the decimal point, defaults to the precision.
+///   Numbers with fewer decimals are padded, so the points line up
+/// * `sign`: `negative` (default), `always`, `never` or `parens`
+/// * `thousands`: separate the thousands with a comma
+/// * `width`: the width of the column, defaults to the width of the number
+///
+/// Negative numbers are painted with the `negative_` attributes, e.g `negative_foreground`.
+///
+/// ```text
+/// vstack
+///     for row in rows
+///         number [width: 12, precision: 2, thousands: true, sign: 'parens', negative_foreground: 'red'] row.amount
+/// ```
+/// ```text
+///    1,204.50
+///      (12.00)
+///        0.25
+/// ```
+#[derive(Debug, Default)]
+pub struct Number {
+    text: String,
+    // Cells before the text
+    offset: usize,
+    is_negative: bool,
+}
+
+impl Number {
+    // The text, whether it's negative, and the width of the fraction (including the decimal point)
+    fn format(
+        number: anathema::Number,
+        precision: Option<usize>,
+        sign: Sign,
+        thousands: bool,
+    ) -> (String, bool, usize) {
+        let formatted = match precision {
+            Some(precision) => format!("{:.precision$}", number.as_float().abs()),
+            None if number.is_float() => number.as_float().abs().to_string(),
+            None => number.as_int().unsigned_abs().to_string(),
+        };
+
+        // Numbers that are rounded to zero are not negative
+        let is_negative = number.as_float() < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9'));
+
+        let (int, fraction) = match formatted.split_once('.') {
+            Some((int, fraction)) => (int, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut text = String::new();
+        match (sign, is_negative) {
+            (Sign::Negative | Sign::Always, true) => text.push('-'),
+            (Sign::Parens, true) => text.push('('),
+            (Sign::Always, false) if number.as_float() != 0.0 => text.push('+'),
+            _ => {}
+        }
+
+        for (i, c) in int.chars().enumerate() {
+            if thousands && i > 0 && (int.len() - i) % 3 == 0 {
+                text.push(',');
+            }
+            text.push(c);
+        }
+
+        let fraction_width = fraction.map_or(0, |fraction| fraction.len() + 1);
+        if let Some(fraction) = fraction {
+            text.push('.');
+            text.push_str(fraction);
+        }
+
+        // Positive numbers leave room for the closing parenthesis, to line up with negative numbers
+        match (sign, is_negative) {
+            (Sign::Parens, true) => text.push(')'),
+            (Sign::Parens, false) => text.push(' '),
+            _ => {}
+        }
+
+        (text, is_negative, fraction_width)
+    }
+}
+
+impl Widget for Number {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(PRECISION, AttributeType::Int, None)
+            .attribute(DECIMALS, AttributeType::Int, None)
+            .attribute(SIGN, AttributeType::String, Some("\"negative\""))
+            .attribute(THOUSANDS, AttributeType::Bool, Some("false"))
+            .attribute(WIDTH, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let precision = attributes.get_usize(PRECISION);
+        let decimals = attributes.get_usize(DECIMALS).or(precision).unwrap_or(0);
+        let sign = attributes.get(SIGN).unwrap_or_default();
+
+        let number = attributes
+            .value()
+            .and_then(|value| value.load_common_val())
+            .and_then(|value| value.load_number());
+
+        let fraction_width;
+        (self.text, self.is_negative, fraction_width) = match number {
+            Some(number) => Self::format(number, precision, sign, attributes.get_bool(THOUSANDS)),
+            None => (String::new(), false, 0),
+        };
+
+        // Pad the fraction to the number of decimals
+        let reserved = match decimals {
+            0 => 0,
+            decimals => decimals + 1,
+        };
+        let padding = reserved.saturating_sub(fraction_width);
+        let content = self.text.len() + padding;
+
+        let width = attributes
+            .get_usize(WIDTH)
+            .unwrap_or(content)
+            .clamp(constraints.min_width, constraints.max_width());
+        self.offset = width.saturating_sub(content);
+
+        Size::new(width, 1.clamp(constraints.min_height, constraints.max_height()))
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The number has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let attributes = attribute_storage.get(id);
+        let negative = Prefixed::new(NEGATIVE, attributes);
+        let style_negative = self.is_negative && !negative.is_empty();
+
+        let mut pos = LocalPos::new(self.offset as u16, 0);
+        for c in self.text.chars() {
+            if style_negative {
+                ctx.set_attributes(&negative, pos);
+            }
+            match ctx.place_glyph(c, pos) {
+                Some(next) => pos = next,
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    fn format(number: impl Into<anathema::Number>, precision: Option<usize>, sign: Sign) -> String {
+        Number::format(number.into(), precision, sign, true).0
+    }
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(format(1234567, None, Sign::Negative), "1,234,567");
+        assert_eq!(format(-1234.5, Some(2), Sign::Negative), "-1,234.50");
+        assert_eq!(format(12.5, None, Sign::Always), "+12.5");
+        assert_eq!(format(0, None, Sign::Always), "0");
+        assert_eq!(format(-12, None, Sign::Never), "12");
+        assert_eq!(format(-12, Some(1), Sign::Parens), "(12.0)");
+        assert_eq!(format(12, Some(1), Sign::Parens), "12.0 ");
+        // Rounded to zero
+        assert_eq!(format(-0.001, Some(2), Sign::Negative), "0.00");
+    }
+
+    #[test]
+    fn align_on_decimal_point() {
+        let tpl = "
+            vstack
+                number [width: 10, decimals: 2] 1234.5
+                number [width: 10, decimals: 2] -3.25
+                number [width: 10, decimals: 2] 42
+                number [width: 10, precision: 2, sign: 'parens'] -42
+                number [width: 10, precision: 2, sign: 'parens'] 7
+        ";
+
+        let expected = "
+            ╔══════════╗
+            ║   1234.5 ║
+            ║     -3.25║
+            ║     42   ║
+            ║   (42.00)║
+            ║     7.00 ║
+            ╚══════════╝
+        ";
+
+        TestRunner::new(tpl, (10, 5)).instance().render_assert(expected);
+    }
+}
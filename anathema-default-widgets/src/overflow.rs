@@ -1,9 +1,12 @@
-use std::ops::ControlFlow;
+use std::ops::{ControlFlow, Range};
 
-use anathema_geometry::{Pos, Size};
-use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Pos, Rect, Size};
+use anathema_widgets::layout::{count_elements, Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use crate::layout::many::Many;
 use crate::layout::{Axis, Direction, AXIS, DIRECTION};
@@ -11,7 +14,35 @@ use crate::{HEIGHT, WIDTH};
 
 const UNCONSTRAINED: &str = "unconstrained";
 const CLAMP: &str = "clamp";
-
+const MINIMAP: &str = "minimap";
+const VIRTUAL: &str = "virtual";
+const ITEM_HEIGHT: &str = "item_height";
+const OVERSCAN: &str = "overscan";
+const DEFAULT_OVERSCAN: usize = 2;
+
+// Glyphs used to represent the density of the content in the minimap
+const DENSITY: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Scrollable container.
+///
+/// With `minimap: true` (vertical axis only) the last column is used to show
+/// a compressed overview of the content, with the visible part highlighted.
+/// Use [`Overflow::minimap_jump`] to scroll to a position on the minimap.
+///
+/// With `virtual: true` (vertical axis, forward direction only) only the children in view,
+/// plus `overscan` children (defaults to 2) on either side, are laid out and painted.
+/// Every child is assumed to be `item_height` high, which defaults to the height of the first child.
+/// This is for very large collections, and does not support the minimap.
+/// Children after the window are not visited, and a loop is counted without visiting
+/// every iteration as long as its body is only elements (see [`count_elements`]).
+///
+/// The overflow scrolls to show an element with [`Elements::scroll_into_view`](anathema_widgets::Elements::scroll_into_view),
+/// e.g for a [`Toc`](crate::Toc).
+/// ```text
+/// overflow [virtual: true, overscan: 4]
+///     for line in lines
+///         text line
+/// ```
 #[derive(Debug, Default)]
 pub struct Overflow {
     offset: Pos,
@@ -20,6 +51,64 @@ pub struct Overflow {
 
     direction: Direction,
     is_dirty: bool,
+
+    minimap: Option<Minimap>,
+
+    // The children laid out in virtual mode,
+    // and the height of each child
+    window: Option<(Range<usize>, usize)>,
+}
+
+/// A compressed overview of vertical content, drawn in the last column.
+#[derive(Debug, Default)]
+struct Minimap {
+    // The vertical offset and width of each child, in content space
+    rows: Vec<(usize, Size)>,
+    // Global position of the minimap column
+    pos: Pos,
+    height: usize,
+}
+
+impl Minimap {
+    // Returns the density glyph for each row of the minimap,
+    // and whether or not the row is inside the viewport.
+    fn glyphs(&self, content: Size, offset: usize, viewport: usize) -> Vec<(char, bool)> {
+        let height = self.height;
+        if height == 0 {
+            return vec![];
+        }
+
+        let content_height = content.height.max(height);
+        let mut coverage = vec![0usize; content_height];
+        for &(y, size) in &self.rows {
+            for row in coverage.iter_mut().skip(y).take(size.height) {
+                *row = (*row).max(size.width);
+            }
+        }
+
+        (0..height)
+            .map(|row| {
+                let from = row * content_height / height;
+                let to = ((row + 1) * content_height / height).max(from + 1);
+
+                let covered = coverage[from..to].iter().sum::<usize>();
+                let max = (to - from) * content.width.max(1);
+                let index = (covered * (DENSITY.len() - 1)).div_ceil(max.max(1));
+                let glyph = DENSITY[index.min(DENSITY.len() - 1)];
+
+                let in_view = from < offset + viewport && to > offset;
+                (glyph, in_view)
+            })
+            .collect()
+    }
+
+    // Content offset for a given row in the minimap
+    fn offset_at(&self, row: usize, content_height: usize) -> usize {
+        match self.height {
+            0 => 0,
+            height => row * content_height / height,
+        }
+    }
 }
 
 impl Overflow {
@@ -74,6 +163,101 @@ impl Overflow {
         self.offset
     }
 
+    /// The range of children that were laid out, if the overflow is virtual
+    pub fn window(&self) -> Option<Range<usize>> {
+        self.window.as_ref().map(|(range, _)| range.clone())
+    }
+
+    fn in_window(&self, index: usize) -> bool {
+        match self.window.as_ref() {
+            Some((range, _)) => range.contains(&index),
+            None => true,
+        }
+    }
+
+    fn past_window(&self, index: usize) -> bool {
+        match self.window.as_ref() {
+            Some((range, _)) => index >= range.end,
+            None => false,
+        }
+    }
+
+    // Only lay out the children in view
+    fn layout_window<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        viewport: usize,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) {
+        let attributes = ctx.attribs.get(id);
+        let overscan = attributes.get_usize(OVERSCAN).unwrap_or(DEFAULT_OVERSCAN);
+        let mut item_height = attributes.get_usize(ITEM_HEIGHT);
+
+        let count = count_elements(&children, ctx.attribs);
+        let measure = item_height.is_none();
+        if measure {
+            children.for_each(|child, children| {
+                item_height = Some(child.layout(children, constraints, ctx).height);
+                ControlFlow::Break(())
+            });
+        }
+
+        let item_height = item_height.unwrap_or(1).max(1);
+        let content_height = count * item_height;
+        let max_offset = content_height.saturating_sub(viewport) as i32;
+        self.offset.y = self.offset.y.clamp(0, max_offset.max(0));
+
+        let offset = self.offset.y as usize;
+        let start = (offset / item_height).saturating_sub(overscan);
+        let end = ((offset + viewport).div_ceil(item_height) + overscan).min(count);
+
+        // Children that were in the previous window are reset,
+        // so they are not left behind where they were last positioned
+        let previous = self.window.take().map(|(range, _)| range).unwrap_or_default();
+        let last = end.max(previous.end);
+
+        let mut width = 0;
+        let mut index = 0;
+        children.for_each(|child, children| {
+            if (start..end).contains(&index) {
+                width = width.max(child.layout(children, constraints, ctx).width);
+            } else if previous.contains(&index) || (measure && index == 0) {
+                child.reset();
+            }
+            index += 1;
+            match index < last {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+
+        self.inner_size = Size::new(width, content_height);
+        self.window = Some((start..end, item_height));
+    }
+
+    /// Jump to the content represented by a (global) position on the minimap,
+    /// centering the viewport on it.
+    /// Returns `false` if the position is not on the minimap.
+    pub fn minimap_jump(&mut self, pos: Pos) -> bool {
+        let Some(minimap) = self.minimap.as_ref() else { return false };
+
+        let row = pos.y - minimap.pos.y;
+        if pos.x != minimap.pos.x || row < 0 || row as usize >= minimap.height {
+            return false;
+        }
+
+        let content_offset = minimap.offset_at(row as usize, self.inner_size.height) as i32;
+        let top = content_offset - minimap.height as i32 / 2;
+        self.offset.y = match self.direction {
+            Direction::Forward => top,
+            Direction::Backward => self.inner_size.height as i32 - minimap.height as i32 - top,
+        };
+        self.is_dirty = true;
+        true
+    }
+
     fn clamp(&mut self, children: Size, parent: Size) {
         if self.offset.x < 0 {
             self.offset.x = 0;
@@ -104,6 +288,29 @@ impl Overflow {
 }
 
 impl Widget for Overflow {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(
+                "axis",
+                AttributeType::Enum(&["horizontal", "vertical"]),
+                Some("\"vertical\""),
+            )
+            .attribute(
+                "direction",
+                AttributeType::Enum(&["forward", "backward"]),
+                Some("\"forward\""),
+            )
+            .attribute(CLAMP, AttributeType::Bool, Some("true"))
+            .attribute(UNCONSTRAINED, AttributeType::Bool, Some("false"))
+            .attribute(MINIMAP, AttributeType::Bool, Some("false"))
+            .attribute(VIRTUAL, AttributeType::Bool, Some("false"))
+            .attribute(ITEM_HEIGHT, AttributeType::Int, Some("1"))
+            .attribute(OVERSCAN, AttributeType::Int, Some("2"))
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -116,6 +323,16 @@ impl Widget for Overflow {
 
         let output_size: Size = (constraints.max_width(), constraints.max_height()).into();
 
+        // The minimap is only available for vertical overflow
+        match axis == Axis::Vertical && attributes.get_bool(MINIMAP) {
+            true => {
+                let minimap = self.minimap.get_or_insert_with(Minimap::default);
+                minimap.height = output_size.height;
+                constraints.sub_max_width(1);
+            }
+            false => self.minimap = None,
+        }
+
         match axis {
             Axis::Horizontal => constraints.unbound_width(),
             Axis::Vertical => constraints.unbound_height(),
@@ -136,6 +353,14 @@ impl Widget for Overflow {
 
         self.direction = attributes.get(DIRECTION).unwrap_or_default();
 
+        let is_virtual = attributes.get_bool(VIRTUAL);
+        if is_virtual && axis == Axis::Vertical && self.direction == Direction::Forward {
+            self.minimap = None;
+            self.layout_window(children, constraints, output_size.height, id, ctx);
+            return output_size;
+        }
+        self.window = None;
+
         // Make `unconstrained` an enum instead of a `bool`
         let unconstrained = true;
         let mut many = Many::new(self.direction, axis, unconstrained);
@@ -177,7 +402,39 @@ impl Widget for Overflow {
             Direction::Backward => pos + self.offset,
         };
 
+        if let Some(minimap) = self.minimap.as_mut() {
+            minimap.rows.clear();
+            minimap.pos = Pos::new(ctx.pos.x + ctx.inner_size.width as i32 - 1, ctx.pos.y);
+        }
+        let content_start = pos.y;
+
+        if let Some((range, item_height)) = self.window.clone() {
+            let mut index = 0;
+            children.for_each(|node, children| {
+                if range.contains(&index) {
+                    let pos = Pos::new(pos.x, content_start + (index * item_height) as i32);
+                    node.position(children, pos, attribute_storage, ctx.viewport);
+                }
+                index += 1;
+                match index < range.end {
+                    true => ControlFlow::Continue(()),
+                    false => ControlFlow::Break(()),
+                }
+            });
+            return;
+        }
+
         children.for_each(|node, children| {
+            if let Some(minimap) = self.minimap.as_mut() {
+                let y = match direction {
+                    Direction::Forward => pos.y - content_start,
+                    Direction::Backward => {
+                        self.inner_size.height as i32 - (content_start - pos.y) - node.size().height as i32
+                    }
+                };
+                minimap.rows.push((y.max(0) as usize, node.size()));
+            }
+
             match direction {
                 Direction::Forward => {
                     node.position(children, pos, attribute_storage, ctx.viewport);
@@ -207,22 +464,83 @@ impl Widget for Overflow {
         mut ctx: PaintCtx<'_, SizePos>,
     ) {
         let region = ctx.create_region();
+        let mut index = 0;
         children.for_each(|widget, children| {
-            ctx.set_clip_region(region);
-            let ctx = ctx.to_unsized();
-            widget.paint(children, ctx, attribute_storage);
-            ControlFlow::Continue(())
+            // Children outside of the window are not laid out
+            if self.in_window(index) {
+                ctx.set_clip_region(region);
+                let ctx = ctx.to_unsized();
+                widget.paint(children, ctx, attribute_storage);
+            }
+            index += 1;
+            match self.past_window(index) {
+                true => ControlFlow::Break(()),
+                false => ControlFlow::Continue(()),
+            }
         });
+
+        if let Some(minimap) = self.minimap.as_ref() {
+            let x = ctx.local_size.width.saturating_sub(1) as u16;
+            let content = Size::new(ctx.local_size.width.saturating_sub(1), self.inner_size.height);
+            // Top of the viewport in content space
+            let offset = match self.direction {
+                Direction::Forward => self.offset.y,
+                Direction::Backward => self.inner_size.height as i32 - minimap.height as i32 - self.offset.y,
+            };
+            let offset = offset.max(0) as usize;
+
+            let mut in_view_style = Style::new();
+            in_view_style.set_inverse(true);
+
+            for (y, (glyph, in_view)) in minimap.glyphs(content, offset, minimap.height).into_iter().enumerate() {
+                let pos = LocalPos::new(x, y as u16);
+                if in_view {
+                    ctx.set_attributes(&in_view_style, pos);
+                }
+                ctx.place_glyph(glyph, pos);
+            }
+        }
     }
 
     fn needs_reflow(&self) -> bool {
         self.is_dirty
     }
+
+    fn scroll_into_view(&mut self, region: Rect, mut viewport: Rect) -> bool {
+        if self.minimap.is_some() {
+            viewport.end.x -= 1;
+        }
+
+        // The distance the content has to move, along one axis
+        let distance = |start: i32, end: i32, view_start: i32, view_end: i32| {
+            if start < view_start {
+                start - view_start
+            } else if end > view_end {
+                (end - view_end).min(start - view_start)
+            } else {
+                0
+            }
+        };
+
+        let amount = Pos::new(
+            distance(region.start.x, region.end.x, viewport.start.x, viewport.end.x),
+            distance(region.start.y, region.end.y, viewport.start.y, viewport.end.y),
+        );
+
+        match self.direction {
+            Direction::Forward => self.offset += amount,
+            Direction::Backward => self.offset -= amount,
+        }
+        self.is_dirty = true;
+        true
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use anathema_geometry::{Pos, Size};
 
+    use super::Minimap;
     use crate::testing::TestRunner;
     use crate::Overflow;
 
@@ -269,6 +587,94 @@ mod test {
             .render_assert(expected_second);
     }
 
+    #[test]
+    fn keep_alive_keeps_scroll_position() {
+        let tpl = "
+    if value == 0 [keep_alive: true]
+        overflow
+            for i in [0, 1, 2]
+                text i
+    else
+        text 'x'
+";
+
+        let scrolled = "
+    ╔═══╗
+    ║1  ║
+    ║2  ║
+    ╚═══╝
+";
+
+        TestRunner::new(tpl, (3, 2))
+            .instance()
+            .render_assert(
+                "
+    ╔═══╗
+    ║0  ║
+    ║1  ║
+    ╚═══╝
+",
+            )
+            .with_widget(|mut query| {
+                query.by_tag("overflow").first(|el, _| {
+                    let overflow = el.to::<Overflow>();
+                    overflow.scroll_down();
+                });
+            })
+            .render_assert(scrolled)
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(
+                "
+    ╔═══╗
+    ║x  ║
+    ║   ║
+    ╚═══╝
+",
+            )
+            .with_state(|state| *state.value.to_mut() = 0)
+            .render_assert(scrolled);
+    }
+
+    #[test]
+    fn hidden_branch_is_removed() {
+        let tpl = "
+    if value == 0 [keep_alive: false]
+        overflow
+            for i in [0, 1, 2]
+                text i
+    else
+        text 'x'
+";
+
+        let first = "
+    ╔═══╗
+    ║0  ║
+    ║1  ║
+    ╚═══╝
+";
+
+        TestRunner::new(tpl, (3, 2))
+            .instance()
+            .render_assert(first)
+            .with_widget(|mut query| {
+                query.by_tag("overflow").first(|el, _| {
+                    let overflow = el.to::<Overflow>();
+                    overflow.scroll_down();
+                });
+            })
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(
+                "
+    ╔═══╗
+    ║x  ║
+    ║   ║
+    ╚═══╝
+",
+            )
+            .with_state(|state| *state.value.to_mut() = 0)
+            .render_assert(first);
+    }
+
     #[test]
     fn clamp_prevents_scrolling() {
         let tpl = "
@@ -292,4 +698,194 @@ mod test {
             })
             .render_assert(expected_first);
     }
+
+    #[test]
+    fn minimap() {
+        let tpl = "
+    overflow [minimap: true]
+        for i in [0, 1, 2, 3, 4, 5]
+            text i i
+";
+
+        let expected_first = "
+    ╔═══╗
+    ║00█║
+    ║11█║
+    ║22█║
+    ╚═══╝
+";
+
+        let expected_second = "
+    ╔═══╗
+    ║33█║
+    ║44█║
+    ║55█║
+    ╚═══╝
+";
+
+        TestRunner::new(tpl, (3, 3))
+            .instance()
+            .render_assert(expected_first)
+            .with_widget(|mut query| {
+                query.by_tag("overflow").first(|el, _| {
+                    let pos = el.get_pos();
+                    let overflow = el.to::<Overflow>();
+                    assert!(!overflow.minimap_jump(pos + Pos::new(0, 2)));
+                    // Jump to the last row on the minimap
+                    assert!(overflow.minimap_jump(pos + Pos::new(2, 2)));
+                });
+            })
+            .render_assert(expected_second);
+    }
+
+    #[test]
+    fn virtual_window() {
+        let tpl = "
+    overflow [virtual: true, overscan: 1]
+        for i in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+            text i
+";
+
+        let expected = "
+    ╔═══╗
+    ║4  ║
+    ║5  ║
+    ║6  ║
+    ╚═══╝
+";
+
+        TestRunner::new(tpl, (3, 3))
+            .instance()
+            .render_assert(
+                "
+    ╔═══╗
+    ║0  ║
+    ║1  ║
+    ║2  ║
+    ╚═══╝
+",
+            )
+            .with_widget(|mut query| {
+                query.by_tag("overflow").first(|el, _| {
+                    let overflow = el.to::<Overflow>();
+                    assert_eq!(overflow.window(), Some(0..4));
+                    overflow.scroll_down_by(4);
+                });
+            })
+            .render_assert(expected)
+            .with_widget(|mut query| {
+                query.by_tag("overflow").first(|el, _| {
+                    let overflow = el.to::<Overflow>();
+                    assert_eq!(overflow.window(), Some(3..8));
+                    // Clamped to the last child
+                    overflow.scroll_down_by(100);
+                });
+            })
+            .render_assert(
+                "
+    ╔═══╗
+    ║7  ║
+    ║8  ║
+    ║9  ║
+    ╚═══╝
+",
+            );
+    }
+
+    #[test]
+    fn virtual_window_hit_test() {
+        let tpl = "
+    overflow [virtual: true, overscan: 0]
+        for i in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+            text i
+";
+
+        let texts_at = |elements: &mut anathema_widgets::Elements<'_, '_>, pos: (i32, i32)| {
+            let mut data = vec![];
+            for id in elements.widget_at(pos) {
+                elements
+                    .by_id(id)
+                    .first(|el, _| data.extend(el.user_data().get::<i32>().copied()));
+            }
+            data
+        };
+
+        TestRunner::new(tpl, (3, 3))
+            .instance()
+            .render_assert(
+                "
+    ╔═══╗
+    ║0  ║
+    ║1  ║
+    ║2  ║
+    ╚═══╝
+",
+            )
+            .with_widget(|mut elements| {
+                let mut index = 0;
+                elements.by_tag("text").each(|el, _| {
+                    el.user_data_mut().insert(index);
+                    index += 1;
+                });
+                assert_eq!(texts_at(&mut elements, (1, 1)), [0]);
+                elements
+                    .by_tag("overflow")
+                    .first(|el, _| el.to::<Overflow>().scroll_down_by(4));
+            })
+            .render_assert(
+                "
+    ╔═══╗
+    ║4  ║
+    ║5  ║
+    ║6  ║
+    ╚═══╝
+",
+            )
+            .with_widget(|mut elements| {
+                // The children that are scrolled out of view are not hit
+                assert_eq!(texts_at(&mut elements, (1, 1)), [4]);
+                assert_eq!(texts_at(&mut elements, (1, 3)), [6]);
+            });
+    }
+
+    #[test]
+    fn virtual_window_count() {
+        let tpl = "
+    overflow [virtual: true]
+        text 'a'
+        for i in [1, 2, 3]
+            text i
+            text '-'
+        if state.show
+            text 'b'
+";
+
+        TestRunner::new(tpl, (3, 2))
+            .instance()
+            .with_widget(|mut elements| {
+                elements
+                    .by_tag("overflow")
+                    .first(|el, _| el.to::<Overflow>().scroll_down_by(100));
+            })
+            .render_assert(
+                "
+    ╔═══╗
+    ║3  ║
+    ║-  ║
+    ╚═══╝
+",
+            );
+    }
+
+    #[test]
+    fn minimap_density() {
+        let minimap = Minimap {
+            rows: vec![(0, Size::new(4, 1)), (1, Size::new(2, 1)), (3, Size::new(1, 1))],
+            pos: Pos::ZERO,
+            height: 4,
+        };
+
+        let glyphs = minimap.glyphs(Size::new(4, 4), 0, 2);
+        assert_eq!(glyphs, vec![('█', true), ('▒', true), (' ', false), ('░', false)]);
+    }
 }
@@ -0,0 +1,199 @@
+use std::ops::ControlFlow;
+
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Pos, Rect, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+const DIM: &str = "dim";
+
+/// A layer covering the entire screen.
+///
+/// The overlay is laid out against the viewport, no matter where it is in the tree,
+/// and is painted on top of the widgets underneath it.
+///
+/// Everything underneath the overlay is dimmed, unless `dim` is set to `false`.
+/// A `foreground` or `background` is applied to the whole screen.
+///
+/// ```text
+/// overlay [background: 'black']
+///     align [alignment: 'centre']
+///         border
+///             text 'Are you sure?'
+/// ```
+///
+/// The runtime wraps templates pushed as modal layers in an overlay.
+#[derive(Debug, Default)]
+pub struct Overlay {
+    size: Size,
+}
+
+impl Widget for Overlay {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many).attribute(DIM, AttributeType::Bool, Some("true"));
+        Some(metadata)
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        Rect::from((Pos::ZERO, self.size))
+    }
+
+    // The children are placed at the top of the screen, which is where
+    // portals mounted under the overlay are placed as well
+    fn inner_bounds(&self, _: Pos, _: Size) -> Rect {
+        Rect::from((Pos::ZERO, self.size))
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        self.size = ctx.viewport.size();
+
+        let constraints = ctx.viewport.constraints();
+        children.for_each(|child, children| {
+            child.layout(children, constraints, ctx);
+            ControlFlow::Break(())
+        });
+
+        self.size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, Pos::ZERO, attribute_storage, ctx.viewport);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let attributes = attribute_storage.get(id);
+        let mut style = Style::from_cell_attribs(attributes);
+        style.set_dim(attributes.get(DIM).unwrap_or(true));
+
+        ctx.clip = None;
+        ctx.update(self.size, Pos::ZERO);
+
+        // Apply the style on top of the layers underneath
+        for y in 0..self.size.height as u16 {
+            for x in 0..self.size.width as u16 {
+                ctx.set_attributes(&style, LocalPos::new(x, y));
+            }
+        }
+
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Break(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_geometry::Pos;
+
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn overlay_covers_the_screen() {
+        // The overlay is positioned relative to the viewport, which includes the test border
+        let tpl = "
+            vstack
+                text 'aaaa'
+                text 'bbbb'
+                overlay
+                    text 'hi'
+        ";
+
+        let expected = "
+            hi═══╗
+            ║aaaa║
+            ║bbbb║
+            ╚════╝
+        ";
+
+        TestRunner::new(tpl, (4, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn overlay_is_hit_first() {
+        let tpl = "
+            vstack
+                text 'aaaa'
+                text 'bbbb'
+                overlay
+                    text 'hi'
+        ";
+
+        let expected = "
+            hi═══╗
+            ║aaaa║
+            ║bbbb║
+            ╚════╝
+        ";
+
+        let mut runner = TestRunner::new(tpl, (4, 2));
+        runner.instance().render_assert(expected).with_widget(|mut elements| {
+            let mut ids = vec![];
+            elements.by_tag("overlay").first(|el, _| ids.push(el.id()));
+            elements.by_tag("text").each(|el, _| ids.push(el.id()));
+            let [overlay, a, b, hi] = ids[..] else { panic!() };
+
+            let hits = elements.widget_at((1, 1));
+            assert_eq!(hits[0], overlay);
+            assert!(hits.contains(&a));
+            assert!(!hits.contains(&b));
+            assert!(!hits.contains(&hi));
+
+            assert_eq!(elements.widget_at((0, 0))[..2], [hi, overlay]);
+        });
+    }
+
+    #[test]
+    fn component_under_mouse() {
+        let tpl = "
+            vstack
+                text 'aaaa'
+                text 'bbbb'
+        ";
+
+        let expected = "
+            ╔════╗
+            ║aaaa║
+            ║bbbb║
+            ╚════╝
+        ";
+
+        let mut runner = TestRunner::new(tpl, (4, 2));
+        runner.instance().render_assert(expected).with_root(|elements| {
+            // The test runner puts the template in a component inside a border
+            let (_, local) = elements.component_at((3, 2)).unwrap();
+            assert_eq!(local, Pos::new(2, 1));
+            assert!(elements.component_at((0, 0)).is_none());
+        });
+    }
+}
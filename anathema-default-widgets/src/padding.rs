@@ -1,79 +1,74 @@
 use std::ops::ControlFlow;
 
-use anathema_geometry::{Pos, Rect, Size};
-use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_geometry::Size;
+use anathema_widgets::layout::{
+    Constraints, LayoutCtx, PositionCtx, Spacing, PADDING_BOTTOM, PADDING_LEFT, PADDING_RIGHT, PADDING_TOP,
+};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, Attributes, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
 
 use crate::{BOTTOM, LEFT, RIGHT, TOP};
 
-const PADDING: &str = "padding";
-
+/// A widget that only adds padding around its child.
+///
+/// The `padding`, `padding_top`, `padding_right`, `padding_bottom` and `padding_left`
+/// attributes work on every widget (see [`anathema_widgets::layout::Spacing`]),
+/// so this is only needed to pad a child without changing the child itself.
+///
+/// The padding widget also accepts `top`, `right`, `bottom` and `left`
+/// for the padding of a single side.
+/// ```text
+/// padding [padding: 1, top: 2]
+///     text 'a'
+/// ```
 #[derive(Default)]
-struct PaddingValues {
-    top: u16,
-    right: u16,
-    bottom: u16,
-    left: u16,
-}
+pub struct Padding;
 
-impl PaddingValues {
-    fn size(&self) -> Size {
-        Size {
-            height: (self.top + self.bottom) as usize,
-            width: (self.left + self.right) as usize,
-        }
+impl Widget for Padding {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::One))
     }
-}
 
-#[derive(Default)]
-pub struct Padding(PaddingValues);
+    fn padding(&self, attributes: &Attributes<'_>) -> Spacing {
+        let mut padding = Spacing::padding(attributes);
+        let sides = [
+            (TOP, PADDING_TOP, &mut padding.top),
+            (RIGHT, PADDING_RIGHT, &mut padding.right),
+            (BOTTOM, PADDING_BOTTOM, &mut padding.bottom),
+            (LEFT, PADDING_LEFT, &mut padding.left),
+        ];
+
+        for (key, padding_key, side) in sides {
+            if attributes.get_usize(padding_key).is_some() {
+                continue;
+            }
+
+            if let Some(value) = attributes.get_usize(key).and_then(|v| v.try_into().ok()) {
+                *side = value;
+            }
+        }
+
+        padding
+    }
 
-impl Widget for Padding {
     fn layout<'bp>(
         &mut self,
         mut children: LayoutChildren<'_, '_, 'bp>,
         constraints: Constraints,
-        id: WidgetId,
+        _: WidgetId,
         ctx: &mut LayoutCtx<'_, 'bp>,
     ) -> Size {
-        let attributes = ctx.attribs.get(id);
         let mut size = Size::ZERO;
-        let padding = attributes.get(PADDING).unwrap_or(0);
-
-        self.0.top = attributes
-            .get_usize(TOP)
-            .and_then(|v| v.try_into().ok())
-            .unwrap_or(padding);
-        self.0.right = attributes
-            .get_usize(RIGHT)
-            .and_then(|v| v.try_into().ok())
-            .unwrap_or(padding);
-        self.0.bottom = attributes
-            .get_usize(BOTTOM)
-            .and_then(|v| v.try_into().ok())
-            .unwrap_or(padding);
-        self.0.left = attributes
-            .get_usize(LEFT)
-            .and_then(|v| v.try_into().ok())
-            .unwrap_or(padding);
-
-        let padding_size = self.0.size();
-
         children.for_each(|child, children| {
-            let mut child_constraints = constraints;
-            child_constraints.sub_max_width(padding_size.width);
-            child_constraints.sub_max_height(padding_size.height);
-            let mut child_size = child.layout(children, child_constraints, ctx);
-            child_size += padding_size;
-            size.width = child_size.width.max(size.width);
-            size.height = child_size.height.max(size.height);
-
+            size = child.layout(children, constraints, ctx);
             ControlFlow::Break(())
         });
 
         size.width = constraints.min_width.max(size.width).min(constraints.max_width());
         size.height = constraints.min_height.max(size.height).min(constraints.max_height());
-
         size
     }
 
@@ -82,12 +77,9 @@ impl Widget for Padding {
         mut children: PositionChildren<'_, '_, 'bp>,
         _: WidgetId,
         attribute_storage: &AttributeStorage<'bp>,
-        mut ctx: PositionCtx,
+        ctx: PositionCtx,
     ) {
         children.for_each(|child, children| {
-            ctx.pos.y += self.0.top as i32;
-            ctx.pos.x += self.0.left as i32;
-
             child.position(children, ctx.pos, attribute_storage, ctx.viewport);
             ControlFlow::Break(())
         });
@@ -95,31 +87,17 @@ impl Widget for Padding {
 
     fn paint<'bp>(
         &mut self,
-        mut children: anathema_widgets::PaintChildren<'_, '_, 'bp>,
-        _id: WidgetId,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
         attribute_storage: &AttributeStorage<'bp>,
-        mut ctx: anathema_widgets::paint::PaintCtx<'_, anathema_widgets::paint::SizePos>,
+        mut ctx: PaintCtx<'_, SizePos>,
     ) {
         children.for_each(|child, children| {
-            let mut ctx = ctx.to_unsized();
-            if let Some(clip) = ctx.clip.as_mut() {
-                clip.from.x += self.0.left as i32;
-                clip.from.y += self.0.top as i32;
-                clip.to.x -= self.0.right as i32;
-                clip.to.y -= self.0.bottom as i32;
-            }
+            let ctx = ctx.to_unsized();
             child.paint(children, ctx, attribute_storage);
             ControlFlow::Break(())
         });
     }
-
-    fn inner_bounds(&self, mut pos: Pos, mut size: Size) -> Rect {
-        pos.x += self.0.left as i32;
-        pos.y += self.0.top as i32;
-        size.width = size.width.saturating_sub(self.0.right as usize);
-        size.height = size.height.saturating_sub(self.0.bottom as usize);
-        Rect::from((pos, size))
-    }
 }
 
 #[cfg(test)]
@@ -290,4 +268,57 @@ mod test {
 
         TestRunner::new(tpl, (4, 3)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn padding_on_any_widget() {
+        let tpl = "
+            vstack [padding_left: 2]
+                text 'a'
+                text 'b'
+        ";
+
+        let expected = "
+            ╔════╗
+            ║  a ║
+            ║  b ║
+            ╚════╝
+        ";
+
+        TestRunner::new(tpl, (4, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn padding_shrinks_constraints() {
+        let tpl = "text [padding_left: 2] 'abcd'";
+
+        let expected = "
+            ╔════╗
+            ║  ab║
+            ║  cd║
+            ╚════╝
+        ";
+
+        TestRunner::new(tpl, (4, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn padding_is_added_to_min_size() {
+        let tpl = "
+            hstack
+                vstack [padding: 1, min_width: 3, min_height: 2]
+                    text 'a'
+                text '|'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║     |║
+            ║ a    ║
+            ║      ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 4)).instance().render_assert(expected);
+    }
 }
@@ -0,0 +1,400 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{LocalPos, Pos, Rect, Size};
+use anathema_widgets::components::events::{MouseButton, MouseEvent, MouseState};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{HEIGHT, LEFT, TOP, WIDTH};
+
+pub const TITLE: &str = "title";
+
+const DEFAULT_WIDTH: usize = 20;
+const DEFAULT_HEIGHT: usize = 8;
+// Room for the corners, the close button and the resize handle
+const MIN_WIDTH: usize = 4;
+const MIN_HEIGHT: usize = 3;
+
+const CLOSE_BUTTON: char = '×';
+const RESIZE_HANDLE: char = '◢';
+// Top left, top, top right, right, bottom right, bottom, bottom left, left
+const EDGES: [char; 8] = ['┌', '─', '┐', '│', '┘', '─', '└', '│'];
+
+/// Position and size of a panel, in screen coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PanelGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl PanelGeometry {
+    pub fn pos(&self) -> Pos {
+        Pos::new(self.x, self.y)
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x >= self.x
+            && pos.y >= self.y
+            && pos.x < self.x + self.width as i32
+            && pos.y < self.y + self.height as i32
+    }
+
+    // Keep the panel inside the viewport (as long as it fits)
+    fn clamp(&mut self, viewport: Size) {
+        self.width = self.width.max(MIN_WIDTH);
+        self.height = self.height.max(MIN_HEIGHT);
+        self.x = self.x.min(viewport.width as i32 - self.width as i32).max(0);
+        self.y = self.y.min(viewport.height as i32 - self.height as i32).max(0);
+    }
+
+    fn close_button(&self) -> Pos {
+        Pos::new(self.x + self.width as i32 - 2, self.y)
+    }
+
+    fn resize_handle(&self) -> Pos {
+        Pos::new(self.x + self.width as i32 - 1, self.y + self.height as i32 - 1)
+    }
+}
+
+/// The result of passing a mouse event to a panel
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PanelEvent {
+    /// The panel was clicked (use this to raise the panel)
+    Focus,
+    /// The panel was dragged by the title bar
+    Moved,
+    /// The panel was resized by the bottom right corner
+    Resized,
+    /// The close button was clicked
+    Close,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Drag {
+    // Offset from the top left corner of the panel to the mouse
+    Move(i32, i32),
+    Resize,
+}
+
+/// A floating, draggable and resizable window with a title bar.
+///
+/// The panel is drawn on top of the other widgets, relative to the viewport,
+/// with a close button in the top right corner and a resize handle in the
+/// bottom right corner.
+///
+/// ```text
+/// panel [title: "Tools", left: 4, top: 2, width: 30, height: 10]
+///     text "content"
+/// ```
+///
+/// `left`, `top`, `width` and `height` only set the initial geometry.
+/// Once the panel has been moved or resized it keeps its own geometry, which
+/// can be read with [`Panel::geometry`] and stored to restore the panel later
+/// (e.g by binding the attributes to values in the state).
+///
+/// Mouse events are passed to the panel from the component:
+/// ```ignore
+/// elements.by_tag("panel").first(|el, _| {
+///     if let Some(PanelEvent::Close) = el.to::<Panel>().on_mouse(mouse) {
+///         state.show_tools.set(false);
+///     }
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct Panel {
+    // `None` until the geometry is set from the attributes
+    geometry: Option<PanelGeometry>,
+    // Set once the geometry is changed by anything other than the attributes
+    detached: bool,
+    drag: Option<Drag>,
+    title: String,
+    is_dirty: bool,
+}
+
+impl Panel {
+    /// The current geometry of the panel
+    pub fn geometry(&self) -> Option<PanelGeometry> {
+        self.geometry
+    }
+
+    /// Move and resize the panel, ignoring the geometry attributes from here on
+    pub fn set_geometry(&mut self, geometry: PanelGeometry) {
+        self.geometry = Some(geometry);
+        self.detached = true;
+        self.is_dirty = true;
+    }
+
+    /// Handle a mouse event.
+    ///
+    /// Returns `None` if the event is outside of the panel
+    /// and the panel is not being dragged.
+    pub fn on_mouse(&mut self, mouse: MouseEvent) -> Option<PanelEvent> {
+        let geometry = self.geometry.as_mut()?;
+        let pos = mouse.pos();
+
+        match mouse.state {
+            MouseState::Down(MouseButton::Left) => {
+                if !geometry.contains(pos) {
+                    return None;
+                }
+
+                if pos == geometry.close_button() {
+                    return Some(PanelEvent::Close);
+                }
+
+                if pos == geometry.resize_handle() {
+                    self.drag = Some(Drag::Resize);
+                } else if pos.y == geometry.y {
+                    self.drag = Some(Drag::Move(pos.x - geometry.x, pos.y - geometry.y));
+                }
+
+                Some(PanelEvent::Focus)
+            }
+            MouseState::Drag(MouseButton::Left) => {
+                let event = match self.drag? {
+                    Drag::Move(dx, dy) => {
+                        geometry.x = pos.x - dx;
+                        geometry.y = pos.y - dy;
+                        PanelEvent::Moved
+                    }
+                    Drag::Resize => {
+                        geometry.width = (pos.x - geometry.x + 1).max(MIN_WIDTH as i32) as usize;
+                        geometry.height = (pos.y - geometry.y + 1).max(MIN_HEIGHT as i32) as usize;
+                        PanelEvent::Resized
+                    }
+                };
+
+                self.detached = true;
+                self.is_dirty = true;
+                Some(event)
+            }
+            MouseState::Up(MouseButton::Left) => {
+                self.drag.take()?;
+                Some(PanelEvent::Focus)
+            }
+            _ => None,
+        }
+    }
+
+    fn inner_size(&self) -> Size {
+        let size = self.geometry.map(|g| g.size()).unwrap_or(Size::ZERO);
+        Size::new(size.width.saturating_sub(2), size.height.saturating_sub(2))
+    }
+}
+
+impl Widget for Panel {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(TITLE, AttributeType::String, None)
+            .attribute(TOP, AttributeType::Int, None)
+            .attribute(LEFT, AttributeType::Int, None)
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        match self.geometry {
+            Some(geometry) => Rect::from((geometry.pos(), geometry.size())),
+            None => Rect::ZERO,
+        }
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+
+        self.title.clear();
+        if let Some(title) = attributes.get_val(TITLE) {
+            title.str_for_each(|s| self.title.push_str(s));
+        }
+
+        let mut geometry = match self.geometry {
+            Some(geometry) if self.detached => geometry,
+            _ => PanelGeometry {
+                x: attributes.get_int(LEFT).unwrap_or(0) as i32,
+                y: attributes.get_int(TOP).unwrap_or(0) as i32,
+                width: attributes.get_usize(WIDTH).unwrap_or(DEFAULT_WIDTH),
+                height: attributes.get_usize(HEIGHT).unwrap_or(DEFAULT_HEIGHT),
+            },
+        };
+        geometry.clamp(ctx.viewport.size());
+        self.geometry = Some(geometry);
+        self.is_dirty = false;
+
+        let inner = self.inner_size();
+        let mut constraints = Constraints::new(inner.width, inner.height);
+        constraints.make_width_tight(inner.width);
+        constraints.make_height_tight(inner.height);
+
+        children.for_each(|child, children| {
+            child.layout(children, constraints, ctx);
+            ControlFlow::Break(())
+        });
+
+        geometry.size()
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let Some(geometry) = self.geometry else { return };
+
+        // Like absolute positioning, the panel ignores the position of the parent
+        children.for_each(|child, children| {
+            child.position(children, geometry.pos() + Pos::new(1, 1), attribute_storage, ctx.viewport);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let Some(geometry) = self.geometry else { return };
+        let Size { width, height } = geometry.size();
+
+        ctx.clip = None;
+        ctx.update(geometry.size(), geometry.pos());
+
+        // Clear the area underneath the panel
+        for y in 0..height as u16 {
+            for x in 0..width as u16 {
+                ctx.place_glyph(' ', LocalPos::new(x, y));
+            }
+        }
+
+        // Frame
+        let (right, bottom) = (width as u16 - 1, height as u16 - 1);
+        for x in 1..right {
+            ctx.place_glyph(EDGES[1], LocalPos::new(x, 0));
+            ctx.place_glyph(EDGES[5], LocalPos::new(x, bottom));
+        }
+        for y in 1..bottom {
+            ctx.place_glyph(EDGES[7], LocalPos::new(0, y));
+            ctx.place_glyph(EDGES[3], LocalPos::new(right, y));
+        }
+        ctx.place_glyph(EDGES[0], LocalPos::new(0, 0));
+        ctx.place_glyph(EDGES[2], LocalPos::new(right, 0));
+        ctx.place_glyph(EDGES[6], LocalPos::new(0, bottom));
+        ctx.place_glyph(RESIZE_HANDLE, LocalPos::new(right, bottom));
+
+        // Title bar
+        ctx.place_glyph(CLOSE_BUTTON, LocalPos::new(right - 1, 0));
+        if !self.title.is_empty() && self.title.width() + 2 < width - 2 {
+            ctx.place_glyphs(&format!(" {} ", self.title), LocalPos::new(1, 0));
+        }
+
+        // Content
+        ctx.update(self.inner_size(), geometry.pos() + Pos::new(1, 1));
+        let region = ctx.create_region();
+        children.for_each(|child, children| {
+            ctx.set_clip_region(region);
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    fn mouse(x: u16, y: u16, state: MouseState) -> MouseEvent {
+        MouseEvent {
+            x,
+            y,
+            state,
+            ctrl: false,
+            shift: false,
+            local: None,
+        }
+    }
+
+    #[test]
+    fn render_panel() {
+        // The panel is positioned relative to the viewport, which includes the test border
+        let tpl = "
+            text 'background'
+            panel [title: 'a', left: 2, top: 2, width: 8, height: 4]
+                text 'hi'
+        ";
+
+        let expected = "
+            ╔══════════╗
+            ║background║
+            ║ ┌ a ──×┐ ║
+            ║ │hi    │ ║
+            ║ │      │ ║
+            ║ └──────◢ ║
+            ╚══════════╝
+        ";
+
+        TestRunner::new(tpl, (10, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn drag_and_resize() {
+        let mut panel = Panel {
+            geometry: Some(PanelGeometry {
+                x: 2,
+                y: 2,
+                width: 10,
+                height: 5,
+            }),
+            ..Default::default()
+        };
+
+        // Move by the title bar
+        let down = MouseState::Down(MouseButton::Left);
+        let drag = MouseState::Drag(MouseButton::Left);
+        assert_eq!(panel.on_mouse(mouse(4, 2, down)), Some(PanelEvent::Focus));
+        assert_eq!(panel.on_mouse(mouse(6, 3, drag)), Some(PanelEvent::Moved));
+        assert_eq!(panel.on_mouse(mouse(6, 3, MouseState::Up(MouseButton::Left))), Some(PanelEvent::Focus));
+        assert_eq!(panel.geometry().unwrap().pos(), Pos::new(4, 3));
+
+        // Resize by the bottom right corner
+        panel.on_mouse(mouse(13, 7, down));
+        assert_eq!(panel.on_mouse(mouse(9, 5, drag)), Some(PanelEvent::Resized));
+        assert_eq!(panel.geometry().unwrap().size(), Size::new(6, 3));
+
+        // Close button
+        assert_eq!(panel.on_mouse(mouse(8, 3, down)), Some(PanelEvent::Close));
+
+        // Outside of the panel
+        assert_eq!(panel.on_mouse(mouse(0, 0, down)), None);
+    }
+}
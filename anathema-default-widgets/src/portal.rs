@@ -0,0 +1,133 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::Size;
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+/// Content declared in one place of the tree but laid out and painted
+/// inside the element with a matching `mount` attribute.
+///
+/// The children are stacked on top of each other at the start of the mount point,
+/// and painted on top of the mount point and everything inside it.
+/// Nothing is painted if there is no mount point with the name in `to`.
+///
+/// ```text
+/// overlay [mount: 'dialogs']
+///
+/// // Somewhere deep inside a component
+/// portal [to: 'dialogs']
+///     align [alignment: 'centre']
+///         border
+///             text 'Are you sure?'
+/// ```
+#[derive(Debug, Default)]
+pub struct Portal;
+
+impl Widget for Portal {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many))
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn is_portal(&self) -> bool {
+        true
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let mut size = Size::ZERO;
+        children.for_each(|child, children| {
+            let child_size = child.layout(children, constraints, ctx);
+            size.width = size.width.max(child_size.width);
+            size.height = size.height.max(child_size.height);
+            ControlFlow::Continue(())
+        });
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, ctx.pos, attribute_storage, ctx.viewport);
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn portal_is_painted_in_mount_point() {
+        let tpl = "
+            vstack
+                border [mount: 'dialogs']
+                    text 'target'
+                vstack
+                    text 'abc'
+                    portal [to: 'dialogs']
+                        text 'X'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║┌──────┐║
+            ║│Xarget│║
+            ║└──────┘║
+            ║abc     ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn portal_without_mount_point() {
+        let tpl = "
+            vstack
+                text 'target'
+                portal [to: 'dialogs']
+                    text 'X'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║target║
+            ║      ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
+    }
+}
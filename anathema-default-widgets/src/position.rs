@@ -4,7 +4,10 @@ use anathema::CommonVal;
 use anathema_geometry::{Pos, Size};
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
 
 use crate::{BOTTOM, LEFT, RIGHT, TOP};
 
@@ -66,6 +69,20 @@ impl Default for Position {
 }
 
 impl Widget for Position {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(
+                "placement",
+                AttributeType::Enum(&["relative", "absolute"]),
+                Some("\"relative\""),
+            )
+            .attribute(TOP, AttributeType::Int, None)
+            .attribute(RIGHT, AttributeType::Int, None)
+            .attribute(BOTTOM, AttributeType::Int, None)
+            .attribute(LEFT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn floats(&self) -> bool {
         true
     }
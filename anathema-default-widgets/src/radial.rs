@@ -0,0 +1,164 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{Pos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+const RADIUS: &str = "radius";
+const START: &str = "start";
+const END: &str = "end";
+
+const FULL_CIRCLE: f64 = 360.0;
+
+/// Places the children around a circle, or along an arc, centred on the widget.
+///
+/// Angles are in degrees, clockwise from the top: `start` is the angle of the first child
+/// and `end` the angle of the last child. On a full circle the children are spread
+/// evenly, so the last child is not placed on top of the first one.
+///
+/// `radius` is the number of lines from the centre to the centre of a child,
+/// and defaults to the largest radius that fits. Cells are about twice as tall as
+/// they are wide, so the horizontal radius is twice as many cells.
+///
+/// ```text
+/// radial [radius: 4, start: 0, end: 360]
+///     for hour in [12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+///         text hour
+/// ```
+#[derive(Debug, Default)]
+pub struct Radial {
+    // Offset of each child, relative to the widget
+    offsets: Vec<Pos>,
+}
+
+impl Widget for Radial {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(RADIUS, AttributeType::Int, None)
+            .attribute(START, AttributeType::Float, Some("0.0"))
+            .attribute(END, AttributeType::Float, Some("360.0"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let radius = attributes.get_usize(RADIUS);
+        let start = attributes.get_float(START).unwrap_or(0.0);
+        let end = attributes.get_float(END).unwrap_or(FULL_CIRCLE);
+
+        let mut sizes = vec![];
+        let child_constraints = Constraints::new(constraints.max_width(), constraints.max_height());
+        children.for_each(|child, children| {
+            sizes.push(child.layout(children, child_constraints, ctx));
+            ControlFlow::Continue(())
+        });
+
+        let largest = sizes.iter().fold(Size::ZERO, |a, b| {
+            Size::new(a.width.max(b.width), a.height.max(b.height))
+        });
+
+        // The largest radius where every child is inside the constraints
+        let radius = radius.unwrap_or_else(|| {
+            let vertical = constraints.max_height().saturating_sub(largest.height) / 2;
+            let horizontal = constraints.max_width().saturating_sub(largest.width) / 4;
+            vertical.min(horizontal)
+        });
+
+        let size = Size::new(
+            (radius * 4 + largest.width).clamp(constraints.min_width, constraints.max_width()),
+            (radius * 2 + largest.height).clamp(constraints.min_height, constraints.max_height()),
+        );
+
+        let count = sizes.len();
+        let step = match count {
+            0 | 1 => 0.0,
+            _ if (end - start).abs() >= FULL_CIRCLE => (end - start) / count as f64,
+            _ => (end - start) / (count - 1) as f64,
+        };
+
+        let centre = (size.width as f64 / 2.0, size.height as f64 / 2.0);
+        self.offsets.clear();
+        for (i, child_size) in sizes.into_iter().enumerate() {
+            let angle = (start + step * i as f64).to_radians();
+            let x = centre.0 + angle.sin() * radius as f64 * 2.0 - child_size.width as f64 / 2.0;
+            let y = centre.1 - angle.cos() * radius as f64 - child_size.height as f64 / 2.0;
+            self.offsets.push(Pos::new(x.round() as i32, y.round() as i32));
+        }
+
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let offset = self.offsets.get(index).copied().unwrap_or(Pos::ZERO);
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn full_circle() {
+        let tpl = "
+            radial [radius: 2]
+                text 'n'
+                text 'e'
+                text 's'
+                text 'w'
+        ";
+
+        let expected = "
+            ╔═════════╗
+            ║    n    ║
+            ║         ║
+            ║w       e║
+            ║         ║
+            ║    s    ║
+            ╚═════════╝
+        ";
+
+        TestRunner::new(tpl, (9, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn arc() {
+        let tpl = "
+            radial [radius: 2, start: -90, end: 90]
+                text 'w'
+                text 'n'
+                text 'e'
+        ";
+
+        let expected = "
+            ╔═════════╗
+            ║    n    ║
+            ║         ║
+            ║w       e║
+            ║         ║
+            ║         ║
+            ╚═════════╝
+        ";
+
+        TestRunner::new(tpl, (9, 5)).instance().render_assert(expected);
+    }
+}
@@ -0,0 +1,137 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{Pos, Rect, Region as ScreenRegion, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::{HEIGHT, WIDTH};
+
+const X: &str = "x";
+const Y: &str = "y";
+
+/// A fixed region of the screen.
+///
+/// The region is placed at `x` and `y` in screen coordinates, no matter where it is in the tree,
+/// and the children are clipped to the `width` and `height` of the region.
+/// Without a `width` or `height` the region extends to the edge of the screen.
+///
+/// ```text
+/// region [y: 0, height: 1]
+///     text 'header'
+/// ```
+///
+/// The runtime wraps the templates of the regions of a document in a region.
+#[derive(Debug, Default)]
+pub struct Region {
+    pos: Pos,
+    size: Size,
+}
+
+impl Widget for Region {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(X, AttributeType::Int, None)
+            .attribute(Y, AttributeType::Int, None)
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        Rect::from((self.pos, self.size))
+    }
+
+    fn inner_bounds(&self, _: Pos, _: Size) -> Rect {
+        Rect::from((self.pos, self.size))
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let viewport = ctx.viewport.size();
+        let x = attributes.get_usize(X).unwrap_or(0).min(viewport.width);
+        let y = attributes.get_usize(Y).unwrap_or(0).min(viewport.height);
+        let width = attributes.get_usize(WIDTH).unwrap_or(usize::MAX);
+        let height = attributes.get_usize(HEIGHT).unwrap_or(usize::MAX);
+
+        self.pos = Pos::new(x as i32, y as i32);
+        self.size = Size::new(width.min(viewport.width - x), height.min(viewport.height - y));
+
+        let constraints = Constraints::new(self.size.width, self.size.height);
+        children.for_each(|child, children| {
+            child.layout(children, constraints, ctx);
+            ControlFlow::Continue(())
+        });
+
+        self.size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, self.pos, attribute_storage, ctx.viewport);
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        ctx.clip = None;
+        ctx.update(self.size, self.pos);
+        ctx.set_clip_region(ScreenRegion::from((self.pos, self.size)));
+
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn region_is_placed_on_the_screen() {
+        // The region is placed relative to the screen, which includes the test border
+        let tpl = "
+            vstack
+                text 'aaaa'
+                region [x: 1, y: 3, width: 4]
+                    text 'region'
+        ";
+
+        let expected = "
+            ╔════╗
+            ║aaaa║
+            ║    ║
+            ╚regi╝
+        ";
+
+        TestRunner::new(tpl, (4, 2)).instance().render_assert(expected);
+    }
+}
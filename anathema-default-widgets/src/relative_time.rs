@@ -0,0 +1,280 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::calendar::Date;
+
+const ABSOLUTE_AFTER: &str = "absolute_after";
+// A week
+const DEFAULT_ABSOLUTE_AFTER: u64 = 7 * DAY;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// A timestamp (seconds since the Unix epoch) relative to now, e.g `3m ago` or `in 2h`.
+///
+/// Timestamps more than `absolute_after` seconds away (defaults to a week) are shown
+/// as a date instead, e.g `2024-02-29` (UTC).
+///
+/// ```text
+/// relativetime [absolute_after: 86400] message.sent_at
+/// ```
+///
+/// The widget doesn't know when time passes. Refresh it from a timer, and it's only
+/// laid out and painted again if the text changed.
+/// [`RelativeTime::next_refresh`] is the time until the text changes, e.g a second for
+/// `12s ago` and an hour for `3h ago`, which makes a good timeout:
+/// ```ignore
+/// // Component::on_message
+/// elements.by_tag("relativetime").each(|el, _| {
+///     let time = el.to::<RelativeTime>();
+///     time.refresh();
+///     if let Some(delay) = time.next_refresh() {
+///         next = next.min(delay);
+///     }
+/// });
+/// runtime.set_timeout(next, component_id, Refresh);
+/// ```
+#[derive(Debug)]
+pub struct RelativeTime {
+    now: SystemTime,
+    // Seconds since the Unix epoch
+    timestamp: Option<f64>,
+    absolute_after: u64,
+    text: String,
+    is_dirty: bool,
+}
+
+impl Default for RelativeTime {
+    fn default() -> Self {
+        Self {
+            now: SystemTime::now(),
+            timestamp: None,
+            absolute_after: DEFAULT_ABSOLUTE_AFTER,
+            text: String::new(),
+            is_dirty: false,
+        }
+    }
+}
+
+impl RelativeTime {
+    /// Update the text to the current time
+    pub fn refresh(&mut self) {
+        self.refresh_at(SystemTime::now());
+    }
+
+    /// Update the text to a given point in time
+    pub fn refresh_at(&mut self, now: SystemTime) {
+        self.now = now;
+        let text = self.format();
+        if text != self.text {
+            self.text = text;
+            self.is_dirty = true;
+        }
+    }
+
+    /// The time until the text changes, or `None` if it never changes
+    pub fn next_refresh(&self) -> Option<Duration> {
+        let diff = self.diff()?;
+        let secs = diff.unsigned_abs();
+        let unit = match secs {
+            0..MINUTE => 1,
+            MINUTE..HOUR => MINUTE,
+            HOUR..DAY => HOUR,
+            _ => DAY,
+        };
+
+        let next = match diff > 0 {
+            // The timestamp is in the future, so the time counts down
+            true if secs > self.absolute_after => secs - self.absolute_after,
+            true => secs % unit + 1,
+            false if secs > self.absolute_after => return None,
+            false => (unit - secs % unit).min(self.absolute_after + 1 - secs),
+        };
+        Some(Duration::from_secs(next))
+    }
+
+    // Whole seconds from now until the timestamp
+    fn diff(&self) -> Option<i64> {
+        let now = match self.now.duration_since(UNIX_EPOCH) {
+            Ok(now) => now.as_secs_f64(),
+            Err(before) => -before.duration().as_secs_f64(),
+        };
+        Some((self.timestamp? - now).floor() as i64)
+    }
+
+    fn format(&self) -> String {
+        let Some(diff) = self.diff() else { return String::new() };
+        let secs = diff.unsigned_abs();
+
+        if secs > self.absolute_after {
+            let timestamp = self.timestamp.unwrap_or(0.0).floor() as i64;
+            return Date::from_days(timestamp.div_euclid(DAY as i64)).to_string();
+        }
+
+        let (amount, unit) = match secs {
+            0 => return "now".into(),
+            1..MINUTE => (secs, "s"),
+            MINUTE..HOUR => (secs / MINUTE, "m"),
+            HOUR..DAY => (secs / HOUR, "h"),
+            _ => (secs / DAY, "d"),
+        };
+
+        match diff > 0 {
+            true => format!("in {amount}{unit}"),
+            false => format!("{amount}{unit} ago"),
+        }
+    }
+}
+
+impl Widget for RelativeTime {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata =
+            WidgetMetadata::new(Children::None).attribute(ABSOLUTE_AFTER, AttributeType::Int, Some("604800"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.absolute_after = attributes
+            .get_usize(ABSOLUTE_AFTER)
+            .map(|secs| secs as u64)
+            .unwrap_or(DEFAULT_ABSOLUTE_AFTER);
+        self.timestamp = attributes
+            .value()
+            .and_then(|value| value.load_common_val())
+            .and_then(|value| value.load_number())
+            .map(|number| number.as_float());
+
+        self.text = self.format();
+        self.is_dirty = false;
+
+        Size::new(
+            self.text.len().clamp(constraints.min_width, constraints.max_width()),
+            1.clamp(constraints.min_height, constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The relative time has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        ctx.place_glyphs(&self.text, LocalPos::ZERO);
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    fn at(timestamp: f64, now: u64) -> RelativeTime {
+        RelativeTime {
+            now: UNIX_EPOCH + Duration::from_secs(now),
+            timestamp: Some(timestamp),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn format_relative_time() {
+        let start = 1_000_000;
+        assert_eq!(at(1_000_000.0, start).format(), "now");
+        assert_eq!(at(999_988.0, start).format(), "12s ago");
+        assert_eq!(at(999_820.0, start).format(), "3m ago");
+        assert_eq!(at(1_007_200.0, start).format(), "in 2h");
+        assert_eq!(at(1_000_000.0 - 3.0 * DAY as f64, start).format(), "3d ago");
+        // Past a week
+        assert_eq!(at(0.0, start).format(), "1970-01-01");
+        assert_eq!(at(1_709_164_800.0, start).format(), "2024-02-29");
+    }
+
+    #[test]
+    fn refresh_cadence() {
+        let start = 1_000_000;
+        let secs = Duration::from_secs;
+        assert_eq!(at(999_988.0, start).next_refresh(), Some(secs(1)));
+        // 3m 10s ago becomes 4m ago in 50 seconds
+        assert_eq!(at(999_810.0, start).next_refresh(), Some(secs(50)));
+        // In 2h 30s becomes in 1h in 31 seconds
+        assert_eq!(at(1_007_230.0, start).next_refresh(), Some(secs(31)));
+        // Shown as a date from now on
+        assert_eq!(at(0.0, start).next_refresh(), None);
+        // Shown relative to now in a day
+        assert_eq!(
+            at((start + DEFAULT_ABSOLUTE_AFTER + DAY) as f64, start).next_refresh(),
+            Some(secs(DAY))
+        );
+    }
+
+    #[test]
+    fn refresh_text() {
+        let tpl = "relativetime 7200";
+
+        let expected_date = "
+            ╔══════════╗
+            ║1970-01-01║
+            ╚══════════╝
+        ";
+
+        let expected_ago = "
+            ╔══════════╗
+            ║3m ago    ║
+            ╚══════════╝
+        ";
+
+        let expected_in = "
+            ╔══════════╗
+            ║in 2h     ║
+            ╚══════════╝
+        ";
+
+        TestRunner::new(tpl, (10, 1))
+            .instance()
+            .render_assert(expected_date)
+            .with_widget(|mut elements| {
+                elements.by_tag("relativetime").first(|el, _| {
+                    let time = el.to::<RelativeTime>();
+                    time.refresh_at(UNIX_EPOCH + Duration::from_secs(7380));
+                    assert!(time.needs_reflow());
+                });
+            })
+            .render_assert(expected_ago)
+            .with_widget(|mut elements| {
+                elements
+                    .by_tag("relativetime")
+                    .first(|el, _| el.to::<RelativeTime>().refresh_at(UNIX_EPOCH));
+            })
+            .render_assert(expected_in);
+    }
+}
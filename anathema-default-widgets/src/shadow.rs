@@ -0,0 +1,146 @@
+use std::ops::ControlFlow;
+
+use anathema_backend::tui::Style;
+use anathema_geometry::{LocalPos, Pos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::style::Prefixed;
+
+const X: &str = "x";
+const Y: &str = "y";
+const FILL: &str = "fill";
+const SHADOW: &str = "shadow";
+
+const DEFAULT_X: usize = 2;
+const DEFAULT_Y: usize = 1;
+
+/// Paints a drop shadow behind the child, to make e.g. a dialog stand out.
+///
+/// The shadow is offset from the child by `x` and `y` (two cells to the right and one cell down by
+/// default) and is painted over the widgets underneath, outside of the shadow widget itself.
+/// The shadow doesn't take up any space in the layout.
+///
+/// The shadow is dimmed, unless it's styled with the `shadow_` attributes,
+/// e.g. `shadow_background`. The shadow can also be filled with a `fill` char.
+///
+/// ```text
+/// overlay
+///     align [alignment: 'centre']
+///         shadow [shadow_background: 'black']
+///             border
+///                 text 'Are you sure?'
+/// ```
+#[derive(Debug, Default)]
+pub struct Shadow;
+
+impl Widget for Shadow {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One)
+            .attribute(X, AttributeType::Int, Some("2"))
+            .attribute(Y, AttributeType::Int, Some("1"))
+            .attribute(FILL, AttributeType::Char, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let mut size = Size::ZERO;
+        children.for_each(|child, children| {
+            size = child.layout(children, constraints, ctx);
+            ControlFlow::Break(())
+        });
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, ctx.pos, attribute_storage, ctx.viewport);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let attributes = attribute_storage.get(id);
+        let x = attributes.get_usize(X).unwrap_or(DEFAULT_X);
+        let y = attributes.get_usize(Y).unwrap_or(DEFAULT_Y);
+        let fill = attributes.get_ref::<&str>(FILL).and_then(|s| s.chars().next());
+
+        let prefixed = Prefixed::new(SHADOW, attributes);
+        let mut style = Style::from_cell_attribs(&prefixed);
+        if prefixed.is_empty() {
+            style.set_dim(true);
+        }
+
+        // The shadow is painted first so the child is painted on top of it
+        let size = ctx.local_size;
+        let mut shadow = ctx.outside(Pos::ZERO, Size::new(size.width + x, size.height + y));
+        for row in y..size.height + y {
+            for col in x..size.width + x {
+                if col < size.width && row < size.height {
+                    continue;
+                }
+
+                let pos = LocalPos::new(col as u16, row as u16);
+                if let Some(fill) = fill {
+                    shadow.place_glyph(fill, pos);
+                }
+                shadow.set_attributes(&style, pos);
+            }
+        }
+
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Break(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn shadow_outside_of_the_child() {
+        let tpl = "
+            zstack
+                text 'aaaaaa aaaaaa aaaaaa aaaaaa'
+                shadow [fill: '░']
+                    border
+                        text 'hi'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║┌──┐aa║
+            ║│hi│░░║
+            ║└──┘░░║
+            ║aa░░░░║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 4)).instance().render_assert(expected);
+    }
+}
@@ -0,0 +1,160 @@
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::WIDTH;
+
+pub const VALUE: &str = "value";
+pub const MIN: &str = "min";
+pub const MAX: &str = "max";
+pub const TRACK: &str = "track";
+pub const THUMB: &str = "thumb";
+
+const DEFAULT_TRACK: char = '─';
+const DEFAULT_THUMB: char = '█';
+// Width used if the constraints are unbounded and no width is given
+const DEFAULT_WIDTH: usize = 20;
+
+/// A horizontal slider, drawing a track and a thumb
+/// at the position of `value` between `min` and `max`.
+///
+/// ```text
+/// slider [value: 5, min: 0, max: 10, track: "-", thumb: "|"]
+/// ```
+#[derive(Debug, Default)]
+pub struct Slider {
+    thumb_offset: usize,
+    track: char,
+    thumb: char,
+}
+
+impl Slider {
+    /// Map an x offset inside the slider to a value between `min` and `max`.
+    pub fn value_at(offset: usize, width: usize, min: f64, max: f64) -> f64 {
+        if width <= 1 {
+            return min;
+        }
+        let offset = offset.min(width - 1) as f64;
+        min + (max - min) * offset / (width - 1) as f64
+    }
+
+    fn thumb_offset(value: f64, width: usize, min: f64, max: f64) -> usize {
+        if width == 0 || max <= min {
+            return 0;
+        }
+        let value = value.clamp(min, max);
+        ((value - min) / (max - min) * (width - 1) as f64).round() as usize
+    }
+}
+
+impl Widget for Slider {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(VALUE, AttributeType::Float, None)
+            .attribute(MIN, AttributeType::Float, Some("0.0"))
+            .attribute(MAX, AttributeType::Float, Some("1.0"))
+            .attribute(TRACK, AttributeType::Char, Some("\"─\""))
+            .attribute(THUMB, AttributeType::Char, Some("\"█\""))
+            .attribute(WIDTH, AttributeType::Int, Some("20"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+
+        let width = match attributes.get_usize(WIDTH) {
+            Some(width) => width.min(constraints.max_width()),
+            None if constraints.is_width_unbounded() => DEFAULT_WIDTH,
+            None => constraints.max_width(),
+        };
+        let width = width.max(constraints.min_width);
+
+        let min = attributes.get_float(MIN).unwrap_or(0.0);
+        let max = attributes.get_float(MAX).unwrap_or(1.0);
+        let value = attributes.get_float(VALUE).unwrap_or(min);
+        self.thumb_offset = Self::thumb_offset(value, width, min, max);
+
+        self.track = attributes
+            .get_ref::<&str>(TRACK)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(DEFAULT_TRACK);
+        self.thumb = attributes
+            .get_ref::<&str>(THUMB)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(DEFAULT_THUMB);
+
+        Size::new(width, 1.max(constraints.min_height).min(constraints.max_height()))
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The slider has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        for x in 0..ctx.local_size.width {
+            let c = match x == self.thumb_offset {
+                true => self.thumb,
+                false => self.track,
+            };
+            ctx.place_glyph(c, LocalPos::new(x as u16, 0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn slider_thumb_position() {
+        let tpl = "slider [value: 5, min: 0, max: 10, width: 5]";
+        let expected = "
+            ╔═════╗
+            ║──█──║
+            ║     ║
+            ╚═════╝
+        ";
+        TestRunner::new(tpl, (5, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn slider_custom_glyphs() {
+        let tpl = "slider [value: 10, max: 10, track: '-', thumb: '|']";
+        let expected = "
+            ╔════╗
+            ║---|║
+            ╚════╝
+        ";
+        TestRunner::new(tpl, (4, 1)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn value_from_offset() {
+        assert_eq!(Slider::value_at(0, 5, 0.0, 10.0), 0.0);
+        assert_eq!(Slider::value_at(2, 5, 0.0, 10.0), 5.0);
+        assert_eq!(Slider::value_at(9, 5, 0.0, 10.0), 10.0);
+    }
+}
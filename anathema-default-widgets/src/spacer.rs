@@ -1,12 +1,18 @@
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
 use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 #[derive(Debug, Default)]
 pub struct Spacer;
 
 impl Widget for Spacer {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::None))
+    }
+
     fn layout<'bp>(
         &mut self,
         _children: LayoutChildren<'_, '_, 'bp>,
@@ -0,0 +1,371 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{LocalPos, Pos, Size};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::layout::{Axis, AXIS};
+use crate::{MIN_HEIGHT, MIN_WIDTH};
+
+const RATIO: &str = "ratio";
+const DIVIDER: &str = "divider";
+const DEFAULT_RATIO: f32 = 0.5;
+const HORZ_DIVIDER: char = '│';
+const VERT_DIVIDER: char = '─';
+
+/// Split the space between two children, with a divider in between.
+///
+/// The `axis` decides the placement of the panes: `horizontal` (default) places the panes
+/// side by side, and `vertical` places the second pane below the first one.
+///
+/// `ratio` is the fraction of the space given to the first pane (defaults to `0.5`).
+/// A pane is never made smaller than the `min_width` / `min_height` of its child.
+///
+/// ```text
+/// splitpane [axis: 'horizontal', ratio: state.ratio, divider: '|']
+///     text 'left'
+///     text 'right'
+/// ```
+///
+/// The divider can be moved with [`SplitPane::handle_key`], or [`SplitPane::ratio_at`]
+/// for the mouse. Both return the new ratio, which should be written back to the state
+/// `ratio` is bound to. Until `ratio` changes the widget keeps the new ratio.
+#[derive(Debug, Default)]
+pub struct SplitPane {
+    axis: Option<Axis>,
+    // Ratio set by resizing the panes
+    ratio: Option<f32>,
+    // The last value of the `ratio` attribute
+    bound: Option<f32>,
+    // Size of the first pane along the axis
+    first: usize,
+    // Space along the axis, excluding the divider
+    available: usize,
+    pos: Pos,
+    size: Size,
+    is_dirty: bool,
+}
+
+impl SplitPane {
+    /// The ratio of the space given to the first pane
+    pub fn ratio(&self) -> f32 {
+        match self.available {
+            0 => 0.0,
+            available => self.first as f32 / available as f32,
+        }
+    }
+
+    /// Set the ratio of the space given to the first pane
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = Some(ratio.clamp(0.0, 1.0));
+        self.is_dirty = true;
+    }
+
+    /// Move the divider by a number of cells.
+    /// Returns the new ratio.
+    pub fn move_divider(&mut self, cells: isize) -> f32 {
+        if self.available == 0 {
+            return 0.0;
+        }
+        let first = (self.first as isize + cells).clamp(0, self.available as isize);
+        let ratio = first as f32 / self.available as f32;
+        self.set_ratio(ratio);
+        ratio
+    }
+
+    /// Apply the default key bindings:
+    /// * `Ctrl+Left` / `Ctrl+Up`: move the divider towards the first pane
+    /// * `Ctrl+Right` / `Ctrl+Down`: move the divider towards the second pane
+    ///
+    /// Returns the new ratio if the key was handled.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<f32> {
+        if !key.ctrl || matches!(key.state, KeyState::Release) {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Left | KeyCode::Up => Some(self.move_divider(-1)),
+            KeyCode::Right | KeyCode::Down => Some(self.move_divider(1)),
+            _ => None,
+        }
+    }
+
+    /// Move the divider to a screen position (e.g. while dragging the divider with the mouse).
+    /// Returns the new ratio.
+    pub fn ratio_at(&mut self, pos: impl Into<Pos>) -> f32 {
+        let pos = pos.into();
+        let offset = match self.axis.unwrap_or(Axis::Horizontal) {
+            Axis::Horizontal => pos.x - self.pos.x,
+            Axis::Vertical => pos.y - self.pos.y,
+        };
+        self.move_divider(offset as isize - self.first as isize)
+    }
+
+    fn first_size(ratio: f32, available: usize, min_first: usize, min_second: usize) -> usize {
+        let first = (available as f32 * ratio.clamp(0.0, 1.0)).round() as usize;
+        first
+            .min(available.saturating_sub(min_second))
+            .max(min_first)
+            .min(available)
+    }
+}
+
+impl Widget for SplitPane {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(
+                "axis",
+                AttributeType::Enum(&["horizontal", "vertical"]),
+                Some("\"horizontal\""),
+            )
+            .attribute(RATIO, AttributeType::Float, Some("0.5"))
+            .attribute(DIVIDER, AttributeType::Char, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let axis = attributes.get(AXIS).unwrap_or(Axis::Horizontal);
+        self.axis = Some(axis);
+        self.is_dirty = false;
+
+        // A new value for the attribute replaces the ratio from resizing
+        let bound = attributes.get_float(RATIO).map(|ratio| ratio as f32);
+        if bound != self.bound {
+            self.bound = bound;
+            self.ratio = None;
+        }
+        let ratio = self.ratio.or(self.bound).unwrap_or(DEFAULT_RATIO);
+
+        let mut mins = [0; 2];
+        let mut index = 0;
+        children.for_each(|child, _| {
+            let attributes = ctx.attribs.get(child.id());
+            mins[index] = match axis {
+                Axis::Horizontal => attributes.get_usize(MIN_WIDTH),
+                Axis::Vertical => attributes.get_usize(MIN_HEIGHT),
+            }
+            .unwrap_or(0);
+            index += 1;
+            match index < mins.len() {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+
+        self.size = constraints.max_size();
+        let (main, cross) = match axis {
+            Axis::Horizontal => (self.size.width, self.size.height),
+            Axis::Vertical => (self.size.height, self.size.width),
+        };
+        self.available = main.saturating_sub(1);
+        self.first = Self::first_size(ratio, self.available, mins[0], mins[1]);
+
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let main = match index {
+                0 => self.first,
+                1 => self.available - self.first,
+                // Only two children are shown
+                _ => 0,
+            };
+            let size = match axis {
+                Axis::Horizontal => Size::new(main, cross),
+                Axis::Vertical => Size::new(cross, main),
+            };
+
+            let mut constraints = Constraints::new(size.width, size.height);
+            constraints.make_width_tight(size.width);
+            constraints.make_height_tight(size.height);
+            child.layout(children, constraints, ctx);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+
+        self.size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        self.pos = ctx.pos;
+        let offset = self.first as i32 + 1;
+        let second = match self.axis.unwrap_or(Axis::Horizontal) {
+            Axis::Horizontal => Pos::new(ctx.pos.x + offset, ctx.pos.y),
+            Axis::Vertical => Pos::new(ctx.pos.x, ctx.pos.y + offset),
+        };
+
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let pos = match index {
+                1 => second,
+                _ => ctx.pos,
+            };
+            child.position(children, pos, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            index += 1;
+            match index < 2 {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+
+        let axis = self.axis.unwrap_or(Axis::Horizontal);
+        let divider = attribute_storage
+            .get(id)
+            .get_ref::<&str>(DIVIDER)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(match axis {
+                Axis::Horizontal => HORZ_DIVIDER,
+                Axis::Vertical => VERT_DIVIDER,
+            });
+
+        match axis {
+            Axis::Horizontal => {
+                for y in 0..self.size.height {
+                    ctx.place_glyph(divider, LocalPos::new(self.first as u16, y as u16));
+                }
+            }
+            Axis::Vertical => {
+                for x in 0..self.size.width {
+                    ctx.place_glyph(divider, LocalPos::new(x as u16, self.first as u16));
+                }
+            }
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn split_horizontally() {
+        let tpl = "
+            splitpane
+                text 'a'
+                text 'b'
+        ";
+
+        let expected = "
+            ╔═══════╗
+            ║a  │b  ║
+            ║   │   ║
+            ╚═══════╝
+        ";
+
+        TestRunner::new(tpl, (7, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn split_vertically() {
+        let tpl = "
+            splitpane [axis: 'vertical', ratio: 0.25]
+                text 'a'
+                text 'b'
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║a  ║
+            ║───║
+            ║b  ║
+            ║   ║
+            ║   ║
+            ╚═══╝
+        ";
+
+        TestRunner::new(tpl, (3, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn clamp_to_min_size() {
+        let tpl = "
+            splitpane [ratio: 0.9]
+                text 'a'
+                text [min_width: 4] 'b'
+        ";
+
+        // The second pane needs at least 4 cells
+        let expected = "
+            ╔════════╗
+            ║a  │b   ║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 1)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn move_divider_with_keys() {
+        let tpl = "
+            splitpane
+                text 'a'
+                text 'b'
+        ";
+
+        let key = KeyEvent {
+            code: KeyCode::Right,
+            ctrl: true,
+            state: KeyState::Press,
+        };
+
+        TestRunner::new(tpl, (5, 1))
+            .instance()
+            .render_assert(
+                "
+                ╔═════╗
+                ║a │b ║
+                ╚═════╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("splitpane").first(|el, _| {
+                    let ratio = el.to::<SplitPane>().handle_key(key);
+                    assert_eq!(ratio, Some(0.75));
+                })
+            })
+            .render_assert(
+                "
+                ╔═════╗
+                ║a  │b║
+                ╚═════╝
+            ",
+            );
+    }
+}
@@ -2,7 +2,9 @@ use std::ops::ControlFlow;
 
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use crate::layout::Axis;
 use crate::stacks::Stack;
@@ -11,11 +13,39 @@ pub struct Column(Stack);
 
 impl Default for Column {
     fn default() -> Self {
-        Self(Stack(Axis::Vertical))
+        Self(Stack::new(Axis::Vertical))
+    }
+}
+
+impl Column {
+    /// Scroll the children up / down by a number of cells.
+    /// This only applies to columns with `overflow: 'scroll'`.
+    pub fn scroll_by(&mut self, amount: isize) {
+        self.0.scroll_by(amount);
+    }
+
+    /// Scroll to an offset from the start of the children.
+    /// This only applies to columns with `overflow: 'scroll'`.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.0.scroll_to(offset);
+    }
+
+    /// The current scroll offset
+    pub fn offset(&self) -> usize {
+        self.0.offset
     }
 }
 
 impl Widget for Column {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many).attribute(
+            "overflow",
+            AttributeType::Enum(&["hide", "clip", "visible", "error", "scroll"]),
+            Some("\"hide\""),
+        );
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -34,6 +64,7 @@ impl Widget for Column {
         mut ctx: PositionCtx,
     ) {
         let x_offset = (ctx.inner_size.width / 2) as i32;
+        ctx.pos.y -= self.0.scroll_offset();
 
         children.for_each(|child, children| {
             let size = child.size();
@@ -48,11 +79,15 @@ impl Widget for Column {
             ControlFlow::Continue(())
         });
     }
+
+    fn needs_reflow(&self) -> bool {
+        self.0.is_dirty
+    }
 }
 
 #[cfg(test)]
 mod test {
-
+    use super::Column;
     use crate::testing::TestRunner;
 
     #[test]
@@ -77,4 +112,40 @@ mod test {
 
         TestRunner::new(tpl, (3, 5)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn column_overflow_scroll() {
+        let tpl = "
+            column [overflow: 'scroll']
+                text 'a'
+                text 'b'
+                text 'c'
+        ";
+
+        let expected = "
+            ╔═╗
+            ║b║
+            ║c║
+            ╚═╝
+        ";
+
+        TestRunner::new(tpl, (1, 2))
+            .instance()
+            .render_assert(
+                "
+                ╔═╗
+                ║a║
+                ║b║
+                ╚═╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("column").first(|el, _| {
+                    let column = el.to::<Column>();
+                    column.scroll_by(10);
+                    assert_eq!(column.offset(), 1);
+                })
+            })
+            .render_assert(expected);
+    }
 }
@@ -1,6 +1,8 @@
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use super::Stack;
 use crate::layout::Axis;
@@ -9,11 +11,49 @@ pub struct HStack(Stack);
 
 impl Default for HStack {
     fn default() -> Self {
-        HStack(Stack(Axis::Horizontal))
+        HStack(Stack::new(Axis::Horizontal))
+    }
+}
+
+impl HStack {
+    /// Scroll the children left / right by a number of cells.
+    /// This only applies to stacks with `overflow: 'scroll'`.
+    pub fn scroll_by(&mut self, amount: isize) {
+        self.0.scroll_by(amount);
+    }
+
+    /// Scroll to an offset from the start of the children.
+    /// This only applies to stacks with `overflow: 'scroll'`.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.0.scroll_to(offset);
+    }
+
+    /// The current scroll offset
+    pub fn offset(&self) -> usize {
+        self.0.offset
     }
 }
 
 impl Widget for HStack {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(
+                "direction",
+                AttributeType::Enum(&["forward", "backward"]),
+                Some("\"forward\""),
+            )
+            .attribute(
+                "overflow",
+                AttributeType::Enum(&["hide", "clip", "visible", "error", "scroll"]),
+                Some("\"hide\""),
+            )
+            .attribute("width", AttributeType::Int, None)
+            .attribute("height", AttributeType::Int, None)
+            .attribute("min_width", AttributeType::Int, None)
+            .attribute("min_height", AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -33,4 +73,90 @@ impl Widget for HStack {
     ) {
         self.0.position(children, attributes, attribute_storage, ctx)
     }
+
+    fn needs_reflow(&self) -> bool {
+        self.0.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn flex() {
+        let tpl = "
+            hstack
+                text 'ab'
+                border [flex: 1]
+                    text 'x'
+                border [flex: 3]
+                    text 'y'
+        ";
+
+        let expected = "
+            ╔════════════╗
+            ║ab┌─┐┌─────┐║
+            ║  │x││y    │║
+            ║  └─┘└─────┘║
+            ╚════════════╝
+        ";
+
+        TestRunner::new(tpl, (12, 3)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn right_to_left() {
+        let tpl = "
+            hstack [direction: 'rtl']
+                text 'one'
+                text 'two'
+        ";
+
+        let expected = "
+            ╔════════╗
+            ║  twoone║
+            ╚════════╝
+        ";
+
+        TestRunner::new(tpl, (8, 1)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn overflow() {
+        let tpl = "
+            vstack [width: 4]
+                hstack [width: 2, overflow: 'clip']
+                    text 'abcd'
+                hstack [width: 2, overflow: 'visible']
+                    text 'abcd'
+        ";
+
+        let expected = "
+            ╔════╗
+            ║ab  ║
+            ║abcd║
+            ╚════╝
+        ";
+
+        TestRunner::new(tpl, (4, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn flex_without_space() {
+        let tpl = "
+            hstack
+                text 'ab'
+                text [flex: 1] 'x'
+                text [flex: 1] 'y'
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║abx║
+            ╚═══╝
+        ";
+
+        TestRunner::new(tpl, (3, 1)).instance().render_assert(expected);
+    }
 }
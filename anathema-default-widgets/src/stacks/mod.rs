@@ -1,7 +1,8 @@
 use std::ops::ControlFlow;
 
+use anathema::CommonVal;
 use anathema_geometry::Size;
-use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::layout::{Constraints, LayoutCtx, LayoutError, PositionCtx};
 use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, WidgetId};
 
 pub use self::column::Column;
@@ -19,9 +20,81 @@ mod row;
 mod vstack;
 mod zstack;
 
-pub struct Stack(Axis);
+const OVERFLOW: &str = "overflow";
+
+/// What happens to children of a stack that don't fit.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum StackOverflow {
+    /// Children that don't fit are not shown,
+    /// and the last visible child is given what space is left.
+    #[default]
+    Hide,
+    /// Children keep their size and are cut off at the edge of the stack.
+    Clip,
+    /// Children keep their size and are painted outside of the stack.
+    Visible,
+    /// Same as `Clip`, and reports a layout error if the children don't fit.
+    Error,
+    /// Children keep their size and can be scrolled into view.
+    Scroll,
+}
+
+impl TryFrom<CommonVal<'_>> for StackOverflow {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, ()> {
+        match value.to_common_str().as_ref() {
+            "hide" => Ok(Self::Hide),
+            "clip" => Ok(Self::Clip),
+            "visible" => Ok(Self::Visible),
+            "error" => Ok(Self::Error),
+            "scroll" => Ok(Self::Scroll),
+            _ => Err(()),
+        }
+    }
+}
+
+pub struct Stack {
+    axis: Axis,
+    overflow: StackOverflow,
+    // Scroll offset along the axis
+    offset: usize,
+    // Size of the children along the axis
+    content: usize,
+    // Size of the stack along the axis
+    visible: usize,
+    is_dirty: bool,
+}
 
 impl Stack {
+    fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            overflow: StackOverflow::Hide,
+            offset: 0,
+            content: 0,
+            visible: 0,
+            is_dirty: false,
+        }
+    }
+
+    fn scroll_to(&mut self, offset: usize) {
+        self.offset = offset.min(self.content.saturating_sub(self.visible));
+        self.is_dirty = true;
+    }
+
+    fn scroll_by(&mut self, amount: isize) {
+        self.scroll_to(self.offset.saturating_add_signed(amount));
+    }
+
+    // The distance the children are moved back along the axis
+    fn scroll_offset(&self) -> i32 {
+        match self.overflow {
+            StackOverflow::Scroll => self.offset as i32,
+            _ => 0,
+        }
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -32,11 +105,11 @@ impl Stack {
         let attributes = ctx.attribs.get(id);
 
         if let Some(width) = attributes.get_usize(MIN_WIDTH) {
-            constraints.min_width = width;
+            constraints.set_min_width(width);
         }
 
         if let Some(height) = attributes.get_usize(MIN_HEIGHT) {
-            constraints.min_height = height;
+            constraints.set_min_height(height);
         }
 
         if let Some(width) = attributes.get_usize(WIDTH) {
@@ -48,10 +121,43 @@ impl Stack {
         }
 
         let dir = attributes.get(DIRECTION).unwrap_or_default();
-        // Make `unconstrained` an enum instead of a `bool`
-        let unconstrained = false;
-        let mut many = Many::new(dir, self.0, unconstrained);
-        many.layout(children, constraints, ctx)
+        self.overflow = attributes.get(OVERFLOW).unwrap_or_default();
+        self.is_dirty = false;
+
+        if let StackOverflow::Hide = self.overflow {
+            let mut many = Many::new(dir, self.axis, false);
+            return many.layout(children, constraints, ctx);
+        }
+
+        // Lay out the children without limiting the axis,
+        // so children keep their size even if they don't fit
+        let max = constraints;
+        match self.axis {
+            Axis::Horizontal => constraints.unbound_width(),
+            Axis::Vertical => constraints.unbound_height(),
+        }
+        let mut many = Many::new(dir, self.axis, true);
+        let mut size = many.layout(children, constraints, ctx);
+
+        let (content, max_size) = match self.axis {
+            Axis::Horizontal => (&mut size.width, max.max_width()),
+            Axis::Vertical => (&mut size.height, max.max_height()),
+        };
+
+        if StackOverflow::Error == self.overflow && *content > max_size {
+            let error = LayoutError::Overflow {
+                needed: *content,
+                available: max_size,
+            };
+            ctx.report(id, error);
+        }
+
+        self.content = *content;
+        *content = (*content).min(max_size);
+        self.visible = *content;
+        self.offset = self.offset.min(self.content.saturating_sub(self.visible));
+
+        size
     }
 
     fn position<'bp>(
@@ -65,8 +171,18 @@ impl Stack {
         let direction = attributes.get(DIRECTION).unwrap_or_default();
         let mut pos = ctx.pos;
 
+        if let StackOverflow::Scroll = self.overflow {
+            let offset = self.offset as i32;
+            match (self.axis, direction) {
+                (Axis::Horizontal, Direction::Forward) => pos.x -= offset,
+                (Axis::Horizontal, Direction::Backward) => pos.x += offset,
+                (Axis::Vertical, Direction::Forward) => pos.y -= offset,
+                (Axis::Vertical, Direction::Backward) => pos.y += offset,
+            }
+        }
+
         if let Direction::Backward = direction {
-            match self.0 {
+            match self.axis {
                 Axis::Horizontal => pos.x += ctx.inner_size.width as i32,
                 Axis::Vertical => pos.y += ctx.inner_size.height as i32,
             }
@@ -77,14 +193,14 @@ impl Stack {
                 Direction::Forward => {
                     node.position(children, pos, attribute_storage, ctx.viewport);
 
-                    match self.0 {
+                    match self.axis {
                         Axis::Horizontal => pos.x += node.size().width as i32,
                         Axis::Vertical => pos.y += node.size().height as i32,
                     }
                 }
                 Direction::Backward => {
-                    match self.0 {
-                        Axis::Horizontal => pos.x += node.size().width as i32,
+                    match self.axis {
+                        Axis::Horizontal => pos.x -= node.size().width as i32,
                         Axis::Vertical => pos.y -= node.size().height as i32,
                     }
 
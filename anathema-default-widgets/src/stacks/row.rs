@@ -2,7 +2,9 @@ use std::ops::ControlFlow;
 
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use crate::layout::Axis;
 use crate::stacks::Stack;
@@ -11,11 +13,39 @@ pub struct Row(Stack);
 
 impl Default for Row {
     fn default() -> Self {
-        Self(Stack(Axis::Horizontal))
+        Self(Stack::new(Axis::Horizontal))
+    }
+}
+
+impl Row {
+    /// Scroll the children left / right by a number of cells.
+    /// This only applies to rows with `overflow: 'scroll'`.
+    pub fn scroll_by(&mut self, amount: isize) {
+        self.0.scroll_by(amount);
+    }
+
+    /// Scroll to an offset from the start of the children.
+    /// This only applies to rows with `overflow: 'scroll'`.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.0.scroll_to(offset);
+    }
+
+    /// The current scroll offset
+    pub fn offset(&self) -> usize {
+        self.0.offset
     }
 }
 
 impl Widget for Row {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many).attribute(
+            "overflow",
+            AttributeType::Enum(&["hide", "clip", "visible", "error", "scroll"]),
+            Some("\"hide\""),
+        );
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -34,6 +64,7 @@ impl Widget for Row {
         mut ctx: PositionCtx,
     ) {
         let y_offset = (ctx.inner_size.height / 2) as i32;
+        ctx.pos.x -= self.0.scroll_offset();
 
         children.for_each(|child, children| {
             let size = child.size();
@@ -47,11 +78,15 @@ impl Widget for Row {
             ControlFlow::Continue(())
         });
     }
+
+    fn needs_reflow(&self) -> bool {
+        self.0.is_dirty
+    }
 }
 
 #[cfg(test)]
 mod test {
-
+    use super::Row;
     use crate::testing::TestRunner;
 
     #[test]
@@ -73,4 +108,37 @@ mod test {
 
         TestRunner::new(tpl, (4, 3)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn row_overflow_scroll() {
+        let tpl = "
+            row [overflow: 'scroll']
+                text 'ab'
+                text 'cd'
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║bcd║
+            ╚═══╝
+        ";
+
+        TestRunner::new(tpl, (3, 1))
+            .instance()
+            .render_assert(
+                "
+                ╔═══╗
+                ║abc║
+                ╚═══╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("row").first(|el, _| {
+                    let row = el.to::<Row>();
+                    row.scroll_by(10);
+                    assert_eq!(row.offset(), 1);
+                })
+            })
+            .render_assert(expected);
+    }
 }
@@ -1,6 +1,8 @@
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
 use super::Stack;
 use crate::layout::Axis;
@@ -9,11 +11,49 @@ pub struct VStack(Stack);
 
 impl Default for VStack {
     fn default() -> Self {
-        VStack(Stack(Axis::Vertical))
+        VStack(Stack::new(Axis::Vertical))
+    }
+}
+
+impl VStack {
+    /// Scroll the children up / down by a number of cells.
+    /// This only applies to stacks with `overflow: 'scroll'`.
+    pub fn scroll_by(&mut self, amount: isize) {
+        self.0.scroll_by(amount);
+    }
+
+    /// Scroll to an offset from the start of the children.
+    /// This only applies to stacks with `overflow: 'scroll'`.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.0.scroll_to(offset);
+    }
+
+    /// The current scroll offset
+    pub fn offset(&self) -> usize {
+        self.0.offset
     }
 }
 
 impl Widget for VStack {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(
+                "direction",
+                AttributeType::Enum(&["forward", "backward"]),
+                Some("\"forward\""),
+            )
+            .attribute(
+                "overflow",
+                AttributeType::Enum(&["hide", "clip", "visible", "error", "scroll"]),
+                Some("\"hide\""),
+            )
+            .attribute("width", AttributeType::Int, None)
+            .attribute("height", AttributeType::Int, None)
+            .attribute("min_width", AttributeType::Int, None)
+            .attribute("min_height", AttributeType::Int, None);
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
@@ -33,11 +73,18 @@ impl Widget for VStack {
     ) {
         self.0.position(children, attributes, attribute_storage, ctx)
     }
+
+    fn needs_reflow(&self) -> bool {
+        self.0.is_dirty
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use anathema_widgets::error::Error;
+    use anathema_widgets::layout::LayoutError;
 
+    use super::*;
     use crate::testing::TestRunner;
 
     #[test]
@@ -160,4 +207,178 @@ mod test {
 
         TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn vstack_overflow_clip() {
+        let tpl = "
+            vstack [overflow: 'clip']
+                text 'a'
+                border
+                    text 'b'
+        ";
+
+        let expected = "
+            ╔═══╗
+            ║a  ║
+            ║┌─┐║
+            ║│b│║
+            ╚═══╝
+        ";
+
+        TestRunner::new(tpl, (3, 3)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn vstack_overflow_scroll() {
+        let tpl = "
+            vstack [overflow: 'scroll']
+                text 'a'
+                text 'b'
+                text 'c'
+                text 'd'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║c     ║
+            ║d     ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("vstack").first(|el, _| {
+                    let vstack = el.to::<VStack>();
+                    vstack.scroll_by(10);
+                    assert_eq!(vstack.offset(), 0);
+                })
+            })
+            .render_assert(
+                "
+                ╔══════╗
+                ║a     ║
+                ║b     ║
+                ╚══════╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("vstack").first(|el, _| {
+                    let vstack = el.to::<VStack>();
+                    // The offset is limited to the size of the children
+                    vstack.scroll_by(10);
+                    assert_eq!(vstack.offset(), 2);
+                })
+            })
+            .render_assert(expected);
+    }
+
+    #[test]
+    fn vstack_overflow_error() {
+        let tpl = "
+            vstack [overflow: 'error']
+                text 'a'
+                text 'b'
+                text 'c'
+        ";
+
+        let err = TestRunner::new(tpl, (6, 2)).instance().render_err();
+        let Error::Layout { widget, error, .. } = err else { panic!("{err}") };
+        assert_eq!(widget, "vstack");
+        assert_eq!(
+            error,
+            LayoutError::Overflow {
+                needed: 3,
+                available: 2
+            }
+        );
+    }
+
+    #[test]
+    fn hide_child() {
+        let tpl = "
+            vstack
+                border [hide: value == 1]
+                    text 'a'
+                text 'b'
+        ";
+
+        TestRunner::new(tpl, (3, 4))
+            .instance()
+            .render_assert(
+                "
+                ╔═══╗
+                ║┌─┐║
+                ║│a│║
+                ║└─┘║
+                ║b  ║
+                ╚═══╝
+            ",
+            )
+            .with_state(|state| *state.value.to_mut() = 1)
+            .render_assert(
+                "
+                ╔═══╗
+                ║b  ║
+                ║   ║
+                ║   ║
+                ║   ║
+                ╚═══╝
+            ",
+            )
+            .with_state(|state| *state.value.to_mut() = 0)
+            .render_assert(
+                "
+                ╔═══╗
+                ║┌─┐║
+                ║│a│║
+                ║└─┘║
+                ║b  ║
+                ╚═══╝
+            ",
+            );
+    }
+
+    #[test]
+    fn margin() {
+        let tpl = "
+            vstack
+                border [margin: 1, margin_left: 2]
+                    text 'a'
+                text 'b'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║      ║
+            ║  ┌─┐ ║
+            ║  │a│ ║
+            ║  └─┘ ║
+            ║      ║
+            ║b     ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 6)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn margin_is_outside_min_size() {
+        let tpl = "
+            hstack
+                border [margin_right: 1, min_width: 4]
+                    text 'a'
+                text 'b'
+        ";
+
+        let expected = "
+            ╔═══════╗
+            ║┌──┐ b ║
+            ║│a │   ║
+            ║└──┘   ║
+            ╚═══════╝
+        ";
+
+        TestRunner::new(tpl, (7, 3)).instance().render_assert(expected);
+    }
 }
@@ -2,12 +2,34 @@ use std::ops::ControlFlow;
 
 use anathema_geometry::Size;
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PositionChildren, Widget, WidgetId};
+use anathema_widgets::{
+    AttributeStorage, Children, LayoutChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
 
+use crate::layout::alignment::Alignment;
+
+// Alignment of a child inside the stack
+const ALIGN: &str = "align";
+
+/// Places the children on top of each other, the last child on top.
+///
+/// Children are placed in the top left corner,
+/// or aligned inside the stack with the `align` attribute of the child:
+/// ```text
+/// zstack
+///     expand
+///         text 'background'
+///     border [align: 'centre']
+///         text 'dialog'
+/// ```
 #[derive(Default)]
 pub struct ZStack;
 
 impl Widget for ZStack {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many))
+    }
+
     fn layout<'bp>(
         &mut self,
         mut children: LayoutChildren<'_, '_, 'bp>,
@@ -33,7 +55,9 @@ impl Widget for ZStack {
         ctx: PositionCtx,
     ) {
         children.for_each(|child, children| {
-            child.position(children, ctx.pos, attribute_storage, ctx.viewport);
+            let alignment: Alignment = attribute_storage.get(child.id()).get(ALIGN).unwrap_or_default();
+            let offset = alignment.offset(ctx.inner_size, child.size());
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
             ControlFlow::Continue(())
         });
     }
@@ -61,4 +85,25 @@ mod test {
 
         TestRunner::new(tpl, (3, 1)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn align_children() {
+        let tpl = "
+            zstack
+                expand
+                    text '.'
+                text [align: 'centre'] 'c'
+                text [align: 'bottom_right'] 'r'
+        ";
+
+        let expected = "
+            ╔═════╗
+            ║.    ║
+            ║  c  ║
+            ║    r║
+            ╚═════╝
+        ";
+
+        TestRunner::new(tpl, (5, 3)).instance().render_assert(expected);
+    }
 }
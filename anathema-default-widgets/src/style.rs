@@ -0,0 +1,82 @@
+use anathema::{Color, Hex};
+use anathema_widgets::paint::CellAttributes;
+use anathema_widgets::Attributes;
+
+/// Cell attributes read from the attributes with a prefix,
+/// e.g `selected_foreground` for the prefix `selected`.
+pub(crate) struct Prefixed<'a, 'bp> {
+    prefix: &'static str,
+    attributes: &'a Attributes<'bp>,
+}
+
+impl<'a, 'bp> Prefixed<'a, 'bp> {
+    pub(crate) fn new(prefix: &'static str, attributes: &'a Attributes<'bp>) -> Self {
+        Self { prefix, attributes }
+    }
+
+    /// Returns true if there are no attributes with the prefix
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.attributes.iter().any(|(key, _)| {
+            key.to_str()
+                .strip_prefix(self.prefix)
+                .is_some_and(|key| key.starts_with('_'))
+        })
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}_{key}", self.prefix)
+    }
+}
+
+impl CellAttributes for Prefixed<'_, '_> {
+    fn with_str(&self, key: &str, f: &mut dyn FnMut(&str)) {
+        self.attributes.with_str(&self.key(key), f)
+    }
+
+    fn get_i64(&self, key: &str) -> Option<i64> {
+        self.attributes.get_i64(&self.key(key))
+    }
+
+    fn get_u8(&self, key: &str) -> Option<u8> {
+        self.attributes.get_u8(&self.key(key))
+    }
+
+    fn get_hex(&self, key: &str) -> Option<Hex> {
+        self.attributes.get_hex(&self.key(key))
+    }
+
+    fn get_color(&self, key: &str) -> Option<Color> {
+        self.attributes.get_color(&self.key(key))
+    }
+
+    fn get_bool(&self, key: &str) -> bool {
+        CellAttributes::get_bool(self.attributes, &self.key(key))
+    }
+}
+
+/// Inverted cells, used to highlight a selection if no style is given
+pub(crate) struct Inverse;
+
+impl CellAttributes for Inverse {
+    fn with_str(&self, _: &str, _: &mut dyn FnMut(&str)) {}
+
+    fn get_i64(&self, _: &str) -> Option<i64> {
+        None
+    }
+
+    fn get_u8(&self, _: &str) -> Option<u8> {
+        None
+    }
+
+    fn get_hex(&self, _: &str) -> Option<Hex> {
+        None
+    }
+
+    fn get_color(&self, _: &str) -> Option<Color> {
+        None
+    }
+
+    fn get_bool(&self, key: &str) -> bool {
+        key == "inverse"
+    }
+}
@@ -1,15 +1,22 @@
 use anathema::{drain_changes, Changes};
 use anathema_backend::test::TestBackend;
+use anathema_backend::tui::Style;
 use anathema_backend::{Backend, WidgetCycle};
-use anathema_geometry::Size;
+use anathema_geometry::{Pos, Size};
 use anathema_state::{State, StateId, States, Value};
 use anathema_templates::blueprints::Blueprint;
 use anathema_templates::{Document, Globals, ToSourceKind};
-use anathema_widgets::components::ComponentRegistry;
+use anathema_widgets::components::events::{Event, InputSettings, KeyCode, KeyEvent, KeyState};
+use anathema_widgets::components::tasks::Tasks;
+use anathema_widgets::components::{
+    AnyEventCtx, AssociatedEvents, Component, ComponentContext, ComponentRegistry, Emitter, FocusQueue, OverlayQueue,
+    UntypedContext,
+};
+use anathema_widgets::error::{Error, Result};
 use anathema_widgets::layout::{Constraints, Viewport};
 use anathema_widgets::{
     eval_blueprint, update_tree, AttributeStorage, Components, DirtyWidgets, Elements, EvalContext, Factory,
-    FloatingWidgets, Scope, WidgetRenderer as _, WidgetTree,
+    FloatingWidgets, Scope, WidgetKind, WidgetRenderer as _, WidgetTree,
 };
 
 use crate::register_default_widgets;
@@ -19,8 +26,9 @@ pub struct TestRunner {
     component_registry: ComponentRegistry,
     factory: Factory,
     backend: TestBackend,
-    blueprint: Blueprint,
-    globals: Globals,
+    document: Document,
+    // Compiled on the first instance, so components can be registered after `new`
+    compiled: Option<(Blueprint, Globals)>,
     components: Components,
 }
 
@@ -48,29 +56,51 @@ impl TestRunner {
         let main = doc.add_component("main", src.to_template()).unwrap();
         component_registry.add_component(main.into(), (), ());
 
-        let (blueprint, globals) = doc.compile().unwrap();
-
         Self {
             factory,
             backend: TestBackend::new(size),
             states,
             component_registry,
-            blueprint,
-            globals,
+            document: doc,
+            compiled: None,
             components: Components::new(),
         }
     }
 
+    pub fn factory(&mut self) -> &mut Factory {
+        &mut self.factory
+    }
+
+    /// Register a component that can be used by the template, e.g `@radio { selected: value }`
+    pub fn register_component<C: Component + 'static>(
+        &mut self,
+        name: &str,
+        template: &str,
+        component: C,
+        state: C::State,
+    ) -> &mut Self {
+        let id = self.document.add_component(name, template.to_template()).unwrap();
+        self.component_registry.add_component(id.into(), component, state);
+        self.compiled = None;
+        self
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: Size) {
+        self.backend.cell_size = cell_size;
+    }
+
     pub fn instance(&mut self) -> TestInstance<'_> {
+        let (blueprint, globals) = self.compiled.get_or_insert_with(|| self.document.compile().unwrap());
         let mut tree = WidgetTree::empty();
         let mut attribute_storage = AttributeStorage::empty();
         let mut floating_widgets = FloatingWidgets::empty();
-        let viewport = Viewport::new(self.backend.surface.size());
+        let mut viewport = Viewport::new(self.backend.surface.size());
+        viewport.set_cell_size(self.backend.cell_size);
 
         let mut scope = Scope::new();
         scope.insert_state(StateId::ZERO);
         let mut ctx = EvalContext::new(
-            &self.globals,
+            globals,
             &self.factory,
             &mut scope,
             &mut self.states,
@@ -80,12 +110,13 @@ impl TestRunner {
             &mut self.components,
         );
 
-        eval_blueprint(&self.blueprint, &mut ctx, &[], &mut tree).unwrap();
+        eval_blueprint(blueprint, &mut ctx, &[], &mut tree).unwrap();
 
         TestInstance {
             states: &mut self.states,
             backend: &mut self.backend,
-            globals: &self.globals,
+            document: &self.document,
+            globals,
             floating_widgets,
             tree,
             attribute_storage,
@@ -104,6 +135,7 @@ pub struct TestInstance<'bp> {
     attribute_storage: AttributeStorage<'bp>,
     floating_widgets: FloatingWidgets,
     states: &'bp mut States,
+    document: &'bp Document,
     globals: &'bp Globals,
     backend: &'bp mut TestBackend,
     viewport: Viewport,
@@ -123,6 +155,79 @@ impl TestInstance<'_> {
         let state = state.to_any_mut().downcast_mut::<TestState>().unwrap();
         f(state);
 
+        self.apply_changes();
+        self
+    }
+
+    /// Send a key press to a component, registered with [`TestRunner::register_component`]
+    pub fn send_key(&mut self, component: &str, code: KeyCode) -> &mut Self {
+        let id = self
+            .document
+            .component_id(component)
+            .expect("component is not registered");
+        let entry = self
+            .components
+            .get_by_component_id(id)
+            .expect("component is not in the tree");
+        let (widget_id, state_id) = (entry.widget_id, entry.state_id);
+
+        let (sender, _receiver) = flume::unbounded();
+        let emitter = Emitter::from(sender);
+        let tasks = Tasks::new();
+        let context = UntypedContext {
+            emitter: &emitter,
+            tasks: &tasks,
+            viewport: self.viewport,
+            strings: &self.document.strings,
+            input: InputSettings::default(),
+        };
+        let mut assoc_events = AssociatedEvents::new();
+        let mut focus_queue = FocusQueue::new();
+        let mut overlay_queue = OverlayQueue::new();
+        let mut exit = None;
+
+        let event = Event::Key(KeyEvent {
+            code,
+            ctrl: false,
+            state: KeyState::Press,
+        });
+
+        self.tree.with_value_mut(widget_id, |path, widget, tree| {
+            let WidgetKind::Component(component) = widget else { return };
+            let Some((node, values)) = tree.get_node_by_path(path) else { return };
+            let elements = Elements::new(
+                node.children(),
+                values,
+                &mut self.attribute_storage,
+                &mut self.dirty_widgets,
+            );
+            let component_ctx = ComponentContext::new(
+                state_id,
+                widget_id,
+                component.component_id,
+                component.parent,
+                component.assoc_functions,
+                &mut assoc_events,
+                &mut focus_queue,
+                &mut overlay_queue,
+                &mut exit,
+                component.external_state.as_ref(),
+            );
+            let ctx = AnyEventCtx {
+                state: self.states.get_mut(state_id),
+                elements,
+                context,
+                component_ctx,
+            };
+            component.dyn_component.any_event(ctx, event);
+        });
+
+        self.apply_changes();
+        self
+    }
+
+    // Update the tree with the changes to the state
+    fn apply_changes(&mut self) {
         let mut scope = Scope::new();
         drain_changes(&mut self.changes);
         self.changes.iter().for_each(|(sub, change)| {
@@ -144,19 +249,36 @@ impl TestInstance<'_> {
                 );
             })
         });
-
-        self
     }
 
     pub fn render_assert(&mut self, expected: &str) -> &mut Self {
         let expected = expected.trim().lines().map(str::trim).collect::<Vec<_>>().join("\n");
 
+        if let Err(err) = self.render() {
+            panic!("{err}");
+        }
+
+        let actual = std::mem::take(&mut self.backend.output);
+        let actual = actual.trim().lines().map(str::trim).collect::<Vec<_>>().join("\n");
+
+        eprintln!("{actual}");
+
+        assert_eq!(actual, expected);
+        self
+    }
+
+    /// Render the tree, expecting the layout to fail
+    pub fn render_err(&mut self) -> Error {
+        self.render().expect_err("the layout did not fail")
+    }
+
+    fn render(&mut self) -> Result<()> {
         let (width, height) = self.backend.surface.size().into();
         let constraints = Constraints::new(width as usize, height as usize);
 
         let attribute_storage = &self.attribute_storage;
 
-        WidgetCycle::new(
+        let res = WidgetCycle::new(
             self.backend,
             &mut self.tree,
             constraints,
@@ -167,13 +289,31 @@ impl TestInstance<'_> {
         .run();
 
         self.backend.render();
+        // Same as the runtime: clear the surface for the next frame.
+        // Without this, glyphs from the previous frame are left on the surface
+        // and a widget that was hidden, moved or removed is still in the output.
+        self.backend.clear();
+        res
+    }
 
-        let actual = std::mem::take(&mut self.backend.output);
-        let actual = actual.trim().lines().map(str::trim).collect::<Vec<_>>().join("\n");
-
-        eprintln!("{actual}");
+    /// The style of a cell after the last call to [`TestInstance::render_assert`].
+    /// The position includes the border around the template.
+    pub fn style(&self, pos: impl Into<Pos>) -> Style {
+        self.backend.style(pos)
+    }
 
-        assert_eq!(actual, expected);
+    // The elements of the whole tree, including the border and the main component
+    pub(crate) fn with_root<F>(&mut self, mut f: F) -> &mut Self
+    where
+        F: FnMut(Elements<'_, '_>),
+    {
+        let (nodes, values) = self.tree.split();
+        f(Elements::new(
+            nodes,
+            values,
+            &mut self.attribute_storage,
+            &mut self.dirty_widgets,
+        ));
         self
     }
 
@@ -1,16 +1,25 @@
 use std::ops::ControlFlow;
 
 use anathema_geometry::{LocalPos, Size};
-use anathema_state::CommonVal;
-use anathema_widgets::layout::text::{ProcessResult, Segment, Strings};
+use anathema_state::{AnsiParser, Color, CommonVal, Hex, SpanStyle};
+use anathema_widgets::layout::bidi::{self, TextDirection};
+use anathema_widgets::layout::text::{ProcessResult, Segment, Strings, Wrap};
 use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
-use anathema_widgets::paint::{PaintCtx, SizePos};
-use anathema_widgets::{AttributeStorage, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId};
-
+use anathema_widgets::paint::{CellAttributes, PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::layout::DIRECTION;
 use crate::{LEFT, RIGHT};
 
 pub(crate) const WRAP: &str = "wrap";
 pub(crate) const TEXT_ALIGN: &str = "text_align";
+const ELLIPSIS: &str = "ellipsis";
+const BIDI: &str = "bidi";
+const PRESERVE_ANSI: &str = "preserve_ansi";
 
 /// Text alignment aligns the text inside its parent.
 ///
@@ -36,6 +45,9 @@ pub enum TextAlignment {
     Centre,
     /// Align the to the right inside the parent
     Right,
+    /// Stretch wrapped lines to the width of the parent by widening the spaces between words.
+    /// The last line of a paragraph is aligned to the left.
+    Justify,
 }
 
 impl TryFrom<CommonVal<'_>> for TextAlignment {
@@ -47,6 +59,7 @@ impl TryFrom<CommonVal<'_>> for TextAlignment {
                 LEFT => Ok(TextAlignment::Left),
                 RIGHT => Ok(TextAlignment::Right),
                 "centre" | "center" => Ok(TextAlignment::Centre),
+                "justify" => Ok(TextAlignment::Justify),
                 _ => Err(()),
             },
             _ => Err(()),
@@ -61,17 +74,122 @@ impl TryFrom<CommonVal<'_>> for TextAlignment {
 /// * foreground
 /// * text-align
 /// * wrap
+/// * ellipsis
+/// * bidi
+/// * direction
+/// * preserve_ansi
+/// * link
+/// ```
+///
+/// `wrap` is one of:
+/// * `word` (default): wrap on whitespace
+/// * `break`: wrap anywhere
+/// * `no_wrap`: one line per line of text, cutting off what doesn't fit
+/// * `truncate`: like `no_wrap`, but ending the line with the `ellipsis` (defaults to `…`)
+///
+/// ```text
+/// text [wrap: 'truncate'] 'long file name.txt'
 /// ```
 ///
+/// Text that mixes left-to-right and right-to-left scripts (e.g Arabic or Hebrew) is only
+/// reordered for display with `bidi: true`, or when the paragraph `direction` is set to `'ltr'` or `'rtl'`.
+/// With `bidi: true` the direction is that of the first letter.
+/// Right-to-left text is aligned to the right unless `text_align` is set.
+///
 /// Note: Spans, unlike other widgets, does not require a widget id
 ///
+/// Spans can be generated with a loop, and are laid out and wrapped as a
+/// single paragraph, each with its own style:
+/// ```text
+/// text
+///     for span in spans
+///         span [foreground: span.foreground, bold: span.bold] span.text
+/// ```
+/// See `anathema_state::StyledSpan` for a state type to use with this.
+///
+/// With `preserve_ansi: true` ANSI escape sequences in the text (e.g the output of another
+/// command line tool) are applied as styles instead of being printed,
+/// see `anathema_state::AnsiParser`:
+/// ```text
+/// text [preserve_ansi: true] output
+/// ```
+///
+/// The text (or a span) is a clickable hyperlink with a `link` attribute,
+/// in terminals that support it:
+/// ```text
+/// text "See the "
+///     span [link: "https://github.com/togglebyte/anathema", underline: true] "repository"
+/// ```
+///
 /// A `Text` widget will be as wide as its text.
 #[derive(Debug, Default)]
 pub struct Text {
     strings: Strings,
+    // The ANSI style of each style change in the strings, in order
+    ansi: Vec<Option<SpanStyle>>,
+}
+
+impl Text {
+    // Add a string that has the style of the widget, split into pieces by the ANSI styles
+    // if there is a parser
+    fn add_str(&mut self, s: &str, style: WidgetId, parser: Option<&mut AnsiParser>) -> ControlFlow<()> {
+        let Some(parser) = parser else {
+            return match self.strings.add_str(s) {
+                ProcessResult::Break => ControlFlow::Break(()),
+                ProcessResult::Continue => ControlFlow::Continue(()),
+            };
+        };
+
+        let mut flow = ControlFlow::Continue(());
+        parser.parse_with(s, |s, ansi| {
+            if flow.is_break() {
+                return;
+            }
+            self.set_style(style, Some(ansi));
+            if let ProcessResult::Break = self.strings.add_str(s) {
+                flow = ControlFlow::Break(());
+            }
+        });
+        flow
+    }
+
+    fn set_style(&mut self, style: WidgetId, ansi: Option<SpanStyle>) {
+        self.strings.set_style(style);
+        self.ansi.push(ansi);
+    }
+
+    fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.strings
+            .lines()
+            .flat_map(|line| {
+                line.entries.flat_map(|segment| match segment {
+                    Segment::Str(s) => Some(s.chars()),
+                    Segment::SetStyle(_) => None,
+                })
+            })
+            .flatten()
+    }
 }
 
 impl Widget for Text {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(
+                "wrap",
+                AttributeType::Enum(&["normal", "break", "no_wrap", "truncate"]),
+                Some("\"normal\""),
+            )
+            .attribute(
+                "text_align",
+                AttributeType::Enum(&["left", "centre", "right", "justify"]),
+                Some("\"left\""),
+            )
+            .attribute(ELLIPSIS, AttributeType::String, None)
+            .attribute(BIDI, AttributeType::Bool, Some("false"))
+            .attribute(PRESERVE_ANSI, AttributeType::Bool, Some("false"));
+        Some(metadata)
+    }
+
     fn layout<'bp>(
         &mut self,
         mut children: LayoutChildren<'_, '_, 'bp>,
@@ -83,29 +201,30 @@ impl Widget for Text {
         let wrap = attributes.get(WRAP).unwrap_or_default();
         let size = constraints.max_size();
         self.strings = Strings::new(size, wrap);
-        self.strings.set_style(id);
+        if let (Wrap::Truncate, Some(ellipsis)) = (wrap, attributes.get_val(ELLIPSIS)) {
+            let mut s = String::new();
+            ellipsis.str_for_each(|e| s.push_str(e));
+            self.strings.set_ellipsis(s);
+        }
+        self.ansi.clear();
+        self.set_style(id, None);
+        let mut parser = attributes.get_bool(PRESERVE_ANSI).then(AnsiParser::new);
 
         // Layout text
-        attributes.value().map(|value| {
-            value.str_iter(|s| match self.strings.add_str(s) {
-                ProcessResult::Break => ControlFlow::Break(()),
-                ProcessResult::Continue => ControlFlow::Continue(()),
-            })
-        });
+        attributes
+            .value()
+            .map(|value| value.str_iter(|s| self.add_str(s, id, parser.as_mut())));
 
         // Layout text of all the sub-nodes
         children.for_each(|child, _| {
             let Some(_span) = child.try_to_ref::<Span>() else {
                 return ControlFlow::Continue(());
             };
-            self.strings.set_style(child.id());
+            self.set_style(child.id(), None);
 
             let attributes = ctx.attribs.get(child.id());
             if let Some(text) = attributes.value() {
-                text.str_iter(|s| match self.strings.add_str(s) {
-                    ProcessResult::Break => ControlFlow::Break(()),
-                    ProcessResult::Continue => ControlFlow::Continue(()),
-                })?;
+                text.str_iter(|s| self.add_str(s, child.id(), parser.as_mut()))?;
 
                 ControlFlow::Continue(())
             } else {
@@ -123,40 +242,117 @@ impl Widget for Text {
         attribute_storage: &AttributeStorage<'bp>,
         mut ctx: PaintCtx<'_, SizePos>,
     ) {
-        let lines = self.strings.lines();
-        let alignment = attribute_storage.get(id).get(TEXT_ALIGN).unwrap_or_default();
+        let attributes = attribute_storage.get(id);
+        let direction = match attributes.get::<TextDirection>(DIRECTION) {
+            Some(direction) => Some(direction),
+            None => attributes.get_bool(BIDI).then(|| TextDirection::of(self.chars())),
+        };
+        let alignment = match (attributes.get(TEXT_ALIGN), direction) {
+            (Some(alignment), _) => alignment,
+            (None, Some(TextDirection::Rtl)) => TextAlignment::Right,
+            (None, _) => TextAlignment::Left,
+        };
 
+        let lines = self.strings.lines();
         let mut pos = LocalPos::ZERO;
-        let mut style = attribute_storage.get(id);
+        let mut style_id = id;
+        // The index of the next style change, and the current ANSI style
+        let mut style_index = 0;
+        let mut ansi = None;
+        let width = ctx.local_size.width as u16;
 
         for line in lines {
-            let x = match alignment {
-                TextAlignment::Left => 0,
-                TextAlignment::Centre => ctx.local_size.width as u16 / 2 - line.width / 2,
-                TextAlignment::Right => ctx.local_size.width as u16 - line.width,
+            pos.x = match alignment {
+                TextAlignment::Left | TextAlignment::Justify => 0,
+                TextAlignment::Centre => width / 2 - line.width / 2,
+                TextAlignment::Right => width - line.width,
             };
 
-            pos.x = x;
+            let segments = line.entries.collect::<Vec<_>>();
+            let mut gaps = match alignment {
+                TextAlignment::Justify if line.wrapped => Gaps::new(&segments, line.width, width),
+                _ => Gaps::default(),
+            };
+
+            if let Some(direction) = direction {
+                // Reorder the line, keeping track of the style of each char.
+                // Chars without a width stay with the char before them.
+                let mut chars = vec![];
+                let mut marks: Vec<String> = vec![];
+                let mut styles = vec![];
+                for segment in segments {
+                    match segment {
+                        Segment::Str(s) => s.chars().for_each(|c| match marks.last_mut() {
+                            Some(marks) if c.width() == Some(0) => marks.push(c),
+                            _ => {
+                                chars.push(c);
+                                marks.push(String::new());
+                                styles.push((style_id, ansi));
+                            }
+                        }),
+                        Segment::SetStyle(attribute_id) => {
+                            style_id = attribute_id;
+                            ansi = self.ansi.get(style_index).copied().flatten();
+                            style_index += 1;
+                        }
+                    }
+                }
+
+                for (index, c) in bidi::reorder(&chars, direction) {
+                    let new_pos = match marks[index].is_empty() {
+                        true => ctx.place_glyph(c, pos),
+                        false => ctx.place_cluster(&format!("{c}{}", marks[index]), pos),
+                    };
+                    let Some(mut new_pos) = new_pos else { continue };
+                    if c == ' ' {
+                        new_pos.x += gaps.next();
+                    }
+                    let (style_id, ansi) = styles[index];
+                    for x in pos.x..new_pos.x {
+                        ctx.set_attributes(attribute_storage.get(style_id), (x, pos.y).into());
+                        if let Some(ansi) = ansi {
+                            ctx.set_attributes(&AnsiStyle(ansi), (x, pos.y).into());
+                        }
+                    }
+                    pos = new_pos;
+                }
 
-            for entry in line.entries {
-                match entry {
+                pos.y += 1;
+                continue;
+            }
+
+            let mut style = attribute_storage.get(style_id);
+            for segment in segments {
+                match segment {
                     Segment::Str(s) => {
-                        if let Some(new_pos) = ctx.place_glyphs(s, pos) {
+                        for word in s.split_inclusive(' ') {
+                            let Some(mut new_pos) = ctx.place_glyphs(word, pos) else { continue };
+                            if word.ends_with(' ') {
+                                new_pos.x += gaps.next();
+                            }
+
                             // NOTE:
                             // This isn't very nice, but it works for now.
                             // In the future there should probably be a means to
                             // provide both style and glyph at the same time.
                             for x in pos.x..new_pos.x {
                                 ctx.set_attributes(style, (x, pos.y).into());
+                                if let Some(ansi) = ansi {
+                                    ctx.set_attributes(&AnsiStyle(ansi), (x, pos.y).into());
+                                }
                             }
                             pos = new_pos;
                         }
                     }
-                    Segment::SetStyle(attribute_id) => style = attribute_storage.get(attribute_id),
+                    Segment::SetStyle(attribute_id) => {
+                        style_id = attribute_id;
+                        style = attribute_storage.get(attribute_id);
+                        ansi = self.ansi.get(style_index).copied().flatten();
+                        style_index += 1;
+                    }
                 }
             }
             pos.y += 1;
-            pos.x = 0;
         }
     }
 
@@ -172,10 +368,90 @@ impl Widget for Text {
     }
 }
 
+// The style of a piece of text from an ANSI escape sequence
+struct AnsiStyle(SpanStyle);
+
+impl CellAttributes for AnsiStyle {
+    fn with_str(&self, _: &str, _: &mut dyn FnMut(&str)) {}
+
+    fn get_i64(&self, _: &str) -> Option<i64> {
+        None
+    }
+
+    fn get_u8(&self, _: &str) -> Option<u8> {
+        None
+    }
+
+    fn get_hex(&self, _: &str) -> Option<Hex> {
+        None
+    }
+
+    fn get_color(&self, key: &str) -> Option<Color> {
+        match key {
+            "foreground" => self.0.foreground,
+            "background" => self.0.background,
+            _ => None,
+        }
+    }
+
+    fn get_bool(&self, key: &str) -> bool {
+        match key {
+            "bold" => self.0.bold,
+            "italic" => self.0.italic,
+            "inverse" => self.0.inverse,
+            _ => false,
+        }
+    }
+}
+
+// The extra space added after each space between the words of a justified line
+#[derive(Debug, Default)]
+struct Gaps {
+    count: u16,
+    extra: u16,
+    index: u16,
+}
+
+impl Gaps {
+    fn new(segments: &[Segment<'_>], line_width: u16, width: u16) -> Self {
+        let mut text = segments.iter().filter_map(|segment| match segment {
+            Segment::Str(s) => Some(*s),
+            Segment::SetStyle(_) => None,
+        });
+
+        let spaces = text.clone().flat_map(str::chars).filter(|c| *c == ' ').count() as u16;
+        let trailing = text
+            .by_ref()
+            .rev()
+            .flat_map(|s| s.chars().rev())
+            .take_while(|c| *c == ' ')
+            .count() as u16;
+
+        Self {
+            count: spaces - trailing,
+            extra: width.saturating_sub(line_width - trailing),
+            index: 0,
+        }
+    }
+
+    fn next(&mut self) -> u16 {
+        if self.index >= self.count {
+            return 0;
+        }
+        let extra = self.extra / self.count + (self.index < self.extra % self.count) as u16;
+        self.index += 1;
+        extra
+    }
+}
+
 #[derive(Default, Copy, Clone)]
 pub struct Span;
 
 impl Widget for Span {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::None))
+    }
+
     fn layout<'bp>(
         &mut self,
         _: LayoutChildren<'_, '_, 'bp>,
@@ -201,6 +477,8 @@ impl Widget for Span {
 
 #[cfg(test)]
 mod test {
+    use anathema_state::Color;
+
     use crate::testing::TestRunner;
 
     #[test]
@@ -247,6 +525,37 @@ mod test {
         TestRunner::new(src, (16, 3)).instance().render_assert(expected);
     }
 
+    #[test]
+    fn no_wrap() {
+        let src = "text [wrap: 'no_wrap'] 'hello how are you'";
+        let expected = r#"
+           ╔══════════╗
+           ║hello how ║
+           ║          ║
+           ╚══════════╝
+           "#;
+
+        TestRunner::new(src, (10, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn truncate() {
+        let src = "
+            vstack
+                text [wrap: 'truncate'] 'long file name.txt'
+                text [wrap: 'truncate', ellipsis: '..'] 'long file name.txt'
+                    span [bold: true] ' (2kb)'
+        ";
+        let expected = r#"
+           ╔════════════╗
+           ║long file n…║
+           ║long file.. ║
+           ╚════════════╝
+           "#;
+
+        TestRunner::new(src, (12, 2)).instance().render_assert(expected);
+    }
+
     #[test]
     fn char_wrap_layout_multiple_spans() {
         let src = r#"
@@ -315,6 +624,61 @@ mod test {
         TestRunner::new(src, (18, 3)).instance().render_assert(expected);
     }
 
+    #[test]
+    fn justify() {
+        let src = "text [text_align: 'justify'] 'a b c dddd e\nf g'";
+        let expected = r#"
+               ╔════════╗
+               ║a   b  c║
+               ║dddd e  ║
+               ║f g     ║
+               ╚════════╝
+           "#;
+
+        TestRunner::new(src, (8, 3)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn wide_chars() {
+        // Wide chars take up two cells and wrap as a whole,
+        // combining marks are drawn in the same cell as the previous char
+        let src = "
+            vstack
+                text '漢字かな交じり'
+                text '🐇🐇 cafe\u{301}'
+        ";
+        let expected = r#"
+               ╔═══════╗
+               ║漢字か ║
+               ║な交じ ║
+               ║り     ║
+               ║🐇🐇   ║
+               ║café   ║
+               ╚═══════╝
+           "#;
+
+        TestRunner::new(src, (7, 5)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn bidi() {
+        let src = "
+            vstack
+                text [bidi: true] 'שלום (עולם)'
+                text [bidi: true] 'abc אבג'
+                text 'abc אבג'
+        ";
+        let expected = r#"
+               ╔════════════╗
+               ║(םלוע) םולש ║
+               ║abc גבא     ║
+               ║abc אבג     ║
+               ╚════════════╝
+           "#;
+
+        TestRunner::new(src, (12, 3)).instance().render_assert(expected);
+    }
+
     #[test]
     fn line_break() {
         let src = "text 'What have you'";
@@ -328,4 +692,78 @@ mod test {
 
         TestRunner::new(src, (9, 3)).instance().render_assert(expected);
     }
+
+    #[test]
+    fn wrap_spans_from_loop() {
+        let src = r#"
+            text
+                for s in [{text: 'one ', fg: 'red'}, {text: 'two three', fg: 'blue'}, {text: ' four'}]
+                    span [foreground: s.fg] s.text
+        "#;
+
+        let expected = r#"
+               ╔═════════╗
+               ║one two  ║
+               ║three    ║
+               ║four     ║
+               ╚═════════╝
+           "#;
+
+        let mut runner = TestRunner::new(src, (9, 3));
+        let mut instance = runner.instance();
+        instance.render_assert(expected);
+
+        // Each span keeps its own style when the spans are wrapped together,
+        // (the border around the template moves everything one cell down and right)
+        let fg = |x: i32, y: i32| instance.style((x + 1, y + 1)).fg;
+        for x in 0..4 {
+            assert_eq!(fg(x, 0), Some(Color::Red));
+        }
+        for x in 4..7 {
+            assert_eq!(fg(x, 0), Some(Color::Blue));
+        }
+        for x in 0..5 {
+            assert_eq!(fg(x, 1), Some(Color::Blue));
+        }
+        for x in 0..4 {
+            assert_eq!(fg(x, 2), None);
+        }
+    }
+
+    #[test]
+    fn preserve_ansi() {
+        let src = "
+            vstack
+                text [preserve_ansi: true] '\x1b[1;31mred\x1b[0m plain'
+                text [preserve_ansi: true]
+                    span '\x1b[32mgreen'
+                    span ' too'
+        ";
+
+        let expected = r#"
+               ╔═════════╗
+               ║red plain║
+               ║green too║
+               ╚═════════╝
+           "#;
+
+        TestRunner::new(src, (9, 2)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn format_functions() {
+        let src = "
+            vstack
+                text '[' pad_left(value, 3, '0') ']'
+                text upper(truncate('abcdef', 4, '.')) ' ' format_number(12345.678, 1)
+        ";
+        let expected = r#"
+           ╔══════════════╗
+           ║[000]         ║
+           ║ABC. 12,345.7 ║
+           ╚══════════════╝
+           "#;
+
+        TestRunner::new(src, (14, 2)).instance().render_assert(expected);
+    }
 }
@@ -0,0 +1,528 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{Pos, Size};
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId, WidgetMetadata,
+};
+
+use crate::layout::Axis;
+
+const DEFAULT_RATIO: f32 = 0.5;
+// Smallest ratio a split can be resized to
+const MIN_RATIO: f32 = 0.1;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tile {
+    // Index of the child
+    Pane(usize),
+    Split {
+        axis: Axis,
+        // The fraction of the space given to `first`
+        ratio: f32,
+        first: Box<Tile>,
+        second: Box<Tile>,
+    },
+}
+
+impl Tile {
+    fn split(axis: Axis, first: Tile, second: Tile) -> Self {
+        Self::Split {
+            axis,
+            ratio: DEFAULT_RATIO,
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    fn panes(&self, panes: &mut Vec<usize>) {
+        match self {
+            Self::Pane(index) => panes.push(*index),
+            Self::Split { first, second, .. } => {
+                first.panes(panes);
+                second.panes(panes);
+            }
+        }
+    }
+
+    // Replace the pane with a split, containing the pane and the new pane
+    fn insert(&mut self, pane: usize, new: usize, axis: Axis) -> bool {
+        match self {
+            Self::Pane(index) if *index == pane => {
+                *self = Self::split(axis, Self::Pane(pane), Self::Pane(new));
+                true
+            }
+            Self::Pane(_) => false,
+            Self::Split { first, second, .. } => first.insert(pane, new, axis) || second.insert(pane, new, axis),
+        }
+    }
+
+    // Remove a pane, replacing the parent split with the remaining sibling.
+    // Returns true if `self` is the pane to remove.
+    fn remove(&mut self, pane: usize) -> bool {
+        match self {
+            Self::Pane(index) => *index == pane,
+            Self::Split { first, second, .. } => {
+                if first.remove(pane) {
+                    *self = std::mem::replace(second, Self::Pane(0));
+                } else if second.remove(pane) {
+                    *self = std::mem::replace(first, Self::Pane(0));
+                }
+                false
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        if let Self::Split {
+            axis, first, second, ..
+        } = self
+        {
+            *axis = match axis {
+                Axis::Horizontal => Axis::Vertical,
+                Axis::Vertical => Axis::Horizontal,
+            };
+            first.rotate();
+            second.rotate();
+        }
+    }
+
+    // Resize the closest split containing the pane.
+    // Returns true if the pane is inside this tile.
+    fn resize(&mut self, pane: usize, delta: f32, resized: &mut bool) -> bool {
+        match self {
+            Self::Pane(index) => *index == pane,
+            Self::Split {
+                ratio, first, second, ..
+            } => {
+                let (in_first, in_second) = match first.resize(pane, delta, resized) {
+                    true => (true, false),
+                    false => (false, second.resize(pane, delta, resized)),
+                };
+
+                if (in_first || in_second) && !*resized {
+                    let delta = if in_first { delta } else { -delta };
+                    *ratio = (*ratio + delta).clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+                    *resized = true;
+                }
+
+                in_first || in_second
+            }
+        }
+    }
+
+    fn rects(&self, pos: Pos, size: Size, rects: &mut Vec<(usize, Pos, Size)>) {
+        match self {
+            Self::Pane(index) => rects.push((*index, pos, size)),
+            Self::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => match axis {
+                Axis::Horizontal => {
+                    let width = (size.width as f32 * ratio).round() as usize;
+                    first.rects(pos, Size::new(width, size.height), rects);
+                    let pos = Pos::new(pos.x + width as i32, pos.y);
+                    second.rects(pos, Size::new(size.width - width, size.height), rects);
+                }
+                Axis::Vertical => {
+                    let height = (size.height as f32 * ratio).round() as usize;
+                    first.rects(pos, Size::new(size.width, height), rects);
+                    let pos = Pos::new(pos.x, pos.y + height as i32);
+                    second.rects(pos, Size::new(size.width, size.height - height), rects);
+                }
+            },
+        }
+    }
+}
+
+/// Window manager style tiling container.
+///
+/// Every child is a pane. Panes are tiled by splitting the focused pane in two,
+/// alternating the axis as new children are added (like a spiral).
+///
+/// Splitting the focused pane shows the next child that is not currently visible,
+/// and closing a pane hides the child (it can be shown again by splitting a pane).
+///
+/// The panes are controlled by the component, using either the methods,
+/// or the default key bindings through [`Tiling::handle_key`]:
+/// ```ignore
+/// fn on_key(&mut self, key: KeyEvent, state: &mut Self::State, mut elements: Elements<'_, '_>, _: Context<'_, Self::State>) {
+///     elements.by_tag("tiling").first(|el, _| el.to::<Tiling>().handle_key(key));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Tiling {
+    root: Option<Tile>,
+    focused: usize,
+    // Number of children seen so far. New children are added as panes
+    children: usize,
+    // Position and size of each pane, relative to the container
+    rects: Vec<(usize, Pos, Size)>,
+    is_dirty: bool,
+}
+
+impl Tiling {
+    /// The child index of the focused pane
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// The child indices of the visible panes, in order
+    pub fn panes(&self) -> Vec<usize> {
+        let mut panes = vec![];
+        if let Some(root) = self.root.as_ref() {
+            root.panes(&mut panes);
+        }
+        panes
+    }
+
+    /// Split the focused pane along the axis, showing the next hidden child in the new pane.
+    /// `Axis::Horizontal` places the new pane to the right, and `Axis::Vertical` below.
+    ///
+    /// Returns false if there are no hidden children.
+    pub fn split(&mut self, axis: Axis) -> bool {
+        let panes = self.panes();
+        let Some(new) = (0..self.children).find(|i| !panes.contains(i)) else { return false };
+        self.insert(new, axis);
+        true
+    }
+
+    /// Hide the focused pane, and focus the next one
+    pub fn close(&mut self) {
+        let panes = self.panes();
+        let Some(position) = panes.iter().position(|&p| p == self.focused) else { return };
+
+        if let Some(root) = self.root.as_mut() {
+            if root.remove(self.focused) {
+                self.root = None;
+            }
+        }
+
+        let panes = self.panes();
+        if let Some(&next) = panes.get(position).or(panes.last()) {
+            self.focused = next;
+        }
+        self.is_dirty = true;
+    }
+
+    /// Swap the axis of every split
+    pub fn rotate(&mut self) {
+        if let Some(root) = self.root.as_mut() {
+            root.rotate();
+            self.is_dirty = true;
+        }
+    }
+
+    /// Move the focus to the next pane
+    pub fn focus_next(&mut self) {
+        self.move_focus(1);
+    }
+
+    /// Move the focus to the previous pane
+    pub fn focus_prev(&mut self) {
+        self.move_focus(-1);
+    }
+
+    /// Grow (or shrink, for a negative value) the focused pane by a fraction of its split
+    pub fn resize(&mut self, delta: f32) {
+        if let Some(root) = self.root.as_mut() {
+            root.resize(self.focused, delta, &mut false);
+            self.is_dirty = true;
+        }
+    }
+
+    /// Apply the default key bindings:
+    /// * `Ctrl+v`: split the focused pane, placing the new pane to the right
+    /// * `Ctrl+s`: split the focused pane, placing the new pane below
+    /// * `Ctrl+x`: close the focused pane
+    /// * `Ctrl+r`: rotate the layout
+    /// * `Ctrl+n` / `Ctrl+p`: focus the next / previous pane
+    /// * `Ctrl+Left` / `Ctrl+Right`: shrink / grow the focused pane
+    ///
+    /// Returns true if the key was handled.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if !key.ctrl || matches!(key.state, KeyState::Release) {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('v') => {
+                self.split(Axis::Horizontal);
+            }
+            KeyCode::Char('s') => {
+                self.split(Axis::Vertical);
+            }
+            KeyCode::Char('x') => self.close(),
+            KeyCode::Char('r') => self.rotate(),
+            KeyCode::Char('n') => self.focus_next(),
+            KeyCode::Char('p') => self.focus_prev(),
+            KeyCode::Left => self.resize(-0.05),
+            KeyCode::Right => self.resize(0.05),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn move_focus(&mut self, offset: isize) {
+        let panes = self.panes();
+        let Some(position) = panes.iter().position(|&p| p == self.focused) else { return };
+        let next = (position as isize + offset).rem_euclid(panes.len() as isize);
+        self.focused = panes[next as usize];
+    }
+
+    // Add a pane next to the focused pane, and focus it
+    fn insert(&mut self, pane: usize, axis: Axis) {
+        match self.root.as_mut() {
+            None => self.root = Some(Tile::Pane(pane)),
+            Some(root) => {
+                if !root.insert(self.focused, pane, axis) {
+                    // The focused pane is gone, so split the whole layout
+                    let root = self.root.take().expect("root exists");
+                    self.root = Some(Tile::split(axis, root, Tile::Pane(pane)));
+                }
+            }
+        }
+
+        self.focused = pane;
+        self.is_dirty = true;
+    }
+
+    // Add new children as panes and remove panes for children that no longer exist
+    fn sync(&mut self, children: usize) {
+        for pane in self.panes().into_iter().filter(|&p| p >= children) {
+            if let Some(root) = self.root.as_mut() {
+                if root.remove(pane) {
+                    self.root = None;
+                }
+            }
+        }
+
+        for new in self.children..children {
+            let axis = match self.panes().len() % 2 {
+                0 => Axis::Vertical,
+                _ => Axis::Horizontal,
+            };
+            self.insert(new, axis);
+        }
+
+        self.children = children;
+        if !self.panes().contains(&self.focused) {
+            self.focused = self.panes().first().copied().unwrap_or(0);
+        }
+    }
+
+    fn rect(&self, index: usize) -> Option<(Pos, Size)> {
+        self.rects
+            .iter()
+            .find(|(i, ..)| *i == index)
+            .map(|(_, pos, size)| (*pos, *size))
+    }
+}
+
+impl Widget for Tiling {
+    fn metadata() -> Option<WidgetMetadata> {
+        Some(WidgetMetadata::new(Children::Many))
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        _: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let mut count = 0;
+        children.for_each(|_, _| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        self.sync(count);
+        self.is_dirty = false;
+
+        let size = constraints.max_size();
+        self.rects.clear();
+        if let Some(root) = self.root.as_ref() {
+            root.rects(Pos::ZERO, size, &mut self.rects);
+        }
+
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let constraints = match self.rect(index) {
+                Some((_, size)) => {
+                    let mut constraints = Constraints::new(size.width, size.height);
+                    constraints.make_width_tight(size.width);
+                    constraints.make_height_tight(size.height);
+                    constraints
+                }
+                // Hidden children still need a layout
+                None => Constraints::ZERO,
+            };
+
+            child.layout(children, constraints, ctx);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let offset = self.rect(index).map(|(pos, _)| pos).unwrap_or(Pos::ZERO);
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            if self.rect(index).is_some() {
+                let ctx = ctx.to_unsized();
+                child.paint(children, ctx, attribute_storage);
+            }
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn tile_children() {
+        let tpl = "
+            tiling
+                text 'a'
+                text 'b'
+                text 'c'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║a  b  ║
+            ║      ║
+            ║   c  ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 4)).instance().render_assert(expected);
+    }
+
+    #[test]
+    fn close_and_split() {
+        let tpl = "
+            tiling
+                text 'a'
+                text 'b'
+        ";
+
+        let expected_closed = "
+            ╔══════╗
+            ║a     ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        let expected_split = "
+            ╔══════╗
+            ║a     ║
+            ║b     ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2))
+            .instance()
+            .render_assert(
+                "
+                ╔══════╗
+                ║a  b  ║
+                ║      ║
+                ╚══════╝
+            ",
+            )
+            .with_widget(|mut elements| {
+                elements.by_tag("tiling").first(|el, _| {
+                    let tiling = el.to::<Tiling>();
+                    assert_eq!(tiling.focused(), 1);
+                    tiling.close();
+                    assert_eq!(tiling.panes(), vec![0]);
+                });
+            })
+            .render_assert(expected_closed)
+            .with_widget(|mut elements| {
+                elements.by_tag("tiling").first(|el, _| {
+                    let tiling = el.to::<Tiling>();
+                    assert!(tiling.split(Axis::Vertical));
+                    assert!(!tiling.split(Axis::Vertical));
+                });
+            })
+            .render_assert(expected_split);
+    }
+
+    #[test]
+    fn rotate_and_focus() {
+        let mut tiling = Tiling::default();
+        tiling.sync(3);
+        assert_eq!(tiling.panes(), vec![0, 1, 2]);
+        assert_eq!(tiling.focused(), 2);
+
+        tiling.focus_next();
+        assert_eq!(tiling.focused(), 0);
+        tiling.focus_prev();
+        assert_eq!(tiling.focused(), 2);
+
+        tiling.rotate();
+        let mut rects = vec![];
+        tiling
+            .root
+            .as_ref()
+            .unwrap()
+            .rects(Pos::ZERO, Size::new(4, 4), &mut rects);
+        assert_eq!(rects[1], (1, Pos::new(0, 2), Size::new(2, 2)));
+        assert_eq!(rects[0], (0, Pos::ZERO, Size::new(4, 2)));
+    }
+
+    #[test]
+    fn resize_pane() {
+        let mut tiling = Tiling::default();
+        tiling.sync(2);
+        tiling.resize(0.25);
+
+        let mut rects = vec![];
+        tiling
+            .root
+            .as_ref()
+            .unwrap()
+            .rects(Pos::ZERO, Size::new(8, 1), &mut rects);
+        // The second pane is focused, and grows to the left
+        assert_eq!(rects[1], (1, Pos::new(2, 0), Size::new(6, 1)));
+    }
+}
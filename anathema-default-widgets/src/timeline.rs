@@ -0,0 +1,298 @@
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{HEIGHT, WIDTH};
+
+pub const BAR: &str = "bar";
+
+const DEFAULT_BAR: char = '█';
+const MIN_SPAN: f64 = f64::EPSILON;
+
+/// An entry in the timeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineSpan {
+    pub label: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Labeled bars along a horizontal time axis.
+///
+/// Each span is drawn on its own row, with the label to the left.
+/// The last row holds the start and end of the visible range.
+///
+/// Spans are added through the widget, as is zooming and panning:
+/// ```ignore
+/// elements.by_tag("timeline").first(|el, _| {
+///     let timeline = el.to::<Timeline>();
+///     timeline.add("build", 0.0, 12.5);
+///     timeline.add("test", 12.5, 20.0);
+///     timeline.zoom(2.0);
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct Timeline {
+    spans: Vec<TimelineSpan>,
+    // Visible range. If this is `None` the range covers all spans
+    view: Option<(f64, f64)>,
+    is_dirty: bool,
+}
+
+impl Timeline {
+    /// Add a span to the timeline
+    pub fn add(&mut self, label: impl Into<String>, start: f64, end: f64) {
+        self.is_dirty = true;
+        self.spans.push(TimelineSpan {
+            label: label.into(),
+            start: start.min(end),
+            end: start.max(end),
+        });
+    }
+
+    /// Update the end of an existing span, e.g to grow a running task.
+    /// Returns false if there is no span with the given label.
+    pub fn set_end(&mut self, label: &str, end: f64) -> bool {
+        let Some(span) = self.spans.iter_mut().find(|s| s.label == label) else { return false };
+        self.is_dirty = true;
+        span.end = end.max(span.start);
+        true
+    }
+
+    pub fn spans(&self) -> &[TimelineSpan] {
+        &self.spans
+    }
+
+    /// Remove all spans and reset the view
+    pub fn clear(&mut self) {
+        self.is_dirty = true;
+        self.spans.clear();
+        self.view = None;
+    }
+
+    /// The visible range of time
+    pub fn view(&self) -> (f64, f64) {
+        match self.view {
+            Some(view) => view,
+            None => self.extent(),
+        }
+    }
+
+    /// Zoom in (`factor` > 1) or out (`factor` < 1) around the centre of the view
+    pub fn zoom(&mut self, factor: f64) {
+        if factor <= 0.0 {
+            return;
+        }
+        let (start, end) = self.view();
+        let centre = (start + end) / 2.0;
+        let half = ((end - start) / factor / 2.0).max(MIN_SPAN);
+        self.view = Some((centre - half, centre + half));
+        self.is_dirty = true;
+    }
+
+    /// Move the view by `delta` units of time
+    pub fn pan(&mut self, delta: f64) {
+        let (start, end) = self.view();
+        self.view = Some((start + delta, end + delta));
+        self.is_dirty = true;
+    }
+
+    /// Reset zoom and pan so all spans are visible
+    pub fn reset_view(&mut self) {
+        self.view = None;
+        self.is_dirty = true;
+    }
+
+    fn extent(&self) -> (f64, f64) {
+        let start = self.spans.iter().map(|s| s.start).fold(f64::MAX, f64::min);
+        let end = self.spans.iter().map(|s| s.end).fold(f64::MIN, f64::max);
+        match start < end {
+            true => (start, end),
+            false => (0.0, 1.0),
+        }
+    }
+
+    fn label_width(&self) -> usize {
+        self.spans.iter().map(|s| s.label.width()).max().unwrap_or(0)
+    }
+}
+
+impl Widget for Timeline {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(BAR, AttributeType::Char, Some("\"█\""))
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        mut constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+
+        if let Some(width) = attributes.get_usize(WIDTH) {
+            constraints.set_max_width(width);
+        }
+
+        // One row per span plus the axis
+        let height = attributes.get_usize(HEIGHT).unwrap_or(self.spans.len() + 1);
+        constraints.set_max_height(height);
+
+        self.is_dirty = false;
+        let mut size = constraints.max_size();
+        size.height = size.height.max(constraints.min_height);
+        size
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The timeline has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let Size { width, height } = ctx.local_size;
+        if height == 0 {
+            return;
+        }
+
+        let bar = attribute_storage
+            .get(id)
+            .get_ref::<&str>(BAR)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(DEFAULT_BAR);
+
+        let label_width = match self.label_width() {
+            0 => 0,
+            w => w + 1,
+        };
+        let track_width = width.saturating_sub(label_width);
+        let (start, end) = self.view();
+        let scale = track_width as f64 / (end - start);
+
+        let rows = height - 1;
+        for (y, span) in self.spans.iter().take(rows).enumerate() {
+            let y = y as u16;
+            ctx.place_glyphs(&span.label, LocalPos::new(0, y));
+
+            if span.end <= start || span.start >= end || track_width == 0 {
+                continue;
+            }
+
+            let from = ((span.start - start) * scale).floor().max(0.0) as usize;
+            let to = ((span.end - start) * scale).ceil().min(track_width as f64) as usize;
+            // Always draw at least one cell for a visible span
+            let to = to.max(from + 1).min(track_width);
+
+            for x in from..to {
+                ctx.place_glyph(bar, LocalPos::new((label_width + x) as u16, y));
+            }
+        }
+
+        // Axis
+        let y = height as u16 - 1;
+        let start_label = format!("{}", round(start));
+        let end_label = format!("{}", round(end));
+        if start_label.len() + end_label.len() < track_width {
+            ctx.place_glyphs(&start_label, LocalPos::new(label_width as u16, y));
+            let x = width - end_label.len();
+            ctx.place_glyphs(&end_label, LocalPos::new(x as u16, y));
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+// Round to two decimal places for the axis labels
+fn round(val: f64) -> f64 {
+    (val * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    fn add_spans(timeline: &mut Timeline) {
+        timeline.add("a", 0.0, 5.0);
+        timeline.add("bb", 5.0, 10.0);
+    }
+
+    #[test]
+    fn render_timeline() {
+        let expected = "
+            ╔═════════════╗
+            ║a  █████     ║
+            ║bb      █████║
+            ║   0       10║
+            ╚═════════════╝
+        ";
+
+        TestRunner::new("timeline", (13, 3))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("timeline").first(|el, _| add_spans(el.to::<Timeline>()));
+            })
+            .render_assert(expected);
+    }
+
+    #[test]
+    fn zoom_and_pan() {
+        let mut timeline = Timeline::default();
+        add_spans(&mut timeline);
+
+        timeline.zoom(2.0);
+        assert_eq!(timeline.view(), (2.5, 7.5));
+
+        timeline.pan(2.5);
+        assert_eq!(timeline.view(), (5.0, 10.0));
+
+        timeline.reset_view();
+        assert_eq!(timeline.view(), (0.0, 10.0));
+    }
+
+    #[test]
+    fn render_zoomed() {
+        let expected = "
+            ╔═════════════╗
+            ║a            ║
+            ║bb ██████████║
+            ║   5       10║
+            ╚═════════════╝
+        ";
+
+        TestRunner::new("timeline", (13, 3))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("timeline").first(|el, _| {
+                    let timeline = el.to::<Timeline>();
+                    add_spans(timeline);
+                    timeline.zoom(2.0);
+                    timeline.pan(2.5);
+                });
+            })
+            .render_assert(expected);
+    }
+}
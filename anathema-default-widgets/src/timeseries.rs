@@ -0,0 +1,268 @@
+use std::collections::VecDeque;
+
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+use crate::{HEIGHT, WIDTH};
+
+/// The width of the time window, in seconds
+pub const WINDOW: &str = "window";
+pub const MIN: &str = "min";
+pub const MAX: &str = "max";
+pub const POINT: &str = "point";
+
+const DEFAULT_WINDOW: f64 = 60.0;
+const DEFAULT_POINT: char = '•';
+
+/// A single timestamped sample
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sample {
+    /// Time in seconds. This can be any monotonically increasing clock,
+    /// as only the difference between two timestamps is used.
+    pub time: f64,
+    pub value: f64,
+}
+
+/// Plot timestamped samples along a time axis.
+///
+/// The newest sample is always at the right edge, and the plot scrolls
+/// as new samples are added. Samples older than the `window`
+/// (in seconds, default 60) are discarded.
+///
+/// The last row is used for the time axis labels.
+///
+/// ```text
+/// timeseries [window: 30, min: 0, max: 100, height: 10]
+/// ```
+///
+/// Samples are added through the widget:
+/// ```ignore
+/// elements.by_tag("timeseries").first(|el, _| el.to::<TimeSeries>().push(now, cpu));
+/// ```
+#[derive(Debug)]
+pub struct TimeSeries {
+    samples: VecDeque<Sample>,
+    window: f64,
+    is_dirty: bool,
+}
+
+impl TimeSeries {
+    /// Add a sample. Samples are expected to be added in chronological order.
+    pub fn push(&mut self, time: f64, value: f64) {
+        self.is_dirty = true;
+        self.samples.push_back(Sample { time, value });
+        self.discard_old();
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> + '_ {
+        self.samples.iter()
+    }
+
+    /// Remove all samples
+    pub fn clear(&mut self) {
+        self.is_dirty = true;
+        self.samples.clear();
+    }
+
+    fn latest(&self) -> f64 {
+        self.samples.back().map(|s| s.time).unwrap_or(0.0)
+    }
+
+    fn discard_old(&mut self) {
+        let start = self.latest() - self.window;
+        while self.samples.front().map(|s| s.time < start).unwrap_or(false) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn value_range(&self) -> (f64, f64) {
+        self.samples
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), s| (min.min(s.value), max.max(s.value)))
+    }
+
+    // Value of the most recent sample in each column
+    fn columns(&self, width: usize) -> Vec<Option<f64>> {
+        let mut columns = vec![None; width];
+        if width == 0 {
+            return columns;
+        }
+
+        let end = self.latest();
+        let start = end - self.window;
+        let step = self.window / width as f64;
+
+        for sample in &self.samples {
+            let col = ((sample.time - start) / step) as usize;
+            columns[col.min(width - 1)] = Some(sample.value);
+        }
+
+        columns
+    }
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window: DEFAULT_WINDOW,
+            is_dirty: true,
+        }
+    }
+}
+
+impl Widget for TimeSeries {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(WINDOW, AttributeType::Float, Some("60.0"))
+            .attribute(MIN, AttributeType::Float, None)
+            .attribute(MAX, AttributeType::Float, None)
+            .attribute(POINT, AttributeType::Char, Some("\"•\""))
+            .attribute(WIDTH, AttributeType::Int, None)
+            .attribute(HEIGHT, AttributeType::Int, None);
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        mut constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+
+        if let Some(width) = attributes.get_usize(WIDTH) {
+            constraints.set_max_width(width);
+        }
+
+        if let Some(height) = attributes.get_usize(HEIGHT) {
+            constraints.set_max_height(height);
+        }
+
+        let window = attributes.get_float(WINDOW).unwrap_or(DEFAULT_WINDOW);
+        if window > 0.0 && window != self.window {
+            self.window = window;
+            self.discard_old();
+        }
+
+        self.is_dirty = false;
+        constraints.max_size()
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+        // The time series has no children
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let Size { width, height } = ctx.local_size;
+        if width == 0 || height < 2 {
+            return;
+        }
+
+        let attributes = attribute_storage.get(id);
+        let (auto_min, auto_max) = self.value_range();
+        let min = attributes.get_float(MIN).unwrap_or(auto_min);
+        let max = attributes.get_float(MAX).unwrap_or(auto_max);
+        let point = attributes
+            .get_ref::<&str>(POINT)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(DEFAULT_POINT);
+
+        // Plot
+        let plot_height = height - 1;
+        for (x, value) in self.columns(width).into_iter().enumerate() {
+            let Some(value) = value else { continue };
+            let row = match max > min {
+                true => ((value.clamp(min, max) - min) / (max - min) * (plot_height - 1) as f64).round() as usize,
+                false => 0,
+            };
+            let y = plot_height - 1 - row;
+            ctx.place_glyph(point, LocalPos::new(x as u16, y as u16));
+        }
+
+        // Time axis
+        let y = height as u16 - 1;
+        let labels = [
+            (0, format!("-{}s", self.window.round())),
+            (width / 2, format!("-{}s", (self.window / 2.0).round())),
+            (width, "0s".to_string()),
+        ];
+
+        let mut next_free = 0;
+        for (x, label) in labels {
+            let len = label.chars().count();
+            let x = match x {
+                0 => 0,
+                x if x == width => width.saturating_sub(len),
+                x => x.saturating_sub(len / 2),
+            };
+
+            if x < next_free || x + len > width {
+                continue;
+            }
+            ctx.place_glyphs(&label, LocalPos::new(x as u16, y));
+            next_free = x + len + 1;
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn discard_samples_outside_window() {
+        let mut series = TimeSeries::default();
+        series.push(0.0, 1.0);
+        series.push(30.0, 1.0);
+        series.push(61.0, 1.0);
+        assert_eq!(series.samples().count(), 2);
+    }
+
+    #[test]
+    fn plot_samples() {
+        let expected = "
+            ╔════════════╗
+            ║           •║
+            ║      •     ║
+            ║•           ║
+            ║-10s -5s  0s║
+            ╚════════════╝
+        ";
+
+        TestRunner::new("timeseries [window: 10]", (12, 4))
+            .instance()
+            .with_widget(|mut elements| {
+                elements.by_tag("timeseries").first(|el, _| {
+                    let series = el.to::<TimeSeries>();
+                    series.push(0.0, 0.0);
+                    series.push(5.0, 5.0);
+                    series.push(10.0, 10.0);
+                });
+            })
+            .render_assert(expected);
+    }
+}
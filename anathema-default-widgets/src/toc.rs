@@ -0,0 +1,206 @@
+use anathema_geometry::{LocalPos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{CellAttributes, PaintCtx, SizePos};
+use anathema_widgets::{
+    Anchor, AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget,
+    WidgetId, WidgetMetadata,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::style::{Inverse, Prefixed};
+
+const SELECTED: &str = "selected";
+const INDENT: &str = "indent";
+const DEFAULT_INDENT: usize = 2;
+
+/// A table of contents, with one row per anchor.
+///
+/// Each row is indented by `indent` cells (defaults to 2) per level below the top level.
+/// The row at the `selected` index is painted with the `selected_` attributes,
+/// or inverted if there are none.
+///
+/// ```text
+/// hstack
+///     toc [selected: selected]
+///     overflow
+///         text [anchor: 'Usage'] '# Usage'
+///         text [anchor: 'Options', level: 2] '## Options'
+/// ```
+///
+/// The anchors are found with [`Elements::anchors`](anathema_widgets::Elements::anchors),
+/// and activating an entry scrolls to the anchor:
+/// ```ignore
+/// let anchors = elements.anchors();
+/// let mut target = None;
+/// elements.by_tag("toc").first(|el, _| {
+///     let toc = el.to::<Toc>();
+///     toc.set_anchors(&anchors);
+///     target = toc.selected().map(|anchor| anchor.id);
+/// });
+/// if let Some(id) = target {
+///     elements.scroll_into_view(id);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Toc {
+    anchors: Vec<Anchor>,
+    selected: Option<usize>,
+    indent: usize,
+    is_dirty: bool,
+}
+
+impl Toc {
+    /// Replace the entries
+    pub fn set_anchors(&mut self, anchors: &[Anchor]) {
+        if self.anchors != anchors {
+            self.anchors = anchors.to_vec();
+            self.is_dirty = true;
+        }
+    }
+
+    pub fn anchors(&self) -> &[Anchor] {
+        &self.anchors
+    }
+
+    /// The anchor of the selected entry
+    pub fn selected(&self) -> Option<&Anchor> {
+        self.anchors.get(self.selected?)
+    }
+
+    /// The anchor of the entry on the row, e.g for selecting an entry with the mouse
+    pub fn anchor_at(&self, row: usize) -> Option<&Anchor> {
+        self.anchors.get(row)
+    }
+
+    fn row_indent(&self, anchor: &Anchor) -> usize {
+        (anchor.level - 1) * self.indent
+    }
+}
+
+impl Widget for Toc {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::None)
+            .attribute(SELECTED, AttributeType::Int, None)
+            .attribute(INDENT, AttributeType::Int, Some("2"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        _: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        self.selected = attributes.get_usize(SELECTED);
+        self.indent = attributes.get_usize(INDENT).unwrap_or(DEFAULT_INDENT);
+        self.is_dirty = false;
+
+        let width = self
+            .anchors
+            .iter()
+            .map(|anchor| self.row_indent(anchor) + anchor.name.width())
+            .max()
+            .unwrap_or(0);
+
+        Size::new(
+            width.clamp(constraints.min_width, constraints.max_width()),
+            self.anchors
+                .len()
+                .clamp(constraints.min_height, constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        _: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        _: &AttributeStorage<'bp>,
+        _: PositionCtx,
+    ) {
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        _: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        let attributes = attribute_storage.get(id);
+        let selected = Prefixed::new(SELECTED, attributes);
+        let selected_style: &dyn CellAttributes = match selected.is_empty() {
+            true => &Inverse,
+            false => &selected,
+        };
+
+        for (row, anchor) in self.anchors.iter().enumerate().take(ctx.local_size.height) {
+            let pos = LocalPos::new(self.row_indent(anchor) as u16, row as u16);
+            if self.selected == Some(row) {
+                for x in 0..ctx.local_size.width as u16 {
+                    ctx.set_attributes(selected_style, LocalPos::new(x, row as u16));
+                }
+            }
+            ctx.place_glyphs(&anchor.name, pos);
+        }
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn list_and_scroll_to_anchor() {
+        let tpl = "
+            hstack
+                toc [selected: 1]
+                overflow
+                    text [anchor: 'One'] '# One'
+                    text 'a'
+                    text 'b'
+                    text [anchor: 'Two', level: 2] '## Two'
+                    text 'c'
+        ";
+
+        let expected_toc = "
+            ╔═════════════╗
+            ║One  # One   ║
+            ║  Twoa       ║
+            ║     b       ║
+            ╚═════════════╝
+        ";
+
+        let expected_scrolled = "
+            ╔═════════════╗
+            ║One  a       ║
+            ║  Twob       ║
+            ║     ## Two  ║
+            ╚═════════════╝
+        ";
+
+        TestRunner::new(tpl, (13, 3))
+            .instance()
+            .with_widget(|mut elements| {
+                let anchors = elements.anchors();
+                elements
+                    .by_tag("toc")
+                    .first(|el, _| el.to::<Toc>().set_anchors(&anchors));
+            })
+            .render_assert(expected_toc)
+            .with_widget(|mut elements| {
+                let mut target = None;
+                elements
+                    .by_tag("toc")
+                    .first(|el, _| target = el.to::<Toc>().selected().map(|anchor| anchor.id));
+                assert!(elements.scroll_into_view(target.unwrap()));
+            })
+            .render_assert(expected_scrolled);
+    }
+}
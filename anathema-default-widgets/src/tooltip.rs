@@ -0,0 +1,326 @@
+use std::ops::ControlFlow;
+
+use anathema::CommonVal;
+use anathema_geometry::{LocalPos, Pos, Rect, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+const SIDE: &str = "side";
+const ABOVE: &str = "above";
+const BELOW: &str = "below";
+const LEFT: &str = "left";
+const RIGHT: &str = "right";
+
+/// The side of the target the tooltip is placed on
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum TooltipSide {
+    Above,
+    #[default]
+    Below,
+    Left,
+    Right,
+}
+
+impl TooltipSide {
+    fn flip(self) -> Self {
+        match self {
+            Self::Above => Self::Below,
+            Self::Below => Self::Above,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+impl TryFrom<CommonVal<'_>> for TooltipSide {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        match value {
+            CommonVal::Str(side) => match side {
+                ABOVE => Ok(TooltipSide::Above),
+                BELOW => Ok(TooltipSide::Below),
+                LEFT => Ok(TooltipSide::Left),
+                RIGHT => Ok(TooltipSide::Right),
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+/// A tooltip placed next to another widget.
+///
+/// The tooltip is placed on the preferred `side` of the target
+/// (`above`, `below`, `left` or `right`, defaults to `below`),
+/// and flips to the opposite side if it would not fit on the screen.
+///
+/// ```text
+/// text 'Save'
+/// tooltip [side: 'right']
+///     text 'Save the file'
+/// ```
+///
+/// The tooltip is hidden until it has a target:
+/// ```ignore
+/// let mut target = None;
+/// elements.by_tag("text").first(|el, _| target = Some(el.id()));
+/// elements.by_tag("tooltip").first(|el, _| el.to::<Tooltip>().set_target(target));
+/// ```
+#[derive(Debug, Default)]
+pub struct Tooltip {
+    target: Option<WidgetId>,
+    anchor: Option<Rect>,
+    side: TooltipSide,
+    pos: Pos,
+    size: Size,
+    is_dirty: bool,
+}
+
+impl Tooltip {
+    /// Set the widget to place the tooltip next to.
+    /// The tooltip is hidden if the target is `None`.
+    pub fn set_target(&mut self, target: Option<WidgetId>) {
+        if target.is_none() {
+            self.anchor = None;
+        }
+        self.target = target;
+        self.is_dirty = true;
+    }
+
+    pub fn target(&self) -> Option<WidgetId> {
+        self.target
+    }
+
+    /// The side of the target the tooltip was placed on,
+    /// which is not the preferred side if the tooltip didn't fit.
+    pub fn side(&self) -> TooltipSide {
+        self.side
+    }
+
+    fn fits(&self, side: TooltipSide, anchor: Rect, size: Size, screen: Size) -> bool {
+        let (width, height) = (size.width as i32, size.height as i32);
+        match side {
+            TooltipSide::Above => anchor.start.y - height >= 0,
+            TooltipSide::Below => anchor.end.y + height <= screen.height as i32,
+            TooltipSide::Left => anchor.start.x - width >= 0,
+            TooltipSide::Right => anchor.end.x + width <= screen.width as i32,
+        }
+    }
+
+    fn place(&self, anchor: Rect, size: Size, screen: Size) -> Pos {
+        let (width, height) = (size.width as i32, size.height as i32);
+        let pos = match self.side {
+            TooltipSide::Above => Pos::new(anchor.start.x, anchor.start.y - height),
+            TooltipSide::Below => Pos::new(anchor.start.x, anchor.end.y),
+            TooltipSide::Left => Pos::new(anchor.start.x - width, anchor.start.y),
+            TooltipSide::Right => Pos::new(anchor.end.x, anchor.start.y),
+        };
+
+        // Keep the tooltip on the screen
+        Pos::new(
+            pos.x.min(screen.width as i32 - width).max(0),
+            pos.y.min(screen.height as i32 - height).max(0),
+        )
+    }
+}
+
+impl Widget for Tooltip {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::One).attribute(
+            "side",
+            AttributeType::Enum(&["above", "below", "left", "right"]),
+            Some("\"below\""),
+        );
+        Some(metadata)
+    }
+
+    fn floats(&self) -> bool {
+        true
+    }
+
+    fn anchor(&self) -> Option<WidgetId> {
+        self.target
+    }
+
+    fn set_anchor_region(&mut self, region: Option<Rect>) {
+        self.anchor = region;
+    }
+
+    fn painted_region(&self, _: Pos, _: Size) -> Rect {
+        match self.anchor {
+            Some(_) => Rect::from((self.pos, self.size)),
+            None => Rect::ZERO,
+        }
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        _: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let preferred = ctx.attribs.get(id).get(SIDE).unwrap_or_default();
+        self.is_dirty = false;
+        self.size = Size::ZERO;
+
+        let Some(anchor) = self.anchor else { return Size::ZERO };
+
+        let constraints = ctx.viewport.constraints();
+        children.for_each(|child, children| {
+            self.size = child.layout(children, constraints, ctx);
+            ControlFlow::Break(())
+        });
+
+        let screen = ctx.viewport.size();
+        self.side = match self.fits(preferred, anchor, self.size, screen) {
+            true => preferred,
+            false if self.fits(preferred.flip(), anchor, self.size, screen) => preferred.flip(),
+            false => preferred,
+        };
+        self.pos = self.place(anchor, self.size, screen);
+
+        // The tooltip paints its own area, as it's not placed
+        // where the container is
+        Size::ZERO
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        children.for_each(|child, children| {
+            child.position(children, self.pos, attribute_storage, ctx.viewport);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        if self.anchor.is_none() {
+            return;
+        }
+
+        ctx.clip = None;
+        ctx.update(self.size, self.pos);
+
+        let attributes = attribute_storage.get(id);
+        for y in 0..self.size.height as u16 {
+            for x in 0..self.size.width as u16 {
+                ctx.set_attributes(attributes, LocalPos::new(x, y));
+            }
+        }
+
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Break(())
+        });
+    }
+
+    fn needs_reflow(&self) -> bool {
+        self.is_dirty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestRunner;
+
+    fn render(tpl: &str, size: (usize, usize), expected: &str, side: TooltipSide) {
+        TestRunner::new(tpl, size)
+            .instance()
+            .with_widget(|mut elements| {
+                let mut target = None;
+                elements
+                    .by_attribute("target", true)
+                    .first(|el, _| target = Some(el.id()));
+                elements
+                    .by_tag("tooltip")
+                    .first(|el, _| el.to::<Tooltip>().set_target(target));
+            })
+            .render_assert(expected)
+            .with_widget(|mut elements| {
+                elements
+                    .by_tag("tooltip")
+                    .first(|el, _| assert_eq!(el.to::<Tooltip>().side(), side));
+            });
+    }
+
+    #[test]
+    fn below_target() {
+        let tpl = "
+            vstack
+                text [target: true] 'save'
+                tooltip
+                    text 'tip'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║save  ║
+            ║tip   ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        render(tpl, (6, 3), expected, TooltipSide::Below);
+    }
+
+    #[test]
+    fn flip_when_overflowing() {
+        let tpl = "
+            vstack
+                text 'a'
+                text 'b'
+                text [target: true] 'save'
+                tooltip
+                    vstack
+                        text 'tip'
+                        text 'tap'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║tip   ║
+            ║tap   ║
+            ║save  ║
+            ╚══════╝
+        ";
+
+        render(tpl, (6, 3), expected, TooltipSide::Above);
+    }
+
+    #[test]
+    fn hidden_without_target() {
+        let tpl = "
+            vstack
+                text 'save'
+                tooltip
+                    text 'tip'
+        ";
+
+        let expected = "
+            ╔══════╗
+            ║save  ║
+            ║      ║
+            ╚══════╝
+        ";
+
+        TestRunner::new(tpl, (6, 2)).instance().render_assert(expected);
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::ControlFlow;
+
+use anathema_geometry::{Pos, Size};
+use anathema_widgets::layout::{Constraints, LayoutCtx, PositionCtx};
+use anathema_widgets::paint::{PaintCtx, SizePos};
+use anathema_widgets::{
+    AttributeStorage, AttributeType, Children, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId,
+    WidgetMetadata,
+};
+
+const SPACING: &str = "spacing";
+const RUN_SPACING: &str = "run_spacing";
+
+/// Lays out the children horizontally, continuing on the next row (run)
+/// when the next child doesn't fit in what is left of the width.
+///
+/// `spacing` is the number of cells between the children of a run,
+/// and `run_spacing` is the number of lines between the runs.
+///
+/// ```text
+/// wrap [spacing: 1, run_spacing: 1]
+///     for tag in tags
+///         text tag
+/// ```
+#[derive(Debug, Default)]
+pub struct Wrap {
+    // Offset of each child, relative to the widget
+    offsets: Vec<Pos>,
+    runs: usize,
+}
+
+impl Wrap {
+    /// The number of rows the children are laid out on
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+}
+
+impl Widget for Wrap {
+    fn metadata() -> Option<WidgetMetadata> {
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute(SPACING, AttributeType::Int, Some("0"))
+            .attribute(RUN_SPACING, AttributeType::Int, Some("0"));
+        Some(metadata)
+    }
+
+    fn layout<'bp>(
+        &mut self,
+        mut children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size {
+        let attributes = ctx.attribs.get(id);
+        let spacing = attributes.get_usize(SPACING).unwrap_or(0);
+        let run_spacing = attributes.get_usize(RUN_SPACING).unwrap_or(0);
+
+        let max_width = constraints.max_width();
+        let child_constraints = Constraints::new(max_width, constraints.max_height());
+
+        self.offsets.clear();
+        self.runs = 0;
+        let mut width = 0;
+        let mut x = 0;
+        let mut y = 0;
+        let mut run_height = 0;
+
+        children.for_each(|child, children| {
+            let size = child.layout(children, child_constraints, ctx);
+
+            // The first child of a run is always placed, even if it's too wide
+            if self.runs == 0 {
+                self.runs = 1;
+            } else if x + spacing + size.width > max_width {
+                y += run_height + run_spacing;
+                x = 0;
+                run_height = 0;
+                self.runs += 1;
+            } else {
+                x += spacing;
+            }
+
+            self.offsets.push(Pos::new(x as i32, y as i32));
+            x += size.width;
+            width = width.max(x);
+            run_height = run_height.max(size.height);
+            ControlFlow::Continue(())
+        });
+
+        let height = y + run_height;
+        Size::new(
+            width.max(constraints.min_width).min(max_width),
+            height.max(constraints.min_height).min(constraints.max_height()),
+        )
+    }
+
+    fn position<'bp>(
+        &mut self,
+        mut children: PositionChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    ) {
+        let mut index = 0;
+        children.for_each(|child, children| {
+            let offset = self.offsets.get(index).copied().unwrap_or(Pos::ZERO);
+            child.position(children, ctx.pos + offset, attribute_storage, ctx.viewport);
+            index += 1;
+            ControlFlow::Continue(())
+        });
+    }
+
+    fn paint<'bp>(
+        &mut self,
+        mut children: PaintChildren<'_, '_, 'bp>,
+        _: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        mut ctx: PaintCtx<'_, SizePos>,
+    ) {
+        children.for_each(|child, children| {
+            let ctx = ctx.to_unsized();
+            child.paint(children, ctx, attribute_storage);
+            ControlFlow::Continue(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::TestRunner;
+
+    #[test]
+    fn wrap_children() {
+        let tpl = "
+            wrap [spacing: 1, run_spacing: 1]
+                for tag in ['one', 'two', 'three', 'four']
+                    text tag
+        ";
+
+        let expected = "
+            ╔═════════╗
+            ║one two  ║
+            ║         ║
+            ║three    ║
+            ║         ║
+            ║four     ║
+            ╚═════════╝
+        ";
+
+        TestRunner::new(tpl, (9, 5)).instance().render_assert(expected);
+    }
+}
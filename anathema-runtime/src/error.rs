@@ -10,6 +10,8 @@ pub enum Error {
     Template(TemplateError),
     Notify(notify::Error),
     Widget(anathema_widgets::error::Error),
+    MissingOverlay(String),
+    InvalidKeyBinding(String),
     Stop,
 }
 
@@ -20,6 +22,8 @@ impl Display for Error {
             Error::Stop => write!(f, "stopping"),
             Error::Notify(err) => write!(f, "{err}"),
             Error::Widget(err) => write!(f, "{err}"),
+            Error::MissingOverlay(name) => write!(f, "`{name}` is not an overlay"),
+            Error::InvalidKeyBinding(chord) => write!(f, "`{chord}` is not a valid key binding"),
         }
     }
 }
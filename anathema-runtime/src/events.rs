@@ -4,13 +4,20 @@ use std::time::{Duration, Instant};
 use anathema_backend::Backend;
 use anathema_geometry::Size;
 use anathema_state::{AnyState, CommonVal, States};
+use anathema_templates::{SourceKind, ToSourceKind};
 use anathema_widgets::components::events::{Event, KeyCode, KeyEvent, KeyState};
-use anathema_widgets::components::{AssociatedEvents, ComponentId, Emitter, FocusQueue, UntypedContext};
+use anathema_widgets::components::{
+    AnyComponent, AssociatedEvents, Component, ComponentId, Emitter, Exit, FocusQueue, OverlayQueue, UntypedContext,
+};
 use anathema_widgets::layout::{Constraints, Viewport};
 use anathema_widgets::{AttributeStorage, Components, DirtyWidgets, Elements, WidgetKind, WidgetTree};
 
 use crate::error::{Error, Result};
+use crate::frame::FrameReport;
+use crate::keymap::KeyMap;
+use crate::macros::Macros;
 use crate::tree::Tree;
+use crate::TAB_INDEX;
 
 // -----------------------------------------------------------------------------
 //   - Ctrl-c quit test -
@@ -37,113 +44,197 @@ fn tab<'bp>(event_ctx: &mut EventCtx<'_, '_, 'bp>, tree: &mut WidgetTree<'bp>, e
         ..
     }) = event
     {
+        #[derive(Copy, Clone)]
         enum Dir {
             F,
             B,
         }
 
-        let index = event_ctx.components.tab_index;
         let dir = match code {
             KeyCode::Tab => Dir::F,
             KeyCode::BackTab => Dir::B,
             _ => return Some(event),
         };
 
-        loop {
-            // -----------------------------------------------------------------------------
-            //   - Blur -
-            // -----------------------------------------------------------------------------
-            if let Some((widget_id, state_id)) = event_ctx.components.get(event_ctx.components.tab_index) {
-                tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_blur(ctx));
-            }
-
-            // -----------------------------------------------------------------------------
-            //   - Change index -
-            // -----------------------------------------------------------------------------
-            match dir {
-                Dir::F => {
-                    event_ctx.components.tab_index += 1;
-                    if event_ctx.components.tab_index >= event_ctx.components.len() {
-                        event_ctx.components.tab_index = 0;
-                    }
-                }
-                Dir::B => match event_ctx.components.tab_index >= 1 {
-                    true => event_ctx.components.tab_index -= 1,
-                    false => event_ctx.components.tab_index = event_ctx.components.len() - 1,
-                },
-            }
+        // Only the components of the top most overlay, or the active focus trap, can receive focus
+        let order = tab_order(event_ctx);
+        let len = order.len();
+        let current = order.iter().position(|&i| i == event_ctx.components.tab_index);
+
+        // The next component that accepts focus, wrapping around
+        let next = (1..=len)
+            .map(|step| match (dir, current) {
+                (Dir::F, Some(current)) => (current + step) % len,
+                (Dir::B, Some(current)) => (current + len - step) % len,
+                (Dir::F, None) => step - 1,
+                (Dir::B, None) => len - step,
+            })
+            .map(|pos| order[pos])
+            .find(|&i| accepts_focus(event_ctx, tree, i));
+
+        let next = next?;
+        if Some(next) == current.map(|pos| order[pos]) {
+            return None;
+        }
 
-            if index == event_ctx.components.tab_index {
-                break;
-            }
+        // -----------------------------------------------------------------------------
+        //   - Blur -
+        // -----------------------------------------------------------------------------
+        if let Some((widget_id, state_id)) = event_ctx.components.current() {
+            tree.set_focus(widget_id, state_id, event_ctx, false);
+        }
 
-            // -----------------------------------------------------------------------------
-            //   - Focus -
-            // -----------------------------------------------------------------------------
-            if let Some((widget_id, state_id)) = event_ctx.components.current() {
-                tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_focus(ctx));
-
-                let cont = tree
-                    .with_component(widget_id, state_id, event_ctx, |comp, ctx| {
-                        if !comp.any_accept_focus() {
-                            return true;
-                        }
-                        comp.any_focus(ctx);
-                        false
-                    })
-                    .unwrap_or(true);
-
-                if !cont {
-                    break;
-                }
-            }
+        // -----------------------------------------------------------------------------
+        //   - Focus -
+        // -----------------------------------------------------------------------------
+        event_ctx.components.tab_index = next;
+        if let Some((widget_id, state_id)) = event_ctx.components.current() {
+            tree.set_focus(widget_id, state_id, event_ctx, true);
         }
 
         return None;
     }
 
-    // Mouse events are global
-    if let Event::Mouse(_) = event {
-        for i in 0..event_ctx.components.len() {
+    // Mouse events are global, apart from the layers underneath an overlay.
+    // The component under the mouse gets the position relative to the component.
+    if let Event::Mouse(mouse) = event {
+        let (nodes, values) = tree.split();
+        let elements = Elements::new(nodes, values, event_ctx.attribute_storage, event_ctx.dirty_widgets);
+        let hit = elements.component_at(mouse.pos());
+
+        for i in event_ctx.components.focus_range() {
             let (widget_id, state_id) = event_ctx
                 .components
                 .get(i)
                 .expect("components can not change during this call");
 
-            tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_event(ctx, event));
+            let mut mouse = mouse;
+            mouse.local = hit.and_then(|(id, local)| (id == widget_id).then_some(local));
+            tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| {
+                comp.any_event(ctx, Event::Mouse(mouse))
+            });
         }
     }
 
     Some(event)
 }
 
+// Send the event to the focused component at `index`.
+// Key events are captured by the ancestors of the component first, starting from the root,
+// and bubble up through the ancestors afterwards, until a component handles the event.
+fn dispatch<'bp>(event_ctx: &mut EventCtx<'_, '_, 'bp>, tree: &mut WidgetTree<'bp>, index: usize, event: Event) {
+    let ancestors = match event {
+        Event::Key(_) => event_ctx.components.ancestors(index),
+        _ => vec![],
+    };
+
+    let mut send = |index: usize, capture: bool| {
+        let (widget_id, state_id) = event_ctx
+            .components
+            .get(index)
+            .expect("components can not change during this call");
+        tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| match capture {
+            true => comp.any_capture(ctx, event),
+            false => comp.any_event(ctx, event),
+        })
+        .flatten()
+    };
+
+    if ancestors.iter().any(|&i| send(i, true).is_none()) {
+        return;
+    }
+
+    if send(index, false).is_none() {
+        return;
+    }
+
+    for &i in ancestors.iter().rev() {
+        if send(i, false).is_none() {
+            return;
+        }
+    }
+}
+
+// The indices of the components in the order they are reached with tab
+fn tab_order(event_ctx: &mut EventCtx<'_, '_, '_>) -> Vec<usize> {
+    let attribute_storage = &*event_ctx.attribute_storage;
+    event_ctx
+        .components
+        .tab_order(|widget_id| attribute_storage.get(widget_id).get_int(TAB_INDEX))
+}
+
+fn accepts_focus<'bp>(event_ctx: &mut EventCtx<'_, '_, 'bp>, tree: &mut WidgetTree<'bp>, index: usize) -> bool {
+    let Some((widget_id, state_id)) = event_ctx.components.get(index) else { return false };
+    tree.with_component(widget_id, state_id, event_ctx, |comp, _| comp.any_accept_focus())
+        .unwrap_or(false)
+}
+
+// A component replaced through the [GlobalContext]
+pub(super) struct ComponentSwap {
+    pub(super) name: String,
+    pub(super) template: SourceKind,
+    pub(super) component: Box<dyn AnyComponent>,
+    pub(super) state: Box<dyn AnyState>,
+}
+
 pub(super) struct EventHandler<T> {
     global: T,
+    layout: Option<String>,
+    swaps: Vec<ComponentSwap>,
+    // An event that arrived while the components were idle
+    deferred: Option<Event>,
+    long_frame: Option<FrameReport>,
+    keymap: KeyMap,
+    macros: Macros,
 }
 
 impl<T: GlobalEvents> EventHandler<T> {
-    pub fn new(global: T) -> Self {
-        Self { global }
+    pub fn new(global: T, keymap: KeyMap) -> Self {
+        Self {
+            global,
+            layout: None,
+            swaps: vec![],
+            deferred: None,
+            long_frame: None,
+            keymap,
+            macros: Macros::default(),
+        }
+    }
+
+    pub(super) fn macros(&mut self) -> &mut Macros {
+        &mut self.macros
+    }
+
+    // The last frame that went over the frame budget
+    pub(super) fn set_long_frame(&mut self, report: FrameReport) {
+        self.long_frame = Some(report);
+    }
+
+    // Handle the event first thing in the next frame
+    pub(super) fn defer(&mut self, event: Event) {
+        self.deferred = Some(event);
+    }
+
+    // The layout requested through the [GlobalContext]
+    pub(super) fn take_layout(&mut self) -> Option<String> {
+        self.layout.take()
+    }
+
+    // The components replaced through the [GlobalContext]
+    pub(super) fn take_swaps(&mut self) -> Vec<ComponentSwap> {
+        std::mem::take(&mut self.swaps)
     }
 
     pub(super) fn set_initial_focus<'bp>(&mut self, tree: &mut WidgetTree<'bp>, event_ctx: &mut EventCtx<'_, '_, 'bp>) {
         // Find the first widget that accepts focus, if no widget accepts focus then move on
-        for i in 0..event_ctx.components.len() {
-            if let Some((widget_id, state_id)) = event_ctx.components.get(i) {
-                let cont = tree
-                    .with_component(widget_id, state_id, event_ctx, |comp, ctx| {
-                        if comp.any_accept_focus() {
-                            comp.any_focus(ctx);
-                            false
-                        } else {
-                            true
-                        }
-                    })
-                    .unwrap_or(false);
-                if !cont {
-                    event_ctx.components.tab_index = i;
-                    break;
-                }
+        let first = tab_order(event_ctx)
+            .into_iter()
+            .find(|&i| accepts_focus(event_ctx, tree, i));
+
+        if let Some(index) = first {
+            event_ctx.components.tab_index = index;
+            if let Some((widget_id, state_id)) = event_ctx.components.current() {
+                tree.set_focus(widget_id, state_id, event_ctx, true);
             }
         }
     }
@@ -159,7 +250,20 @@ impl<T: GlobalEvents> EventHandler<T> {
         constraints: &mut Constraints,
         event_ctx: &mut EventCtx<'_, '_, 'bp>,
     ) -> Result<()> {
-        while let Some(event) = backend.next_event(poll_duration) {
+        while let Some(event) = self.next_event(backend, poll_duration) {
+            self.macros.record(event);
+
+            // Key bindings come before tabbing, so tab can be bound as well
+            if let Event::Key(key) = event {
+                let focus = event_ctx.components.current_component_id();
+                if self
+                    .keymap
+                    .handle(key, focus, event_ctx.context.emitter, &mut self.macros)
+                {
+                    return Ok(());
+                }
+            }
+
             let event = match self.global.enable_tab_navigation() {
                 false => event,
                 true => match tab(event_ctx, tree, event) {
@@ -172,7 +276,13 @@ impl<T: GlobalEvents> EventHandler<T> {
             let mut elements = Elements::new(nodes, values, event_ctx.attribute_storage, event_ctx.dirty_widgets);
             let mut global_ctx = GlobalContext {
                 focus_queue: event_ctx.focus_queue,
+                overlay_queue: event_ctx.overlay_queue,
+                exit: event_ctx.exit,
                 emitter: event_ctx.context.emitter,
+                layout: &mut self.layout,
+                swaps: &mut self.swaps,
+                long_frame: self.long_frame.as_ref(),
+                macros: &mut self.macros,
             };
 
             let event = match is_ctrl_c(event) {
@@ -185,10 +295,9 @@ impl<T: GlobalEvents> EventHandler<T> {
             let Some(event) = event else { return Ok(()) };
 
             // Ignore mouse events, as they are handled by global event
-            if !event.is_mouse_event() {
-                if let Some((widget_id, state_id)) = event_ctx.components.get(event_ctx.components.tab_index) {
-                    tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_event(ctx, event));
-                }
+            let tab_index = event_ctx.components.tab_index;
+            if !event.is_mouse_event() && event_ctx.components.focus_range().contains(&tab_index) {
+                dispatch(event_ctx, tree, tab_index, event);
             }
 
             match event {
@@ -196,6 +305,7 @@ impl<T: GlobalEvents> EventHandler<T> {
                     let size = Size::from((width, height));
                     backend.resize(size);
                     viewport.resize(size);
+                    viewport.set_cell_size(backend.cell_size());
                     constraints.set_max_width(size.width);
                     constraints.set_max_height(size.height);
 
@@ -244,6 +354,8 @@ impl<T: GlobalEvents> EventHandler<T> {
                         attribute_storage: event_ctx.attribute_storage,
                         assoc_events: event_ctx.assoc_events,
                         focus_queue: event_ctx.focus_queue,
+                        overlay_queue: event_ctx.overlay_queue,
+                        exit: event_ctx.exit,
                         context: event_ctx.context,
                         dirty_widgets: event_ctx.dirty_widgets,
                     };
@@ -260,8 +372,7 @@ impl<T: GlobalEvents> EventHandler<T> {
         //   - Drain focus queue -
         // -----------------------------------------------------------------------------
         while let Some((key, value)) = event_ctx.focus_queue.pop() {
-            let len = event_ctx.components.len();
-            for i in 0..len {
+            for i in event_ctx.components.focus_range() {
                 let (widget_id, state_id) = event_ctx
                     .components
                     .get(i)
@@ -289,12 +400,12 @@ impl<T: GlobalEvents> EventHandler<T> {
                 //   - Blur -
                 // -----------------------------------------------------------------------------
                 if let Some((widget_id, state_id)) = event_ctx.components.get(event_ctx.components.tab_index) {
-                    tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_blur(ctx));
+                    tree.set_focus(widget_id, state_id, event_ctx, false);
                 }
 
                 if found {
                     event_ctx.components.tab_index = i;
-                    tree.with_component(widget_id, state_id, event_ctx, |comp, ctx| comp.any_focus(ctx));
+                    tree.set_focus(widget_id, state_id, event_ctx, true);
                     break;
                 }
             }
@@ -302,6 +413,14 @@ impl<T: GlobalEvents> EventHandler<T> {
 
         Ok(())
     }
+
+    // The deferred event, then the events of the macros that are playing, then new input
+    fn next_event(&mut self, backend: &mut impl Backend, poll_duration: Duration) -> Option<Event> {
+        self.deferred
+            .take()
+            .or_else(|| self.macros.next_event())
+            .or_else(|| backend.next_event(poll_duration))
+    }
 }
 
 // TODO: rename this, it has nothing to do with the events,
@@ -313,12 +432,20 @@ pub(crate) struct EventCtx<'a, 'rt, 'bp> {
     pub attribute_storage: &'a mut AttributeStorage<'bp>,
     pub assoc_events: &'a mut AssociatedEvents,
     pub focus_queue: &'a mut FocusQueue<'static>,
+    pub overlay_queue: &'a mut OverlayQueue,
+    pub exit: &'a mut Option<Exit>,
     pub context: UntypedContext<'rt>,
 }
 
 pub struct GlobalContext<'rt> {
     emitter: &'rt Emitter,
     focus_queue: &'rt mut FocusQueue<'static>,
+    overlay_queue: &'rt mut OverlayQueue,
+    exit: &'rt mut Option<Exit>,
+    layout: &'rt mut Option<String>,
+    swaps: &'rt mut Vec<ComponentSwap>,
+    long_frame: Option<&'rt FrameReport>,
+    macros: &'rt mut Macros,
 }
 
 impl<'rt> GlobalContext<'rt> {
@@ -334,6 +461,68 @@ impl<'rt> GlobalContext<'rt> {
     pub fn set_focus(&mut self, key: impl Into<Cow<'static, str>>, value: impl Into<CommonVal<'static>>) {
         self.focus_queue.push(key.into(), value.into());
     }
+
+    /// Show a named overlay of the document on top of everything else.
+    /// Input is limited to the components inside the overlay until it's closed.
+    pub fn push_overlay(&mut self, name: impl Into<String>) {
+        self.overlay_queue.push(name);
+    }
+
+    /// Close the top most overlay
+    pub fn pop_overlay(&mut self) {
+        self.overlay_queue.pop();
+    }
+
+    /// Stop the runtime once the current events are handled.
+    /// The exit code is returned from [`crate::Runtime::run`].
+    pub fn exit(&mut self, code: i32) {
+        *self.exit = Some(Exit::new(code));
+    }
+
+    /// Stop the runtime with a value that is returned from [`crate::Runtime::run`]
+    /// along with the exit code.
+    pub fn exit_with<V: std::any::Any + Send>(&mut self, code: i32, value: V) {
+        *self.exit = Some(Exit::new(code).with_value(value));
+    }
+
+    /// The report of the last frame that took longer than [`crate::Runtime::frame_budget`]
+    pub fn long_frame(&self) -> Option<&FrameReport> {
+        self.long_frame
+    }
+
+    /// The keyboard macros, to record and play macros from the global events
+    pub fn macros(&mut self) -> &mut Macros {
+        self.macros
+    }
+
+    /// Switch to a named layout of the document.
+    /// The tree is rebuilt once the current events are handled,
+    /// and registered components keep their state.
+    pub fn set_layout(&mut self, name: impl Into<String>) {
+        *self.layout = Some(name.into());
+    }
+
+    /// Replace the template, the component and the state of a registered component,
+    /// e.g. for the next step of a wizard.
+    ///
+    /// All components replaced while handling the current events, along with the layout
+    /// selected with [`GlobalContext::set_layout`], are applied together when the tree is rebuilt,
+    /// so the next frame shows the new templates without any frame in between.
+    /// Trying to replace a component that isn't registered stops the rebuild with an error.
+    pub fn swap_component<C: Component + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        template: impl ToSourceKind,
+        component: C,
+        state: C::State,
+    ) {
+        self.swaps.push(ComponentSwap {
+            name: name.into(),
+            template: template.to_source_kind(),
+            component: Box::new(component),
+            state: Box::new(state),
+        });
+    }
 }
 
 pub trait GlobalEvents {
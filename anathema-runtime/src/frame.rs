@@ -0,0 +1,145 @@
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+use anathema_widgets::WidgetId;
+
+/// A phase of a frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    /// Handling messages sent to components
+    Messages,
+    /// Calling `tick` on the components
+    Tick,
+    /// Handling input
+    Events,
+    /// Applying state changes to the widget tree
+    Update,
+    /// Layout, position and paint
+    Paint,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Messages => write!(f, "messages"),
+            Phase::Tick => write!(f, "tick"),
+            Phase::Events => write!(f, "events"),
+            Phase::Update => write!(f, "update"),
+            Phase::Paint => write!(f, "paint"),
+        }
+    }
+}
+
+/// Where the time went in a frame that took longer than the frame budget,
+/// see [`crate::Runtime::frame_budget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameReport {
+    /// The time the frame took, excluding the time the runtime was idle
+    pub total: Duration,
+    /// The frame budget that was exceeded
+    pub budget: Duration,
+    /// The time spent in each phase, in the order the phases ran
+    pub phases: Vec<(Phase, Duration)>,
+    /// The name of the component that spent the most time handling messages
+    /// and ticks, and how long it took
+    pub component: Option<(String, Duration)>,
+}
+
+impl FrameReport {
+    /// The phase that took the longest
+    pub fn slowest_phase(&self) -> Option<(Phase, Duration)> {
+        self.phases.iter().copied().max_by_key(|(_, duration)| *duration)
+    }
+}
+
+impl Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "frame took {:?} (budget {:?})", self.total, self.budget)?;
+        for (phase, duration) in &self.phases {
+            write!(f, ", {phase}: {duration:?}")?;
+        }
+        if let Some((name, duration)) = &self.component {
+            write!(f, ", slowest component: `{name}` ({duration:?})")?;
+        }
+        Ok(())
+    }
+}
+
+// Records the time of each phase of a frame
+pub(crate) struct FrameTimer {
+    start: Instant,
+    phase_start: Instant,
+    phases: Vec<(Phase, Duration)>,
+    component: Option<(WidgetId, Duration)>,
+}
+
+impl FrameTimer {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            phase_start: now,
+            phases: vec![],
+            component: None,
+        }
+    }
+
+    pub(crate) fn start(&mut self) {
+        *self = Self::new();
+    }
+
+    pub(crate) fn end_phase(&mut self, phase: Phase) {
+        self.phases.push((phase, self.phase_start.elapsed()));
+        self.phase_start = Instant::now();
+    }
+
+    // Keep track of the slowest component
+    pub(crate) fn component(&mut self, widget_id: WidgetId, duration: Duration) {
+        match self.component {
+            Some((_, slowest)) if slowest >= duration => (),
+            _ => self.component = Some((widget_id, duration)),
+        }
+    }
+
+    // The report of the frame if it went over the budget,
+    // along with the slowest component, which the report doesn't have a name for
+    pub(crate) fn over_budget(&mut self, budget: Duration) -> Option<(FrameReport, Option<(WidgetId, Duration)>)> {
+        let total = self.start.elapsed();
+        if total <= budget {
+            return None;
+        }
+
+        let report = FrameReport {
+            total,
+            budget,
+            phases: std::mem::take(&mut self.phases),
+            component: None,
+        };
+
+        Some((report, self.component.take()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let report = FrameReport {
+            total: Duration::from_millis(40),
+            budget: Duration::from_millis(33),
+            phases: vec![
+                (Phase::Tick, Duration::from_millis(30)),
+                (Phase::Paint, Duration::from_millis(10)),
+            ],
+            component: Some(("main".into(), Duration::from_millis(29))),
+        };
+
+        assert_eq!(report.slowest_phase(), Some((Phase::Tick, Duration::from_millis(30))));
+        assert_eq!(
+            report.to_string(),
+            "frame took 40ms (budget 33ms), tick: 30ms, paint: 10ms, slowest component: `main` (29ms)"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use anathema_widgets::components::{ComponentId, Emitter};
+use anathema_widgets::layout::Viewport;
+use anathema_widgets::{AttributeStorage, DirtyWidgets, Elements, WidgetTree};
+
+pub(crate) type Hook = Box<dyn FnMut(&mut HookContext<'_, '_>)>;
+
+/// Given to the frame hooks, see [`crate::Runtime::before_update`],
+/// [`crate::Runtime::after_layout`] and [`crate::Runtime::after_render`].
+pub struct HookContext<'tree, 'bp> {
+    /// The widgets of the tree, e.g to read their position after layout
+    pub elements: Elements<'tree, 'bp>,
+    pub viewport: Viewport,
+    emitter: &'tree Emitter,
+}
+
+impl HookContext<'_, '_> {
+    /// Send a message to a given component
+    pub fn emit<M: 'static + Send + Sync>(&self, recipient: ComponentId<M>, value: M) {
+        self.emitter
+            .emit(recipient, value)
+            .expect("this will not fail unless the runtime is droped")
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct FrameHooks {
+    pub(crate) before_update: Vec<Hook>,
+    pub(crate) after_layout: Vec<Hook>,
+    pub(crate) after_render: Vec<Hook>,
+}
+
+pub(crate) fn run_hooks<'bp>(
+    hooks: &mut [Hook],
+    tree: &mut WidgetTree<'bp>,
+    attribute_storage: &mut AttributeStorage<'bp>,
+    dirty_widgets: &mut DirtyWidgets,
+    viewport: Viewport,
+    emitter: &Emitter,
+) {
+    if hooks.is_empty() {
+        return;
+    }
+
+    let (nodes, values) = tree.split();
+    let mut ctx = HookContext {
+        elements: Elements::new(nodes, values, attribute_storage, dirty_widgets),
+        viewport,
+        emitter,
+    };
+
+    for hook in hooks {
+        hook(&mut ctx);
+    }
+}
@@ -0,0 +1,249 @@
+use anathema_templates::WidgetComponentId;
+use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+use anathema_widgets::components::{ComponentId, Emitter};
+
+use crate::error::{Error, Result};
+use crate::macros::Macros;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Key {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+pub(crate) enum Action {
+    Send(Box<dyn Fn(&Emitter)>),
+    // Start or stop recording a macro to the register
+    RecordMacro(String),
+    PlayMacro(String),
+}
+
+struct Binding {
+    keys: Box<[Key]>,
+    // The binding is only active while this component has focus
+    focus: Option<WidgetComponentId>,
+    action: Action,
+}
+
+/// Key chords mapped to messages, see [`RuntimeBuilder::bind`](crate::RuntimeBuilder::bind).
+#[derive(Default)]
+pub(crate) struct KeyMap {
+    bindings: Vec<Binding>,
+    // The keys of the chord that is being typed
+    pending: Vec<Key>,
+}
+
+impl KeyMap {
+    pub(crate) fn bind<M: Clone + Send + Sync + 'static>(
+        &mut self,
+        chord: &str,
+        focus: Option<WidgetComponentId>,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> Result<()> {
+        let send = Box::new(move |emitter: &Emitter| {
+            // Can only fail once the runtime is gone
+            let _ = emitter.emit(recipient, message.clone());
+        });
+        self.bind_action(chord, focus, Action::Send(send))
+    }
+
+    pub(crate) fn bind_action(&mut self, chord: &str, focus: Option<WidgetComponentId>, action: Action) -> Result<()> {
+        let keys = parse_chord(chord).ok_or_else(|| Error::InvalidKeyBinding(chord.into()))?;
+        self.bindings.push(Binding { keys, focus, action });
+        Ok(())
+    }
+
+    /// Perform the action of the chord that ends with the key.
+    /// Returns true if the key was consumed, which is also the case for keys that
+    /// start or continue a chord.
+    pub(crate) fn handle(
+        &mut self,
+        event: KeyEvent,
+        focus: Option<WidgetComponentId>,
+        emitter: &Emitter,
+        macros: &mut Macros,
+    ) -> bool {
+        if self.bindings.is_empty() || !matches!(event.state, KeyState::Press) {
+            return false;
+        }
+
+        self.pending.push(Key {
+            code: event.code,
+            ctrl: event.ctrl,
+        });
+
+        let active = |binding: &&Binding| binding.focus.is_none() || binding.focus == focus;
+
+        // A binding for the focused component overrides one without a focus
+        let exact = self
+            .bindings
+            .iter()
+            .filter(active)
+            .filter(|binding| *binding.keys == *self.pending)
+            .max_by_key(|binding| binding.focus.is_some());
+
+        if let Some(binding) = exact {
+            let keys = binding.keys.len();
+            match &binding.action {
+                Action::Send(send) => send(emitter),
+                Action::RecordMacro(register) => macros.toggle_recording(register, keys),
+                Action::PlayMacro(register) => {
+                    macros.play_after(register, keys);
+                }
+            }
+            self.pending.clear();
+            return true;
+        }
+
+        let started = self
+            .bindings
+            .iter()
+            .filter(active)
+            .any(|binding| binding.keys.starts_with(&self.pending));
+
+        if started {
+            return true;
+        }
+
+        // A key that doesn't continue the chord ends it
+        let ended_chord = self.pending.len() > 1;
+        self.pending.clear();
+        ended_chord
+    }
+}
+
+// Keys are separated by spaces, e.g `Ctrl+x Ctrl+s`
+fn parse_chord(chord: &str) -> Option<Box<[Key]>> {
+    let keys = chord.split_whitespace().map(parse_key).collect::<Option<Box<[_]>>>()?;
+    match keys.is_empty() {
+        true => None,
+        false => Some(keys),
+    }
+}
+
+fn parse_key(key: &str) -> Option<Key> {
+    let (ctrl, name) = match key.split_once('+') {
+        Some((modifier, name)) if modifier.eq_ignore_ascii_case("ctrl") && !name.is_empty() => (true, name),
+        Some((_, name)) if !name.is_empty() => return None,
+        _ => (false, key),
+    };
+
+    let mut chars = name.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match name.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+        },
+    };
+
+    Some(Key { code, ctrl })
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widgets::components::events::Event;
+
+    use super::*;
+
+    fn press(code: KeyCode, ctrl: bool) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl,
+            state: KeyState::Press,
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let keys = parse_chord("Ctrl+x Ctrl+s").unwrap();
+        assert_eq!(
+            keys[1],
+            Key {
+                code: KeyCode::Char('s'),
+                ctrl: true
+            }
+        );
+        assert_eq!(parse_chord("F5").unwrap()[0].code, KeyCode::F(5));
+        assert_eq!(parse_chord("+").unwrap()[0].code, KeyCode::Char('+'));
+        assert!(parse_chord("Alt+x").is_none());
+        assert!(parse_chord("").is_none());
+    }
+
+    #[test]
+    fn chords() {
+        let (sender, receiver) = flume::unbounded();
+        let emitter = Emitter::from(sender);
+        let recipient = ComponentId::<&str>::from(WidgetComponentId::from(0));
+        let focus = Some(WidgetComponentId::from(1));
+
+        let mut keymap = KeyMap::default();
+        keymap.bind("Ctrl+x Ctrl+s", None, recipient, "save").unwrap();
+        keymap.bind("q", None, recipient, "quit").unwrap();
+        keymap.bind("q", focus, recipient, "close").unwrap();
+
+        let mut message = |key, ctrl, focus| {
+            let consumed = keymap.handle(press(key, ctrl), focus, &emitter, &mut Macros::default());
+            let message = receiver
+                .try_recv()
+                .ok()
+                .map(|msg| *msg.payload().downcast::<&str>().unwrap());
+            (consumed, message)
+        };
+
+        assert_eq!(message(KeyCode::Char('x'), true, None), (true, None));
+        assert_eq!(message(KeyCode::Char('s'), true, None), (true, Some("save")));
+        assert_eq!(message(KeyCode::Char('q'), false, None), (true, Some("quit")));
+        assert_eq!(message(KeyCode::Char('q'), false, focus), (true, Some("close")));
+
+        // An unbound key is not consumed, unless it ends a chord
+        assert_eq!(message(KeyCode::Char('a'), false, None), (false, None));
+        assert_eq!(message(KeyCode::Char('x'), true, None), (true, None));
+        assert_eq!(message(KeyCode::Char('a'), false, None), (true, None));
+    }
+
+    #[test]
+    fn macro_bindings() {
+        let emitter = Emitter::from(flume::unbounded().0);
+        let mut macros = Macros::default();
+        let mut keymap = KeyMap::default();
+        keymap
+            .bind_action("Ctrl+x q", None, Action::RecordMacro("a".into()))
+            .unwrap();
+        keymap.bind_action("@", None, Action::PlayMacro("a".into())).unwrap();
+
+        let mut press = |key, ctrl| {
+            let event = press(key, ctrl);
+            macros.record(Event::Key(event));
+            keymap.handle(event, None, &emitter, &mut macros);
+        };
+
+        press(KeyCode::Char('x'), true);
+        press(KeyCode::Char('q'), false);
+        press(KeyCode::Char('a'), false);
+        press(KeyCode::Char('x'), true);
+        press(KeyCode::Char('q'), false);
+        press(KeyCode::Char('@'), false);
+
+        // Only `a` is recorded, and played back
+        assert_eq!(macros.get("a").unwrap().len(), 1);
+        assert!(macros.next_event().is_some());
+        assert!(macros.next_event().is_none());
+    }
+}
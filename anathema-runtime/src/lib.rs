@@ -18,37 +18,66 @@
 // -----------------------------------------------------------------------------
 
 use std::fmt::Write;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use anathema_backend::{Backend, WidgetCycle};
 use anathema_default_widgets::register_default_widgets;
+use anathema_geometry::{Pos, Size};
 use anathema_state::{
     clear_all_changes, clear_all_futures, clear_all_subs, drain_changes, drain_futures, Changes, FutureValues, States,
 };
 use anathema_store::tree::root_node;
 use anathema_templates::blueprints::Blueprint;
 use anathema_templates::{Document, Globals, ToSourceKind};
+use anathema_widgets::components::events::InputSettings;
+use anathema_widgets::components::tasks::{Spawner, Tasks};
 use anathema_widgets::components::{
-    AssociatedEvents, Component, ComponentId, ComponentKind, ComponentRegistry, Emitter, FocusQueue, UntypedContext,
-    ViewMessage,
+    Address, AssociatedEvents, Component, ComponentId, ComponentKind, ComponentRegistry, Emitter, Exit, FocusQueue,
+    OverlayQueue, OverlayRequest, UntypedContext, ViewMessage,
 };
-use anathema_widgets::layout::{Constraints, Viewport};
+use anathema_widgets::layout::{Constraints, Layout, Spacing, Viewport};
 use anathema_widgets::{
     eval_blueprint, try_resolve_future_values, update_tree, AttributeStorage, Components, DirtyWidgets, EvalContext,
     Factory, FloatingWidgets, Scope, WidgetKind, WidgetTree,
 };
-use events::{EventCtx, EventHandler};
+use events::{ComponentSwap, EventCtx, EventHandler};
+use frame::FrameTimer;
+use hooks::{run_hooks, FrameHooks};
+use keymap::{Action, KeyMap};
 use notify::{recommended_watcher, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use timers::Timers;
 use tree::Tree;
 
+// The `State` derive refers to `anathema::state`
+#[cfg(test)]
+extern crate anathema_state as anathema;
+
 pub use self::events::{GlobalContext, GlobalEvents};
+pub use self::frame::{FrameReport, Phase};
+pub use self::hooks::HookContext;
+pub use self::macros::Macros;
+pub use self::timers::TimerId;
 pub use crate::error::{Error, Result};
 
 static REBUILD: AtomicBool = AtomicBool::new(false);
 
+// While an element has this attribute set to true, tab only moves the focus between
+// the components inside the element, e.g `border [focus_trap: state.dialog_open]`
+const FOCUS_TRAP: &str = "focus_trap";
+
+// The position of a component in the tab order, e.g `@input [tab_index: 1]`.
+// Components with a negative tab index are skipped when tabbing.
+const TAB_INDEX: &str = "tab_index";
+
 mod error;
 mod events;
+mod frame;
+mod hooks;
+mod keymap;
+mod macros;
+mod timers;
 mod tree;
 
 pub struct RuntimeBuilder<T, G> {
@@ -59,6 +88,8 @@ pub struct RuntimeBuilder<T, G> {
     message_receiver: flume::Receiver<ViewMessage>,
     emitter: Emitter,
     global_events: G,
+    keymap: KeyMap,
+    tasks: Tasks,
 }
 
 impl<T, G: GlobalEvents> RuntimeBuilder<T, G> {
@@ -90,6 +121,8 @@ impl<T, G: GlobalEvents> RuntimeBuilder<T, G> {
             message_receiver: self.message_receiver,
             emitter: self.emitter,
             global_events,
+            keymap: self.keymap,
+            tasks: self.tasks,
         }
     }
 
@@ -141,62 +174,120 @@ impl<T, G: GlobalEvents> RuntimeBuilder<T, G> {
         Ok(id.into())
     }
 
-    /// Returns an [Emitter] to send messages to components
-    pub fn emitter(&self) -> Emitter {
-        self.emitter.clone()
+    /// Sends the message to the component when the key chord is pressed.
+    /// The keys of a chord are separated by spaces, and `Ctrl+` is the only modifier,
+    /// e.g `Ctrl+x Ctrl+s`, `Enter` or `F5`.
+    ///
+    /// Bound keys are not passed on to the components or the global events,
+    /// neither is a key that ends a chord that was started but not finished.
+    pub fn bind<M: Clone + Send + Sync + 'static>(
+        &mut self,
+        chord: &str,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> Result<()> {
+        self.keymap.bind(chord, None, recipient, message)
     }
 
-    fn set_watcher(&mut self) -> Result<RecommendedWatcher> {
-        let paths = self
-            .document
-            .template_paths()
-            .filter_map(|p| p.canonicalize().ok())
-            .collect::<Vec<_>>();
+    /// Like [RuntimeBuilder::bind], but only while the `focus` component has focus.
+    /// This overrides a binding of the same chord made with [RuntimeBuilder::bind].
+    pub fn bind_focused<F, M: Clone + Send + Sync + 'static>(
+        &mut self,
+        focus: ComponentId<F>,
+        chord: &str,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> Result<()> {
+        self.keymap.bind(chord, Some(focus.into()), recipient, message)
+    }
 
-        let mut watcher = recommended_watcher(move |event: std::result::Result<Event, _>| match event {
-            Ok(event) => match event.kind {
-                notify::EventKind::Create(_) | notify::EventKind::Remove(_) | notify::EventKind::Modify(_) => {
-                    if paths.iter().any(|p| event.paths.contains(p)) {
-                        REBUILD.store(true, Ordering::Relaxed);
-                    }
-                }
-                notify::EventKind::Any | notify::EventKind::Access(_) | notify::EventKind::Other => (),
-            },
-            Err(_err) => (),
-        })?;
+    /// Start recording a keyboard macro to the register when the key chord is pressed,
+    /// and stop recording when it's pressed again.
+    /// See [`Macros`] for what is recorded.
+    ///
+    /// ```ignore
+    /// runtime.bind_record_macro("Ctrl+x (", "last")?;
+    /// runtime.bind_play_macro("Ctrl+x e", "last")?;
+    /// ```
+    pub fn bind_record_macro(&mut self, chord: &str, register: impl Into<String>) -> Result<()> {
+        self.keymap
+            .bind_action(chord, None, Action::RecordMacro(register.into()))
+    }
 
-        for path in self.document.template_paths() {
-            let path = path.canonicalize().unwrap();
+    /// Play the keyboard macro of the register when the key chord is pressed
+    pub fn bind_play_macro(&mut self, chord: &str, register: impl Into<String>) -> Result<()> {
+        self.keymap.bind_action(chord, None, Action::PlayMacro(register.into()))
+    }
 
-            if let Some(parent) = path.parent() {
-                watcher.watch(parent, RecursiveMode::NonRecursive)?;
-            }
-        }
+    /// The async runtime for the futures spawned by components,
+    /// see [`Context::spawn`](anathema_widgets::components::Context::spawn).
+    ///
+    /// The output of a future is sent to the component as a message,
+    /// and the future is cancelled when the component is removed.
+    ///
+    /// ```text
+    /// builder.spawner(|task| drop(tokio::spawn(task)));
+    /// ```
+    pub fn spawner(&mut self, spawner: impl Spawner + 'static) {
+        self.tasks.set_spawner(spawner);
+    }
+
+    /// The registered widgets and their metadata
+    pub fn factory(&self) -> &Factory {
+        &self.factory
+    }
+
+    /// Register a layout for the `layout` widget, e.g `layout [layout: "masonry"]`
+    pub fn register_layout<L: Layout + 'static>(&mut self, name: &str, layout: impl Fn() -> L + 'static) {
+        self.factory.register_layout(name, layout);
+    }
+
+    /// Returns an [Emitter] to send messages to components
+    pub fn emitter(&self) -> Emitter {
+        self.emitter.clone()
+    }
 
-        Ok(watcher)
+    /// Watch the template files while the runtime is running, and rebuild the tree
+    /// when a file changes (on by default). The components keep their state.
+    pub fn hot_reload(&mut self, enabled: bool) {
+        self.document.hot_reload = enabled;
     }
 
     /// Builds the [Runtime].
-    /// Fails if compiling the [Document] or creating the file watcher fails.
+    /// Fails if compiling the [Document], checking it against the widget metadata
+    /// or creating the file watcher fails.
     pub fn finish(mut self) -> Result<Runtime<T, G>>
     where
         T: Backend,
     {
         let (blueprint, globals) = self.document.compile()?;
+        validate(&self.factory, &blueprint, &self.document)?;
         let watcher = match self.document.hot_reload {
             false => None,
-            true => Some(self.set_watcher()?),
+            true => Some(watch(self.document.template_paths())?),
         };
 
         let (width, height) = self.backend.size().into();
         let constraints = Constraints::new(width as usize, height as usize);
+        let mut viewport = Viewport::new((width, height));
+        viewport.set_cell_size(self.backend.cell_size());
 
         let inst = Runtime {
-            _watcher: watcher,
+            watcher,
             backend: self.backend,
             emitter: self.emitter,
             message_receiver: self.message_receiver,
             fps: 30,
+            frame_budget: None,
+            min_size: None,
+            reserved: Spacing::ZERO,
+            input: InputSettings::default(),
+            too_small: false,
+            frame_timer: FrameTimer::new(),
+            on_long_frame: None,
+            hooks: FrameHooks::default(),
+            timers: Timers::default(),
+            tasks: self.tasks,
             constraints,
             blueprint,
             factory: self.factory,
@@ -206,11 +297,15 @@ impl<T, G: GlobalEvents> RuntimeBuilder<T, G> {
             component_registry: self.component_registry,
             globals,
             document: self.document,
-            viewport: Viewport::new((width, height)),
+            viewport,
             floating_widgets: FloatingWidgets::empty(),
             components: Components::new(),
             dirty_widgets: DirtyWidgets::empty(),
-            event_handler: EventHandler::new(self.global_events),
+            event_handler: EventHandler::new(self.global_events, self.keymap),
+            overlay_queue: OverlayQueue::new(),
+            exit: None,
+            overlay_stack: vec![],
+            focus_traps: vec![],
         };
 
         Ok(inst)
@@ -229,8 +324,19 @@ impl<T, G: GlobalEvents> RuntimeBuilder<T, G> {
 /// ```
 pub struct Runtime<T, G> {
     pub fps: u16,
-
-    _watcher: Option<RecommendedWatcher>,
+    /// Frames that take longer than this are reported,
+    /// see [`Runtime::on_long_frame`] and [`GlobalContext::long_frame`].
+    pub frame_budget: Option<Duration>,
+    /// The smallest size the document can be shown at.
+    /// A smaller terminal shows a message with the size needed instead of the document.
+    pub min_size: Option<Size>,
+    /// Rows and columns at the edges of the terminal that are kept free,
+    /// e.g. the last row for the shell prompt. The document is laid out in the rest.
+    pub reserved: Spacing,
+    /// How components interpret input, e.g. the steps of the mouse wheel on numeric inputs.
+    pub input: InputSettings,
+
+    watcher: Option<RecommendedWatcher>,
     message_receiver: flume::Receiver<ViewMessage>,
     emitter: Emitter,
     blueprint: Blueprint,
@@ -269,6 +375,21 @@ pub struct Runtime<T, G> {
     component_registry: ComponentRegistry,
     // * Layout
     floating_widgets: FloatingWidgets,
+    // * Event handling
+    overlay_queue: OverlayQueue,
+    // Set by a component or the global events to stop the runtime
+    exit: Option<Exit>,
+    // The open overlays and the tab index to restore once they are closed
+    overlay_stack: Vec<(String, usize)>,
+    // The paths of the active focus traps and the tab index to restore once they are released
+    focus_traps: Vec<(Box<[u16]>, usize)>,
+    // True while the terminal is smaller than `min_size`
+    too_small: bool,
+    frame_timer: FrameTimer,
+    on_long_frame: Option<Box<dyn FnMut(&FrameReport)>>,
+    hooks: FrameHooks,
+    timers: Timers,
+    tasks: Tasks,
 }
 
 impl<T> Runtime<T, ()>
@@ -290,6 +411,8 @@ where
             emitter: message_sender.into(),
             message_receiver,
             global_events: (),
+            keymap: KeyMap::default(),
+            tasks: Tasks::new(),
         }
     }
 }
@@ -299,10 +422,96 @@ where
     T: Backend,
     G: GlobalEvents,
 {
+    /// The registered widgets and their metadata
+    pub fn factory(&self) -> &Factory {
+        &self.factory
+    }
+
     pub fn emitter(&self) -> Emitter {
         self.emitter.clone()
     }
 
+    /// Call a function with the report of every frame that goes over the [`Runtime::frame_budget`],
+    /// e.g. to write a warning to a log.
+    pub fn on_long_frame(&mut self, f: impl FnMut(&FrameReport) + 'static) {
+        self.on_long_frame = Some(Box::new(f));
+    }
+
+    /// Send the message to the component every `interval`, until the timer is cleared
+    /// with [`Runtime::clear_timer`].
+    /// The message is handled with the other messages at the start of a frame,
+    /// so the precision of the timer is limited by the frame rate.
+    ///
+    /// ```ignore
+    /// let clock = runtime.register_component("clock", "clock.aml", Clock, ClockState::new())?;
+    /// let mut runtime = runtime.finish()?;
+    /// runtime.set_interval(Duration::from_secs(1), clock, ClockMessage::Tick);
+    /// ```
+    pub fn set_interval<M: Clone + Send + Sync + 'static>(
+        &mut self,
+        interval: Duration,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> TimerId {
+        self.timers.add(Instant::now(), interval, true, recipient, message)
+    }
+
+    /// Send the message to the component once, after `delay`
+    pub fn set_timeout<M: Clone + Send + Sync + 'static>(
+        &mut self,
+        delay: Duration,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> TimerId {
+        self.timers.add(Instant::now(), delay, false, recipient, message)
+    }
+
+    /// Stop a timer. Clearing a timer that already fired does nothing.
+    pub fn clear_timer(&mut self, timer: TimerId) {
+        self.timers.remove(timer);
+    }
+
+    /// The keyboard macros, e.g to load macros saved from an earlier session before
+    /// the runtime starts. Use [`GlobalContext::macros`] while the runtime runs.
+    pub fn macros(&mut self) -> &mut Macros {
+        self.event_handler.macros()
+    }
+
+    /// Call a function every frame once the events are handled,
+    /// before the state changes are applied to the widget tree.
+    ///
+    /// Hooks are for integrations like profilers, recorders and bridges to other systems,
+    /// that need to observe each frame or inject work, e.g by sending messages to components.
+    ///
+    /// ```
+    /// # use anathema_runtime::Runtime;
+    /// # use anathema_templates::Document;
+    /// # use anathema_backend::test::TestBackend;
+    /// # let backend = TestBackend::new((10, 10));
+    /// let mut runtime = Runtime::builder(Document::new("text 'hi'"), backend).finish().unwrap();
+    /// let mut frames = 0;
+    /// runtime.before_update(move |ctx| {
+    ///     frames += 1;
+    ///     ctx.elements.by_tag("text").each(|_, _| {});
+    /// });
+    /// ```
+    pub fn before_update(&mut self, f: impl FnMut(&mut HookContext<'_, '_>) + 'static) {
+        self.hooks.before_update.push(Box::new(f));
+    }
+
+    /// Call a function once the widgets are laid out, positioned and painted,
+    /// before the frame is rendered.
+    /// This is only called for frames where something changed.
+    pub fn after_layout(&mut self, f: impl FnMut(&mut HookContext<'_, '_>) + 'static) {
+        self.hooks.after_layout.push(Box::new(f));
+    }
+
+    /// Call a function once the frame is rendered to the backend.
+    /// This is only called for frames where something changed.
+    pub fn after_render(&mut self, f: impl FnMut(&mut HookContext<'_, '_>) + 'static) {
+        self.hooks.after_render.push(Box::new(f));
+    }
+
     fn apply_futures<'bp>(
         &mut self,
         globals: &'bp Globals,
@@ -387,8 +596,10 @@ where
     ) -> Duration {
         let context = UntypedContext {
             emitter: &self.emitter,
+            tasks: &self.tasks,
             viewport: self.viewport,
-            strings: &mut self.document.strings,
+            strings: &self.document.strings,
+            input: self.input,
         };
 
         let mut event_ctx = EventCtx {
@@ -398,18 +609,28 @@ where
             attribute_storage,
             assoc_events,
             focus_queue,
+            overlay_queue: &mut self.overlay_queue,
+            exit: &mut self.exit,
             context,
         };
 
         while let Ok(msg) = self.message_receiver.try_recv() {
-            if let Some((widget_id, state_id)) = event_ctx
-                .components
-                .get_by_component_id(msg.recipient())
-                .map(|e| (e.widget_id, e.state_id))
-            {
+            let components = &mut *event_ctx.components;
+            let entry = match msg.recipient() {
+                Address::Component(id) => components.get_by_component_id(*id),
+                Address::Node(id) => components.iter().find(|e| e.widget_id == *id),
+                Address::Name(name) => match self.document.component_id(name) {
+                    Some(id) => components.get_by_component_id(id),
+                    None => None,
+                },
+            };
+
+            if let Some((widget_id, state_id)) = entry.map(|e| (e.widget_id, e.state_id)) {
+                let now = Instant::now();
                 tree.with_component(widget_id, state_id, &mut event_ctx, |a, b| {
                     a.any_message(msg.payload(), b)
                 });
+                self.frame_timer.component(widget_id, now.elapsed());
             }
 
             // Make sure event handling isn't holding up the rest of the event loop.
@@ -421,13 +642,31 @@ where
         fps_now.elapsed()
     }
 
-    /// Start the runtime
-    pub fn run(&mut self) {
+    /// Select a named layout of the [Document].
+    /// Use [GlobalContext::set_layout] to switch layout once the runtime is running.
+    pub fn set_layout(&mut self, name: &str) -> Result<()> {
+        self.document.set_layout(name)?;
+        let (blueprint, globals) = self.document.compile()?;
+        validate(&self.factory, &blueprint, &self.document)?;
+        self.blueprint = blueprint;
+        self.globals = globals;
+        Ok(())
+    }
+
+    /// Start the runtime.
+    ///
+    /// Runs until a component calls [`Context::exit`](anathema_widgets::components::Context::exit) (or the global events [`GlobalContext::exit`]),
+    /// or the runtime is stopped (e.g with ctrl+c), which exits with code zero.
+    /// ```ignore
+    /// let exit = runtime.run();
+    /// std::process::exit(exit.code);
+    /// ```
+    pub fn run(&mut self) -> Exit {
         self.backend.finalize();
         loop {
             match self.internal_run() {
                 Ok(()) => (),
-                Err(Error::Stop) => return,
+                Err(Error::Stop) => return self.exit.take().unwrap_or_default(),
                 Err(err) => self.show_error(err),
             }
         }
@@ -435,11 +674,16 @@ where
 
     // 1 - Tries to build the tree
     // 2 - Selects the first [Component] and calls [Component::on_focus] on it
-    // 3 - Repeatedly calls [Self::tick] until [REBUILD] is set to true or an error occurs. Using the [Error::Stop] breaks the main loop.
+    // 3 - Repeatedly calls [Self::tick] until [REBUILD] is set to true, a layout is selected or an error occurs. Using the [Error::Stop] breaks the main loop.
     // 4 - Resets using [Self::reset]
     // 5 - Recursively calls [Self::internal_run].
     // TODO: We should move this into a loop in [Self::run].
     fn internal_run(&mut self) -> Result<()> {
+        self.too_small = self.is_too_small();
+        if self.too_small {
+            self.show_too_small();
+        }
+
         let mut fps_now = Instant::now();
         let sleep_micros = ((1.0 / self.fps as f64) * 1000.0 * 1000.0) as u128;
         let mut tree = WidgetTree::empty();
@@ -463,9 +707,15 @@ where
         );
 
         let blueprint = self.blueprint.clone();
-
-        // First build the tree
-        let res = eval_blueprint(&blueprint, &mut ctx, root_node(), &mut tree);
+        let overlays = self.document.overlays().to_vec();
+        let regions = self.document.regions().to_vec();
+
+        // First build the tree, followed by the regions
+        let res = eval_blueprint(&blueprint, &mut ctx, root_node(), &mut tree).and_then(|()| {
+            regions
+                .iter()
+                .try_for_each(|(_, region)| eval_blueprint(region, &mut ctx, root_node(), &mut tree))
+        });
 
         match res {
             Ok(_) => (),
@@ -478,26 +728,48 @@ where
             }
         }
 
+        // Reopen the overlays that were open before the tree was rebuilt
+        let stack = match self.too_small {
+            false => std::mem::take(&mut self.overlay_stack),
+            true => vec![],
+        };
+        for (name, tab_index) in stack {
+            let Some((_, overlay)) = overlays.iter().find(|(n, _)| *n == name) else { continue };
+            self.overlay_stack.push((name, tab_index));
+            if let Err(err) = self.eval_overlay(overlay, &globals, &mut tree, &mut states, &mut attribute_storage) {
+                self.reset(tree, &mut states)?;
+                return Err(err);
+            }
+        }
+
         let mut dt = Instant::now();
 
         // Initial layout, position and paint
-        WidgetCycle::new(
+        let (origin, constraints) = self.root();
+        let res = WidgetCycle::new(
             &mut self.backend,
             &mut tree,
-            self.constraints,
+            constraints,
             &attribute_storage,
             &self.floating_widgets,
             self.viewport,
         )
+        .origin(origin)
         .run();
+        if let Err(err) = res {
+            self.reset(tree, &mut states)?;
+            return Err(err.into());
+        }
         self.backend.render();
         self.backend.clear();
 
         // Try to set focus on the first available component
         let context = UntypedContext {
             emitter: &self.emitter,
+            tasks: &self.tasks,
             viewport: self.viewport,
             strings: &self.document.strings,
+            input: self.input,
         };
 
         let mut event_ctx = EventCtx {
@@ -508,12 +780,21 @@ where
             assoc_events: &mut assoc_events,
             context,
             focus_queue: &mut focus_queue,
+            overlay_queue: &mut self.overlay_queue,
+            exit: &mut self.exit,
         };
 
         self.event_handler.set_initial_focus(&mut tree, &mut event_ctx);
+        self.apply_focus_traps(
+            &mut tree,
+            &mut states,
+            &mut attribute_storage,
+            &mut assoc_events,
+            &mut focus_queue,
+        );
 
         loop {
-            self.tick(
+            let res = self.tick(
                 fps_now,
                 &mut dt,
                 sleep_micros,
@@ -523,18 +804,92 @@ where
                 &globals,
                 &mut assoc_events,
                 &mut focus_queue,
-            )?;
+            );
+
+            // Stopping doesn't need the components back, any other error rebuilds the tree
+            match res {
+                Ok(()) => (),
+                Err(Error::Stop) => return Err(Error::Stop),
+                Err(err) => {
+                    self.reset(tree, &mut states)?;
+                    return Err(err);
+                }
+            }
+
+            if self.exit.is_some() {
+                return Err(Error::Stop);
+            }
 
             if REBUILD.swap(false, Ordering::Relaxed) {
                 break;
             }
 
+            // Switch between the document and the "too small" message
+            if self.is_too_small() != self.too_small {
+                break;
+            }
+
+            let res = self.apply_overlays(
+                &overlays,
+                &globals,
+                &mut tree,
+                &mut states,
+                &mut attribute_storage,
+                &mut assoc_events,
+                &mut focus_queue,
+            );
+
+            if let Err(err) = res {
+                self.reset(tree, &mut states)?;
+                return Err(err);
+            }
+
+            // Switching layout and swapping components rebuilds the tree
+            let layout = self.event_handler.take_layout();
+            let swaps = self.event_handler.take_swaps();
+            if layout.is_some() || !swaps.is_empty() {
+                return self.rebuild(layout, swaps, tree, &mut states);
+            }
+
             fps_now = Instant::now();
         }
 
         self.reset(tree, &mut states)
     }
 
+    // The position and the constraints of the root, inside the reserved rows and columns
+    fn root(&self) -> (Pos, Constraints) {
+        let reserved = self.reserved.size();
+        let mut constraints = self.constraints;
+        constraints.set_max_width(constraints.max_width().saturating_sub(reserved.width));
+        constraints.set_max_height(constraints.max_height().saturating_sub(reserved.height));
+        (self.reserved.offset(), constraints)
+    }
+
+    fn is_too_small(&self) -> bool {
+        let Some(min_size) = self.min_size else { return false };
+        let (_, constraints) = self.root();
+        constraints.max_width() < min_size.width || constraints.max_height() < min_size.height
+    }
+
+    // Show the size needed instead of the document.
+    // The document is compiled again by the reset once the terminal is large enough.
+    fn show_too_small(&mut self) {
+        let Some(min_size) = self.min_size else { return };
+        let tpl = format!(
+            "
+            align [alignment: 'centre']
+                text 'terminal too small (need {}x{})'
+            ",
+            min_size.width, min_size.height
+        );
+
+        let mut document = Document::new(tpl);
+        let (blueprint, globals) = document.compile().expect("the template can't fail");
+        self.blueprint = blueprint;
+        self.globals = globals;
+    }
+
     pub fn show_error(&mut self, err: Error) {
         let tpl = "
             align [alignment: 'centre']
@@ -549,6 +904,9 @@ where
             s
         });
 
+        // The overlays are not part of the error template
+        self.overlay_stack.clear();
+
         let mut document = Document::new(tpl);
         let _component_id = document.add_component("errors", errors.to_template());
         let (blueprint, globals) = document.compile().expect("the error template can't fail");
@@ -556,6 +914,39 @@ where
         self.globals = globals;
     }
 
+    // Apply a layout switch and component swaps in one go,
+    // so the tree is only rebuilt once.
+    fn rebuild(
+        &mut self,
+        layout: Option<String>,
+        swaps: Vec<ComponentSwap>,
+        tree: WidgetTree<'_>,
+        states: &mut States,
+    ) -> Result<()> {
+        let mut res = Ok(());
+        if let Some(layout) = layout {
+            res = self.document.set_layout(&layout).map_err(Into::into);
+        }
+
+        // The templates are replaced before the document is recompiled by the reset
+        let mut components = vec![];
+        for swap in swaps {
+            match self.document.replace_component(&swap.name, swap.template) {
+                Ok(id) => components.push((id.into(), swap.component, swap.state)),
+                Err(err) => res = res.and(Err(err.into())),
+            }
+        }
+
+        self.reset(tree, states)?;
+
+        // The components are replaced once the old components are returned to the registry
+        for (id, component, state) in components {
+            self.component_registry.replace_component(id, component, state);
+        }
+
+        res
+    }
+
     // Resets the Runtime:
     // * Reloads all components
     // * Moves all the components from the tree back to the registry.
@@ -566,10 +957,13 @@ where
         clear_all_subs();
 
         self.components = Components::new();
+        self.tasks.cancel_all();
+        self.focus_traps.clear();
         self.floating_widgets = FloatingWidgets::empty();
+        while self.overlay_queue.next().is_some() {}
 
         // The only way we can get here is if we break the loop
-        // as a result of the hot_reload triggering or when building the first tree fails.
+        // as a result of the hot_reload triggering, a layout switch or when building the first tree fails.
         self.document.reload_templates()?;
 
         // Move all components from the tree back to the registry.
@@ -582,9 +976,15 @@ where
         }
 
         let (blueprint, globals) = self.document.compile()?;
+        validate(&self.factory, &blueprint, &self.document)?;
         self.blueprint = blueprint;
         self.globals = globals;
 
+        // The templates might include other files now
+        if self.watcher.is_some() {
+            self.watcher = Some(watch(self.document.template_paths())?);
+        }
+
         Ok(())
     }
 
@@ -603,6 +1003,11 @@ where
         // Clear the text buffer
         // self.string_storage.clear();
 
+        self.frame_timer.start();
+
+        // The messages of the timers are handled along with the other messages
+        self.timers.fire(Instant::now(), &self.emitter);
+
         // Pull and keep consuming events while there are events present in the queue.
         let poll_duration = self.handle_messages(
             fps_now,
@@ -613,14 +1018,18 @@ where
             assoc_events,
             focus_queue,
         );
+        self.frame_timer.end_phase(Phase::Messages);
 
         // Call the `tick` function on all components
         self.tick_components(tree, states, attribute_storage, dt.elapsed(), assoc_events, focus_queue);
+        self.frame_timer.end_phase(Phase::Tick);
 
         let context = UntypedContext {
             emitter: &self.emitter,
+            tasks: &self.tasks,
             viewport: self.viewport,
             strings: &self.document.strings,
+            input: self.input,
         };
 
         let mut event_ctx = EventCtx {
@@ -631,6 +1040,8 @@ where
             assoc_events,
             context,
             focus_queue,
+            overlay_queue: &mut self.overlay_queue,
+            exit: &mut self.exit,
         };
 
         self.event_handler.handle(
@@ -643,9 +1054,19 @@ where
             &mut self.constraints,
             &mut event_ctx,
         )?;
+        self.frame_timer.end_phase(Phase::Events);
 
         *dt = Instant::now();
 
+        run_hooks(
+            &mut self.hooks.before_update,
+            tree,
+            attribute_storage,
+            &mut self.dirty_widgets,
+            self.viewport,
+            &self.emitter,
+        );
+
         self.apply_futures(globals, tree, states, attribute_storage);
 
         self.apply_changes(globals, tree, states, attribute_storage);
@@ -655,35 +1076,67 @@ where
         //   Mark dirty widgets for redraw, along with their parents
         // -----------------------------------------------------------------------------
         self.dirty_widgets.apply(tree);
+        let needs_reflow = !self.changes.is_empty() || !self.dirty_widgets.is_empty();
 
-        // Cleanup removed attributes from widgets.
-        for key in tree.drain_removed() {
-            attribute_storage.try_remove(key);
-            self.floating_widgets.try_remove(key);
-            // TODO: this function is rubbish and has to be rewritten
-            self.components.dodgy_remove(key);
+        self.cleanup_removed(tree, attribute_storage);
+        if needs_reflow {
+            self.apply_focus_traps(tree, states, attribute_storage, assoc_events, focus_queue);
         }
+        self.frame_timer.end_phase(Phase::Update);
 
         // -----------------------------------------------------------------------------
         //   - Layout, position and paint -
         // -----------------------------------------------------------------------------
-        let needs_reflow = !self.changes.is_empty() || !self.dirty_widgets.is_empty();
         if needs_reflow {
+            let (origin, constraints) = self.root();
             let mut cycle = WidgetCycle::new(
                 &mut self.backend,
                 tree,
-                self.constraints,
+                constraints,
                 attribute_storage,
                 &self.floating_widgets,
                 self.viewport,
+            )
+            .origin(origin);
+            cycle.run()?;
+
+            // Widgets marked dirty by the hooks are drawn in the next frame
+            self.changes.clear();
+            self.dirty_widgets.clear();
+
+            run_hooks(
+                &mut self.hooks.after_layout,
+                tree,
+                attribute_storage,
+                &mut self.dirty_widgets,
+                self.viewport,
+                &self.emitter,
             );
-            cycle.run();
 
             self.backend.render();
             self.backend.clear();
-            self.changes.clear();
-            self.dirty_widgets.clear();
+
+            run_hooks(
+                &mut self.hooks.after_render,
+                tree,
+                attribute_storage,
+                &mut self.dirty_widgets,
+                self.viewport,
+                &self.emitter,
+            );
         }
+        self.frame_timer.end_phase(Phase::Paint);
+        self.report_long_frame(tree);
+
+        self.idle_components(
+            fps_now,
+            sleep_micros,
+            tree,
+            states,
+            attribute_storage,
+            assoc_events,
+            focus_queue,
+        );
 
         let sleep = sleep_micros.saturating_sub(fps_now.elapsed().as_micros()) as u64;
         if sleep > 0 {
@@ -693,6 +1146,287 @@ where
         Ok(())
     }
 
+    fn report_long_frame(&mut self, tree: &WidgetTree<'_>) {
+        let Some(budget) = self.frame_budget else { return };
+        let Some((mut report, component)) = self.frame_timer.over_budget(budget) else { return };
+
+        report.component = component.and_then(|(widget_id, duration)| {
+            let WidgetKind::Component(component) = tree.get_ref_by_id(widget_id)? else { return None };
+            let name = self.document.component_name(component.component_id)?;
+            Some((name.to_string(), duration))
+        });
+
+        if let Some(f) = self.on_long_frame.as_mut() {
+            f(&report);
+        }
+        self.event_handler.set_long_frame(report);
+    }
+
+    // Cleanup removed attributes from widgets.
+    fn cleanup_removed<'bp>(&mut self, tree: &mut WidgetTree<'bp>, attribute_storage: &mut AttributeStorage<'bp>) {
+        for key in tree.drain_removed() {
+            attribute_storage.try_remove(key);
+            self.floating_widgets.try_remove(key);
+            self.tasks.cancel(key);
+            // TODO: this function is rubbish and has to be rewritten
+            self.components.dodgy_remove(key);
+        }
+    }
+
+    // The first child of the root is the layout, followed by the regions and the overlays.
+    // This is the index of the nth overlay, starting at one.
+    fn overlay_root(&self, overlay: usize) -> u16 {
+        (self.document.regions().len() + overlay) as u16
+    }
+
+    // Evaluate the top most overlay as the last child of the root,
+    // and limit the input to the components of the overlay.
+    fn eval_overlay<'bp>(
+        &mut self,
+        blueprint: &'bp Blueprint,
+        globals: &'bp Globals,
+        tree: &mut WidgetTree<'bp>,
+        states: &mut States,
+        attribute_storage: &mut AttributeStorage<'bp>,
+    ) -> Result<()> {
+        let mut scope = Scope::new();
+        let mut ctx = EvalContext::new(
+            globals,
+            &self.factory,
+            &mut scope,
+            states,
+            &mut self.component_registry,
+            attribute_storage,
+            &mut self.floating_widgets,
+            &mut self.components,
+        );
+        eval_blueprint(blueprint, &mut ctx, root_node(), tree)?;
+
+        let path = Box::new([self.overlay_root(self.overlay_stack.len())]);
+        self.components.set_scope(Some(path));
+        Ok(())
+    }
+
+    // Remove the top most overlay from the tree and return the components to the registry
+    fn remove_overlay<'bp>(
+        &mut self,
+        tree: &mut WidgetTree<'bp>,
+        states: &mut States,
+        attribute_storage: &mut AttributeStorage<'bp>,
+    ) {
+        let path = [self.overlay_root(self.overlay_stack.len() + 1)];
+
+        for (widget_path, widget) in tree.iter_mut() {
+            if !widget_path.starts_with(&path) {
+                continue;
+            }
+            let WidgetKind::Component(comp) = widget else { continue };
+            let state = states.remove(comp.state_id);
+            let ComponentKind::Instance = comp.kind else { continue };
+            let component = std::mem::replace(&mut comp.dyn_component, Box::new(()));
+            self.component_registry
+                .return_component(comp.component_id, component, state);
+        }
+
+        tree.remove(&path);
+        self.cleanup_removed(tree, attribute_storage);
+
+        let scope = match self.overlay_stack.len() {
+            0 => None,
+            len => Some(Box::from([self.overlay_root(len)])),
+        };
+        self.components.set_scope(scope);
+    }
+
+    // Limit tab traversal to the last widget with `focus_trap` set to true.
+    // Once the trap is released the component that had focus before the trap was activated
+    // is given focus again.
+    fn apply_focus_traps<'bp>(
+        &mut self,
+        tree: &mut WidgetTree<'bp>,
+        states: &mut States,
+        attribute_storage: &mut AttributeStorage<'bp>,
+        assoc_events: &mut AssociatedEvents,
+        focus_queue: &mut FocusQueue<'static>,
+    ) {
+        let mut active = vec![];
+        for (path, widget) in tree.iter_mut() {
+            let WidgetKind::Element(element) = widget else { continue };
+            if attribute_storage.get(element.id()).get_bool(FOCUS_TRAP) {
+                active.push(path.clone());
+            }
+        }
+
+        let mut restore = None;
+        while let Some((path, tab_index)) = self.focus_traps.last() {
+            if active.contains(path) {
+                break;
+            }
+            restore = Some(*tab_index);
+            self.focus_traps.pop();
+        }
+        self.focus_traps.retain(|(path, _)| active.contains(path));
+
+        let new_trap = active
+            .into_iter()
+            .filter(|path| !self.focus_traps.iter().any(|(trap, _)| trap == path))
+            .max();
+
+        if new_trap.is_none() && restore.is_none() {
+            return;
+        }
+
+        let context = UntypedContext {
+            emitter: &self.emitter,
+            tasks: &self.tasks,
+            viewport: self.viewport,
+            strings: &self.document.strings,
+            input: self.input,
+        };
+
+        let mut event_ctx = EventCtx {
+            components: &mut self.components,
+            dirty_widgets: &mut self.dirty_widgets,
+            states,
+            attribute_storage,
+            assoc_events,
+            focus_queue,
+            overlay_queue: &mut self.overlay_queue,
+            exit: &mut self.exit,
+            context,
+        };
+
+        if let Some((widget_id, state_id)) = event_ctx.components.current() {
+            tree.set_focus(widget_id, state_id, &mut event_ctx, false);
+        }
+
+        match new_trap {
+            Some(path) => {
+                let tab_index = restore.unwrap_or(event_ctx.components.tab_index);
+                event_ctx.components.set_focus_trap(Some(path.clone()));
+                self.focus_traps.push((path, tab_index));
+                self.event_handler.set_initial_focus(tree, &mut event_ctx);
+            }
+            None => {
+                let trap = self.focus_traps.last().map(|(path, _)| path.clone());
+                event_ctx.components.set_focus_trap(trap);
+                event_ctx.components.tab_index = restore.unwrap_or(0);
+                if let Some((widget_id, state_id)) = event_ctx.components.current() {
+                    tree.set_focus(widget_id, state_id, &mut event_ctx, true);
+                }
+            }
+        }
+    }
+
+    // Open and close the overlays requested while handling events
+    fn apply_overlays<'bp>(
+        &mut self,
+        overlays: &'bp [(String, Blueprint)],
+        globals: &'bp Globals,
+        tree: &mut WidgetTree<'bp>,
+        states: &mut States,
+        attribute_storage: &mut AttributeStorage<'bp>,
+        assoc_events: &mut AssociatedEvents,
+        focus_queue: &mut FocusQueue<'static>,
+    ) -> Result<()> {
+        let mut changed = false;
+
+        while let Some(request) = self.overlay_queue.next() {
+            let context = UntypedContext {
+                emitter: &self.emitter,
+                tasks: &self.tasks,
+                viewport: self.viewport,
+                strings: &self.document.strings,
+                input: self.input,
+            };
+
+            let mut event_ctx = EventCtx {
+                components: &mut self.components,
+                dirty_widgets: &mut self.dirty_widgets,
+                states,
+                attribute_storage,
+                assoc_events,
+                focus_queue,
+                overlay_queue: &mut self.overlay_queue,
+                exit: &mut self.exit,
+                context,
+            };
+
+            let restore_focus = match request {
+                OverlayRequest::Push(name) => {
+                    let Some((_, overlay)) = overlays.iter().find(|(n, _)| *n == name) else {
+                        return Err(Error::MissingOverlay(name));
+                    };
+
+                    if let Some((widget_id, state_id)) = event_ctx.components.current() {
+                        tree.set_focus(widget_id, state_id, &mut event_ctx, false);
+                    }
+
+                    self.overlay_stack.push((name, self.components.tab_index));
+                    self.eval_overlay(overlay, globals, tree, states, attribute_storage)?;
+                    false
+                }
+                OverlayRequest::Pop => {
+                    let Some((_, tab_index)) = self.overlay_stack.pop() else { continue };
+                    self.remove_overlay(tree, states, attribute_storage);
+                    self.components.tab_index = tab_index;
+                    true
+                }
+            };
+
+            let context = UntypedContext {
+                emitter: &self.emitter,
+                tasks: &self.tasks,
+                viewport: self.viewport,
+                strings: &self.document.strings,
+                input: self.input,
+            };
+
+            let mut event_ctx = EventCtx {
+                components: &mut self.components,
+                dirty_widgets: &mut self.dirty_widgets,
+                states,
+                attribute_storage,
+                assoc_events,
+                focus_queue,
+                overlay_queue: &mut self.overlay_queue,
+                exit: &mut self.exit,
+                context,
+            };
+
+            // Focus the component that had focus before the overlay was opened,
+            // or the first component of the new overlay
+            match restore_focus {
+                true => {
+                    if let Some((widget_id, state_id)) = event_ctx.components.current() {
+                        tree.set_focus(widget_id, state_id, &mut event_ctx, true);
+                    }
+                }
+                false => self.event_handler.set_initial_focus(tree, &mut event_ctx),
+            }
+
+            changed = true;
+        }
+
+        if changed {
+            let (origin, constraints) = self.root();
+            WidgetCycle::new(
+                &mut self.backend,
+                tree,
+                constraints,
+                attribute_storage,
+                &self.floating_widgets,
+                self.viewport,
+            )
+            .origin(origin)
+            .run()?;
+            self.backend.render();
+            self.backend.clear();
+        }
+
+        Ok(())
+    }
+
     fn tick_components<'bp>(
         &mut self,
         tree: &mut WidgetTree<'bp>,
@@ -704,8 +1438,10 @@ where
     ) {
         let context = UntypedContext {
             emitter: &self.emitter,
+            tasks: &self.tasks,
             viewport: self.viewport,
             strings: &self.document.strings,
+            input: self.input,
         };
 
         for i in 0..self.components.len() {
@@ -721,10 +1457,501 @@ where
                 attribute_storage,
                 assoc_events,
                 focus_queue,
+                overlay_queue: &mut self.overlay_queue,
+                exit: &mut self.exit,
                 context,
             };
 
+            let now = Instant::now();
             tree.with_component(widget_id, state_id, &mut event_ctx, |a, b| a.any_tick(b, dt));
+            self.frame_timer.component(widget_id, now.elapsed());
+        }
+    }
+
+    // Call `idle` on the components with the time left of the frame,
+    // until no component has any work left, the frame is over or input arrives.
+    fn idle_components<'bp>(
+        &mut self,
+        fps_now: Instant,
+        sleep_micros: u128,
+        tree: &mut WidgetTree<'bp>,
+        states: &mut States,
+        attribute_storage: &mut AttributeStorage<'bp>,
+        assoc_events: &mut AssociatedEvents,
+        focus_queue: &mut FocusQueue<'static>,
+    ) {
+        let context = UntypedContext {
+            emitter: &self.emitter,
+            tasks: &self.tasks,
+            viewport: self.viewport,
+            strings: &self.document.strings,
+            input: self.input,
+        };
+
+        let mut busy = true;
+        while busy {
+            busy = false;
+
+            for i in 0..self.components.len() {
+                if fps_now.elapsed().as_micros() >= sleep_micros || !self.message_receiver.is_empty() {
+                    return;
+                }
+
+                // Yield to the input, which is handled at the start of the next frame
+                if let Some(event) = self.backend.next_event(Duration::ZERO) {
+                    self.event_handler.defer(event);
+                    return;
+                }
+
+                let (widget_id, state_id) = self
+                    .components
+                    .get(i)
+                    .expect("the components can not change as a result of this step");
+
+                let mut event_ctx = EventCtx {
+                    components: &mut self.components,
+                    dirty_widgets: &mut self.dirty_widgets,
+                    states,
+                    attribute_storage,
+                    assoc_events,
+                    focus_queue,
+                    overlay_queue: &mut self.overlay_queue,
+                    exit: &mut self.exit,
+                    context,
+                };
+
+                busy |= tree
+                    .with_component(widget_id, state_id, &mut event_ctx, |a, b| a.any_idle(b))
+                    .unwrap_or(false);
+            }
+        }
+    }
+}
+
+// Check the literal attribute values of the compiled document against the widget metadata
+fn validate(factory: &Factory, blueprint: &Blueprint, document: &Document) -> Result<()> {
+    factory.validate(blueprint)?;
+    document
+        .regions()
+        .iter()
+        .chain(document.overlays())
+        .try_for_each(|(_, blueprint)| factory.validate(blueprint))?;
+    Ok(())
+}
+
+// Set `REBUILD` when one of the files changes
+fn watch<'a>(paths: impl Iterator<Item = &'a PathBuf>) -> Result<RecommendedWatcher> {
+    let paths = paths.filter_map(|p| p.canonicalize().ok()).collect::<Vec<_>>();
+    let dirs = paths
+        .iter()
+        .filter_map(|p| p.parent())
+        .map(|p| p.to_path_buf())
+        .collect::<Vec<_>>();
+
+    let mut watcher = recommended_watcher(move |event: std::result::Result<Event, _>| match event {
+        Ok(event) => match event.kind {
+            notify::EventKind::Create(_) | notify::EventKind::Remove(_) | notify::EventKind::Modify(_) => {
+                if paths.iter().any(|p| event.paths.contains(p)) {
+                    REBUILD.store(true, Ordering::Relaxed);
+                }
+            }
+            notify::EventKind::Any | notify::EventKind::Access(_) | notify::EventKind::Other => (),
+        },
+        Err(_err) => (),
+    })?;
+
+    for dir in dirs {
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    }
+
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use anathema_backend::test::TestBackend;
+    use anathema_state::{State, Value};
+    use anathema_store::tree::{Node, TreeValues};
+    use anathema_widgets::components::events::{Event, KeyCode, KeyEvent, KeyState};
+    use anathema_widgets::components::Context;
+    use anathema_widgets::{Element, Elements};
+
+    use super::*;
+
+    // The test backend, keeping every frame that is rendered
+    struct Recorder {
+        backend: TestBackend,
+        frames: Vec<String>,
+    }
+
+    impl Backend for Recorder {
+        fn size(&self) -> Size {
+            self.backend.size()
+        }
+
+        fn next_event(&mut self, timeout: Duration) -> Option<Event> {
+            self.backend.next_event(timeout)
+        }
+
+        fn resize(&mut self, new_size: Size) {
+            self.backend.resize(new_size);
+        }
+
+        fn paint<'bp>(
+            &mut self,
+            element: &mut Element<'bp>,
+            children: &[Node],
+            values: &mut TreeValues<WidgetKind<'bp>>,
+            attribute_storage: &AttributeStorage<'bp>,
+            ignore_floats: bool,
+        ) {
+            self.backend
+                .paint(element, children, values, attribute_storage, ignore_floats);
+        }
+
+        fn render(&mut self) {
+            self.backend.render();
+            let frame = self.backend.output.lines().map(str::trim_end).collect::<Vec<_>>();
+            self.frames.push(frame.join("\n").trim_end().to_string());
+        }
+
+        fn clear(&mut self) {
+            self.backend.clear();
+        }
+    }
+
+    // Each event is followed by a poll that times out, so every event is handled in a frame of its own.
+    // The runtime is stopped once the events are handled.
+    fn recorder(size: (u16, u16), events: impl IntoIterator<Item = Event>) -> Recorder {
+        let mut backend = TestBackend::new(size);
+        for event in events {
+            backend.push_event(event);
+            backend.push_timeout();
+        }
+        backend.push_event(Event::Stop);
+
+        Recorder {
+            backend,
+            frames: vec![],
+        }
+    }
+
+    fn press(c: char) -> Event {
+        key(KeyCode::Char(c))
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent {
+            code,
+            ctrl: false,
+            state: KeyState::Press,
+        })
+    }
+
+    // Counts the calls to `idle`, and records the count with every key
+    #[derive(Default)]
+    struct Worker {
+        idle: Rc<Cell<usize>>,
+        keys: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Component for Worker {
+        type Message = ();
+        type State = ();
+
+        fn on_key(&mut self, _: KeyEvent, _: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+            self.keys.borrow_mut().push(self.idle.get());
+        }
+
+        fn idle(&mut self, _: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) -> bool {
+            self.idle.set(self.idle.get() + 1);
+            true
         }
     }
+
+    #[test]
+    fn idle_yields_to_input() {
+        let worker = Worker::default();
+        let idle = worker.idle.clone();
+        let keys = worker.keys.clone();
+
+        // The idle pass of the first frame gets one empty poll before the second key arrives
+        let mut backend = TestBackend::new((10, 1));
+        backend.push_event(press('a'));
+        backend.push_timeout();
+        backend.push_timeout();
+        backend.push_event(press('b'));
+        backend.push_timeout();
+        backend.push_event(Event::Stop);
+
+        let mut builder = Runtime::builder(Document::new("@worker"), backend);
+        builder
+            .register_component("worker", "text 'work'".to_template(), worker, ())
+            .unwrap();
+        let mut runtime = builder.finish().unwrap();
+        runtime.run();
+
+        // Nothing runs idle before the first key is handled,
+        // and the second key stops the idle work straight away
+        assert_eq!(*keys.borrow(), [0, 1]);
+        assert_eq!(idle.get(), 1);
+    }
+
+    #[test]
+    fn too_small() {
+        let backend = recorder((40, 3), [Event::Resize(35, 2), Event::Resize(40, 3)]);
+        let mut runtime = Runtime::builder(Document::new("text 'hello'"), backend)
+            .finish()
+            .unwrap();
+        runtime.min_size = Some(Size::new(30, 3));
+        runtime.run();
+
+        // The message is centred in the smaller terminal, and the document is back once it's large enough
+        assert_eq!(runtime.backend.backend.size(), Size::new(40, 3));
+        let mut frames = runtime.backend.frames;
+        frames.dedup();
+        assert_eq!(frames, ["hello", "\n  terminal too small (need 30x3)", "hello"]);
+    }
+
+    #[test]
+    fn reserved() {
+        let backend = recorder((6, 4), []);
+        let mut runtime = Runtime::builder(Document::new("border [width: 10, height: 10]"), backend)
+            .finish()
+            .unwrap();
+        runtime.reserved = Spacing {
+            top: 1,
+            right: 0,
+            bottom: 1,
+            left: 2,
+        };
+
+        let (pos, constraints) = runtime.root();
+        assert_eq!(pos, Pos::new(2, 1));
+        assert_eq!(constraints.max_width(), 4);
+        assert_eq!(constraints.max_height(), 2);
+
+        // The border is limited to the space between the reserved rows and columns
+        runtime.run();
+        assert_eq!(runtime.backend.frames, ["\n  ┌──┐\n  └──┘"]);
+    }
+
+    // Records the name of the component every time it gets focus
+    struct Item {
+        name: &'static str,
+        focus: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl Component for Item {
+        type Message = ();
+        type State = ();
+
+        fn on_focus(&mut self, _: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+            self.focus.borrow_mut().push(self.name);
+        }
+    }
+
+    #[derive(State)]
+    struct DialogState {
+        open: Value<bool>,
+    }
+
+    // Opens the dialog with `o` and closes it with `c`
+    struct Dialog;
+
+    impl Component for Dialog {
+        type Message = ();
+        type State = DialogState;
+
+        fn on_key(&mut self, key: KeyEvent, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+            match key.get_char() {
+                Some('o') => state.open.set(true),
+                Some('c') => state.open.set(false),
+                _ => (),
+            }
+        }
+
+        fn accept_focus(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn focus_trap_restores_focus() {
+        let tpl = "
+            vstack
+                @a
+                @b
+                border [focus_trap: open]
+                    vstack
+                        @c
+                        @d
+        ";
+
+        let events = [key(KeyCode::Tab), press('o'), key(KeyCode::Tab), press('c')];
+        let mut builder = Runtime::builder(Document::new("@dialog"), recorder((10, 10), events));
+        builder
+            .register_component("dialog", tpl.to_template(), Dialog, DialogState { open: false.into() })
+            .unwrap();
+
+        let focus = Rc::new(RefCell::new(vec![]));
+        for name in ["a", "b", "c", "d"] {
+            let item = Item {
+                name,
+                focus: focus.clone(),
+            };
+            let tpl = format!("text '{name}'");
+            builder.register_component(name, tpl.to_template(), item, ()).unwrap();
+        }
+
+        let mut runtime = builder.finish().unwrap();
+        runtime.run();
+
+        // Tab moves from `a` to `b`, the trap moves the focus to `c` and tab to `d`,
+        // and once the trap is released `b` has focus again
+        assert_eq!(*focus.borrow(), ["a", "b", "c", "d", "b"]);
+    }
+
+    // Exits with the key that was pressed
+    struct Picker;
+
+    impl Component for Picker {
+        type Message = ();
+        type State = ();
+
+        fn on_key(&mut self, key: KeyEvent, _: &mut Self::State, _: Elements<'_, '_>, mut context: Context<'_, ()>) {
+            let choice = key.get_char().unwrap_or_default().to_string();
+            context.exit_with(3, choice);
+        }
+    }
+
+    #[test]
+    fn exit_with_value() {
+        let backend = recorder((10, 1), [press('y'), press('n')]);
+        let mut builder = Runtime::builder(Document::new("@picker"), backend);
+        builder
+            .register_component("picker", "text 'y/n'".to_template(), Picker, ())
+            .unwrap();
+        let mut runtime = builder.finish().unwrap();
+
+        // The runtime stops after the first key
+        let exit = runtime.run();
+        assert_eq!(exit.code, 3);
+        assert_eq!(exit.value::<String>().as_deref(), Some("y"));
+    }
+
+    #[derive(State)]
+    struct CounterState {
+        count: Value<i64>,
+    }
+
+    // Counts the keys
+    struct Counter;
+
+    impl Component for Counter {
+        type Message = ();
+        type State = CounterState;
+
+        fn on_key(&mut self, _: KeyEvent, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>) {
+            *state.count.to_mut() += 1;
+        }
+    }
+
+    #[test]
+    fn hook_order() {
+        let backend = recorder((10, 1), [press('+')]);
+        let mut builder = Runtime::builder(Document::new("@counter"), backend);
+        builder
+            .register_component(
+                "counter",
+                "text count".to_template(),
+                Counter,
+                CounterState { count: 0.into() },
+            )
+            .unwrap();
+        let mut runtime = builder.finish().unwrap();
+
+        let calls = Rc::new(RefCell::new(vec![]));
+        let record = |name| {
+            let calls = calls.clone();
+            move |_: &mut HookContext<'_, '_>| calls.borrow_mut().push(name)
+        };
+        runtime.before_update(record("before_update"));
+        runtime.after_layout(record("after_layout"));
+        runtime.after_render(record("after_render"));
+        runtime.run();
+
+        // The key is handled and drawn in one frame, and the runtime stops in the next
+        assert_eq!(*calls.borrow(), ["before_update", "after_layout", "after_render"]);
+        assert_eq!(runtime.backend.frames.last().unwrap(), "1");
+    }
+
+    // Records the messages it receives along with its name
+    struct Inbox {
+        name: &'static str,
+        received: Rc<RefCell<Vec<(&'static str, &'static str)>>>,
+    }
+
+    impl Component for Inbox {
+        type Message = &'static str;
+        type State = ();
+
+        fn message(&mut self, message: Self::Message, _: &mut (), _: Elements<'_, '_>, _: Context<'_, ()>) {
+            self.received.borrow_mut().push((self.name, message));
+        }
+
+        fn accept_focus(&self) -> bool {
+            false
+        }
+    }
+
+    // Sends a message to `b` by its id and to `c` by its name
+    struct Sender(ComponentId<&'static str>);
+
+    impl Component for Sender {
+        type Message = ();
+        type State = ();
+
+        fn on_key(&mut self, _: KeyEvent, _: &mut (), _: Elements<'_, '_>, context: Context<'_, ()>) {
+            context.emit(self.0, "by id");
+            context.emit_to("c", "by name");
+        }
+    }
+
+    #[test]
+    fn addressed_messages() {
+        let tpl = "
+            vstack
+                @sender
+                @a
+                @b
+                @c
+        ";
+
+        let backend = recorder((10, 4), [press('s')]);
+        let mut builder = Runtime::builder(Document::new(tpl), backend);
+
+        let received = Rc::new(RefCell::new(vec![]));
+        let mut ids = vec![];
+        for name in ["a", "b", "c"] {
+            let inbox = Inbox {
+                name,
+                received: received.clone(),
+            };
+            let tpl = format!("text '{name}'");
+            ids.push(builder.register_component(name, tpl.to_template(), inbox, ()).unwrap());
+        }
+        builder
+            .register_component("sender", "text 'send'".to_template(), Sender(ids[1]), ())
+            .unwrap();
+
+        let mut runtime = builder.finish().unwrap();
+        runtime.run();
+
+        // `a` gets neither message
+        assert_eq!(*received.borrow(), [("b", "by id"), ("c", "by name")]);
+    }
 }
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, VecDeque};
+
+use anathema_widgets::components::events::Event;
+
+struct Recording {
+    register: String,
+    events: Vec<Event>,
+}
+
+/// Keyboard macros: sequences of input events recorded to a named register,
+/// that can be played back as if they were typed again.
+///
+/// Macros are recorded and played with the key bindings from
+/// [`RuntimeBuilder::bind_record_macro`](crate::RuntimeBuilder::bind_record_macro) and
+/// [`RuntimeBuilder::bind_play_macro`](crate::RuntimeBuilder::bind_play_macro),
+/// or through [`GlobalContext::macros`](crate::GlobalContext::macros).
+///
+/// Key and mouse events are recorded, apart from the keys of the bindings that record
+/// and play macros. Playing a macro while recording adds the events of that macro to the
+/// recording, so the events of a macro never start, stop or play other macros.
+#[derive(Default)]
+pub struct Macros {
+    registers: HashMap<String, Vec<Event>>,
+    recording: Option<Recording>,
+    // The events of the macros that are playing, handled before new input
+    playback: VecDeque<Event>,
+    // The event that is being handled comes from a macro
+    replaying: bool,
+}
+
+impl Macros {
+    /// Start recording to a register, which replaces the events of the register once the
+    /// recording stops. A recording that is already in progress is stopped first.
+    pub fn start_recording(&mut self, register: impl Into<String>) {
+        if self.replaying {
+            return;
+        }
+        self.stop_recording();
+        self.recording = Some(Recording {
+            register: register.into(),
+            events: vec![],
+        });
+    }
+
+    /// Stop recording and store the events in the register.
+    /// The event that is being handled is part of the recording.
+    pub fn stop_recording(&mut self) {
+        self.stop(0);
+    }
+
+    /// The register that is being recorded to
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_ref().map(|recording| recording.register.as_str())
+    }
+
+    /// Play the events of a register after the event that is being handled.
+    /// Returns false if the register is empty.
+    pub fn play(&mut self, register: &str) -> bool {
+        self.play_after(register, 0)
+    }
+
+    /// The events of a register
+    pub fn get(&self, register: &str) -> Option<&[Event]> {
+        self.registers.get(register).map(Vec::as_slice)
+    }
+
+    /// Replace the events of a register, e.g with macros saved from an earlier session
+    pub fn set(&mut self, register: impl Into<String>, events: impl IntoIterator<Item = Event>) {
+        self.registers.insert(register.into(), events.into_iter().collect());
+    }
+
+    /// Remove a register, returning the events
+    pub fn remove(&mut self, register: &str) -> Option<Vec<Event>> {
+        self.registers.remove(register)
+    }
+
+    // Start or stop recording to the register from a binding of `keys` keys
+    pub(crate) fn toggle_recording(&mut self, register: &str, keys: usize) {
+        match self.recording() {
+            Some(current) if current == register => self.stop(keys),
+            _ => {
+                self.drop_keys(keys);
+                self.start_recording(register);
+            }
+        }
+    }
+
+    // Play a macro from a binding of `keys` keys
+    pub(crate) fn play_after(&mut self, register: &str, keys: usize) -> bool {
+        if self.replaying {
+            return false;
+        }
+
+        let Some(events) = self.registers.get(register).filter(|events| !events.is_empty()) else {
+            return false;
+        };
+
+        if let Some(recording) = self.recording.as_mut() {
+            let len = recording.events.len().saturating_sub(keys);
+            recording.events.truncate(len);
+            recording.events.extend_from_slice(events);
+        }
+
+        self.playback.extend(events.iter().copied());
+        true
+    }
+
+    // Record the input event, unless it comes from a macro
+    pub(crate) fn record(&mut self, event: Event) {
+        if self.replaying || !matches!(event, Event::Key(_) | Event::Mouse(_)) {
+            return;
+        }
+
+        if let Some(recording) = self.recording.as_mut() {
+            recording.events.push(event);
+        }
+    }
+
+    // The next event of the macros that are playing
+    pub(crate) fn next_event(&mut self) -> Option<Event> {
+        let event = self.playback.pop_front();
+        self.replaying = event.is_some();
+        event
+    }
+
+    // Stop recording, without the last `keys` events
+    fn stop(&mut self, keys: usize) {
+        if self.replaying {
+            return;
+        }
+
+        self.drop_keys(keys);
+        if let Some(recording) = self.recording.take() {
+            self.registers.insert(recording.register, recording.events);
+        }
+    }
+
+    // Remove the keys of the binding that was just pressed from the recording
+    fn drop_keys(&mut self, keys: usize) {
+        if let Some(recording) = self.recording.as_mut() {
+            let len = recording.events.len().saturating_sub(keys);
+            recording.events.truncate(len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widgets::components::events::{KeyCode, KeyEvent, KeyState};
+
+    use super::*;
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            ctrl: false,
+            state: KeyState::Press,
+        })
+    }
+
+    // The characters typed by the events
+    fn chars<'a>(events: impl IntoIterator<Item = &'a Event>) -> String {
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char(c), ..
+                }) => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn replay(macros: &mut Macros) -> String {
+        let events = std::iter::from_fn(|| macros.next_event()).collect::<Vec<_>>();
+        chars(&events)
+    }
+
+    #[test]
+    fn record_and_play() {
+        let mut macros = Macros::default();
+
+        // `q` toggles the recording
+        macros.toggle_recording("a", 1);
+        assert_eq!(macros.recording(), Some("a"));
+        macros.record(key('x'));
+        macros.record(Event::Resize(10, 10));
+        macros.record(key('y'));
+        macros.record(key('q'));
+        macros.toggle_recording("a", 1);
+        assert_eq!(macros.recording(), None);
+        assert_eq!(macros.get("a").map(chars).unwrap(), "xy");
+
+        assert!(macros.play("a"));
+        assert!(!macros.play("b"));
+        assert_eq!(replay(&mut macros), "xy");
+    }
+
+    #[test]
+    fn play_while_recording() {
+        let mut macros = Macros::default();
+        macros.set("a", [key('x')]);
+
+        macros.start_recording("b");
+        macros.record(key('y'));
+        // The key that plays the macro
+        macros.record(key('@'));
+        macros.play_after("a", 1);
+
+        // The events played back are not recorded twice, and can't play macros
+        assert_eq!(chars(macros.next_event().as_slice()), "x");
+        macros.record(key('x'));
+        assert!(!macros.play("a"));
+        macros.stop_recording();
+        assert_eq!(macros.recording(), Some("b"));
+        assert!(macros.next_event().is_none());
+
+        macros.stop_recording();
+        assert_eq!(macros.get("b").map(chars).unwrap(), "yx");
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use anathema_widgets::components::{ComponentId, Emitter};
+
+/// Identifies a timer, see [`Runtime::set_interval`](crate::Runtime::set_interval)
+/// and [`Runtime::set_timeout`](crate::Runtime::set_timeout).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TimerId(usize);
+
+struct Timer {
+    id: TimerId,
+    due: Instant,
+    // Timers without an interval are removed once they fire
+    interval: Option<Duration>,
+    send: Box<dyn Fn(&Emitter)>,
+}
+
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: Vec<Timer>,
+    next_id: usize,
+}
+
+impl Timers {
+    pub(crate) fn add<M: Clone + Send + Sync + 'static>(
+        &mut self,
+        now: Instant,
+        duration: Duration,
+        repeat: bool,
+        recipient: ComponentId<M>,
+        message: M,
+    ) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+
+        let send = Box::new(move |emitter: &Emitter| {
+            // Can only fail once the runtime is gone
+            let _ = emitter.emit(recipient, message.clone());
+        });
+
+        self.timers.push(Timer {
+            id,
+            due: now + duration,
+            interval: repeat.then_some(duration),
+            send,
+        });
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: TimerId) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// Send the messages of the timers that are due.
+    /// An interval that fell behind, e.g while the runtime was busy, fires once and
+    /// continues from now, instead of firing for every missed interval.
+    pub(crate) fn fire(&mut self, now: Instant, emitter: &Emitter) {
+        self.timers.retain_mut(|timer| {
+            if timer.due > now {
+                return true;
+            }
+
+            (timer.send)(emitter);
+
+            match timer.interval {
+                Some(interval) => {
+                    timer.due += interval;
+                    if timer.due <= now {
+                        timer.due = now + interval;
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_templates::WidgetComponentId;
+
+    use super::*;
+
+    #[test]
+    fn intervals_and_timeouts() {
+        let (sender, receiver) = flume::unbounded();
+        let emitter = Emitter::from(sender);
+        let recipient = ComponentId::<&str>::from(WidgetComponentId::from(0));
+        let ms = Duration::from_millis;
+        let start = Instant::now();
+
+        let mut timers = Timers::default();
+        timers.add(start, ms(10), true, recipient, "interval");
+        timers.add(start, ms(15), false, recipient, "timeout");
+        let removed = timers.add(start, ms(5), false, recipient, "removed");
+        timers.remove(removed);
+
+        let mut fire = |elapsed| {
+            timers.fire(start + ms(elapsed), &emitter);
+            receiver
+                .try_iter()
+                .map(|msg| *msg.payload().downcast::<&str>().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(fire(5).is_empty());
+        assert_eq!(fire(10), ["interval"]);
+        assert_eq!(fire(15), ["timeout"]);
+        assert_eq!(fire(20), ["interval"]);
+        assert!(fire(25).is_empty());
+
+        // Missed intervals fire once
+        assert_eq!(fire(100), ["interval"]);
+        assert!(fire(105).is_empty());
+        assert_eq!(fire(110), ["interval"]);
+    }
+}
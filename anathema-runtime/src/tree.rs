@@ -14,6 +14,9 @@ pub(crate) trait Tree<'bp> {
     ) -> Option<V>
     where
         F: FnOnce(&mut dyn AnyComponent, AnyEventCtx<'_, '_, '_>) -> V;
+
+    /// Focus or blur the component, updating `has_focus` in the template of the component
+    fn set_focus(&mut self, widget_id: WidgetId, state_id: StateId, event_ctx: &mut EventCtx<'_, '_, 'bp>, focus: bool);
 }
 
 impl<'bp> Tree<'bp> for WidgetTree<'bp> {
@@ -40,10 +43,14 @@ impl<'bp> Tree<'bp> for WidgetTree<'bp> {
 
             let component_ctx = ComponentContext::new(
                 state_id,
+                widget_id,
+                component.component_id,
                 component.parent,
                 component.assoc_functions,
                 event_ctx.assoc_events,
                 event_ctx.focus_queue,
+                event_ctx.overlay_queue,
+                event_ctx.exit,
                 component.external_state.as_ref(),
             );
 
@@ -58,4 +65,23 @@ impl<'bp> Tree<'bp> for WidgetTree<'bp> {
             Some(value)
         })
     }
+
+    fn set_focus(
+        &mut self,
+        widget_id: WidgetId,
+        state_id: StateId,
+        event_ctx: &mut EventCtx<'_, '_, 'bp>,
+        focus: bool,
+    ) {
+        self.with_value_mut(widget_id, |_, widget, _| {
+            if let WidgetKind::Component(component) = widget {
+                component.set_focus(focus);
+            }
+        });
+
+        self.with_component(widget_id, state_id, event_ctx, |comp, ctx| match focus {
+            true => comp.any_focus(ctx),
+            false => comp.any_blur(ctx),
+        });
+    }
 }
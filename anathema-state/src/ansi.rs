@@ -0,0 +1,242 @@
+//! Parse text with ANSI escape sequences, e.g the output of other command line tools,
+//! into [`StyledSpan`]s.
+//!
+//! Only the colors and the text attributes of [`SpanStyle`] are kept
+//! (SGR sequences, `ESC [ ... m`), any other escape sequence is removed from the text.
+use crate::{Color, List, SpanStyle, StyledSpan, Value};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Parser for text with ANSI escape sequences.
+///
+/// The style is kept between calls to [`AnsiParser::parse`], so a style set on one line
+/// carries over to the next line, the same way it would in a terminal.
+///
+/// ```
+/// use anathema_state::{AnsiParser, Color};
+///
+/// let mut parser = AnsiParser::new();
+/// let spans = parser.parse("\x1b[31merror\x1b[0m: not found");
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!(spans[0].foreground.copy_value(), Some(Color::Red));
+/// assert_eq!(spans[1].text.to_ref().as_str(), ": not found");
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct AnsiParser {
+    style: SpanStyle,
+}
+
+impl AnsiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The style of the next span
+    pub fn style(&self) -> SpanStyle {
+        self.style
+    }
+
+    /// Forget the current style
+    pub fn reset(&mut self) {
+        self.style = SpanStyle::default();
+    }
+
+    /// Split the text into spans, without the escape sequences
+    pub fn parse(&mut self, text: &str) -> Vec<StyledSpan> {
+        let mut spans = vec![];
+        self.parse_with(text, |text, style| spans.push(StyledSpan::new(text, style)));
+        spans
+    }
+
+    /// Call `f` with each piece of text and its style, without the escape sequences.
+    /// Unlike [`AnsiParser::parse`] this doesn't create any state values.
+    pub fn parse_with(&mut self, text: &str, mut f: impl FnMut(&str, SpanStyle)) {
+        let mut current = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                ESC => {
+                    let style = self.style;
+                    match chars.next() {
+                        // Control sequence: parameters followed by a final byte
+                        Some('[') => {
+                            let mut params = String::new();
+                            for c in chars.by_ref() {
+                                match c {
+                                    '\x40'..='\x7e' => {
+                                        if c == 'm' {
+                                            self.apply_sgr(&params);
+                                        }
+                                        break;
+                                    }
+                                    c => params.push(c),
+                                }
+                            }
+                        }
+                        // Operating system command, terminated by BEL or `ESC \`
+                        Some(']') => {
+                            while let Some(c) = chars.next() {
+                                match c {
+                                    BEL => break,
+                                    ESC if chars.peek() == Some(&'\\') => {
+                                        chars.next();
+                                        break;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    if style != self.style && !current.is_empty() {
+                        f(&current, style);
+                        current.clear();
+                    }
+                }
+                '\t' => current.push(c),
+                c if c.is_control() => {}
+                c => current.push(c),
+            }
+        }
+
+        if !current.is_empty() {
+            f(&current, self.style);
+        }
+    }
+
+    fn apply_sgr(&mut self, params: &str) {
+        let mut params = params.split([';', ':']).map(|p| p.parse::<u16>().unwrap_or(0));
+
+        while let Some(param) = params.next() {
+            match param {
+                0 => self.reset(),
+                1 => self.style.bold = true,
+                3 => self.style.italic = true,
+                7 => self.style.inverse = true,
+                22 => self.style.bold = false,
+                23 => self.style.italic = false,
+                27 => self.style.inverse = false,
+                30..=37 => self.style.foreground = Some(color(param - 30)),
+                38 => self.style.foreground = extended_color(&mut params),
+                39 => self.style.foreground = None,
+                40..=47 => self.style.background = Some(color(param - 40)),
+                48 => self.style.background = extended_color(&mut params),
+                49 => self.style.background = None,
+                90..=97 => self.style.foreground = Some(color(param - 90 + 8)),
+                100..=107 => self.style.background = Some(color(param - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+// One of the 16 standard colors
+fn color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Grey,
+        8 => Color::DarkGrey,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+// `5;n` for 256 colors or `2;r;g;b` for rgb
+fn extended_color(params: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    let mut next = || params.next().map(|p| p.min(255) as u8);
+    match next()? {
+        5 => Some(Color::AnsiVal(next()?)),
+        2 => Some(Color::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+/// Parse text with ANSI escape sequences into a list of spans.
+///
+/// ```
+/// use anathema_state::ansi_spans;
+///
+/// let spans = ansi_spans("\x1b[1mbold\x1b[22m and not");
+/// assert_eq!(spans.to_ref().len(), 2);
+/// ```
+pub fn ansi_spans(text: &str) -> Value<List<StyledSpan>> {
+    List::from_iter(AnsiParser::new().parse(text))
+}
+
+/// Remove all ANSI escape sequences from the text
+pub fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    AnsiParser::new().parse_with(text, |text, _| output.push_str(text));
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(parser: &mut AnsiParser, text: &str) -> Vec<(String, SpanStyle)> {
+        parser
+            .parse(text)
+            .iter()
+            .map(|span| (span.text.to_ref().clone(), span.style()))
+            .collect()
+    }
+
+    #[test]
+    fn colors_and_attributes() {
+        let mut parser = AnsiParser::new();
+        let spans = parse(&mut parser, "a\x1b[1;38;5;200mb\x1b[22;48;2;1;2;3mc\x1b[mdef");
+
+        let style = SpanStyle::default().bold().foreground(Color::AnsiVal(200));
+        assert_eq!(
+            spans,
+            [
+                ("a".to_string(), SpanStyle::default()),
+                ("b".to_string(), style),
+                (
+                    "c".to_string(),
+                    SpanStyle::default()
+                        .foreground(Color::AnsiVal(200))
+                        .background(Color::Rgb(1, 2, 3))
+                ),
+                ("def".to_string(), SpanStyle::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn style_carries_over() {
+        let mut parser = AnsiParser::new();
+        parse(&mut parser, "\x1b[92mgreen");
+        let spans = parse(&mut parser, "still green\x1b[39m");
+        assert_eq!(
+            spans,
+            [(
+                "still green".to_string(),
+                SpanStyle::default().foreground(Color::LightGreen)
+            )]
+        );
+    }
+
+    #[test]
+    fn remove_other_sequences() {
+        let mut parser = AnsiParser::new();
+        let spans = parse(&mut parser, "\x1b[2K\x1b]0;title\x07a\rb\x1b]8;;x\x1b\\c");
+        assert_eq!(spans, [("abc".to_string(), SpanStyle::default())]);
+        assert_eq!(strip_ansi("\x1b[1;31ma\x1b[0mb"), "ab");
+    }
+}
@@ -40,6 +40,68 @@ pub enum Color {
     AnsiVal(u8),
 }
 
+// The xterm defaults of the first 16 colors
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl Color {
+    /// The color as rgb, using the xterm defaults for named colors
+    /// as the actual color depends on the terminal.
+    /// `Reset` has no rgb value.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        let index = match self {
+            Self::Reset => return None,
+            Self::Rgb(r, g, b) => return Some((r, g, b)),
+            Self::AnsiVal(index @ 16..=231) => {
+                let level = |n: u8| match n {
+                    0 => 0,
+                    n => 55 + n * 40,
+                };
+                let index = index - 16;
+                return Some((level(index / 36), level(index / 6 % 6), level(index % 6)));
+            }
+            Self::AnsiVal(index @ 232..) => {
+                let grey = 8 + (index - 232) * 10;
+                return Some((grey, grey, grey));
+            }
+            Self::AnsiVal(index) => index,
+            Self::Black => 0,
+            Self::Red => 1,
+            Self::Green => 2,
+            Self::Yellow => 3,
+            Self::Blue => 4,
+            Self::Magenta => 5,
+            Self::Cyan => 6,
+            Self::Grey => 7,
+            Self::DarkGrey => 8,
+            Self::LightRed => 9,
+            Self::LightGreen => 10,
+            Self::LightYellow => 11,
+            Self::LightBlue => 12,
+            Self::LightMagenta => 13,
+            Self::LightCyan => 14,
+            Self::White => 15,
+        };
+        Some(ANSI_RGB[index as usize])
+    }
+}
+
 impl State for Color {
     fn to_common(&self) -> Option<CommonVal<'_>> {
         Some(CommonVal::Color(*self))
@@ -173,6 +235,16 @@ mod test {
         assert_eq!(Color::from_str("10").unwrap(), Color::AnsiVal(10));
     }
 
+    #[test]
+    fn to_rgb() {
+        assert_eq!(Color::Rgb(1, 2, 3).to_rgb(), Some((1, 2, 3)));
+        assert_eq!(Color::White.to_rgb(), Some((255, 255, 255)));
+        assert_eq!(Color::AnsiVal(9).to_rgb(), Color::LightRed.to_rgb());
+        assert_eq!(Color::AnsiVal(196).to_rgb(), Some((255, 0, 0)));
+        assert_eq!(Color::AnsiVal(232).to_rgb(), Some((8, 8, 8)));
+        assert_eq!(Color::Reset.to_rgb(), None);
+    }
+
     #[test]
     fn to_string() {
         assert_eq!(Color::from_str("#242424").unwrap().to_string(), "#242424");
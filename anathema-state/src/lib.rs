@@ -1,19 +1,29 @@
 pub use anathema_state_derive::State;
 use anathema_store::slab::Key;
 
+pub use crate::ansi::{ansi_spans, strip_ansi, AnsiParser};
 pub use crate::colors::{Color, FromColor};
 pub use crate::common::{CommonString, CommonVal};
+#[cfg(feature = "metrics")]
+pub use crate::metrics::SystemMetrics;
 pub use crate::numbers::Number;
+pub use crate::rate::{format_bytes, format_count, format_duration, RateCounter, Unit};
+pub use crate::spans::{highlight_matches, SpanStyle, StyledSpan};
 pub use crate::states::{AnyState, State, StateId, States};
 pub use crate::store::{
     clear_all_changes, clear_all_futures, clear_all_subs, debug, drain_changes, drain_futures, register_future, Change,
     Changes, FutureValues, Subscriber,
 };
-pub use crate::value::{List, Map, PendingValue, SharedState, Value, ValueRef};
+pub use crate::value::{Computed, Debounced, List, Map, PendingValue, SharedState, Throttled, Value, ValueRef};
 
+mod ansi;
 mod colors;
 mod common;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod numbers;
+mod rate;
+mod spans;
 mod states;
 mod store;
 mod value;
@@ -0,0 +1,142 @@
+//! System metrics (requires the `metrics` feature).
+//!
+//! [`SystemMetrics`] samples CPU, memory and network counters into values
+//! that can be used directly in templates:
+//!
+//! ```text
+//! text "cpu: " metrics.cpu "% mem: " metrics.memory " rx: " metrics.rx " tx: " metrics.tx
+//! ```
+//!
+//! Sampling happens on [`SystemMetrics::tick`], which is meant to be called
+//! from a component's `tick` function.
+use std::time::Duration;
+
+use sysinfo::{Networks, System};
+
+use crate::{format_bytes, State, Value};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(State)]
+pub struct SystemMetrics {
+    /// Global CPU usage as a percentage
+    pub cpu: Value<u8>,
+    /// Used memory in bytes
+    pub memory_used: Value<u64>,
+    /// Total memory in bytes
+    pub memory_total: Value<u64>,
+    /// Used memory as a percentage
+    pub memory_percent: Value<u8>,
+    /// Used and total memory, e.g `3.1 GiB / 16.0 GiB`
+    pub memory: Value<String>,
+    /// Bytes received per second, across all network interfaces, e.g `1.2 KiB/s`
+    pub rx: Value<String>,
+    /// Bytes transmitted per second, across all network interfaces
+    pub tx: Value<String>,
+    #[state_ignore]
+    sampler: Sampler,
+}
+
+struct Sampler {
+    system: System,
+    networks: Networks,
+    interval: Duration,
+    elapsed: Duration,
+}
+
+impl SystemMetrics {
+    /// Create a new instance sampling once per second
+    pub fn new() -> Self {
+        Self::with_interval(DEFAULT_INTERVAL)
+    }
+
+    /// Create a new instance sampling on the given interval.
+    ///
+    /// Note that CPU usage is calculated between samples, so very short
+    /// intervals (less than 200ms) will produce inaccurate values.
+    pub fn with_interval(interval: Duration) -> Self {
+        let sampler = Sampler {
+            system: System::new(),
+            networks: Networks::new_with_refreshed_list(),
+            interval,
+            elapsed: Duration::ZERO,
+        };
+
+        let mut metrics = Self {
+            cpu: 0.into(),
+            memory_used: 0.into(),
+            memory_total: 0.into(),
+            memory_percent: 0.into(),
+            memory: String::new().into(),
+            rx: format!("{}/s", format_bytes(0.0)).into(),
+            tx: format!("{}/s", format_bytes(0.0)).into(),
+            sampler,
+        };
+
+        metrics.sample();
+        metrics
+    }
+
+    /// Advance the internal timer by `dt`, and sample if the interval has elapsed.
+    /// Returns `true` if a sample was taken.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        self.sampler.elapsed += dt;
+        if self.sampler.elapsed < self.sampler.interval {
+            return false;
+        }
+
+        self.sample();
+        true
+    }
+
+    /// Sample all the metrics now
+    pub fn sample(&mut self) {
+        let elapsed = std::mem::take(&mut self.sampler.elapsed);
+        let system = &mut self.sampler.system;
+
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+
+        self.cpu.set(system.global_cpu_usage().round().clamp(0.0, 100.0) as u8);
+
+        let used = system.used_memory();
+        let total = system.total_memory();
+        self.memory_used.set(used);
+        self.memory_total.set(total);
+        let percent = match total {
+            0 => 0,
+            _ => (used * 100 / total) as u8,
+        };
+        self.memory_percent.set(percent);
+        self.memory
+            .set(format!("{} / {}", format_bytes(used as f64), format_bytes(total as f64)));
+
+        // Network counters are the bytes since the last refresh
+        self.sampler.networks.refresh();
+        if elapsed.is_zero() {
+            return;
+        }
+
+        let (rx, tx) = self
+            .sampler
+            .networks
+            .iter()
+            .fold((0, 0), |(rx, tx), (_, data)| (rx + data.received(), tx + data.transmitted()));
+        let secs = elapsed.as_secs_f64();
+        self.rx.set(format!("{}/s", format_bytes(rx as f64 / secs)));
+        self.tx.set(format!("{}/s", format_bytes(tx as f64 / secs)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sample_on_interval() {
+        let mut metrics = SystemMetrics::with_interval(Duration::from_millis(500));
+        assert!(!metrics.tick(Duration::from_millis(200)));
+        assert!(metrics.tick(Duration::from_millis(300)));
+        assert!(metrics.memory_total.copy_value() >= metrics.memory_used.copy_value());
+    }
+}
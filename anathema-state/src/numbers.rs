@@ -5,11 +5,12 @@
 //!
 //! Note: If either the `lhs` or the `rhs` is a float then the entire
 //! number has to be treated as a float
+use std::cmp::Ordering;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
 use crate::{CommonVal, State};
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Copy, Clone)]
 pub enum Number {
     Usize(usize),
     Isize(isize),
@@ -60,6 +61,24 @@ impl Number {
         }
     }
 
+    // Every integer variant fits in an i128 without loss
+    fn as_wide_int(self) -> i128 {
+        match self {
+            Self::Usize(n) => n as i128,
+            Self::Isize(n) => n as i128,
+            Self::U64(n) => n as i128,
+            Self::I64(n) => n as i128,
+            Self::U32(n) => n as i128,
+            Self::I32(n) => n as i128,
+            Self::U16(n) => n as i128,
+            Self::I16(n) => n as i128,
+            Self::U8(n) => n as i128,
+            Self::I8(n) => n as i128,
+            Self::F64(n) => n as i128,
+            Self::F32(n) => n as i128,
+        }
+    }
+
     pub fn is_float(&self) -> bool {
         matches!(self, Self::F64(_) | Self::F32(_))
     }
@@ -83,6 +102,39 @@ impl Number {
     }
 }
 
+// Numbers are compared by value, no matter the type,
+// e.g `Number::Usize(1) == Number::F64(1.0)`
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Usize(a), Self::Usize(b)) => a.partial_cmp(b),
+            (Self::Isize(a), Self::Isize(b)) => a.partial_cmp(b),
+            (Self::U64(a), Self::U64(b)) => a.partial_cmp(b),
+            (Self::I64(a), Self::I64(b)) => a.partial_cmp(b),
+            (Self::U32(a), Self::U32(b)) => a.partial_cmp(b),
+            (Self::I32(a), Self::I32(b)) => a.partial_cmp(b),
+            (Self::U16(a), Self::U16(b)) => a.partial_cmp(b),
+            (Self::I16(a), Self::I16(b)) => a.partial_cmp(b),
+            (Self::U8(a), Self::U8(b)) => a.partial_cmp(b),
+            (Self::I8(a), Self::I8(b)) => a.partial_cmp(b),
+            (Self::F64(a), Self::F64(b)) => a.partial_cmp(b),
+            (Self::F32(a), Self::F32(b)) => a.partial_cmp(b),
+            _ => match IsFloat(*self, *other).is_float() {
+                true => self.as_float().partial_cmp(&other.as_float()),
+                // Widen to i128 so mixed signed and unsigned values
+                // can be compared without wrapping
+                false => self.as_wide_int().partial_cmp(&other.as_wide_int()),
+            },
+        }
+    }
+}
+
 impl State for Number {
     fn to_number(&self) -> Option<Number> {
         Some(*self)
@@ -169,3 +221,23 @@ impl Neg for Number {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compare_mixed_types() {
+        assert_eq!(Number::Usize(1), Number::F64(1.0));
+        assert_eq!(Number::U8(3), Number::I64(3));
+        assert!(Number::I8(-1) < Number::U8(0));
+    }
+
+    #[test]
+    fn compare_above_i64_max() {
+        assert!(Number::U64(u64::MAX) > Number::U64(1));
+        assert!(Number::U64(u64::MAX) > Number::I64(i64::MAX));
+        assert_ne!(Number::U64(u64::MAX), Number::I64(-1));
+        assert!(Number::Usize(usize::MAX) > Number::Isize(-1));
+    }
+}
@@ -0,0 +1,316 @@
+//! Rate tracking for progress and status displays.
+//!
+//! A [`RateCounter`] is updated incrementally and exposes human readable
+//! values to templates:
+//!
+//! ```text
+//! text counter.current " / " counter.total " (" counter.rate ", eta " counter.eta ")"
+//! ```
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::{State, Value};
+
+const DEFAULT_WINDOW: Duration = Duration::from_secs(5);
+const UNKNOWN: &str = "--";
+
+/// The unit used when formatting values
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Unit {
+    /// Plain items, e.g `12.5/s`
+    #[default]
+    Items,
+    /// Bytes, formatted as binary units, e.g `1.2 MiB/s`
+    Bytes,
+}
+
+impl Unit {
+    /// Format an amount in the given unit
+    pub fn format(&self, amount: f64) -> String {
+        match self {
+            Self::Items => format_count(amount),
+            Self::Bytes => format_bytes(amount),
+        }
+    }
+}
+
+/// Track the rate of change of a counter.
+///
+/// The rate is calculated over a sliding window (five seconds by default),
+/// and the ETA is calculated from the rate and the (optional) total.
+#[derive(State)]
+pub struct RateCounter {
+    /// Current count, formatted according to the unit
+    pub current: Value<String>,
+    /// The total, formatted according to the unit, or `--` if there is no total
+    pub total: Value<String>,
+    /// Change per second, e.g `1.2 MiB/s`
+    pub rate: Value<String>,
+    /// Estimated time remaining, e.g `1m 05s`
+    pub eta: Value<String>,
+    /// Time since the counter was created
+    pub elapsed: Value<String>,
+    #[state_ignore]
+    inner: Inner,
+}
+
+struct Inner {
+    unit: Unit,
+    count: u64,
+    total: Option<u64>,
+    start: Instant,
+    window: Duration,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RateCounter {
+    pub fn new() -> Self {
+        Self::new_at(Instant::now())
+    }
+
+    fn new_at(start: Instant) -> Self {
+        let inner = Inner {
+            unit: Unit::Items,
+            count: 0,
+            total: None,
+            start,
+            window: DEFAULT_WINDOW,
+            samples: VecDeque::from([(start, 0)]),
+        };
+
+        let mut counter = Self {
+            current: String::new().into(),
+            total: String::new().into(),
+            rate: String::new().into(),
+            eta: String::new().into(),
+            elapsed: String::new().into(),
+            inner,
+        };
+        counter.update_values(start);
+        counter
+    }
+
+    /// Format values as bytes
+    pub fn bytes(mut self) -> Self {
+        self.inner.unit = Unit::Bytes;
+        self.update_values(self.inner.start);
+        self
+    }
+
+    /// Set the total, used to calculate the ETA
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.set_total(total);
+        self
+    }
+
+    /// Set the duration of the sliding window used to calculate the rate
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.inner.window = window;
+        self
+    }
+
+    /// Set the total, used to calculate the ETA
+    pub fn set_total(&mut self, total: u64) {
+        self.inner.total = Some(total);
+        let now = self.inner.samples.back().map(|(t, _)| *t).unwrap_or(self.inner.start);
+        self.update_values(now);
+    }
+
+    /// Increment the count by `amount`
+    pub fn add(&mut self, amount: u64) {
+        self.add_at(amount, Instant::now());
+    }
+
+    /// Set the count
+    pub fn set(&mut self, count: u64) {
+        self.set_at(count, Instant::now());
+    }
+
+    /// Increment the count by `amount` at a given point in time
+    pub fn add_at(&mut self, amount: u64, now: Instant) {
+        self.set_at(self.inner.count.saturating_add(amount), now);
+    }
+
+    /// Set the count at a given point in time
+    pub fn set_at(&mut self, count: u64, now: Instant) {
+        self.inner.count = count;
+        self.inner.samples.push_back((now, count));
+
+        // Keep at least two samples so there is always something to measure
+        while self.inner.samples.len() > 2 {
+            match self.inner.samples.get(1) {
+                Some((t, _)) if now.duration_since(*t) >= self.inner.window => {
+                    self.inner.samples.pop_front();
+                }
+                _ => break,
+            }
+        }
+
+        self.update_values(now);
+    }
+
+    /// The raw count
+    pub fn count(&self) -> u64 {
+        self.inner.count
+    }
+
+    /// Change per second over the sliding window
+    pub fn per_second(&self) -> f64 {
+        let (Some((first_t, first)), Some((last_t, last))) = (self.inner.samples.front(), self.inner.samples.back())
+        else {
+            return 0.0;
+        };
+
+        let secs = last_t.duration_since(*first_t).as_secs_f64();
+        match secs > 0.0 {
+            true => last.saturating_sub(*first) as f64 / secs,
+            false => 0.0,
+        }
+    }
+
+    /// Estimated time remaining.
+    /// This is `None` if there is no total or no progress is being made.
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.inner.total?;
+        let remaining = total.saturating_sub(self.inner.count);
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+
+        let rate = self.per_second();
+        match rate > 0.0 {
+            true => Some(Duration::from_secs_f64(remaining as f64 / rate)),
+            false => None,
+        }
+    }
+
+    fn update_values(&mut self, now: Instant) {
+        let unit = self.inner.unit;
+        self.current.set(unit.format(self.inner.count as f64));
+
+        let total = match self.inner.total {
+            Some(total) => unit.format(total as f64),
+            None => UNKNOWN.into(),
+        };
+        self.total.set(total);
+
+        self.rate.set(format!("{}/s", unit.format(self.per_second())));
+
+        let eta = match self.eta() {
+            Some(eta) => format_duration(eta),
+            None => UNKNOWN.into(),
+        };
+        self.eta.set(eta);
+        self.elapsed.set(format_duration(now.duration_since(self.inner.start)));
+    }
+}
+
+/// Format a number of bytes using binary units.
+/// ```
+/// # use anathema_state::format_bytes;
+/// assert_eq!(format_bytes(1536.0), "1.5 KiB");
+/// ```
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes < 1024.0 {
+        return format!("{} B", bytes.round() as u64);
+    }
+
+    let mut value = bytes;
+    let mut unit = UNITS[0];
+    for u in UNITS {
+        value /= 1024.0;
+        unit = u;
+        if value < 1024.0 {
+            break;
+        }
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+/// Format a count, using `k`, `M` and `G` suffixes for large numbers.
+/// ```
+/// # use anathema_state::format_count;
+/// assert_eq!(format_count(12.0), "12");
+/// assert_eq!(format_count(12_300.0), "12.3k");
+/// ```
+pub fn format_count(count: f64) -> String {
+    match count {
+        c if c >= 1_000_000_000.0 => format!("{:.1}G", c / 1_000_000_000.0),
+        c if c >= 1_000_000.0 => format!("{:.1}M", c / 1_000_000.0),
+        c if c >= 1_000.0 => format!("{:.1}k", c / 1_000.0),
+        c if c.fract() == 0.0 => format!("{c}"),
+        c => format!("{c:.1}"),
+    }
+}
+
+/// Format a duration as hours, minutes and seconds.
+/// ```
+/// # use std::time::Duration;
+/// # use anathema_state::format_duration;
+/// assert_eq!(format_duration(Duration::from_secs(65)), "1m 05s");
+/// assert_eq!(format_duration(Duration::from_secs(3_725)), "1h 02m 05s");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds:02}s"),
+        _ => format!("{hours}h {minutes:02}m {seconds:02}s"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rate_and_eta() {
+        let start = Instant::now();
+        let mut counter = RateCounter::new_at(start).with_total(100);
+
+        counter.add_at(10, start + Duration::from_secs(1));
+        counter.add_at(10, start + Duration::from_secs(2));
+
+        assert_eq!(counter.per_second(), 10.0);
+        assert_eq!(counter.eta(), Some(Duration::from_secs(8)));
+        assert_eq!(*counter.rate.to_ref(), "10/s");
+        assert_eq!(*counter.eta.to_ref(), "8s");
+        assert_eq!(*counter.elapsed.to_ref(), "2s");
+    }
+
+    #[test]
+    fn sliding_window() {
+        let start = Instant::now();
+        let mut counter = RateCounter::new_at(start).with_window(Duration::from_secs(2));
+
+        counter.add_at(100, start + Duration::from_secs(1));
+        for secs in 2..=5 {
+            counter.add_at(10, start + Duration::from_secs(secs));
+        }
+
+        // The initial burst is outside of the window
+        assert_eq!(counter.per_second(), 10.0);
+    }
+
+    #[test]
+    fn no_total_no_eta() {
+        let start = Instant::now();
+        let mut counter = RateCounter::new_at(start).bytes();
+        counter.add_at(2048, start + Duration::from_secs(1));
+
+        assert_eq!(counter.eta(), None);
+        assert_eq!(*counter.eta.to_ref(), "--");
+        assert_eq!(*counter.current.to_ref(), "2.0 KiB");
+        assert_eq!(*counter.rate.to_ref(), "2.0 KiB/s");
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(12.0), "12 B");
+        assert_eq!(format_bytes(1024.0 * 1024.0 * 3.5), "3.5 MiB");
+    }
+}
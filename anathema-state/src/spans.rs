@@ -0,0 +1,152 @@
+//! Styled text spans.
+//!
+//! A list of [`StyledSpan`]s can be rendered as a single paragraph,
+//! where each span carries its own style:
+//!
+//! ```text
+//! text
+//!     for span in spans
+//!         span [foreground: span.foreground, background: span.background, bold: span.bold] span.text
+//! ```
+use crate::{Color, List, State, Value};
+
+/// The style of a single span.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SpanStyle {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub inverse: bool,
+}
+
+impl SpanStyle {
+    pub fn foreground(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn inverse(mut self) -> Self {
+        self.inverse = true;
+        self
+    }
+}
+
+/// A piece of text with a style.
+/// Colors that are not set are inherited from the surrounding text.
+#[derive(State)]
+pub struct StyledSpan {
+    pub text: Value<String>,
+    pub foreground: Value<Option<Color>>,
+    pub background: Value<Option<Color>>,
+    pub bold: Value<bool>,
+    pub italic: Value<bool>,
+    pub inverse: Value<bool>,
+}
+
+impl StyledSpan {
+    pub fn new(text: impl Into<String>, style: SpanStyle) -> Self {
+        Self {
+            text: text.into().into(),
+            foreground: style.foreground.into(),
+            background: style.background.into(),
+            bold: style.bold.into(),
+            italic: style.italic.into(),
+            inverse: style.inverse.into(),
+        }
+    }
+
+    /// Unstyled text
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(text, SpanStyle::default())
+    }
+
+    pub fn style(&self) -> SpanStyle {
+        SpanStyle {
+            foreground: self.foreground.copy_value(),
+            background: self.background.copy_value(),
+            bold: self.bold.copy_value(),
+            italic: self.italic.copy_value(),
+            inverse: self.inverse.copy_value(),
+        }
+    }
+
+    pub fn set_style(&mut self, style: SpanStyle) {
+        self.foreground.set(style.foreground);
+        self.background.set(style.background);
+        self.bold.set(style.bold);
+        self.italic.set(style.italic);
+        self.inverse.set(style.inverse);
+    }
+}
+
+/// Split `text` into spans where every occurrence of `needle` has the `highlight` style.
+///
+/// ```
+/// use anathema_state::{highlight_matches, Color, SpanStyle};
+///
+/// let spans = highlight_matches("one two one", "one", SpanStyle::default().foreground(Color::Red));
+/// assert_eq!(spans.to_ref().len(), 3);
+/// ```
+pub fn highlight_matches(text: &str, needle: &str, highlight: SpanStyle) -> Value<List<StyledSpan>> {
+    let mut spans = vec![];
+
+    match needle.is_empty() {
+        true => spans.push(StyledSpan::plain(text)),
+        false => {
+            let mut last = 0;
+            for (index, matched) in text.match_indices(needle) {
+                if index > last {
+                    spans.push(StyledSpan::plain(&text[last..index]));
+                }
+                spans.push(StyledSpan::new(matched, highlight));
+                last = index + matched.len();
+            }
+
+            if last < text.len() {
+                spans.push(StyledSpan::plain(&text[last..]));
+            }
+        }
+    }
+
+    List::from_iter(spans)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn highlight() {
+        let style = SpanStyle::default().bold();
+        let spans = highlight_matches("a-b-", "-", style);
+        let spans = spans.to_ref();
+
+        let texts = spans.iter().map(|s| s.to_ref().text.to_ref().clone()).collect::<Vec<_>>();
+        assert_eq!(texts, ["a", "-", "b", "-"]);
+
+        let styles = spans.iter().map(|s| s.to_ref().style().bold).collect::<Vec<_>>();
+        assert_eq!(styles, [false, true, false, true]);
+    }
+
+    #[test]
+    fn highlight_empty_needle() {
+        let spans = highlight_matches("abc", "", SpanStyle::default());
+        assert_eq!(spans.to_ref().len(), 1);
+    }
+}
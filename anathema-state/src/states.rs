@@ -41,6 +41,8 @@ pub trait AnyState: 'static {
     fn to_bool(&self) -> bool;
 
     fn count(&self) -> usize;
+
+    fn is_none(&self) -> bool;
 }
 
 impl AnyState for Box<dyn AnyState> {
@@ -75,6 +77,10 @@ impl AnyState for Box<dyn AnyState> {
     fn count(&self) -> usize {
         self.as_ref().count()
     }
+
+    fn is_none(&self) -> bool {
+        self.as_ref().is_none()
+    }
 }
 
 impl<T: State> AnyState for T {
@@ -109,6 +115,10 @@ impl<T: State> AnyState for T {
     fn count(&self) -> usize {
         <Self as State>::count(self)
     }
+
+    fn is_none(&self) -> bool {
+        <Self as State>::is_none(self)
+    }
 }
 
 pub trait State: 'static {
@@ -140,6 +150,12 @@ pub trait State: 'static {
         false
     }
 
+    /// Returns true if the state has no value, e.g `Option::None`.
+    /// Used by the `??` operator in templates.
+    fn is_none(&self) -> bool {
+        false
+    }
+
     fn to_common(&self) -> Option<CommonVal<'_>>;
 }
 
@@ -167,6 +183,10 @@ impl State for Box<dyn State> {
     fn count(&self) -> usize {
         self.as_ref().count()
     }
+
+    fn is_none(&self) -> bool {
+        self.as_ref().is_none()
+    }
 }
 
 impl<T: 'static + State> State for Value<T> {
@@ -193,6 +213,10 @@ impl<T: 'static + State> State for Value<T> {
     fn count(&self) -> usize {
         self.to_ref().count()
     }
+
+    fn is_none(&self) -> bool {
+        self.to_ref().is_none()
+    }
 }
 
 impl Debug for dyn State {
@@ -311,6 +335,10 @@ impl<T: State> State for Option<T> {
     fn to_bool(&self) -> bool {
         self.as_ref().map(|s| s.to_bool()).unwrap_or(false)
     }
+
+    fn is_none(&self) -> bool {
+        self.as_ref().is_none_or(|s| s.is_none())
+    }
 }
 
 impl_num_state!(u8);
@@ -1,5 +1,6 @@
 use anathema_store::stack::Stack;
 
+use super::computed::mark_dirty;
 use super::subscriber::{SubKey, Subscribers};
 use super::{CHANGES, SUBSCRIBERS};
 use crate::PendingValue;
@@ -25,6 +26,8 @@ pub fn clear_all_changes() {
 }
 
 pub(crate) fn changed(subkey: SubKey, change: Change) {
+    mark_dirty(subkey);
+
     let subscribers = SUBSCRIBERS.with_borrow(|subs| subs.get(subkey));
     if subscribers.is_empty() {
         return;
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, HashSet};
+
+use super::subscriber::SubKey;
+use super::COMPUTED;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ComputedId(usize);
+
+// Keeps track of the values that computed values depend on
+pub(super) struct Computations {
+    next_id: usize,
+    dependents: HashMap<SubKey, Vec<ComputedId>>,
+    dirty: HashSet<ComputedId>,
+    // The values read by each computation that is currently resolving
+    tracking: Vec<Vec<SubKey>>,
+}
+
+impl Computations {
+    pub(super) fn empty() -> Self {
+        Self {
+            next_id: 0,
+            dependents: HashMap::new(),
+            dirty: HashSet::new(),
+            tracking: vec![],
+        }
+    }
+}
+
+pub(crate) fn new_computed() -> ComputedId {
+    COMPUTED.with_borrow_mut(|computed| {
+        let id = ComputedId(computed.next_id);
+        computed.next_id += 1;
+        id
+    })
+}
+
+// Record that a value was read, if a computed value is resolving
+pub(crate) fn track(sub_key: SubKey) {
+    COMPUTED.with_borrow_mut(|computed| {
+        if let Some(reads) = computed.tracking.last_mut() {
+            reads.push(sub_key);
+        }
+    });
+}
+
+// Mark every computed value that depends on the value as dirty
+pub(super) fn mark_dirty(sub_key: SubKey) {
+    COMPUTED.with_borrow_mut(|computed| {
+        let Computations { dependents, dirty, .. } = computed;
+        if let Some(ids) = dependents.get(&sub_key) {
+            dirty.extend(ids.iter().copied());
+        }
+    });
+}
+
+pub(crate) fn is_dirty(id: ComputedId) -> bool {
+    COMPUTED.with_borrow(|computed| computed.dirty.contains(&id))
+}
+
+// Track the values read by `f`, and return them along with the output of `f`
+pub(crate) fn track_reads<T>(f: impl FnOnce() -> T) -> (T, Vec<SubKey>) {
+    COMPUTED.with_borrow_mut(|computed| computed.tracking.push(vec![]));
+    let output = f();
+    let mut reads = COMPUTED.with_borrow_mut(|computed| computed.tracking.pop().unwrap_or_default());
+    reads.sort_by_key(|key| usize::from(*key));
+    reads.dedup();
+    (output, reads)
+}
+
+// Replace the dependencies of a computed value, and mark it as clean
+pub(crate) fn set_dependencies(id: ComputedId, old: &[SubKey], new: &[SubKey]) {
+    COMPUTED.with_borrow_mut(|computed| {
+        remove(computed, id, old);
+        for key in new {
+            computed.dependents.entry(*key).or_default().push(id);
+        }
+        computed.dirty.remove(&id);
+    });
+}
+
+pub(crate) fn drop_computed(id: ComputedId, dependencies: &[SubKey]) {
+    // The store might already be gone if this happens as the thread exits
+    let _ = COMPUTED.try_with(|computed| {
+        let mut computed = computed.borrow_mut();
+        remove(&mut computed, id, dependencies);
+        computed.dirty.remove(&id);
+    });
+}
+
+fn remove(computed: &mut Computations, id: ComputedId, dependencies: &[SubKey]) {
+    for key in dependencies {
+        if let Some(ids) = computed.dependents.get_mut(key) {
+            ids.retain(|dependent| *dependent != id);
+            if ids.is_empty() {
+                computed.dependents.remove(key);
+            }
+        }
+    }
+}
@@ -5,11 +5,13 @@ use anathema_store::store::{Owned, OwnedKey, Shared};
 
 pub(crate) use self::change::changed;
 pub use self::change::{clear_all_changes, drain_changes, Change, Changes};
+use self::computed::Computations;
 pub use self::subscriber::{FutureValues, Subscriber};
 use self::subscriber::{SubKey, SubscriberMap};
 use crate::states::AnyState;
 
 mod change;
+pub(crate) mod computed;
 pub mod debug;
 pub(crate) mod subscriber;
 pub(crate) mod values;
@@ -20,6 +22,7 @@ thread_local! {
     static SUBSCRIBERS: RefCell<SubscriberMap> = const { RefCell::new(SubscriberMap::empty()) };
     static CHANGES: RefCell<Changes> = const { RefCell::new(Stack::empty()) };
     static FUTURE_VALUES: RefCell<FutureValues> = const { RefCell::new(Stack::empty()) };
+    static COMPUTED: RefCell<Computations> = RefCell::new(Computations::empty());
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
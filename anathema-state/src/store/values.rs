@@ -87,6 +87,20 @@ pub(crate) fn make_shared(owned_key: OwnedKey) -> Option<(SharedKey, Element<Box
     })
 }
 
+// Replace an owned value of the type `T`.
+// Returns false if the value is gone, checked out, shared or of another type.
+pub(crate) fn try_set<T: 'static>(key: OwnedKey, value: T) -> bool {
+    let mut value = Some(value);
+    // The previous value is dropped outside of the store, as it can hold values of its own
+    let previous = OWNED.with(|owned| {
+        owned.try_with_mut(key, |current| {
+            let current = current.to_any_mut().downcast_mut::<T>()?;
+            value.take().map(|value| std::mem::replace(current, value))
+        })
+    });
+    previous.flatten().is_some()
+}
+
 // Return an owned value back into `OWNED`.
 pub(crate) fn return_owned(key: OwnedKey, value: Box<dyn AnyState>) {
     OWNED.with(|owned| owned.return_unique_borrow(key, value));
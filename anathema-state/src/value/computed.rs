@@ -0,0 +1,139 @@
+use std::ops::Deref;
+
+use super::Value;
+use crate::states::AnyState;
+use crate::store::computed::{drop_computed, is_dirty, new_computed, set_dependencies, track_reads, ComputedId};
+use crate::store::subscriber::SubKey;
+
+/// A [`Value`] derived from other values.
+///
+/// The values read while resolving become the dependencies of the computed value,
+/// and the value is only resolved again once one of them changes.
+/// This makes [`Computed::resolve`] cheap enough to call for every update,
+/// e.g from `Component::tick`.
+/// ```
+/// # extern crate anathema_state as anathema;
+/// # use anathema_state::*;
+/// #[derive(State)]
+/// struct Cart {
+///     prices: Value<List<u32>>,
+///     total: Computed<u32>,
+/// }
+///
+/// let mut cart = Cart {
+///     prices: List::from_iter([10, 5]),
+///     total: Computed::new(0),
+/// };
+///
+/// let sum = |prices: &Value<List<u32>>| prices.to_ref().iter().map(|price| price.copy_value()).sum();
+/// assert!(cart.total.resolve(|| sum(&cart.prices)));
+/// assert_eq!(cart.total.copy_value(), 15);
+///
+/// // Nothing changed
+/// assert!(!cart.total.resolve(|| sum(&cart.prices)));
+///
+/// cart.prices.push_back(1);
+/// assert!(cart.total.resolve(|| sum(&cart.prices)));
+/// assert_eq!(cart.total.copy_value(), 16);
+/// ```
+#[derive(Debug)]
+pub struct Computed<T> {
+    value: Value<T>,
+    id: ComputedId,
+    dependencies: Vec<SubKey>,
+    resolved: bool,
+}
+
+impl<T: AnyState + 'static> Computed<T> {
+    /// Create a computed value with the value it has until it's resolved
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Value::new(value),
+            id: new_computed(),
+            dependencies: vec![],
+            resolved: false,
+        }
+    }
+
+    /// Set the value to the output of `f`, if it has never been resolved or one of the
+    /// values read the last time changed.
+    /// Returns true if the value was resolved.
+    pub fn resolve(&mut self, f: impl FnOnce() -> T) -> bool {
+        if !self.is_dirty() {
+            return false;
+        }
+
+        let (value, dependencies) = track_reads(f);
+        set_dependencies(self.id, &self.dependencies, &dependencies);
+        self.dependencies = dependencies;
+        self.resolved = true;
+        self.value.set(value);
+        true
+    }
+
+    /// Returns true if the value has to be resolved
+    pub fn is_dirty(&self) -> bool {
+        !self.resolved || is_dirty(self.id)
+    }
+}
+
+impl<T> Deref for Computed<T> {
+    type Target = Value<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> Drop for Computed<T> {
+    fn drop(&mut self) {
+        drop_computed(self.id, &self.dependencies);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Map;
+
+    #[test]
+    fn resolve_on_change() {
+        let mut a = Value::new(1);
+        let mut b = Value::new(2);
+        let mut sum = Computed::new(0);
+
+        assert!(sum.is_dirty());
+        assert!(sum.resolve(|| a.copy_value() + b.copy_value()));
+        assert_eq!(sum.copy_value(), 3);
+        assert!(!sum.resolve(|| unreachable!()));
+
+        b.set(3);
+        assert!(sum.is_dirty());
+        assert!(sum.resolve(|| a.copy_value() + b.copy_value()));
+        assert_eq!(sum.copy_value(), 4);
+
+        // Only the values read the last time are dependencies
+        *a.to_mut() = 10;
+        assert!(sum.resolve(|| b.copy_value()));
+        a.set(20);
+        assert!(!sum.is_dirty());
+        assert_eq!(sum.copy_value(), 3);
+    }
+
+    #[test]
+    fn computed_from_computed() {
+        let mut map = Map::empty();
+        map.insert("a", 2);
+        let mut double = Computed::new(0);
+        let mut quadruple = Computed::new(0);
+
+        double.resolve(|| map.to_ref().get("a").unwrap().copy_value() * 2);
+        quadruple.resolve(|| double.copy_value() * 2);
+        assert_eq!(quadruple.copy_value(), 8);
+
+        map.insert("a", 3);
+        assert!(double.resolve(|| map.to_ref().get("a").unwrap().copy_value() * 2));
+        assert!(quadruple.resolve(|| double.copy_value() * 2));
+        assert_eq!(quadruple.copy_value(), 12);
+    }
+}
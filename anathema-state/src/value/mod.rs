@@ -5,19 +5,25 @@ use std::ops::{Deref, DerefMut};
 use anathema_store::slab::Element;
 use anathema_store::store::{OwnedKey, SharedKey};
 
+pub use self::computed::Computed;
 pub use self::list::List;
 pub use self::map::Map;
+pub use self::rate_limit::{Debounced, Throttled};
 use super::State;
 use crate::states::AnyState;
+use crate::store::computed::track;
 use crate::store::subscriber::{subscribe, unsubscribe};
 use crate::store::values::{
-    copy_val, drop_value, get_unique, make_shared, new_value, return_owned, return_shared, try_make_shared, with_owned,
+    copy_val, drop_value, get_unique, make_shared, new_value, return_owned, return_shared, try_make_shared, try_set,
+    with_owned,
 };
 use crate::store::{changed, ValueKey};
 use crate::{Change, Subscriber};
 
+mod computed;
 mod list;
 mod map;
+mod rate_limit;
 
 /// A value that reacts to change.
 ///
@@ -75,6 +81,7 @@ impl<T: AnyState + 'static> Value<T> {
     /// is no unique access to the value.
     #[must_use]
     pub fn to_ref(&self) -> Shared<'_, T> {
+        track(self.key.sub());
         let (key, value) = make_shared(self.key.owned()).expect("the value exists as it's coming directly from `Self`");
 
         Shared {
@@ -103,6 +110,7 @@ impl<T: AnyState + 'static> Value<T> {
     }
 
     pub fn shared_state(&self) -> Option<SharedState<'_>> {
+        track(self.key.sub());
         let (key, value) = try_make_shared(self.key.owned())?;
         let shared = SharedState::new(key, value);
         Some(shared)
@@ -123,6 +131,7 @@ impl<T: AnyState + 'static> Value<T> {
 /// Copy the inner value from the owned value.
 impl<T: State + 'static + Copy> Value<T> {
     pub fn copy_value(&self) -> T {
+        track(self.key.sub());
         copy_val(self.key.owned())
     }
 }
@@ -332,6 +341,7 @@ pub struct ValueRef {
 impl ValueRef {
     /// Load the value. This will return `None` if the owner has dropped the value
     pub fn value<T: 'static>(&self) -> Option<Shared<'_, T>> {
+        track(self.value_key.sub());
         let (key, value) = try_make_shared(self.value_key.owned())?;
         let shared = Shared::new(key, value);
         Some(shared)
@@ -341,6 +351,7 @@ impl ValueRef {
     /// This will return `None` if the `Value<T>` behind this `ValueRef` has
     /// been dropped.
     pub fn as_state(&self) -> Option<SharedState<'_>> {
+        track(self.value_key.sub());
         let (key, value) = try_make_shared(self.value_key.owned())?;
         let shared = SharedState::new(key, value);
         Some(shared)
@@ -350,6 +361,16 @@ impl ValueRef {
         PendingValue(self.value_key)
     }
 
+    /// Replace the value, notifying the subscribers as if it was set through the `Value`.
+    /// Returns false if the value has been dropped, is currently borrowed, or isn't a `T`.
+    pub fn set<T: 'static>(&self, value: T) -> bool {
+        let is_set = try_set(self.value_key.owned(), value);
+        if is_set {
+            changed(self.value_key.sub(), Change::Changed);
+        }
+        is_set
+    }
+
     /// Get a copy of the owned key.
     /// Used for debugging.
     pub fn owned_key(&self) -> OwnedKey {
@@ -387,6 +408,7 @@ impl PendingValue {
     where
         F: Fn(&dyn AnyState) -> T,
     {
+        track(self.0.sub());
         with_owned(self.0.owned(), f)
     }
 
@@ -398,6 +420,7 @@ impl PendingValue {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::store::testing::drain_changes;
 
     #[test]
     fn new_value() {
@@ -453,4 +476,23 @@ mod test {
 
         assert_eq!(val.as_int(), 2);
     }
+
+    #[test]
+    fn set_through_value_ref() {
+        let value = Value::new(1);
+        let value_ref = value.value_ref(Subscriber::ZERO);
+
+        assert!(value_ref.set(2));
+        assert_eq!(value.copy_value(), 2);
+        let (subscribers, change) = drain_changes().remove(0);
+        assert_eq!(subscribers, [Subscriber::ZERO]);
+        assert!(matches!(change, Change::Changed));
+
+        // Wrong type
+        assert!(!value_ref.set("a"));
+
+        // Borrowed
+        let _shared = value.to_ref();
+        assert!(!value_ref.set(3));
+    }
 }
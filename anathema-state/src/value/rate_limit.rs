@@ -0,0 +1,250 @@
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+use super::Value;
+use crate::states::AnyState;
+
+/// A [`Value`] that changes at most once per interval, for values that change faster than
+/// they need to be shown, e.g the mouse position or a stream of metrics.
+///
+/// The first change is applied straight away. Changes within the interval are held back,
+/// and the latest one is applied by [`Throttled::tick`] once the interval has passed.
+/// ```
+/// # use std::time::Duration;
+/// # extern crate anathema_state as anathema;
+/// # use anathema_state::*;
+/// #[derive(State)]
+/// struct Metrics {
+///     cpu: Throttled<f64>,
+/// }
+///
+/// let mut metrics = Metrics { cpu: Throttled::new(0.0, Duration::from_millis(250)) };
+/// metrics.cpu.set(12.5);
+/// metrics.cpu.set(13.0);
+/// assert_eq!(metrics.cpu.copy_value(), 12.5);
+/// assert_eq!(metrics.cpu.pending(), Some(&13.0));
+///
+/// // Once per frame, e.g from `Component::tick`
+/// metrics.cpu.tick();
+/// ```
+#[derive(Debug)]
+pub struct Throttled<T> {
+    value: Value<T>,
+    interval: Duration,
+    last_change: Option<Instant>,
+    pending: Option<T>,
+}
+
+impl<T: AnyState + 'static> Throttled<T> {
+    pub fn new(value: T, interval: Duration) -> Self {
+        Self {
+            value: Value::new(value),
+            interval,
+            last_change: None,
+            pending: None,
+        }
+    }
+
+    /// Set the value, or hold it back if the value changed less than an interval ago
+    pub fn set(&mut self, value: T) {
+        self.set_at(value, Instant::now());
+    }
+
+    /// Set the value at a given point in time
+    pub fn set_at(&mut self, value: T, now: Instant) {
+        match self.last_change {
+            Some(last) if now.saturating_duration_since(last) < self.interval => self.pending = Some(value),
+            _ => self.apply(value, now),
+        }
+    }
+
+    /// Apply the latest change if the interval has passed.
+    /// Returns true if the value changed.
+    pub fn tick(&mut self) -> bool {
+        self.tick_at(Instant::now())
+    }
+
+    /// Apply the latest change if the interval has passed at a given point in time.
+    /// Returns true if the value changed.
+    pub fn tick_at(&mut self, now: Instant) -> bool {
+        let elapsed = self
+            .last_change
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval);
+        match self.pending.take() {
+            Some(value) if elapsed => {
+                self.apply(value, now);
+                true
+            }
+            pending => {
+                self.pending = pending;
+                false
+            }
+        }
+    }
+
+    /// Apply the latest change now, no matter the interval
+    pub fn flush(&mut self) {
+        if let Some(value) = self.pending.take() {
+            self.apply(value, Instant::now());
+        }
+    }
+
+    /// The latest change, if it's held back
+    pub fn pending(&self) -> Option<&T> {
+        self.pending.as_ref()
+    }
+
+    fn apply(&mut self, value: T, now: Instant) {
+        self.value.set(value);
+        self.last_change = Some(now);
+        self.pending = None;
+    }
+}
+
+impl<T> Deref for Throttled<T> {
+    type Target = Value<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// A [`Value`] that only changes once it has stopped changing for a while,
+/// e.g to filter a list once the user stops typing.
+///
+/// Every change is held back, and restarts the delay.
+/// The latest change is applied by [`Debounced::tick`] once the delay has passed.
+/// ```
+/// # use std::time::Duration;
+/// # extern crate anathema_state as anathema;
+/// # use anathema_state::*;
+/// #[derive(State)]
+/// struct Search {
+///     query: Debounced<String>,
+/// }
+///
+/// let mut search = Search { query: Debounced::new(String::new(), Duration::from_millis(300)) };
+/// search.query.set("ana".into());
+/// assert_eq!(*search.query.to_ref(), "");
+///
+/// // Once per frame, e.g from `Component::tick`
+/// search.query.tick();
+/// ```
+#[derive(Debug)]
+pub struct Debounced<T> {
+    value: Value<T>,
+    delay: Duration,
+    pending: Option<(T, Instant)>,
+}
+
+impl<T: AnyState + 'static> Debounced<T> {
+    pub fn new(value: T, delay: Duration) -> Self {
+        Self {
+            value: Value::new(value),
+            delay,
+            pending: None,
+        }
+    }
+
+    /// Hold the value back until there have been no changes for the delay
+    pub fn set(&mut self, value: T) {
+        self.set_at(value, Instant::now());
+    }
+
+    /// Set the value at a given point in time
+    pub fn set_at(&mut self, value: T, now: Instant) {
+        self.pending = Some((value, now));
+    }
+
+    /// Apply the latest change if there have been no changes for the delay.
+    /// Returns true if the value changed.
+    pub fn tick(&mut self) -> bool {
+        self.tick_at(Instant::now())
+    }
+
+    /// Apply the latest change if there have been no changes for the delay at a given point in time.
+    /// Returns true if the value changed.
+    pub fn tick_at(&mut self, now: Instant) -> bool {
+        match self.pending.take() {
+            Some((value, changed)) if now.saturating_duration_since(changed) >= self.delay => {
+                self.value.set(value);
+                true
+            }
+            pending => {
+                self.pending = pending;
+                false
+            }
+        }
+    }
+
+    /// Apply the latest change now, no matter the delay
+    pub fn flush(&mut self) {
+        if let Some((value, _)) = self.pending.take() {
+            self.value.set(value);
+        }
+    }
+
+    /// The latest change, if it's held back
+    pub fn pending(&self) -> Option<&T> {
+        self.pending.as_ref().map(|(value, _)| value)
+    }
+}
+
+impl<T> Deref for Debounced<T> {
+    type Target = Value<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn throttle() {
+        let start = Instant::now();
+        let mut value = Throttled::new(0, MS * 100);
+
+        value.set_at(1, start);
+        assert_eq!(value.copy_value(), 1);
+
+        value.set_at(2, start + MS * 10);
+        value.set_at(3, start + MS * 20);
+        assert_eq!(value.copy_value(), 1);
+        assert_eq!(value.pending(), Some(&3));
+
+        assert!(!value.tick_at(start + MS * 50));
+        assert!(value.tick_at(start + MS * 100));
+        assert_eq!(value.copy_value(), 3);
+        assert!(value.pending().is_none());
+
+        // The interval starts over from the last change
+        value.set_at(4, start + MS * 150);
+        assert_eq!(value.copy_value(), 3);
+        value.set_at(5, start + MS * 200);
+        assert_eq!(value.copy_value(), 5);
+    }
+
+    #[test]
+    fn debounce() {
+        let start = Instant::now();
+        let mut value = Debounced::new(0, MS * 100);
+
+        value.set_at(1, start);
+        value.set_at(2, start + MS * 80);
+        assert!(!value.tick_at(start + MS * 100));
+        assert_eq!(value.copy_value(), 0);
+
+        assert!(value.tick_at(start + MS * 180));
+        assert_eq!(value.copy_value(), 2);
+        assert!(!value.tick_at(start + MS * 300));
+
+        value.set_at(3, start + MS * 300);
+        value.flush();
+        assert_eq!(value.copy_value(), 3);
+    }
+}
@@ -0,0 +1,472 @@
+//! A rope for large, editable text.
+//!
+//! The text is stored in a binary tree of chunks, where every branch
+//! caches the number of chars and newlines underneath it.
+//! Edits and line lookups are `O(log n)` and only copy the chunks they touch.
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+// The largest chunk, in bytes, created by edits
+const MAX_CHUNK: usize = 1024;
+// The tree is rebuilt once it's deeper than this
+const MAX_DEPTH: usize = 48;
+
+/// Text stored as a rope, with line lookups.
+///
+/// Positions are char indices, and lines are separated by `\n`.
+///
+/// ```
+/// # use anathema_store::rope::Rope;
+/// let mut rope = Rope::from("hello\nworld");
+/// rope.insert(5, ", there");
+/// rope.remove(0..1);
+/// assert_eq!(rope.line(0), "ello, there");
+/// assert_eq!(rope.len_lines(), 2);
+/// assert_eq!(rope.line_to_char(1), 12);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Rope {
+    root: Node,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of chars
+    pub fn len_chars(&self) -> usize {
+        self.root.chars()
+    }
+
+    /// The number of lines, which is one more than the number of newlines
+    pub fn len_lines(&self) -> usize {
+        self.root.newlines() + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_chars() == 0
+    }
+
+    /// Insert text at the char index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is past the end of the text.
+    pub fn insert(&mut self, index: usize, text: &str) {
+        assert!(index <= self.len_chars(), "index out of bounds");
+        if text.is_empty() {
+            return;
+        }
+
+        self.root.insert(index, text);
+        if self.root.depth() > MAX_DEPTH {
+            self.rebuild();
+        }
+    }
+
+    /// Remove the chars in the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is past the end of the text.
+    pub fn remove(&mut self, range: Range<usize>) {
+        assert!(range.end <= self.len_chars(), "range out of bounds");
+        if range.is_empty() {
+            return;
+        }
+        self.root.remove(range);
+    }
+
+    /// The char index of the start of the line.
+    /// Lines past the last line start at the end of the text.
+    pub fn line_to_char(&self, line: usize) -> usize {
+        match line {
+            0 => 0,
+            _ if line >= self.len_lines() => self.len_chars(),
+            _ => self.root.line_to_char(line),
+        }
+    }
+
+    /// The line of the char index
+    pub fn char_to_line(&self, index: usize) -> usize {
+        self.root.char_to_line(index.min(self.len_chars()))
+    }
+
+    /// The text of the line, without the newline
+    pub fn line(&self, line: usize) -> String {
+        let start = self.line_to_char(line);
+        let mut end = self.line_to_char(line + 1);
+        if line + 1 < self.len_lines() {
+            end -= 1;
+        }
+        self.slice(start..end)
+    }
+
+    /// The number of chars in the line, without the newline
+    pub fn line_len(&self, line: usize) -> usize {
+        let start = self.line_to_char(line);
+        let end = self.line_to_char(line + 1);
+        match line + 1 < self.len_lines() {
+            true => end - start - 1,
+            false => end - start,
+        }
+    }
+
+    /// The text in the range of chars
+    pub fn slice(&self, range: Range<usize>) -> String {
+        let mut text = String::new();
+        let end = range.end.min(self.len_chars());
+        if range.start < end {
+            self.root.slice(range.start..end, &mut text);
+        }
+        text
+    }
+
+    /// The chunks of the text, in order
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        let mut chunks = vec![];
+        self.root.chunks(&mut chunks);
+        chunks.into_iter()
+    }
+
+    // Build a balanced tree from the chunks
+    fn rebuild(&mut self) {
+        let mut leaves = vec![];
+        std::mem::take(&mut self.root).into_leaves(&mut leaves);
+        self.root = Node::balanced(leaves);
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Self {
+            root: Node::from_str(text),
+        }
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Leaf {
+    text: String,
+    chars: usize,
+    newlines: usize,
+}
+
+impl Leaf {
+    fn new(text: String) -> Self {
+        Self {
+            chars: text.chars().count(),
+            newlines: text.matches('\n').count(),
+            text,
+        }
+    }
+
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Branch {
+    left: Node,
+    right: Node,
+    chars: usize,
+    newlines: usize,
+    depth: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(Leaf),
+    Branch(Box<Branch>),
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self::Leaf(Leaf::default())
+    }
+}
+
+impl Node {
+    fn branch(left: Node, right: Node) -> Self {
+        Self::Branch(Box::new(Branch {
+            chars: left.chars() + right.chars(),
+            newlines: left.newlines() + right.newlines(),
+            depth: left.depth().max(right.depth()) + 1,
+            left,
+            right,
+        }))
+    }
+
+    fn from_str(text: &str) -> Self {
+        let mut leaves = vec![];
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut end = rest.len().min(MAX_CHUNK);
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            leaves.push(Leaf::new(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        Self::balanced(leaves)
+    }
+
+    fn balanced(mut leaves: Vec<Leaf>) -> Self {
+        match leaves.len() {
+            0 => Self::default(),
+            1 => Self::Leaf(leaves.remove(0)),
+            len => {
+                let right = leaves.split_off(len / 2);
+                Self::branch(Self::balanced(leaves), Self::balanced(right))
+            }
+        }
+    }
+
+    fn into_leaves(self, leaves: &mut Vec<Leaf>) {
+        match self {
+            Self::Leaf(leaf) if leaf.text.is_empty() => {}
+            Self::Leaf(leaf) => leaves.push(leaf),
+            Self::Branch(branch) => {
+                branch.left.into_leaves(leaves);
+                branch.right.into_leaves(leaves);
+            }
+        }
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            Self::Leaf(leaf) => leaf.chars,
+            Self::Branch(branch) => branch.chars,
+        }
+    }
+
+    fn newlines(&self) -> usize {
+        match self {
+            Self::Leaf(leaf) => leaf.newlines,
+            Self::Branch(branch) => branch.newlines,
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Self::Leaf(_) => 0,
+            Self::Branch(branch) => branch.depth,
+        }
+    }
+
+    fn insert(&mut self, index: usize, text: &str) {
+        match self {
+            Self::Leaf(leaf) => {
+                let byte = leaf.byte_index(index);
+                if leaf.text.len() + text.len() <= MAX_CHUNK {
+                    leaf.text.insert_str(byte, text);
+                    leaf.chars += text.chars().count();
+                    leaf.newlines += text.matches('\n').count();
+                } else {
+                    // Only the chunk that is inserted into is copied
+                    let mut joined = String::with_capacity(leaf.text.len() + text.len());
+                    joined.push_str(&leaf.text[..byte]);
+                    joined.push_str(text);
+                    joined.push_str(&leaf.text[byte..]);
+                    *self = Self::from_str(&joined);
+                }
+            }
+            Self::Branch(branch) => {
+                let left = branch.left.chars();
+                match index <= left {
+                    true => branch.left.insert(index, text),
+                    false => branch.right.insert(index - left, text),
+                }
+                branch.chars += text.chars().count();
+                branch.newlines += text.matches('\n').count();
+                branch.depth = branch.left.depth().max(branch.right.depth()) + 1;
+            }
+        }
+    }
+
+    fn remove(&mut self, range: Range<usize>) {
+        match self {
+            Self::Leaf(leaf) => {
+                let start = leaf.byte_index(range.start);
+                let end = leaf.byte_index(range.end);
+                let removed = &leaf.text[start..end];
+                leaf.chars -= range.len();
+                leaf.newlines -= removed.matches('\n').count();
+                leaf.text.replace_range(start..end, "");
+            }
+            Self::Branch(branch) => {
+                let left = branch.left.chars();
+                if range.start < left {
+                    branch.left.remove(range.start..range.end.min(left));
+                }
+                if range.end > left {
+                    branch.right.remove(range.start.saturating_sub(left)..range.end - left);
+                }
+
+                let left = std::mem::take(&mut branch.left);
+                let right = std::mem::take(&mut branch.right);
+                *self = match (left, right) {
+                    (node, Self::Leaf(leaf)) | (Self::Leaf(leaf), node) if leaf.chars == 0 => node,
+                    // Merge small chunks
+                    (Self::Leaf(mut left), Self::Leaf(right)) if left.text.len() + right.text.len() <= MAX_CHUNK => {
+                        left.text.push_str(&right.text);
+                        Self::Leaf(Leaf::new(left.text))
+                    }
+                    (left, right) => Self::branch(left, right),
+                };
+            }
+        }
+    }
+
+    // The char index after the newline that ends the line before the given line
+    fn line_to_char(&self, line: usize) -> usize {
+        match self {
+            Self::Leaf(leaf) => leaf
+                .text
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '\n')
+                .nth(line - 1)
+                .map(|(i, _)| i + 1)
+                .unwrap_or(leaf.chars),
+            Self::Branch(branch) => match branch.left.newlines() >= line {
+                true => branch.left.line_to_char(line),
+                false => branch.left.chars() + branch.right.line_to_char(line - branch.left.newlines()),
+            },
+        }
+    }
+
+    fn char_to_line(&self, index: usize) -> usize {
+        match self {
+            Self::Leaf(leaf) => leaf.text.chars().take(index).filter(|c| *c == '\n').count(),
+            Self::Branch(branch) => {
+                let left = branch.left.chars();
+                match index <= left {
+                    true => branch.left.char_to_line(index),
+                    false => branch.left.newlines() + branch.right.char_to_line(index - left),
+                }
+            }
+        }
+    }
+
+    fn slice(&self, range: Range<usize>, text: &mut String) {
+        match self {
+            Self::Leaf(leaf) => {
+                let start = leaf.byte_index(range.start);
+                let end = leaf.byte_index(range.end);
+                text.push_str(&leaf.text[start..end]);
+            }
+            Self::Branch(branch) => {
+                let left = branch.left.chars();
+                if range.start < left {
+                    branch.left.slice(range.start..range.end.min(left), text);
+                }
+                if range.end > left {
+                    branch
+                        .right
+                        .slice(range.start.saturating_sub(left)..range.end - left, text);
+                }
+            }
+        }
+    }
+
+    fn chunks<'a>(&'a self, chunks: &mut Vec<&'a str>) {
+        match self {
+            Self::Leaf(leaf) => chunks.push(&leaf.text),
+            Self::Branch(branch) => {
+                branch.left.chunks(chunks);
+                branch.right.chunks(chunks);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Compare the rope to a string while applying the same edits
+    #[test]
+    fn edits_match_string() {
+        let line = "the quick brown fox jumps over the lazy dög\n";
+        let mut expected = line.repeat(200);
+        let mut rope = Rope::from(expected.as_str());
+
+        let mut seed = 7_usize;
+        let mut next = |max: usize| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % (max + 1)
+        };
+
+        for i in 0..2000 {
+            let len = expected.chars().count();
+            let index = next(len);
+            let byte = expected
+                .char_indices()
+                .nth(index)
+                .map(|(i, _)| i)
+                .unwrap_or(expected.len());
+            match i % 3 {
+                0 => {
+                    let end = (index + next(20)).min(len);
+                    let end_byte = expected
+                        .char_indices()
+                        .nth(end)
+                        .map(|(i, _)| i)
+                        .unwrap_or(expected.len());
+                    expected.replace_range(byte..end_byte, "");
+                    rope.remove(index..end);
+                }
+                _ => {
+                    let text = ["a", "\n", "ö", "hello\nworld"][next(3)];
+                    expected.insert_str(byte, text);
+                    rope.insert(index, text);
+                }
+            }
+        }
+
+        assert_eq!(rope.to_string(), expected);
+        assert_eq!(rope.len_chars(), expected.chars().count());
+
+        let lines = expected.split('\n').collect::<Vec<_>>();
+        assert_eq!(rope.len_lines(), lines.len());
+        let mut start = 0;
+        for (index, line) in lines.iter().enumerate() {
+            assert_eq!(rope.line(index), *line);
+            assert_eq!(rope.line_to_char(index), start);
+            assert_eq!(rope.char_to_line(start), index);
+            start += line.chars().count() + 1;
+        }
+    }
+
+    #[test]
+    fn large_insert() {
+        let mut rope = Rope::from("ab");
+        let text = "x".repeat(MAX_CHUNK * 10);
+        rope.insert(1, &text);
+        assert_eq!(rope.len_chars(), text.len() + 2);
+        assert_eq!(rope.slice(0..2), "ax");
+        assert!(rope.chunks().all(|chunk| chunk.len() <= MAX_CHUNK));
+    }
+
+    #[test]
+    fn stay_balanced() {
+        let mut rope = Rope::new();
+        for _ in 0..MAX_CHUNK * 20 {
+            rope.insert(rope.len_chars(), "a");
+        }
+        assert!(rope.root.depth() <= MAX_DEPTH);
+    }
+}
@@ -99,6 +99,18 @@ impl<T> Owned<T> {
         })
     }
 
+    /// Call closure on a value, if it exists and is neither
+    /// checked out nor shared.
+    pub fn try_with_mut<F, U>(&self, key: OwnedKey, f: F) -> Option<U>
+    where
+        F: FnOnce(&mut T) -> U,
+    {
+        match self.inner.borrow_mut().get_mut(key)? {
+            OwnedEntry::Occupied(val) => Some(f(val)),
+            OwnedEntry::Unique | OwnedEntry::Shared(_) => None,
+        }
+    }
+
     /// Get unique access to the value at a given key.
     ///
     /// # Panics
@@ -13,12 +13,30 @@ impl<'a, 'filter, T, Fil> TreeForEach<'a, 'filter, T, Fil> {
         Self { nodes, values, filter }
     }
 
+    /// The nodes, before they are filtered
+    pub fn nodes(&self) -> &[Node] {
+        self.nodes
+    }
+
+    pub fn values(&self) -> &TreeValues<T> {
+        self.values
+    }
+
+    /// Iterate over the same nodes with a different filter
+    pub fn with_filter<'b, F>(&'b mut self, filter: &'b F) -> TreeForEach<'b, 'b, T, F> {
+        TreeForEach {
+            nodes: self.nodes,
+            values: self.values,
+            filter,
+        }
+    }
+
     pub fn for_each<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut Fil::Output, TreeForEach<'_, '_, T, Fil>) -> ControlFlow<()>,
         Fil: TreeFilter<Input = T>,
     {
-        self.inner_for_each(&mut f);
+        let _ = self.inner_for_each(&mut f);
     }
 
     /// Apply to the first element that matches the filter
@@ -28,7 +46,7 @@ impl<'a, 'filter, T, Fil> TreeForEach<'a, 'filter, T, Fil> {
         Fil: TreeFilter<Input = T>,
     {
         for node in self.nodes {
-            self.values.with_mut(node.value(), |(_, value), values| {
+            let _ = self.values.with_mut(node.value(), |(_, value), values| {
                 let filter = self.filter.filter(node.value(), value, node.children(), values);
 
                 match filter {
@@ -303,7 +303,7 @@ impl<T> Tree<T> {
 
     /// Apply a [`NodeVisitor`], depth first
     pub fn apply_visitor<V: NodeVisitor<T>>(&mut self, visitor: &mut V) {
-        apply_visitor(&self.layout, &mut self.values, visitor);
+        let _ = apply_visitor(&self.layout, &mut self.values, visitor);
     }
 
     /// Split the tree giving access to the layout and the values.
@@ -447,7 +447,7 @@ impl Nodes {
     pub fn iter_with_values<'a, T>(
         &'a self,
         values: &'a TreeValues<T>,
-    ) -> impl Iterator<Item = (&Node, &Box<[u16]>, &T)> {
+    ) -> impl Iterator<Item = (&'a Node, &'a Box<[u16]>, &'a T)> {
         self.inner.iter().filter_map(|node| {
             let (path, value) = values.get(node.value)?;
             Some((node, path, value))
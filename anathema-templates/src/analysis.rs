@@ -0,0 +1,263 @@
+//! Information about a template for editor tooling, e.g a language server:
+//! the errors of a template and the symbols in it, with their locations.
+//!
+//! ```
+//! use anathema_templates::analysis::{diagnostics, Symbols, SymbolKind};
+//!
+//! let template = "border [width: 10]\n    @list";
+//! assert!(diagnostics(template).is_empty());
+//!
+//! let symbols = Symbols::new(template);
+//! let symbol = symbols.at(9).unwrap();
+//! assert_eq!(&*symbol.name, "width");
+//! assert_eq!(symbol.kind, SymbolKind::Attribute { element: "border".into() });
+//! assert_eq!(symbols.components().map(|s| &*s.name).collect::<Vec<_>>(), ["list"]);
+//! ```
+use std::ops::Range;
+use std::rc::Rc;
+
+use anathema_store::storage::strings::Strings;
+
+use crate::builder::parse;
+use crate::error::{src_line_no, Error};
+use crate::token::{Kind, Operator, Token, Value};
+use crate::Lexer;
+
+/// An error in a template.
+/// Lines and columns start at one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+}
+
+/// The errors of the template.
+/// Parsing stops at the first error, so there is at most one diagnostic.
+pub fn diagnostics(template: &str) -> Vec<Diagnostic> {
+    match parse(template) {
+        Ok(_) => vec![],
+        Err(Error::ParseError(err)) => vec![Diagnostic {
+            line: err.line,
+            col: err.col,
+            message: err.kind.message(),
+        }],
+        Err(err) => vec![Diagnostic {
+            line: 1,
+            col: 1,
+            message: err.to_string(),
+        }],
+    }
+}
+
+/// The line and column of the byte offset, both starting at one
+pub fn position(template: &str, offset: usize) -> (usize, usize) {
+    src_line_no(offset.min(template.len()), template)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolKind {
+    /// The name of an element, e.g `text`
+    Element,
+    /// The name of a component, e.g `list` in `@list`
+    Component,
+    /// A slot, e.g `body` in `$body`
+    Slot,
+    /// An attribute key, with the name of the element, component or condition it belongs to
+    Attribute { element: Rc<str> },
+    /// The binding of a declaration, e.g `x` in `let x = 1`
+    Declaration,
+    /// The binding of a loop, e.g `item` in `for item in items`
+    Binding,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: Rc<str>,
+    /// The byte range of the symbol in the template
+    pub span: Range<usize>,
+}
+
+/// The symbols of a template, in the order they are written.
+pub struct Symbols(Vec<Symbol>);
+
+impl Symbols {
+    /// The symbols of the template, up until the first token that can't be lexed.
+    pub fn new(template: &str) -> Self {
+        let mut strings = Strings::empty();
+        let tokens = Lexer::new(template, &mut strings)
+            .map_while(Result::ok)
+            .collect::<Vec<_>>();
+        Self(symbols(&tokens, &strings))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.0.iter()
+    }
+
+    /// The symbol at the byte offset, e.g for hover information
+    pub fn at(&self, offset: usize) -> Option<&Symbol> {
+        self.0.iter().find(|symbol| symbol.span.contains(&offset))
+    }
+
+    /// The components used by the template
+    pub fn components(&self) -> impl Iterator<Item = &Symbol> {
+        self.0.iter().filter(|symbol| symbol.kind == SymbolKind::Component)
+    }
+
+    /// The attributes of the element, component or condition
+    pub fn attributes<'a>(&'a self, element: &'a str) -> impl Iterator<Item = &'a Symbol> {
+        self.0
+            .iter()
+            .filter(move |symbol| matches!(&symbol.kind, SymbolKind::Attribute { element: e } if &**e == element))
+    }
+}
+
+fn symbols(tokens: &[Token], strings: &Strings) -> Vec<Symbol> {
+    let mut symbols = vec![];
+    // The element, component or condition of the current line
+    let mut owner: Option<Rc<str>> = None;
+    let mut line_start = true;
+    // The depth of brackets, braces and parens
+    let mut depth = 0usize;
+    // The depth of the attributes of the current line, if they are open
+    let mut attributes: Option<usize> = None;
+    let mut seen_attributes = false;
+    let mut prev = Kind::Newline;
+
+    let code = tokens
+        .iter()
+        .copied()
+        .filter(|Token(kind, _)| !matches!(kind, Kind::Indent(_)))
+        .collect::<Vec<_>>();
+
+    for (i, &Token(kind, pos)) in code.iter().enumerate() {
+        let next = code[i + 1..]
+            .iter()
+            .map(|token| token.0)
+            .find(|kind| !matches!(kind, Kind::Newline));
+
+        let first = line_start;
+        line_start = false;
+
+        match kind {
+            Kind::Newline if depth == 0 => {
+                line_start = true;
+                owner = None;
+                seen_attributes = false;
+            }
+            Kind::If if first || prev == Kind::Else => owner = Some("if".into()),
+            Kind::Else if first => owner = Some("else".into()),
+            Kind::Op(Operator::LBracket | Operator::LCurly | Operator::LParen) => {
+                // Attributes come first, so the first bracket of the line is the attributes
+                let bracket = matches!(kind, Kind::Op(Operator::LBracket));
+                if bracket && depth == 0 && owner.is_some() && !seen_attributes {
+                    attributes = Some(depth + 1);
+                    seen_attributes = true;
+                }
+                depth += 1;
+            }
+            Kind::Op(Operator::RBracket | Operator::RCurly | Operator::RParen) => {
+                if attributes == Some(depth) {
+                    attributes = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            Kind::Value(Value::Ident(id)) => {
+                let name: Rc<str> = strings.get_unchecked(id).into();
+                let symbol_kind = match prev {
+                    Kind::Component => Some(SymbolKind::Component),
+                    Kind::ComponentSlot => Some(SymbolKind::Slot),
+                    Kind::Decl => Some(SymbolKind::Declaration),
+                    Kind::For => Some(SymbolKind::Binding),
+                    // The index of `for item, i in items`
+                    Kind::Op(Operator::Comma) if i >= 3 && code[i - 3].0 == Kind::For => Some(SymbolKind::Binding),
+                    _ if first => Some(SymbolKind::Element),
+                    _ if attributes == Some(depth) && next == Some(Kind::Op(Operator::Colon)) => {
+                        owner.clone().map(|element| SymbolKind::Attribute { element })
+                    }
+                    _ => None,
+                };
+
+                if let Some(symbol_kind) = symbol_kind {
+                    if matches!(symbol_kind, SymbolKind::Element | SymbolKind::Component) {
+                        owner = Some(name.clone());
+                    }
+
+                    symbols.push(Symbol {
+                        kind: symbol_kind,
+                        span: pos..pos + name.len(),
+                        name,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        prev = kind;
+    }
+
+    symbols
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn symbols(template: &str) -> Vec<(SymbolKind, String)> {
+        Symbols::new(template)
+            .iter()
+            .map(|symbol| (symbol.kind.clone(), symbol.name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn template_symbols() {
+        let template = "
+let x = {a: 1}
+vstack [width: x.a, height: 2]
+    for item, i in [1, 2]
+        text [bold: true] [item]
+    if x [keep_alive: true]
+        @comp (click->on_click) [id: 1] {b: 2}
+            $body
+                text 'a'
+";
+        let attribute = |element: &str, name: &str| {
+            (
+                SymbolKind::Attribute {
+                    element: element.into(),
+                },
+                name.to_string(),
+            )
+        };
+
+        assert_eq!(
+            symbols(template),
+            [
+                (SymbolKind::Declaration, "x".to_string()),
+                (SymbolKind::Element, "vstack".into()),
+                attribute("vstack", "width"),
+                attribute("vstack", "height"),
+                (SymbolKind::Binding, "item".into()),
+                (SymbolKind::Binding, "i".into()),
+                (SymbolKind::Element, "text".into()),
+                attribute("text", "bold"),
+                attribute("if", "keep_alive"),
+                (SymbolKind::Component, "comp".into()),
+                attribute("comp", "id"),
+                (SymbolKind::Slot, "body".into()),
+                (SymbolKind::Element, "text".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostic() {
+        let errors = diagnostics("vstack\n    text [a 1]");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+        assert!(diagnostics("text 'a'").is_empty());
+    }
+}
@@ -17,6 +17,8 @@ pub struct Single {
 #[derive(Debug, Clone, PartialEq)]
 pub struct For {
     pub binding: Rc<str>,
+    /// The name of the loop index, e.g `i` in `for item, i in items`
+    pub index: Option<Rc<str>>,
     pub data: Expression,
     pub body: Vec<Blueprint>,
 }
@@ -31,12 +33,16 @@ pub struct ControlFlow {
 pub struct If {
     pub cond: Expression,
     pub body: Vec<Blueprint>,
+    /// Keep the body in the tree while the branch is hidden
+    pub keep_alive: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Else {
     pub cond: Option<Expression>,
     pub body: Vec<Blueprint>,
+    /// Keep the body in the tree while the branch is hidden
+    pub keep_alive: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
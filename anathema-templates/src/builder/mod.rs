@@ -0,0 +1,773 @@
+//! Build the widget tree in Rust instead of a template.
+//!
+//! The nodes are turned into the same [`Blueprint`]s as a template would compile to,
+//! when the document is compiled:
+//!
+//! ```
+//! use anathema_templates::builder::{component, for_each, path, when, widget};
+//! use anathema_templates::expressions::{greater_than, ident, num};
+//! use anathema_templates::Document;
+//!
+//! // border [foreground: "red"]
+//! //     vstack
+//! //         for item in items
+//! //             text item.name
+//! //         if count > 0
+//! //             text "count: " count
+//! //         else
+//! //             text "empty"
+//! //         @counter (increment->on_increment)
+//! let root = widget("border").attr("foreground", "red").child(
+//!     widget("vstack")
+//!         .child(for_each("item", path("items")).child(widget("text").value(path("item.name"))))
+//!         .child(
+//!             when(*greater_than(ident("count"), num(0)))
+//!                 .child(widget("text").values(["count: ".into(), path("count")]))
+//!                 .otherwise()
+//!                 .child(widget("text").value("empty")),
+//!         )
+//!         .child(component("counter").on("increment", "on_increment")),
+//! );
+//!
+//! let mut doc = Document::from_builder(root);
+//! doc.add_component("counter", "text 'counter'".to_string().into()).unwrap();
+//! assert!(doc.compile().is_ok());
+//! ```
+//!
+//! The nodes are also the syntax tree of a template: [`parse`] turns a template into nodes,
+//! and [`to_template`] turns nodes back into a template, so tools can generate,
+//! transform or lint templates:
+//!
+//! ```
+//! use anathema_templates::builder::{parse, to_template, Node};
+//!
+//! let mut nodes = parse("vstack\n    text [bold: true] 'hello'").unwrap();
+//! let Node::Widget(vstack) = &mut nodes[0] else { panic!() };
+//! vstack.ident = "hstack".into();
+//!
+//! assert_eq!(to_template(&nodes), "hstack\n    text [bold: true] \"hello\"\n");
+//! ```
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use anathema_store::smallmap::SmallMap;
+use anathema_store::storage::strings::Strings;
+
+pub use self::template::to_template;
+use crate::blueprints::{Blueprint, Component, ControlFlow, Else, For, If, Single};
+use crate::components::ComponentTemplates;
+use crate::error::{Error, Result};
+use crate::expressions::{eq, Expression};
+use crate::lexer::Lexer;
+use crate::statements::const_eval::const_eval;
+use crate::statements::parser::Parser;
+use crate::statements::{ast, Context, Statements};
+use crate::token::Tokens;
+
+mod template;
+
+/// A node of the tree: a widget, a loop, a condition, a match, a component,
+/// a slot, a declaration, a component definition or an include.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Widget(Widget),
+    For(Loop),
+    If(Conditional),
+    Match(Match),
+    Component(ComponentNode),
+    /// A slot of a component template, the same as `$name`
+    Slot(Rc<str>),
+    /// A global declaration, the same as `let name = value`
+    Let(Rc<str>, Expression),
+    /// A component defined in the template, the same as `component name [props]`
+    Definition(Definition),
+    /// A template file, the same as `@include "path"`
+    Include(Rc<str>),
+}
+
+impl Node {
+    pub(crate) fn build(&self, ctx: &mut Context<'_>, output: &mut Vec<Blueprint>) -> Result<()> {
+        let blueprint = match self {
+            Self::Widget(widget) => Blueprint::Single(Single {
+                ident: widget.ident.clone(),
+                children: build_all(&widget.children, ctx)?,
+                attributes: attributes(&widget.attributes, ctx),
+                value: widget.value.clone().map(|value| const_eval(value, ctx)),
+            }),
+            Self::For(node) => Blueprint::For(For {
+                binding: node.binding.clone(),
+                index: node.index.clone(),
+                data: const_eval(node.data.clone(), ctx),
+                body: build_all(&node.body, ctx)?,
+            }),
+            Self::If(node) => {
+                let mut branches = node.branches.iter();
+                let first = branches.next().expect("a condition always has one branch");
+                let if_node = If {
+                    cond: const_eval(first.cond.clone().unwrap_or(true.into()), ctx),
+                    body: build_body(&first.body, ctx)?,
+                    keep_alive: first.keep_alive,
+                };
+
+                let elses = branches
+                    .map(|branch| {
+                        Ok(Else {
+                            cond: branch.cond.clone().map(|cond| const_eval(cond, ctx)),
+                            body: build_body(&branch.body, ctx)?,
+                            keep_alive: branch.keep_alive,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+
+                Blueprint::ControlFlow(ControlFlow { if_node, elses })
+            }
+            Self::Match(node) => match node.branches.is_empty() {
+                true => return Err(Error::InvalidMatch),
+                false => return Node::If(node.conditional()).build(ctx, output),
+            },
+            Self::Component(node) => {
+                let parent = ctx.component_parent();
+                let id = ctx.components.insert_id(node.name.to_string());
+
+                let assoc_functions = node
+                    .assoc_functions
+                    .iter()
+                    .map(|(internal, external)| (ctx.strings.push(&**internal), ctx.strings.push(&**external)))
+                    .collect();
+
+                let mut slots = SmallMap::empty();
+                for (name, body) in &node.slots {
+                    let body = build_all(body, ctx)?;
+                    slots.set(ctx.strings.push(&**name), body);
+                }
+
+                let state = node.state.as_ref().map(|state| {
+                    let state = state.iter().map(|(k, v)| (k.clone(), const_eval(v.clone(), ctx)));
+                    Rc::new(state.collect())
+                });
+
+                if let Some(definition) = ctx.definition(&node.name) {
+                    let body = ctx.expand(definition, &attributes(&node.attributes, ctx), slots)?;
+                    output.extend(body);
+                    return Ok(());
+                }
+
+                Blueprint::Component(Component {
+                    id,
+                    body: ctx.load_component(id, slots)?,
+                    attributes: attributes(&node.attributes, ctx),
+                    state,
+                    assoc_functions,
+                    parent,
+                })
+            }
+            Self::Slot(name) => {
+                let id = ctx.strings.push(&**name);
+                if let Some(body) = ctx.slots.get(&id) {
+                    output.extend(body.iter().cloned());
+                }
+                return Ok(());
+            }
+            Self::Let(binding, value) => {
+                let value = const_eval(value.clone(), ctx);
+                ctx.globals.declare(binding.to_string(), value);
+                return Ok(());
+            }
+            Self::Include(path) => {
+                output.extend(ctx.include(path)?);
+                return Ok(());
+            }
+            Self::Definition(definition) => {
+                let props = definition
+                    .props
+                    .iter()
+                    .map(|(key, value)| (key.clone(), const_eval(value.clone(), ctx)))
+                    .collect();
+                let definition = Definition {
+                    name: definition.name.clone(),
+                    props,
+                    body: definition.body.clone(),
+                };
+                ctx.definitions.insert(definition.name.clone(), Rc::new(definition));
+                return Ok(());
+            }
+        };
+
+        output.push(blueprint);
+        Ok(())
+    }
+}
+
+pub(crate) fn build_all(nodes: &[Node], ctx: &mut Context<'_>) -> Result<Vec<Blueprint>> {
+    let mut output = vec![];
+    for node in nodes {
+        node.build(ctx, &mut output)?;
+    }
+    Ok(output)
+}
+
+// The body of a branch can't be empty, the same as in a template
+fn build_body(nodes: &[Node], ctx: &mut Context<'_>) -> Result<Vec<Blueprint>> {
+    let body = build_all(nodes, ctx)?;
+    match body.is_empty() {
+        true => Err(Error::EmptyBody),
+        false => Ok(body),
+    }
+}
+
+fn attributes(attributes: &[(Rc<str>, Expression)], ctx: &Context<'_>) -> SmallMap<Rc<str>, Expression> {
+    let mut map = SmallMap::empty();
+    for (key, value) in attributes {
+        map.set(key.clone(), const_eval(value.clone(), ctx));
+    }
+    map
+}
+
+/// A widget, e.g `text` or `vstack`. See [`widget`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Widget {
+    pub ident: Rc<str>,
+    /// The attributes, in the order they are written in the template
+    pub attributes: Vec<(Rc<str>, Expression)>,
+    pub value: Option<Expression>,
+    pub children: Vec<Node>,
+}
+
+impl Widget {
+    /// Set an attribute, e.g `attr("foreground", "red")`
+    pub fn attr(mut self, key: &str, value: impl Into<Expression>) -> Self {
+        self.attributes.retain(|(k, _)| &**k != key);
+        self.attributes.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the value, e.g the text of a `text` widget
+    pub fn value(mut self, value: impl Into<Expression>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Set multiple values, the same as `text "a" b "c"` in a template
+    pub fn values(self, values: impl IntoIterator<Item = Expression>) -> Self {
+        let values = values.into_iter().collect::<Vec<_>>();
+        self.value(Expression::List(values.into()))
+    }
+
+    /// Add a child
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    /// Add multiple children
+    pub fn children<N: Into<Node>>(mut self, children: impl IntoIterator<Item = N>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// A loop over a collection, the same as `for binding in data`. See [`for_each`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loop {
+    pub binding: Rc<str>,
+    /// The loop index, the same as `for binding, index in data`
+    pub index: Option<Rc<str>>,
+    pub data: Expression,
+    pub body: Vec<Node>,
+}
+
+impl Loop {
+    /// Bind the index of each value to `index`
+    pub fn index(mut self, index: &str) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    /// Add a child to the body of the loop
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.body.push(child.into());
+        self
+    }
+}
+
+/// A condition, the same as `if` followed by any number of `else`. See [`when`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conditional {
+    /// The first branch is the `if`, the rest are `else` branches
+    pub branches: Vec<Branch>,
+}
+
+/// A branch of a [`Conditional`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    /// The condition, which is only `None` for the last `else`
+    pub cond: Option<Expression>,
+    /// Keep the body in the tree while the branch is hidden
+    pub keep_alive: bool,
+    pub body: Vec<Node>,
+}
+
+impl Conditional {
+    fn branch(&mut self) -> &mut Branch {
+        self.branches.last_mut().expect("a condition always has one branch")
+    }
+
+    /// Add a child to the last branch
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.branch().body.push(child.into());
+        self
+    }
+
+    /// Start an `else if` branch
+    pub fn or_when(mut self, cond: impl Into<Expression>) -> Self {
+        self.branches.push(Branch {
+            cond: Some(cond.into()),
+            keep_alive: true,
+            body: vec![],
+        });
+        self
+    }
+
+    /// Start an `else` branch
+    pub fn otherwise(mut self) -> Self {
+        self.branches.push(Branch {
+            cond: None,
+            keep_alive: true,
+            body: vec![],
+        });
+        self
+    }
+
+    /// Set if the body of the last branch is kept in the tree while it's hidden.
+    /// Bodies are kept alive by default, `false` is the same as `if cond [keep_alive: false]`
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.branch().keep_alive = keep_alive;
+        self
+    }
+}
+
+/// A match, the same as `match value` followed by any number of `case` and a final `else`.
+/// See [`match_on`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub value: Expression,
+    /// The condition of a branch is the value of the `case`, and `None` for the `else`
+    pub branches: Vec<Branch>,
+}
+
+impl Match {
+    fn branch(&mut self) -> &mut Branch {
+        self.branches.last_mut().expect("a child is added to a case")
+    }
+
+    /// Add a child to the last case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no case.
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.branch().body.push(child.into());
+        self
+    }
+
+    /// Start a branch for the value
+    pub fn case(mut self, value: impl Into<Expression>) -> Self {
+        self.branches.push(Branch {
+            cond: Some(value.into()),
+            keep_alive: true,
+            body: vec![],
+        });
+        self
+    }
+
+    /// Start the branch used when no case is equal to the value
+    pub fn otherwise(mut self) -> Self {
+        self.branches.push(Branch {
+            cond: None,
+            keep_alive: true,
+            body: vec![],
+        });
+        self
+    }
+
+    /// Set if the body of the last case is kept in the tree while it's hidden.
+    /// Bodies are kept alive by default, `false` is the same as `case value [keep_alive: false]`
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.branch().keep_alive = keep_alive;
+        self
+    }
+
+    // The same condition as `if value == a` followed by `else if value == b`
+    fn conditional(&self) -> Conditional {
+        let branches = self
+            .branches
+            .iter()
+            .map(|branch| Branch {
+                cond: branch
+                    .cond
+                    .clone()
+                    .map(|case| *eq(self.value.clone().into(), case.into())),
+                keep_alive: branch.keep_alive,
+                body: branch.body.clone(),
+            })
+            .collect();
+        Conditional { branches }
+    }
+}
+
+/// A component added to the document with `Document::add_component`. See [`component`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentNode {
+    pub name: Rc<str>,
+    pub attributes: Vec<(Rc<str>, Expression)>,
+    pub state: Option<Rc<HashMap<Rc<str>, Expression>>>,
+    /// The events of the component and the functions of the parent they are associated with
+    pub assoc_functions: Vec<(Rc<str>, Rc<str>)>,
+    pub slots: Vec<(Rc<str>, Vec<Node>)>,
+}
+
+impl ComponentNode {
+    /// Set an attribute
+    pub fn attr(mut self, key: &str, value: impl Into<Expression>) -> Self {
+        self.attributes.retain(|(k, _)| &**k != key);
+        self.attributes.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the external state, the same as `@name { key: value }`
+    pub fn state(mut self, state: impl IntoIterator<Item = (&'static str, Expression)>) -> Self {
+        let state = state.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        self.state = Some(Rc::new(state));
+        self
+    }
+
+    /// Associate an event published by the component with a function of the parent,
+    /// the same as `@name (internal->external)`
+    pub fn on(mut self, internal: &str, external: &str) -> Self {
+        self.assoc_functions.push((internal.into(), external.into()));
+        self
+    }
+
+    /// Fill a slot of the component, the same as `$name` under the component in a template
+    pub fn slot<N: Into<Node>>(mut self, name: &str, children: impl IntoIterator<Item = N>) -> Self {
+        let children = children.into_iter().map(Into::into).collect();
+        self.slots.push((name.into(), children));
+        self
+    }
+}
+
+/// A component defined in the template, without any Rust code. See [`define`].
+///
+/// The body is built in place of each use of the component, e.g `@card [title: "a"]`,
+/// with the props replaced by the attributes of the component:
+///
+/// ```text
+/// component card [title: "untitled"]
+///     border
+///         vstack
+///             text title
+///             $body
+/// ```
+///
+/// A component can only be used after it's defined, in the same template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub name: Rc<str>,
+    /// The props and their defaults
+    pub props: Vec<(Rc<str>, Expression)>,
+    pub body: Vec<Node>,
+}
+
+impl Definition {
+    /// Declare a prop, with the value used when the component doesn't set it
+    pub fn prop(mut self, name: &str, default: impl Into<Expression>) -> Self {
+        self.props.retain(|(k, _)| &**k != name);
+        self.props.push((name.into(), default.into()));
+        self
+    }
+
+    /// Add a child to the body
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.body.push(child.into());
+        self
+    }
+}
+
+impl From<Widget> for Node {
+    fn from(value: Widget) -> Self {
+        Self::Widget(value)
+    }
+}
+
+impl From<Loop> for Node {
+    fn from(value: Loop) -> Self {
+        Self::For(value)
+    }
+}
+
+impl From<Conditional> for Node {
+    fn from(value: Conditional) -> Self {
+        Self::If(value)
+    }
+}
+
+impl From<Match> for Node {
+    fn from(value: Match) -> Self {
+        Self::Match(value)
+    }
+}
+
+impl From<ComponentNode> for Node {
+    fn from(value: ComponentNode) -> Self {
+        Self::Component(value)
+    }
+}
+
+impl From<Definition> for Node {
+    fn from(value: Definition) -> Self {
+        Self::Definition(value)
+    }
+}
+
+/// A widget
+pub fn widget(ident: &str) -> Widget {
+    Widget {
+        ident: ident.into(),
+        attributes: vec![],
+        value: None,
+        children: vec![],
+    }
+}
+
+/// Loop over `data`, with each value bound to `binding`
+pub fn for_each(binding: &str, data: impl Into<Expression>) -> Loop {
+    Loop {
+        binding: binding.into(),
+        index: None,
+        data: data.into(),
+        body: vec![],
+    }
+}
+
+/// Show the children while the condition is true
+pub fn when(cond: impl Into<Expression>) -> Conditional {
+    Conditional {
+        branches: vec![Branch {
+            cond: Some(cond.into()),
+            keep_alive: true,
+            body: vec![],
+        }],
+    }
+}
+
+/// Show the children of the first case equal to the value
+pub fn match_on(value: impl Into<Expression>) -> Match {
+    Match {
+        value: value.into(),
+        branches: vec![],
+    }
+}
+
+/// A component, by the name it was added to the document with
+pub fn component(name: &str) -> ComponentNode {
+    ComponentNode {
+        name: name.into(),
+        attributes: vec![],
+        state: None,
+        assoc_functions: vec![],
+        slots: vec![],
+    }
+}
+
+/// The slot of a component template, filled by the parent of the component
+pub fn slot(name: &str) -> Node {
+    Node::Slot(name.into())
+}
+
+/// Declare a global value
+pub fn declare(binding: &str, value: impl Into<Expression>) -> Node {
+    Node::Let(binding.into(), value.into())
+}
+
+/// Include a template file, relative to the template with the include
+pub fn include(path: &str) -> Node {
+    Node::Include(path.into())
+}
+
+/// Define a component in the template, used with [`component`]
+pub fn define(name: &str) -> Definition {
+    Definition {
+        name: name.into(),
+        props: vec![],
+        body: vec![],
+    }
+}
+
+/// A lookup, e.g `path("state.items")`, the same as `state.items` in a template
+pub fn path(path: &str) -> Expression {
+    let mut parts = path.split('.');
+    let root = Expression::Ident(parts.next().unwrap_or_default().into());
+    parts.fold(root, |lhs, key| {
+        Expression::Index(lhs.into(), Expression::Str(key.into()).into())
+    })
+}
+
+/// Parse a template into nodes, without compiling it.
+/// Components are not loaded, so the template can use components that don't exist.
+pub fn parse(template: &str) -> Result<Vec<Node>> {
+    let mut strings = Strings::empty();
+    let mut components = ComponentTemplates::new();
+
+    let tokens = Lexer::new(template, &mut strings).collect::<Result<Vec<_>>>()?;
+    let tokens = Tokens::new(tokens, template.len());
+    let parser = Parser::new(tokens, &mut strings, template, &mut components);
+    let statements = parser.collect::<Result<Statements>>()?;
+
+    ast::nodes(statements, &strings, &components)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::expressions::{ident, strlit};
+    use crate::{Document, ToSourceKind};
+
+    // The string ids of the associated functions depend on the order the strings are added,
+    // so they are replaced with the strings
+    fn compile(mut doc: Document) -> (Blueprint, Vec<(String, String)>) {
+        doc.add_component("comp", "text 'comp'\n$body".to_template()).unwrap();
+        let (mut blueprint, _) = doc.compile().unwrap();
+
+        let Blueprint::Single(root) = &mut blueprint else { panic!() };
+        let Some(Blueprint::Component(component)) = root.children.last_mut() else { panic!() };
+        let functions = component
+            .assoc_functions
+            .drain(..)
+            .map(|(a, b)| (doc.strings.get_unchecked(a), doc.strings.get_unchecked(b)))
+            .collect();
+
+        (blueprint, functions)
+    }
+
+    const TEMPLATE: &str = "
+vstack [width: 10]
+    for x in state.list
+        text x
+    if a
+        text 'a'
+    else if b
+        text 'b'
+    else
+        text 'c'
+    @comp (change->on_change)
+        $body
+            text 'slot'
+        ";
+
+    #[test]
+    fn same_as_template() {
+        let root = widget("vstack")
+            .attr("width", 10)
+            .child(for_each("x", path("state.list")).child(widget("text").value(*ident("x"))))
+            .child(
+                when(*ident("a"))
+                    .child(widget("text").value(*strlit("a")))
+                    .or_when(*ident("b"))
+                    .child(widget("text").value("b"))
+                    .otherwise()
+                    .child(widget("text").value("c")),
+            )
+            .child(
+                component("comp")
+                    .on("change", "on_change")
+                    .slot("body", [widget("text").value("slot")]),
+            );
+
+        assert_eq!(compile(Document::new(TEMPLATE)), compile(Document::from_builder(root)));
+    }
+
+    #[test]
+    fn parsed_same_as_template() {
+        let root = parse(TEMPLATE).unwrap().remove(0);
+        assert_eq!(compile(Document::new(TEMPLATE)), compile(Document::from_builder(root)));
+    }
+
+    #[test]
+    fn empty_branch() {
+        let mut doc = Document::from_builder(when(true));
+        assert!(doc.compile().is_err());
+    }
+
+    #[test]
+    fn declarations() {
+        let nodes = parse("let x = 1\ntext x\n$slot").unwrap();
+        assert_eq!(nodes[0], declare("x", 1));
+        assert_eq!(nodes[1], widget("text").value(*ident("x")).into());
+        assert_eq!(nodes[2], slot("slot"));
+    }
+
+    #[test]
+    fn definitions() {
+        let nodes = parse("component card [title: 'a']\n    text title").unwrap();
+        let card = define("card")
+            .prop("title", "a")
+            .child(widget("text").value(*ident("title")));
+        assert_eq!(nodes[0], card.into());
+    }
+
+    #[test]
+    fn match_cases() {
+        let nodes = parse("match state\n    case 'a'\n        text 'a'\n    else\n        text 'b'").unwrap();
+        let node = match_on(*ident("state"))
+            .case("a")
+            .child(widget("text").value("a"))
+            .otherwise()
+            .child(widget("text").value("b"));
+        assert_eq!(nodes[0], node.into());
+
+        let mut doc = Document::from_builder(match_on(*ident("state")));
+        assert!(matches!(doc.compile(), Err(Error::InvalidMatch)));
+    }
+
+    #[test]
+    fn template_round_trip() {
+        let template = "
+let x = -(1 + 2) * 3 - (4 - 5)
+component card [title: 'untitled']
+    border
+        text title
+        $body
+vstack [width: 10, height: a.b[0], key: state[\"key with spaces\"]]
+    text 'a' c \"it's\"
+    text [] [1]
+    text [] [1, -1] a
+    text [] [1, -1] -a
+    text !(a && b) || c >= f(1, 2.5) % 2
+    for x in state.list
+        text x
+    for x, i in 0..len - 1
+        text i
+    if a [keep_alive: false]
+        text 'a'
+    else if b != #ff00aa
+        text 'b'
+    else
+        $slot
+    match state.status
+        case 'loading' [keep_alive: false]
+            text 'loading'
+        case 1
+            text 'one'
+        else
+            text 'done'
+    @include \"header.aml\"
+    @comp (change->on_change) [x: 1] {list: [1, 2], name: 'x'}
+        $body
+            text 'slot'
+";
+        let nodes = parse(template).unwrap();
+        let output = to_template(&nodes);
+        assert_eq!(parse(&output).unwrap(), nodes);
+        assert_eq!(to_template(&parse(&output).unwrap()), output);
+    }
+}
@@ -0,0 +1,167 @@
+use std::rc::Rc;
+
+use super::{Branch, Node};
+use crate::expressions::Expression;
+use crate::statements::parser::{CASE, DEFINITION, INCLUDE, MATCH};
+
+const INDENT: usize = 4;
+
+/// Write the nodes as a template.
+///
+/// Parsing the template gives the same nodes, with the exception of
+/// the order of the keys of maps, which are sorted.
+pub fn to_template(nodes: &[Node]) -> String {
+    let mut output = String::new();
+    write_nodes(&mut output, nodes, 0);
+    output
+}
+
+fn write_nodes(output: &mut String, nodes: &[Node], level: usize) {
+    for node in nodes {
+        write_node(output, node, level);
+    }
+}
+
+fn write_node(output: &mut String, node: &Node, level: usize) {
+    line_start(output, level);
+
+    match node {
+        Node::Widget(widget) => {
+            output.push_str(&widget.ident);
+            write_attributes(output, &widget.attributes);
+            if let Some(value) = &widget.value {
+                write_value(output, value, widget.attributes.is_empty());
+            }
+            output.push('\n');
+            write_nodes(output, &widget.children, level + 1);
+        }
+        Node::For(node) => {
+            output.push_str("for ");
+            output.push_str(&node.binding);
+            if let Some(index) = &node.index {
+                output.push_str(", ");
+                output.push_str(index);
+            }
+            output.push_str(&format!(" in {}\n", node.data.to_template()));
+            write_nodes(output, &node.body, level + 1);
+        }
+        Node::If(node) => {
+            for (i, branch) in node.branches.iter().enumerate() {
+                if i > 0 {
+                    line_start(output, level);
+                }
+                write_branch(output, branch, i == 0, level);
+            }
+        }
+        Node::Match(node) => {
+            output.push_str(&format!("{MATCH} {}\n", node.value.to_template()));
+            for branch in &node.branches {
+                line_start(output, level + 1);
+                match &branch.cond {
+                    Some(case) => output.push_str(&format!("{CASE} {}", case.to_template())),
+                    None => output.push_str("else"),
+                }
+                if !branch.keep_alive {
+                    output.push_str(" [keep_alive: false]");
+                }
+                output.push('\n');
+                write_nodes(output, &branch.body, level + 2);
+            }
+        }
+        Node::Component(node) => {
+            output.push('@');
+            output.push_str(&node.name);
+
+            if !node.assoc_functions.is_empty() {
+                let functions = node
+                    .assoc_functions
+                    .iter()
+                    .map(|(internal, external)| format!("{internal}->{external}"))
+                    .collect::<Vec<_>>();
+                output.push_str(&format!(" ({})", functions.join(", ")));
+            }
+
+            write_attributes(output, &node.attributes);
+
+            if let Some(state) = &node.state {
+                output.push(' ');
+                output.push_str(&Expression::Map(state.clone()).to_template());
+            }
+            output.push('\n');
+
+            for (name, body) in &node.slots {
+                line_start(output, level + 1);
+                output.push_str(&format!("${name}\n"));
+                write_nodes(output, body, level + 2);
+            }
+        }
+        Node::Slot(name) => output.push_str(&format!("${name}\n")),
+        Node::Include(path) => {
+            output.push_str(&format!("@{INCLUDE} {}\n", Expression::Str(path.clone()).to_template()))
+        }
+        Node::Let(binding, value) => output.push_str(&format!("let {binding} = {}\n", value.to_template())),
+        Node::Definition(definition) => {
+            output.push_str(&format!("{DEFINITION} {}", definition.name));
+            write_attributes(output, &definition.props);
+            output.push('\n');
+            write_nodes(output, &definition.body, level + 1);
+        }
+    }
+}
+
+fn write_branch(output: &mut String, branch: &Branch, first: bool, level: usize) {
+    match (first, &branch.cond) {
+        (true, cond) => {
+            let cond = cond.clone().unwrap_or(true.into());
+            output.push_str(&format!("if {}", cond.to_template()));
+        }
+        (false, Some(cond)) => output.push_str(&format!("else if {}", cond.to_template())),
+        (false, None) => output.push_str("else"),
+    }
+
+    if !branch.keep_alive {
+        output.push_str(" [keep_alive: false]");
+    }
+    output.push('\n');
+
+    write_nodes(output, &branch.body, level + 1);
+}
+
+fn write_attributes(output: &mut String, attributes: &[(Rc<str>, Expression)]) {
+    if attributes.is_empty() {
+        return;
+    }
+
+    let attributes = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}: {}", value.to_template()))
+        .collect::<Vec<_>>();
+    output.push_str(&format!(" [{}]", attributes.join(", ")));
+}
+
+fn write_value(output: &mut String, value: &Expression, no_attributes: bool) {
+    // Multiple values are parsed as a list, which reads better than the list itself.
+    // Values that could continue the previous value (`a -b`, `a [0]`) have to be in a list.
+    let value = match value {
+        Expression::List(values) if values.len() > 1 => {
+            let values = values.iter().map(Expression::to_template).collect::<Vec<_>>();
+            match values[1..].iter().all(|v| !v.starts_with(['-', '[', '('])) {
+                true => values.join(" "),
+                false => value.to_template(),
+            }
+        }
+        value => value.to_template(),
+    };
+
+    // Without the empty attributes a list would be parsed as the attributes
+    if no_attributes && value.starts_with('[') {
+        output.push_str(" []");
+    }
+
+    output.push(' ');
+    output.push_str(&value);
+}
+
+fn line_start(output: &mut String, level: usize) {
+    output.extend(std::iter::repeat_n(' ', level * INDENT));
+}
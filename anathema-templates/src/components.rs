@@ -102,6 +102,8 @@ impl From<usize> for WidgetComponentId {
 pub(crate) struct ComponentTemplates {
     dependencies: Stack<WidgetComponentId>,
     components: Storage<WidgetComponentId, String, ComponentSource>,
+    // The files included by the templates, see `Context::include`
+    pub(crate) included: Vec<PathBuf>,
 }
 
 impl ComponentTemplates {
@@ -109,6 +111,7 @@ impl ComponentTemplates {
         Self {
             dependencies: Stack::empty(),
             components: Storage::empty(),
+            included: vec![],
         }
     }
 
@@ -121,6 +124,31 @@ impl ComponentTemplates {
         self.components.insert(ident, template)
     }
 
+    /// Replace the template of an existing component
+    pub(crate) fn replace(&mut self, ident: &str, template: ComponentSource) -> Option<WidgetComponentId> {
+        self.components.iter().find(|(_, (key, _))| key == ident)?;
+        Some(self.components.insert(ident, template))
+    }
+
+    // The components with a template, as opposed to the ones only referred to by a template
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.components
+            .iter()
+            .filter(|(_, (_, src))| !matches!(src, ComponentSource::Empty))
+            .map(|(_, (name, _))| name.as_str())
+    }
+
+    pub(crate) fn name(&self, id: WidgetComponentId) -> Option<&str> {
+        self.components.get(id).map(|(name, _)| name.as_str())
+    }
+
+    pub(crate) fn id(&self, name: &str) -> Option<WidgetComponentId> {
+        self.components
+            .iter()
+            .find(|(_, (key, _))| key == name)
+            .map(|(id, _)| id)
+    }
+
     pub(crate) fn load(
         &mut self,
         parent_id: WidgetComponentId,
@@ -136,12 +164,12 @@ impl ComponentTemplates {
 
         let ret = match self.components.remove(parent_id) {
             Some((key, component_src)) => {
-                let template = match &component_src {
-                    ComponentSource::File { template, .. } => template,
-                    ComponentSource::InMemory(template) => template,
+                let (template, path) = match &component_src {
+                    ComponentSource::File { template, path } => (template, Some(path)),
+                    ComponentSource::InMemory(template) => (template, None),
                     ComponentSource::Empty => return Err(Error::MissingComponent(key)),
                 };
-                let ret = self.compile(template, globals, slots, strings, parent_id);
+                let ret = self.compile(template, path, globals, slots, strings, parent_id);
                 // This will re-insert the component in the same location
                 // as it was removed from since nothing else has
                 // written to the component storage since the component
@@ -161,6 +189,7 @@ impl ComponentTemplates {
     fn compile(
         &mut self,
         template: &str,
+        path: Option<&PathBuf>,
         globals: &mut Variables,
         slots: SmallMap<StringId, Vec<Blueprint>>,
         strings: &mut Strings,
@@ -173,6 +202,9 @@ impl ComponentTemplates {
         let statements = parser.collect::<Result<Statements>>()?;
 
         let mut context = Context::new(globals, self, strings, slots, Some(parent));
+        if let Some(path) = path {
+            context.set_path(path);
+        }
 
         Scope::new(statements).eval(&mut context)
     }
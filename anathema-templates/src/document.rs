@@ -4,8 +4,9 @@ use std::path::PathBuf;
 use anathema_store::smallmap::SmallMap;
 use anathema_store::storage::strings::Strings;
 
-use crate::blueprints::Blueprint;
-use crate::components::{ComponentSource, ComponentTemplates, SourceKind};
+use crate::blueprints::{Blueprint, Single};
+use crate::builder::Node;
+use crate::components::{ComponentSource, ComponentTemplates, SourceKind, WidgetComponentId};
 use crate::error::{Error, Result};
 use crate::statements::eval::Scope;
 use crate::statements::parser::Parser;
@@ -14,16 +15,44 @@ use crate::token::Tokens;
 use crate::variables::Variables;
 use crate::{Globals, Lexer};
 
+// The widget wrapping overlays
+const OVERLAY: &str = "overlay";
+
+// The widget wrapping regions
+const REGION: &str = "region";
+
+/// The name of the layout given to [`Document::new`].
+pub const DEFAULT_LAYOUT: &str = "default";
+
 /// A document containing templates and components
 /// ```
 /// # use anathema_templates::Document;
 /// let mut doc = Document::new("text 'I am a widget'");
 /// ```
+///
+/// A document can have multiple named root layouts, and the active
+/// layout is the one used when compiling the document:
+/// ```
+/// # use anathema_templates::Document;
+/// let mut doc = Document::new("hstack\n    @sidebar\n    @main");
+/// doc.add_layout("zen", "@main");
+/// doc.set_layout("zen").unwrap();
+/// assert_eq!(doc.layout(), "zen");
+/// ```
 pub struct Document {
     template: String,
+    // The file of the template, if it was read from a file
+    path: Option<PathBuf>,
+    layouts: Vec<(String, String)>,
+    layout: Option<usize>,
+    overlays: Vec<(String, String)>,
+    compiled_overlays: Vec<(String, Blueprint)>,
+    regions: Vec<(String, String)>,
+    compiled_regions: Vec<(String, Blueprint)>,
     pub strings: Strings,
     globals: Variables,
     components: ComponentTemplates,
+    root: Option<Node>,
     pub hot_reload: bool,
 }
 
@@ -32,13 +61,38 @@ impl Document {
         let template = template.into();
         Self {
             template,
+            path: None,
+            layouts: vec![],
+            layout: None,
+            overlays: vec![],
+            compiled_overlays: vec![],
+            regions: vec![],
+            compiled_regions: vec![],
             strings: Strings::empty(),
             globals: Variables::default(),
             components: ComponentTemplates::new(),
+            root: None,
             hot_reload: true,
         }
     }
 
+    /// A document with the template in the file.
+    /// The file is read again when the templates are reloaded, see [`Document::reload_templates`].
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut doc = Self::new(read_to_string(&path)?);
+        doc.path = Some(path);
+        Ok(doc)
+    }
+
+    /// A document with a tree made with the [`builder`](crate::builder) instead of a template.
+    /// The tree is used in place of the template of [`DEFAULT_LAYOUT`].
+    pub fn from_builder(root: impl Into<Node>) -> Self {
+        let mut doc = Self::new("");
+        doc.root = Some(root.into());
+        doc
+    }
+
     #[allow(private_bounds)]
     pub fn add_component(&mut self, name: impl Into<String>, src: SourceKind) -> Result<usize> {
         let name = name.into();
@@ -55,36 +109,393 @@ impl Document {
         Ok(id.into())
     }
 
+    /// Replace the template of a component added with [`Document::add_component`].
+    /// The component keeps its id.
+    #[allow(private_bounds)]
+    pub fn replace_component(&mut self, name: &str, src: SourceKind) -> Result<usize> {
+        let component_src = match src {
+            SourceKind::Str(s) => ComponentSource::InMemory(s),
+            SourceKind::Path(path) => {
+                let template = read_to_string(&path)?;
+                ComponentSource::File { path, template }
+            }
+        };
+
+        match self.components.replace(name, component_src) {
+            Some(id) => Ok(id.into()),
+            None => Err(Error::MissingComponent(name.into())),
+        }
+    }
+
+    /// The name of a component added with [`Document::add_component`]
+    pub fn component_name(&self, id: WidgetComponentId) -> Option<&str> {
+        self.components.name(id)
+    }
+
+    /// The id of the component with the name
+    pub fn component_id(&self, name: &str) -> Option<WidgetComponentId> {
+        self.components.id(name)
+    }
+
+    /// The names of the components added with [`Document::add_component`],
+    /// e.g to find the components a template uses that don't exist
+    /// (see [`Symbols::components`](crate::analysis::Symbols::components)).
+    pub fn component_names(&self) -> impl Iterator<Item = &str> {
+        self.components.names()
+    }
+
+    /// Add a named root layout.
+    /// Adding a layout with an existing name replaces the template of that layout.
+    pub fn add_layout(&mut self, name: impl Into<String>, template: impl Into<String>) {
+        let name = name.into();
+        let template = template.into();
+
+        if name == DEFAULT_LAYOUT {
+            self.template = template;
+            return;
+        }
+
+        match self.layouts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, tpl)) => *tpl = template,
+            None => self.layouts.push((name, template)),
+        }
+    }
+
+    /// Select the layout to use the next time the document is compiled.
+    /// Use [`DEFAULT_LAYOUT`] to select the template given to [`Document::new`].
+    pub fn set_layout(&mut self, name: &str) -> Result<()> {
+        if name == DEFAULT_LAYOUT {
+            self.layout = None;
+            return Ok(());
+        }
+
+        match self.layouts.iter().position(|(n, _)| n == name) {
+            Some(index) => {
+                self.layout = Some(index);
+                Ok(())
+            }
+            None => Err(Error::MissingLayout(name.into())),
+        }
+    }
+
+    /// The name of the active layout
+    pub fn layout(&self) -> &str {
+        match self.layout {
+            Some(index) => &self.layouts[index].0,
+            None => DEFAULT_LAYOUT,
+        }
+    }
+
+    /// The names of all layouts, starting with [`DEFAULT_LAYOUT`]
+    pub fn layouts(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(DEFAULT_LAYOUT).chain(self.layouts.iter().map(|(name, _)| name.as_str()))
+    }
+
+    /// Add a named template that can be shown as a modal layer on top of the layout.
+    ///
+    /// If the root of the template isn't an `overlay` widget, the template is wrapped in one.
+    pub fn add_overlay(&mut self, name: impl Into<String>, template: impl Into<String>) {
+        let name = name.into();
+        let template = template.into();
+
+        match self.overlays.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, tpl)) => *tpl = template,
+            None => self.overlays.push((name, template)),
+        }
+    }
+
+    /// The overlays compiled by the last call to [`Document::compile`]
+    pub fn overlays(&self) -> &[(String, Blueprint)] {
+        &self.compiled_overlays
+    }
+
+    /// Add a named template that is shown in a fixed region of the screen,
+    /// next to the layout, e.g. a header that is independent of the layout.
+    ///
+    /// If the root of the template isn't a `region` widget, the template is wrapped in one.
+    pub fn add_region(&mut self, name: impl Into<String>, template: impl Into<String>) {
+        let name = name.into();
+        let template = template.into();
+
+        match self.regions.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, tpl)) => *tpl = template,
+            None => self.regions.push((name, template)),
+        }
+    }
+
+    /// The regions compiled by the last call to [`Document::compile`]
+    pub fn regions(&self) -> &[(String, Blueprint)] {
+        &self.compiled_regions
+    }
+
     pub fn compile(&mut self) -> Result<(Blueprint, Globals)> {
         self.strings = Strings::empty();
         self.globals = Variables::default();
+        self.compiled_overlays.clear();
+        self.compiled_regions.clear();
+        self.components.included.clear();
 
-        let tokens = Lexer::new(&self.template, &mut self.strings).collect::<Result<Vec<_>>>()?;
-        let tokens = Tokens::new(tokens, self.template.len());
-        let parser = Parser::new(tokens, &mut self.strings, &self.template, &mut self.components);
-
-        let statements = parser.collect::<Result<Statements>>()?;
+        let root = match (self.layout, &self.root) {
+            (None, Some(root)) => {
+                let mut context = Context::new(
+                    &mut self.globals,
+                    &mut self.components,
+                    &mut self.strings,
+                    SmallMap::empty(),
+                    None,
+                );
+                let mut blueprints = vec![];
+                root.build(&mut context, &mut blueprints)?;
+                match blueprints.is_empty() {
+                    true => return Err(Error::EmptyTemplate),
+                    false => blueprints.remove(0),
+                }
+            }
+            (layout, _) => {
+                let (template, path) = match layout {
+                    Some(index) => (&self.layouts[index].1, None),
+                    None => (&self.template, self.path.as_ref()),
+                };
 
-        let mut context = Context {
-            globals: &mut self.globals,
-            strings: &mut self.strings,
-            components: &mut self.components,
-            slots: SmallMap::empty(),
-            current_component_parent: None,
+                let mut blueprints = compile(
+                    template,
+                    path,
+                    &mut self.strings,
+                    &mut self.globals,
+                    &mut self.components,
+                )?;
+                if blueprints.is_empty() {
+                    return Err(Error::EmptyTemplate);
+                }
+                blueprints.remove(0)
+            }
         };
 
-        let mut blueprints = Scope::new(statements).eval(&mut context)?;
-        match blueprints.is_empty() {
-            true => Err(Error::EmptyTemplate),
-            false => Ok((blueprints.remove(0), self.globals.take().into())),
+        for (name, template) in &self.overlays {
+            let blueprints = compile(
+                template,
+                None,
+                &mut self.strings,
+                &mut self.globals,
+                &mut self.components,
+            )?;
+            self.compiled_overlays.push((name.clone(), wrap(blueprints, OVERLAY)?));
+        }
+
+        for (name, template) in &self.regions {
+            let blueprints = compile(
+                template,
+                None,
+                &mut self.strings,
+                &mut self.globals,
+                &mut self.components,
+            )?;
+            self.compiled_regions.push((name.clone(), wrap(blueprints, REGION)?));
         }
+
+        Ok((root, self.globals.take().into()))
     }
 
+    /// The files of the templates: the template of the document, the components
+    /// and the files included by the last call to [`Document::compile`]
     pub fn template_paths(&self) -> impl Iterator<Item = &PathBuf> {
-        self.components.file_paths()
+        self.path
+            .iter()
+            .chain(self.components.file_paths())
+            .chain(self.components.included.iter())
     }
 
+    /// Read the templates from the files again.
+    /// Included files are read when the document is compiled.
     pub fn reload_templates(&mut self) -> Result<()> {
+        if let Some(path) = &self.path {
+            self.template = read_to_string(path)?;
+        }
         self.components.reload()
     }
 }
+
+// Wrap the blueprints in a widget, unless the widget is the only root
+fn wrap(mut blueprints: Vec<Blueprint>, ident: &str) -> Result<Blueprint> {
+    let blueprint = match &blueprints[..] {
+        [] => return Err(Error::EmptyTemplate),
+        [Blueprint::Single(single)] if &*single.ident == ident => blueprints.remove(0),
+        _ => Blueprint::Single(Single {
+            ident: ident.into(),
+            children: blueprints,
+            attributes: SmallMap::empty(),
+            value: None,
+        }),
+    };
+    Ok(blueprint)
+}
+
+fn compile(
+    template: &str,
+    path: Option<&PathBuf>,
+    strings: &mut Strings,
+    globals: &mut Variables,
+    components: &mut ComponentTemplates,
+) -> Result<Vec<Blueprint>> {
+    let tokens = Lexer::new(template, strings).collect::<Result<Vec<_>>>()?;
+    let tokens = Tokens::new(tokens, template.len());
+    let parser = Parser::new(tokens, strings, template, components);
+
+    let statements = parser.collect::<Result<Statements>>()?;
+
+    let mut context = Context::new(globals, components, strings, SmallMap::empty(), None);
+    if let Some(path) = path {
+        context.set_path(path);
+    }
+
+    Scope::new(statements).eval(&mut context)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ToSourceKind;
+
+    #[test]
+    fn switch_layout() {
+        let mut doc = Document::new("text 'a'");
+        doc.add_layout("wide", "hstack");
+        assert!(doc.compile().is_ok());
+
+        doc.set_layout("wide").unwrap();
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Single(single) = blueprint else { panic!() };
+        assert_eq!(&*single.ident, "hstack");
+
+        doc.set_layout(DEFAULT_LAYOUT).unwrap();
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Single(single) = blueprint else { panic!() };
+        assert_eq!(&*single.ident, "text");
+
+        assert!(doc.set_layout("zen").is_err());
+        assert_eq!(doc.layouts().collect::<Vec<_>>(), [DEFAULT_LAYOUT, "wide"]);
+    }
+
+    #[test]
+    fn wrap_overlays() {
+        let mut doc = Document::new("text 'a'");
+        doc.add_overlay("modal", "text 'b'");
+        doc.add_overlay("dialog", "overlay [dim: false]\n    text 'c'");
+        doc.compile().unwrap();
+
+        let overlays = doc.overlays();
+        assert_eq!(overlays.len(), 2);
+        for (_, blueprint) in overlays {
+            let Blueprint::Single(single) = blueprint else { panic!() };
+            assert_eq!(&*single.ident, OVERLAY);
+            assert_eq!(single.children.len(), 1);
+        }
+    }
+
+    #[test]
+    fn wrap_regions() {
+        let mut doc = Document::new("text 'a'");
+        doc.add_region("header", "text 'b'");
+        doc.add_region("footer", "region [y: 9]\n    text 'c'");
+        doc.compile().unwrap();
+
+        let regions = doc.regions();
+        assert_eq!(regions.len(), 2);
+        for (_, blueprint) in regions {
+            let Blueprint::Single(single) = blueprint else { panic!() };
+            assert_eq!(&*single.ident, REGION);
+            assert_eq!(single.children.len(), 1);
+        }
+    }
+
+    #[test]
+    fn replace_component() {
+        let mut doc = Document::new("@main");
+        let id = doc.add_component("main", "text 'a'".to_template()).unwrap();
+        doc.compile().unwrap();
+
+        assert_eq!(doc.replace_component("main", "hstack".to_template()).unwrap(), id);
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Component(component) = blueprint else { panic!() };
+        let Blueprint::Single(single) = &component.body[0] else { panic!() };
+        assert_eq!(&*single.ident, "hstack");
+
+        assert!(doc.replace_component("sidebar", "text 'b'".to_template()).is_err());
+        assert_eq!(doc.component_name(id.into()), Some("main"));
+        assert_eq!(doc.component_id("main"), Some(id.into()));
+        assert_eq!(doc.component_id("sidebar"), None);
+    }
+
+    // Write the templates to a new directory in the temp dir
+    fn template_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("anathema-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (path, template) in files {
+            std::fs::write(dir.join(path), template).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn include_templates() {
+        // The include in `a.aml` is relative to `a.aml`
+        let dir = template_dir(
+            "include",
+            &[("a.aml", "text 'a'\n@include \"b.aml\""), ("b.aml", "text 'b'")],
+        );
+        let template = format!("vstack\n    @include \"{}\"", dir.join("a.aml").display());
+
+        let mut doc = Document::new(template);
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Single(vstack) = blueprint else { panic!() };
+        assert_eq!(vstack.children.len(), 2);
+    }
+
+    #[test]
+    fn circular_include() {
+        let dir = template_dir(
+            "circular-include",
+            &[("a.aml", "@include \"b.aml\""), ("b.aml", "@include \"a.aml\"")],
+        );
+        let a = dir.join("a.aml");
+
+        let mut doc = Document::new(format!("@include \"{}\"", a.display()));
+        let Err(Error::Include(path, err)) = doc.compile() else { panic!() };
+        assert_eq!(path, a);
+        let Error::Include(path, err) = *err else { panic!() };
+        assert_eq!(path.file_name(), Some("b.aml".as_ref()));
+        assert!(matches!(*err, Error::CircularDependency));
+    }
+
+    #[test]
+    fn include_error_in_file() {
+        let dir = template_dir("include-error", &[("a.aml", "text 'a'\ntext 'b")]);
+        let a = dir.join("a.aml");
+
+        let mut doc = Document::new(format!("vstack\n    @include \"{}\"", a.display()));
+        let Err(Error::Include(path, err)) = doc.compile() else { panic!() };
+        assert_eq!(path, a);
+        // The line is the line in the included file
+        let Error::ParseError(err) = *err else { panic!() };
+        assert_eq!(err.line, 2);
+        assert_eq!(err.src, "text 'a'\ntext 'b");
+    }
+
+    #[test]
+    fn reload_template_from_path() {
+        let dir = template_dir("reload", &[("main.aml", "@include \"a.aml\""), ("a.aml", "text 'a'")]);
+        let main = dir.join("main.aml");
+
+        let mut doc = Document::from_path(&main).unwrap();
+        doc.compile().unwrap();
+        let paths = doc.template_paths().map(|p| p.file_name().unwrap()).collect::<Vec<_>>();
+        assert_eq!(paths, ["main.aml", "a.aml"]);
+
+        std::fs::write(&main, "hstack").unwrap();
+        doc.reload_templates().unwrap();
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Single(single) = blueprint else { panic!() };
+        assert_eq!(&*single.ident, "hstack");
+        assert_eq!(doc.template_paths().count(), 1);
+    }
+}
@@ -1,5 +1,6 @@
 use std::error::Error as StdError;
 use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
 
 pub(crate) use self::parse::{src_line_no, ParseError, ParseErrorKind};
 
@@ -12,8 +13,17 @@ pub enum Error {
     ParseError(ParseError),
     CircularDependency,
     MissingComponent(String),
+    MissingLayout(String),
+    /// A prop that isn't declared by the component defined in the template
+    InvalidProperty(String, String),
+    /// An error in a template included with `@include`
+    Include(PathBuf, Box<Error>),
     EmptyTemplate,
     EmptyBody,
+    /// A `case` outside of a `match`, or a `match` with something other than `case` and `else`
+    InvalidMatch,
+    /// `keep_alive` on an `if`, `else` or `case` is not `true` or `false`
+    InvalidKeepAlive,
     Io(std::io::Error),
 }
 
@@ -23,8 +33,13 @@ impl Display for Error {
             Error::ParseError(err) => write!(f, "{err}"),
             Error::CircularDependency => write!(f, "circular dependency"),
             Error::MissingComponent(name) => write!(f, "`@{name}` is not a registered component"),
+            Error::MissingLayout(name) => write!(f, "`{name}` is not a layout"),
+            Error::InvalidProperty(name, prop) => write!(f, "`@{name}` has no prop `{prop}`"),
+            Error::Include(path, err) => write!(f, "in `{}`: {err}", path.display()),
             Error::EmptyTemplate => write!(f, "empty template"),
-            Error::EmptyBody => write!(f, "if or else node has no children"),
+            Error::EmptyBody => write!(f, "if, else or case node has no children"),
+            Error::InvalidMatch => write!(f, "a match can only contain case and else nodes"),
+            Error::InvalidKeepAlive => write!(f, "`keep_alive` has to be either `true` or `false`"),
             Error::Io(err) => write!(f, "{err}"),
         }
     }
@@ -48,25 +48,7 @@ impl Display for ParseError {
         let start_line = self.line;
         let lines = self.src.lines().enumerate().skip(start_line.saturating_sub(2)).take(3);
 
-        let msg = match &self.kind {
-            ParseErrorKind::UnterminatedString => "unterminated string".into(),
-            ParseErrorKind::UnterminatedAttributes => "unterminated attributes (missing `]`)".into(),
-            ParseErrorKind::UnterminatedAssociation => "unterminated association (missing `)`)".into(),
-            ParseErrorKind::UnterminatedElement => "unterminated element".into(),
-            ParseErrorKind::InvalidToken { expected } => {
-                format!("invalid token (expected: \"{expected}\")")
-            }
-            ParseErrorKind::InvalidNumber => "invalid number".into(),
-            ParseErrorKind::InvalidIndex => "invalid index".into(),
-            ParseErrorKind::InvalidPath => "invalid path".into(),
-            ParseErrorKind::InvalidHexValue => "invalid hex value".into(),
-            ParseErrorKind::UnexpectedEof => "unexpected end of file".into(),
-            ParseErrorKind::TrailingPipe => "trailing pipe character".into(),
-            ParseErrorKind::InvalidDedent => "dedent does not match previous indentation levels".into(),
-            ParseErrorKind::InvalidOperator(_op) => "invalid operator: {op}".into(),
-            ParseErrorKind::UnexpectedToken(_msg) => "unexpected token: {msg}".into(),
-            ParseErrorKind::InvalidKey => todo!(),
-        };
+        let msg = self.kind.message();
 
         writeln!(f, "error on line {start_line}: {msg}")?;
 
@@ -107,3 +89,28 @@ pub enum ParseErrorKind {
     UnexpectedToken(String),
     InvalidKey,
 }
+
+impl ParseErrorKind {
+    /// The description of the error, without the location
+    pub fn message(&self) -> String {
+        match self {
+            ParseErrorKind::UnterminatedString => "unterminated string".into(),
+            ParseErrorKind::UnterminatedAttributes => "unterminated attributes (missing `]`)".into(),
+            ParseErrorKind::UnterminatedAssociation => "unterminated association (missing `)`)".into(),
+            ParseErrorKind::UnterminatedElement => "unterminated element".into(),
+            ParseErrorKind::InvalidToken { expected } => {
+                format!("invalid token (expected: \"{expected}\")")
+            }
+            ParseErrorKind::InvalidNumber => "invalid number".into(),
+            ParseErrorKind::InvalidIndex => "invalid index".into(),
+            ParseErrorKind::InvalidPath => "invalid path".into(),
+            ParseErrorKind::InvalidHexValue => "invalid hex value".into(),
+            ParseErrorKind::UnexpectedEof => "unexpected end of file".into(),
+            ParseErrorKind::TrailingPipe => "trailing pipe character".into(),
+            ParseErrorKind::InvalidDedent => "dedent does not match previous indentation levels".into(),
+            ParseErrorKind::InvalidOperator(op) => format!("invalid operator: {op}"),
+            ParseErrorKind::UnexpectedToken(msg) => format!("unexpected token: {msg}"),
+            ParseErrorKind::InvalidKey => "invalid map key".into(),
+        }
+    }
+}
@@ -72,6 +72,10 @@ pub fn eval(expr: Expr, strings: &Strings) -> Result<Expression, ParseErrorKind>
                 };
                 Expression::Equality(eval(*lhs, strings)?.into(), eval(*rhs, strings)?.into(), equality)
             }
+            Operator::Range => Expression::Range(eval(*lhs, strings)?.into(), eval(*rhs, strings)?.into()),
+            Operator::QuestionQuestion => {
+                Expression::Coalesce(eval(*lhs, strings)?.into(), eval(*rhs, strings)?.into())
+            }
             _ => return Err(ParseErrorKind::InvalidToken { expected: "" }),
         },
         Expr::Unary { op, expr } => {
@@ -105,6 +109,11 @@ pub fn eval(expr: Expr, strings: &Strings) -> Result<Expression, ParseErrorKind>
 
             Expression::Map(inner.into())
         }
+        Expr::Conditional { cond, then, otherwise } => Expression::Conditional(
+            eval(*cond, strings)?.into(),
+            eval(*then, strings)?.into(),
+            eval(*otherwise, strings)?.into(),
+        ),
         Expr::Call { fun, args } => {
             let args = args
                 .into_iter()
@@ -228,6 +237,16 @@ mod test {
         assert_eq!(expr.to_string(), "a + b * c");
     }
 
+    #[test]
+    fn range() {
+        let expr = eval_src("0..10");
+        assert_eq!(expr.to_string(), "0..10");
+
+        let expr = eval_src("a..b + 1");
+        assert_eq!(expr.to_string(), "a..b + 1");
+        assert_eq!(expr.to_template(), "a..b + 1");
+    }
+
     #[test]
     fn multiplication() {
         let expr = eval_src("2 * 2");
@@ -327,6 +346,25 @@ mod test {
         assert_eq!(expr.to_string(), "true || true");
     }
 
+    #[test]
+    fn conditional() {
+        let expr = eval_src("a > 1 ? 'many' : 'one'");
+        assert_eq!(expr.to_string(), "a > 1 ? many : one");
+        assert_eq!(expr.to_template(), "a > 1 ? \"many\" : \"one\"");
+
+        let expr = eval_src("(a ? b : c) ? d : e ? f : g");
+        assert_eq!(expr.to_template(), "(a ? b : c) ? d : e ? f : g");
+    }
+
+    #[test]
+    fn coalesce() {
+        let expr = eval_src("user.name ?? 'anonymous'");
+        assert_eq!(expr.to_string(), "user[name] ?? anonymous");
+
+        let expr = eval_src("(a ?? 1) + 2");
+        assert_eq!(expr.to_template(), "(a ?? 1) + 2");
+    }
+
     #[test]
     fn list() {
         let expr = eval_src("[1, 2, 3]");
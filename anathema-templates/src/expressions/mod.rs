@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use std::rc::Rc;
 
+use self::parser::prec;
 use crate::primitives::Primitive;
 
 pub(crate) mod eval;
@@ -42,6 +43,10 @@ pub enum Expression {
 
     // Conditionals
     Equality(Box<Self>, Box<Self>, Equality),
+    // `cond ? then : otherwise`
+    Conditional(Box<Self>, Box<Self>, Box<Self>),
+    // `value ?? fallback`, the fallback is used if the value is missing
+    Coalesce(Box<Self>, Box<Self>),
 
     // Lookup
     Ident(Rc<str>),
@@ -50,6 +55,9 @@ pub enum Expression {
     // Operations
     Op(Box<Self>, Box<Self>, Op),
 
+    // Range of integers, excluding the end
+    Range(Box<Self>, Box<Self>),
+
     // Function call
     Call { fun: Box<Self>, args: Box<[Self]> },
 }
@@ -121,6 +129,9 @@ impl Display for Expression {
                 };
                 write!(f, "{lhs} {equality} {rhs}")
             }
+            Self::Conditional(cond, then, otherwise) => write!(f, "{cond} ? {then} : {otherwise}"),
+            Self::Coalesce(value, fallback) => write!(f, "{value} ?? {fallback}"),
+            Self::Range(start, end) => write!(f, "{start}..{end}"),
             Self::Call { fun, args } => {
                 write!(
                     f,
@@ -132,6 +143,173 @@ impl Display for Expression {
     }
 }
 
+impl Expression {
+    /// The expression as it would be written in a template.
+    /// Unlike the `Display` output this can be parsed back into the same expression.
+    pub fn to_template(&self) -> String {
+        let mut output = String::new();
+        self.write_template(&mut output, prec::INITIAL);
+        output
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Op(_, _, Op::Add | Op::Sub) => prec::SUM,
+            Self::Op(_, _, Op::Mul | Op::Div | Op::Mod) => prec::PRODUCT,
+            Self::Equality(_, _, Equality::Eq | Equality::NotEq) => prec::EQUALITY,
+            Self::Equality(_, _, Equality::Or) => prec::OR,
+            Self::Equality(_, _, Equality::And) => prec::AND,
+            Self::Equality(..) => prec::LOGICAL,
+            Self::Conditional(..) => prec::CONDITIONAL,
+            Self::Coalesce(..) => prec::COALESCE,
+            Self::Range(..) => prec::RANGE,
+            Self::Not(_) | Self::Negative(_) => prec::PREFIX,
+            Self::Call { .. } => prec::CALL,
+            _ => prec::SUBCRIPT,
+        }
+    }
+
+    // Parenthesise the expression if it binds less tightly than `precedence`
+    fn write_template(&self, output: &mut String, precedence: u8) {
+        let parens = self.precedence() < precedence;
+        if parens {
+            output.push('(');
+        }
+
+        match self {
+            Self::Primitive(Primitive::Char(c)) => write_str(output, &c.to_string()),
+            Self::Primitive(Primitive::Float(f)) => output.push_str(&format!("{f:?}")),
+            Self::Primitive(Primitive::Hex(hex)) => {
+                output.push_str(&format!("#{:02x}{:02x}{:02x}", hex.r, hex.g, hex.b))
+            }
+            Self::Primitive(val) => output.push_str(&val.to_string()),
+            Self::Str(s) => write_str(output, s),
+            Self::Ident(ident) => output.push_str(ident),
+            Self::Index(lhs, index) => {
+                lhs.write_template(output, prec::SUBCRIPT);
+                match &**index {
+                    Self::Str(key) if is_ident(key) => {
+                        output.push('.');
+                        output.push_str(key);
+                    }
+                    index => {
+                        output.push('[');
+                        index.write_template(output, prec::INITIAL);
+                        output.push(']');
+                    }
+                }
+            }
+            Self::Not(expr) | Self::Negative(expr) => {
+                output.push(if let Self::Not(_) = self { '!' } else { '-' });
+                expr.write_template(output, prec::PREFIX);
+            }
+            Self::Op(lhs, rhs, op) => {
+                let op = match op {
+                    Op::Add => " + ",
+                    Op::Sub => " - ",
+                    Op::Div => " / ",
+                    Op::Mul => " * ",
+                    Op::Mod => " % ",
+                };
+                self.write_binary(output, lhs, rhs, op);
+            }
+            Self::Equality(lhs, rhs, equality) => {
+                let equality = match equality {
+                    Equality::Eq => " == ",
+                    Equality::NotEq => " != ",
+                    Equality::And => " && ",
+                    Equality::Or => " || ",
+                    Equality::Gt => " > ",
+                    Equality::Gte => " >= ",
+                    Equality::Lt => " < ",
+                    Equality::Lte => " <= ",
+                };
+                self.write_binary(output, lhs, rhs, equality);
+            }
+            Self::Conditional(cond, then, otherwise) => {
+                // Right associative, so only the condition is parenthesised if it's a conditional
+                cond.write_template(output, prec::CONDITIONAL + 1);
+                output.push_str(" ? ");
+                then.write_template(output, prec::INITIAL);
+                output.push_str(" : ");
+                otherwise.write_template(output, prec::CONDITIONAL);
+            }
+            Self::Coalesce(value, fallback) => self.write_binary(output, value, fallback, " ?? "),
+            Self::Range(start, end) => self.write_binary(output, start, end, ".."),
+            Self::List(list) => {
+                output.push('[');
+                write_list(output, list.iter());
+                output.push(']');
+            }
+            Self::Map(map) => {
+                // Sorted, as the order of a map isn't kept
+                let mut entries = map.iter().collect::<Vec<_>>();
+                entries.sort_by_key(|(key, _)| *key);
+
+                output.push('{');
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        output.push_str(", ");
+                    }
+                    match is_ident(key) {
+                        true => output.push_str(key),
+                        false => write_str(output, key),
+                    }
+                    output.push_str(": ");
+                    value.write_template(output, prec::INITIAL);
+                }
+                output.push('}');
+            }
+            Self::Call { fun, args } => {
+                fun.write_template(output, prec::CALL);
+                output.push('(');
+                write_list(output, args.iter());
+                output.push(')');
+            }
+        }
+
+        if parens {
+            output.push(')');
+        }
+    }
+
+    // Operators are left associative, so the right hand side is parenthesised
+    // if it has the same precedence
+    fn write_binary(&self, output: &mut String, lhs: &Self, rhs: &Self, op: &str) {
+        let precedence = self.precedence();
+        lhs.write_template(output, precedence);
+        output.push_str(op);
+        rhs.write_template(output, precedence + 1);
+    }
+}
+
+fn write_list<'a>(output: &mut String, values: impl Iterator<Item = &'a Expression>) {
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            output.push_str(", ");
+        }
+        value.write_template(output, prec::INITIAL);
+    }
+}
+
+fn write_str(output: &mut String, s: &str) {
+    let quote = match s.contains('"') && !s.contains('\'') {
+        true => '\'',
+        false => '"',
+    };
+    output.push(quote);
+    output.push_str(s);
+    output.push(quote);
+}
+
+// Can be written as an identifier in a template
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    let starts_with_letter = matches!(chars.next(), Some('a'..='z' | 'A'..='Z' | '_'));
+    let keyword = matches!(s, "for" | "in" | "if" | "else" | "true" | "false" | "let");
+    starts_with_letter && !keyword && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 // -----------------------------------------------------------------------------
 //   - Paths -
 // -----------------------------------------------------------------------------
@@ -182,6 +360,10 @@ pub fn less_than_equal(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Expres
     Expression::Equality(lhs, rhs, Equality::Lte).into()
 }
 
+pub fn range(start: Box<Expression>, end: Box<Expression>) -> Box<Expression> {
+    Expression::Range(start, end).into()
+}
+
 // -----------------------------------------------------------------------------
 //   - Values -
 // -----------------------------------------------------------------------------
@@ -240,3 +422,19 @@ pub fn and(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Expression> {
 pub fn or(lhs: Box<Expression>, rhs: Box<Expression>) -> Box<Expression> {
     Expression::Equality(lhs, rhs, Equality::Or).into()
 }
+
+pub fn conditional(cond: Box<Expression>, then: Box<Expression>, otherwise: Box<Expression>) -> Box<Expression> {
+    Expression::Conditional(cond, then, otherwise).into()
+}
+
+pub fn coalesce(value: Box<Expression>, fallback: Box<Expression>) -> Box<Expression> {
+    Expression::Coalesce(value, fallback).into()
+}
+
+// -----------------------------------------------------------------------------
+//   - Function call -
+// -----------------------------------------------------------------------------
+pub fn call<E: Into<Expression>>(fun: Box<Expression>, args: impl IntoIterator<Item = E>) -> Box<Expression> {
+    let args = args.into_iter().map(|arg| arg.into()).collect::<Vec<_>>();
+    Expression::Call { fun, args: args.into() }.into()
+}
@@ -10,14 +10,18 @@ use crate::token::{Kind, Operator, Tokens, Value};
 
 pub(crate) mod prec {
     pub const INITIAL: u8 = 0;
-    pub const CONDITIONAL: u8 = 2;
-    pub const EQUALITY: u8 = 3;
-    pub const LOGICAL: u8 = 4;
-    pub const SUM: u8 = 5;
-    pub const PRODUCT: u8 = 6;
-    pub const PREFIX: u8 = 8;
-    pub const CALL: u8 = 10;
-    pub const SUBCRIPT: u8 = 11;
+    pub const CONDITIONAL: u8 = 1;
+    pub const RANGE: u8 = 2;
+    pub const OR: u8 = 3;
+    pub const AND: u8 = 4;
+    pub const EQUALITY: u8 = 5;
+    pub const LOGICAL: u8 = 6;
+    pub const COALESCE: u8 = 7;
+    pub const SUM: u8 = 8;
+    pub const PRODUCT: u8 = 9;
+    pub const PREFIX: u8 = 10;
+    pub const CALL: u8 = 12;
+    pub const SUBCRIPT: u8 = 13;
 }
 
 fn get_precedence(op: Operator) -> u8 {
@@ -30,7 +34,11 @@ fn get_precedence(op: Operator) -> u8 {
             prec::LOGICAL
         }
         Operator::EqualEqual | Operator::NotEqual => prec::EQUALITY,
-        Operator::Or | Operator::And => prec::CONDITIONAL,
+        Operator::Or => prec::OR,
+        Operator::And => prec::AND,
+        Operator::Range => prec::RANGE,
+        Operator::QuestionQuestion => prec::COALESCE,
+        Operator::Question => prec::CONDITIONAL,
 
         _ => prec::INITIAL,
     }
@@ -60,6 +68,11 @@ pub(crate) enum Expr {
     },
     List(Vec<Expr>),
     Map(Vec<(Expr, Expr)>),
+    Conditional {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
 }
 
 impl Display for Expr {
@@ -87,6 +100,7 @@ impl Display for Expr {
                 let s = args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", ");
                 write!(f, "{fun}({s})")
             }
+            Expr::Conditional { cond, then, otherwise } => write!(f, "(? {cond} {then} {otherwise})"),
         }
     }
 }
@@ -134,6 +148,13 @@ fn expr_bp(tokens: &mut Tokens, precedence: u8) -> Result<Expr, ParseErrorKind>
             return Ok(left);
         };
 
+        // Attributes following the expression, e.g `if x [keep_alive: true]`
+        if let Operator::LBracket = op {
+            if tokens.peek_attributes() {
+                return Ok(left);
+            }
+        }
+
         let token_prec = get_precedence(op);
 
         // If the current token precedence is higher than the current precedence, then we bind to the right,
@@ -161,6 +182,19 @@ fn expr_bp(tokens: &mut Tokens, precedence: u8) -> Result<Expr, ParseErrorKind>
                 };
                 continue;
             }
+            // `cond ? then : otherwise`, where `otherwise` can be another conditional
+            Operator::Question => {
+                let then = expr_bp(tokens, prec::INITIAL)?;
+                let Kind::Op(Operator::Colon) = tokens.next_no_indent() else {
+                    return Err(ParseErrorKind::InvalidToken { expected: ":" });
+                };
+                left = Expr::Conditional {
+                    cond: Box::new(left),
+                    then: Box::new(then),
+                    otherwise: Box::new(expr_bp(tokens, prec::INITIAL)?),
+                };
+                continue;
+            }
             _ => {}
         }
 
@@ -382,4 +416,37 @@ mod test {
         let _output = parse(input);
         assert_eq!(parse(input), "(&& (!= 1 2) (!= 3 4))");
     }
+
+    #[test]
+    fn and_before_or() {
+        let input = "a || b && c";
+        assert_eq!(parse(input), "(|| <sid 0> (&& <sid 1> <sid 2>))");
+
+        let input = "a < 1 || b + 1 >= 2 * c";
+        assert_eq!(parse(input), "(|| (< <sid 0> 1) (>= (+ <sid 1> 1) (* 2 <sid 2>)))");
+    }
+
+    #[test]
+    fn conditional() {
+        let input = "a == 1 ? b : c + 1";
+        assert_eq!(parse(input), "(? (== <sid 0> 1) <sid 1> (+ <sid 2> 1))");
+
+        let input = "a ? b : c ? d : e";
+        assert_eq!(parse(input), "(? <sid 0> <sid 1> (? <sid 2> <sid 3> <sid 4>))");
+
+        let input = "a ? b ? c : d : e";
+        assert_eq!(parse(input), "(? <sid 0> (? <sid 1> <sid 2> <sid 3>) <sid 4>)");
+    }
+
+    #[test]
+    fn coalesce() {
+        let input = "a ?? b ?? 1";
+        assert_eq!(parse(input), "(?? (?? <sid 0> <sid 1>) 1)");
+
+        let input = "a ?? 0 > b ?? 1 + 2";
+        assert_eq!(parse(input), "(> (?? <sid 0> 0) (?? <sid 1> (+ 1 2)))");
+
+        let input = "0..a ?? 10";
+        assert_eq!(parse(input), "(.. 0 (?? <sid 0> 10))");
+    }
 }
@@ -0,0 +1,223 @@
+//! Canonical formatting of templates, for editors and pre-commit hooks.
+//!
+//! ```
+//! use anathema_templates::format_template;
+//!
+//! let template = "vstack   [width: 10,background: 'red']\n  // greeting\n  text 'hi'";
+//! let formatted = format_template(template).unwrap();
+//! assert_eq!(formatted, "vstack [background: \"red\", width: 10]\n    // greeting\n    text \"hi\"\n");
+//! ```
+use crate::builder::{parse, to_template, Node};
+use crate::error::Result;
+
+/// Format the template: four spaces of indentation per level, attributes sorted
+/// by name, and single spaces between the parts of a line.
+///
+/// Comments are kept, and consecutive blank lines are collapsed into one.
+/// A comment inside attributes, a list or a map that spans several lines is
+/// moved to the end of the line, as the formatted statement is written on one line.
+/// Formatting a formatted template returns the same template.
+pub fn format_template(template: &str) -> Result<String> {
+    let mut nodes = parse(template)?;
+    sort_attributes(&mut nodes);
+    let formatted = to_template(&nodes);
+
+    let mut statements = statements(template).into_iter();
+    let mut output = String::with_capacity(formatted.len());
+
+    for line in formatted.lines() {
+        // Every statement of the template is written as a line
+        let statement = statements.next().unwrap_or_default();
+        let indent = &line[..line.len() - line.trim_start().len()];
+
+        for item in statement.before {
+            match item {
+                Item::Blank if !output.is_empty() => output.push('\n'),
+                Item::Blank => {}
+                Item::Comment(comment) => {
+                    output.push_str(indent);
+                    output.push_str(comment);
+                    output.push('\n');
+                }
+            }
+        }
+
+        output.push_str(line);
+        for comment in statement.trailing {
+            output.push(' ');
+            output.push_str(comment);
+        }
+        output.push('\n');
+    }
+
+    // Comments after the last statement
+    let mut rest = statements.flat_map(|statement| statement.before).collect::<Vec<_>>();
+    while let Some(Item::Blank) = rest.last() {
+        rest.pop();
+    }
+    for item in rest {
+        if let Item::Comment(comment) = item {
+            output.push_str(comment);
+        }
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+fn sort_attributes(nodes: &mut [Node]) {
+    for node in nodes {
+        match node {
+            Node::Widget(widget) => {
+                widget.attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+                sort_attributes(&mut widget.children);
+            }
+            Node::For(node) => sort_attributes(&mut node.body),
+            Node::If(node) => node.branches.iter_mut().for_each(|b| sort_attributes(&mut b.body)),
+            Node::Match(node) => node.branches.iter_mut().for_each(|b| sort_attributes(&mut b.body)),
+            Node::Component(node) => {
+                node.attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+                node.slots.iter_mut().for_each(|(_, body)| sort_attributes(body));
+            }
+            Node::Definition(definition) => {
+                definition.props.sort_by(|(a, _), (b, _)| a.cmp(b));
+                sort_attributes(&mut definition.body);
+            }
+            Node::Slot(_) | Node::Let(..) | Node::Include(_) => {}
+        }
+    }
+}
+
+enum Item<'src> {
+    Blank,
+    Comment(&'src str),
+}
+
+// The blank lines and comments of a statement of the template
+#[derive(Default)]
+struct Statement<'src> {
+    before: Vec<Item<'src>>,
+    trailing: Vec<&'src str>,
+}
+
+// Split the template into statements. A line starts a statement unless it continues
+// an open list, map or set of attributes from a previous line.
+// The last statement holds the comments after the last line of code.
+fn statements(template: &str) -> Vec<Statement<'_>> {
+    let mut statements: Vec<Statement<'_>> = vec![];
+    let mut before = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for line in template.lines() {
+        let continues = depth > 0 || quote.is_some();
+        let mut comment = None;
+        let mut code = false;
+        let mut chars = line.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match (quote, c) {
+                // Only the quote can be escaped
+                (Some(q), '\\') if line[i + 1..].starts_with(q) => drop(chars.next()),
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '/') if line[i + 1..].starts_with('/') => {
+                    comment = Some(line[i..].trim_end());
+                    break;
+                }
+                (None, '[' | '{' | '(') => depth += 1,
+                (None, ']' | '}' | ')') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            code |= !c.is_whitespace();
+        }
+
+        match statements.last_mut() {
+            // Comments inside a statement that spans lines are moved to the end of the statement
+            Some(statement) if continues => statement.trailing.extend(comment),
+            _ if code => statements.push(Statement {
+                before: std::mem::take(&mut before),
+                trailing: comment.into_iter().collect(),
+            }),
+            _ => match comment {
+                Some(comment) => before.push(Item::Comment(comment)),
+                None if matches!(before.last(), Some(Item::Blank)) => {}
+                None => before.push(Item::Blank),
+            },
+        }
+    }
+
+    statements.push(Statement {
+        before,
+        trailing: vec![],
+    });
+    statements
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    fn assert_round_trip(template: &str) {
+        let formatted = format_template(template).unwrap();
+        assert_eq!(format_template(&formatted).unwrap(), formatted);
+
+        let mut nodes = parse(template).unwrap();
+        sort_attributes(&mut nodes);
+        assert_eq!(parse(&formatted).unwrap(), nodes);
+    }
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let template = "
+// header
+
+
+vstack [
+  b: 1, // b
+  a: '//'
+]
+  text 'a' // trailing
+
+  // last
+// end
+";
+        let expected = "// header
+
+vstack [a: \"//\", b: 1] // b
+    text \"a\" // trailing
+
+// last
+// end
+";
+        assert_eq!(format_template(template).unwrap(), expected);
+        assert_round_trip(template);
+    }
+
+    #[test]
+    fn examples() {
+        fn visit(dir: &Path, templates: &mut Vec<String>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                match path.is_dir() {
+                    true => visit(&path, templates),
+                    false if path.extension().is_some_and(|ext| ext == "aml") => {
+                        templates.push(std::fs::read_to_string(path).unwrap())
+                    }
+                    false => {}
+                }
+            }
+        }
+
+        let mut templates = vec![];
+        visit(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples"),
+            &mut templates,
+        );
+        assert!(!templates.is_empty());
+        templates.iter().for_each(|template| assert_round_trip(template));
+    }
+}
@@ -82,6 +82,14 @@ impl<'src, 'strings> Lexer<'src, 'strings> {
                 let _ = self.chars.next();
                 Ok(Kind::Op(Operator::Association).to_token(index))
             }
+            ('.', Some('.')) => {
+                let _ = self.chars.next();
+                Ok(Kind::Op(Operator::Range).to_token(index))
+            }
+            ('?', Some('?')) => {
+                let _ = self.chars.next();
+                Ok(Kind::Op(Operator::QuestionQuestion).to_token(index))
+            }
 
             // -----------------------------------------------------------------------------
             //     - Single tokens -
@@ -103,6 +111,7 @@ impl<'src, 'strings> Lexer<'src, 'strings> {
             ('%', _) => Ok(Kind::Op(Operator::Mod).to_token(index)),
             ('>', _) => Ok(Kind::Op(Operator::GreaterThan).to_token(index)),
             ('<', _) => Ok(Kind::Op(Operator::LessThan).to_token(index)),
+            ('?', _) => Ok(Kind::Op(Operator::Question).to_token(index)),
             ('=', _) => Ok(Kind::Equal.to_token(index)),
             ('\n', _) => Ok(Kind::Newline.to_token(index)),
             ('@', _) => Ok(Kind::Component.to_token(index)),
@@ -180,6 +189,10 @@ impl<'src, 'strings> Lexer<'src, 'strings> {
         let _signed = &self.src[index..=index] == "-" || self.chars.peek().map(|(_, c)| *c == '-').unwrap_or(false);
 
         while let Some((e, c @ ('0'..='9' | '.'))) = self.chars.peek() {
+            // The start of a range, e.g `0..10`
+            if *c == '.' && self.src[*e + 1..].starts_with('.') {
+                break;
+            }
             if *c == '.' {
                 parse_float = true;
             }
@@ -311,8 +324,13 @@ mod test {
             crate::error::Error::ParseError(err) => err.kind,
             crate::error::Error::CircularDependency
             | crate::error::Error::MissingComponent(_)
+            | crate::error::Error::MissingLayout(_)
+            | crate::error::Error::InvalidProperty(..)
+            | crate::error::Error::Include(..)
             | crate::error::Error::EmptyTemplate
             | crate::error::Error::EmptyBody
+            | crate::error::Error::InvalidMatch
+            | crate::error::Error::InvalidKeepAlive
             | crate::error::Error::Io(_) => panic!("invalid error"),
         }
     }
@@ -351,7 +369,11 @@ mod test {
 
     #[test]
     fn double_char_token() {
-        let inputs = [("<=", Operator::LessThanOrEqual), ("&&", Operator::And)];
+        let inputs = [
+            ("<=", Operator::LessThanOrEqual),
+            ("&&", Operator::And),
+            ("??", Operator::QuestionQuestion),
+        ];
 
         for (input, expected) in inputs {
             let actual = operator(input);
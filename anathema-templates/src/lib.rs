@@ -1,15 +1,19 @@
 pub use crate::components::{SourceKind, ToSourceKind, WidgetComponentId};
-pub use crate::document::Document;
+pub use crate::document::{Document, DEFAULT_LAYOUT};
 pub use crate::expressions::Expression;
+pub use crate::format::format_template;
 pub use crate::lexer::Lexer;
 pub use crate::primitives::Primitive;
 pub use crate::variables::Globals;
 
+pub mod analysis;
 pub mod blueprints;
+pub mod builder;
 pub(crate) mod components;
 mod document;
 pub mod error;
 pub mod expressions;
+mod format;
 mod lexer;
 mod primitives;
 mod statements;
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use anathema_store::storage::strings::Strings;
+
+use super::{Statement, Statements};
+use crate::builder::{Branch, ComponentNode, Conditional, Definition, Loop, Match, Node, Widget};
+use crate::components::ComponentTemplates;
+use crate::error::{Error, Result};
+use crate::expressions::Expression;
+
+// Turn the statements into nodes, without evaluating them
+pub(crate) fn nodes(
+    mut statements: Statements,
+    strings: &Strings,
+    components: &ComponentTemplates,
+) -> Result<Vec<Node>> {
+    let mut output = vec![];
+
+    while let Some(statement) = statements.next() {
+        let node = match statement {
+            Statement::Node(ident) => {
+                let attributes = attributes(&mut statements, strings);
+                let value = statements.take_value();
+                let children = nodes(statements.take_scope(), strings, components)?;
+                Node::Widget(Widget {
+                    ident: strings.get_unchecked(ident).into(),
+                    attributes,
+                    value,
+                    children,
+                })
+            }
+            Statement::For { binding, index, data } => Node::For(Loop {
+                binding: strings.get_unchecked(binding).into(),
+                index: index.map(|index| strings.get_unchecked(index).into()),
+                data,
+                body: nodes(statements.take_scope(), strings, components)?,
+            }),
+            Statement::If(cond) => {
+                let mut branches = vec![branch(Some(cond), &mut statements, strings, components)?];
+                while let Some(cond) = statements.next_else() {
+                    branches.push(branch(cond, &mut statements, strings, components)?);
+                }
+                Node::If(Conditional { branches })
+            }
+            Statement::Match(value) => {
+                let mut cases = statements.take_scope();
+                let mut branches = vec![];
+                while let Some(case) = cases.next_case() {
+                    branches.push(branch(case, &mut cases, strings, components)?);
+                }
+                if !cases.is_empty() {
+                    return Err(Error::InvalidMatch);
+                }
+                Node::Match(Match { value, branches })
+            }
+            Statement::Case(_) => return Err(Error::InvalidMatch),
+            Statement::Component(id) => {
+                let assoc_functions = statements
+                    .take_assoc_functions()
+                    .into_iter()
+                    .map(|(internal, external)| {
+                        (
+                            strings.get_unchecked(internal).into(),
+                            strings.get_unchecked(external).into(),
+                        )
+                    })
+                    .collect();
+
+                let attributes = attributes(&mut statements, strings);
+
+                // Anything but a map is not a valid state, and fails once the template is compiled
+                let state = match statements.take_value() {
+                    Some(Expression::Map(map)) => Some(map),
+                    _ => None,
+                };
+
+                let mut slots = vec![];
+                let mut scope = statements.take_scope();
+                while let Some(slot) = scope.next_slot() {
+                    let body = nodes(scope.take_scope(), strings, components)?;
+                    slots.push((strings.get_unchecked(slot).into(), body));
+                }
+
+                Node::Component(ComponentNode {
+                    name: components.name(id).unwrap_or_default().into(),
+                    attributes,
+                    state,
+                    assoc_functions,
+                    slots,
+                })
+            }
+            Statement::ComponentSlot(slot) => Node::Slot(strings.get_unchecked(slot).into()),
+            Statement::Include(path) => Node::Include(path),
+            Statement::Declaration { binding, value } => Node::Let(strings.get_unchecked(binding).into(), value),
+            Statement::Definition(name) => {
+                let props = attributes(&mut statements, strings);
+                // A definition has no value
+                let _ = statements.take_value();
+                Node::Definition(Definition {
+                    name: strings.get_unchecked(name).into(),
+                    props,
+                    body: nodes(statements.take_scope(), strings, components)?,
+                })
+            }
+
+            // These statements are part of the statements above
+            Statement::ScopeStart
+            | Statement::ScopeEnd
+            | Statement::LoadAttribute { .. }
+            | Statement::AssociatedFunction { .. }
+            | Statement::Else(_)
+            | Statement::LoadValue(_) => {
+                unreachable!("\"{statement:?}\" found: this is a bug in Anathema. Please open an issue")
+            }
+            Statement::Eof => break,
+        };
+
+        output.push(node);
+    }
+
+    Ok(output)
+}
+
+fn attributes(statements: &mut Statements, strings: &Strings) -> Vec<(Rc<str>, Expression)> {
+    statements
+        .take_attributes()
+        .into_iter()
+        .map(|(key, value)| (strings.get_unchecked(key).into(), value))
+        .collect()
+}
+
+fn branch(
+    cond: Option<Expression>,
+    statements: &mut Statements,
+    strings: &Strings,
+    components: &ComponentTemplates,
+) -> Result<Branch> {
+    // Only `keep_alive` is used by a branch
+    let attributes = attributes(statements, strings);
+    let keep_alive = super::keep_alive(attributes.iter().map(|(key, value)| (&**key, value)))?;
+
+    Ok(Branch {
+        cond,
+        keep_alive,
+        body: nodes(statements.take_scope(), strings, components)?,
+    })
+}
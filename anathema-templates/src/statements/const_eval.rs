@@ -16,8 +16,9 @@ fn eval_path(expr: &Expression, ctx: &Context<'_>) -> Option<Expression> {
     use {Expression as E, Primitive as P};
 
     match expr {
-        // NOTE: if `None` is not returned here then overriding globals in templates will fail
-        E::Ident(_) => None, //ctx.fetch(ident),
+        // NOTE: if `None` is returned for globals here then overriding them in templates will fail.
+        // Props can't be overridden, so they are always replaced.
+        E::Ident(ident) => ctx.props.get(ident).cloned(),
         E::Str(strlit) => ctx.fetch(strlit),
         E::Index(lhs, rhs) => match eval_path(lhs, ctx)? {
             E::List(list) => match const_eval(rhs.clone(), ctx) {
@@ -69,7 +70,8 @@ pub(crate) fn const_eval(expr: impl Into<Expression>, ctx: &Context<'_>) -> Expr
             E::Map(hm.into())
         }
         E::Op(lhs, rhs, op) => match (ce!(*lhs), ce!(*rhs)) {
-            (E::Primitive(P::Int(lhs)), E::Primitive(P::Int(rhs))) => {
+            // Division by zero is left for the runtime, where it has no value
+            (E::Primitive(P::Int(lhs)), E::Primitive(P::Int(rhs))) if rhs != 0 || !matches!(op, Op::Div | Op::Mod) => {
                 let val = match op {
                     Op::Add => lhs + rhs,
                     Op::Sub => lhs - rhs,
@@ -81,6 +83,17 @@ pub(crate) fn const_eval(expr: impl Into<Expression>, ctx: &Context<'_>) -> Expr
             }
             (lhs, rhs) => E::Op(lhs.into(), rhs.into(), op),
         },
+        E::Conditional(cond, then, otherwise) => match ce!(*cond) {
+            E::Primitive(P::Bool(true)) => ce!(*then),
+            E::Primitive(P::Bool(false)) => ce!(*otherwise),
+            cond => E::Conditional(cond.into(), ce!(*then), ce!(*otherwise)),
+        },
+        // Values and strings are never missing
+        E::Coalesce(value, fallback) => match ce!(*value) {
+            value @ (E::Primitive(_) | E::Str(_)) => value,
+            value => E::Coalesce(value.into(), ce!(*fallback)),
+        },
+        E::Range(start, end) => E::Range(ce!(*start), ce!(*end)),
         E::Call { fun, args } => E::Call {
             fun: fun.clone(),
             args: args.iter().map(|expr| ce!(expr.clone())).collect(),
@@ -91,7 +104,7 @@ pub(crate) fn const_eval(expr: impl Into<Expression>, ctx: &Context<'_>) -> Expr
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::expressions::{add, div, mul, num, sub};
+    use crate::expressions::{add, boolean, coalesce, conditional, div, ident, mul, num, strlit, sub};
     use crate::statements::with_context;
 
     #[test]
@@ -133,4 +146,26 @@ mod test {
             assert_eq!(output, *num(1));
         });
     }
+
+    #[test]
+    fn conditional_with_constant_condition() {
+        with_context(|ctx| {
+            let expr = conditional(boolean(false), num(1), add(num(1), num(1)));
+            assert_eq!(const_eval(expr, &ctx), *num(2));
+
+            let expr = conditional(ident("a"), num(1), num(2));
+            assert_eq!(const_eval(expr.clone(), &ctx), *expr);
+        });
+    }
+
+    #[test]
+    fn coalesce_constant() {
+        with_context(|ctx| {
+            let expr = coalesce(strlit("a"), strlit("b"));
+            assert_eq!(const_eval(expr, &ctx), *strlit("a"));
+
+            let expr = coalesce(ident("a"), strlit("b"));
+            assert_eq!(const_eval(expr.clone(), &ctx), *expr);
+        });
+    }
 }
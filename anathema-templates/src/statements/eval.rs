@@ -4,10 +4,11 @@ use anathema_store::smallmap::SmallMap;
 use anathema_store::storage::strings::StringId;
 
 use super::const_eval::const_eval;
-use super::{Context, Statement, Statements};
+use super::{ast, Context, Statement, Statements};
 use crate::blueprints::{Blueprint, Component, ControlFlow, Else, For, If, Single};
+use crate::builder::Definition;
 use crate::error::{Error, Result};
-use crate::expressions::Expression;
+use crate::expressions::{eq, Expression};
 use crate::WidgetComponentId;
 
 pub(crate) struct Scope {
@@ -25,9 +26,13 @@ impl Scope {
         while let Some(statement) = self.statements.next() {
             match statement {
                 Statement::Node(ident) => output.push(self.eval_node(ident, ctx)?),
-                Statement::Component(component_id) => output.push(self.eval_component(component_id, ctx)?),
-                Statement::For { binding, data } => output.push(self.eval_for(binding, data, ctx)?),
+                Statement::Component(component_id) => output.extend(self.eval_component(component_id, ctx)?),
+                Statement::Definition(name) => self.eval_definition(name, ctx)?,
+                Statement::Include(path) => output.extend(ctx.include(&path)?),
+                Statement::For { binding, index, data } => output.push(self.eval_for(binding, index, data, ctx)?),
                 Statement::If(cond) => output.push(self.eval_if(cond, ctx)?),
+                Statement::Match(value) => output.push(self.eval_match(value, ctx)?),
+                Statement::Case(_) => return Err(Error::InvalidMatch),
                 Statement::Declaration { binding, value } => {
                     let value = const_eval(value, ctx);
                     let binding = ctx.strings.get_unchecked(binding);
@@ -70,12 +75,20 @@ impl Scope {
         Ok(node)
     }
 
-    fn eval_for(&mut self, binding: StringId, data: Expression, ctx: &mut Context<'_>) -> Result<Blueprint> {
+    fn eval_for(
+        &mut self,
+        binding: StringId,
+        index: Option<StringId>,
+        data: Expression,
+        ctx: &mut Context<'_>,
+    ) -> Result<Blueprint> {
         let data = const_eval(data, ctx);
         let binding = ctx.strings.get_unchecked(binding);
+        let index = index.map(|index| ctx.strings.get_unchecked(index).into());
         let body = self.consume_scope(ctx)?;
         let node = Blueprint::For(For {
             binding: binding.into(),
+            index,
             data,
             body,
         });
@@ -99,29 +112,87 @@ impl Scope {
         Ok(hm)
     }
 
+    fn eval_keep_alive(&mut self, ctx: &mut Context<'_>) -> Result<bool> {
+        let attributes = self.eval_attributes(ctx)?;
+        super::keep_alive(attributes.iter().map(|(key, value)| (&**key, value)))
+    }
+
     fn eval_if(&mut self, cond: Expression, ctx: &mut Context<'_>) -> Result<Blueprint> {
         let cond = const_eval(cond, ctx);
+        let keep_alive = self.eval_keep_alive(ctx)?;
         let body = self.consume_scope(ctx)?;
         if body.is_empty() {
             return Err(Error::EmptyBody);
         }
 
-        let if_node = If { cond, body };
+        let if_node = If { cond, body, keep_alive };
         let mut elses = vec![];
         while let Some(cond) = self.statements.next_else() {
             let cond = cond.map(|v| const_eval(v, ctx));
+            let keep_alive = self.eval_keep_alive(ctx)?;
             let body = self.consume_scope(ctx)?;
 
             if body.is_empty() {
                 return Err(Error::EmptyBody);
             }
 
-            elses.push(Else { cond, body });
+            elses.push(Else { cond, body, keep_alive });
         }
         Ok(Blueprint::ControlFlow(ControlFlow { if_node, elses }))
     }
 
-    fn eval_component(&mut self, component_id: WidgetComponentId, ctx: &mut Context<'_>) -> Result<Blueprint> {
+    // Each `case` is a condition comparing the value to the case,
+    // the same as `if value == a` followed by `else if value == b`
+    fn eval_match(&mut self, value: Expression, ctx: &mut Context<'_>) -> Result<Blueprint> {
+        let value = const_eval(value, ctx);
+        let mut cases = Scope::new(self.statements.take_scope());
+        let mut branches = vec![];
+
+        while let Some(case) = cases.statements.next_case() {
+            let cond = case.map(|case| *eq(value.clone().into(), const_eval(case, ctx).into()));
+            let keep_alive = cases.eval_keep_alive(ctx)?;
+            let body = cases.consume_scope(ctx)?;
+            if body.is_empty() {
+                return Err(Error::EmptyBody);
+            }
+            branches.push(Else { cond, body, keep_alive });
+        }
+
+        if !cases.statements.is_empty() || branches.is_empty() {
+            return Err(Error::InvalidMatch);
+        }
+
+        let first = branches.remove(0);
+        let if_node = If {
+            cond: first.cond.unwrap_or(true.into()),
+            body: first.body,
+            keep_alive: first.keep_alive,
+        };
+        Ok(Blueprint::ControlFlow(ControlFlow {
+            if_node,
+            elses: branches,
+        }))
+    }
+
+    // The body is built each time the component is used, see `Context::expand`
+    fn eval_definition(&mut self, name: StringId, ctx: &mut Context<'_>) -> Result<()> {
+        let name: Rc<str> = ctx.strings.get_unchecked(name).into();
+        let props = self
+            .statements
+            .take_attributes()
+            .into_iter()
+            .map(|(key, value)| (ctx.strings.get_unchecked(key).into(), const_eval(value, ctx)))
+            .collect();
+
+        // A definition has no value
+        let _ = self.statements.take_value();
+        let body = ast::nodes(self.statements.take_scope(), ctx.strings, ctx.components)?;
+        ctx.definitions
+            .insert(name.clone(), Rc::new(Definition { name, props, body }));
+        Ok(())
+    }
+
+    fn eval_component(&mut self, component_id: WidgetComponentId, ctx: &mut Context<'_>) -> Result<Vec<Blueprint>> {
         let parent = ctx.component_parent();
 
         // Associated functions
@@ -149,6 +220,11 @@ impl Scope {
             slots.set(slot_id, body);
         }
 
+        let definition = ctx.components.name(component_id).and_then(|name| ctx.definition(name));
+        if let Some(definition) = definition {
+            return ctx.expand(definition, &attributes, slots);
+        }
+
         let body = ctx.load_component(component_id, slots)?;
 
         let component = Component {
@@ -160,7 +236,7 @@ impl Scope {
             parent,
         };
 
-        Ok(Blueprint::Component(component))
+        Ok(vec![Blueprint::Component(component)])
     }
 }
 
@@ -212,6 +288,74 @@ mod test {
         assert!(matches!(blueprint, Blueprint::For(For { .. })));
     }
 
+    #[test]
+    fn eval_keep_alive() {
+        let src = "
+            if a [keep_alive: false]
+                node
+            else
+                node
+        ";
+        let mut doc = Document::new(src);
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::ControlFlow(control_flow) = blueprint else { panic!() };
+        assert!(!control_flow.if_node.keep_alive);
+        assert!(control_flow.elses[0].keep_alive);
+    }
+
+    #[test]
+    fn eval_keep_alive_not_a_literal() {
+        let src = "
+            if a [keep_alive: b]
+                node
+        ";
+        let mut doc = Document::new(src);
+        assert!(matches!(doc.compile(), Err(Error::InvalidKeepAlive)));
+    }
+
+    #[test]
+    fn eval_match() {
+        let src = "
+            match state
+                case 'loading'
+                    node
+                case 'error' [keep_alive: true]
+                    node
+                else
+                    node
+        ";
+        let mut doc = Document::new(src);
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::ControlFlow(control_flow) = blueprint else { panic!() };
+        assert_eq!(
+            control_flow.if_node.cond,
+            *eq(
+                Expression::Ident("state".into()).into(),
+                Expression::Str("loading".into()).into()
+            )
+        );
+        assert_eq!(control_flow.elses.len(), 2);
+        assert!(control_flow.elses[0].keep_alive);
+        assert!(control_flow.elses[1].cond.is_none());
+    }
+
+    #[test]
+    fn invalid_match() {
+        let src = "
+            match state
+                node
+        ";
+        let mut doc = Document::new(src);
+        assert!(matches!(doc.compile(), Err(Error::InvalidMatch)));
+
+        let src = "
+            case 'a'
+                node
+        ";
+        let mut doc = Document::new(src);
+        assert!(matches!(doc.compile(), Err(Error::InvalidMatch)));
+    }
+
     #[test]
     fn eval_component() {
         let src = "@comp {a: 1}";
@@ -268,4 +412,63 @@ mod test {
         doc.add_component("comp", "node a".to_template()).unwrap();
         let _ = doc.compile().unwrap();
     }
+
+    #[test]
+    fn eval_template_component() {
+        let src = "
+            component card [title: 'untitled', width: 5]
+                border [width: width]
+                    text title
+                    $body
+            vstack
+                @card [title: 'a']
+                    $body
+                        text 'b'
+                @card
+        ";
+
+        let mut doc = Document::new(src);
+        let (blueprint, _) = doc.compile().unwrap();
+        let Blueprint::Single(vstack) = blueprint else { panic!() };
+        assert_eq!(vstack.children.len(), 2);
+
+        let Blueprint::Single(first) = &vstack.children[0] else { panic!() };
+        assert_eq!(&*first.ident, "border");
+        assert_eq!(first.attributes.get("width"), Some(&Expression::Primitive(5.into())));
+        let Blueprint::Single(title) = &first.children[0] else { panic!() };
+        assert_eq!(title.value, Some(Expression::Str("a".into())));
+        let Blueprint::Single(body) = &first.children[1] else { panic!() };
+        assert_eq!(body.value, Some(Expression::Str("b".into())));
+
+        // The defaults are used and the empty slot is left out
+        let Blueprint::Single(second) = &vstack.children[1] else { panic!() };
+        let Blueprint::Single(title) = &second.children[0] else { panic!() };
+        assert_eq!(title.value, Some(Expression::Str("untitled".into())));
+        assert_eq!(second.children.len(), 1);
+    }
+
+    #[test]
+    fn template_component_invalid_prop() {
+        let src = "
+            component card [title: '']
+                text title
+            @card [name: 'a']
+        ";
+
+        let mut doc = Document::new(src);
+        let err = doc.compile().unwrap_err();
+        assert_eq!(err.to_string(), "`@card` has no prop `name`");
+    }
+
+    #[test]
+    fn recursive_template_component() {
+        let src = "
+            component card
+                @card
+            @card
+        ";
+
+        let mut doc = Document::new(src);
+        assert!(doc.compile().is_err());
+    }
 }
@@ -1,16 +1,28 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
 use anathema_store::smallmap::SmallMap;
 use anathema_store::storage::strings::{StringId, Strings};
 
 use crate::blueprints::Blueprint;
+use crate::builder::{build_all, Definition};
 use crate::components::ComponentTemplates;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::expressions::Expression;
+use crate::primitives::Primitive;
+use crate::token::Tokens;
 use crate::variables::Variables;
-use crate::WidgetComponentId;
+use crate::{Lexer, WidgetComponentId};
 
-mod const_eval;
+pub(crate) mod ast;
+pub(crate) mod const_eval;
 pub(crate) mod eval;
-pub(super) mod parser;
+pub(crate) mod parser;
+
+use self::eval::Scope;
+use self::parser::Parser;
 
 pub(crate) struct Context<'vars> {
     pub(crate) globals: &'vars mut Variables,
@@ -18,6 +30,13 @@ pub(crate) struct Context<'vars> {
     pub(crate) strings: &'vars mut Strings,
     pub(crate) slots: SmallMap<StringId, Vec<Blueprint>>,
     pub(crate) current_component_parent: Option<WidgetComponentId>,
+    // The components defined in the template, see `Context::expand`
+    pub(crate) definitions: HashMap<Rc<str>, Rc<Definition>>,
+    // The props of the definition that is being expanded
+    props: HashMap<Rc<str>, Expression>,
+    expanding: Vec<Rc<str>>,
+    // The files that are being compiled, the last one is the current file
+    includes: Vec<PathBuf>,
 }
 
 impl<'vars> Context<'vars> {
@@ -34,6 +53,10 @@ impl<'vars> Context<'vars> {
             strings,
             slots,
             current_component_parent,
+            definitions: HashMap::new(),
+            props: HashMap::new(),
+            expanding: vec![],
+            includes: vec![],
         }
     }
 }
@@ -47,7 +70,82 @@ impl Context<'_> {
         self.globals.fetch(key)
     }
 
-    fn load_component(
+    /// The component defined in the template with the name
+    pub(crate) fn definition(&self, name: &str) -> Option<Rc<Definition>> {
+        self.definitions.get(name).cloned()
+    }
+
+    /// Build the body of a component defined in the template, in place of the component.
+    /// The props are replaced by the attributes, or the defaults of the definition.
+    pub(crate) fn expand(
+        &mut self,
+        definition: Rc<Definition>,
+        attributes: &SmallMap<Rc<str>, Expression>,
+        slots: SmallMap<StringId, Vec<Blueprint>>,
+    ) -> Result<Vec<Blueprint>> {
+        if self.expanding.contains(&definition.name) {
+            return Err(Error::CircularDependency);
+        }
+
+        let mut props = definition.props.iter().cloned().collect::<HashMap<_, _>>();
+        for (key, value) in attributes.iter() {
+            if !props.contains_key(key) {
+                return Err(Error::InvalidProperty(definition.name.to_string(), key.to_string()));
+            }
+            props.insert(key.clone(), value.clone());
+        }
+
+        let props = std::mem::replace(&mut self.props, props);
+        let slots = std::mem::replace(&mut self.slots, slots);
+        self.expanding.push(definition.name.clone());
+
+        let body = build_all(&definition.body, self);
+
+        self.expanding.pop();
+        self.slots = slots;
+        self.props = props;
+        body
+    }
+
+    /// The file of the template, which includes are relative to
+    pub(crate) fn set_path(&mut self, path: &Path) {
+        self.includes = vec![canonical(path)];
+    }
+
+    /// Build the template file in place of the include.
+    /// The path is relative to the current file, or the working directory.
+    pub(crate) fn include(&mut self, path: &str) -> Result<Vec<Blueprint>> {
+        let path = match self.includes.last().and_then(|current| current.parent()) {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        };
+        let canonical = canonical(&path);
+
+        if self.includes.contains(&canonical) {
+            return Err(Error::CircularDependency);
+        }
+
+        if !self.components.included.contains(&canonical) {
+            self.components.included.push(canonical.clone());
+        }
+
+        self.includes.push(canonical);
+        let body = self.compile_file(&path);
+        self.includes.pop();
+
+        body.map_err(|err| Error::Include(path, Box::new(err)))
+    }
+
+    fn compile_file(&mut self, path: &PathBuf) -> Result<Vec<Blueprint>> {
+        let template = read_to_string(path)?;
+        let tokens = Lexer::new(&template, self.strings).collect::<Result<Vec<_>>>()?;
+        let tokens = Tokens::new(tokens, template.len());
+        let parser = Parser::new(tokens, self.strings, &template, self.components);
+        let statements = parser.collect::<Result<Statements>>()?;
+        Scope::new(statements).eval(self)
+    }
+
+    pub(crate) fn load_component(
         &mut self,
         parent_component_id: WidgetComponentId,
         slots: SmallMap<StringId, Vec<Blueprint>>,
@@ -57,18 +155,54 @@ impl Context<'_> {
     }
 }
 
+const KEEP_ALIVE: &str = "keep_alive";
+
+// The body of a branch is kept in the tree while the branch is hidden unless
+// `keep_alive` is false, in which case the body is only built while it's shown.
+// `keep_alive` has to be a literal, since it decides if the body
+// is removed from the tree, not when.
+pub(crate) fn keep_alive<'a>(mut attributes: impl Iterator<Item = (&'a str, &'a Expression)>) -> Result<bool> {
+    match attributes.find(|(key, _)| *key == KEEP_ALIVE) {
+        None => Ok(true),
+        Some((_, Expression::Primitive(Primitive::Bool(keep_alive)))) => Ok(*keep_alive),
+        Some(_) => Err(Error::InvalidKeepAlive),
+    }
+}
+
+// The same file has the same path, no matter how it's included
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum Statement {
     LoadValue(Expression),
-    LoadAttribute { key: StringId, value: Expression },
-    AssociatedFunction { internal: StringId, external: StringId },
+    LoadAttribute {
+        key: StringId,
+        value: Expression,
+    },
+    AssociatedFunction {
+        internal: StringId,
+        external: StringId,
+    },
     Component(WidgetComponentId),
     ComponentSlot(StringId),
+    Definition(StringId),
+    Include(Rc<str>),
     Node(StringId),
-    For { binding: StringId, data: Expression },
-    Declaration { binding: StringId, value: Expression },
+    For {
+        binding: StringId,
+        index: Option<StringId>,
+        data: Expression,
+    },
+    Declaration {
+        binding: StringId,
+        value: Expression,
+    },
     If(Expression),
     Else(Option<Expression>),
+    Match(Expression),
+    Case(Expression),
     ScopeStart,
     ScopeEnd,
     Eof,
@@ -168,6 +302,18 @@ impl Statements {
         }
     }
 
+    // A `case` or the final `else` of a `match`
+    fn next_case(&mut self) -> Option<Option<Expression>> {
+        match matches!(self.0.first(), Some(Statement::Case(_) | Statement::Else(None))) {
+            true => match self.0.remove(0) {
+                Statement::Case(value) => Some(Some(value)),
+                Statement::Else(_) => Some(None),
+                _ => unreachable!(),
+            },
+            false => None,
+        }
+    }
+
     fn next_slot(&mut self) -> Option<StringId> {
         match matches!(self.0.first(), Some(Statement::ComponentSlot(_))) {
             true => match self.0.remove(0) {
@@ -193,13 +339,7 @@ where
     let mut strings = Strings::empty();
     let mut components = ComponentTemplates::new();
 
-    let context = Context {
-        globals: &mut globals,
-        strings: &mut strings,
-        components: &mut components,
-        slots: SmallMap::empty(),
-        current_component_parent: None,
-    };
+    let context = Context::new(&mut globals, &mut components, &mut strings, SmallMap::empty(), None);
 
     f(context)
 }
@@ -244,6 +384,19 @@ mod test {
     pub(crate) fn for_loop(binding: impl Into<StringId>, data: impl Into<Expression>) -> Statement {
         Statement::For {
             binding: binding.into(),
+            index: None,
+            data: data.into(),
+        }
+    }
+
+    pub(crate) fn for_index(
+        binding: impl Into<StringId>,
+        index: impl Into<StringId>,
+        data: impl Into<Expression>,
+    ) -> Statement {
+        Statement::For {
+            binding: binding.into(),
+            index: Some(index.into()),
             data: data.into(),
         }
     }
@@ -267,6 +420,14 @@ mod test {
         Statement::Else(None)
     }
 
+    pub(crate) fn match_stmt(value: impl Into<Expression>) -> Statement {
+        Statement::Match(value.into())
+    }
+
+    pub(crate) fn case(value: impl Into<Expression>) -> Statement {
+        Statement::Case(value.into())
+    }
+
     pub(crate) fn scope_start() -> Statement {
         Statement::ScopeStart
     }
@@ -8,6 +8,16 @@ use crate::expressions::Expression;
 use crate::token::{Kind, Operator, Tokens, Value};
 // use crate::variables::Visibility;
 
+// The keyword of a component defined in a template, e.g `component card [title: ""]`
+pub(crate) const DEFINITION: &str = "component";
+
+// Include another template file, e.g `@include "header.aml"`
+pub(crate) const INCLUDE: &str = "include";
+
+// Show the first `case` equal to the value, e.g `match state` followed by `case "loading"`
+pub(crate) const MATCH: &str = "match";
+pub(crate) const CASE: &str = "case";
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum State {
     EnterScope,
@@ -243,8 +253,30 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
         }
 
         let ident = self.read_ident()?;
-
         self.tokens.consume_indent();
+
+        // `match value` and `case value` take an expression rather than a node value
+        let keyword = self.strings.get_ref_unchecked(ident);
+        if keyword == MATCH || keyword == CASE {
+            let is_match = keyword == MATCH;
+            let value = parse_expr(&mut self.tokens, self.strings).map_err(|e| self.error(e))?;
+            self.next_state();
+            return match is_match {
+                true => Ok(Some(Statement::Match(value))),
+                false => Ok(Some(Statement::Case(value))),
+            };
+        }
+
+        // `component name [props]` defines a component in the template
+        if self.strings.get_ref_unchecked(ident) == DEFINITION {
+            if let Kind::Value(Value::Ident(name)) = self.tokens.peek() {
+                self.tokens.consume();
+                self.tokens.consume_indent();
+                self.next_state();
+                return Ok(Some(Statement::Definition(name)));
+            }
+        }
+
         self.next_state();
         Ok(Some(Statement::Node(ident)))
     }
@@ -259,6 +291,15 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
 
         let binding = self.read_ident()?;
 
+        // The index binding, e.g `for item, i in items`
+        let index = match self.tokens.peek_skip_indent() {
+            Kind::Op(Operator::Comma) => {
+                self.tokens.consume();
+                Some(self.read_ident()?)
+            }
+            _ => None,
+        };
+
         if Kind::In != self.tokens.peek_skip_indent() {
             return Err(self.error(ParseErrorKind::InvalidToken { expected: "in" }));
         }
@@ -271,7 +312,7 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
             Err(e) => return Err(self.error(e)),
         };
         self.next_state();
-        Ok(Some(Statement::For { data, binding }))
+        Ok(Some(Statement::For { binding, index, data }))
     }
 
     fn parse_if(&mut self) -> Result<Option<Statement>, ParseError> {
@@ -283,6 +324,7 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
                     _ => None,
                 };
 
+                self.parse_if_attributes();
                 Ok(Some(Statement::Else(cond)))
             }
             Kind::If => {
@@ -290,6 +332,7 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
                 let cond = parse_expr(&mut self.tokens, self.strings).map_err(|e| self.error(e))?;
 
                 self.next_state();
+                self.parse_if_attributes();
                 Ok(Some(Statement::If(cond)))
             }
             _ => {
@@ -299,6 +342,15 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
         }
     }
 
+    // An if / else can have attributes, e.g `if x [keep_alive: true]`.
+    // This is the second exception to setting the state directly,
+    // as the attributes are parsed as if they belonged to a node.
+    fn parse_if_attributes(&mut self) {
+        if Kind::Op(Operator::LBracket) == self.tokens.peek_skip_indent() {
+            self.state = State::ParseAttributes;
+        }
+    }
+
     fn parse_declaration(&mut self) -> Result<Option<Statement>, ParseError> {
         // Check if it's a declaration otherwise move on
         match self.tokens.peek_skip_indent() {
@@ -335,9 +387,19 @@ impl<'src, 'strings, 'view> Parser<'src, 'strings, 'view> {
 
         let ident = self.read_ident()?;
         let ident = self.strings.get_unchecked(ident);
-        let component_id = self.components.insert_id(ident.to_owned());
         self.tokens.consume_indent();
 
+        if ident == INCLUDE {
+            let path = match parse_expr(&mut self.tokens, self.strings).map_err(|e| self.error(e))? {
+                Expression::Str(path) => path,
+                _ => return Err(self.error(ParseErrorKind::InvalidToken { expected: "path" })),
+            };
+            self.next_state();
+            return Ok(Some(Statement::Include(path)));
+        }
+
+        let component_id = self.components.insert_id(ident.to_owned());
+
         self.next_state();
         Ok(Some(Statement::Component(component_id)))
     }
@@ -548,11 +610,11 @@ impl Iterator for Parser<'_, '_, '_> {
 mod test {
     use super::*;
     use crate::error::Error;
-    use crate::expressions::{ident, list, map, num, strlit};
+    use crate::expressions::{boolean, ident, list, map, num, range, strlit};
     use crate::lexer::Lexer;
     use crate::statements::test::{
-        associated_fun, component, decl, else_stmt, eof, for_loop, if_else, if_stmt, load_attrib, load_value, node,
-        scope_end, scope_start, slot,
+        associated_fun, case, component, decl, else_stmt, eof, for_index, for_loop, if_else, if_stmt, load_attrib,
+        load_value, match_stmt, node, scope_end, scope_start, slot,
     };
 
     fn parse(src: &str) -> Vec<Result<Statement>> {
@@ -667,6 +729,19 @@ mod test {
         assert_eq!(statements.remove(0), scope_end());
     }
 
+    #[test]
+    fn parse_for_index_and_range() {
+        let src = "
+        for x, i in 0..10
+            x
+        ";
+        let mut statements = parse_ok(src);
+        assert_eq!(statements.remove(0), for_index(0, 1, range(num(0), num(10))));
+        assert_eq!(statements.remove(0), scope_start());
+        assert_eq!(statements.remove(0), node(0));
+        assert_eq!(statements.remove(0), scope_end());
+    }
+
     #[test]
     fn parse_scopes_and_for() {
         let src = "
@@ -746,6 +821,30 @@ mod test {
         assert_eq!(statements.remove(0), scope_end());
     }
 
+    #[test]
+    fn parse_match() {
+        let src = "
+        match state
+            case 'a'
+                x
+            else
+                y
+        ";
+        let mut statements = parse_ok(src);
+
+        assert_eq!(statements.remove(0), match_stmt(ident("state")));
+        assert_eq!(statements.remove(0), scope_start());
+        assert_eq!(statements.remove(0), case(strlit("a")));
+        assert_eq!(statements.remove(0), scope_start());
+        assert_eq!(statements.remove(0), node(4));
+        assert_eq!(statements.remove(0), scope_end());
+        assert_eq!(statements.remove(0), else_stmt());
+        assert_eq!(statements.remove(0), scope_start());
+        assert_eq!(statements.remove(0), node(5));
+        assert_eq!(statements.remove(0), scope_end());
+        assert_eq!(statements.remove(0), scope_end());
+    }
+
     #[test]
     fn parse_component() {
         let src = "@mycomp";
@@ -777,6 +876,25 @@ mod test {
         assert_eq!(statements.remove(0), node(0));
     }
 
+    #[test]
+    fn parse_if_attributes() {
+        let src = "
+            if x [keep_alive: true]
+                x
+            else [keep_alive: true]
+                x
+        ";
+
+        let mut statements = parse_ok(src);
+        assert_eq!(statements.remove(0), if_stmt(ident("x")));
+        assert_eq!(statements.remove(0), load_attrib(1, boolean(true)));
+        assert_eq!(statements.remove(0), scope_start());
+        assert_eq!(statements.remove(0), node(0));
+        assert_eq!(statements.remove(0), scope_end());
+        assert_eq!(statements.remove(0), else_stmt());
+        assert_eq!(statements.remove(0), load_attrib(1, boolean(true)));
+    }
+
     #[test]
     fn indented_comment() {
         let src = "
@@ -35,6 +35,9 @@ pub enum Operator {
     Comma,
     Colon,
     Association,
+    Range,
+    Question,
+    QuestionQuestion,
 }
 
 impl Display for Operator {
@@ -69,6 +72,9 @@ impl Display for Operator {
             Self::LCurly => write!(f, "{{"),
             Self::RCurly => write!(f, "}}"),
             Self::Association => write!(f, "->"),
+            Self::Range => write!(f, ".."),
+            Self::Question => write!(f, "?"),
+            Self::QuestionQuestion => write!(f, "??"),
         }
     }
 }
@@ -248,6 +254,24 @@ impl Tokens {
         }
     }
 
+    /// Returns true if the next tokens are the start of attributes (`[key:`)
+    /// rather than an index.
+    pub fn peek_attributes(&self) -> bool {
+        let mut kinds = self.inner[self.index.min(self.inner.len())..]
+            .iter()
+            .map(|token| token.0)
+            .filter(|kind| !matches!(kind, Kind::Indent(_) | Kind::Newline));
+
+        matches!(
+            (kinds.next(), kinds.next(), kinds.next()),
+            (
+                Some(Kind::Op(Operator::LBracket)),
+                Some(Kind::Value(Value::Ident(_))),
+                Some(Kind::Op(Operator::Colon))
+            )
+        )
+    }
+
     pub fn read_indent(&mut self) -> Option<usize> {
         match self.peek() {
             Kind::Indent(indent) => {
@@ -1,12 +1,11 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(test, not(target_os = "windows")))]
 use anathema_debug::DebugWriter;
 use anathema_store::slab::Slab;
 
 use crate::expressions::Expression;
-use crate::primitives::Primitive;
 
 #[derive(Debug, Default, Clone)]
 pub struct Globals(HashMap<Rc<str>, Expression>);
@@ -46,24 +45,6 @@ impl From<VarId> for usize {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum Variable {
-    Static(Primitive),
-    Str(Rc<str>),
-}
-
-impl From<&str> for Variable {
-    fn from(value: &str) -> Self {
-        Self::Str(value.into())
-    }
-}
-
-impl From<Primitive> for Variable {
-    fn from(value: Primitive) -> Self {
-        Self::Static(value)
-    }
-}
-
 /// The scope id acts as a path made up of indices
 /// into the scope tree.
 /// E.g `[0, 1, 0]` would point to `root.children[0].children[1].children[0]`.
@@ -371,14 +352,14 @@ impl From<Variables> for HashMap<Rc<str>, Expression> {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(test, not(target_os = "windows")))]
 pub struct ScopeDebug<'a> {
     level: usize,
     scope: &'a Scope,
     store: &'a Slab<VarId, Expression>,
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(test, not(target_os = "windows")))]
 impl DebugWriter for ScopeDebug<'_> {
     fn write(&mut self, output: &mut impl std::fmt::Write) -> std::fmt::Result {
         let indent = " ".repeat(self.level * 4);
@@ -407,10 +388,10 @@ impl DebugWriter for ScopeDebug<'_> {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(test, not(target_os = "windows")))]
 pub struct VariablesDebug<'a>(pub(crate) &'a Variables);
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(test, not(target_os = "windows")))]
 impl DebugWriter for VariablesDebug<'_> {
     fn write(&mut self, output: &mut impl std::fmt::Write) -> std::fmt::Result {
         ScopeDebug {
@@ -555,4 +536,18 @@ mod test {
         dec.add("var", [0, 1], 0);
         assert!(dec.get("var", [0, 0, 1]).is_none());
     }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn debug_scopes() {
+        let mut vars = Variables::new();
+        vars.declare("a", 1);
+        vars.push();
+        vars.declare("b", "b");
+        vars.pop();
+
+        let mut output = String::new();
+        VariablesDebug(&vars).write(&mut output).unwrap();
+        assert_eq!(output, "a: Primitive(Int(1))\n    b: Str(\"b\")\n");
+    }
 }
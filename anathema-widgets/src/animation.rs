@@ -0,0 +1,571 @@
+//! Animating values over time.
+//!
+//! A [`Transition`] describes how a value moves towards a target,
+//! either along an [`Easing`] curve for a fixed duration, or as a [`Spring`].
+//! An [`Animator`] runs a transition, and is updated from [`crate::components::Component::tick`]:
+//! ```
+//! # use std::time::Duration;
+//! # use anathema_widgets::animation::{Animator, Transition};
+//! let transition: Transition = "linear 200ms".parse().unwrap();
+//! let mut animator = Animator::new(transition, 0.0);
+//! animator.set_target(10.0);
+//! animator.update(Duration::from_millis(100));
+//! assert_eq!(animator.value(), 5.0);
+//! ```
+//!
+//! Transitions can be selected from templates, as an attribute or a state value:
+//! * `linear`, `ease_in`, `ease_out`, `ease_in_out` or `cubic_bezier(x1, y1, x2, y2)`,
+//!   optionally followed by the duration, e.g. `ease_out 300ms`
+//! * `spring(stiffness, damping)`, e.g. `spring(170, 26)`
+//!
+//! A [`Tween`] animates a number or a color from one value to another,
+//! and can write the value to the state of a component on every tick:
+//! ```
+//! # use std::time::Duration;
+//! # use anathema_state::{Color, Value};
+//! # use anathema_widgets::animation::{Transition, Tween};
+//! let mut highlight = Value::new(Color::Rgb(0, 0, 0));
+//! let mut tween = Tween::new("linear 100ms".parse().unwrap(), Color::Rgb(0, 0, 0));
+//! tween.set_target(Color::Rgb(200, 100, 0));
+//!
+//! // In `Component::tick`
+//! tween.tick(Duration::from_millis(50), &mut highlight);
+//! assert_eq!(highlight.copy_value(), Color::Rgb(100, 50, 0));
+//! ```
+//!
+//! With [reduced motion](reduced_motion) every animation completes instantly.
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use anathema_state::{AnyState, Color, CommonVal, Value};
+
+const DEFAULT_DURATION: Duration = Duration::from_millis(250);
+// How close a spring has to be to the target, and how slow, to stop
+const SPRING_REST: f64 = 0.001;
+// The largest time step of the spring simulation, in seconds
+const SPRING_STEP: f64 = 1.0 / 120.0;
+
+const MOTION_UNSET: u8 = 0;
+const MOTION_FULL: u8 = 1;
+const MOTION_REDUCED: u8 = 2;
+static REDUCED_MOTION: AtomicU8 = AtomicU8::new(MOTION_UNSET);
+
+/// Returns true if animations should complete instantly.
+///
+/// Unless set with [`set_reduced_motion`] this is read from the environment
+/// (see [`reduced_motion_from_env`]).
+pub fn reduced_motion() -> bool {
+    match REDUCED_MOTION.load(Ordering::Relaxed) {
+        MOTION_FULL => false,
+        MOTION_REDUCED => true,
+        _ => {
+            let reduced = reduced_motion_from_env(|key| std::env::var(key).ok());
+            set_reduced_motion(reduced);
+            reduced
+        }
+    }
+}
+
+/// Make all animations complete instantly (or not), for every animator.
+/// This overrides the environment.
+pub fn set_reduced_motion(reduced: bool) {
+    let value = match reduced {
+        true => MOTION_REDUCED,
+        false => MOTION_FULL,
+    };
+    REDUCED_MOTION.store(value, Ordering::Relaxed);
+}
+
+/// Returns true if `ANATHEMA_REDUCED_MOTION` is set to `1`, `true`, `yes` or `on`,
+/// using a function to look up environment variables.
+pub fn reduced_motion_from_env(var: impl Fn(&str) -> Option<String>) -> bool {
+    var("ANATHEMA_REDUCED_MOTION")
+        .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+fn parse_args<const N: usize>(s: &str, name: &str) -> Option<[f64; N]> {
+    let args = s.strip_prefix(name)?.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let mut values = [0.0; N];
+    let mut args = args.split(',');
+    for value in values.iter_mut() {
+        *value = args.next()?.trim().parse().ok()?;
+    }
+    args.next().is_none().then_some(values)
+}
+
+/// The shape of a transition from zero to one
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A CSS style cubic bézier curve from (0, 0) to (1, 1),
+    /// with the control points (x1, y1) and (x2, y2).
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl Easing {
+    /// The progress of the transition at `t`, where `t` goes from zero to one.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Self::Linear => t,
+            Self::EaseIn => Self::CubicBezier(0.42, 0.0, 1.0, 1.0).apply(t),
+            Self::EaseOut => Self::CubicBezier(0.0, 0.0, 0.58, 1.0).apply(t),
+            Self::EaseInOut => Self::CubicBezier(0.42, 0.0, 0.58, 1.0).apply(t),
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                let s = Self::solve_bezier(t, x1, x2);
+                Self::bezier(s, y1, y2)
+            }
+        }
+    }
+
+    // One dimension of the curve at `s`
+    fn bezier(s: f64, p1: f64, p2: f64) -> f64 {
+        let r = 1.0 - s;
+        3.0 * r * r * s * p1 + 3.0 * r * s * s * p2 + s * s * s
+    }
+
+    // Find where the curve is at `x`, using Newton's method and falling back on bisection
+    fn solve_bezier(x: f64, x1: f64, x2: f64) -> f64 {
+        let mut s = x;
+        for _ in 0..8 {
+            let error = Self::bezier(s, x1, x2) - x;
+            if error.abs() < 1e-7 {
+                return s;
+            }
+            let r = 1.0 - s;
+            let slope = 3.0 * r * r * x1 + 6.0 * r * s * (x2 - x1) + 3.0 * s * s * (1.0 - x2);
+            if slope.abs() < 1e-6 {
+                break;
+            }
+            s -= error / slope;
+        }
+
+        let (mut low, mut high) = (0.0, 1.0);
+        s = x;
+        for _ in 0..50 {
+            let value = Self::bezier(s, x1, x2);
+            if (value - x).abs() < 1e-7 {
+                break;
+            }
+            match value < x {
+                true => low = s,
+                false => high = s,
+            }
+            s = (low + high) / 2.0;
+        }
+        s
+    }
+}
+
+impl FromStr for Easing {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let easing = match s.trim() {
+            "linear" => Self::Linear,
+            "ease_in" => Self::EaseIn,
+            "ease_out" => Self::EaseOut,
+            "ease_in_out" => Self::EaseInOut,
+            s => {
+                let [x1, y1, x2, y2] = parse_args(s, "cubic_bezier").ok_or(())?;
+                Self::CubicBezier(x1, y1, x2, y2)
+            }
+        };
+        Ok(easing)
+    }
+}
+
+/// Spring physics: the further the value is from the target the harder it's pulled
+/// towards it (`stiffness`), while `damping` slows the value down.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Spring {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub mass: f64,
+}
+
+impl Spring {
+    pub fn new(stiffness: f64, damping: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.0,
+        }
+    }
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self::new(170.0, 26.0)
+    }
+}
+
+/// How a value moves towards a new target
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Transition {
+    /// Follow an easing curve over a fixed duration
+    Ease(Easing, Duration),
+    /// Move like a spring, taking as long as it takes to come to rest
+    Spring(Spring),
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::Ease(Easing::default(), DEFAULT_DURATION)
+    }
+}
+
+impl FromStr for Transition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some([stiffness, damping]) = parse_args(s, "spring") {
+            return Ok(Self::Spring(Spring::new(stiffness, damping)));
+        }
+
+        // An easing curve, optionally followed by the duration
+        let duration = s
+            .rsplit_once(' ')
+            .and_then(|(easing, duration)| Some((easing, duration.strip_suffix("ms")?.parse().ok()?)));
+
+        match duration {
+            Some((easing, millis)) => Ok(Self::Ease(easing.parse()?, Duration::from_millis(millis))),
+            None => Ok(Self::Ease(s.parse()?, DEFAULT_DURATION)),
+        }
+    }
+}
+
+impl TryFrom<CommonVal<'_>> for Transition {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        value.to_common_str().parse()
+    }
+}
+
+/// Animates a value towards a target using a [`Transition`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animator {
+    transition: Transition,
+    value: f64,
+    target: f64,
+    // The value when the current target was set
+    start: f64,
+    elapsed: Duration,
+    velocity: f64,
+}
+
+impl Animator {
+    pub fn new(transition: Transition, value: f64) -> Self {
+        Self {
+            transition,
+            value,
+            target: value,
+            start: value,
+            elapsed: Duration::ZERO,
+            velocity: 0.0,
+        }
+    }
+
+    /// Change the transition. A running transition continues from the current value
+    pub fn set_transition(&mut self, transition: Transition) {
+        self.transition = transition;
+        self.start = self.value;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Start moving towards a new target from the current value
+    pub fn set_target(&mut self, target: f64) {
+        self.target = target;
+        self.start = self.value;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Move to the value without a transition
+    pub fn jump_to(&mut self, value: f64) {
+        *self = Self::new(self.transition, value);
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Returns true once the value has reached the target
+    pub fn is_done(&self) -> bool {
+        self.value == self.target && self.velocity == 0.0
+    }
+
+    /// Advance the animation by `dt` and return the new value
+    pub fn update(&mut self, dt: Duration) -> f64 {
+        if self.is_done() {
+            return self.value;
+        }
+
+        if reduced_motion() {
+            self.jump_to(self.target);
+            return self.value;
+        }
+
+        match self.transition {
+            Transition::Ease(easing, duration) => {
+                self.elapsed += dt;
+                let t = match duration.is_zero() {
+                    true => 1.0,
+                    false => self.elapsed.as_secs_f64() / duration.as_secs_f64(),
+                };
+                self.value = match t >= 1.0 {
+                    true => self.target,
+                    false => self.start + (self.target - self.start) * easing.apply(t),
+                };
+            }
+            Transition::Spring(spring) => {
+                let mut remaining = dt.as_secs_f64();
+                while remaining > 0.0 {
+                    let step = remaining.min(SPRING_STEP);
+                    remaining -= step;
+
+                    let force = -spring.stiffness * (self.value - self.target) - spring.damping * self.velocity;
+                    self.velocity += force / spring.mass * step;
+                    self.value += self.velocity * step;
+                }
+
+                if (self.value - self.target).abs() < SPRING_REST && self.velocity.abs() < SPRING_REST {
+                    self.value = self.target;
+                    self.velocity = 0.0;
+                }
+            }
+        }
+
+        self.value
+    }
+}
+
+/// Values that can be interpolated by a [`Tween`]
+pub trait Lerp: Copy + PartialEq {
+    /// The value at `t` between `from` and `to`.
+    /// `t` goes from zero to one, but can overshoot with a spring.
+    fn lerp(from: Self, to: Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        f64::lerp(from as f64, to as f64, t) as f32
+    }
+}
+
+macro_rules! lerp_int {
+    ($($t:ty),*) => {
+        $(impl Lerp for $t {
+            fn lerp(from: Self, to: Self, t: f64) -> Self {
+                f64::lerp(from as f64, to as f64, t).round().clamp(<$t>::MIN as f64, <$t>::MAX as f64) as $t
+            }
+        })*
+    };
+}
+
+lerp_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Colors are interpolated as rgb.
+/// `Color::Reset` has no rgb value, so it changes to or from the other color at the end.
+impl Lerp for Color {
+    fn lerp(from: Self, to: Self, t: f64) -> Self {
+        match (from.to_rgb(), to.to_rgb()) {
+            _ if t >= 1.0 => to,
+            (Some(from), Some(to)) => Color::Rgb(
+                u8::lerp(from.0, to.0, t),
+                u8::lerp(from.1, to.1, t),
+                u8::lerp(from.2, to.2, t),
+            ),
+            _ => from,
+        }
+    }
+}
+
+/// Animates a value of any type that implements [`Lerp`], like numbers and colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    // Animates the progress from `from` to `to`, from zero to one
+    progress: Animator,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(transition: Transition, value: T) -> Self {
+        Self {
+            from: value,
+            to: value,
+            progress: Animator::new(transition, 1.0),
+        }
+    }
+
+    /// Change the transition. A running transition continues from the current value
+    pub fn set_transition(&mut self, transition: Transition) {
+        self.from = self.value();
+        self.progress = Animator::new(transition, 0.0);
+        self.progress.set_target(1.0);
+    }
+
+    /// Start moving towards a new target from the current value
+    pub fn set_target(&mut self, target: T) {
+        self.from = self.value();
+        self.to = target;
+        self.progress.jump_to(0.0);
+        self.progress.set_target(1.0);
+    }
+
+    /// Move to the value without a transition
+    pub fn jump_to(&mut self, value: T) {
+        self.from = value;
+        self.to = value;
+        self.progress.jump_to(1.0);
+    }
+
+    pub fn value(&self) -> T {
+        T::lerp(self.from, self.to, self.progress.value())
+    }
+
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    /// Returns true once the value has reached the target
+    pub fn is_done(&self) -> bool {
+        self.progress.is_done()
+    }
+
+    /// Advance the animation by `dt` and return the new value
+    pub fn update(&mut self, dt: Duration) -> T {
+        self.progress.update(dt);
+        self.value()
+    }
+
+    /// Advance the animation by `dt` and store the new value in the state value.
+    /// The value is only set if it changed, so a finished tween doesn't cause updates.
+    pub fn tick(&mut self, dt: Duration, value: &mut Value<T>)
+    where
+        T: AnyState + 'static,
+    {
+        if self.is_done() && *value.to_ref() == self.to {
+            return;
+        }
+
+        let new_value = self.update(dt);
+        if *value.to_ref() != new_value {
+            value.set(new_value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn easing_curves() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+
+        // A bézier curve along the diagonal is linear
+        let easing = Easing::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        assert!((easing.apply(0.3) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parse_transitions() {
+        assert_eq!(
+            "ease_out 300ms".parse(),
+            Ok(Transition::Ease(Easing::EaseOut, Duration::from_millis(300)))
+        );
+        assert_eq!(
+            "cubic_bezier(0.4, 0, 0.2, 1)".parse(),
+            Ok(Transition::Ease(
+                Easing::CubicBezier(0.4, 0.0, 0.2, 1.0),
+                DEFAULT_DURATION
+            ))
+        );
+        assert_eq!(
+            "spring(100, 10)".parse(),
+            Ok(Transition::Spring(Spring::new(100.0, 10.0)))
+        );
+        assert!("bounce".parse::<Transition>().is_err());
+        assert!("cubic_bezier(0.4, 0)".parse::<Transition>().is_err());
+    }
+
+    #[test]
+    fn tween_numbers_and_colors() {
+        let transition = Transition::Ease(Easing::Linear, Duration::from_millis(100));
+        let mut tween = Tween::new(transition, 0u16);
+        tween.set_target(10);
+        assert_eq!(tween.update(Duration::from_millis(30)), 3);
+
+        // A new target starts from the current value
+        tween.set_target(0);
+        assert_eq!(tween.update(Duration::from_millis(50)), 2);
+        assert_eq!(tween.update(Duration::from_millis(50)), 0);
+        assert!(tween.is_done());
+
+        let mut tween = Tween::new(transition, Color::Black);
+        tween.set_target(Color::Rgb(100, 200, 50));
+        assert_eq!(tween.update(Duration::from_millis(50)), Color::Rgb(50, 100, 25));
+        assert_eq!(tween.update(Duration::from_millis(50)), Color::Rgb(100, 200, 50));
+
+        // Reset can't be interpolated
+        tween.set_target(Color::Reset);
+        assert_eq!(tween.update(Duration::from_millis(50)), Color::Rgb(100, 200, 50));
+        assert_eq!(tween.update(Duration::from_millis(50)), Color::Reset);
+    }
+
+    #[test]
+    fn reduced_motion_env() {
+        let env = |value: &'static str| move |_: &str| Some(value.to_string());
+        assert!(reduced_motion_from_env(env("1")));
+        assert!(reduced_motion_from_env(env("True")));
+        assert!(!reduced_motion_from_env(env("0")));
+        assert!(!reduced_motion_from_env(|_| None));
+    }
+
+    #[test]
+    fn spring_comes_to_rest() {
+        let mut animator = Animator::new(Transition::Spring(Spring::default()), 0.0);
+        animator.set_target(1.0);
+
+        let mut overshoot = false;
+        for _ in 0..300 {
+            overshoot |= animator.update(Duration::from_millis(16)) > 1.0;
+        }
+        assert!(animator.is_done());
+        assert_eq!(animator.value(), 1.0);
+
+        // A spring with little damping overshoots the target
+        let mut animator = Animator::new(Transition::Spring(Spring::new(170.0, 5.0)), 0.0);
+        animator.set_target(1.0);
+        for _ in 0..30 {
+            overshoot |= animator.update(Duration::from_millis(16)) > 1.0;
+        }
+        assert!(overshoot);
+    }
+}
@@ -21,7 +21,7 @@ impl KeyEvent {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum KeyCode {
     Char(char),
     Tab,
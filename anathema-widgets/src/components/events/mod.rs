@@ -1,6 +1,8 @@
 pub use self::key::{KeyCode, KeyEvent, KeyState};
 pub use self::mouse::{MouseButton, MouseEvent, MouseState};
 
+use crate::layout::words::WordBoundary;
+
 mod key;
 mod mouse;
 
@@ -23,6 +25,42 @@ pub enum Event {
     Resize(u16, u16),
 }
 
+/// Settings for how components interpret input,
+/// see `Runtime::input`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InputSettings {
+    /// Scrolling the mouse wheel adjusts numeric inputs that are hovered or in focus
+    pub scroll_adjust: bool,
+    /// The step is multiplied by this while shift is held
+    pub coarse_factor: f64,
+    /// The step is multiplied by this while ctrl is held
+    pub fine_factor: f64,
+    /// What makes up a word when moving between words and selecting words in text
+    pub word_boundary: WordBoundary,
+}
+
+impl InputSettings {
+    /// The step to adjust a value by, given the modifiers of the event
+    pub fn step(&self, step: f64, ctrl: bool, shift: bool) -> f64 {
+        match (ctrl, shift) {
+            (true, _) => step * self.fine_factor,
+            (false, true) => step * self.coarse_factor,
+            (false, false) => step,
+        }
+    }
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            scroll_adjust: true,
+            coarse_factor: 10.0,
+            fine_factor: 0.1,
+            word_boundary: WordBoundary::Identifier,
+        }
+    }
+}
+
 impl Event {
     pub fn is_mouse_event(&self) -> bool {
         matches!(self, Self::Mouse(_))
@@ -5,6 +5,12 @@ pub struct MouseEvent {
     pub x: u16,
     pub y: u16,
     pub state: MouseState,
+    pub ctrl: bool,
+    pub shift: bool,
+    /// The position relative to the component, if the component is under the mouse.
+    /// Only the innermost component of the top-most widget under the mouse is given a position,
+    /// the rest of the components receive the event without one.
+    pub local: Option<Pos>,
 }
 
 impl MouseEvent {
@@ -13,6 +19,11 @@ impl MouseEvent {
         (self.x, self.y).into()
     }
 
+    /// Returns true if the component is under the mouse
+    pub fn is_hit(&self) -> bool {
+        self.local.is_some()
+    }
+
     /// Returns true if the left mouse button is down
     pub fn lsb_down(&self) -> bool {
         matches!(
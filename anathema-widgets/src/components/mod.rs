@@ -1,24 +1,27 @@
 use std::any::Any;
 use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
 
-use anathema_state::{AnyState, CommonVal, SharedState, State, StateId, Value};
+use anathema_state::{AnyState, CommonVal, SharedState, State, StateId, Value, ValueRef};
 use anathema_store::slab::Slab;
 use anathema_store::storage::strings::{StringId, Strings};
 use anathema_templates::WidgetComponentId;
 use flume::SendError;
 
-use self::events::{Event, KeyEvent, MouseEvent};
+use self::events::{Event, InputSettings, KeyEvent, MouseEvent};
+use self::tasks::Tasks;
 use crate::expressions::Either;
 use crate::layout::Viewport;
 use crate::nodes::ExternalState;
 use crate::widget::Parent;
-use crate::Elements;
+use crate::{Elements, WidgetId};
 
 pub mod events;
+pub mod tasks;
 
 pub type ComponentFn = dyn Fn() -> Box<dyn AnyComponent>;
 pub type StateFn = dyn FnMut() -> Box<dyn AnyState>;
@@ -63,6 +66,18 @@ impl ComponentRegistry {
         self.0.insert_at(id, comp_type);
     }
 
+    /// Replace the component and the state of a component id,
+    /// no matter if it was a component or a prototype.
+    pub fn replace_component(
+        &mut self,
+        id: WidgetComponentId,
+        component: Box<dyn AnyComponent>,
+        state: Box<dyn AnyState>,
+    ) {
+        self.0
+            .insert_at(id, ComponentType::Component(Some(component), Some(state)));
+    }
+
     /// # Panics
     ///
     /// Panics if the component isn't registered.
@@ -110,6 +125,12 @@ impl<T> From<WidgetComponentId> for ComponentId<T> {
     }
 }
 
+impl<T> From<ComponentId<T>> for WidgetComponentId {
+    fn from(value: ComponentId<T>) -> Self {
+        value.0
+    }
+}
+
 impl<T> Clone for ComponentId<T> {
     fn clone(&self) -> Self {
         *self
@@ -118,14 +139,56 @@ impl<T> Clone for ComponentId<T> {
 
 impl<T> Copy for ComponentId<T> {}
 
+/// The recipient of a message, see [`Emitter::emit_to`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Address {
+    /// A component by its id
+    Component(WidgetComponentId),
+    /// A single instance of a component, by the id of its node in the widget tree,
+    /// see [`Context::node_id`]
+    Node(WidgetId),
+    /// A component by the name it was registered with
+    Name(Box<str>),
+}
+
+impl From<WidgetComponentId> for Address {
+    fn from(value: WidgetComponentId) -> Self {
+        Self::Component(value)
+    }
+}
+
+impl<T> From<ComponentId<T>> for Address {
+    fn from(value: ComponentId<T>) -> Self {
+        Self::Component(value.0)
+    }
+}
+
+impl From<WidgetId> for Address {
+    fn from(value: WidgetId) -> Self {
+        Self::Node(value)
+    }
+}
+
+impl From<&str> for Address {
+    fn from(value: &str) -> Self {
+        Self::Name(value.into())
+    }
+}
+
+impl From<String> for Address {
+    fn from(value: String) -> Self {
+        Self::Name(value.into())
+    }
+}
+
 pub struct ViewMessage {
     pub(super) payload: Box<dyn Any + Send + Sync>,
-    pub(super) recipient: WidgetComponentId,
+    pub(super) recipient: Address,
 }
 
 impl ViewMessage {
-    pub fn recipient(&self) -> WidgetComponentId {
-        self.recipient
+    pub fn recipient(&self) -> &Address {
+        &self.recipient
     }
 
     pub fn payload(self) -> Box<dyn Any + Send + Sync> {
@@ -147,10 +210,21 @@ impl Emitter {
         &self,
         component_id: ComponentId<T>,
         value: T,
+    ) -> Result<(), SendError<ViewMessage>> {
+        self.emit_to(component_id, value)
+    }
+
+    /// Send a message to a component by its id, the node id of an instance or its name.
+    /// A message to a component that doesn't exist, or doesn't accept messages
+    /// of this type, is dropped.
+    pub fn emit_to<T: 'static + Send + Sync>(
+        &self,
+        address: impl Into<Address>,
+        value: T,
     ) -> Result<(), SendError<ViewMessage>> {
         let msg = ViewMessage {
             payload: Box::new(value),
-            recipient: component_id.0,
+            recipient: address.into(),
         };
         self.0.send(msg)
     }
@@ -162,7 +236,7 @@ impl Emitter {
     ) -> Result<(), SendError<ViewMessage>> {
         let msg = ViewMessage {
             payload: Box::new(value),
-            recipient: component_id.0,
+            recipient: component_id.into(),
         };
         self.0.send_async(msg).await
     }
@@ -172,6 +246,8 @@ pub struct Context<'rt, T> {
     inner: UntypedContext<'rt>,
     _p: PhantomData<T>,
     component_ctx: ComponentContext<'rt>,
+    // Set while handling an event, see `propagate`
+    propagate: Option<&'rt mut bool>,
 }
 
 impl<'rt, T: 'static> Context<'rt, T> {
@@ -180,6 +256,23 @@ impl<'rt, T: 'static> Context<'rt, T> {
             inner: context,
             _p: PhantomData,
             component_ctx,
+            propagate: None,
+        }
+    }
+
+    fn with_propagate(mut self, propagate: &'rt mut bool) -> Self {
+        self.propagate = Some(propagate);
+        self
+    }
+
+    /// Pass the key event on instead of handling it.
+    ///
+    /// During the capture phase the event continues down towards the focused component,
+    /// after that it bubbles up to the parent component.
+    /// The event is handled by the first component that doesn't call this.
+    pub fn propagate(&mut self) {
+        if let Some(propagate) = self.propagate.as_deref_mut() {
+            *propagate = true;
         }
     }
 
@@ -228,6 +321,42 @@ impl<'rt, T: 'static> Context<'rt, T> {
         val.and_then(|(_, val)| val.load_common_val())
     }
 
+    /// The value of the state bound to a key of the external state, e.g `state.volume` in
+    /// `@slider { value: state.volume }`.
+    /// Returns `None` unless the key is bound to state of the type `V`.
+    pub fn external<V: Clone + 'static>(&self, key: &str) -> Option<V> {
+        let value = self.external_ref(key)?.value::<V>()?;
+        value.try_as_ref().cloned()
+    }
+
+    /// Write a value back to the state bound to a key of the external state, notifying everything
+    /// that depends on the state, as if the owner of the state had set it.
+    /// Returns false unless the key is bound to state of the type `V`.
+    ///
+    /// Together with [`Context::external`] this binds the component both ways:
+    /// ```ignore
+    /// fn tick(&mut self, state: &mut Self::State, _: Elements<'_, '_>, context: Context<'_, Self::State>, _: Duration) {
+    ///     // Follow changes made by the parent
+    ///     let volume = context.external::<f64>("value");
+    ///     if let Some(volume) = volume.filter(|volume| *volume != state.volume.copy_value()) {
+    ///         state.volume.set(volume);
+    ///     }
+    /// }
+    ///
+    /// fn on_key(&mut self, key: KeyEvent, state: &mut Self::State, _: Elements<'_, '_>, context: Context<'_, Self::State>) {
+    ///     // ... change `state.volume`
+    ///     context.set_external("value", state.volume.copy_value());
+    /// }
+    /// ```
+    pub fn set_external<V: 'static>(&self, key: &str, value: V) -> bool {
+        self.external_ref(key).is_some_and(|value_ref| value_ref.set(value))
+    }
+
+    fn external_ref(&self, key: &str) -> Option<&ValueRef> {
+        let (_, value) = self.component_ctx.external_state?.get(key)?;
+        value.value_ref()
+    }
+
     /// Send a message to a given component
     pub fn emit<M: 'static + Send + Sync>(&self, recipient: ComponentId<M>, value: M) {
         self.emitter
@@ -235,11 +364,64 @@ impl<'rt, T: 'static> Context<'rt, T> {
             .expect("this will not fail unless the runtime is droped")
     }
 
+    /// Send a message to a component by its id, the node id of an instance or its name,
+    /// e.g `context.emit_to("sidebar", Message::Collapse)`
+    pub fn emit_to<M: 'static + Send + Sync>(&self, address: impl Into<Address>, value: M) {
+        self.emitter
+            .emit_to(address, value)
+            .expect("this will not fail unless the runtime is droped")
+    }
+
+    /// The id of the node of this component instance,
+    /// to send messages to this instance with [`Address::Node`]
+    pub fn node_id(&self) -> WidgetId {
+        self.component_ctx.widget_id
+    }
+
+    /// Run the future on the async runtime and send the output to this component as a message.
+    /// The future is cancelled when the component is removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime has no spawner, see `RuntimeBuilder::spawner`.
+    pub fn spawn<M, F>(&self, future: F)
+    where
+        M: 'static + Send + Sync,
+        F: Future<Output = M> + Send + 'static,
+    {
+        let recipient = ComponentId::from(self.component_ctx.component_id);
+        let owner = self.component_ctx.widget_id;
+        self.tasks.spawn(owner, self.emitter.clone(), recipient, future);
+    }
+
     /// Queue a focus call to a component that might have
     /// an attribute matching the key and value pair
     pub fn set_focus(&mut self, key: impl Into<Cow<'static, str>>, value: impl Into<CommonVal<'static>>) {
         self.component_ctx.focus_queue.push(key.into(), value.into());
     }
+
+    /// Show a named overlay on top of everything else.
+    /// Input is limited to the components inside the overlay until it's closed.
+    pub fn push_overlay(&mut self, name: impl Into<String>) {
+        self.component_ctx.overlay_queue.push(name);
+    }
+
+    /// Close the top most overlay
+    pub fn pop_overlay(&mut self) {
+        self.component_ctx.overlay_queue.pop();
+    }
+
+    /// Stop the runtime once the current events are handled.
+    /// The exit code is returned from `Runtime::run`.
+    pub fn exit(&mut self, code: i32) {
+        *self.component_ctx.exit = Some(Exit::new(code));
+    }
+
+    /// Stop the runtime with a value, e.g the choice of a picker,
+    /// that is returned from `Runtime::run` along with the exit code.
+    pub fn exit_with<V: Any + Send>(&mut self, code: i32, value: V) {
+        *self.component_ctx.exit = Some(Exit::new(code).with_value(value));
+    }
 }
 
 impl<'rt, T> Deref for Context<'rt, T> {
@@ -266,34 +448,48 @@ pub struct AnyEventCtx<'state, 'tree, 'bp> {
 #[derive(Copy, Clone)]
 pub struct UntypedContext<'rt> {
     pub emitter: &'rt Emitter,
+    pub tasks: &'rt Tasks,
     pub viewport: Viewport,
     pub strings: &'rt Strings,
+    pub input: InputSettings,
 }
 
 pub struct ComponentContext<'rt> {
     pub parent: Option<Parent>,
     pub state_id: StateId,
+    pub widget_id: WidgetId,
+    pub component_id: WidgetComponentId,
     pub assoc_functions: &'rt [(StringId, StringId)],
     pub assoc_events: &'rt mut AssociatedEvents,
     focus_queue: &'rt mut FocusQueue<'static>,
+    overlay_queue: &'rt mut OverlayQueue,
+    exit: &'rt mut Option<Exit>,
     external_state: Option<&'rt ExternalState<'rt>>,
 }
 
 impl<'rt> ComponentContext<'rt> {
     pub fn new(
         state_id: StateId,
+        widget_id: WidgetId,
+        component_id: WidgetComponentId,
         parent: Option<WidgetComponentId>,
         assoc_functions: &'rt [(StringId, StringId)],
         assoc_events: &'rt mut AssociatedEvents,
         focus_queue: &'rt mut FocusQueue<'static>,
+        overlay_queue: &'rt mut OverlayQueue,
+        exit: &'rt mut Option<Exit>,
         external_state: Option<&'rt ExternalState<'rt>>,
     ) -> Self {
         Self {
             parent: parent.map(Into::into),
             state_id,
+            widget_id,
+            component_id,
             assoc_functions,
             assoc_events,
             focus_queue,
+            overlay_queue,
+            exit,
             external_state,
         }
     }
@@ -358,6 +554,78 @@ impl<'rt> FocusQueue<'rt> {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum OverlayRequest {
+    Push(String),
+    Pop,
+}
+
+/// Overlays to open or close once the current events are handled.
+pub struct OverlayQueue {
+    requests: VecDeque<OverlayRequest>,
+}
+
+impl OverlayQueue {
+    pub fn new() -> Self {
+        Self {
+            requests: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, name: impl Into<String>) {
+        self.requests.push_back(OverlayRequest::Push(name.into()));
+    }
+
+    pub fn pop(&mut self) {
+        self.requests.push_back(OverlayRequest::Pop);
+    }
+
+    pub fn next(&mut self) -> Option<OverlayRequest> {
+        self.requests.pop_front()
+    }
+}
+
+/// The reason the runtime stopped, with the exit code and an optional value
+/// set by a component (see [`Context::exit_with`]).
+///
+/// ```ignore
+/// let exit = runtime.run();
+/// if let Some(choice) = exit.value::<String>() {
+///     println!("{choice}");
+/// }
+/// std::process::exit(exit.code);
+/// ```
+#[derive(Default)]
+pub struct Exit {
+    pub code: i32,
+    value: Option<Box<dyn Any + Send>>,
+}
+
+impl Exit {
+    pub fn new(code: i32) -> Self {
+        Self { code, value: None }
+    }
+
+    pub fn with_value<V: Any + Send>(mut self, value: V) -> Self {
+        self.value = Some(Box::new(value));
+        self
+    }
+
+    /// The value, if there is one and it's of type `V`
+    pub fn value<V: Any>(self) -> Option<V> {
+        self.value?.downcast().ok().map(|value| *value)
+    }
+}
+
+impl std::fmt::Debug for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Exit")
+            .field("code", &self.code)
+            .field("value", &self.value.is_some())
+            .finish()
+    }
+}
+
 pub trait Component {
     type State: State;
     type Message;
@@ -380,6 +648,11 @@ pub trait Component {
     ) {
     }
 
+    /// Called with the key events of the focused component, or of a child component
+    /// that passed the event on with [`Context::propagate`].
+    ///
+    /// The event is handled unless `context.propagate()` is called.
+    /// Without an implementation the event is passed on to the parent component.
     #[allow(unused_variables, unused_mut)]
     fn on_key(
         &mut self,
@@ -388,6 +661,23 @@ pub trait Component {
         mut elements: Elements<'_, '_>,
         mut context: Context<'_, Self::State>,
     ) {
+        context.propagate();
+    }
+
+    /// Called with the key events of a child component before the child gets them,
+    /// starting with the component closest to the root.
+    ///
+    /// Call `context.propagate()` to let the event through,
+    /// otherwise the event is handled and no other component gets it.
+    #[allow(unused_variables, unused_mut)]
+    fn on_capture_key(
+        &mut self,
+        key: KeyEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        context.propagate();
     }
 
     #[allow(unused_variables, unused_mut)]
@@ -410,6 +700,22 @@ pub trait Component {
     ) {
     }
 
+    /// Called with the time left of a frame once it's painted,
+    /// for low priority work like pre-computing content that isn't visible yet.
+    ///
+    /// Do a small piece of work and return `true` if there is more to do.
+    /// The runtime keeps calling this until every component returns `false`,
+    /// the frame is over or input arrives.
+    #[allow(unused_variables, unused_mut)]
+    fn idle(
+        &mut self,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) -> bool {
+        false
+    }
+
     #[allow(unused_variables, unused_mut)]
     fn message(
         &mut self,
@@ -440,6 +746,9 @@ pub trait Component {
     ) {
     }
 
+    /// Whether tab can move the focus to the component.
+    /// While the component has focus `has_focus` is true in its template,
+    /// so the template can style itself with `if has_focus`.
     fn accept_focus(&self) -> bool {
         true
     }
@@ -461,12 +770,18 @@ pub enum ComponentKind {
 }
 
 pub trait AnyComponent {
-    fn any_event(&mut self, ctx: AnyEventCtx<'_, '_, '_>, ev: Event) -> Event;
+    /// Returns the event if it wasn't handled and should bubble up to the parent component
+    fn any_event(&mut self, ctx: AnyEventCtx<'_, '_, '_>, ev: Event) -> Option<Event>;
+
+    /// Returns the event if it should continue down to the child component
+    fn any_capture(&mut self, ctx: AnyEventCtx<'_, '_, '_>, ev: Event) -> Option<Event>;
 
     fn any_message(&mut self, message: Box<dyn Any>, ctx: AnyEventCtx<'_, '_, '_>);
 
     fn any_tick(&mut self, ctx: AnyEventCtx<'_, '_, '_>, dt: Duration);
 
+    fn any_idle(&mut self, ctx: AnyEventCtx<'_, '_, '_>) -> bool;
+
     fn any_focus(&mut self, ctx: AnyEventCtx<'_, '_, '_>);
 
     fn any_blur(&mut self, ctx: AnyEventCtx<'_, '_, '_>);
@@ -483,19 +798,32 @@ where
     T: Component,
     T: 'static,
 {
-    fn any_event(&mut self, ctx: AnyEventCtx<'_, '_, '_>, event: Event) -> Event {
+    fn any_event(&mut self, ctx: AnyEventCtx<'_, '_, '_>, event: Event) -> Option<Event> {
         let state = ctx
             .state
             .and_then(|s| s.to_any_mut().downcast_mut::<T::State>())
             .expect("components always have a state");
-        let context = Context::<T::State>::new(ctx.context, ctx.component_ctx);
+        let mut propagate = false;
+        let context = Context::<T::State>::new(ctx.context, ctx.component_ctx).with_propagate(&mut propagate);
         match event {
             Event::Blur | Event::Focus => (), // Application focus, not component focus.
             Event::Key(ev) => self.on_key(ev, state, ctx.elements, context),
             Event::Mouse(ev) => self.on_mouse(ev, state, ctx.elements, context),
             Event::Resize(_, _) | Event::Noop | Event::Stop => (),
         }
-        event
+        propagate.then_some(event)
+    }
+
+    fn any_capture(&mut self, ctx: AnyEventCtx<'_, '_, '_>, event: Event) -> Option<Event> {
+        let Event::Key(ev) = event else { return Some(event) };
+        let state = ctx
+            .state
+            .and_then(|s| s.to_any_mut().downcast_mut::<T::State>())
+            .expect("components always have a state");
+        let mut propagate = false;
+        let context = Context::<T::State>::new(ctx.context, ctx.component_ctx).with_propagate(&mut propagate);
+        self.on_capture_key(ev, state, ctx.elements, context);
+        propagate.then_some(event)
     }
 
     fn any_accept_focus(&self) -> bool {
@@ -539,6 +867,15 @@ where
         self.tick(state, ctx.elements, context, dt);
     }
 
+    fn any_idle(&mut self, ctx: AnyEventCtx<'_, '_, '_>) -> bool {
+        let state = ctx
+            .state
+            .and_then(|s| s.to_any_mut().downcast_mut::<T::State>())
+            .expect("components always have a state");
+        let context = Context::<T::State>::new(ctx.context, ctx.component_ctx);
+        self.idle(state, ctx.elements, context)
+    }
+
     fn any_resize(&mut self, ctx: AnyEventCtx<'_, '_, '_>) {
         let state = ctx
             .state
@@ -0,0 +1,188 @@
+//! Futures spawned by components, see [`Context::spawn`](super::Context::spawn).
+//!
+//! Anathema doesn't run the futures itself, they are handed to a [`Spawner`]
+//! of an async runtime, e.g with tokio:
+//!
+//! ```text
+//! builder.spawner(|task| drop(tokio::spawn(task)));
+//! ```
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+
+use super::{ComponentId, Emitter};
+use crate::WidgetId;
+
+/// A future that sends its output as a message once it's done
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Runs the tasks on an async runtime
+pub trait Spawner {
+    fn spawn(&self, task: Task);
+}
+
+impl<F: Fn(Task)> Spawner for F {
+    fn spawn(&self, task: Task) {
+        self(task)
+    }
+}
+
+#[derive(Default)]
+struct Handle {
+    cancelled: AtomicBool,
+    done: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Handle {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        if let Some(waker) = self.waker.lock().expect("the lock is never poisoned").take() {
+            waker.wake();
+        }
+    }
+}
+
+// Completes with `None` once the handle is cancelled
+struct Cancellable<F> {
+    future: Pin<Box<F>>,
+    handle: Arc<Handle>,
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        if self.handle.cancelled.load(Ordering::Relaxed) {
+            return Poll::Ready(None);
+        }
+        *self.handle.waker.lock().expect("the lock is never poisoned") = Some(cx.waker().clone());
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// The running tasks, by the widget id of the component that spawned them
+#[derive(Default)]
+pub struct Tasks {
+    spawner: Option<Box<dyn Spawner>>,
+    running: RefCell<Vec<(WidgetId, Arc<Handle>)>>,
+}
+
+impl Tasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_spawner(&mut self, spawner: impl Spawner + 'static) {
+        self.spawner = Some(Box::new(spawner));
+    }
+
+    /// Spawn the future and send the output to the recipient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no spawner.
+    pub fn spawn<M, F>(&self, owner: WidgetId, emitter: Emitter, recipient: ComponentId<M>, future: F)
+    where
+        M: 'static + Send + Sync,
+        F: Future<Output = M> + Send + 'static,
+    {
+        let spawner = self
+            .spawner
+            .as_ref()
+            .expect("spawning a future requires a spawner, see `RuntimeBuilder::spawner`");
+
+        let handle = Arc::new(Handle::default());
+        let cancellable = Cancellable {
+            future: Box::pin(future),
+            handle: handle.clone(),
+        };
+
+        let mut running = self.running.borrow_mut();
+        running.retain(|(_, handle)| !handle.done.load(Ordering::Relaxed));
+        running.push((owner, handle.clone()));
+
+        spawner.spawn(Box::pin(async move {
+            if let Some(message) = cancellable.await {
+                // Can only fail once the runtime is gone
+                let _ = emitter.emit(recipient, message);
+            }
+            handle.done.store(true, Ordering::Relaxed);
+        }));
+    }
+
+    /// Cancel the tasks of the component.
+    /// A cancelled task completes the next time it's polled, without sending a message.
+    pub fn cancel(&self, owner: WidgetId) {
+        self.running.borrow_mut().retain(|(id, handle)| {
+            if *id == owner {
+                handle.cancel();
+            }
+            *id != owner
+        });
+    }
+
+    /// Cancel all the tasks
+    pub fn cancel_all(&self) {
+        self.running
+            .borrow_mut()
+            .drain(..)
+            .for_each(|(_, handle)| handle.cancel());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+    use std::task::Context;
+
+    use anathema_templates::WidgetComponentId;
+
+    use super::*;
+
+    // Polls every task once
+    fn poll_all(tasks: &RefCell<Vec<Task>>) {
+        let mut cx = Context::from_waker(Waker::noop());
+        tasks
+            .borrow_mut()
+            .retain_mut(|task| task.as_mut().poll(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn spawn_and_cancel() {
+        let (sender, receiver) = flume::unbounded();
+        let emitter = Emitter::from(sender);
+        let recipient = ComponentId::<&str>::from(WidgetComponentId::from(0));
+        let (first, second) = (WidgetId::from((0, 0)), WidgetId::from((1, 0)));
+
+        let queued = Rc::new(RefCell::new(Vec::<Task>::new()));
+        let mut tasks = Tasks::new();
+        tasks.set_spawner({
+            let queued = queued.clone();
+            move |task| queued.borrow_mut().push(task)
+        });
+
+        // A future that is pending until the value is sent
+        let (done, wait) = flume::unbounded::<()>();
+        tasks.spawn(first, emitter.clone(), recipient, async move {
+            let _ = wait.recv_async().await;
+            "first"
+        });
+        tasks.spawn(second, emitter, recipient, async { "second" });
+
+        poll_all(&queued);
+        let message = receiver.try_recv().unwrap();
+        assert_eq!(*message.payload().downcast::<&str>().unwrap(), "second");
+        assert_eq!(queued.borrow().len(), 1);
+
+        // The cancelled task doesn't send a message
+        tasks.cancel(first);
+        done.send(()).unwrap();
+        poll_all(&queued);
+        assert!(queued.borrow().is_empty());
+        assert!(receiver.try_recv().is_err());
+    }
+}
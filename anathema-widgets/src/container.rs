@@ -1,9 +1,9 @@
 use anathema_geometry::{LocalPos, Pos, Rect, Size};
 
-use crate::layout::{Constraints, LayoutCtx, PositionCtx, Viewport};
-use crate::paint::{PaintCtx, Unsized};
+use crate::layout::{Constraints, LayoutCtx, PositionCtx, Spacing, Viewport};
+use crate::paint::{Background, Gradient, Overflow, PaintCtx, Unsized, BACKGROUND_CHAR, OVERFLOW};
 use crate::widget::{AnyWidget, PositionChildren};
-use crate::{AttributeStorage, LayoutChildren, PaintChildren, WidgetId};
+use crate::{AttributeStorage, LayoutChildren, PaintChildren, UserData, WidgetId};
 
 #[derive(Debug)]
 pub struct Container {
@@ -12,6 +12,9 @@ pub struct Container {
     pub size: Size,
     pub pos: Pos,
     pub inner_bounds: Rect,
+    pub padding: Spacing,
+    pub margin: Spacing,
+    pub user_data: UserData,
     pub needs_layout: bool,
     pub needs_position: bool,
 }
@@ -24,18 +27,56 @@ impl Container {
         ctx: &mut LayoutCtx<'_, 'bp>,
     ) -> Size {
         if !self.needs_layout {
-            return self.size;
+            return self.outer_size();
         }
         self.needs_layout = false;
         self.needs_position = true;
 
-        self.size = self.inner.any_layout(children, constraints, self.id, ctx);
         // Floating widgets always report a zero size
         // as they should not affect their parents
-        match self.inner.any_floats() {
-            true => Size::ZERO,
-            false => self.size,
+        if self.inner.any_floats() {
+            self.size = self.inner.any_layout(children, constraints, self.id, ctx);
+            return Size::ZERO;
         }
+
+        // The widget is laid out inside the padding and the margin, which are added on the
+        // outside of the size of the widget, within the constraints of the parent
+        let attributes = ctx.attribs.get(self.id);
+        self.padding = self.inner.any_padding(attributes);
+        self.margin = Spacing::margin(attributes);
+
+        let margin = self.margin.size();
+        let spacing = self.padding.size() + margin;
+        let mut inner_constraints = constraints;
+        inner_constraints.sub_max_width(spacing.width);
+        inner_constraints.sub_max_height(spacing.height);
+        inner_constraints.min_width = constraints.min_width.saturating_sub(spacing.width);
+        inner_constraints.min_height = constraints.min_height.saturating_sub(spacing.height);
+
+        let size = self.inner.any_layout(children, inner_constraints, self.id, ctx);
+        let outer = Size::new(
+            (size.width + spacing.width).min(constraints.max_width()),
+            (size.height + spacing.height).min(constraints.max_height()),
+        );
+        self.size = Size::new(
+            outer.width.saturating_sub(margin.width),
+            outer.height.saturating_sub(margin.height),
+        );
+        outer
+    }
+
+    // The size including the margin
+    fn outer_size(&self) -> Size {
+        self.size + self.margin.size()
+    }
+
+    // The size of the area inside the padding
+    fn content_size(&self) -> Size {
+        let padding = self.padding.size();
+        Size::new(
+            self.size.width.saturating_sub(padding.width),
+            self.size.height.saturating_sub(padding.height),
+        )
     }
 
     pub fn position<'bp>(
@@ -45,19 +86,22 @@ impl Container {
         attribute_storage: &AttributeStorage<'bp>,
         viewport: Viewport,
     ) {
+        let pos = pos + self.margin.offset();
         if !self.needs_position && pos == self.pos {
             return;
         }
         self.needs_position = false;
 
         self.pos = pos;
+        let pos = pos + self.padding.offset();
+        let size = self.content_size();
         let ctx = PositionCtx {
-            inner_size: self.size,
+            inner_size: size,
             pos,
             viewport,
         };
         self.inner.any_position(children, self.id, attribute_storage, ctx);
-        self.inner_bounds = self.inner.any_inner_bounds(self.pos, self.size);
+        self.inner_bounds = self.inner.any_inner_bounds(pos, size);
     }
 
     pub fn paint<'bp>(
@@ -69,17 +113,43 @@ impl Container {
         if self.needs_layout || self.needs_position {
             return;
         }
-        let mut ctx = ctx.into_sized(self.size, self.pos);
-        let region = ctx.create_region();
-        ctx.set_clip_region(region);
-
         let attrs = attribute_storage.get(self.id);
+        let clip = !matches!(attrs.get(OVERFLOW), Some(Overflow::Visible));
 
-        // Apply all attributes
+        let mut ctx = ctx.into_sized(self.size, self.pos);
+        if clip {
+            let region = ctx.create_region();
+            ctx.set_clip_region(region);
+        }
+
+        // Apply all attributes, and fill the region before the children are painted
+        let gradient = Gradient::from_attributes(attrs);
+        let fill = attrs.get_ref::<&str>(BACKGROUND_CHAR).and_then(|s| s.chars().next());
         for y in 0..self.size.height as u16 {
             for x in 0..self.size.width as u16 {
                 let pos = LocalPos::new(x, y);
                 ctx.set_attributes(attrs, pos);
+                if let Some(gradient) = gradient {
+                    ctx.set_attributes(&Background(gradient.at(pos, self.size)), pos);
+                }
+            }
+
+            if let Some(fill) = fill {
+                let mut pos = LocalPos::new(0, y);
+                while (pos.x as usize) < self.size.width {
+                    match ctx.place_glyph(fill, pos) {
+                        Some(next) if next.x > pos.x => pos = next,
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        if self.padding != Spacing::ZERO {
+            ctx.update(self.content_size(), self.pos + self.padding.offset());
+            if clip {
+                let region = ctx.create_region();
+                ctx.set_clip_region(region);
             }
         }
 
@@ -37,6 +37,26 @@ impl DebugWriter for EvalValueDebug<'_> {
             EvalValue::Op(_, _, _) => todo!(),
             EvalValue::Not(_) => todo!(),
             EvalValue::Equality(_, _, _) => todo!(),
+            EvalValue::Conditional(cond, then, otherwise) => {
+                EvalValueDebug(cond).write(output)?;
+                write!(output, " ? ")?;
+                EvalValueDebug(then).write(output)?;
+                write!(output, " : ")?;
+                EvalValueDebug(otherwise).write(output)
+            }
+            EvalValue::Coalesce(value, fallback) => {
+                EvalValueDebug(value).write(output)?;
+                write!(output, " ?? ")?;
+                EvalValueDebug(fallback).write(output)
+            }
+            EvalValue::Call(fun, args) => {
+                write!(output, "{fun:?}(")?;
+                args.iter().for_each(|val| {
+                    EvalValueDebug(val).write(output).unwrap();
+                    write!(output, ", ").unwrap();
+                });
+                write!(output, ")")
+            }
         }
     }
 }
@@ -81,6 +101,8 @@ impl DebugWriter for ForDebug<'_> {
             }
             crate::values::Collection::Static(_) => write!(output, " <value> "),
             crate::values::Collection::Future => write!(output, " <future> "),
+            crate::values::Collection::Range(..) => write!(output, " <range> "),
+            crate::values::Collection::Reversed(_) => write!(output, " <reversed> "),
             crate::values::Collection::Index(_, _) => todo!(),
         }?;
         write!(output, ">")
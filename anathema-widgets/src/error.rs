@@ -1,23 +1,43 @@
 use std::fmt::Display;
 
+use crate::layout::LayoutError;
+use crate::AttributeType;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
     InvalidElement(String),
+    InvalidAttribute {
+        widget: String,
+        attribute: String,
+        expected: AttributeType,
+    },
     TreeTransactionFailed,
     ComponentConsumed,
+    /// An error found while laying out the widget at the path
+    Layout {
+        widget: String,
+        path: Box<[u16]>,
+        error: LayoutError,
+    },
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::InvalidElement(el) => write!(f, "element `{el}` does not exist"),
+            Error::InvalidAttribute {
+                widget,
+                attribute,
+                expected,
+            } => write!(f, "`{attribute}` on `{widget}` should be {expected}"),
             Error::TreeTransactionFailed => write!(
                 f,
                 "failed to insert into the widget tree (most likely the parent was removed)"
             ),
             Error::ComponentConsumed => write!(f, "this component has already been used"),
+            Error::Layout { widget, path, error } => write!(f, "`{widget}` at {path:?}: {error}"),
         }
     }
 }
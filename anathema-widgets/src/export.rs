@@ -0,0 +1,113 @@
+//! Export the content of an element as plain text, e.g to save a report.
+use anathema_geometry::{Pos, Size};
+use anathema_store::tree::{Node, TreeValues};
+use unicode_width::UnicodeWidthChar;
+
+use crate::layout::{layout_widget, position_widget, Constraints, LayoutCtx, Viewport};
+use crate::nodes::element::Element;
+use crate::paint::{paint, CellAttributes};
+use crate::{AttributeStorage, WidgetKind, WidgetRenderer};
+
+// Painting is done in `u16` coordinates
+const MAX_HEIGHT: usize = u16::MAX as usize;
+
+/// Lay out the element at `width`, as tall as the content, and return what was painted as
+/// plain text: one line per row, without trailing whitespace and without any attributes.
+///
+/// The element and its children are laid out again on the next frame.
+/// See [`Elements::export_text`](crate::Elements::export_text) to export an element of the tree.
+pub fn export_text<'bp>(
+    element: &mut Element<'bp>,
+    children: &[Node],
+    values: &mut TreeValues<WidgetKind<'bp>>,
+    attribute_storage: &AttributeStorage<'bp>,
+    width: usize,
+) -> String {
+    let viewport = Viewport::new((width, MAX_HEIGHT));
+
+    needs_layout(element, children, values);
+    let mut ctx = LayoutCtx::new(attribute_storage, &viewport);
+    layout_widget(
+        element,
+        children,
+        values,
+        Constraints::new(width, MAX_HEIGHT),
+        &mut ctx,
+        true,
+    );
+    position_widget(Pos::ZERO, element, children, values, attribute_storage, true, viewport);
+
+    let mut surface = TextSurface::new(Size::new(width, element.size().height));
+    paint(&mut surface, element, children, values, attribute_storage, true);
+
+    // Reset the layout for the next frame
+    needs_layout(element, children, values);
+
+    surface.to_text()
+}
+
+fn needs_layout(element: &mut Element<'_>, children: &[Node], values: &mut TreeValues<WidgetKind<'_>>) {
+    element.container.needs_layout = true;
+    mark_children(children, values);
+}
+
+fn mark_children(nodes: &[Node], values: &mut TreeValues<WidgetKind<'_>>) {
+    for node in nodes {
+        if let Some((_, WidgetKind::Element(el))) = values.get_mut(node.value()) {
+            el.container.needs_layout = true;
+        }
+        mark_children(node.children(), values);
+    }
+}
+
+struct TextSurface {
+    size: Size,
+    buffer: Vec<String>,
+}
+
+impl TextSurface {
+    fn new(size: Size) -> Self {
+        Self {
+            buffer: vec![" ".to_string(); size.width * size.height],
+            size,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let mut output = String::new();
+        for row in self.buffer.chunks(self.size.width.max(1)) {
+            let mut line = String::new();
+            // The cell after a wide char is covered by the char
+            let mut x = 0;
+            while x < row.len() {
+                line.push_str(&row[x]);
+                x += row[x].chars().next().and_then(|c| c.width()).unwrap_or(1).max(1);
+            }
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+impl WidgetRenderer for TextSurface {
+    fn draw_glyph(&mut self, c: char, local_pos: Pos) {
+        let (x, y) = (local_pos.x as usize, local_pos.y as usize);
+        if x < self.size.width && y < self.size.height {
+            self.buffer[y * self.size.width + x] = c.to_string();
+        }
+    }
+
+    fn draw_cluster(&mut self, cluster: &str, local_pos: Pos) {
+        let (x, y) = (local_pos.x as usize, local_pos.y as usize);
+        if x < self.size.width && y < self.size.height {
+            self.buffer[y * self.size.width + x] = cluster.to_string();
+        }
+    }
+
+    fn set_attributes(&mut self, _: &dyn CellAttributes, _: Pos) {}
+
+    fn size(&self) -> Size {
+        self.size
+    }
+}
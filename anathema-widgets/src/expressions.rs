@@ -6,10 +6,14 @@ use anathema_state::{register_future, CommonVal, Number, Path, PendingValue, Sha
 use anathema_templates::expressions::{Equality, Op};
 use anathema_templates::{Expression, Globals};
 
+use crate::functions::Function;
 use crate::scope::{Scope, ScopeLookup};
 use crate::values::{Collection, ValueId};
 use crate::Value;
 
+// Iterate over a collection in reverse order, e.g `for x in reverse(list)`
+const REVERSE: &str = "reverse";
+
 pub(crate) fn future_value<'a>(id: ValueId) -> EvalValue<'a> {
     register_future(id);
     EvalValue::Empty
@@ -18,6 +22,8 @@ pub(crate) fn future_value<'a>(id: ValueId) -> EvalValue<'a> {
 pub enum Either<'a> {
     Static(CommonVal<'a>),
     Dyn(SharedState<'a>),
+    /// The output of a function, e.g `upper(name)`
+    Owned(String),
 }
 
 impl<'a> Either<'a> {
@@ -25,6 +31,7 @@ impl<'a> Either<'a> {
         match self {
             Either::Static(val) => val.to_bool(),
             Either::Dyn(state) => state.to_common().map(|v| v.to_bool()).unwrap_or(false),
+            Either::Owned(s) => !s.is_empty(),
         }
     }
 
@@ -32,6 +39,7 @@ impl<'a> Either<'a> {
         match self {
             Either::Static(val) => val.to_number(),
             Either::Dyn(state) => state.to_common().and_then(|v| v.to_number()),
+            Either::Owned(_) => None,
         }
     }
 
@@ -45,6 +53,7 @@ impl<'a> Either<'a> {
                 CommonVal::Str(s) => Some(Path::Key(s)),
                 _ => None,
             },
+            Either::Owned(s) => Some(Path::Key(s)),
         }
     }
 
@@ -52,6 +61,7 @@ impl<'a> Either<'a> {
         match self {
             Either::Static(val) => Some(*val),
             Either::Dyn(state) => state.to_common(),
+            Either::Owned(s) => Some(CommonVal::Str(s)),
         }
     }
 }
@@ -97,6 +107,13 @@ pub enum EvalValue<'bp> {
     Not(Box<Self>),
     Equality(Box<Self>, Box<Self>, Equality),
 
+    // Function call
+    Call(Function, Box<[Self]>),
+
+    // Conditionals, see `EvalValue::select`
+    Conditional(Box<Self>, Box<Self>, Box<Self>),
+    Coalesce(Box<Self>, Box<Self>),
+
     Empty,
 }
 
@@ -128,16 +145,55 @@ impl<'bp> EvalValue<'bp> {
                 rhs.copy_with_sub(value_id).into(),
                 *eq,
             ),
+            Self::Call(fun, args) => Self::Call(*fun, args.iter().map(|arg| arg.copy_with_sub(value_id)).collect()),
+            Self::Conditional(cond, then, otherwise) => Self::Conditional(
+                cond.copy_with_sub(value_id).into(),
+                then.copy_with_sub(value_id).into(),
+                otherwise.copy_with_sub(value_id).into(),
+            ),
+            Self::Coalesce(value, fallback) => Self::Coalesce(
+                value.copy_with_sub(value_id).into(),
+                fallback.copy_with_sub(value_id).into(),
+            ),
             Self::Empty => Self::Empty,
         }
     }
 
+    // The branch that is used by a conditional (`cond ? then : otherwise`)
+    // or a coalesce (`value ?? fallback`).
+    // Both branches are resolved, so a change to either of them updates the value.
+    fn select(&self) -> &Self {
+        match self {
+            Self::Conditional(cond, then, otherwise) => match cond.load_bool() {
+                true => then.select(),
+                false => otherwise.select(),
+            },
+            Self::Coalesce(value, fallback) => match value.is_missing() {
+                true => fallback.select(),
+                false => value.select(),
+            },
+            value => value,
+        }
+    }
+
+    // The value doesn't exist (yet), is `None`, or can't be evaluated, e.g division by zero
+    fn is_missing(&self) -> bool {
+        match self.select() {
+            Self::Empty => true,
+            Self::Dyn(value) => value.as_state().is_none_or(|state| state.is_none()),
+            Self::Index(value, _) => value.is_missing(),
+            Self::Static(_) | Self::ExprMap(_) | Self::ExprList(_) => false,
+            value => value.load_common_val().is_none(),
+        }
+    }
+
     fn get(&self, path: Path<'_>, value_id: ValueId) -> Option<EvalValue<'bp>> {
         match self {
             EvalValue::Dyn(value) => Some(EvalValue::Dyn(
                 value.as_state().and_then(|state| state.state_get(path, value_id))?,
             )),
             EvalValue::Index(value, _) => value.get(path, value_id),
+            EvalValue::Conditional(..) | EvalValue::Coalesce(..) => self.select().get(path, value_id),
             EvalValue::Pending(_) => {
                 unreachable!("pending values are resolved by the scope and should never exist here")
             }
@@ -154,6 +210,7 @@ impl<'bp> EvalValue<'bp> {
             | EvalValue::Op(_, _, _)
             | EvalValue::Not(_)
             | EvalValue::Equality(_, _, _)
+            | EvalValue::Call(..)
             | EvalValue::Empty => None,
         }
     }
@@ -184,6 +241,15 @@ impl<'bp> EvalValue<'bp> {
                 let rhs = rhs.inner_downgrade().into();
                 Self::Equality(lhs, rhs, *eq)
             }
+            Self::Call(fun, args) => Self::Call(*fun, args.iter().map(Self::inner_downgrade).collect()),
+            Self::Conditional(cond, then, otherwise) => Self::Conditional(
+                cond.inner_downgrade().into(),
+                then.inner_downgrade().into(),
+                otherwise.inner_downgrade().into(),
+            ),
+            Self::Coalesce(value, fallback) => {
+                Self::Coalesce(value.inner_downgrade().into(), fallback.inner_downgrade().into())
+            }
             Self::Empty => Self::Empty,
         }
     }
@@ -220,6 +286,16 @@ impl<'bp> EvalValue<'bp> {
                 let rhs = rhs.inner_upgrade(value_id).into();
                 Self::Equality(lhs, rhs, *eq)
             }
+            Self::Call(fun, args) => Self::Call(*fun, args.iter().map(|arg| arg.inner_upgrade(value_id)).collect()),
+            Self::Conditional(cond, then, otherwise) => Self::Conditional(
+                cond.inner_upgrade(value_id).into(),
+                then.inner_upgrade(value_id).into(),
+                otherwise.inner_upgrade(value_id).into(),
+            ),
+            Self::Coalesce(value, fallback) => Self::Coalesce(
+                value.inner_upgrade(value_id).into(),
+                fallback.inner_upgrade(value_id).into(),
+            ),
             Self::Empty => future_value(value_id),
         }
     }
@@ -237,10 +313,7 @@ impl<'bp> EvalValue<'bp> {
             ControlFlow::Continue(())
         };
 
-        match self.internal_str_iter(&mut wrapped_f) {
-            Some(control_flow) => control_flow,
-            None => ControlFlow::Break(()),
-        };
+        let _ = self.internal_str_iter(&mut wrapped_f);
     }
 
     pub fn str_iter<F>(&self, mut f: F) -> ControlFlow<()>
@@ -260,7 +333,7 @@ impl<'bp> EvalValue<'bp> {
         let val = match self {
             EvalValue::ExprList(list) => {
                 for value in list.iter() {
-                    value.internal_str_iter(f)?;
+                    let _ = value.internal_str_iter(f)?;
                 }
                 ControlFlow::Continue(())
             }
@@ -277,6 +350,7 @@ impl<'bp> EvalValue<'bp> {
                 f(s)
             }
             EvalValue::Index(val, _) => val.internal_str_iter(f)?,
+            EvalValue::Conditional(..) | EvalValue::Coalesce(..) => self.select().internal_str_iter(f)?,
             _ => {
                 let val = self.load_common_val()?;
                 let val = val.to_common()?;
@@ -303,6 +377,11 @@ impl<'bp> EvalValue<'bp> {
             EvalValue::Op(lhs, rhs, op) => {
                 let lhs = lhs.load_number()?;
                 let rhs = rhs.load_number()?;
+                // Integer division by zero has no value
+                let integers = !lhs.is_float() && !rhs.is_float();
+                if integers && rhs.as_int() == 0 && matches!(op, Op::Div | Op::Mod) {
+                    return None;
+                }
                 let res = match *op {
                     Op::Add => lhs + rhs,
                     Op::Sub => lhs - rhs,
@@ -317,16 +396,8 @@ impl<'bp> EvalValue<'bp> {
             EvalValue::Not(val) => Some(CommonVal::from(!val.load_bool()).into()),
             EvalValue::Equality(lhs, rhs, eq) => {
                 let b = match eq {
-                    Equality::Eq => {
-                        let lhs = lhs.load_common_val()?;
-                        let rhs = rhs.load_common_val()?;
-                        lhs.to_common()? == rhs.to_common()?
-                    }
-                    Equality::NotEq => {
-                        let lhs = lhs.load_common_val()?;
-                        let rhs = rhs.load_common_val()?;
-                        lhs.to_common()? != rhs.to_common()?
-                    }
+                    Equality::Eq => is_equal(lhs, rhs)?,
+                    Equality::NotEq => !is_equal(lhs, rhs)?,
                     Equality::And => lhs.load_bool() && rhs.load_bool(),
                     Equality::Or => lhs.load_bool() || rhs.load_bool(),
                     Equality::Gt => lhs.load_number()? > rhs.load_number()?,
@@ -336,24 +407,31 @@ impl<'bp> EvalValue<'bp> {
                 };
                 Some(CommonVal::from(b).into())
             }
+
+            // Function call
+            EvalValue::Call(fun, args) => fun.call(args).map(Either::Owned),
+
+            // Conditionals
+            EvalValue::Conditional(..) | EvalValue::Coalesce(..) => self.select().load_common_val(),
             EvalValue::Empty => None,
         }
     }
 
-    pub(crate) fn load_bool(&self) -> bool {
-        let Some(value) = self.load_common_val() else { return false };
-        match value {
-            Either::Static(val) => val.to_bool(),
-            Either::Dyn(state) => (*state).to_common().map(|v| v.to_bool()).unwrap_or(false),
+    // The state the value comes from, if it comes straight from state
+    pub(crate) fn value_ref(&self) -> Option<&ValueRef> {
+        match self {
+            EvalValue::Dyn(value) => Some(value),
+            EvalValue::Index(value, _) => value.value_ref(),
+            _ => None,
         }
     }
 
+    pub(crate) fn load_bool(&self) -> bool {
+        self.load_common_val().is_some_and(|value| value.load_bool())
+    }
+
     pub(crate) fn load_number(&self) -> Option<Number> {
-        let val = self.load_common_val()?;
-        match val {
-            Either::Static(val) => val.to_number(),
-            Either::Dyn(state) => (*state).to_common().and_then(|v| v.to_number()),
-        }
+        self.load_common_val()?.load_number()
     }
 
     // Load a value from an expression.
@@ -375,20 +453,7 @@ impl<'bp> EvalValue<'bp> {
                 None => val.as_state()?.to_common()?.try_into().ok(),
             },
             EvalValue::Index(val, _) => val.load::<T>(),
-            EvalValue::Op(lhs, rhs, op) => {
-                let lhs = lhs.load_number()?;
-                let rhs = rhs.load_number()?;
-                let res = match *op {
-                    Op::Add => Some(lhs + rhs),
-                    Op::Sub => Some(lhs - rhs),
-                    Op::Mul => Some(lhs * rhs),
-                    Op::Div => Some(lhs / rhs),
-                    Op::Mod => Some(lhs % rhs),
-                };
-
-                T::try_from(res?.into()).ok()
-            }
-            expr @ EvalValue::Negative(_) => {
+            expr @ (EvalValue::Op(..) | EvalValue::Negative(_)) => {
                 let val = expr.load_number()?;
                 T::try_from(val.into()).ok()
             }
@@ -400,6 +465,11 @@ impl<'bp> EvalValue<'bp> {
                 let val = CommonVal::Bool(s.load_bool());
                 T::try_from(val).ok()
             }
+            EvalValue::Call(fun, args) => {
+                let val = fun.call(args)?;
+                T::try_from(CommonVal::Str(&val)).ok()
+            }
+            EvalValue::Conditional(..) | EvalValue::Coalesce(..) => self.select().load::<T>(),
             EvalValue::Empty => None,
             e => panic!("{e:?}"),
         }
@@ -411,6 +481,10 @@ impl<'bp> EvalValue<'bp> {
         match self {
             Self::Index(..) => true,
             Self::ExprList(list) => list.iter().any(Self::contains_index),
+            Self::Conditional(cond, then, otherwise) => {
+                cond.contains_index() || then.contains_index() || otherwise.contains_index()
+            }
+            Self::Coalesce(value, fallback) => value.contains_index() || fallback.contains_index(),
             Self::ExprMap(_) => todo!(),
             _ => false,
         }
@@ -593,6 +667,15 @@ impl<'bp> ValueResolver<'bp> {
                 self.reset_offset().resolve(rhs, scope, states).into(),
                 *eq,
             ),
+            E::Conditional(cond, then, otherwise) => V::Conditional(
+                self.reset_offset().resolve(cond, scope, states).into(),
+                self.reset_offset().resolve(then, scope, states).into(),
+                self.reset_offset().resolve(otherwise, scope, states).into(),
+            ),
+            E::Coalesce(value, fallback) => V::Coalesce(
+                self.reset_offset().resolve(value, scope, states).into(),
+                self.reset_offset().resolve(fallback, scope, states).into(),
+            ),
 
             // -----------------------------------------------------------------------------
             //   - Maths -
@@ -608,11 +691,34 @@ impl<'bp> ValueResolver<'bp> {
             // -----------------------------------------------------------------------------
             //   - Function call -
             // -----------------------------------------------------------------------------
-            E::Call { fun: _, args: _ } => todo!(),
+            // Ranges are only resolved as the collection of a loop, see `eval_collection`
+            E::Range(..) => V::Empty,
+
+            // See `crate::functions` for the functions
+            E::Call { fun, args } => {
+                let Expression::Ident(name) = &**fun else { return V::Empty };
+                let Some(fun) = Function::from_name(name) else { return V::Empty };
+                let args = args
+                    .iter()
+                    .map(|arg| self.reset_offset().resolve(arg, scope, states))
+                    .collect();
+                V::Call(fun, args)
+            }
         }
     }
 }
 
+// Numbers are equal by value, e.g `1 == 1.0`
+fn is_equal(lhs: &EvalValue<'_>, rhs: &EvalValue<'_>) -> Option<bool> {
+    let lhs = lhs.load_common_val()?;
+    let rhs = rhs.load_common_val()?;
+    let (lhs, rhs) = (lhs.to_common()?, rhs.to_common()?);
+    match (lhs.to_number(), rhs.to_number()) {
+        (Some(lhs), Some(rhs)) => Some(lhs == rhs),
+        _ => Some(lhs == rhs),
+    }
+}
+
 pub(crate) fn eval<'bp>(
     expr: &'bp Expression,
     globals: &'bp Globals,
@@ -632,9 +738,35 @@ pub(crate) fn eval_collection<'bp>(
     states: &States,
     value_id: ValueId,
 ) -> Value<'bp, Collection<'bp>> {
-    let value = ValueResolver::new(globals, value_id).resolve(expr, scope, states);
+    let collection = resolve_collection(expr, globals, scope, states, value_id);
+    Value::new(collection, Some(expr))
+}
 
-    let collection = match value {
+fn resolve_collection<'bp>(
+    expr: &'bp Expression,
+    globals: &'bp Globals,
+    scope: &Scope<'bp>,
+    states: &States,
+    value_id: ValueId,
+) -> Collection<'bp> {
+    match expr {
+        Expression::Range(start, end) => {
+            let start = ValueResolver::new(globals, value_id).resolve(start, scope, states);
+            let end = ValueResolver::new(globals, value_id).resolve(end, scope, states);
+            return Collection::Range(start.into(), end.into());
+        }
+        Expression::Call { fun, args } => match (&**fun, &**args) {
+            (Expression::Ident(fun), [collection]) if &**fun == REVERSE => {
+                let collection = resolve_collection(collection, globals, scope, states, value_id);
+                return Collection::Reversed(collection.into());
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+
+    let value = ValueResolver::new(globals, value_id).resolve(expr, scope, states);
+    match value {
         EvalValue::Dyn(val) => Collection::Dyn(val),
         EvalValue::ExprList(list) => Collection::Static(list),
         EvalValue::Index(list, rhs) => match *list {
@@ -643,9 +775,7 @@ pub(crate) fn eval_collection<'bp>(
             _ => Collection::Future,
         },
         _ => Collection::Future,
-    };
-
-    Value::new(collection, Some(expr))
+    }
 }
 
 #[cfg(test)]
@@ -653,8 +783,8 @@ mod test {
 
     use anathema_state::{List, Map, Value};
     use anathema_templates::expressions::{
-        add, and, eq, greater_than, greater_than_equal, ident, index, less_than, less_than_equal, mul, neg, not, num,
-        or, strlit, sub,
+        add, and, coalesce, conditional, div, eq, float, greater_than, greater_than_equal, ident, index, less_than,
+        less_than_equal, modulo, mul, neg, not, num, or, strlit, sub,
     };
 
     use crate::testing::ScopedTest;
@@ -853,4 +983,68 @@ mod test {
                 assert!(b);
             });
     }
+
+    #[test]
+    fn compare_numbers_of_different_types() {
+        ScopedTest::new()
+            .with_value("a", 1usize)
+            .with_expr(and(greater_than(ident("a"), float(0.5)), eq(ident("a"), float(1.0))))
+            .eval(|value| {
+                let b = value.load::<bool>().unwrap();
+                assert!(b);
+            });
+    }
+
+    #[test]
+    fn division_by_zero() {
+        ScopedTest::new()
+            .with_value("a", 1)
+            .with_expr(div(ident("a"), num(0)))
+            .eval(|value| assert!(value.load::<i32>().is_none()));
+
+        ScopedTest::new()
+            .with_value("a", 1)
+            .with_expr(modulo(ident("a"), num(0)))
+            .eval(|value| assert!(value.load::<i32>().is_none()));
+    }
+
+    #[test]
+    fn conditional_value() {
+        ScopedTest::new()
+            .with_value("a", 5)
+            .with_expr(conditional(
+                greater_than(ident("a"), num(3)),
+                strlit("many"),
+                strlit("few"),
+            ))
+            .eval(|value| {
+                let mut output = String::new();
+                value.str_for_each(|s| output.push_str(s));
+                assert_eq!(output, "many");
+            });
+
+        ScopedTest::new()
+            .with_value("a", 1)
+            .with_expr(conditional(eq(ident("a"), num(0)), num(1), add(ident("a"), num(1))))
+            .eval(|value| assert_eq!(value.load::<i32>().unwrap(), 2));
+    }
+
+    #[test]
+    fn coalesce_missing_values() {
+        ScopedTest::new()
+            .with_value("none", None)
+            .with_value("some", Some(2))
+            .with_expr(add(coalesce(ident("none"), num(1)), coalesce(ident("some"), num(1))))
+            .eval(|value| assert_eq!(value.load::<i32>().unwrap(), 3));
+
+        // Not in the state
+        ScopedTest::<i32, _>::new()
+            .with_expr(coalesce(ident("missing"), num(3)))
+            .eval(|value| assert_eq!(value.load::<i32>().unwrap(), 3));
+
+        // No value
+        ScopedTest::<i32, _>::new()
+            .with_expr(coalesce(div(num(1), num(0)), num(4)))
+            .eval(|value| assert_eq!(value.load::<i32>().unwrap(), 4));
+    }
 }
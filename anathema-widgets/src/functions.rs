@@ -0,0 +1,226 @@
+//! Functions that format values for display, so the state doesn't need
+//! to hold strings that only exist to be shown:
+//! ```text
+//! text pad_left(count, 4) " " upper(truncate(name, 10, "…"))
+//! text format_number(total, 2) " (" join(tags, ", ") ")"
+//! ```
+//!
+//! | Function                               | Result                                            |
+//! |----------------------------------------|---------------------------------------------------|
+//! | `upper(s)`, `lower(s)`                 | `s` in upper / lower case                         |
+//! | `pad_left(s, width, fill = " ")`       | `s` padded to `width` cells, aligned to the right |
+//! | `pad_right(s, width, fill = " ")`      | `s` padded to `width` cells, aligned to the left  |
+//! | `truncate(s, width, suffix = "")`      | `s` cut to `width` cells, ending with the suffix  |
+//! | `format_number(n, decimals = 0)`       | `n` with thousands separators, e.g `1,234.50`     |
+//! | `join(list, separator = "")`           | The values of the list with the separator between |
+//!
+//! The widths are in cells, so wide characters count twice.
+use anathema_state::Path;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::expressions::EvalValue;
+
+/// A function that can be called from a template
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Function {
+    Upper,
+    Lower,
+    PadLeft,
+    PadRight,
+    Truncate,
+    FormatNumber,
+    Join,
+}
+
+impl Function {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let function = match name {
+            "upper" => Self::Upper,
+            "lower" => Self::Lower,
+            "pad_left" => Self::PadLeft,
+            "pad_right" => Self::PadRight,
+            "truncate" => Self::Truncate,
+            "format_number" => Self::FormatNumber,
+            "join" => Self::Join,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    /// Call the function, returns `None` if an argument is missing or has the wrong type
+    pub(crate) fn call(&self, args: &[EvalValue<'_>]) -> Option<String> {
+        let string = |index: usize| args.get(index).map(to_string);
+        let usize = |index: usize| -> Option<usize> {
+            let number = args.get(index)?.load_number()?;
+            Some(number.as_int().max(0) as usize)
+        };
+
+        let output = match self {
+            Self::Upper => string(0)?.to_uppercase(),
+            Self::Lower => string(0)?.to_lowercase(),
+            Self::PadLeft | Self::PadRight => {
+                let s = string(0)?;
+                let width = usize(1)?;
+                let fill = string(2).and_then(|fill| fill.chars().next()).unwrap_or(' ');
+                let padding = width.saturating_sub(s.width()) / fill.width().unwrap_or(1).max(1);
+                let padding = std::iter::repeat_n(fill, padding);
+                match self {
+                    Self::PadLeft => padding.chain(s.chars()).collect(),
+                    _ => s.chars().chain(padding).collect(),
+                }
+            }
+            Self::Truncate => truncate(&string(0)?, usize(1)?, &string(2).unwrap_or_default()),
+            Self::FormatNumber => {
+                let number = args.first()?.load_number()?;
+                format_number(number.as_float(), usize(1).unwrap_or(0))
+            }
+            Self::Join => {
+                let separator = string(1).unwrap_or_default();
+                list_strings(args.first()?)?.join(&separator)
+            }
+        };
+
+        Some(output)
+    }
+}
+
+fn to_string(value: &EvalValue<'_>) -> String {
+    let mut output = String::new();
+    value.str_for_each(|s| output.push_str(s));
+    output
+}
+
+// The values of a list, from the template or the state
+fn list_strings(value: &EvalValue<'_>) -> Option<Vec<String>> {
+    match value {
+        EvalValue::ExprList(list) => Some(list.iter().map(to_string).collect()),
+        EvalValue::Index(value, _) => list_strings(value),
+        EvalValue::Dyn(value) => {
+            let state = value.as_state()?;
+            let strings = (0..state.count())
+                .filter_map(|index| {
+                    let value = state.state_lookup(Path::Index(index))?;
+                    value.as_state(|state| Some(state.to_common()?.to_common_str().as_ref().to_string()))
+                })
+                .collect();
+            Some(strings)
+        }
+        _ => None,
+    }
+}
+
+fn truncate(s: &str, width: usize, suffix: &str) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+
+    let width = width.saturating_sub(suffix.width());
+    let mut output = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            break;
+        }
+        output.push(c);
+    }
+    output.push_str(suffix);
+    output
+}
+
+fn format_number(number: f64, decimals: usize) -> String {
+    let formatted = format!("{:.decimals$}", number.abs());
+    let (int, fraction) = match formatted.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut output = String::new();
+    if number < 0.0 && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+        output.push('-');
+    }
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            output.push(',');
+        }
+        output.push(c);
+    }
+    if let Some(fraction) = fraction {
+        output.push('.');
+        output.push_str(fraction);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_state::List;
+    use anathema_templates::expressions::{call, float, ident, list, num, strlit};
+    use anathema_templates::Expression;
+
+    use crate::testing::ScopedTest;
+
+    fn assert_call(name: &str, args: impl IntoIterator<Item = Box<Expression>>, expected: &str) {
+        ScopedTest::<i64, _>::new()
+            .with_expr(call(ident(name), args))
+            .eval(|value| {
+                let mut output = String::new();
+                value.str_for_each(|s| output.push_str(s));
+                assert_eq!(output, expected);
+            });
+    }
+
+    #[test]
+    fn case() {
+        assert_call("upper", vec![strlit("abc")], "ABC");
+        assert_call("lower", vec![strlit("AbC")], "abc");
+    }
+
+    #[test]
+    fn padding() {
+        assert_call("pad_left", vec![num(7), num(3)], "  7");
+        assert_call("pad_left", vec![num(7), num(3), strlit("0")], "007");
+        assert_call("pad_right", vec![strlit("ab"), num(4), strlit(".")], "ab..");
+        assert_call("pad_right", vec![strlit("abcde"), num(4)], "abcde");
+    }
+
+    #[test]
+    fn truncate() {
+        assert_call("truncate", vec![strlit("abcdef"), num(4)], "abcd");
+        assert_call("truncate", vec![strlit("abcdef"), num(4), strlit("…")], "abc…");
+        assert_call("truncate", vec![strlit("abc"), num(4), strlit("…")], "abc");
+        // Wide characters take two cells
+        assert_call("truncate", vec![strlit("日本語"), num(5)], "日本");
+    }
+
+    #[test]
+    fn format_number() {
+        assert_call("format_number", vec![num(1234567)], "1,234,567");
+        assert_call("format_number", vec![float(-1234.5), num(2)], "-1,234.50");
+        assert_call("format_number", vec![float(999.999), num(2)], "1,000.00");
+        assert_call("format_number", vec![float(-0.001), num(1)], "0.0");
+    }
+
+    #[test]
+    fn join() {
+        assert_call("join", vec![list([num(1), num(2), num(3)]), strlit(", ")], "1, 2, 3");
+
+        let mut tags = List::empty();
+        tags.push_back("a");
+        tags.push_back("b");
+        ScopedTest::new()
+            .with_value("tags", tags)
+            .with_expr(call(ident("join"), vec![ident("tags"), strlit("|")]))
+            .eval(|value| {
+                let mut output = String::new();
+                value.str_for_each(|s| output.push_str(s));
+                assert_eq!(output, "a|b");
+            });
+    }
+
+    #[test]
+    fn unknown_function_or_missing_argument() {
+        assert_call("nope", vec![strlit("a")], "");
+        assert_call("pad_left", vec![strlit("a")], "");
+    }
+}
@@ -0,0 +1,119 @@
+use anathema_geometry::{Pos, Size};
+use anathema_store::tree::TreeValues;
+
+use super::{is_hidden, layout_widget, position_widget, Constraints, LayoutCtx, Viewport};
+use crate::{AttributeStorage, WidgetKind, WidgetTree};
+
+/// The name other elements are aligned to
+pub const ALIGN_ID: &str = "id";
+
+/// Move the left edge of the element to the left edge of the named element
+pub const ALIGN_LEFT: &str = "align_left";
+
+/// Give the element the width of the named element
+pub const MATCH_WIDTH: &str = "match_width";
+
+/// Resolve the alignment constraints, once the tree is laid out and positioned.
+///
+/// An element with `align_left` or `match_width` is laid out and positioned again,
+/// using the element with the matching `id`, e.g to line up the fields of a form:
+/// ```text
+/// vstack
+///     hstack
+///         text 'Email: '
+///         border [id: 'email']
+///             text email
+///     hstack [width: 40]
+///         text 'Name: '
+///         border [align_left: 'email', match_width: 'email']
+///             text name
+/// ```
+///
+/// Elements are aligned in the order they appear in the tree, so an element can be
+/// aligned to an element that is aligned itself. The parent is not laid out again,
+/// it should have room for the element.
+pub fn align_elements<'bp>(tree: &mut WidgetTree<'bp>, attribute_storage: &AttributeStorage<'bp>, viewport: Viewport) {
+    let (_, values) = tree.split();
+    let mut constrained = values
+        .iter()
+        .filter_map(|(path, widget)| match widget {
+            WidgetKind::Element(el) => {
+                let attributes = attribute_storage.get(el.id());
+                let constrained = attributes.contains(ALIGN_LEFT) || attributes.contains(MATCH_WIDTH);
+                (constrained && !is_hidden(attributes)).then(|| (path.clone(), el.id()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    constrained.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (path, widget_id) in constrained {
+        let attributes = attribute_storage.get(widget_id);
+        let (_, values) = tree.split();
+        let left = attributes
+            .get_ref::<&str>(ALIGN_LEFT)
+            .and_then(|name| find_target(name, values, attribute_storage))
+            .map(|(pos, _)| pos.x);
+        let width = attributes
+            .get_ref::<&str>(MATCH_WIDTH)
+            .and_then(|name| find_target(name, values, attribute_storage))
+            .map(|(_, size)| size.width);
+
+        if left.is_none() && width.is_none() {
+            continue;
+        }
+
+        // The children are laid out again with the new width
+        if width.is_some() {
+            for (child_path, widget) in tree.iter_mut() {
+                if let WidgetKind::Element(el) = widget {
+                    if child_path.starts_with(&path) {
+                        el.container.needs_layout = true;
+                    }
+                }
+            }
+        }
+
+        tree.with_nodes_and_values(widget_id, |widget, children, values| {
+            let WidgetKind::Element(el) = widget else { return };
+            let margin = el.container.margin;
+
+            if let Some(width) = width {
+                let width = width + margin.size().width;
+                let mut constraints = Constraints::new(width, viewport.size().height);
+                constraints.make_width_tight(width);
+                let mut ctx = LayoutCtx::new(attribute_storage, &viewport);
+                layout_widget(el, children, values, constraints, &mut ctx, true);
+            }
+
+            let mut pos = el.container.pos;
+            if let Some(left) = left {
+                pos.x = left;
+            }
+            el.container.needs_position = true;
+            position_widget(
+                pos - margin.offset(),
+                el,
+                children,
+                values,
+                attribute_storage,
+                true,
+                viewport,
+            );
+        });
+    }
+}
+
+// The position and size of the element with the id
+fn find_target(
+    name: &str,
+    values: &TreeValues<WidgetKind<'_>>,
+    attribute_storage: &AttributeStorage<'_>,
+) -> Option<(Pos, Size)> {
+    values.iter().find_map(|(_, widget)| match widget {
+        WidgetKind::Element(el) if attribute_storage.get(el.id()).get_ref::<&str>(ALIGN_ID) == Some(name) => {
+            Some((el.container.pos, el.container.size))
+        }
+        _ => None,
+    })
+}
@@ -0,0 +1,255 @@
+//! Reordering of bidirectional text, for lines that mix left-to-right and right-to-left scripts.
+//!
+//! This implements a subset of the Unicode bidirectional algorithm (UAX #9):
+//! characters are classified as left-to-right, right-to-left, numbers or neutral,
+//! without support for explicit embeddings and isolates.
+//! Lines are reordered after the text has been wrapped, as the text is wrapped
+//! in logical order.
+use anathema_state::CommonVal;
+
+/// The direction of a paragraph
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// The direction of a paragraph is the direction of the first character with a direction,
+    /// or left-to-right if there is no such character.
+    pub fn of(text: impl IntoIterator<Item = char>) -> Self {
+        text.into_iter()
+            .find_map(|c| match class(c) {
+                Class::L => Some(Self::Ltr),
+                Class::R => Some(Self::Rtl),
+                Class::EN | Class::N => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn level(self) -> u8 {
+        match self {
+            Self::Ltr => 0,
+            Self::Rtl => 1,
+        }
+    }
+}
+
+impl TryFrom<CommonVal<'_>> for TextDirection {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        match value.to_common_str().as_ref() {
+            "ltr" => Ok(Self::Ltr),
+            "rtl" => Ok(Self::Rtl),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Class {
+    // Left-to-right
+    L,
+    // Right-to-left
+    R,
+    // Number
+    EN,
+    // Neutral, e.g whitespace and punctuation
+    N,
+}
+
+fn is_rtl_script(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms
+        | 0xFE70..=0xFEFF
+        | 0x10800..=0x10FFF
+        | 0x1E800..=0x1EFFF)
+}
+
+fn class(c: char) -> Class {
+    if c.is_numeric() {
+        Class::EN
+    } else if c.is_alphabetic() {
+        match is_rtl_script(c) {
+            true => Class::R,
+            false => Class::L,
+        }
+    } else {
+        Class::N
+    }
+}
+
+// Brackets are mirrored in right-to-left text
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        c => c,
+    }
+}
+
+// The embedding level of each character
+fn levels(line: &[char], direction: TextDirection) -> Vec<u8> {
+    let base = direction.level();
+    let mut classes = line.iter().copied().map(class).collect::<Vec<_>>();
+
+    // Numbers following left-to-right text are left-to-right
+    let mut strong = direction;
+    for class in classes.iter_mut() {
+        match class {
+            Class::L => strong = TextDirection::Ltr,
+            Class::R => strong = TextDirection::Rtl,
+            Class::EN if strong == TextDirection::Ltr => *class = Class::L,
+            _ => {}
+        }
+    }
+
+    // Neutrals between characters of the same direction take that direction,
+    // otherwise the direction of the paragraph. Numbers count as right-to-left.
+    let is_rtl = |class: Class| matches!(class, Class::R | Class::EN);
+    let mut index = 0;
+    while index < classes.len() {
+        if classes[index] != Class::N {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < classes.len() && classes[index] == Class::N {
+            index += 1;
+        }
+
+        let before = match start {
+            0 => direction == TextDirection::Rtl,
+            _ => is_rtl(classes[start - 1]),
+        };
+        let after = match classes.get(index) {
+            None => direction == TextDirection::Rtl,
+            Some(class) => is_rtl(*class),
+        };
+
+        let resolved = match (before, after) {
+            (true, true) => Class::R,
+            (false, false) => Class::L,
+            _ => match direction {
+                TextDirection::Ltr => Class::L,
+                TextDirection::Rtl => Class::R,
+            },
+        };
+        classes[start..index].fill(resolved);
+    }
+
+    let mut levels = classes
+        .into_iter()
+        .map(|class| match (class, base) {
+            (Class::L, 0) => 0,
+            (Class::R, 0) => 1,
+            (Class::EN, 0) => 2,
+            (Class::R, _) => 1,
+            _ => 2,
+        })
+        .collect::<Vec<_>>();
+
+    // Trailing whitespace is at the level of the paragraph
+    for (level, c) in levels.iter_mut().zip(line).rev() {
+        if !c.is_whitespace() {
+            break;
+        }
+        *level = base;
+    }
+
+    levels
+}
+
+/// Reorder a line of text for display.
+/// Returns the characters in the order in which they should be drawn from left to right,
+/// along with the index of each character in the line.
+///
+/// ```
+/// # use anathema_widgets::layout::bidi::{reorder, TextDirection};
+/// let line = "abc אבג".chars().collect::<Vec<_>>();
+/// let visual = reorder(&line, TextDirection::Ltr).into_iter().map(|(_, c)| c).collect::<String>();
+/// assert_eq!(visual, "abc גבא");
+/// ```
+pub fn reorder(line: &[char], direction: TextDirection) -> Vec<(usize, char)> {
+    let levels = levels(line, direction);
+    let mut order = (0..line.len()).collect::<Vec<_>>();
+
+    // From the highest level to the lowest odd level, reverse any run
+    // of characters at that level or higher
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let lowest_odd = levels
+        .iter()
+        .copied()
+        .filter(|l| l % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+    for level in (lowest_odd..=highest).rev() {
+        let mut index = 0;
+        while index < order.len() {
+            if levels[order[index]] < level {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < order.len() && levels[order[index]] >= level {
+                index += 1;
+            }
+            order[start..index].reverse();
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|index| match levels[index] % 2 {
+            1 => (index, mirror(line[index])),
+            _ => (index, line[index]),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn visual(line: &str, direction: TextDirection) -> String {
+        let line = line.chars().collect::<Vec<_>>();
+        reorder(&line, direction).into_iter().map(|(_, c)| c).collect()
+    }
+
+    #[test]
+    fn paragraph_direction() {
+        assert_eq!(TextDirection::of("123 abc".chars()), TextDirection::Ltr);
+        assert_eq!(TextDirection::of("123 שלום".chars()), TextDirection::Rtl);
+        assert_eq!(TextDirection::of("...".chars()), TextDirection::Ltr);
+    }
+
+    #[test]
+    fn reorder_lines() {
+        // Left-to-right text is unchanged
+        assert_eq!(visual("hello (world)", TextDirection::Ltr), "hello (world)");
+
+        // Right-to-left text is reversed, with the brackets mirrored
+        assert_eq!(visual("אבג (דה)", TextDirection::Rtl), "(הד) גבא");
+
+        // Left-to-right text and numbers keep their order inside right-to-left text
+        assert_eq!(visual("אבג abc 123 דה", TextDirection::Rtl), "הד abc 123 גבא");
+
+        // Right-to-left text inside left-to-right text
+        assert_eq!(visual("one אבג two", TextDirection::Ltr), "one גבא two");
+
+        // Trailing whitespace stays at the end
+        assert_eq!(visual("אב ", TextDirection::Ltr), "בא ");
+    }
+}
@@ -1,7 +1,47 @@
+use std::fmt::Display;
+
 use anathema_geometry::Rect;
 
 use crate::layout::Size;
 
+/// Invalid constraints
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// The minimum width is larger than the max width
+    MinWidthAboveMax { min: usize, max: usize },
+    /// The minimum height is larger than the max height
+    MinHeightAboveMax { min: usize, max: usize },
+    /// The width was made tight to a width smaller than the minimum width
+    TightWidthBelowMin { width: usize, min: usize },
+    /// The height was made tight to a height smaller than the minimum height
+    TightHeightBelowMin { height: usize, min: usize },
+}
+
+impl Display for ConstraintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MinWidthAboveMax { min, max } => write!(f, "min width ({min}) is larger than max width ({max})"),
+            Self::MinHeightAboveMax { min, max } => {
+                write!(f, "min height ({min}) is larger than max height ({max})")
+            }
+            Self::TightWidthBelowMin { width, min } => {
+                write!(
+                    f,
+                    "width made tight to {width}, which is less than the min width ({min})"
+                )
+            }
+            Self::TightHeightBelowMin { height, min } => {
+                write!(
+                    f,
+                    "height made tight to {height}, which is less than the min height ({min})"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
 /// `Constraints` are used to ensure that a widget doesn't size it self outside of a set of given bounds.
 /// A constraint can be tight, meaning then minimum and maximum width / height are the same.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -14,6 +54,8 @@ pub struct Constraints {
     max_width: usize,
     /// Max height.
     max_height: usize,
+    // A conflict from making the constraints tight, kept for `validate`
+    conflict: Option<ConstraintError>,
 }
 
 impl Constraints {
@@ -22,6 +64,7 @@ impl Constraints {
         min_height: 0,
         max_width: 0,
         max_height: 0,
+        conflict: None,
     };
 
     pub fn max_height(&self) -> usize {
@@ -65,6 +108,7 @@ impl Constraints {
             min_height: 0,
             max_width,
             max_height,
+            conflict: None,
         }
     }
 
@@ -75,6 +119,7 @@ impl Constraints {
             min_height: 0,
             max_width: usize::MAX,
             max_height: usize::MAX,
+            conflict: None,
         }
     }
 
@@ -103,6 +148,36 @@ impl Constraints {
         self.max_height == usize::MAX
     }
 
+    /// Check that the minimum width / height are not larger than the max width / height,
+    /// and that the constraints were not made tight below the minimum width / height.
+    /// ```
+    /// # use anathema_widgets::layout::{ConstraintError, Constraints};
+    /// let mut constraints = Constraints::new(10, 10);
+    /// constraints.min_width = 11;
+    /// assert_eq!(constraints.validate(), Err(ConstraintError::MinWidthAboveMax { min: 11, max: 10 }));
+    /// ```
+    pub fn validate(&self) -> Result<(), ConstraintError> {
+        if let Some(conflict) = self.conflict {
+            return Err(conflict);
+        }
+
+        if self.min_width > self.max_width {
+            return Err(ConstraintError::MinWidthAboveMax {
+                min: self.min_width,
+                max: self.max_width,
+            });
+        }
+
+        if self.min_height > self.max_height {
+            return Err(ConstraintError::MinHeightAboveMax {
+                min: self.min_height,
+                max: self.max_height,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Returns true if the `min_width` and `max_width` are the same.
     pub fn is_width_tight(&self) -> bool {
         self.max_width == self.min_width
@@ -120,7 +195,17 @@ impl Constraints {
     /// constraints.make_width_tight(constraints.max_width());
     /// # assert_eq!(constraints.min_width, constraints.max_width());
     /// ```
+    ///
+    /// The width is clamped to the max width, and the min width is
+    /// lowered to match if the width is less than the min width.
+    /// The conflict with the min width is returned by [`Constraints::validate`].
     pub fn make_width_tight(&mut self, width: usize) {
+        if width < self.min_width {
+            self.conflict = Some(ConstraintError::TightWidthBelowMin {
+                width,
+                min: self.min_width,
+            });
+        }
         self.max_width = self.max_width.min(width);
         self.min_width = self.max_width;
     }
@@ -132,11 +217,48 @@ impl Constraints {
     /// constraints.make_height_tight(constraints.max_height());
     /// # assert_eq!(constraints.min_height, constraints.max_height());
     /// ```
+    ///
+    /// The height is clamped to the max height, and the min height is
+    /// lowered to match if the height is less than the min height.
+    /// The conflict with the min height is returned by [`Constraints::validate`].
     pub fn make_height_tight(&mut self, height: usize) {
+        if height < self.min_height {
+            self.conflict = Some(ConstraintError::TightHeightBelowMin {
+                height,
+                min: self.min_height,
+            });
+        }
         self.max_height = self.max_height.min(height);
         self.min_height = self.max_height;
     }
 
+    // Forget the conflict once it's reported, so it isn't reported again for the children
+    pub(crate) fn clear_conflict(&mut self) {
+        self.conflict = None;
+    }
+
+    /// Set the min width, clamped to the max width.
+    /// ```
+    /// # use anathema_widgets::layout::Constraints;
+    /// let mut constraints = Constraints::new(5, 5);
+    /// constraints.set_min_width(10);
+    /// # assert_eq!(constraints.min_width, 5);
+    /// ```
+    pub fn set_min_width(&mut self, width: usize) {
+        self.min_width = width.min(self.max_width);
+    }
+
+    /// Set the min height, clamped to the max height.
+    /// ```
+    /// # use anathema_widgets::layout::Constraints;
+    /// let mut constraints = Constraints::new(5, 5);
+    /// constraints.set_min_height(10);
+    /// # assert_eq!(constraints.min_height, 5);
+    /// ```
+    pub fn set_min_height(&mut self, height: usize) {
+        self.min_height = height.min(self.max_height);
+    }
+
     pub fn expand_horz(&mut self, mut size: Size) -> Size {
         size.width = self.max_width;
         size
@@ -206,3 +328,31 @@ impl Default for Constraints {
         Self::ZERO
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn min_height_above_max() {
+        let mut constraints = Constraints::new(10, 10);
+        constraints.min_height = 20;
+        assert_eq!(
+            constraints.validate(),
+            Err(ConstraintError::MinHeightAboveMax { min: 20, max: 10 })
+        );
+    }
+
+    #[test]
+    fn tight_width_below_min() {
+        let mut constraints = Constraints::new(10, 10);
+        constraints.min_width = 5;
+        constraints.make_width_tight(2);
+        assert_eq!(constraints.min_width, 2);
+        assert_eq!(constraints.max_width(), 2);
+        assert_eq!(
+            constraints.validate(),
+            Err(ConstraintError::TightWidthBelowMin { width: 2, min: 5 })
+        );
+    }
+}
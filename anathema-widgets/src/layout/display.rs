@@ -1,5 +1,20 @@
 use anathema_state::CommonVal;
 
+use crate::Attributes;
+
+/// Widgets with `hide` set to true are removed from layout and painting,
+/// along with all their children.
+/// ```text
+/// border [hide: state.collapsed]
+///     text 'panel'
+/// ```
+pub const HIDE: &str = "hide";
+
+/// Returns true if the widget is removed by the `hide` attribute
+pub fn is_hidden(attributes: &Attributes<'_>) -> bool {
+    attributes.get_bool(HIDE)
+}
+
 #[derive(Debug, Copy, Clone, Default, PartialEq)]
 pub enum Display {
     #[default]
@@ -3,30 +3,54 @@ use std::ops::ControlFlow;
 use anathema_geometry::{Pos, Size};
 use anathema_store::tree::{Node, TreeFilter, TreeForEach, TreeValues};
 
-pub use self::constraints::Constraints;
-pub use self::display::Display;
+pub use self::align::{align_elements, ALIGN_ID, ALIGN_LEFT, MATCH_WIDTH};
+pub use self::constraints::{ConstraintError, Constraints};
+pub use self::display::{is_hidden, Display, HIDE};
+pub use self::portal::{find_mount, portal_order, MOUNT, PORTAL_TO};
+pub use self::spacing::{
+    Spacing, MARGIN, MARGIN_BOTTOM, MARGIN_LEFT, MARGIN_RIGHT, MARGIN_TOP, PADDING, PADDING_BOTTOM, PADDING_LEFT,
+    PADDING_RIGHT, PADDING_TOP,
+};
 use crate::nodes::element::Element;
-use crate::{AttributeStorage, WidgetId, WidgetKind};
+use crate::{AttributeStorage, LayoutChildren, PositionChildren, WidgetId, WidgetKind};
 
+mod align;
+pub mod bidi;
 mod constraints;
 mod display;
+mod portal;
+mod spacing;
 pub mod text;
+pub mod words;
 
 #[derive(Debug, Copy, Clone)]
 /// A viewport represents the available space in the root
 pub struct Viewport {
     size: Size,
+    cell_size: Size,
 }
 
 impl Viewport {
     pub fn new(size: impl Into<Size>) -> Self {
-        Self { size: size.into() }
+        Self {
+            size: size.into(),
+            cell_size: Size::ZERO,
+        }
     }
 
     pub fn size(&self) -> Size {
         self.size
     }
 
+    /// The size of a cell in pixels, or zero if the backend doesn't know it
+    pub fn cell_size(&self) -> Size {
+        self.cell_size
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: Size) {
+        self.cell_size = cell_size;
+    }
+
     pub fn constraints(&self) -> Constraints {
         Constraints::new(self.size.width, self.size.height)
     }
@@ -65,6 +89,7 @@ impl<'frame, 'bp> TreeFilter for LayoutFilter<'frame, 'bp> {
     ) -> ControlFlow<(), Option<&'val mut Self::Output>> {
         match input {
             WidgetKind::Element(el) if el.container.inner.any_floats() && self.ignore_floats => ControlFlow::Break(()),
+            WidgetKind::Element(el) if is_hidden(self.attributes.get(el.id())) => ControlFlow::Break(()),
             WidgetKind::Element(el) => match self
                 .attributes
                 .get(el.id())
@@ -91,14 +116,101 @@ impl<'frame, 'bp> TreeFilter for LayoutFilter<'frame, 'bp> {
     }
 }
 
+/// The number of elements the children would lay out.
+///
+/// The iterations of a loop are not visited one by one if the body of the loop
+/// is only elements that can't be hidden.
+pub fn count_elements(children: &LayoutChildren<'_, '_, '_>, attributes: &AttributeStorage<'_>) -> usize {
+    count(children.nodes(), children.values(), attributes)
+}
+
+fn count(nodes: &[Node], values: &TreeValues<WidgetKind<'_>>, attributes: &AttributeStorage<'_>) -> usize {
+    nodes
+        .iter()
+        .map(|node| match values.get(node.value()) {
+            Some((_, WidgetKind::Element(el))) => {
+                let attribs = attributes.get(el.id());
+                if is_hidden(attribs) {
+                    return 0;
+                }
+                match attribs.get::<Display>("display").unwrap_or_default() {
+                    Display::Show | Display::Hide => 1,
+                    Display::Exclude => count(node.children(), values, attributes),
+                }
+            }
+            Some((_, WidgetKind::For(_))) => {
+                let iterations = node.children();
+                match iterations.first() {
+                    Some(first) if is_fixed(first.children(), values, attributes) => {
+                        iterations.len() * first.children().len()
+                    }
+                    _ => count(iterations, values, attributes),
+                }
+            }
+            Some((_, WidgetKind::If(widget))) if !widget.show => 0,
+            Some((_, WidgetKind::Else(widget))) if !widget.show => 0,
+            _ => count(node.children(), values, attributes),
+        })
+        .sum()
+}
+
+// Every iteration of a loop has the same number of elements
+// if the body is only elements without `hide` or `display`
+fn is_fixed(nodes: &[Node], values: &TreeValues<WidgetKind<'_>>, attributes: &AttributeStorage<'_>) -> bool {
+    nodes.iter().all(|node| match values.get(node.value()) {
+        Some((_, WidgetKind::Element(el))) => {
+            let attribs = attributes.get(el.id());
+            !attribs.contains(HIDE) && !attribs.contains("display")
+        }
+        _ => false,
+    })
+}
+
+/// An error found while laying out a widget.
+/// The layout carries on regardless, see [`LayoutCtx::report`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The widget was given invalid constraints
+    Constraints(ConstraintError),
+    /// The children of the widget need more cells than are available
+    Overflow { needed: usize, available: usize },
+}
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Constraints(err) => write!(f, "invalid constraints: {err}"),
+            Self::Overflow { needed, available } => {
+                write!(f, "the children need {needed} cells but only {available} are available")
+            }
+        }
+    }
+}
+
 pub struct LayoutCtx<'a, 'bp> {
     pub attribs: &'a AttributeStorage<'bp>,
     pub viewport: &'a Viewport,
+    errors: Vec<(WidgetId, LayoutError)>,
 }
 
 impl<'a, 'bp> LayoutCtx<'a, 'bp> {
     pub fn new(attribs: &'a AttributeStorage<'bp>, viewport: &'a Viewport) -> Self {
-        Self { attribs, viewport }
+        Self {
+            attribs,
+            viewport,
+            errors: vec![],
+        }
+    }
+
+    /// Report an error for a widget.
+    /// The layout carries on, and the errors are collected with [`LayoutCtx::take_errors`].
+    pub fn report(&mut self, id: WidgetId, error: LayoutError) {
+        self.errors.push((id, error));
+    }
+
+    /// The errors reported since the last call, in the order they were reported
+    pub fn take_errors(&mut self) -> Vec<(WidgetId, LayoutError)> {
+        std::mem::take(&mut self.errors)
     }
 }
 
@@ -136,6 +248,32 @@ pub struct PositionCtx {
     pub viewport: Viewport,
 }
 
+/// A layout algorithm for the `layout` widget, selected with the `layout` attribute,
+/// e.g `layout [layout: "masonry"]`.
+///
+/// This is the layout and position part of a [`crate::Widget`],
+/// for layouts that don't need a widget of their own.
+/// Layouts are registered with [`crate::Factory::register_layout`].
+pub trait Layout {
+    /// Lay out the children and return the size of the widget
+    fn layout<'bp>(
+        &mut self,
+        children: LayoutChildren<'_, '_, 'bp>,
+        constraints: Constraints,
+        id: WidgetId,
+        ctx: &mut LayoutCtx<'_, 'bp>,
+    ) -> Size;
+
+    /// Position the children, relative to `ctx.pos`
+    fn position<'bp>(
+        &mut self,
+        children: PositionChildren<'_, '_, 'bp>,
+        id: WidgetId,
+        attribute_storage: &AttributeStorage<'bp>,
+        ctx: PositionCtx,
+    );
+}
+
 #[cfg(test)]
 mod test {
     use anathema_geometry::Region;
@@ -0,0 +1,36 @@
+use anathema_store::tree::TreeValues;
+
+use crate::{AttributeStorage, WidgetId, WidgetKind};
+
+/// Elements with a `mount` attribute are targets for portals,
+/// see [`crate::Widget::is_portal`].
+/// ```text
+/// overlay [mount: 'dialogs']
+/// ```
+pub const MOUNT: &str = "mount";
+
+/// The name of the mount point a portal is mounted under
+pub const PORTAL_TO: &str = "to";
+
+/// Find the element a portal is mounted under:
+/// the element with a `mount` attribute matching the `to` attribute of the portal.
+/// Returns the path and the id of the element.
+pub fn find_mount<'a>(
+    portal: WidgetId,
+    values: &'a TreeValues<WidgetKind<'_>>,
+    attributes: &AttributeStorage<'_>,
+) -> Option<(&'a [u16], WidgetId)> {
+    let name = attributes.get(portal).get_ref::<&str>(PORTAL_TO)?;
+    values.iter().find_map(|(path, widget)| match widget {
+        WidgetKind::Element(el) if attributes.get(el.id()).get_ref::<&str>(MOUNT) == Some(name) => {
+            Some((&**path, el.id()))
+        }
+        _ => None,
+    })
+}
+
+/// Portals are painted after their mount point and everything inside it,
+/// as if they were the last child of the mount point.
+pub fn portal_order(mount: &[u16]) -> Box<[u16]> {
+    mount.iter().copied().chain([u16::MAX]).collect()
+}
@@ -0,0 +1,113 @@
+use anathema_geometry::{Pos, Size};
+
+use crate::Attributes;
+
+/// Padding on all sides of a widget
+pub const PADDING: &str = "padding";
+pub const PADDING_TOP: &str = "padding_top";
+pub const PADDING_RIGHT: &str = "padding_right";
+pub const PADDING_BOTTOM: &str = "padding_bottom";
+pub const PADDING_LEFT: &str = "padding_left";
+
+/// Margin on all sides of a widget
+pub const MARGIN: &str = "margin";
+pub const MARGIN_TOP: &str = "margin_top";
+pub const MARGIN_RIGHT: &str = "margin_right";
+pub const MARGIN_BOTTOM: &str = "margin_bottom";
+pub const MARGIN_LEFT: &str = "margin_left";
+
+/// Space around the content of a widget, on each side.
+///
+/// Both the padding and the margin of a widget are handled for every widget.
+/// The widget is laid out with the space removed from its constraints and positioned
+/// inside the space.
+///
+/// The padding is set with the `padding` attribute and overridden for a single side with
+/// `padding_top`, `padding_right`, `padding_bottom` or `padding_left`.
+/// The attributes of the widget, e.g. the background, cover the padding as well.
+///
+/// The margin is set the same way with `margin`, `margin_top` etc.
+/// Unlike the padding the margin is outside of the widget: it's space the widget
+/// reserves in its parent, without painting it.
+/// ```text
+/// vstack [padding: 1, padding_left: 2, margin_bottom: 1, background: 'blue']
+///     text 'padded'
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Spacing {
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+    pub left: u16,
+}
+
+impl Spacing {
+    pub const ZERO: Self = Self {
+        top: 0,
+        right: 0,
+        bottom: 0,
+        left: 0,
+    };
+
+    /// The padding of a widget
+    pub fn padding(attributes: &Attributes<'_>) -> Self {
+        Self::from_attributes(
+            attributes,
+            PADDING,
+            [PADDING_TOP, PADDING_RIGHT, PADDING_BOTTOM, PADDING_LEFT],
+        )
+    }
+
+    /// The margin of a widget
+    pub fn margin(attributes: &Attributes<'_>) -> Self {
+        Self::from_attributes(
+            attributes,
+            MARGIN,
+            [MARGIN_TOP, MARGIN_RIGHT, MARGIN_BOTTOM, MARGIN_LEFT],
+        )
+    }
+
+    fn from_attributes(attributes: &Attributes<'_>, all: &'static str, sides: [&'static str; 4]) -> Self {
+        let all = attributes.get::<u16>(all).unwrap_or(0);
+        let [top, right, bottom, left] =
+            sides.map(|key| attributes.get_usize(key).and_then(|v| v.try_into().ok()).unwrap_or(all));
+
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// The combined width and height of the space
+    pub fn size(&self) -> Size {
+        Size::new(
+            self.left as usize + self.right as usize,
+            self.top as usize + self.bottom as usize,
+        )
+    }
+
+    /// The offset from the outside of the space to the inside
+    pub fn offset(&self) -> Pos {
+        Pos::new(self.left as i32, self.top as i32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn size_of_max_spacing() {
+        let spacing = Spacing {
+            top: u16::MAX,
+            right: u16::MAX,
+            bottom: u16::MAX,
+            left: u16::MAX,
+        };
+
+        let expected = u16::MAX as usize * 2;
+        assert_eq!(spacing.size(), Size::new(expected, expected));
+    }
+}
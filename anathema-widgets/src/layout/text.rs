@@ -16,6 +16,11 @@ pub enum Wrap {
     Normal,
     /// Insert a newline in the middle of any text
     WordBreak,
+    /// Don't wrap the text. Anything that doesn't fit on a line is cut off.
+    NoWrap,
+    /// Don't wrap the text, and end a line that doesn't fit with an ellipsis
+    /// (see [`Strings::set_ellipsis`]).
+    Truncate,
 }
 
 impl Wrap {
@@ -23,6 +28,11 @@ impl Wrap {
     pub fn is_word_wrap(&self) -> bool {
         matches!(self, Self::Normal)
     }
+
+    /// Returns true if text that doesn't fit continues on the next line
+    pub fn wraps(&self) -> bool {
+        matches!(self, Self::Normal | Self::WordBreak)
+    }
 }
 
 impl TryFrom<CommonVal<'_>> for Wrap {
@@ -31,8 +41,10 @@ impl TryFrom<CommonVal<'_>> for Wrap {
     fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
         match value {
             CommonVal::Str(wrap) => match wrap {
-                "normal" => Ok(Wrap::Normal),
+                "normal" | "word" => Ok(Wrap::Normal),
                 "break" => Ok(Wrap::WordBreak),
+                "no_wrap" | "no-wrap" => Ok(Wrap::NoWrap),
+                "truncate" => Ok(Wrap::Truncate),
                 _ => Err(()),
             },
             _ => Err(()),
@@ -86,7 +98,8 @@ pub enum ProcessResult {
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) enum Entry {
-    Newline,
+    // True if the line was wrapped, rather than ending with a newline character
+    Newline(bool),
     LineWidth(u16),
     Style(ValueId),
 }
@@ -95,6 +108,9 @@ pub(crate) enum Entry {
 #[derive(Debug)]
 pub struct Line<I> {
     pub width: u16,
+    /// True if the text continues on the next line because it was wrapped,
+    /// false if the line ends with a newline character or the end of the text
+    pub wrapped: bool,
     pub entries: I,
 }
 
@@ -109,7 +125,7 @@ pub enum Segment<'a> {
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub(crate) enum LineEntry {
-    Width(u16),
+    Width(u16, bool),
     Str(u32, u32),
     SetStyle(ValueId),
     Newline,
@@ -141,6 +157,9 @@ pub struct Strings {
     // Byte index where the current line starts
     line: usize,
     current_width: LineWidth,
+    // The current line didn't fit (only used when the text isn't wrapped)
+    clipped: bool,
+    ellipsis: Option<String>,
 }
 
 impl Strings {
@@ -156,9 +175,17 @@ impl Strings {
             size: Size::new(0, 1),
             line: 0,
             current_width: LineWidth::ZERO,
+            clipped: false,
+            ellipsis: None,
         }
     }
 
+    /// Set the string that ends a truncated line when using [`Wrap::Truncate`].
+    /// The default is `…`
+    pub fn set_ellipsis(&mut self, ellipsis: impl Into<String>) {
+        self.ellipsis = Some(ellipsis.into());
+    }
+
     /// Layout another string slice.
     pub fn add_str(&mut self, s: &str) -> ProcessResult {
         if self.max.height == 0 || self.max.width == 0 {
@@ -169,14 +196,13 @@ impl Strings {
             return ProcessResult::Break;
         }
 
-        for word in s.split_inclusive(char::is_whitespace) {
-            self.bytes.extend(word.bytes());
-            for c in word.chars() {
-                if let res @ ProcessResult::Break = self.chomp(c) {
-                    self.bytes.truncate(self.chomper.index());
-                    self.freeze();
-                    return res;
-                }
+        let mut buf = [0; 4];
+        for c in s.chars() {
+            self.bytes.extend(c.encode_utf8(&mut buf).bytes());
+            if let res @ ProcessResult::Break = self.chomp(c) {
+                self.bytes.truncate(self.chomper.index());
+                self.freeze();
+                return res;
             }
         }
 
@@ -189,17 +215,18 @@ impl Strings {
         let lines = self.lines.split(|e| *e == LineEntry::Newline);
 
         lines.map(|entries| {
-            let LineEntry::Width(width) = entries[0] else { unreachable!() };
+            let LineEntry::Width(width, wrapped) = entries[0] else { unreachable!() };
 
             Line {
                 width,
+                wrapped,
                 entries: entries[1..].iter().map(|e| match e {
                     LineEntry::Str(from, to) => Segment::Str(
                         std::str::from_utf8(&self.bytes[*from as usize..*to as usize])
                             .expect("only strings written to the byte store"),
                     ),
                     LineEntry::SetStyle(style) => Segment::SetStyle(*style),
-                    LineEntry::Width(_) | LineEntry::Newline => unreachable!("consumed already"),
+                    LineEntry::Width(..) | LineEntry::Newline => unreachable!("consumed already"),
                 }),
             }
         })
@@ -213,7 +240,7 @@ impl Strings {
     /// Finalize the layout, converting entries to lines
     pub fn finish(&mut self) -> Size {
         self.frozen = true;
-        self.layout.sort_by(|a, b| a.0.cmp(&b.0));
+        self.layout.sort_by_key(|a| a.0);
 
         let last_line = self.line(self.bytes.len());
         let last_line_width = last_line.width();
@@ -222,14 +249,19 @@ impl Strings {
 
         // Write the entries as lines
         let mut from = 0;
-        for line in self.layout.split(|e| matches!(e.1, Entry::Newline)) {
+        for line in self.layout.split_inclusive(|e| matches!(e.1, Entry::Newline(_))) {
+            let (line, wrapped) = match line.split_last() {
+                Some(((_, Entry::Newline(wrapped)), line)) => (line, *wrapped),
+                _ => (line, false),
+            };
+
             // Find the line width (always the last entry)
             let width = match line.last() {
                 Some((_, Entry::LineWidth(w))) => *w,
                 _ => unreachable!("the last entry is always the line width"),
             };
 
-            self.lines.push(LineEntry::Width(width));
+            self.lines.push(LineEntry::Width(width, wrapped));
 
             for (i, entry) in line {
                 // Don't bother adding a string entry for an empty string
@@ -242,7 +274,7 @@ impl Strings {
                 match entry {
                     Entry::Style(style) => self.lines.push(LineEntry::SetStyle(*style)),
                     Entry::LineWidth(_) => {}
-                    Entry::Newline => unreachable!("consumed by the split"),
+                    Entry::Newline(_) => unreachable!("consumed by the split"),
                 }
             }
             self.lines.push(LineEntry::Newline);
@@ -261,14 +293,14 @@ impl Strings {
         self.frozen = true;
     }
 
-    fn newline(&mut self) {
+    fn newline(&mut self, wrapped: bool) {
         self.size.height += 1;
         self.update_width();
         self.line = match self.chomper {
             Chomper::Continuous(idx) => {
                 self.layout
                     .push((idx as u32, Entry::LineWidth(self.current_width.swap(0))));
-                self.layout.push((idx as u32, Entry::Newline));
+                self.layout.push((idx as u32, Entry::Newline(wrapped)));
                 idx
             }
             Chomper::WordBoundary {
@@ -278,7 +310,7 @@ impl Strings {
                 let diff = self.line(current_index).width() - self.line(word_boundary).width();
                 let width = *self.current_width - diff;
                 self.layout.push((word_boundary as u32, Entry::LineWidth(width as u16)));
-                self.layout.push((word_boundary as u32, Entry::Newline));
+                self.layout.push((word_boundary as u32, Entry::Newline(wrapped)));
                 let _ = self.current_width.swap(diff);
                 self.chomper = Chomper::Continuous(current_index);
                 word_boundary
@@ -321,7 +353,22 @@ impl Strings {
             }
 
             self.chomper.force_word_boundary();
-            self.newline();
+            self.newline(false);
+            self.clipped = false;
+            return ProcessResult::Continue;
+        }
+
+        // NOTE
+        // Without wrapping everything that doesn't fit on the line is dropped
+        if !self.wrap.wraps() && (self.clipped || width + *self.current_width > self.max.width) {
+            for _ in 0..c.len_utf8() {
+                self.bytes.pop();
+            }
+
+            if !self.clipped && self.wrap == Wrap::Truncate {
+                self.truncate_line();
+            }
+            self.clipped = true;
             return ProcessResult::Continue;
         }
 
@@ -338,7 +385,7 @@ impl Strings {
                 }
 
                 self.chomper.force_word_boundary();
-                self.newline();
+                self.newline(true);
 
                 return ProcessResult::Continue;
             }
@@ -347,7 +394,7 @@ impl Strings {
                 return ProcessResult::Break;
             }
 
-            self.newline();
+            self.newline(true);
         }
 
         self.chomper.chomp(c, self.wrap);
@@ -357,6 +404,39 @@ impl Strings {
     }
 }
 
+impl Strings {
+    // Make room for the ellipsis at the end of the current line, and add it
+    fn truncate_line(&mut self) {
+        let ellipsis = self.ellipsis.take().unwrap_or_else(|| "…".into());
+        let ellipsis_width = ellipsis.width();
+        if ellipsis_width > self.max.width {
+            self.ellipsis = Some(ellipsis);
+            return;
+        }
+
+        let mut index = self.bytes.len();
+        let mut width = *self.current_width;
+        for c in self.line(index).chars().rev() {
+            if width + ellipsis_width <= self.max.width && !c.is_whitespace() {
+                break;
+            }
+            index -= c.len_utf8();
+            width -= c.width().unwrap_or(0);
+        }
+
+        // Styles set in the removed part of the line apply to the ellipsis
+        self.bytes.truncate(index);
+        for (i, _) in self.layout.iter_mut().filter(|(i, _)| *i as usize > index) {
+            *i = index as u32;
+        }
+
+        self.bytes.extend(ellipsis.bytes());
+        self.chomper = Chomper::Continuous(self.bytes.len());
+        let _ = self.current_width.swap(width + ellipsis_width);
+        self.ellipsis = Some(ellipsis);
+    }
+}
+
 impl Default for Strings {
     fn default() -> Self {
         Self::new(Size::ZERO, Wrap::default())
@@ -460,6 +540,8 @@ mod test {
             (&["onereallylongword"], "onere\nallyl\nongwo\nrd"),
             (&["ahello do the"], "ahell\no do \nthe"),
             (&["hello do the"], "hello\ndo \nthe"),
+            (&["漢字かな"], "漢字\nかな"),
+            (&["a\u{301}bcde\u{301}f"], "a\u{301}bcde\u{301}\nf"),
         ];
 
         for (input, expected) in inputs {
@@ -499,6 +581,59 @@ mod test {
         }
     }
 
+    #[test]
+    fn no_wrap_layout() {
+        let inputs: &[(&[&str], &str)] = &[
+            (&["123 4567"], "123 4"),
+            (&["12", "3\n45", "678"], "123\n45678"),
+            (&["1234🐇"], "1234"),
+        ];
+
+        for (input, expected) in inputs {
+            test_layout(Size::new(5, 3), input, expected, Wrap::NoWrap);
+        }
+    }
+
+    #[test]
+    fn truncate_layout() {
+        let inputs: &[(&[&str], &str)] = &[
+            (&["12345"], "12345"),
+            (&["123456"], "1234…"),
+            (&["123 4567"], "123…"),
+            (&["12🐇🐇"], "12🐇…"),
+            (&["123456\n1"], "1234…\n1"),
+        ];
+
+        for (input, expected) in inputs {
+            test_layout(Size::new(5, 3), input, expected, Wrap::Truncate);
+        }
+
+        let mut strings = Strings::new(Size::new(8, 1), Wrap::Truncate);
+        strings.set_ellipsis("...");
+        strings.add_str("long file name");
+        strings.finish();
+        let line = strings.lines().next().unwrap();
+        assert_eq!(line.width, 7);
+        let text = line
+            .entries
+            .map(|e| match e {
+                Segment::Str(s) => s,
+                Segment::SetStyle(_) => "",
+            })
+            .collect::<String>();
+        assert_eq!(text, "long...");
+    }
+
+    #[test]
+    fn wrapped_lines() {
+        let mut strings = Strings::new(Size::new(5, 10), Wrap::Normal);
+        strings.add_str("one two three\nfour");
+        strings.finish();
+
+        let wrapped = strings.lines().map(|line| line.wrapped).collect::<Vec<_>>();
+        assert_eq!(wrapped, [true, true, false, false]);
+    }
+
     #[test]
     fn freeze_layout() {
         let mut strings = Strings::new(Size::new(100, 10), Wrap::Normal);
@@ -0,0 +1,150 @@
+//! Word boundaries for text navigation, e.g moving the cursor with ctrl + arrow keys
+//! and selecting a word with a double click.
+//!
+//! The positions are char indices into the text.
+use std::ops::Range;
+
+use anathema_state::CommonVal;
+
+/// The rules for what makes up a word.
+///
+/// ```
+/// # use anathema_widgets::layout::words::WordBoundary;
+/// let text = "let max_width = don't;".chars().collect::<Vec<_>>();
+/// assert_eq!(WordBoundary::Identifier.word_at(&text, 6), 4..13);
+/// assert_eq!(WordBoundary::Natural.word_at(&text, 6), 4..7);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum WordBoundary {
+    /// Letters, digits and underscores, e.g `max_width`
+    #[default]
+    Identifier,
+    /// Letters and digits, including apostrophes and hyphens between them, e.g `don't` and `well-known`
+    Natural,
+    /// Letters and digits, along with the given chars, e.g `"-./"` for paths
+    Custom(&'static str),
+}
+
+impl WordBoundary {
+    /// Returns true if the char at the index is part of a word
+    pub fn is_word_char(&self, text: &[char], index: usize) -> bool {
+        let Some(&c) = text.get(index) else { return false };
+        if c.is_alphanumeric() {
+            return true;
+        }
+
+        match self {
+            Self::Identifier => c == '_',
+            Self::Natural => {
+                let joiner = matches!(c, '\'' | '’' | '-');
+                let before = index.checked_sub(1).and_then(|i| text.get(i));
+                let after = text.get(index + 1);
+                joiner && before.is_some_and(|c| c.is_alphanumeric()) && after.is_some_and(|c| c.is_alphanumeric())
+            }
+            Self::Custom(chars) => chars.contains(c),
+        }
+    }
+
+    /// The end of the word at or after the index, e.g where ctrl + right moves the cursor
+    pub fn next(&self, text: &[char], index: usize) -> usize {
+        let mut index = index.min(text.len());
+        while index < text.len() && !self.is_word_char(text, index) {
+            index += 1;
+        }
+        while index < text.len() && self.is_word_char(text, index) {
+            index += 1;
+        }
+        index
+    }
+
+    /// The start of the word before the index, e.g where ctrl + left moves the cursor
+    pub fn previous(&self, text: &[char], index: usize) -> usize {
+        let mut index = index.min(text.len());
+        while index > 0 && !self.is_word_char(text, index - 1) {
+            index -= 1;
+        }
+        while index > 0 && self.is_word_char(text, index - 1) {
+            index -= 1;
+        }
+        index
+    }
+
+    /// The range of the word at the index, e.g the selection of a double click.
+    ///
+    /// If the char at the index is not part of a word the range covers
+    /// the surrounding whitespace, or the single char for anything else.
+    pub fn word_at(&self, text: &[char], index: usize) -> Range<usize> {
+        if index >= text.len() {
+            return text.len()..text.len();
+        }
+
+        if self.is_word_char(text, index) {
+            let start = self.previous(text, index + 1);
+            let end = self.next(text, index);
+            return start..end;
+        }
+
+        if !text[index].is_whitespace() {
+            return index..index + 1;
+        }
+
+        let mut start = index;
+        while start > 0 && text[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        let mut end = index;
+        while end < text.len() && text[end].is_whitespace() {
+            end += 1;
+        }
+        start..end
+    }
+}
+
+impl TryFrom<CommonVal<'_>> for WordBoundary {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        match value.to_common_str().as_ref() {
+            "identifier" => Ok(Self::Identifier),
+            "natural" => Ok(Self::Natural),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn navigate_words() {
+        let text = chars("fn set_value(x) -> well-known");
+
+        let boundary = WordBoundary::Identifier;
+        assert_eq!(boundary.next(&text, 0), 2);
+        assert_eq!(boundary.next(&text, 2), 12);
+        assert_eq!(boundary.previous(&text, 12), 3);
+        assert_eq!(boundary.previous(&text, text.len()), 24);
+
+        let boundary = WordBoundary::Natural;
+        assert_eq!(boundary.next(&text, 2), 6);
+        assert_eq!(boundary.previous(&text, text.len()), 19);
+
+        let boundary = WordBoundary::Custom("-");
+        assert_eq!(boundary.previous(&text, text.len()), 19);
+    }
+
+    #[test]
+    fn select_word() {
+        let text = chars("a  -= b");
+        let boundary = WordBoundary::Identifier;
+        assert_eq!(boundary.word_at(&text, 0), 0..1);
+        assert_eq!(boundary.word_at(&text, 1), 1..3);
+        assert_eq!(boundary.word_at(&text, 3), 3..4);
+        assert_eq!(boundary.word_at(&text, 10), 7..7);
+    }
+}
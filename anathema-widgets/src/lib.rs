@@ -3,22 +3,28 @@ pub use values::ValueIndex;
 
 pub use crate::nodes::eval::EvalContext;
 pub use crate::nodes::{eval_blueprint, try_resolve_future_values, update_tree, Element, Stringify, WidgetKind};
+pub use crate::user_data::UserData;
 pub use crate::values::{Value, Values};
 pub use crate::widget::{
-    AnyWidget, AttributeStorage, Attributes, ComponentParents, Components, DirtyWidgets, Elements, Factory,
-    FloatingWidgets, LayoutChildren, PaintChildren, PositionChildren, Widget, WidgetId, WidgetRenderer, WidgetTree,
+    Anchor, AnyWidget, AttributeMetadata, AttributeStorage, AttributeType, Attributes, Children, ComponentParents,
+    Components, DirtyWidgets, Elements, Factory, FloatingWidgets, LayoutChildren, Layouts, PaintChildren,
+    PositionChildren, Widget, WidgetId, WidgetMetadata, WidgetRenderer, WidgetTree,
 };
 
+pub mod animation;
 pub mod components;
 mod container;
 pub mod debug;
 pub mod error;
+pub mod export;
 pub mod expressions;
+pub mod functions;
 pub mod layout;
 mod nodes;
 pub mod paint;
 mod scope;
 #[cfg(test)]
 mod testing;
+mod user_data;
 mod values;
 mod widget;
@@ -6,8 +6,12 @@ use anathema_templates::WidgetComponentId;
 
 use crate::components::{AnyComponent, ComponentKind};
 use crate::expressions::EvalValue;
+use crate::scope::Scope;
 use crate::{Value, ValueIndex};
 
+/// True while the component has focus, e.g `if has_focus` in the template of the component
+pub(super) const HAS_FOCUS: &str = "has_focus";
+
 pub type ExternalState<'bp> = SmallMap<&'bp str, (ValueIndex, Value<'bp, EvalValue<'bp>>)>;
 
 #[derive(Debug)]
@@ -20,6 +24,7 @@ pub struct Component<'bp> {
     pub parent: Option<WidgetComponentId>,
     pub kind: ComponentKind,
     pub assoc_functions: &'bp [(StringId, StringId)],
+    has_focus: anathema_state::Value<bool>,
 }
 
 impl<'bp> Component<'bp> {
@@ -42,9 +47,25 @@ impl<'bp> Component<'bp> {
             kind,
             assoc_functions,
             parent,
+            has_focus: anathema_state::Value::new(false),
+        }
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.has_focus.copy_value()
+    }
+
+    /// Set by the runtime when the component gains or loses focus
+    pub fn set_focus(&mut self, focus: bool) {
+        if self.has_focus() != focus {
+            self.has_focus.set(focus);
         }
     }
 
+    pub(super) fn scope_focus(&self, scope: &mut Scope<'bp>) {
+        scope.scope_pending(HAS_FOCUS, self.has_focus.to_pending());
+    }
+
     pub(crate) fn state_id(&self) -> StateId {
         self.state_id
     }
@@ -1,8 +1,10 @@
 use anathema_store::tree::{Node, TreeValues};
 use anathema_templates::blueprints::Blueprint;
 
+use crate::error::Result;
 use crate::expressions::EvalValue;
-use crate::{Value, WidgetKind};
+use crate::nodes::EvalContext;
+use crate::{eval_blueprint, Value, WidgetKind, WidgetTree};
 
 #[derive(Debug)]
 pub struct ControlFlow;
@@ -37,9 +39,86 @@ impl ControlFlow {
     }
 }
 
+// A branch of a control flow, as seen when the branches are updated
+struct Branch<'bp> {
+    path: Box<[u16]>,
+    is_true: bool,
+    keep_alive: bool,
+    body: &'bp [Blueprint],
+    has_children: bool,
+}
+
+impl<'bp> Branch<'bp> {
+    fn new(path: &[u16], widget: &WidgetKind<'bp>, has_children: bool) -> Self {
+        let (is_true, keep_alive, body) = match widget {
+            WidgetKind::If(widget) => (widget.is_true(), widget.keep_alive, widget.body),
+            WidgetKind::Else(widget) => (widget.is_true(), widget.keep_alive, widget.body),
+            _ => unreachable!(),
+        };
+
+        Self {
+            path: path.into(),
+            is_true,
+            keep_alive,
+            body,
+            has_children,
+        }
+    }
+}
+
+/// Evaluate the body of the branch that should be shown, unless it's already in the tree,
+/// and remove the bodies of the hidden branches.
+///
+/// Branches that are kept alive (the default) are evaluated once and never removed,
+/// so only branches with `keep_alive: false` are changed here.
+///
+/// `checked_out` is the branch that is currently checked out of the tree (if any),
+/// e.g the branch that received a change to its condition.
+pub(crate) fn update_branches<'bp>(
+    path: &[u16],
+    checked_out: Option<(&[u16], &WidgetKind<'bp>)>,
+    ctx: &mut EvalContext<'_, '_, 'bp>,
+    tree: &mut WidgetTree<'bp>,
+) -> Result<()> {
+    let mut branches = vec![];
+    tree.children_of(path, |node, values| {
+        let has_children = !node.children().is_empty();
+        let branch = match values.get_mut(node.value()) {
+            Some((path, widget)) => Branch::new(path, widget, has_children),
+            None => match checked_out {
+                Some((path, widget)) => Branch::new(path, widget, has_children),
+                None => return,
+            },
+        };
+        branches.push(branch);
+    });
+
+    let shown = branches.iter().position(|branch| branch.is_true);
+
+    for (index, branch) in branches.iter().enumerate() {
+        if branch.keep_alive {
+            continue;
+        }
+
+        if Some(index) == shown {
+            if !branch.has_children {
+                for bp in branch.body {
+                    eval_blueprint(bp, ctx, &branch.path, tree)?;
+                }
+            }
+        } else if branch.has_children {
+            tree.remove_children(&branch.path);
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct If<'bp> {
     pub cond: Value<'bp, EvalValue<'bp>>,
+    pub body: &'bp [Blueprint],
+    pub keep_alive: bool,
     pub show: bool,
 }
 
@@ -53,6 +132,7 @@ impl If<'_> {
 pub struct Else<'bp> {
     pub cond: Option<Value<'bp, EvalValue<'bp>>>,
     pub body: &'bp [Blueprint],
+    pub keep_alive: bool,
     pub show: bool,
 }
 
@@ -1,10 +1,10 @@
 use anathema_geometry::{Pos, Rect, Size};
 
 use crate::container::Container;
-use crate::layout::{Constraints, LayoutCtx, Viewport};
+use crate::layout::{Constraints, LayoutCtx, LayoutError, Viewport};
 use crate::paint::{PaintCtx, Unsized};
 use crate::widget::{PaintChildren, PositionChildren};
-use crate::{AttributeStorage, LayoutChildren, WidgetId};
+use crate::{AttributeStorage, LayoutChildren, UserData, WidgetId};
 
 #[derive(Debug)]
 pub struct Element<'bp> {
@@ -24,9 +24,15 @@ impl<'bp> Element<'bp> {
     pub fn layout(
         &mut self,
         children: LayoutChildren<'_, '_, 'bp>,
-        constraints: Constraints,
+        mut constraints: Constraints,
         ctx: &mut LayoutCtx<'_, 'bp>,
     ) -> Size {
+        // Report invalid constraints made by the parent, once
+        if let Err(err) = constraints.validate() {
+            ctx.report(self.id(), LayoutError::Constraints(err));
+            constraints.clear_conflict();
+        }
+
         self.container.layout(children, constraints, ctx)
     }
 
@@ -51,8 +57,9 @@ impl<'bp> Element<'bp> {
         self.container.paint(children, ctx, attribute_storage)
     }
 
+    /// The size the element takes up in its parent, which includes the margin
     pub fn size(&self) -> Size {
-        self.container.size
+        self.container.size + self.container.margin.size()
     }
 
     pub fn inner_bounds(&self) -> Rect {
@@ -91,4 +98,56 @@ impl<'bp> Element<'bp> {
     pub fn get_pos(&self) -> Pos {
         self.container.pos
     }
+
+    /// The region the element is painted in, in screen coordinates, once it's positioned
+    pub fn region(&self) -> Rect {
+        self.container
+            .inner
+            .any_painted_region(self.container.pos, self.container.size)
+    }
+
+    /// The widget this element is anchored to, see [`crate::Widget::anchor`]
+    pub fn anchor(&self) -> Option<WidgetId> {
+        self.container.inner.any_anchor()
+    }
+
+    /// Set the region of the anchor.
+    /// The element is laid out again as the anchor might have moved.
+    pub fn set_anchor_region(&mut self, region: Option<Rect>) {
+        self.container.inner.any_set_anchor_region(region);
+        self.container.needs_layout = true;
+    }
+
+    /// Scroll the content of the element so the region is in view, see [`crate::Widget::scroll_into_view`].
+    /// Returns `false` if the element doesn't scroll.
+    pub fn scroll_into_view(&mut self, region: Rect) -> bool {
+        let viewport = Rect::from((self.container.pos, self.container.size));
+        let scrolled = self.container.inner.any_scroll_into_view(region, viewport);
+        if scrolled {
+            self.container.needs_layout = true;
+        }
+        scrolled
+    }
+
+    /// Data attached to the element by the application, see [`UserData`]
+    pub fn user_data(&self) -> &UserData {
+        &self.container.user_data
+    }
+
+    pub fn user_data_mut(&mut self) -> &mut UserData {
+        &mut self.container.user_data
+    }
+
+    /// Take the element out of the layout until it's laid out again,
+    /// e.g a child that is scrolled out of view and no longer laid out.
+    /// The element is not painted, and not hit by [`Elements::widget_at`](crate::Elements::widget_at).
+    pub fn reset(&mut self) {
+        self.container.size = Size::ZERO;
+        self.container.needs_layout = true;
+    }
+
+    /// Returns true if the element is a portal, see [`crate::Widget::is_portal`]
+    pub fn is_portal(&self) -> bool {
+        self.container.inner.any_is_portal()
+    }
 }
@@ -1,19 +1,19 @@
 use anathema_geometry::{Pos, Rect, Size};
-use anathema_state::{AnyState, States, Value};
+use anathema_state::{AnyState, States};
 use anathema_store::smallmap::{SmallIndex, SmallMap};
 use anathema_templates::blueprints::{Component, ControlFlow, Else, For, If, Single};
 use anathema_templates::{Globals, WidgetComponentId};
 
 use super::element::Element;
-use super::loops::{Iteration, LOOP_INDEX};
 use super::{component, controlflow};
 use crate::components::{AnyComponent, ComponentKind, ComponentRegistry};
 use crate::container::Container;
 use crate::error::{Error, Result};
 use crate::expressions::{eval, eval_collection};
+use crate::layout::Spacing;
 use crate::values::{ValueId, ValueIndex};
 use crate::widget::{Attributes, Components, FloatingWidgets, ValueKey};
-use crate::{eval_blueprint, AttributeStorage, Factory, Scope, WidgetKind, WidgetTree};
+use crate::{eval_blueprint, AttributeStorage, Factory, Scope, UserData, WidgetKind, WidgetTree};
 
 /// Evaluation context
 pub struct EvalContext<'a, 'b, 'bp> {
@@ -120,6 +120,9 @@ impl Evaluator for SingleEval {
             pos: Pos::ZERO,
             size: Size::ZERO,
             inner_bounds: Rect::ZERO,
+            padding: Spacing::ZERO,
+            margin: Spacing::ZERO,
+            user_data: UserData::default(),
             needs_layout: true,
             needs_position: false,
         };
@@ -155,16 +158,13 @@ impl ForLoopEval {
 
             let iter_id = tree
                 .insert(parent)
-                .commit_child(WidgetKind::Iteration(Iteration {
-                    loop_index: Value::new(index as i64),
-                    binding: for_loop.binding,
-                }))
+                .commit_child(WidgetKind::Iteration(for_loop.iteration(index)))
                 .ok_or(Error::TreeTransactionFailed)?;
 
             // Scope the iteration value
             tree.with_value_mut(iter_id, |parent, widget, tree| {
                 let WidgetKind::Iteration(iter) = widget else { unreachable!() };
-                ctx.scope.scope_pending(LOOP_INDEX, iter.loop_index.to_pending());
+                iter.scope_index(ctx.scope);
 
                 for bp in for_loop.body {
                     eval_blueprint(bp, ctx, parent, tree)?;
@@ -194,6 +194,7 @@ impl Evaluator for ForLoopEval {
 
         let for_loop = super::loops::For {
             binding: &for_loop.binding,
+            index: for_loop.index.as_deref(),
             collection: eval_collection(&for_loop.data, ctx.globals, ctx.scope, ctx.states, value_id),
             body: &for_loop.body,
         };
@@ -229,7 +230,7 @@ impl Evaluator for ControlFlowEval {
         let for_loop_id = transaction.commit_child(widget).ok_or(Error::TreeTransactionFailed)?;
         let parent = tree.path(for_loop_id);
 
-        tree.with_value_mut(for_loop_id, move |parent, _widget, tree| {
+        tree.with_value_mut(for_loop_id, |parent, _widget, tree| {
             IfEval.eval(&control_flow.if_node, ctx, parent, tree)?;
             control_flow
                 .elses
@@ -263,6 +264,9 @@ impl Evaluator for ControlFlowEval {
             }
         });
 
+        // Evaluate the body of the branch that is shown, if it's not kept alive
+        controlflow::update_branches(&parent, None, ctx, tree)?;
+
         Ok(())
     }
 }
@@ -285,15 +289,23 @@ impl Evaluator for IfEval {
         let value_id = (node_id, ValueIndex::ZERO);
         let cond = eval(&input.cond, ctx.globals, ctx.scope, ctx.states, value_id);
 
-        let if_widget = controlflow::If { cond, show: false };
+        let if_widget = controlflow::If {
+            cond,
+            body: &input.body,
+            keep_alive: input.keep_alive,
+            show: false,
+        };
 
         let if_widget_id = transaction
             .commit_child(WidgetKind::If(if_widget))
             .ok_or(Error::TreeTransactionFailed)?;
 
-        let parent = tree.path(if_widget_id);
-        for bp in &input.body {
-            eval_blueprint(bp, ctx, &parent, tree)?;
+        // A body that isn't kept alive is only evaluated while it's shown
+        if input.keep_alive {
+            let parent = tree.path(if_widget_id);
+            for bp in &input.body {
+                eval_blueprint(bp, ctx, &parent, tree)?;
+            }
         }
 
         Ok(())
@@ -324,16 +336,20 @@ impl Evaluator for ElseEval {
         let else_widget = controlflow::Else {
             cond,
             body: &input.body,
+            keep_alive: input.keep_alive,
             show: false,
         };
 
-        let _ = transaction
+        let widget_id = transaction
             .commit_child(WidgetKind::Else(else_widget))
             .ok_or(Error::TreeTransactionFailed)?;
 
-        let parent = tree.path(widget_id);
-        for bp in &input.body {
-            eval_blueprint(bp, ctx, &parent, tree)?;
+        // A body that isn't kept alive is only evaluated while it's shown
+        if input.keep_alive {
+            let parent = tree.path(widget_id);
+            for bp in &input.body {
+                eval_blueprint(bp, ctx, &parent, tree)?;
+            }
         }
 
         Ok(())
@@ -404,6 +420,7 @@ impl Evaluator for ComponentEval {
             // Insert internal state
             let state_id = component.state_id();
             ctx.scope.insert_state(state_id);
+            component.scope_focus(ctx.scope);
 
             // Insert external state (if there is one)
             if let Some(state) = &component.external_state {
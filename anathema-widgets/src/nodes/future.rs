@@ -2,14 +2,14 @@ use anathema_state::States;
 use anathema_store::tree::PathFinder;
 use anathema_templates::Globals;
 
+use super::controlflow;
 use super::element::Element;
 use super::eval::EvalContext;
-use super::loops::LOOP_INDEX;
 use super::update::scope_value;
 use crate::components::ComponentRegistry;
-use crate::error::{Error, Result};
-use crate::expressions::{eval, eval_collection};
-use crate::values::{Collection, ValueId};
+use crate::error::Result;
+use crate::expressions::eval;
+use crate::values::ValueId;
 use crate::widget::{Components, FloatingWidgets};
 use crate::{AttributeStorage, Factory, Scope, WidgetKind, WidgetTree};
 
@@ -86,6 +86,8 @@ fn try_resolve_value<'bp>(
     path: &[u16],
     tree: &mut WidgetTree<'bp>,
 ) -> Result<()> {
+    let mut update_branches = false;
+
     match widget {
         WidgetKind::Element(Element { container, .. }) => {
             let Some(val) = ctx
@@ -101,92 +103,21 @@ fn try_resolve_value<'bp>(
                 *val = value;
             }
         }
-        WidgetKind::For(for_loop) => {
-            // 1. Assign a new collection
-            // 2. Remove the current children
-            // 3. Build up new children
-
-            for_loop.collection = eval_collection(
-                for_loop.collection.expr.unwrap(),
-                ctx.globals,
-                ctx.scope,
-                ctx.states,
-                value_id,
-            );
-
-            tree.remove_children(path);
-
-            let collection = &for_loop.collection;
-            let binding = &for_loop.binding;
-            let body = for_loop.body;
-            let parent = path;
-
-            for index in 0..collection.count() {
-                ctx.scope.push();
-
-                match collection.inner() {
-                    Collection::Static(expressions) => {
-                        let downgrade = expressions[index].downgrade();
-                        ctx.scope.scope_downgrade(binding, downgrade)
-                    }
-                    Collection::Dyn(value_ref) => {
-                        let value = value_ref
-                            .as_state()
-                            .and_then(|state| state.state_lookup(index.into()))
-                            .expect("the collection has a value since it has a length");
-                        ctx.scope.scope_pending(binding, value)
-                    }
-                    Collection::Index(collection, _) => match &**collection {
-                        Collection::Static(expressions) => {
-                            let downgrade = expressions[index].downgrade();
-                            ctx.scope.scope_downgrade(binding, downgrade)
-                        }
-                        Collection::Dyn(value_ref) => {
-                            let value = value_ref
-                                .as_state()
-                                .and_then(|state| state.state_lookup(index.into()))
-                                .expect("the collection has a value since it has a length");
-                            ctx.scope.scope_pending(binding, value)
-                        }
-                        Collection::Future => {}
-                        Collection::Index(_, _) => unreachable!("maaybe it's not?"),
-                    },
-                    Collection::Future => {}
-                }
-
-                let iter_id = tree
-                    .insert(parent)
-                    .commit_child(WidgetKind::Iteration(super::loops::Iteration {
-                        loop_index: anathema_state::Value::new(index as i64),
-                        binding,
-                    }))
-                    .ok_or(Error::TreeTransactionFailed)?;
-
-                // Scope the iteration value
-                tree.with_value_mut(iter_id, |parent, widget, tree| {
-                    let WidgetKind::Iteration(iter) = widget else { unreachable!() };
-                    ctx.scope.scope_pending(LOOP_INDEX, iter.loop_index.to_pending());
-
-                    for bp in body {
-                        crate::eval_blueprint(bp, ctx, parent, tree)?;
-                    }
-                    Ok(())
-                })?;
-
-                ctx.scope.pop();
-            }
-        }
+        // Assign a new collection and build up new children
+        WidgetKind::For(for_loop) => for_loop.rebuild(ctx, value_id, path, tree)?,
         WidgetKind::If(widget) => {
             if let Some(expr) = widget.cond.expr {
                 let value = eval(expr, ctx.globals, ctx.scope, ctx.states, value_id);
                 widget.cond = value;
             }
+            update_branches = true;
         }
         WidgetKind::Else(el) => {
             let Some(val) = &mut el.cond else { return Ok(()) };
             if let Some(expr) = val.expr {
                 *val = eval(expr, ctx.globals, ctx.scope, ctx.states, value_id);
             }
+            update_branches = true;
         }
         WidgetKind::ControlFlow(_) => unreachable!(),
         WidgetKind::Iteration(_) => unreachable!(),
@@ -202,6 +133,12 @@ fn try_resolve_value<'bp>(
         }
     }
 
+    // The resolved condition might change which branch is shown
+    if update_branches {
+        let parent = &path[..path.len() - 1];
+        controlflow::update_branches(parent, Some((path, widget)), ctx, tree)?;
+    }
+
     Ok(())
 }
 
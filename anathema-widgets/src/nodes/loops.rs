@@ -15,6 +15,8 @@ pub(super) const LOOP_INDEX: &str = "loop";
 #[derive(Debug)]
 pub struct For<'bp> {
     pub(super) binding: &'bp str,
+    /// The name of the loop index, e.g `i` in `for x, i in list`
+    pub(super) index: Option<&'bp str>,
     pub(super) collection: Value<'bp, Collection<'bp>>,
     pub(super) body: &'bp [Blueprint],
 }
@@ -28,6 +30,14 @@ impl<'bp> For<'bp> {
         self.collection.inner()
     }
 
+    pub(super) fn iteration(&self, index: usize) -> Iteration<'bp> {
+        Iteration {
+            loop_index: anathema_state::Value::new(index as i64),
+            binding: self.binding,
+            index: self.index,
+        }
+    }
+
     pub(crate) fn update(
         &mut self,
         ctx: &mut EvalContext<'_, '_, 'bp>,
@@ -48,13 +58,17 @@ impl<'bp> For<'bp> {
                 ctx.scope.push();
                 ctx.scope.scope_pending(self.binding, *value);
 
-                let insert_at = new_node_path(path, *index as u16);
+                // A reversed collection has the new value after as many
+                // iterations as there are values after the index
+                let index = match self.collection.is_reversed() {
+                    true => iteration_count(path, tree) - *index as usize,
+                    false => *index as usize,
+                };
+
+                let insert_at = new_node_path(path, index as u16);
                 let iter_id = tree
                     .insert(&insert_at)
-                    .commit_at(WidgetKind::Iteration(Iteration {
-                        loop_index: anathema_state::Value::new(*index as i64),
-                        binding: self.binding,
-                    }))
+                    .commit_at(WidgetKind::Iteration(self.iteration(index)))
                     .unwrap(); // TODO unwrap
 
                 // Bump the index for every subsequent sibling of the newly inserted node
@@ -88,7 +102,7 @@ impl<'bp> For<'bp> {
                     // change is applied, which would lead to scoping `"c" to `0`
                     // twice.
                     let WidgetKind::Iteration(iter) = iter_widget else { unreachable!() };
-                    ctx.scope.scope_pending(LOOP_INDEX, iter.loop_index.to_pending());
+                    iter.scope_index(ctx.scope);
 
                     for bp in self.body {
                         eval_blueprint(bp, ctx, parent, tree)?;
@@ -100,48 +114,24 @@ impl<'bp> For<'bp> {
                 ctx.scope.pop();
             }
             Change::Removed(index) => {
-                let child_to_remove = new_node_path(path, *index as u16);
-                tree.remove(&child_to_remove);
-            }
-            Change::Dropped => {
-                tree.remove_children(path);
-
-                // TODO unwrap, ewww
-                self.collection = eval_collection(
-                    self.collection.expr.unwrap(),
-                    ctx.globals,
-                    ctx.scope,
-                    ctx.states,
-                    value_id,
-                );
-
-                for index in 0..self.collection.count() {
-                    self.scope_value(ctx.scope, index);
-                    ctx.scope.push();
-
-                    let iter_id = tree
-                        .insert(path)
-                        .commit_child(WidgetKind::Iteration(Iteration {
-                            loop_index: anathema_state::Value::new(index as i64),
-                            binding: self.binding,
-                        }))
-                        .ok_or(Error::TreeTransactionFailed)?;
-
-                    // Scope the iteration value
-                    tree.with_value_mut(iter_id, |parent, widget, tree| -> Result<()> {
-                        let WidgetKind::Iteration(iter) = widget else { unreachable!() };
-                        ctx.scope.scope_pending(LOOP_INDEX, iter.loop_index.to_pending());
-
-                        for bp in self.body {
-                            eval_blueprint(bp, ctx, parent, tree)?;
-                        }
-
-                        Ok(())
-                    })?;
+                let index = match self.collection.is_reversed() {
+                    true => iteration_count(path, tree) - 1 - *index as usize,
+                    false => *index as usize,
+                };
+                let child_to_remove = new_node_path(path, index as u16);
+
+                // Lower the index for every subsequent sibling of the removed node
+                tree.children_after(&child_to_remove, |node, values| {
+                    let iter_widget = values.get_mut(node.value());
+                    let Some((_, WidgetKind::Iteration(iter))) = iter_widget else { unreachable!() };
+                    *iter.loop_index.to_mut() -= 1;
+                });
 
-                    ctx.scope.pop();
-                }
+                tree.remove(&child_to_remove);
             }
+            Change::Dropped => self.rebuild(ctx, value_id, path, tree)?,
+            // The start or the end of a range changed
+            Change::Changed if self.collection.is_range() => self.rebuild(ctx, value_id, path, tree)?,
             Change::Changed => {
                 // TODO implement this as an optimisation once the runtime is done.
                 //      Use this to flag the element as needs-layout.
@@ -154,12 +144,77 @@ impl<'bp> For<'bp> {
 
         Ok(())
     }
+
+    /// Evaluate the collection again and replace every iteration
+    pub(super) fn rebuild(
+        &mut self,
+        ctx: &mut EvalContext<'_, '_, 'bp>,
+        value_id: ValueId,
+        path: &[u16],
+        tree: &mut WidgetTree<'bp>,
+    ) -> Result<()> {
+        tree.remove_children(path);
+
+        // TODO unwrap, ewww
+        self.collection = eval_collection(
+            self.collection.expr.unwrap(),
+            ctx.globals,
+            ctx.scope,
+            ctx.states,
+            value_id,
+        );
+
+        for index in 0..self.collection.count() {
+            ctx.scope.push();
+            self.scope_value(ctx.scope, index);
+
+            let iter_id = tree
+                .insert(path)
+                .commit_child(WidgetKind::Iteration(self.iteration(index)))
+                .ok_or(Error::TreeTransactionFailed)?;
+
+            // Scope the iteration value
+            tree.with_value_mut(iter_id, |parent, widget, tree| -> Result<()> {
+                let WidgetKind::Iteration(iter) = widget else { unreachable!() };
+                iter.scope_index(ctx.scope);
+
+                for bp in self.body {
+                    eval_blueprint(bp, ctx, parent, tree)?;
+                }
+
+                Ok(())
+            })?;
+
+            ctx.scope.pop();
+        }
+
+        Ok(())
+    }
+}
+
+// The number of iterations in the tree, which can differ from the
+// number of values in the collection while changes are applied
+fn iteration_count(path: &[u16], tree: &mut WidgetTree<'_>) -> usize {
+    let mut count = 0;
+    tree.children_of(path, |_, _| count += 1);
+    count
 }
 
 #[derive(Debug)]
 pub struct Iteration<'bp> {
     pub loop_index: anathema_state::Value<i64>,
     pub binding: &'bp str,
+    pub index: Option<&'bp str>,
+}
+
+impl<'bp> Iteration<'bp> {
+    /// Scope the loop index, as `loop` and the name of the index binding
+    pub(super) fn scope_index(&self, scope: &mut Scope<'bp>) {
+        scope.scope_pending(LOOP_INDEX, self.loop_index.to_pending());
+        if let Some(index) = self.index {
+            scope.scope_pending(index, self.loop_index.to_pending());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -275,19 +330,19 @@ mod test {
 
         let expected = "
 <for>
-    <iter binding = x, index = 2>
+    <iter binding = x, index = 0>
         test Int(9)
-    <iter binding = x, index = 3>
+    <iter binding = x, index = 1>
         test Int(1)
-    <iter binding = x, index = 4>
+    <iter binding = x, index = 2>
         test Int(2)
-    <iter binding = x, index = 5>
+    <iter binding = x, index = 3>
         test Int(3)
-    <iter binding = x, index = 6>
+    <iter binding = x, index = 4>
         test Int(100)
-    <iter binding = x, index = 7>
+    <iter binding = x, index = 5>
         test Int(101)
-    <iter binding = x, index = 8>
+    <iter binding = x, index = 6>
         test Int(102)";
         assert_eq!(expected.trim(), output.trim());
     }
@@ -348,6 +403,150 @@ mod test {
     <iter binding = x, index = 3>
         test Int(4)
             test Int(4)
+";
+        assert_eq!(expected.trim(), output.trim());
+    }
+
+    #[test]
+    fn reversed_loop_remove() {
+        let mut list = List::empty();
+        list.push_back(1u32);
+        list.push_back(2u32);
+        list.push_back(3u32);
+
+        let mut map = Map::<List<_>>::empty();
+        map.insert("a", list);
+
+        let tpl = "
+        for x, i in reverse(a)
+            test x
+                test i
+        ";
+
+        let (blueprint, globals) = Document::new(tpl).compile().unwrap();
+        let mut widget_tree = WidgetTree::empty();
+        let mut attribute_storage = AttributeStorage::empty();
+        let mut floating_widgets = FloatingWidgets::empty();
+        let factory = setup_test_factory();
+        let mut component_registry = ComponentRegistry::new();
+        let mut components = Components::new();
+        let mut states = States::new();
+        let state_id = states.insert(Box::new(map));
+        let mut scope = Scope::new();
+        scope.insert_state(state_id);
+        let mut ctx = EvalContext::new(
+            &globals,
+            &factory,
+            &mut scope,
+            &mut states,
+            &mut component_registry,
+            &mut attribute_storage,
+            &mut floating_widgets,
+            &mut components,
+        );
+
+        eval_blueprint(&blueprint, &mut ctx, &[], &mut widget_tree).unwrap();
+
+        {
+            let map = states.get_mut(StateId::ZERO).unwrap();
+            let map = map
+                .to_any_mut()
+                .downcast_mut::<anathema_state::Value<Map<List<u32>>>>()
+                .unwrap();
+            let mut map = map.to_mut();
+            let list = map.get_mut("a").unwrap();
+            list.push_back(4); // 1, 2, 3, 4
+            list.insert(0, 0); // 0, 1, 2, 3, 4
+            list.remove(2); // 0, 1, 3, 4
+        }
+
+        let mut local_changes = Changes::empty();
+        drain_changes(&mut local_changes);
+        local_changes.drain().rev().for_each(|(subs, change)| {
+            subs.with(|sub| {
+                // The removed value is dropped after the iteration is removed
+                let Some(widget_path): Option<Box<_>> = widget_tree.try_path_ref(sub).map(Into::into) else {
+                    return;
+                };
+                let mut scope = Scope::with_capacity(10);
+                update_tree(
+                    &globals,
+                    &factory,
+                    &mut scope,
+                    &mut states,
+                    &mut component_registry,
+                    &change,
+                    sub,
+                    &widget_path,
+                    &mut widget_tree,
+                    &mut attribute_storage,
+                    &mut floating_widgets,
+                    &mut components,
+                );
+            });
+        });
+
+        let mut stringify = Stringify::new(&attribute_storage);
+        widget_tree.apply_visitor(&mut stringify);
+        let output = stringify.finish();
+
+        let expected = "
+<for>
+    <iter binding = x, index = 0>
+        test Int(4)
+            test Int(0)
+    <iter binding = x, index = 1>
+        test Int(3)
+            test Int(1)
+    <iter binding = x, index = 2>
+        test Int(1)
+            test Int(2)
+    <iter binding = x, index = 3>
+        test Int(0)
+            test Int(3)";
+        assert_eq!(expected.trim(), output.trim());
+    }
+
+    #[test]
+    fn eval_range() {
+        let tpl = "
+        for x, i in 2..4
+            test x
+                test i
+        ";
+        let (blueprint, globals) = Document::new(tpl).compile().unwrap();
+        let mut tree = WidgetTree::empty();
+        let mut attribute_storage = AttributeStorage::empty();
+        let mut floating_widgets = FloatingWidgets::empty();
+        let mut components = Components::new();
+        let factory = setup_test_factory();
+        let mut component_reg = ComponentRegistry::new();
+        let mut states = States::new();
+        let mut scope = Scope::new();
+        let mut ctx = EvalContext::new(
+            &globals,
+            &factory,
+            &mut scope,
+            &mut states,
+            &mut component_reg,
+            &mut attribute_storage,
+            &mut floating_widgets,
+            &mut components,
+        );
+        eval_blueprint(&blueprint, &mut ctx, root_node(), &mut tree).unwrap();
+
+        let mut stringify = Stringify::new(&attribute_storage);
+        tree.apply_visitor(&mut stringify);
+        let output = stringify.finish();
+
+        let expected = "
+<for>
+    <iter binding = x, index = 0>
+        test Int(2)
+            test Int(0)
+    <iter binding = x, index = 1>
+        test Int(3)
+            test Int(1)
 ";
         assert_eq!(expected.trim(), output.trim());
     }
@@ -2,9 +2,9 @@ use anathema_state::{Change, States};
 use anathema_store::tree::PathFinder;
 use anathema_templates::Globals;
 
+use super::controlflow;
 use super::element::Element;
 use super::eval::EvalContext;
-use super::loops::LOOP_INDEX;
 use crate::components::ComponentRegistry;
 use crate::error::Result;
 use crate::values::ValueId;
@@ -115,7 +115,24 @@ fn update_widget<'bp>(
         // the ControlFlow has access to all the
         // branches.
         WidgetKind::ControlFlow(_) => unreachable!("update is never called on ControlFlow, only the children"),
-        WidgetKind::If(_) | WidgetKind::Else(_) => (), // If / Else are not updated by themselves
+        WidgetKind::If(_) | WidgetKind::Else(_) => {
+            if let Change::Dropped | Change::Changed = change {
+                match widget {
+                    WidgetKind::If(widget) => widget.cond.reload_val(value_id, ctx.globals, ctx.scope, ctx.states),
+                    WidgetKind::Else(widget) => {
+                        if let Some(cond) = widget.cond.as_mut() {
+                            cond.reload_val(value_id, ctx.globals, ctx.scope, ctx.states);
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            // The condition of one branch decides which of the
+            // sibling branches are shown, so all of them are updated
+            let parent = &path[..path.len() - 1];
+            controlflow::update_branches(parent, Some((path, widget)), ctx, tree)?;
+        }
         WidgetKind::Component(_) => {
             if let Change::Dropped = change {
                 ctx.components.remove(path);
@@ -128,15 +145,14 @@ fn update_widget<'bp>(
 
 pub(super) fn scope_value<'bp>(widget: &WidgetKind<'bp>, scope: &mut Scope<'bp>, children: &[u16]) {
     match widget {
-        WidgetKind::For(for_loop) => match children {
-            [next, ..] => {
+        WidgetKind::For(for_loop) => {
+            if let [next, ..] = children {
                 let index = *next as usize;
                 for_loop.collection.scope(scope, for_loop.binding, index);
             }
-            [] => {}
-        },
+        }
         WidgetKind::Iteration(iter) => {
-            scope.scope_pending(LOOP_INDEX, iter.loop_index.to_pending());
+            iter.scope_index(scope);
         }
         WidgetKind::Component(component) => {
             if let Some(state) = &component.external_state {
@@ -148,6 +164,7 @@ pub(super) fn scope_value<'bp>(widget: &WidgetKind<'bp>, scope: &mut Scope<'bp>,
             // Insert internal state
             let state_id = component.state_id();
             scope.insert_state(state_id);
+            component.scope_focus(scope);
         }
         WidgetKind::ControlFlow(_) | WidgetKind::Element(Element { .. }) | WidgetKind::If(_) | WidgetKind::Else(_) => {}
     }
@@ -1,14 +1,49 @@
 use std::ops::{ControlFlow, Deref};
 
 use anathema_geometry::{LocalPos, Pos, Region, Size};
-use anathema_state::{Color, Hex};
+use anathema_state::{Color, CommonVal, Hex};
 use anathema_store::tree::{Node, TreeFilter, TreeForEach, TreeValues};
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
-use crate::layout::Display;
+use crate::expressions::EvalValue;
+use crate::layout::{is_hidden, Display};
 use crate::nodes::element::Element;
 use crate::widget::WidgetRenderer;
-use crate::{AttributeStorage, WidgetId, WidgetKind};
+use crate::{AttributeStorage, Attributes, WidgetId, WidgetKind};
+
+pub const OVERFLOW: &str = "overflow";
+pub const GRADIENT: &str = "gradient";
+pub const GRADIENT_DIRECTION: &str = "gradient_direction";
+pub const BACKGROUND_CHAR: &str = "background_char";
+
+/// What happens to anything painted outside of a widget, set with the `overflow` attribute.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum Overflow {
+    /// Nothing is painted outside of the widget
+    #[default]
+    Clip,
+    /// The widget and the children can paint outside of the widget,
+    /// as long as it's inside the parent
+    Visible,
+    /// The widget scrolls the children itself.
+    /// Nothing is painted outside of the widget
+    Scroll,
+}
+
+impl TryFrom<CommonVal<'_>> for Overflow {
+    type Error = ();
+
+    fn try_from(value: CommonVal<'_>) -> Result<Self, Self::Error> {
+        let overflow = match value.to_common_str().as_ref() {
+            "clip" => Self::Clip,
+            "visible" => Self::Visible,
+            "scroll" => Self::Scroll,
+            _ => return Err(()),
+        };
+        Ok(overflow)
+    }
+}
 
 pub trait CellAttributes {
     fn with_str(&self, key: &str, f: &mut dyn FnMut(&str));
@@ -24,6 +59,88 @@ pub trait CellAttributes {
     fn get_bool(&self, key: &str) -> bool;
 }
 
+/// A background going from one colour to another across a widget,
+/// set with the `gradient` and `gradient_direction` (`horizontal` or `vertical`) attributes:
+/// ```text
+/// vstack [gradient: [#000044, #0000ff], gradient_direction: "vertical"]
+/// ```
+///
+/// The gradient is approximated with one colour per cell.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Gradient {
+    pub from: Hex,
+    pub to: Hex,
+    pub vertical: bool,
+}
+
+impl Gradient {
+    pub fn from_attributes(attributes: &Attributes<'_>) -> Option<Self> {
+        let value = attributes.get_val(GRADIENT)?;
+        let EvalValue::ExprList(stops) = &**value else { return None };
+        let [from, to] = &stops[..] else { return None };
+
+        let hex = |value: &EvalValue<'_>| match value.load_common_val()?.to_common()? {
+            CommonVal::Hex(hex) => Some(hex),
+            CommonVal::Color(Color::Rgb(r, g, b)) => Some(Hex { r, g, b }),
+            _ => None,
+        };
+
+        let vertical = attributes.get_ref::<&str>(GRADIENT_DIRECTION) == Some("vertical");
+        Some(Self {
+            from: hex(from)?,
+            to: hex(to)?,
+            vertical,
+        })
+    }
+
+    /// The colour of the cell at the position, in a region of the given size
+    pub fn at(&self, pos: LocalPos, size: Size) -> Hex {
+        let (index, len) = match self.vertical {
+            true => (pos.y as usize, size.height),
+            false => (pos.x as usize, size.width),
+        };
+
+        let t = match len {
+            0 | 1 => 0.0,
+            len => index.min(len - 1) as f32 / (len - 1) as f32,
+        };
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+
+        Hex {
+            r: mix(self.from.r, self.to.r),
+            g: mix(self.from.g, self.to.g),
+            b: mix(self.from.b, self.to.b),
+        }
+    }
+}
+
+// The background of a single cell
+pub(crate) struct Background(pub(crate) Hex);
+
+impl CellAttributes for Background {
+    fn with_str(&self, _: &str, _: &mut dyn FnMut(&str)) {}
+
+    fn get_i64(&self, _: &str) -> Option<i64> {
+        None
+    }
+
+    fn get_u8(&self, _: &str) -> Option<u8> {
+        None
+    }
+
+    fn get_hex(&self, key: &str) -> Option<Hex> {
+        (key == "background").then_some(self.0)
+    }
+
+    fn get_color(&self, _: &str) -> Option<Color> {
+        None
+    }
+
+    fn get_bool(&self, _: &str) -> bool {
+        false
+    }
+}
+
 pub struct PaintFilter<'frame, 'bp> {
     attributes: &'frame AttributeStorage<'bp>,
     ignore_floats: bool,
@@ -51,6 +168,7 @@ impl<'frame, 'bp> TreeFilter for PaintFilter<'frame, 'bp> {
     ) -> ControlFlow<(), Option<&'val mut Self::Output>> {
         match input {
             WidgetKind::Element(el) if el.container.inner.any_floats() && self.ignore_floats => ControlFlow::Break(()),
+            WidgetKind::Element(el) if is_hidden(self.attributes.get(el.id())) => ControlFlow::Break(()),
             WidgetKind::Element(el) => match self
                 .attributes
                 .get(el.id())
@@ -106,6 +224,8 @@ impl SizePos {
 pub struct PaintCtx<'surface, Size> {
     surface: &'surface mut dyn WidgetRenderer,
     pub clip: Option<Region>,
+    // The clipping region of the parent, before the widget was clipped to its own region
+    outer_clip: Option<Region>,
     pub(crate) state: Size,
 }
 
@@ -122,6 +242,7 @@ impl<'surface> PaintCtx<'surface, Unsized> {
         Self {
             surface,
             clip,
+            outer_clip: clip,
             state: Unsized,
         }
     }
@@ -131,6 +252,7 @@ impl<'surface> PaintCtx<'surface, Unsized> {
         PaintCtx {
             surface: self.surface,
             clip: self.clip,
+            outer_clip: self.clip,
             state: SizePos::new(size, global_pos),
         }
     }
@@ -141,6 +263,22 @@ impl<'screen> PaintCtx<'screen, SizePos> {
         PaintCtx::new(self.surface, self.clip)
     }
 
+    /// Paint outside of the widget, e.g. a drop shadow.
+    ///
+    /// The new context is at an offset from the widget, and is only clipped by the parents of the
+    /// widget, not the region of the widget itself.
+    /// Anything painted this way is painted on top of what is already painted underneath,
+    /// and underneath what is painted after it.
+    pub fn outside(&mut self, offset: Pos, size: Size) -> PaintCtx<'_, SizePos> {
+        let global_pos = self.global_pos + offset;
+        PaintCtx {
+            surface: self.surface,
+            clip: self.outer_clip,
+            outer_clip: self.outer_clip,
+            state: SizePos::new(size, global_pos),
+        }
+    }
+
     pub fn update(&mut self, new_size: Size, new_pos: Pos) {
         self.state.local_size = new_size;
         self.state.global_pos = new_pos;
@@ -204,13 +342,54 @@ impl<'screen> PaintCtx<'screen, SizePos> {
     }
 
     pub fn place_glyphs(&mut self, s: &str, mut pos: LocalPos) -> Option<LocalPos> {
-        for c in s.chars() {
-            let p = self.place_glyph(c, pos)?;
+        for cluster in s.graphemes(true) {
+            let p = self.place_cluster(cluster, pos)?;
             pos = p;
         }
         Some(pos)
     }
 
+    /// Place a grapheme cluster, e.g a char followed by combining marks,
+    /// in a single cell and return the next cursor position in local space.
+    ///
+    /// The width of the cluster is the width of the first char.
+    pub fn place_cluster(&mut self, cluster: &str, input_pos: LocalPos) -> Option<LocalPos> {
+        // A carriage return followed by a newline is a single cluster
+        if cluster == "\r\n" {
+            return self.place_glyph('\n', input_pos);
+        }
+
+        let mut chars = cluster.chars();
+        let Some(c) = chars.next() else { return Some(input_pos) };
+        match chars.as_str().is_empty() {
+            true => self.place(c, None, input_pos),
+            false => self.place(c, Some(cluster), input_pos),
+        }
+    }
+
+    /// Draw an image using the graphics protocol of the renderer.
+    ///
+    /// The image is only drawn if the entire area is visible.
+    /// Returns `false` if the image was not drawn.
+    pub fn draw_image(&mut self, pixels: &[u8], image_size: Size, pos: LocalPos, cells: Size) -> bool {
+        if cells.width == 0 || cells.height == 0 {
+            return false;
+        }
+
+        let last = LocalPos::new(pos.x + cells.width as u16 - 1, pos.y + cells.height as u16 - 1);
+        if let Some(clip) = self.clip.as_ref() {
+            if !self.clip(pos, clip) || !self.clip(last, clip) {
+                return false;
+            }
+        }
+
+        let (Some(screen_pos), Some(_)) = (self.translate_to_global(pos), self.translate_to_global(last)) else {
+            return false;
+        };
+
+        self.surface.draw_image(pixels, image_size, screen_pos, cells)
+    }
+
     pub fn set_attributes(&mut self, attrs: &dyn CellAttributes, pos: LocalPos) {
         // Ensure that the position is inside provided clipping region
         if let Some(clip) = self.clip.as_ref() {
@@ -233,7 +412,16 @@ impl<'screen> PaintCtx<'screen, SizePos> {
     // should be placed. This will (possibly) be offset if there is clipping available.
     //
     // The `output_pos` is the same as the `input_pos` unless clipping has been applied.
+    //
+    // Wide characters (e.g CJK and most emoji) occupy two cells.
+    // Characters without a width, like combining marks and zero width joiners, are not placed
+    // on their own. Use `place_cluster` to place them together with the char they belong to.
     pub fn place_glyph(&mut self, c: char, input_pos: LocalPos) -> Option<LocalPos> {
+        self.place(c, None, input_pos)
+    }
+
+    // Place either a single char, or a cluster starting with the char
+    fn place(&mut self, c: char, cluster: Option<&str>, input_pos: LocalPos) -> Option<LocalPos> {
         let width = c.width().unwrap_or(0);
         let next = LocalPos {
             x: input_pos.x + width as u16,
@@ -253,6 +441,10 @@ impl<'screen> PaintCtx<'screen, SizePos> {
         }
 
         // 2. Check if the char can be placed
+        if width == 0 {
+            return Some(next);
+        }
+
         if !self.pos_inside_local_region(input_pos, width) {
             return None;
         }
@@ -262,7 +454,10 @@ impl<'screen> PaintCtx<'screen, SizePos> {
             Some(pos) => pos,
             None => return Some(next),
         };
-        self.surface.draw_glyph(c, screen_pos);
+        match cluster {
+            Some(cluster) => self.surface.draw_cluster(cluster, screen_pos),
+            None => self.surface.draw_glyph(c, screen_pos),
+        }
 
         // 4. Advance the cursor (which might trigger another newline)
         if input_pos.x >= self.local_size.width as u16 {
@@ -275,3 +470,26 @@ impl<'screen> PaintCtx<'screen, SizePos> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gradient_stops() {
+        let mut gradient = Gradient {
+            from: Hex { r: 0, g: 0, b: 0 },
+            to: Hex { r: 200, g: 100, b: 0 },
+            vertical: false,
+        };
+
+        let size = Size::new(5, 2);
+        assert_eq!(gradient.at(LocalPos::new(0, 1), size), gradient.from);
+        assert_eq!(gradient.at(LocalPos::new(2, 0), size), Hex { r: 100, g: 50, b: 0 });
+        assert_eq!(gradient.at(LocalPos::new(4, 0), size), gradient.to);
+
+        gradient.vertical = true;
+        assert_eq!(gradient.at(LocalPos::new(4, 0), size), gradient.from);
+        assert_eq!(gradient.at(LocalPos::new(0, 1), size), gradient.to);
+    }
+}
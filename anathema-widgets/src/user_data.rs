@@ -0,0 +1,83 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+
+/// Typed data attached to an element by the application,
+/// at most one value per type.
+///
+/// ```ignore
+/// struct Row(usize);
+///
+/// elements.by_tag("text").each(|el, _| {
+///     el.user_data_mut().insert(Row(index));
+/// });
+///
+/// for id in elements.widget_at(mouse.pos()) {
+///     elements.by_id(id).first(|el, _| {
+///         if let Some(Row(index)) = el.user_data().get::<Row>() {
+///             // ...
+///         }
+///     });
+/// }
+/// ```
+#[derive(Default)]
+pub struct UserData(HashMap<TypeId, Box<dyn Any>>);
+
+impl UserData {
+    /// Insert a value, returning the previous value of the same type
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.0
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.0.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.0.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.0
+            .remove(&TypeId::of::<T>())
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.0.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for UserData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<user data: {}>", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn one_value_per_type() {
+        let mut data = UserData::default();
+        assert!(data.insert(1usize).is_none());
+        assert!(data.insert("row").is_none());
+
+        assert_eq!(data.insert(2usize), Some(1));
+        *data.get_mut::<usize>().unwrap() += 1;
+        assert_eq!(data.get::<usize>(), Some(&3));
+
+        assert_eq!(data.remove::<&str>(), Some("row"));
+        assert!(!data.contains::<&str>());
+        assert!(data.get::<u8>().is_none());
+    }
+}
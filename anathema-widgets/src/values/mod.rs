@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use anathema_state::ValueRef;
+use anathema_state::{CommonVal, ValueRef};
 use anathema_store::smallmap::{SmallIndex, SmallMap};
 use anathema_templates::Expression;
 
@@ -116,6 +116,19 @@ pub(crate) enum Collection<'bp> {
     /// Index value.
     #[allow(dead_code)]
     Index(Box<Collection<'bp>>, Box<EvalValue<'bp>>),
+    /// A range of integers, excluding the end.
+    /// The start and the end are kept, so the loop is rebuilt if they change.
+    /// ```text
+    /// for i in 0..count
+    ///     text i
+    /// ```
+    Range(Box<EvalValue<'bp>>, Box<EvalValue<'bp>>),
+    /// The collection in reverse order
+    /// ```text
+    /// for x in reverse(list)
+    ///     text x
+    /// ```
+    Reversed(Box<Collection<'bp>>),
     /// This value doesn't exist now, but might exist in the future.
     /// See [`nodes::future::try_resolve_value`].
     Future,
@@ -127,10 +140,34 @@ impl<'bp> Collection<'bp> {
             Self::Static(e) => e.len(),
             Self::Dyn(value_ref) => value_ref.as_state().map(|state| state.count()).unwrap_or(0),
             Self::Index(collection, _) => collection.count(),
+            Self::Range(..) => self
+                .range()
+                .map(|(start, end)| (end - start).max(0) as usize)
+                .unwrap_or(0),
+            Self::Reversed(collection) => collection.count(),
             Self::Future => 0,
         }
     }
 
+    /// The collection is rebuilt rather than updated when it changes
+    pub(crate) fn is_range(&self) -> bool {
+        match self {
+            Self::Range(..) => true,
+            Self::Reversed(collection) => collection.is_range(),
+            _ => false,
+        }
+    }
+
+    /// The collection is in reverse order
+    pub(crate) fn is_reversed(&self) -> bool {
+        matches!(self, Self::Reversed(_))
+    }
+
+    fn range(&self) -> Option<(i64, i64)> {
+        let Self::Range(start, end) = self else { return None };
+        Some((start.load_number()?.as_int(), end.load_number()?.as_int()))
+    }
+
     pub(crate) fn scope(&self, scope: &mut Scope<'bp>, binding: &'bp str, index: usize) {
         match self {
             Collection::Static(expressions) => {
@@ -145,6 +182,15 @@ impl<'bp> Collection<'bp> {
                 scope.scope_pending(binding, value)
             }
             Collection::Index(collection, _) => collection.scope(scope, binding, index),
+            Collection::Range(..) => {
+                let Some((start, _)) = self.range() else { return };
+                let value = EvalValue::Static(CommonVal::Int(start + index as i64));
+                scope.scope_downgrade(binding, value.downgrade());
+            }
+            Collection::Reversed(collection) => {
+                let index = collection.count() - 1 - index;
+                collection.scope(scope, binding, index)
+            }
             Collection::Future => {}
         }
     }
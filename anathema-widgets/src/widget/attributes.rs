@@ -159,6 +159,18 @@ impl<'bp> Attributes<'bp> {
             .and_then(|e| e.load_number().map(|n| n.as_uint()))
     }
 
+    /// Get a float regardless of how the value was stored.
+    /// This will convert any state value of any numerical type
+    /// into a `f64`.
+    pub fn get_float(&self, key: &'bp str) -> Option<f64> {
+        let key = ValueKey::Attribute(key);
+
+        let value = self.values.get(&key)?;
+        value
+            .load_common_val()
+            .and_then(|e| e.load_number().map(|n| n.as_float()))
+    }
+
     pub(crate) fn get_mut_with_index(&mut self, index: SmallIndex) -> Option<&mut Value<'bp, EvalValue<'bp>>> {
         self.values.get_mut_with_index(index)
     }
@@ -1,26 +1,273 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use anathema_templates::blueprints::Blueprint;
+use anathema_templates::{Expression, Primitive};
 
 use super::{AnyWidget, Widget};
 use crate::error::{Error, Result};
+use crate::layout::Layout;
 use crate::Attributes;
 
-pub struct Factory(HashMap<Box<str>, Box<dyn Fn(&Attributes<'_>) -> Box<dyn AnyWidget>>>);
+pub struct Factory {
+    widgets: HashMap<Box<str>, Box<dyn Fn(&Attributes<'_>) -> Box<dyn AnyWidget>>>,
+    metadata: HashMap<Box<str>, WidgetMetadata>,
+    layouts: Layouts,
+}
 
 impl Factory {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            widgets: HashMap::new(),
+            metadata: HashMap::new(),
+            layouts: Layouts::default(),
+        }
     }
 
     pub(crate) fn make(&self, ident: &str, attribs: &Attributes<'_>) -> Result<Box<dyn AnyWidget>> {
-        let f = self.0.get(ident).ok_or(Error::InvalidElement(ident.to_string()))?;
+        let f = self
+            .widgets
+            .get(ident)
+            .ok_or(Error::InvalidElement(ident.to_string()))?;
         Ok((f)(attribs))
     }
 
     pub fn register_widget(&mut self, ident: &str, factory: impl Fn(&Attributes<'_>) -> Box<dyn AnyWidget> + 'static) {
-        self.0.insert(ident.into(), Box::new(factory));
+        self.widgets.insert(ident.into(), Box::new(factory));
     }
 
+    /// Register a widget, with the metadata from [`Widget::metadata`]
     pub fn register_default<W: 'static + Widget + Default>(&mut self, ident: &str) {
-        self.0.insert(ident.into(), Box::new(|_| Box::<W>::default()));
+        self.widgets.insert(ident.into(), Box::new(|_| Box::<W>::default()));
+        if let Some(metadata) = W::metadata() {
+            self.metadata.insert(ident.into(), metadata);
+        }
+    }
+
+    /// Set the metadata of a widget, e.g one registered with [`Factory::register_widget`]
+    pub fn set_metadata(&mut self, ident: &str, metadata: WidgetMetadata) {
+        self.metadata.insert(ident.into(), metadata);
+    }
+
+    /// The metadata of the widget, if it has any
+    pub fn metadata(&self, ident: &str) -> Option<&WidgetMetadata> {
+        self.metadata.get(ident)
+    }
+
+    /// Check the attributes of every widget in the blueprint against the metadata.
+    ///
+    /// Only literal values can be checked: an attribute set to a state value or an
+    /// expression is resolved at runtime and always passes.
+    /// Attributes without metadata and widgets without metadata are not checked.
+    pub fn validate(&self, blueprint: &Blueprint) -> Result<()> {
+        match blueprint {
+            Blueprint::Single(single) => {
+                if let Some(metadata) = self.metadata.get(&*single.ident) {
+                    for (name, value) in single.attributes.iter() {
+                        let Some(attribute) = metadata.get(name) else { continue };
+                        if !attribute.ty.accepts(value) {
+                            return Err(Error::InvalidAttribute {
+                                widget: single.ident.to_string(),
+                                attribute: name.to_string(),
+                                expected: attribute.ty,
+                            });
+                        }
+                    }
+                }
+                single.children.iter().try_for_each(|child| self.validate(child))
+            }
+            Blueprint::For(for_loop) => for_loop.body.iter().try_for_each(|child| self.validate(child)),
+            Blueprint::ControlFlow(flow) => flow
+                .if_node
+                .body
+                .iter()
+                .chain(flow.elses.iter().flat_map(|e| &e.body))
+                .try_for_each(|child| self.validate(child)),
+            Blueprint::Component(component) => component.body.iter().try_for_each(|child| self.validate(child)),
+        }
+    }
+
+    /// Register a layout algorithm for the `layout` widget
+    pub fn register_layout<L: Layout + 'static>(&mut self, name: &str, layout: impl Fn() -> L + 'static) {
+        self.layouts
+            .0
+            .borrow_mut()
+            .insert(name.into(), Box::new(move || Box::new(layout())));
+    }
+
+    /// The registered layouts. Layouts registered later are included.
+    pub fn layouts(&self) -> Layouts {
+        self.layouts.clone()
+    }
+
+    /// The names of the registered widgets, sorted
+    pub fn widgets(&self) -> Vec<&str> {
+        let mut widgets = self.widgets.keys().map(|ident| &**ident).collect::<Vec<_>>();
+        widgets.sort();
+        widgets
+    }
+}
+
+/// Layout algorithms by name, shared between the factory and the `layout` widgets
+#[derive(Clone, Default)]
+pub struct Layouts(Rc<RefCell<HashMap<Box<str>, Box<dyn Fn() -> Box<dyn Layout>>>>>);
+
+impl Layouts {
+    /// Create the layout, if it's registered
+    pub fn make(&self, name: &str) -> Option<Box<dyn Layout>> {
+        self.0.borrow().get(name).map(|layout| layout())
+    }
+
+    /// The names of the layouts, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self.0.borrow().keys().map(|name| name.to_string()).collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
+/// How many children a widget accepts
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Children {
+    None,
+    One,
+    Many,
+}
+
+/// The type of the value of an attribute
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AttributeType {
+    Bool,
+    Int,
+    Float,
+    Char,
+    String,
+    Color,
+    /// One of the strings
+    Enum(&'static [&'static str]),
+    /// A list, a map or a value of more than one type
+    Any,
+}
+
+impl AttributeType {
+    // Anything that isn't a literal is only known at runtime
+    fn accepts(&self, value: &Expression) -> bool {
+        match (self, value) {
+            (Self::Any, _) => true,
+            (Self::Bool, Expression::Primitive(Primitive::Bool(_))) => true,
+            (Self::Int, Expression::Primitive(Primitive::Int(_))) => true,
+            (Self::Float, Expression::Primitive(Primitive::Int(_) | Primitive::Float(_))) => true,
+            (Self::Char, Expression::Primitive(Primitive::Char(_))) => true,
+            (Self::Char, Expression::Str(s)) => s.chars().count() == 1,
+            (Self::String, Expression::Str(_)) => true,
+            (Self::Color, Expression::Str(_) | Expression::List(_)) => true,
+            (Self::Color, Expression::Primitive(Primitive::Hex(_) | Primitive::Int(_))) => true,
+            // Widgets accept aliases (e.g `center`) that are not listed, so only the type is checked
+            (Self::Enum(_), Expression::Str(_)) => true,
+            (_, Expression::Primitive(_) | Expression::Str(_) | Expression::List(_) | Expression::Map(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+impl Display for AttributeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool => write!(f, "a bool"),
+            Self::Int => write!(f, "an integer"),
+            Self::Float => write!(f, "a number"),
+            Self::Char => write!(f, "a char"),
+            Self::String => write!(f, "a string"),
+            Self::Color => write!(f, "a colour"),
+            Self::Enum(variants) => write!(f, "one of {}", variants.join(", ")),
+            Self::Any => write!(f, "any value"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeMetadata {
+    pub name: &'static str,
+    pub ty: AttributeType,
+    /// The default value as it would be written in a template
+    pub default: Option<&'static str>,
+}
+
+/// The attributes of a widget and whether it accepts children,
+/// for tooling like validation and completion of templates.
+///
+/// The runtime checks literal attribute values against the metadata
+/// when the templates are compiled (see [`Factory::validate`]).
+///
+/// Attributes shared by every widget, like `foreground` and `background`,
+/// are not part of the metadata.
+///
+/// ```
+/// use anathema_widgets::{AttributeType, Children, WidgetMetadata};
+///
+/// let metadata = WidgetMetadata::new(Children::Many)
+///     .attribute("width", AttributeType::Int, None)
+///     .attribute("title_align", AttributeType::Enum(&["left", "centre", "right"]), Some("\"left\""));
+///
+/// assert_eq!(metadata.get("width").unwrap().ty, AttributeType::Int);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetMetadata {
+    pub children: Children,
+    pub attributes: Vec<AttributeMetadata>,
+}
+
+impl WidgetMetadata {
+    pub fn new(children: Children) -> Self {
+        Self {
+            children,
+            attributes: vec![],
+        }
+    }
+
+    pub fn attribute(mut self, name: &'static str, ty: AttributeType, default: Option<&'static str>) -> Self {
+        self.attributes.push(AttributeMetadata { name, ty, default });
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AttributeMetadata> {
+        self.attributes.iter().find(|attribute| attribute.name == name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_templates::Document;
+
+    use super::*;
+
+    fn validate(template: &str) -> Result<()> {
+        let mut factory = Factory::new();
+        let metadata = WidgetMetadata::new(Children::Many)
+            .attribute("open", AttributeType::Bool, None)
+            .attribute("width", AttributeType::Int, None)
+            .attribute("align", AttributeType::Enum(&["left", "right"]), None);
+        factory.set_metadata("panel", metadata);
+
+        let (blueprint, _) = Document::new(template).compile().unwrap();
+        factory.validate(&blueprint)
+    }
+
+    #[test]
+    fn literal_attributes() {
+        assert!(validate("panel [open: true, width: 3, align: 'left', foreground: 'red']").is_ok());
+
+        let err = validate("panel [open: 'yes']").unwrap_err().to_string();
+        assert_eq!(err, "`open` on `panel` should be a bool");
+
+        let err = validate("vstack\n    panel [width: 1.5]").unwrap_err().to_string();
+        assert_eq!(err, "`width` on `panel` should be an integer");
+    }
+
+    #[test]
+    fn attributes_resolved_at_runtime() {
+        assert!(validate("panel [open: state.open, width: 1 + 2, align: state.align]").is_ok());
     }
 }
@@ -1,7 +1,7 @@
 use std::any::Any;
 use std::cmp::Ordering;
 use std::fmt::{self, Debug};
-use std::ops::ControlFlow;
+use std::ops::{ControlFlow, Range};
 
 pub type WidgetId = anathema_store::slab::Key;
 
@@ -14,9 +14,9 @@ use anathema_store::tree::{NodeWalker, Tree, TreeForEach};
 use anathema_templates::WidgetComponentId;
 
 pub use self::attributes::{AttributeStorage, Attributes};
-pub use self::factory::Factory;
-pub use self::query::Elements;
-use crate::layout::{Constraints, LayoutCtx, LayoutFilter, PositionCtx};
+pub use self::factory::{AttributeMetadata, AttributeType, Children, Factory, Layouts, WidgetMetadata};
+pub use self::query::{Anchor, Elements};
+use crate::layout::{Constraints, LayoutCtx, LayoutFilter, PositionCtx, Spacing};
 use crate::paint::{CellAttributes, PaintCtx, PaintFilter, SizePos};
 use crate::WidgetKind;
 
@@ -34,7 +34,7 @@ pub struct CompEntry {
 
 impl PartialOrd for CompEntry {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.path.cmp(&other.path))
+        Some(self.cmp(other))
     }
 }
 
@@ -56,6 +56,8 @@ pub struct Components {
     pub tab_index: usize,
     inner: SortedList<CompEntry>,
     comp_ids: SmallMap<WidgetComponentId, usize>,
+    scope: Option<Box<[u16]>>,
+    trap: Option<Box<[u16]>>,
 }
 
 impl Components {
@@ -64,9 +66,91 @@ impl Components {
             tab_index: 0,
             inner: SortedList::empty(),
             comp_ids: SmallMap::empty(),
+            scope: None,
+            trap: None,
         }
     }
 
+    /// Limit focus and input to the components under the node at `path`.
+    pub fn set_scope(&mut self, path: Option<Box<[u16]>>) {
+        self.scope = path;
+    }
+
+    /// Limit tab traversal to the components under the node at `path`.
+    /// Unlike the scope this does not limit input.
+    pub fn set_focus_trap(&mut self, path: Option<Box<[u16]>>) {
+        self.trap = path;
+    }
+
+    /// The indices of the components that can receive focus and input.
+    pub fn focus_range(&self) -> Range<usize> {
+        let Some(scope) = self.scope.as_deref() else { return 0..self.len() };
+        self.range_of(scope)
+    }
+
+    /// The indices of the components that can be reached with tab.
+    /// A focus trap outside of the scope is ignored.
+    pub fn tab_range(&self) -> Range<usize> {
+        match (self.trap.as_deref(), self.scope.as_deref()) {
+            (Some(trap), Some(scope)) if trap.starts_with(scope) => self.range_of(trap),
+            (Some(trap), None) => self.range_of(trap),
+            _ => self.focus_range(),
+        }
+    }
+
+    /// The indices of the components in the tab range, in the order they are reached with tab.
+    ///
+    /// Components with a positive tab index come first, lowest first, followed by the
+    /// components without one in the order they appear in the tree.
+    /// Components with a negative tab index are skipped, but can still be focused
+    /// through the focus queue.
+    pub fn tab_order(&mut self, tab_index: impl Fn(WidgetId) -> Option<i64>) -> Vec<usize> {
+        let mut order = self
+            .tab_range()
+            .filter_map(|i| match tab_index(self.get(i)?.0) {
+                Some(index) if index < 0 => None,
+                Some(index) if index > 0 => Some((index, i)),
+                _ => Some((i64::MAX, i)),
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so components with the same tab index stay in tree order
+        order.sort_by_key(|(tab_index, _)| *tab_index);
+        order.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// The indices of the components that are ancestors of the component at `index`,
+    /// starting with the one closest to the root.
+    /// Only ancestors in the focus range are included, as the rest can't receive input.
+    pub fn ancestors(&self, index: usize) -> Vec<usize> {
+        let Some(entry) = self.inner.iter().nth(index) else { return vec![] };
+        let range = self.focus_range();
+        // Ancestors are sorted before their children
+        self.inner
+            .iter()
+            .enumerate()
+            .take(index)
+            .skip(range.start)
+            .filter(|(_, e)| entry.path.starts_with(&e.path))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn range_of(&self, path: &[u16]) -> Range<usize> {
+        let start = self
+            .inner
+            .iter()
+            .position(|entry| entry.path.starts_with(path))
+            .unwrap_or(self.len());
+        let len = self
+            .inner
+            .iter()
+            .skip(start)
+            .take_while(|entry| entry.path.starts_with(path))
+            .count();
+        start..start + len
+    }
+
     pub fn push(&mut self, path: Box<[u16]>, widget_id: WidgetId, state_id: StateId, component_id: WidgetComponentId) {
         let entry = CompEntry {
             path,
@@ -89,6 +173,11 @@ impl Components {
         self.get(self.tab_index)
     }
 
+    /// The component that has focus
+    pub fn current_component_id(&mut self) -> Option<WidgetComponentId> {
+        self.inner.get(self.tab_index).map(|e| e.component_id)
+    }
+
     pub fn get(&mut self, index: usize) -> Option<(WidgetId, StateId)> {
         self.inner.get(index).map(|e| (e.widget_id, e.state_id))
     }
@@ -261,6 +350,18 @@ pub trait AnyWidget {
     fn any_inner_bounds(&self, pos: Pos, size: Size) -> Rect;
 
     fn any_needs_reflow(&self) -> bool;
+
+    fn any_anchor(&self) -> Option<WidgetId>;
+
+    fn any_painted_region(&self, pos: Pos, size: Size) -> Rect;
+
+    fn any_set_anchor_region(&mut self, region: Option<Rect>);
+
+    fn any_is_portal(&self) -> bool;
+
+    fn any_scroll_into_view(&mut self, region: Rect, viewport: Rect) -> bool;
+
+    fn any_padding(&self, attributes: &Attributes<'_>) -> Spacing;
 }
 
 impl<T: 'static + Widget> AnyWidget for T {
@@ -313,6 +414,30 @@ impl<T: 'static + Widget> AnyWidget for T {
     fn any_needs_reflow(&self) -> bool {
         self.needs_reflow()
     }
+
+    fn any_anchor(&self) -> Option<WidgetId> {
+        self.anchor()
+    }
+
+    fn any_painted_region(&self, pos: Pos, size: Size) -> Rect {
+        self.painted_region(pos, size)
+    }
+
+    fn any_set_anchor_region(&mut self, region: Option<Rect>) {
+        self.set_anchor_region(region)
+    }
+
+    fn any_is_portal(&self) -> bool {
+        self.is_portal()
+    }
+
+    fn any_scroll_into_view(&mut self, region: Rect, viewport: Rect) -> bool {
+        self.scroll_into_view(region, viewport)
+    }
+
+    fn any_padding(&self, attributes: &Attributes<'_>) -> Spacing {
+        self.padding(attributes)
+    }
 }
 
 impl Debug for dyn AnyWidget {
@@ -356,6 +481,15 @@ pub trait Widget {
         false
     }
 
+    /// The attributes of the widget and whether it accepts children,
+    /// used by [`Factory::register_default`].
+    fn metadata() -> Option<WidgetMetadata>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
     fn inner_bounds(&self, pos: Pos, size: Size) -> Rect {
         Rect::from((pos, size))
     }
@@ -363,6 +497,47 @@ pub trait Widget {
     fn needs_reflow(&self) -> bool {
         false
     }
+
+    /// The region the widget is painted in, in screen coordinates.
+    /// Floating widgets that are not painted at their position should return where they are painted.
+    fn painted_region(&self, pos: Pos, size: Size) -> Rect {
+        Rect::from((pos, size))
+    }
+
+    /// The widget this widget is anchored to.
+    ///
+    /// Floating widgets are laid out once all other widgets are positioned,
+    /// so a floating widget can be placed next to its anchor, see [`Widget::set_anchor_region`].
+    fn anchor(&self) -> Option<WidgetId> {
+        None
+    }
+
+    /// The region of the anchor in screen coordinates,
+    /// given before the widget is laid out.
+    /// The region is `None` if the anchor doesn't exist.
+    fn set_anchor_region(&mut self, _region: Option<Rect>) {}
+
+    /// Portals are floating widgets laid out and painted inside the element
+    /// named by their `to` attribute, instead of inside their parent.
+    /// A portal is not painted if there is no such element.
+    /// See [`crate::layout::MOUNT`].
+    fn is_portal(&self) -> bool {
+        false
+    }
+
+    /// Scroll the content so the region is inside the viewport, both in screen coordinates.
+    /// The viewport is the region of the widget itself.
+    ///
+    /// Returns `false` if the widget doesn't scroll, see [`Elements::scroll_into_view`].
+    fn scroll_into_view(&mut self, _region: Rect, _viewport: Rect) -> bool {
+        false
+    }
+
+    /// The padding of the widget, which is handled by the container of the widget.
+    /// See [`Spacing`].
+    fn padding(&self, attributes: &Attributes<'_>) -> Spacing {
+        Spacing::padding(attributes)
+    }
 }
 
 impl Debug for dyn Widget {
@@ -374,7 +549,60 @@ impl Debug for dyn Widget {
 pub trait WidgetRenderer {
     fn draw_glyph(&mut self, c: char, local_pos: Pos);
 
+    /// Draw a grapheme cluster (a char followed by combining marks or joined chars) in a single cell.
+    ///
+    /// Renderers that can't store a cluster in a cell draw the first char.
+    fn draw_cluster(&mut self, cluster: &str, local_pos: Pos) {
+        if let Some(c) = cluster.chars().next() {
+            self.draw_glyph(c, local_pos);
+        }
+    }
+
     fn set_attributes(&mut self, attribs: &dyn CellAttributes, local_pos: Pos);
 
     fn size(&self) -> Size;
+
+    /// Draw an image (RGBA, eight bits per channel, row by row) covering `cells`.
+    ///
+    /// Returns `false` if the renderer can't draw images,
+    /// in which case the image should be drawn with glyphs instead.
+    fn draw_image(&mut self, _pixels: &[u8], _image_size: Size, _pos: Pos, _cells: Size) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tab_order() {
+        let mut components = Components::new();
+        for i in 0..4 {
+            let path = vec![i as u16].into_boxed_slice();
+            components.push(path, WidgetId::new(i), StateId::ZERO, i.into());
+        }
+
+        // The second is skipped, and the last one comes first
+        let tab_indices = [None, Some(-1), None, Some(1)];
+        let tab_index = |id: WidgetId| (0..4).find(|&i| WidgetId::new(i) == id).and_then(|i| tab_indices[i]);
+        assert_eq!(components.tab_order(tab_index), [3, 0, 2]);
+    }
+
+    #[test]
+    fn ancestors() {
+        let mut components = Components::new();
+        let paths: [&[u16]; 4] = [&[0], &[0, 1], &[0, 1, 0], &[0, 2]];
+        for (i, path) in paths.into_iter().enumerate() {
+            components.push(path.into(), WidgetId::new(i), StateId::ZERO, i.into());
+        }
+
+        assert_eq!(components.ancestors(2), [0, 1]);
+        assert_eq!(components.ancestors(3), [0]);
+        assert!(components.ancestors(0).is_empty());
+
+        // Ancestors outside of the scope don't receive input
+        components.set_scope(Some([0, 1].into()));
+        assert_eq!(components.ancestors(2), [1]);
+    }
 }
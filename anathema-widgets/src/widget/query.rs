@@ -1,13 +1,32 @@
 use std::ops::ControlFlow;
 
-use anathema_geometry::{Pos, Region};
+use anathema_geometry::{Pos, Rect, Region};
 use anathema_state::CommonVal;
 use anathema_store::tree::visitor::NodeVisitor;
 use anathema_store::tree::{apply_visitor, Node, TreeValues};
 
+use crate::export::export_text;
+use crate::layout::{find_mount, is_hidden, portal_order};
 use crate::nodes::element::Element;
 use crate::{AttributeStorage, Attributes, DirtyWidgets, WidgetId, WidgetKind};
 
+/// Elements with an anchor are listed by [`Elements::anchors`], e.g a heading in a document:
+/// ```text
+/// text [anchor: 'Installation', level: 2] '## Installation'
+/// ```
+pub const ANCHOR: &str = "anchor";
+
+/// The level of an anchor, one for the top-level headings
+pub const ANCHOR_LEVEL: &str = "level";
+
+/// An element with an [`ANCHOR`] attribute
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+    pub name: String,
+    pub level: usize,
+    pub id: WidgetId,
+}
+
 // -----------------------------------------------------------------------------
 //   - Elements -
 // -----------------------------------------------------------------------------
@@ -57,6 +76,250 @@ impl<'tree, 'bp> Elements<'tree, 'bp> {
             elements: self,
         }
     }
+
+    /// The element with the id, e.g. a result of [`Elements::widget_at`]
+    pub fn by_id(&mut self, id: WidgetId) -> Query<'_, 'tree, 'bp, Kind<'static>> {
+        Query {
+            filter: Kind::ById(id),
+            elements: self,
+        }
+    }
+
+    /// Elements that have the attribute, no matter the value
+    pub fn with_attribute<'a>(&mut self, key: &'a str) -> Query<'_, 'tree, 'bp, Kind<'a>> {
+        Query {
+            filter: Kind::HasAttribute(key),
+            elements: self,
+        }
+    }
+
+    /// The visible elements with an [`ANCHOR`] attribute, in the order they appear in the tree
+    pub fn anchors(&self) -> Vec<Anchor> {
+        let mut anchors = vec![];
+        self.collect_anchors(self.nodes, &mut anchors);
+        anchors
+    }
+
+    fn collect_anchors(&self, nodes: &[Node], anchors: &mut Vec<Anchor>) {
+        for node in nodes {
+            if let Some((_, WidgetKind::Element(el))) = self.widgets.get(node.value()) {
+                let attributes = self.attributes.get(el.id());
+                if is_hidden(attributes) {
+                    continue;
+                }
+
+                if let Some(name) = attributes.get_ref::<&str>(ANCHOR) {
+                    anchors.push(Anchor {
+                        name: name.to_string(),
+                        level: attributes.get_usize(ANCHOR_LEVEL).unwrap_or(1).max(1),
+                        id: el.id(),
+                    });
+                }
+            }
+            self.collect_anchors(node.children(), anchors);
+        }
+    }
+
+    /// Scroll the closest ancestor that scrolls (e.g an `overflow`) so the element is in view.
+    /// Returns `false` if the element doesn't exist or none of its ancestors scroll.
+    ///
+    /// The region of the element is known once it's positioned,
+    /// and the element is in view after the next layout:
+    /// ```ignore
+    /// let anchor = elements.anchors().into_iter().find(|anchor| anchor.name == "Usage");
+    /// if let Some(anchor) = anchor {
+    ///     elements.scroll_into_view(anchor.id);
+    /// }
+    /// ```
+    pub fn scroll_into_view(&mut self, id: WidgetId) -> bool {
+        let mut ancestors = vec![];
+        if !find_ancestors(self.nodes, id, &mut ancestors) {
+            return false;
+        }
+
+        let region = match self.widgets.get(id) {
+            Some((_, WidgetKind::Element(el))) => Rect::from((el.container.pos, el.container.size)),
+            _ => return false,
+        };
+
+        for &ancestor in ancestors.iter().rev() {
+            let Some((_, WidgetKind::Element(el))) = self.widgets.get_mut(ancestor) else { continue };
+            if el.scroll_into_view(region) {
+                self.dirty_widgets.push(ancestor);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// The content of the element as plain text, laid out at `width` with no limit to the height,
+    /// e.g to save a document to a file. See [`export_text`].
+    ///
+    /// The element is laid out again on the next frame.
+    /// ```ignore
+    /// let mut report = None;
+    /// elements.by_tag("document").first(|el, _| report = Some(el.id()));
+    /// let text = report.and_then(|id| elements.export_text(id, 80));
+    /// ```
+    pub fn export_text(&mut self, id: WidgetId, width: usize) -> Option<String> {
+        let node = find_node(self.nodes, id)?;
+        let Some((_, WidgetKind::Element(_))) = self.widgets.get(id) else { return None };
+
+        let attributes = &*self.attributes;
+        let text = self.widgets.with_mut(id, |(_, widget), values| {
+            let WidgetKind::Element(el) = widget else { unreachable!() };
+            export_text(el, node.children(), values, attributes, width)
+        });
+
+        self.dirty_widgets.push(id);
+        Some(text)
+    }
+
+    /// All the widgets painted under the position, top-most first.
+    ///
+    /// Unlike [`Elements::at_position`] this takes into account where widgets are painted:
+    /// floating widgets (and overlays) are on top of the rest of the tree, and a widget is only
+    /// hit if it's not clipped by its parent.
+    pub fn widget_at(&self, pos: impl Into<Pos>) -> Vec<WidgetId> {
+        let mut hit = HitTest {
+            pos: pos.into(),
+            widgets: &*self.widgets,
+            attributes: &*self.attributes,
+            hits: vec![],
+            floating: vec![],
+        };
+
+        hit.visit(self.nodes, None);
+
+        // Floating widgets are painted in tree order after the rest of the tree.
+        while let Some(index) = hit.next_floating() {
+            let node = hit.floating.remove(index).1;
+            hit.visit_element(node, None);
+        }
+
+        hit.hits.reverse();
+        hit.hits
+    }
+
+    /// The innermost component of the top-most widget under the position (see [`Elements::widget_at`]),
+    /// and the position relative to the first widget of the component.
+    pub fn component_at(&self, pos: impl Into<Pos>) -> Option<(WidgetId, Pos)> {
+        let pos = pos.into();
+        let top = *self.widget_at(pos).first()?;
+        let component = self.find_component(self.nodes, top, None)?;
+        let origin = self.first_element(component.children())?;
+        Some((component.value(), pos - origin))
+    }
+
+    // The innermost component containing the widget
+    fn find_component<'a>(&'a self, nodes: &'a [Node], id: WidgetId, component: Option<&'a Node>) -> Option<&'a Node> {
+        nodes.iter().find_map(|node| {
+            if node.value() == id {
+                return component;
+            }
+
+            match self.widgets.get(node.value()) {
+                Some((_, WidgetKind::Component(_))) => self.find_component(node.children(), id, Some(node)),
+                _ => self.find_component(node.children(), id, component),
+            }
+        })
+    }
+
+    // The position of the first visible element
+    fn first_element(&self, nodes: &[Node]) -> Option<Pos> {
+        nodes.iter().find_map(|node| match self.widgets.get(node.value()) {
+            Some((_, WidgetKind::Element(el))) if is_hidden(self.attributes.get(el.id())) => None,
+            Some((_, WidgetKind::Element(el))) => Some(el.get_pos()),
+            _ => self.first_element(node.children()),
+        })
+    }
+}
+
+fn find_node(nodes: &[Node], id: WidgetId) -> Option<&Node> {
+    nodes.iter().find_map(|node| match node.value() == id {
+        true => Some(node),
+        false => find_node(node.children(), id),
+    })
+}
+
+// The ids of the ancestors of the widget, outermost first
+fn find_ancestors(nodes: &[Node], id: WidgetId, ancestors: &mut Vec<WidgetId>) -> bool {
+    nodes.iter().any(|node| {
+        if node.value() == id {
+            return true;
+        }
+
+        ancestors.push(node.value());
+        match find_ancestors(node.children(), id, ancestors) {
+            true => true,
+            false => {
+                ancestors.pop();
+                false
+            }
+        }
+    })
+}
+
+// -----------------------------------------------------------------------------
+//   - Hit test -
+// -----------------------------------------------------------------------------
+struct HitTest<'a, 'bp> {
+    pos: Pos,
+    widgets: &'a TreeValues<WidgetKind<'bp>>,
+    attributes: &'a AttributeStorage<'bp>,
+    // Every widget under the position, in the order they are painted
+    hits: Vec<WidgetId>,
+    floating: Vec<(Box<[u16]>, &'a Node)>,
+}
+
+impl<'a, 'bp> HitTest<'a, 'bp> {
+    fn visit(&mut self, nodes: &'a [Node], clip: Option<Region>) {
+        for node in nodes {
+            match self.widgets.get(node.value()) {
+                Some((_, WidgetKind::Element(el))) if is_hidden(self.attributes.get(el.id())) => continue,
+                Some((_, WidgetKind::Element(el))) if el.is_portal() => {
+                    // A portal is only painted if it has a mount point
+                    let Some((mount, id)) = find_mount(el.id(), self.widgets, self.attributes) else { continue };
+                    if !is_hidden(self.attributes.get(id)) {
+                        self.floating.push((portal_order(mount), node))
+                    }
+                }
+                Some((path, WidgetKind::Element(el))) if el.container.inner.any_floats() => {
+                    self.floating.push((path.clone(), node))
+                }
+                Some((_, WidgetKind::Element(_))) => self.visit_element(node, clip),
+                // Components, loops and control flow are not painted
+                _ => self.visit(node.children(), clip),
+            }
+        }
+    }
+
+    fn visit_element(&mut self, node: &'a Node, clip: Option<Region>) {
+        let Some((_, WidgetKind::Element(el))) = self.widgets.get(node.value()) else { return };
+
+        let rect = el.region();
+        let mut region = Region::new(rect.start, rect.end);
+        if let Some(clip) = clip {
+            region = region.intersect_with(&clip);
+        }
+
+        if region.contains(self.pos) {
+            self.hits.push(el.id());
+        }
+
+        // The children are visited even if the element was not hit,
+        // as there could be floating widgets further down the tree
+        self.visit(node.children(), Some(region));
+    }
+
+    fn next_floating(&self) -> Option<usize> {
+        self.floating
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (path, _))| path)
+            .map(|(index, _)| index)
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -106,7 +369,7 @@ where
             dirty_widgets: self.elements.dirty_widgets,
         };
 
-        apply_visitor(self.elements.nodes, self.elements.widgets, &mut run);
+        let _ = apply_visitor(self.elements.nodes, self.elements.widgets, &mut run);
     }
 
     pub fn each<T>(self, f: T)
@@ -127,6 +390,8 @@ where
 pub enum Kind<'a> {
     ByTag(&'a str),
     ByAttribute(&'a str, CommonVal<'a>),
+    HasAttribute(&'a str),
+    ById(WidgetId),
     AtPosition(Pos),
 }
 
@@ -145,6 +410,8 @@ impl<'bp, 'a> Filter<'bp> for Kind<'a> {
                     })
                     .unwrap_or(false)
             }
+            Kind::ById(id) => el.id() == *id,
+            Kind::HasAttribute(key) => attributes.get(el.container.id).get_val(key).is_some(),
             Kind::AtPosition(pos) => {
                 let region = Region::from((el.container.pos, el.container.size));
                 region.contains(*pos)
@@ -62,3 +62,43 @@ else
         "#,
     );
 }
+
+#[test]
+fn if_else_without_keep_alive() {
+    let state = TestState { is_true: true.into() };
+    TestCase::setup(
+        r#"
+if is_true [keep_alive: false]
+    test "a"
+else [keep_alive: false]
+    test "b"
+    "#,
+    )
+    .build(state)
+    .expect_frame(
+        r#"
+<control flow>
+    <if cond = true>
+        test Str("a")
+    <else>
+        "#,
+    )
+    .with_state(0, |state| *state.is_true.to_mut() = false)
+    .expect_frame(
+        r#"
+<control flow>
+    <if cond = false>
+    <else>
+        test Str("b")
+        "#,
+    )
+    .with_state(0, |state| *state.is_true.to_mut() = true)
+    .expect_frame(
+        r#"
+<control flow>
+    <if cond = true>
+        test Str("a")
+    <else>
+        "#,
+    );
+}
@@ -67,7 +67,7 @@ for val in list
 
     let f2 = r#"
 <for>
-    <iter binding = val, index = 1>
+    <iter binding = val, index = 0>
         test Int(2)
         "#;
 
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use anathema::component::*;
+use anathema::prelude::*;
+use anathema::state::SystemMetrics;
+
+struct Monitor;
+
+impl Component for Monitor {
+    type Message = ();
+    type State = SystemMetrics;
+
+    fn tick(&mut self, state: &mut Self::State, _: Elements<'_, '_>, _: Context<'_, Self::State>, dt: Duration) {
+        state.tick(dt);
+    }
+}
+
+fn main() {
+    let doc = Document::new("@main");
+
+    let backend = TuiBackend::builder()
+        .enable_alt_screen()
+        .enable_raw_mode()
+        .hide_cursor()
+        .finish()
+        .unwrap();
+
+    let mut runtime = Runtime::builder(doc, backend);
+    runtime
+        .register_component(
+            "main",
+            "examples/templates/system-monitor/system-monitor.aml",
+            Monitor,
+            SystemMetrics::new(),
+        )
+        .unwrap();
+
+    let mut runtime = runtime.finish().unwrap();
+    runtime.run();
+}
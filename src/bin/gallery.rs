@@ -0,0 +1,295 @@
+//! An interactive catalog of the built-in widgets, built from the widget metadata.
+//!
+//! ```text
+//! cargo run --bin gallery --features gallery
+//! ```
+//!
+//! * `Up` / `Down`: select a widget
+//! * `Left` / `Right`: select an attribute
+//! * `Enter`: edit the attribute, `Enter` again to apply it or `Esc` to cancel
+//! * `Delete`: reset the attribute to the default
+//! * `Esc`: quit
+use anathema::backend::tui::TuiBackend;
+use anathema::runtime::{GlobalContext, GlobalEvents, Runtime};
+use anathema::templates::analysis::diagnostics;
+use anathema::templates::{Document, ToSourceKind};
+use anathema::widgets::components::events::{Event, KeyCode, KeyEvent, KeyState};
+use anathema::widgets::{AttributeType, Children, Elements, Factory, WidgetMetadata};
+
+const INDENT: &str = "    ";
+
+struct Entry {
+    name: String,
+    metadata: WidgetMetadata,
+    // The values of the attributes, in the template syntax.
+    // Attributes without a value use the default of the widget.
+    values: Vec<Option<String>>,
+}
+
+enum Action {
+    Redraw,
+    Exit,
+    None,
+}
+
+struct Gallery {
+    widgets: Vec<Entry>,
+    widget: usize,
+    attribute: usize,
+    editing: Option<String>,
+    error: Option<String>,
+}
+
+impl Gallery {
+    fn new(factory: &Factory) -> Self {
+        let widgets = factory
+            .widgets()
+            .into_iter()
+            .filter_map(|name| {
+                let metadata = factory.metadata(name)?.clone();
+                Some(Entry {
+                    name: name.to_string(),
+                    values: vec![None; metadata.attributes.len()],
+                    metadata,
+                })
+            })
+            .collect();
+
+        Self {
+            widgets,
+            widget: 0,
+            attribute: 0,
+            editing: None,
+            error: None,
+        }
+    }
+
+    fn key(&mut self, key: KeyEvent) -> Action {
+        if let Some(value) = self.editing.as_mut() {
+            match key.code {
+                KeyCode::Char(c) => value.push(c),
+                KeyCode::Backspace => drop(value.pop()),
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Enter => {
+                    let value = self.editing.take().filter(|value| !value.trim().is_empty());
+                    self.set_value(value);
+                }
+                _ => return Action::None,
+            }
+            return Action::Redraw;
+        }
+
+        let attributes = self.widgets[self.widget].metadata.attributes.len();
+        match key.code {
+            KeyCode::Esc => return Action::Exit,
+            KeyCode::Up => self.select_widget(self.widget.checked_sub(1).unwrap_or(self.widgets.len() - 1)),
+            KeyCode::Down => self.select_widget((self.widget + 1) % self.widgets.len()),
+            KeyCode::Left if attributes > 0 => self.attribute = (self.attribute + attributes - 1) % attributes,
+            KeyCode::Right if attributes > 0 => self.attribute = (self.attribute + 1) % attributes,
+            KeyCode::Enter if attributes > 0 => {
+                let entry = &self.widgets[self.widget];
+                let value = entry.values[self.attribute].clone();
+                self.editing = value.or(entry.metadata.attributes[self.attribute].default.map(Into::into));
+                self.editing.get_or_insert_with(String::new);
+            }
+            KeyCode::Delete if attributes > 0 => self.set_value(None),
+            _ => return Action::None,
+        }
+        Action::Redraw
+    }
+
+    fn select_widget(&mut self, index: usize) {
+        self.widget = index;
+        self.attribute = 0;
+        self.error = None;
+    }
+
+    // Values that would make the template invalid are rejected
+    fn set_value(&mut self, value: Option<String>) {
+        let entry = &mut self.widgets[self.widget];
+        let previous = std::mem::replace(&mut entry.values[self.attribute], value);
+
+        self.error = diagnostics(&self.template()).into_iter().next().map(|d| d.message);
+        if self.error.is_some() {
+            self.widgets[self.widget].values[self.attribute] = previous;
+        }
+    }
+
+    fn template(&self) -> String {
+        let mut template = String::from("hstack\n    border [width: 22]\n        vstack\n");
+        for (i, entry) in self.widgets.iter().enumerate() {
+            let style = match i == self.widget {
+                true => " [inverse: true]",
+                false => "",
+            };
+            template.push_str(&format!("{INDENT}{INDENT}{INDENT}text{style} {}\n", quote(&entry.name)));
+        }
+
+        let entry = &self.widgets[self.widget];
+        template.push_str("    vstack\n        border\n            vstack\n");
+        let children = match entry.metadata.children {
+            Children::None => "no children",
+            Children::One => "one child",
+            Children::Many => "any number of children",
+        };
+        let heading = format!("{} ({children})", entry.name);
+        template.push_str(&format!("                text [bold: true] {}\n", quote(&heading)));
+
+        let attributes = entry.metadata.attributes.iter().zip(&entry.values).enumerate();
+        for (i, (attribute, value)) in attributes {
+            let value = match (i == self.attribute, &self.editing, value) {
+                (true, Some(editing), _) => format!("{editing}_"),
+                (_, _, Some(value)) => value.clone(),
+                (_, _, None) => format!("{} (default)", attribute.default.unwrap_or("none")),
+            };
+            let line = format!("{}: {} = {value}", attribute.name, type_name(attribute.ty));
+            let style = match i == self.attribute {
+                true => " [inverse: true]",
+                false => "",
+            };
+            template.push_str(&format!("                text{style} {}\n", quote(&line)));
+        }
+
+        if let Some(error) = &self.error {
+            template.push_str(&format!(
+                "                text [foreground: \"red\"] {}\n",
+                quote(error)
+            ));
+        }
+
+        template.push_str("        border\n");
+        for line in preview(entry).lines() {
+            template.push_str(&format!("{INDENT}{INDENT}{INDENT}{line}\n"));
+        }
+        template
+    }
+}
+
+impl GlobalEvents for Gallery {
+    fn handle(&mut self, event: Event, _: &mut Elements<'_, '_>, ctx: &mut GlobalContext<'_>) -> Option<Event> {
+        let Event::Key(key) = event else { return Some(event) };
+        if !matches!(key.state, KeyState::Press) {
+            return None;
+        }
+
+        match self.key(key) {
+            Action::Redraw => ctx.swap_component("gallery", self.template().to_template(), (), ()),
+            Action::Exit => ctx.exit(0),
+            Action::None => {}
+        }
+        None
+    }
+
+    fn enable_tab_navigation(&mut self) -> bool {
+        false
+    }
+}
+
+// The selected widget with the attributes that have a value, and some children
+fn preview(entry: &Entry) -> String {
+    let attributes = entry
+        .metadata
+        .attributes
+        .iter()
+        .zip(&entry.values)
+        .filter_map(|(attribute, value)| Some(format!("{}: {}", attribute.name, value.as_deref()?)))
+        .collect::<Vec<_>>();
+
+    let mut widget = entry.name.clone();
+    if !attributes.is_empty() {
+        widget.push_str(&format!(" [{}]", attributes.join(", ")));
+    }
+
+    match entry.name.as_str() {
+        "text" => widget.push_str(" \"Hello, world\""),
+        "span" => return format!("text \"A \"\n{INDENT}{widget} \"span\""),
+        _ => {}
+    }
+
+    let children: &[&str] = match entry.metadata.children {
+        Children::None => &[],
+        Children::One => &["text \"child\""],
+        Children::Many => &["text \"first\"", "text \"second\""],
+    };
+    for child in children {
+        widget.push_str(&format!("\n{INDENT}{child}"));
+    }
+    widget
+}
+
+fn type_name(ty: AttributeType) -> String {
+    match ty {
+        AttributeType::Bool => "bool".into(),
+        AttributeType::Int => "int".into(),
+        AttributeType::Float => "float".into(),
+        AttributeType::Char => "char".into(),
+        AttributeType::String => "string".into(),
+        AttributeType::Color => "color".into(),
+        AttributeType::Enum(values) => values.join(" | "),
+        AttributeType::Any => "any".into(),
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+fn main() {
+    let backend = TuiBackend::builder()
+        .enable_alt_screen()
+        .enable_raw_mode()
+        .hide_cursor()
+        .finish()
+        .unwrap();
+
+    let builder = Runtime::builder(Document::new("@gallery"), backend);
+    let gallery = Gallery::new(builder.factory());
+    let template = gallery.template();
+
+    let mut builder = builder.global_events(gallery);
+    builder
+        .register_component("gallery", template.to_template(), (), ())
+        .unwrap();
+
+    let mut runtime = builder.finish().unwrap();
+    runtime.run();
+}
+
+#[cfg(test)]
+mod test {
+    use anathema::default_widgets::register_default_widgets;
+
+    use super::*;
+
+    #[test]
+    fn every_widget_has_a_valid_template() {
+        let mut factory = Factory::new();
+        register_default_widgets(&mut factory);
+        let mut gallery = Gallery::new(&factory);
+
+        for widget in 0..gallery.widgets.len() {
+            gallery.select_widget(widget);
+            assert!(
+                diagnostics(&gallery.template()).is_empty(),
+                "{}",
+                gallery.widgets[widget].name
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let mut factory = Factory::new();
+        register_default_widgets(&mut factory);
+        let mut gallery = Gallery::new(&factory);
+        gallery.select_widget(gallery.widgets.iter().position(|e| e.name == "border").unwrap());
+
+        gallery.set_value(Some("[1".into()));
+        assert!(gallery.error.is_some());
+        assert!(gallery.widgets[gallery.widget].values[0].is_none());
+
+        gallery.set_value(Some("\"thick\"".into()));
+        assert!(gallery.error.is_none());
+        assert!(preview(&gallery.widgets[gallery.widget]).starts_with("border [border_style: \"thick\"]"));
+    }
+}